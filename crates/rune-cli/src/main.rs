@@ -41,7 +41,7 @@
 
 use anyhow::{bail, Result};
 use rune::termcolor::{ColorChoice, StandardStream};
-use rune::EmitDiagnostics as _;
+use rune::{EmitDiagnostics as _, IntoJsonDiagnostics as _};
 use std::env;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -62,8 +62,11 @@ async fn main() -> Result<()> {
     let mut dump_functions = false;
     let mut dump_types = false;
     let mut help = false;
-
-    let mut options = rune::Options::default();
+    let mut compiler_options = Vec::new();
+    let mut defines = Vec::new();
+    let mut json = false;
+    let mut cache = None;
+    let mut compile_report = false;
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -89,6 +92,9 @@ async fn main() -> Result<()> {
             "--dump-types" => {
                 dump_types = true;
             }
+            "--compile-report" => {
+                compile_report = true;
+            }
             "-O" => {
                 let opt = match args.next() {
                     Some(opt) => opt,
@@ -98,7 +104,64 @@ async fn main() -> Result<()> {
                     }
                 };
 
-                options.parse_option(&opt)?;
+                compiler_options.push(opt);
+            }
+            "-D" => {
+                let define = match args.next() {
+                    Some(define) => define,
+                    None => {
+                        println!("expected variable to `-D`");
+                        return Ok(());
+                    }
+                };
+
+                defines.push(define);
+            }
+            "--message-format" | "--message-format=human" | "--message-format=json" => {
+                let format = match arg.strip_prefix("--message-format=") {
+                    Some(format) => format.to_owned(),
+                    None => match args.next() {
+                        Some(format) => format,
+                        None => {
+                            println!("expected format to `--message-format`");
+                            return Ok(());
+                        }
+                    },
+                };
+
+                json = match format.as_str() {
+                    "human" => false,
+                    "json" => true,
+                    other => {
+                        println!("unsupported message format: {}", other);
+                        return Ok(());
+                    }
+                };
+            }
+            "--cache" => {
+                cache = match args.next() {
+                    Some(dir) => Some(rune::UnitCache::new(dir)),
+                    None => {
+                        println!("expected directory to `--cache`");
+                        return Ok(());
+                    }
+                };
+            }
+            "--explain" => {
+                let code = match args.next() {
+                    Some(code) => code,
+                    None => {
+                        println!("expected error code to `--explain`");
+                        return Ok(());
+                    }
+                };
+
+                match rune::explain(&code) {
+                    Some(explanation) => print!("{}", explanation),
+                    None => println!("no extended explanation for `{}`", code),
+                }
+
+                return Ok(());
             }
             "--help" | "-h" => {
                 help = true;
@@ -125,14 +188,30 @@ async fn main() -> Result<()> {
         println!("  --dump-stack      - Dump the state of the stack after completion. If compiled with `--trace` will dump it after each instruction.");
         println!("  --dump-functions  - Dump available functions.");
         println!("  --dump-types      - Dump available types.");
+        println!(
+            "  --compile-report  - Print timings and statistics on the compilation to stderr."
+        );
         println!("  --no-linking      - Disable link time checks.");
+        println!("  --explain <code>  - Show an extended explanation of the given error code, like `E0001`.");
+        println!("  --message-format <human/json> - Format of diagnostics. `json` emits one JSON object per line on stdout.");
+        println!("  --cache <dir>     - Cache compiled units in the given directory, and reuse them when the script is unchanged.");
         println!();
         println!("Compiler options:");
         println!("  -O <option>       - Update the given compiler option.");
+        println!("  -D <key>[=<value>] - Define a compile-time variable, available through `env!` and `#[cfg]`.");
         println!();
         println!("Available <option> arguments:");
         println!("  memoize-instance-fn[=<true/false>] - Inline the lookup of an instance function where appropriate.");
         println!("  link-checks[=<true/false>] - Perform linker checks which makes sure that called functions exist.");
+        println!("  concat-in-place[=<true/false>] - Append to strings in place for assignments like `s = s + x` where possible.");
+        println!("  intrinsics[=<true/false>] - Compile calls to intrinsics registered by native modules into dedicated instructions.");
+        println!("  negative-indices[=<true/false>] - Let negative indexes like `v[-1]` count from the end of vectors, tuples and strings.");
+        println!("  checked-arithmetic[=<true/false>] - Return integer overflows and divisions by zero as `Err` values from the function they happen in.");
+        println!("  macro-depth=<n>   - Maximum depth of nested macro expansions (default: 64).");
+        println!("  macro-tokens=<n>  - Maximum number of tokens produced by a single macro expansion (default: 100000).");
+        println!("  macro-time=<ms>   - Maximum time in milliseconds a single macro expansion may take (default: 0, unlimited).");
+        println!();
+        println!("Options can also be declared in the frontmatter of a script, like `options: link-checks=false`.");
         return Ok(());
     }
 
@@ -143,6 +222,31 @@ async fn main() -> Result<()> {
         }
     };
 
+    let mut options = rune::Options::default();
+
+    // NB: options declared in the frontmatter of the script are applied first,
+    // so that they can be overridden on the command line. Any errors in the
+    // header are reported when the script is loaded.
+    if let Ok(source) = runestick::Source::from_path(&path) {
+        let frontmatter = source.header().ok().and_then(|h| h.frontmatter);
+
+        if let Some(opts) = frontmatter.as_ref().and_then(|f| f.get("options")) {
+            for opt in opts.split_whitespace() {
+                options.parse_option(opt)?;
+            }
+        }
+    }
+
+    for opt in &compiler_options {
+        options.parse_option(opt)?;
+    }
+
+    for define in &defines {
+        let mut it = define.splitn(2, '=');
+        let key = it.next().unwrap_or_default();
+        options.define(key, it.next().unwrap_or_default());
+    }
+
     let mut context = rune::default_context()?;
     context.install(&rune_macros::module()?)?;
     let context = Arc::new(context);
@@ -150,11 +254,34 @@ async fn main() -> Result<()> {
     let mut warnings = rune::Warnings::new();
     let mut sources = rune::Sources::new();
 
-    let unit = match rune::load_path(&*context, &options, &mut sources, &path, &mut warnings) {
+    let result = match &cache {
+        Some(cache) => {
+            sources.insert_default(runestick::Source::from_path(&path)?);
+            cache.load_sources(&*context, &options, &mut sources, &mut warnings)
+        }
+        None if compile_report => {
+            sources.insert_default(runestick::Source::from_path(&path)?);
+            rune::load_sources_with_report(&*context, &options, &mut sources, &mut warnings).map(
+                |(unit, report)| {
+                    eprint!("{}", report);
+                    unit
+                },
+            )
+        }
+        None => rune::load_path(&*context, &options, &mut sources, &path, &mut warnings),
+    };
+
+    let unit = match result {
         Ok(unit) => Arc::new(unit),
         Err(error) => {
-            let mut writer = StandardStream::stderr(ColorChoice::Always);
-            error.emit_diagnostics(&mut writer, &sources)?;
+            if json {
+                let diagnostics = error.into_json_diagnostics(&sources);
+                rune::write_json_diagnostics(std::io::stdout(), &diagnostics)?;
+            } else {
+                let mut writer = StandardStream::stderr(ColorChoice::Always);
+                error.emit_diagnostics(&mut writer, &sources)?;
+            }
+
             return Ok(());
         }
     };
@@ -162,8 +289,13 @@ async fn main() -> Result<()> {
     let vm = runestick::Vm::new(context.clone(), unit.clone());
 
     if !warnings.is_empty() {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
-        warnings.emit_diagnostics(&mut writer, &sources)?;
+        if json {
+            let diagnostics = warnings.into_json_diagnostics(&sources);
+            rune::write_json_diagnostics(std::io::stdout(), &diagnostics)?;
+        } else {
+            let mut writer = StandardStream::stderr(ColorChoice::Always);
+            warnings.emit_diagnostics(&mut writer, &sources)?;
+        }
     }
 
     if dump_functions {
@@ -316,8 +448,13 @@ async fn main() -> Result<()> {
     }
 
     if let Some(error) = errored {
-        let mut writer = StandardStream::stderr(ColorChoice::Always);
-        error.emit_diagnostics(&mut writer, &sources)?;
+        if json {
+            let diagnostics = error.into_json_diagnostics(&sources);
+            rune::write_json_diagnostics(std::io::stdout(), &diagnostics)?;
+        } else {
+            let mut writer = StandardStream::stderr(ColorChoice::Always);
+            error.emit_diagnostics(&mut writer, &sources)?;
+        }
     }
 
     Ok(())
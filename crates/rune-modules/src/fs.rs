@@ -20,25 +20,88 @@
 //! # }
 //! ```
 //!
+//! Reading files requires the `fs-read` capability, see
+//! [Context::grant_only][runestick::Context::grant_only].
+//!
 //! Use it in Rune:
 //!
 //! ```rust,ignore
+//! use std::path::Path;
+//!
 //! fn main() {
 //!     let file = fs::read_to_string("file.txt").await?;
 //!     println(`{file}`);
+//!
+//!     let config = Path::new("config").join("app.toml");
+//!     let config = fs::read_to_string(config).await?;
+//!     println(`{config}`);
+//!
+//!     // The file is closed when the block is exited.
+//!     with fs::open("file.txt").await? as file {
+//!         let file = file.read_to_string().await?;
+//!         println(`{file}`);
+//!     }
 //! }
 //! ```
 
+use runestick::modules::path::Path;
+use runestick::{Value, VmError};
 use std::io;
 use tokio::fs;
+use tokio::io::AsyncReadExt as _;
 
 /// Construct the `fs` module.
 pub fn module() -> Result<runestick::Module, runestick::ContextError> {
     let mut module = runestick::Module::new(&["fs"]);
     module.async_function(&["read_to_string"], read_to_string)?;
+    module.require_capability(&["read_to_string"], "fs-read")?;
+
+    module.ty(&["File"]).build::<File>()?;
+    module.async_function(&["open"], open)?;
+    module.require_capability(&["open"], "fs-read")?;
+    module.async_inst_fn("read_to_string", File::read_to_string)?;
+    module.inst_fn("close", File::close)?;
     Ok(module)
 }
 
-async fn read_to_string(path: &str) -> io::Result<String> {
-    fs::read_to_string(path).await
+/// Read a file, where the path is either a string or a `std::path::Path`.
+async fn read_to_string(path: Value) -> Result<io::Result<String>, VmError> {
+    let path = Path::to_path_buf(path)?;
+    Ok(fs::read_to_string(path).await)
+}
+
+/// Open a file for reading, where the path is either a string or a
+/// `std::path::Path`.
+async fn open(path: Value) -> Result<io::Result<File>, VmError> {
+    let path = Path::to_path_buf(path)?;
+
+    Ok(fs::File::open(path)
+        .await
+        .map(|file| File { inner: Some(file) }))
 }
+
+/// A file opened with `fs::open`, which can be closed with a `with`
+/// expression.
+struct File {
+    inner: Option<fs::File>,
+}
+
+impl File {
+    /// Read the rest of the file.
+    async fn read_to_string(&mut self) -> Result<io::Result<String>, VmError> {
+        let file = match &mut self.inner {
+            Some(file) => file,
+            None => return Ok(Err(io::Error::new(io::ErrorKind::Other, "file is closed"))),
+        };
+
+        let mut string = String::new();
+        Ok(file.read_to_string(&mut string).await.map(|_| string))
+    }
+
+    /// Close the file.
+    fn close(&mut self) {
+        self.inner = None;
+    }
+}
+
+runestick::impl_external!(File);
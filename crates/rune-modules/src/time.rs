@@ -28,36 +28,30 @@
 //! fn main() {
 //!     time::delay_for(time::Duration::from_secs(10)).await;
 //!     println("Message after 10 seconds!");
+//!
+//!     time::delay_for(std::time::Duration::parse("1m30s")).await;
+//!     println("Message after another 90 seconds!");
 //! }
 //! ```
 
-use runestick::{ContextError, Module};
+use runestick::{ContextError, Duration, Module, VmError, VmErrorKind};
 
 /// Construct the `time` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["time"]);
-    module.function(&["Duration", "from_secs"], Duration::from_secs)?;
+    module.function(&["Duration", "from_secs"], from_secs)?;
     module.async_function(&["delay_for"], delay_for)?;
     Ok(module)
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Duration {
-    inner: tokio::time::Duration,
-}
-
-impl Duration {
-    /// Construct a duration from seconds.
-    fn from_secs(secs: u64) -> Self {
-        Self {
-            inner: tokio::time::Duration::from_secs(secs),
-        }
-    }
+/// Construct a duration from seconds.
+fn from_secs(secs: i64) -> Result<Duration, VmError> {
+    Duration::from_secs(secs).ok_or_else(|| VmError::from(VmErrorKind::Overflow))
 }
 
-/// Convert any value to a json string.
+/// Wait for the given duration, where a negative duration doesn't wait at
+/// all.
 async fn delay_for(duration: &Duration) {
-    tokio::time::delay_for(duration.inner).await;
+    let duration = duration.to_std().unwrap_or_default();
+    tokio::time::delay_for(duration).await;
 }
-
-runestick::impl_external!(Duration);
@@ -45,11 +45,18 @@ pub use rune::WarningKind::*;
 use rune::Warnings;
 pub use runestick::VmErrorKind::*;
 pub use runestick::{CompileMeta, Function, Span, Value};
-use runestick::{Component, Item, Source, Unit};
+use runestick::{Component, Item, Source, Unit, Vm};
+use serde::Serialize;
 use std::cell::RefCell;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
 
+/// The environment variable which causes [assert_snapshot!] to write snapshots
+/// instead of comparing against them, when set to `1`.
+pub const UPDATE_SNAPSHOTS: &str = "RUNE_UPDATE_SNAPSHOTS";
+
 /// The result returned from our functions.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -62,8 +69,7 @@ pub fn compile_source(
     source: &str,
 ) -> Result<(Unit, Warnings), rune::LoadError> {
     let mut warnings = Warnings::new();
-    let mut sources = Sources::new();
-    sources.insert_default(Source::new("main", source.to_owned()));
+    let mut sources = sources(source);
     let unit = Rc::new(RefCell::new(UnitBuilder::with_default_prelude()));
 
     rune::compile(context, &mut sources, &unit, &mut warnings)?;
@@ -72,6 +78,37 @@ pub fn compile_source(
     Ok((unit.into_unit(), warnings))
 }
 
+/// Construct the sources for a test, which consist of the given source named
+/// `main`.
+pub fn sources(source: &str) -> Sources {
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source));
+    sources
+}
+
+/// Load the given source into a unit with the given options, like
+/// [rune::load_sources].
+///
+/// Unlike [compile_source], the unit is linked against the context and has
+/// the ABI of the context recorded.
+pub fn load_source(
+    context: &runestick::Context,
+    options: &rune::Options,
+    source: &str,
+) -> Result<(Unit, Warnings), rune::LoadError> {
+    let mut sources = sources(source);
+    let mut warnings = Warnings::new();
+    let unit = rune::load_sources(context, options, &mut sources, &mut warnings)?;
+    Ok((unit, warnings))
+}
+
+/// Load the given source with the default options, and construct a virtual
+/// machine for it with the given context.
+pub fn load_vm(context: runestick::Context, source: &str) -> Result<Vm, rune::LoadError> {
+    let (unit, _) = load_source(&context, &Default::default(), source)?;
+    Ok(Vm::new(Arc::new(context), Arc::new(unit)))
+}
+
 /// Call the specified function in the given script.
 pub async fn run_async<N, A, T>(function: N, args: A, source: &str) -> Result<T>
 where
@@ -100,6 +137,87 @@ where
     block_on(run_async(function, args, source))
 }
 
+/// Compare the value, serialized as pretty JSON with sorted object keys,
+/// against the snapshot stored at the given path.
+///
+/// If the [UPDATE_SNAPSHOTS] environment variable is set to `1`, the snapshot
+/// is written instead.
+///
+/// # Panics
+///
+/// Panics if the value can't be serialized, if the snapshot doesn't exist, or
+/// if it doesn't match the serialized value.
+pub fn assert_snapshot_at<T>(path: &Path, value: &T)
+where
+    T: ?Sized + Serialize,
+{
+    // NB: going through a JSON value sorts the keys of objects, which are
+    // otherwise serialized in an unspecified order.
+    let mut actual =
+        match serde_json::to_value(value).and_then(|v| serde_json::to_string_pretty(&v)) {
+            Ok(actual) => actual,
+            Err(error) => panic!("failed to serialize snapshot: {}", error),
+        };
+
+    actual.push('\n');
+
+    if std::env::var(UPDATE_SNAPSHOTS).map_or(false, |value| value == "1") {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("failed to create snapshot directory");
+        }
+
+        fs::write(path, actual).expect("failed to write snapshot");
+        return;
+    }
+
+    let expected = match fs::read_to_string(path) {
+        Ok(expected) => expected.replace("\r\n", "\n"),
+        Err(error) => panic!(
+            "failed to read snapshot `{}`: {}\n\nrun with {}=1 to create it",
+            path.display(),
+            error,
+            UPDATE_SNAPSHOTS
+        ),
+    };
+
+    if expected != actual {
+        panic!(
+            "snapshot `{}` doesn't match\n\nexpected:\n{}\nactual:\n{}\nrun with {}=1 to update it",
+            path.display(),
+            expected,
+            actual,
+            UPDATE_SNAPSHOTS
+        );
+    }
+}
+
+/// Get the path of the snapshot for the given function, as named by
+/// [std::any::type_name], and an optional name for the snapshot.
+#[doc(hidden)]
+pub fn snapshot_path(manifest_dir: &str, function: &str, name: Option<&str>) -> PathBuf {
+    let function = function.trim_end_matches("::{{closure}}");
+    let function = function.strip_suffix("::f").unwrap_or(function);
+
+    let mut file = function.replace("::", "__");
+
+    if let Some(name) = name {
+        file.push('-');
+        file.push_str(name);
+    }
+
+    file.push_str(".snap");
+    Path::new(manifest_dir)
+        .join("tests")
+        .join("snapshots")
+        .join(file)
+}
+
+/// Get the name of the type of the given value.
+#[doc(hidden)]
+pub fn type_name_of<T>(_: T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
 /// Run the given program and return the expected type from it.
 ///
 /// # Examples
@@ -130,7 +248,7 @@ macro_rules! rune {
 ///
 /// # fn main() {
 /// assert_parse_error! {
-///     r#"fn main() { 0 < 10 >= 10 }"#,
+///     r#"fn main() { 0 < 10 == 10 }"#,
 ///     PrecedenceGroupRequired { span } => {
 ///         assert_eq!(span, Span::new(12, 18));
 ///     }
@@ -285,3 +403,42 @@ macro_rules! assert_warnings {
         assert!(it.next().is_none(), "there should be no more warnings");
     }};
 }
+
+/// Assert that a value matches the golden snapshot stored for the current
+/// test, which is serialized as pretty JSON through `serde`. This includes
+/// values produced by scripts.
+///
+/// Snapshots are stored in the `tests/snapshots` directory of the crate, named
+/// after the test function and the optional name given to the snapshot. Run
+/// the tests with the [UPDATE_SNAPSHOTS] environment variable set to `1` to
+/// create or update them.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// use rune_testing::*;
+///
+/// # fn main() {
+/// let value = rune!(Value => r#"fn main() { #{name: "rune", tags: ["fast", "small"]} }"#);
+/// assert_snapshot!(value);
+/// assert_snapshot!("tags", rune!(Value => r#"fn main() { ["fast", "small"] }"#));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_snapshot {
+    ($value:expr) => {{
+        fn f() {}
+        let path = $crate::snapshot_path(env!("CARGO_MANIFEST_DIR"), $crate::type_name_of(f), None);
+        $crate::assert_snapshot_at(&path, &$value);
+    }};
+
+    ($name:expr, $value:expr) => {{
+        fn f() {}
+        let path = $crate::snapshot_path(
+            env!("CARGO_MANIFEST_DIR"),
+            $crate::type_name_of(f),
+            Some($name),
+        );
+        $crate::assert_snapshot_at(&path, &$value);
+    }};
+}
@@ -0,0 +1,157 @@
+use rune::{Options, Sources, UnitCache, Warnings};
+use runestick::{Context, Item, Source, Unit, Vm};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// A temporary directory which is removed when dropped.
+struct TempDir(PathBuf);
+
+impl TempDir {
+    fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("rune-cache-test-{}-{}", std::process::id(), name));
+
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn entries(dir: &Path) -> usize {
+    fs::read_dir(dir).map(|d| d.count()).unwrap_or_default()
+}
+
+fn load(
+    cache: &UnitCache,
+    context: &Context,
+    options: &Options,
+    source: Source,
+) -> (Unit, Sources, Warnings) {
+    let mut sources = Sources::new();
+    sources.insert_default(source);
+    let mut warnings = Warnings::new();
+    let unit = cache
+        .load_sources(context, options, &mut sources, &mut warnings)
+        .unwrap();
+    (unit, sources, warnings)
+}
+
+fn run(context: &Arc<Context>, unit: Unit) -> i64 {
+    let vm = Vm::new(context.clone(), Arc::new(unit));
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    output.into_integer().unwrap()
+}
+
+#[test]
+fn test_cached_unit() {
+    let dir = TempDir::new("cached-unit");
+    let cache = UnitCache::new(dir.0.join("cache"));
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let options = Options::default();
+
+    let source = r#"
+    fn add(a, b) { a + b }
+    fn main() { let unused = 1; add(1, 2) + `{41}`.len() }
+    "#;
+
+    let (unit, _, warnings) = load(&cache, &context, &options, Source::new("main", source));
+    assert_eq!(run(&context, unit), 5);
+    assert_eq!(entries(cache.dir()), 1);
+
+    let (unit, _, cached_warnings) = load(&cache, &context, &options, Source::new("main", source));
+    assert_eq!(run(&context, unit), 5);
+    assert_eq!(entries(cache.dir()), 1);
+    assert_eq!(
+        format!("{:?}", cached_warnings.iter().collect::<Vec<_>>()),
+        format!("{:?}", warnings.iter().collect::<Vec<_>>()),
+    );
+
+    // Different options compile into a different entry.
+    let mut options = Options::default();
+    options.define("FOO", "1");
+
+    let (unit, _, _) = load(&cache, &context, &options, Source::new("main", source));
+    assert_eq!(run(&context, unit), 5);
+    assert_eq!(entries(cache.dir()), 2);
+}
+
+#[test]
+fn test_cached_unit_with_modules() {
+    let dir = TempDir::new("modules");
+    let cache = UnitCache::new(dir.0.join("cache"));
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let options = Options::default();
+
+    let main = dir.0.join("main.rn");
+    let foo = dir.0.join("foo.rn");
+    fs::write(&main, "mod foo;\nfn main() { foo::value() }").unwrap();
+    fs::write(&foo, "fn value() { 1 }").unwrap();
+
+    let (unit, sources, _) = load(
+        &cache,
+        &context,
+        &options,
+        Source::from_path(&main).unwrap(),
+    );
+    assert_eq!(run(&context, unit), 1);
+    assert_eq!(sources.get(1).unwrap().path(), Some(&*foo));
+
+    let (unit, sources, _) = load(
+        &cache,
+        &context,
+        &options,
+        Source::from_path(&main).unwrap(),
+    );
+    assert_eq!(run(&context, unit), 1);
+    assert_eq!(sources.get(1).unwrap().path(), Some(&*foo));
+
+    // Changing a loaded module invalidates the cached unit.
+    fs::write(&foo, "fn value() { 2 }").unwrap();
+
+    let (unit, _, _) = load(
+        &cache,
+        &context,
+        &options,
+        Source::from_path(&main).unwrap(),
+    );
+    assert_eq!(run(&context, unit), 2);
+}
+
+#[test]
+fn test_concurrent_writes() {
+    let dir = TempDir::new("concurrent");
+    let cache_dir = dir.0.join("cache");
+
+    let threads = (0..8)
+        .map(|_| {
+            let cache_dir = cache_dir.clone();
+
+            std::thread::spawn(move || {
+                let cache = UnitCache::new(cache_dir);
+                let context = Arc::new(Context::with_default_modules().unwrap());
+                let source = Source::new("main", "fn main() { 1 + 2 }");
+                let (unit, _, _) = load(&cache, &context, &Options::default(), source);
+                run(&context, unit)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 3);
+    }
+
+    // Every write went through its own temporary file, so only the entry
+    // remains.
+    assert_eq!(entries(&cache_dir), 1);
+}
@@ -0,0 +1,150 @@
+use rune::{CompileError, LoadErrorKind, Warnings};
+use rune_testing::load_source;
+use runestick::{FromValue, Item, Span, Unit, Vm};
+use std::sync::Arc;
+
+fn load(defines: &[(&str, &str)], source: &str) -> Result<(Arc<Unit>, Warnings), rune::LoadError> {
+    let context = runestick::Context::with_default_modules().unwrap();
+    let mut options = rune::Options::default();
+
+    for (key, value) in defines {
+        options.define(*key, *value);
+    }
+
+    let (unit, warnings) = load_source(&context, &options, source)?;
+    Ok((Arc::new(unit), warnings))
+}
+
+fn run<T>(defines: &[(&str, &str)], source: &str) -> T
+where
+    T: FromValue,
+{
+    let context = Arc::new(runestick::Context::with_default_modules().unwrap());
+    let (unit, _) = load(defines, source).unwrap();
+    let vm = Vm::new(context, unit);
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    T::from_value(output).unwrap()
+}
+
+fn compile_error(defines: &[(&str, &str)], source: &str) -> CompileError {
+    match load(defines, source).unwrap_err().into_kind() {
+        LoadErrorKind::CompileError { error, .. } => error,
+        kind => panic!("expected compile error but got: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_env() {
+    let defines = [("TARGET", "web")];
+
+    assert_eq!(
+        run::<String>(&defines, r#"fn main() { env!("TARGET") }"#),
+        "web"
+    );
+
+    assert_eq!(
+        run::<Option<String>>(&defines, r#"fn main() { option_env!("TARGET") }"#),
+        Some(String::from("web"))
+    );
+
+    assert_eq!(
+        run::<Option<String>>(&defines, r#"fn main() { option_env!("MISSING") }"#),
+        None
+    );
+
+    match compile_error(&defines, r#"fn main() { env!("MISSING") }"#) {
+        CompileError::MissingDefine { span, name } => {
+            assert_eq!(span, Span::new(12, 27));
+            assert_eq!(name, "MISSING");
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_cfg() {
+    let source = r#"
+    #[cfg(target = "web")]
+    fn target() { "web" }
+
+    #[cfg(not(target = "web"))]
+    fn target() { "native" }
+
+    #[cfg(all(debug, any(target = "web", target = "native")))]
+    fn debug() { true }
+
+    #[cfg(not(debug))]
+    fn debug() { false }
+
+    fn main() { (target(), debug()) }
+    "#;
+
+    assert_eq!(
+        run::<(String, bool)>(&[("target", "web")], source),
+        (String::from("web"), false)
+    );
+
+    assert_eq!(
+        run::<(String, bool)>(&[("target", "native"), ("debug", "")], source),
+        (String::from("native"), true)
+    );
+}
+
+#[test]
+fn test_cfg_attr() {
+    let source = r#"
+    #[cfg_attr(web, cfg(debug))]
+    fn only_debug_on_web() { 1 }
+
+    fn main() { only_debug_on_web() }
+    "#;
+
+    assert_eq!(run::<i64>(&[], source), 1);
+    assert_eq!(run::<i64>(&[("web", ""), ("debug", "")], source), 1);
+
+    assert!(matches!(
+        compile_error(&[("web", "")], source),
+        CompileError::MissingFunction { .. }
+    ));
+}
+
+#[test]
+fn test_cfg_in_module() {
+    let source = r#"
+    mod a {
+        #[cfg(web)]
+        fn f() { 1 }
+    }
+
+    fn main() { a::f() }
+    "#;
+
+    assert_eq!(run::<i64>(&[("web", "")], source), 1);
+
+    assert!(matches!(
+        compile_error(&[], source),
+        CompileError::MissingFunction { .. }
+    ));
+}
+
+#[test]
+fn test_unsupported_attributes() {
+    assert!(matches!(
+        compile_error(&[], "#[inline] fn main() {}"),
+        CompileError::UnsupportedAttribute { .. }
+    ));
+
+    match compile_error(&[], "#[cfg(either(a, b))] fn main() {}") {
+        CompileError::UnsupportedCfg { span } => assert_eq!(span, Span::new(6, 18)),
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    assert!(matches!(
+        compile_error(&[], "#[cfg(a, b)] fn main() {}"),
+        CompileError::UnsupportedCfg { .. }
+    ));
+}
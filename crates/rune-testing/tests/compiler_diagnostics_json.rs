@@ -0,0 +1,58 @@
+use rune::{IntoJsonDiagnostics as _, JsonLevel, JsonPosition, Sources, Warnings};
+use rune_testing::{load_source, sources};
+use runestick::Unit;
+
+fn load(source: &str) -> (Result<(Unit, Warnings), rune::LoadError>, Sources) {
+    let context = runestick::Context::with_default_modules().unwrap();
+    let result = load_source(&context, &Default::default(), source);
+    (result, sources(source))
+}
+
+#[test]
+fn test_json_error() {
+    let (result, sources) = load("fn main() {\n    let a = 1 +;\n}");
+    let diagnostics = result.unwrap_err().into_json_diagnostics(&sources);
+
+    assert_eq!(diagnostics.len(), 1);
+    let d = &diagnostics[0];
+    assert_eq!(d.level, JsonLevel::Error);
+    assert_eq!(d.code.as_deref(), Some("E0015"));
+    assert_eq!(d.message, "parse error");
+    assert_eq!(d.labels.len(), 1);
+
+    let label = &d.labels[0];
+    assert!(label.primary);
+    assert_eq!(label.location.file, "main");
+    assert_eq!(label.location.span.start, 27);
+    assert_eq!(
+        label.location.start,
+        JsonPosition {
+            line: 2,
+            column: 16
+        }
+    );
+}
+
+#[test]
+fn test_json_warnings() {
+    let (result, sources) = load("fn main() { None() }");
+    let (_, warnings) = result.unwrap();
+
+    let diagnostics = warnings.into_json_diagnostics(&sources);
+    assert_eq!(diagnostics.len(), 1);
+
+    let d = &diagnostics[0];
+    assert_eq!(d.level, JsonLevel::Warning);
+    assert_eq!(d.suggestions.len(), 1);
+    assert_eq!(d.suggestions[0].replacement, "None");
+    assert_eq!(d.suggestions[0].location.span.start, 12);
+    assert_eq!(d.suggestions[0].location.span.end, 18);
+
+    let mut out = Vec::new();
+    rune::write_json_diagnostics(&mut out, &diagnostics).unwrap();
+    let out = String::from_utf8(out).unwrap();
+
+    assert_eq!(out.lines().count(), 1);
+    assert!(out.starts_with(r#"{"level":"warning","code":null,"#));
+    assert!(out.contains(r#""span":{"start":12,"end":18},"start":{"line":1,"column":13}"#));
+}
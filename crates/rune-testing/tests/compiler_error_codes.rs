@@ -0,0 +1,44 @@
+use rune_testing::*;
+
+fn error_code(source: &str) -> &'static str {
+    let context = runestick::Context::with_default_modules().unwrap();
+
+    let error = match compile_source(&context, source) {
+        Ok(..) => panic!("expected compilation to fail"),
+        Err(error) => error,
+    };
+
+    let code = match error.into_kind() {
+        rune::LoadErrorKind::ParseError { error, .. } => error.code(),
+        rune::LoadErrorKind::CompileError { error, .. } => error.code(),
+        kind => panic!("unexpected error: {:?}", kind),
+    };
+
+    assert!(rune::explain(code).is_some(), "no explanation for {}", code);
+    code
+}
+
+#[test]
+fn test_error_codes() {
+    assert_eq!(error_code("fn main() { let a = 1 +; }"), "E0015");
+    assert_eq!(error_code("fn main() { let a = \"hello; }"), "E0006");
+    assert_eq!(error_code("fn main() { 1 == 2 == 3 }"), "E0028");
+    assert_eq!(error_code("fn main() { `}` }"), "E0042");
+    assert_eq!(error_code("impl Foo { #[test] fn a() {} }"), "E0049");
+    assert_eq!(error_code("fn main() { a + 1 }"), "E0110");
+    assert_eq!(error_code("fn main() { loop { break 'outer; } }"), "E0113");
+    assert_eq!(error_code("fn main() { #{a: 1, a: 2} }"), "E0140");
+    assert_eq!(error_code("fn foo(self) { }"), "E0144");
+    assert_eq!(error_code("fn main() { #{a: 1, ..a} }"), "E0161");
+    assert_eq!(
+        error_code("#[derive(Builder)] struct A; fn main() {}"),
+        "E0162"
+    );
+    assert_eq!(error_code("fn a(b) {} fn main() { a() }"), "E0163");
+    assert_eq!(error_code("mod a { dbg(1); }"), "E0164");
+    assert_eq!(error_code("fn main() {} dbg(1);"), "E0165");
+    assert_eq!(error_code("let A = A; fn main() { A }"), "E0166");
+    assert_eq!(error_code("let A = { yield 1; }; fn main() { A }"), "E0167");
+    assert_eq!(error_code("#[memoize] async fn f() {}"), "E0168");
+    assert_eq!(error_code("use std::{string, vec}::new;"), "E0169");
+}
@@ -3,7 +3,7 @@ use rune_testing::*;
 #[test]
 fn test_binary_exprs() {
     assert_parse_error! {
-        r#"fn main() { 0 < 10 >= 10 }"#,
+        r#"fn main() { 0 < 10 == 10 }"#,
         PrecedenceGroupRequired { span } => {
             assert_eq!(span, Span::new(12, 18));
         }
@@ -14,4 +14,7 @@ fn test_binary_exprs() {
     assert_parse!(r#"fn main() { 0 < (10 >= 10) }"#);
     assert_parse!(r#"fn main() { 0 < 10 && 10 > 0 }"#);
     assert_parse!(r#"fn main() { 0 < 10 && 10 > 0 || true }"#);
+
+    // Ordering comparisons can be chained.
+    assert_parse!(r#"fn main() { 0 < 10 >= 10 }"#);
 }
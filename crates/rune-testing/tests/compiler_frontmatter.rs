@@ -0,0 +1,50 @@
+use rune_testing::*;
+
+#[test]
+fn test_shebang() {
+    assert_eq! {
+        rune!(i64 => "#!/usr/bin/env rune\nfn main() { 42 }"),
+        42,
+    };
+}
+
+#[test]
+fn test_frontmatter() {
+    let context = runestick::Context::with_default_modules().unwrap();
+
+    let source = r#"#!/usr/bin/env rune
+---
+# tool configuration for the script.
+name: hello
+options: link-checks=false
+---
+fn main() { 42 }
+"#;
+
+    let (unit, _) = compile_source(&context, source).unwrap();
+    let frontmatter = unit.frontmatter();
+
+    assert_eq!(frontmatter.get("name"), Some("hello"));
+    assert_eq!(frontmatter.get("options"), Some("link-checks=false"));
+    assert_eq!(frontmatter.iter().count(), 2);
+
+    let (unit, _) = compile_source(&context, "fn main() { 42 }").unwrap();
+    assert!(unit.frontmatter().is_empty());
+}
+
+#[test]
+fn test_bad_frontmatter() {
+    assert_parse_error! {
+        "---\nname: hello\n",
+        FrontmatterError { error: runestick::FrontmatterError::Unterminated { span } } => {
+            assert_eq!(span, Span::new(0, 16));
+        }
+    };
+
+    assert_parse_error! {
+        "---\nname: hello\nbad entry\n---\nfn main() { 42 }",
+        FrontmatterError { error: runestick::FrontmatterError::ExpectedEntry { span } } => {
+            assert_eq!(span, Span::new(16, 25));
+        }
+    };
+}
@@ -20,6 +20,16 @@ fn break_outside_of_loop() {
     };
 }
 
+#[test]
+fn continue_outside_of_loop() {
+    assert_compile_error! {
+        r#"fn main() { continue; }"#,
+        ContinueOutsideOfLoop { span } => {
+            assert_eq!(span, Span::new(12, 20));
+        }
+    };
+}
+
 #[test]
 fn test_pointers() {
     assert_compile_error! {
@@ -40,6 +50,16 @@ fn test_template_strings() {
             assert_eq!(span, Span::new(13, 20));
         }
     };
+
+    assert_parse!(r#"fn main() { `{{}}` }"#);
+    assert_parse!(r#"fn main() { let a = 1; `{`{`{a}`}`}` }"#);
+
+    assert_parse_error! {
+        r#"fn main() { `{"{"` }"#,
+        InvalidTemplateLiteral { span } => {
+            assert_eq!(span, Span::new(12, 20));
+        }
+    };
 }
 
 #[test]
@@ -63,6 +83,34 @@ fn test_wrong_arguments() {
     };
 }
 
+#[test]
+fn test_bad_argument_count() {
+    assert_compile_error! {
+        r#"fn connect(host, port) { } fn main() { connect("localhost") }"#,
+        CallArgumentCount { span, signature, expected, actual } => {
+            assert_eq!(span, Span::new(39, 59));
+            assert_eq!(signature.to_string(), "connect(host, port)");
+            assert_eq!(signature.declared, Some((0, Span::new(0, 22))));
+            assert_eq!((expected, actual), (2, 1));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Foo; impl Foo { fn new() { Foo } } fn main() { Foo::new(1) }"#,
+        CallArgumentCount { signature, .. } => {
+            assert_eq!(signature.to_string(), "Foo::new()");
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { String::new(1) }"#,
+        CallArgumentCount { signature, .. } => {
+            assert_eq!(signature.to_string(), "std::string::String::new()");
+            assert_eq!(signature.declared, None);
+        }
+    };
+}
+
 #[test]
 fn test_bad_struct_declaration() {
     assert_compile_error! {
@@ -90,3 +138,37 @@ fn test_bad_struct_declaration() {
         }
     };
 }
+
+#[test]
+fn test_computed_key_in_struct() {
+    assert_compile_error! {
+        r#"struct Foo { a } fn main() { Foo { a: 1, ["b"]: 2 } }"#,
+        UnsupportedComputedKey { span } => {
+            assert_eq!(span, Span::new(41, 49));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Foo { a } fn main() { Foo { a: 1, ["b"]: 2 }; }"#,
+        UnsupportedComputedKey { span } => {
+            assert_eq!(span, Span::new(41, 49));
+        }
+    };
+}
+
+#[test]
+fn test_struct_update() {
+    assert_compile_error! {
+        r#"struct Foo { a, b } fn main() { let b = #{}; #{a: 1, ..b} }"#,
+        UnsupportedStructUpdate { span } => {
+            assert_eq!(span, Span::new(53, 56));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Foo { a, b } fn main() { let f = Foo { a: 1, b: 2 }; Foo { c: 1, ..f } }"#,
+        LitObjectNotField { field, .. } => {
+            assert_eq!(field, "c");
+        }
+    };
+}
@@ -38,4 +38,100 @@ fn test_number_literals() {
             assert_eq!(span, Span::new(12, 78));
         }
     };
+
+    assert_parse!(r#"fn main() { 1_000_000 }"#);
+    assert_parse!(r#"fn main() { 1.5e-9 }"#);
+
+    assert_compile_error! {
+        r#"fn main() { 9_223_372_036_854_775_808 }"#,
+        ParseError { error: BadNumberOutOfBounds { span, .. }} => {
+            assert_eq!(span, Span::new(12, 37));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { 1e400 }"#,
+        ParseError { error: BadNumberOutOfBounds { span, .. }} => {
+            assert_eq!(span, Span::new(12, 17));
+        }
+    };
+}
+
+#[test]
+fn test_escape_spans() {
+    assert_compile_error! {
+        r#"fn main() { "hello \q world" }"#,
+        ParseError { error: BadEscapeSequence { span, .. }} => {
+            assert_eq!(span, Span::new(19, 21));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { '\u{110000}' }"#,
+        ParseError { error: BadUnicodeEscape { span, .. }} => {
+            assert_eq!(span, Span::new(13, 23));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { `{a} \xff` }"#,
+        ParseError { error: UnsupportedUnicodeByteEscape { span, .. }} => {
+            assert_eq!(span, Span::new(17, 21));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { b'\u{e5}' }"#,
+        ParseError { error: UnicodeEscapeNotSupported { span, .. }} => {
+            assert_eq!(span, Span::new(14, 20));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { b'\u{41}' }"#,
+        ParseError { error: UnicodeEscapeNotSupported { span, .. }} => {
+            assert_eq!(span, Span::new(14, 20));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { b"a\u{41}" }"#,
+        ParseError { error: UnicodeEscapeNotSupported { span, .. }} => {
+            assert_eq!(span, Span::new(15, 21));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { b"\q" }"#,
+        ParseError { error: BadEscapeSequence { span, .. }} => {
+            assert_eq!(span, Span::new(14, 16));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { b'' }"#,
+        ParseError { error: BadByteLiteral { span, .. }} => {
+            assert_eq!(span, Span::new(12, 15));
+        }
+    };
+}
+
+#[test]
+fn test_decimal_literals() {
+    assert_parse!(r#"fn main() { -1_000.05d }"#);
+    assert_parse!(r#"fn main() { 10d }"#);
+
+    assert_compile_error! {
+        r#"fn main() { 1e5d }"#,
+        ParseError { error: BadNumberLiteral { span, .. }} => {
+            assert_eq!(span, Span::new(12, 16));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { 0.00000000000000000000000000001d }"#,
+        ParseError { error: BadNumberLiteral { span, .. }} => {
+            assert_eq!(span, Span::new(12, 44));
+        }
+    };
 }
@@ -0,0 +1,107 @@
+use rune::Sources;
+use rune::{CompileError, IntoJsonDiagnostics as _, LoadErrorKind, MacroContext, TokenStream};
+use rune_testing::{load_source, sources};
+use runestick::{Source, Span, Unit};
+
+fn passthrough(_: &mut MacroContext, stream: &TokenStream) -> runestick::Result<TokenStream> {
+    Ok(stream.clone())
+}
+
+fn broken(ctx: &mut MacroContext, _: &TokenStream) -> runestick::Result<TokenStream> {
+    let stream = rune::quote!(ctx => + +);
+    assert!(ctx.expansion().is_some());
+    assert_eq!(stream.expansion(), ctx.expansion());
+    Ok(stream)
+}
+
+fn load(source: &str) -> (Result<Unit, rune::LoadError>, Sources) {
+    let mut module = runestick::Module::new(&["test"]);
+    module.macro_(&["passthrough"], passthrough).unwrap();
+    module.macro_(&["broken"], broken).unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let mut options = rune::Options::default();
+    options.parse_option("macros=true").unwrap();
+
+    let result = load_source(&context, &options, source).map(|(unit, _)| unit);
+    (result, sources(source))
+}
+
+fn compile_error(source: &str) -> CompileError {
+    match load(source).0.unwrap_err().into_kind() {
+        LoadErrorKind::CompileError { error, .. } => error,
+        kind => panic!("expected compile error but got: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_error_in_macro_input() {
+    let error = compile_error("fn main() { test::passthrough!(missing) }");
+
+    assert_eq!(error.code(), "E0110");
+    assert_eq!(error.span(), Span::new(31, 38));
+
+    let expansions = error
+        .expansions()
+        .map(|(span, item)| (span, item.to_string()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        expansions,
+        vec![(Span::new(12, 39), String::from("test::passthrough"))]
+    );
+
+    match error.root() {
+        CompileError::MissingLocal { name, .. } => assert_eq!(name, "missing"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_error_in_macro_output() {
+    let error = compile_error("fn main() { test::broken!() }");
+
+    assert_eq!(error.span(), Span::new(12, 27));
+    assert_eq!(error.expansions().count(), 1);
+    assert!(matches!(error.root(), CompileError::ParseError { .. }));
+}
+
+#[test]
+fn test_error_outside_macro() {
+    let error = compile_error("fn main() { test::passthrough!(1); missing }");
+
+    assert_eq!(error.expansions().count(), 0);
+    assert!(matches!(error, CompileError::MissingLocal { .. }));
+}
+
+#[test]
+fn test_json_expansion_labels() {
+    let (result, sources) = load("fn main() { test::passthrough!(missing) }");
+    let diagnostics = result.unwrap_err().into_json_diagnostics(&sources);
+
+    assert_eq!(diagnostics.len(), 1);
+    let labels = &diagnostics[0].labels;
+    assert_eq!(labels.len(), 2);
+
+    assert!(labels[0].primary);
+    assert_eq!(labels[0].location.span.start, 31);
+
+    assert!(!labels[1].primary);
+    assert_eq!(
+        labels[1].message,
+        "in this invocation of `test::passthrough!`"
+    );
+    assert_eq!(labels[1].location.span.start, 12);
+    assert_eq!(labels[1].location.span.end, 39);
+}
+
+#[test]
+fn test_no_expansion_outside_macro() {
+    let source = std::sync::Arc::new(Source::new("main", ""));
+    let ctx = MacroContext::new(source);
+    assert_eq!(ctx.expansion(), None);
+    assert_eq!(ctx.token_stream().expansion(), None);
+    assert_eq!(ctx.expansion_at(Span::new(0, 0)), None);
+}
@@ -0,0 +1,122 @@
+use rune::ast::{Delimiter, Kind};
+use rune::{CompileError, IntoTokens as _, LoadErrorKind, MacroContext, TokenStream};
+use rune_testing::load_source;
+use runestick::Span;
+use std::time::Duration;
+
+/// Expands `test::nest!(<path>)` into `<path>!(<path>)`, which recurses
+/// forever when given its own path.
+fn nest(ctx: &mut MacroContext, stream: &TokenStream) -> runestick::Result<TokenStream> {
+    let mut output = ctx.token_stream();
+    output.extend(stream.clone());
+    Kind::Bang.into_tokens(ctx, &mut output);
+    Kind::Open(Delimiter::Parenthesis).into_tokens(ctx, &mut output);
+    output.extend(stream.clone());
+    Kind::Close(Delimiter::Parenthesis).into_tokens(ctx, &mut output);
+    Ok(output)
+}
+
+fn many(ctx: &mut MacroContext, _: &TokenStream) -> runestick::Result<TokenStream> {
+    let mut output = ctx.token_stream();
+    Kind::Open(Delimiter::Parenthesis).into_tokens(ctx, &mut output);
+
+    for _ in 0..16 {
+        Kind::Comma.into_tokens(ctx, &mut output);
+    }
+
+    Kind::Close(Delimiter::Parenthesis).into_tokens(ctx, &mut output);
+    Ok(output)
+}
+
+fn slow(ctx: &mut MacroContext, _: &TokenStream) -> runestick::Result<TokenStream> {
+    std::thread::sleep(Duration::from_millis(20));
+    Ok(rune::quote!(ctx => ()))
+}
+
+fn compile_error(options: &[&str], source: &str) -> CompileError {
+    let mut module = runestick::Module::new(&["test"]);
+    module.macro_(&["nest"], nest).unwrap();
+    module.macro_(&["many"], many).unwrap();
+    module.macro_(&["slow"], slow).unwrap();
+
+    let mut context = runestick::Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let mut opts = rune::Options::default();
+    opts.parse_option("macros=true").unwrap();
+
+    for option in options {
+        opts.parse_option(option).unwrap();
+    }
+
+    match load_source(&context, &opts, source) {
+        Ok(..) => panic!("expected compile error"),
+        Err(error) => match error.into_kind() {
+            LoadErrorKind::CompileError { error, .. } => error,
+            kind => panic!("expected compile error but got: {:?}", kind),
+        },
+    }
+}
+
+#[test]
+fn test_macro_depth_limit() {
+    let error = compile_error(&["macro-depth=8"], "fn main() { test::nest!(test::nest) }");
+
+    match error {
+        CompileError::MacroDepthLimit { span, limit } => {
+            assert_eq!(limit, 8);
+            assert_eq!(span.start, 12);
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    let error = compile_error(&[], "fn main() { test::nest!(test::nest) }");
+    assert!(matches!(
+        error,
+        CompileError::MacroDepthLimit { limit: 64, .. }
+    ));
+    assert_eq!(error.code(), "E0148");
+}
+
+#[test]
+fn test_macro_token_limit() {
+    let error = compile_error(&["macro-tokens=10"], "fn main() { test::many!() }");
+
+    match error {
+        CompileError::MacroTokenLimit {
+            span,
+            tokens,
+            limit,
+        } => {
+            assert_eq!(span, Span::new(12, 25));
+            assert_eq!(tokens, 18);
+            assert_eq!(limit, 10);
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_macro_time_limit() {
+    let error = compile_error(&["macro-time=1"], "fn main() { test::slow!() }");
+
+    match error {
+        CompileError::MacroTimeLimit {
+            span,
+            elapsed,
+            limit,
+        } => {
+            assert_eq!(span, Span::new(12, 25));
+            assert!(elapsed >= Duration::from_millis(20));
+            assert_eq!(limit, Duration::from_millis(1));
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
+
+#[test]
+fn test_invalid_limit() {
+    let mut options = rune::Options::default();
+    assert!(options.parse_option("macro-depth=many").is_err());
+    assert!(options.parse_option("macro-tokens").is_err());
+}
@@ -0,0 +1,54 @@
+use rune::{Options, Warnings};
+use rune_testing::sources;
+use runestick::Context;
+
+#[test]
+fn test_compile_report() {
+    let context = Context::with_default_modules().unwrap();
+    let options = Options::default();
+    let mut warnings = Warnings::new();
+
+    let mut sources = sources(
+        r#"
+        struct Point { x, y }
+
+        impl Point {
+            fn sum(self) { self.x + self.y }
+        }
+
+        fn main() {
+            let f = |p| p.sum();
+            f(Point { x: 1, y: 2 })
+        }
+        "#,
+    );
+
+    let (unit, report) =
+        rune::load_sources_with_report(&context, &options, &mut sources, &mut warnings).unwrap();
+
+    assert_eq!(report.sources, 1);
+    // The struct and the closure.
+    assert_eq!(report.indexed, 2);
+
+    let mut items = report
+        .functions
+        .iter()
+        .map(|f| f.item.to_string())
+        .collect::<Vec<_>>();
+
+    items.sort();
+    assert_eq!(
+        items,
+        vec!["Point::sum", "main", "main::$block0::$closure0"]
+    );
+
+    assert!(report.functions.iter().all(|f| f.instructions > 0));
+    assert_eq!(report.instructions(), unit.iter_instructions().count());
+    assert_eq!(
+        report.total(),
+        report.lex + report.parse + report.index + report.build + report.assembly + report.link
+    );
+
+    let output = report.to_string();
+    assert!(output.contains("functions: 3"));
+}
@@ -0,0 +1,109 @@
+use rune::testing;
+use runestick::{Context, Unit};
+
+const SOURCE: &str = r#"
+struct Point { x, y }
+
+enum Shape { Circle(radius), Rect { w, h } }
+
+fn area(shape) {
+    match shape {
+        Shape::Circle(r) => 3 * r * r,
+        Shape::Rect { w, h } => w * h,
+    }
+}
+
+fn point(x, y) {
+    Point { x, y }
+}
+
+async fn later(n) {
+    n + 1
+}
+
+fn gen() {
+    yield 1;
+    yield 2;
+}
+
+fn main() {
+    let v = [1, 2, 3];
+    v.push(4);
+    let o = #{ first: 1, second: "two", third: b"three" };
+    let add = |a, b| a + b;
+    let p = point(1, 2);
+
+    match o {
+        #{ first, second, .. } => add(first, p.x),
+        _ => area(Shape::Rect { w: p.x, h: p.y }),
+    }
+}
+"#;
+
+/// Render everything in the unit which affects how it's executed, in the
+/// order the unit provides it.
+fn layout(unit: &Unit) -> Vec<String> {
+    let mut out = Vec::new();
+
+    for inst in unit.iter_instructions() {
+        out.push(format!("inst {:?}", inst));
+    }
+
+    for (hash, f) in unit.iter_functions() {
+        out.push(format!("fn {} {:?}", hash, f));
+    }
+
+    for string in unit.iter_static_strings() {
+        out.push(format!("string {:?}", string.as_str()));
+    }
+
+    for (hash, keys) in unit.iter_static_object_keys() {
+        out.push(format!("keys {} {:?}", hash, keys));
+    }
+
+    out
+}
+
+#[test]
+fn test_compiles_are_reproducible() {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = testing::compile(&context, SOURCE).unwrap();
+    let expected = layout(&unit);
+
+    for _ in 0..16 {
+        let (unit, _) = testing::compile(&context, SOURCE).unwrap();
+        assert_eq!(layout(&unit), expected);
+    }
+}
+
+#[test]
+fn test_missing_imports_are_reported_in_order() {
+    let context = Context::with_default_modules().unwrap();
+
+    let source = r#"
+    use zzz::a;
+    use aaa::b;
+    use mmm::c;
+
+    fn main() {}
+    "#;
+
+    let expected = format!("{:?}", testing::compile(&context, source).unwrap_err());
+
+    for _ in 0..16 {
+        let error = testing::compile(&context, source).unwrap_err();
+        assert_eq!(format!("{:?}", error), expected);
+    }
+}
+
+#[test]
+fn test_serialized_units_are_reproducible() {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = testing::compile(&context, SOURCE).unwrap();
+    let expected = serde_json::to_string(&unit).unwrap();
+
+    for _ in 0..16 {
+        let (unit, _) = testing::compile(&context, SOURCE).unwrap();
+        assert_eq!(serde_json::to_string(&unit).unwrap(), expected);
+    }
+}
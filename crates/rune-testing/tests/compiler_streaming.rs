@@ -0,0 +1,129 @@
+use rune::{CompileError, LoadErrorKind, Options, Sources, StreamingCompiler, StreamingParser};
+use runestick::{Context, Item, Span, Unit, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+use std::string::String;
+
+struct Point { x, y };
+
+impl Point {
+    fn sum(self) {
+        #{ x: self.x, y: self.y }.x + self.y
+    }
+}
+
+fn apply(f, p) {
+    f(p.x, p.y)
+}
+
+fn main() {
+    let a = apply(|x, y| x * y, Point { x: 3, y: 4 });
+    let b = apply(|x, y| x + y, Point { x: 3, y: 4 });
+    let s = String::from_str("abc");
+    let c = `{a}/{b}/{s}/{Point { x: 1, y: 2 }.sum()}`;
+    c
+}
+"#;
+
+fn compile_streaming(context: &Context, chunks: &[&str]) -> Result<Unit, LoadErrorKind> {
+    let options = Options::default();
+    let mut sources = Sources::new();
+    let mut warnings = rune::Warnings::new();
+
+    let mut compiler =
+        StreamingCompiler::new(context, &options, &mut sources, &mut warnings, "main");
+
+    for chunk in chunks {
+        compiler.push(chunk).map_err(|e| e.into_kind())?;
+    }
+
+    compiler.finish().map_err(|e| e.into_kind())
+}
+
+fn run(context: Arc<Context>, unit: Unit) -> String {
+    let vm = Vm::new(context, Arc::new(unit));
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    output.into_string().unwrap().take().unwrap()
+}
+
+#[test]
+fn test_streaming_by_character() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let chunks = SOURCE
+        .char_indices()
+        .map(|(n, c)| &SOURCE[n..n + c.len_utf8()])
+        .collect::<Vec<_>>();
+
+    let unit = compile_streaming(&context, &chunks).unwrap();
+    assert_eq!(run(context, unit), "12/7/abc/3");
+}
+
+#[test]
+fn test_streaming_matches_compile() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let (expected, _) = rune::testing::compile(&context, SOURCE).unwrap();
+    let unit = compile_streaming(&context, &[SOURCE]).unwrap();
+
+    assert_eq!(
+        format!("{:?}", unit.iter_instructions().collect::<Vec<_>>()),
+        format!("{:?}", expected.iter_instructions().collect::<Vec<_>>()),
+    );
+}
+
+#[test]
+fn test_streaming_parser_declarations() {
+    let mut parser = StreamingParser::new();
+
+    assert_eq!(parser.push("struct A { a").decls.len(), 0);
+    // The declaration isn't complete until we know if a semi-colon follows.
+    assert_eq!(parser.push("}").decls.len(), 0);
+    assert_eq!(parser.push(";\nfn main() {}").decls.len(), 1);
+    assert_eq!(parser.push(" fn foo() {").decls.len(), 1);
+    assert_eq!(parser.push("}").decls.len(), 0);
+    assert_eq!(parser.finish().unwrap().decls.len(), 1);
+}
+
+#[test]
+fn test_streaming_errors() {
+    let context = Context::with_default_modules().unwrap();
+
+    match compile_streaming(&context, &["fn main() { 1 }\nfn ", "foo( {}"]).unwrap_err() {
+        LoadErrorKind::ParseError { source_id, error } => {
+            assert_eq!(source_id, 0);
+            assert_eq!(error.span(), Span::new(24, 25));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    match compile_streaming(&context, &["fn main() { missing() }\n", "fn foo() {}"]).unwrap_err() {
+        LoadErrorKind::CompileError {
+            error: CompileError::MissingFunction { span, .. },
+            ..
+        } => {
+            assert_eq!(span, Span::new(12, 21));
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_streaming_script() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let source = "let a = 1;\nif a > 0 {\n`a`\n} else {\n`b`\n}\n";
+
+    let chunks = source
+        .char_indices()
+        .map(|(n, c)| &source[n..n + c.len_utf8()])
+        .collect::<Vec<_>>();
+
+    let unit = compile_streaming(&context, &chunks).unwrap();
+    assert_eq!(run(context, unit), "a");
+}
@@ -0,0 +1,58 @@
+use rune::testing;
+use rune::{CompileError, WarningKind};
+use runestick::{Context, Module, Span};
+
+fn context() -> Context {
+    let mut module = Module::new(&["http"]);
+    module.function(&["get"], |url: &str| url.len()).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    context
+}
+
+#[test]
+fn test_assert_compile_error() {
+    let context = context();
+
+    let error = testing::assert_compile_error(&context, "fn main() { http::post() }", |error| {
+        matches!(error, CompileError::MissingFunction { .. })
+    });
+
+    assert_eq!(error.span(), Span::new(12, 24));
+    assert!(testing::compile(&context, r#"fn main() { http::get("/") }"#).is_ok());
+}
+
+#[test]
+#[should_panic(expected = "expected a compile error, but the source compiled")]
+fn test_assert_compile_error_compiles() {
+    testing::assert_compile_error(&context(), r#"fn main() { http::get("/") }"#, |_| true);
+}
+
+#[test]
+#[should_panic(expected = "wasn't matched")]
+fn test_assert_compile_error_unmatched() {
+    testing::assert_compile_error(&context(), "fn main() { break; }", |error| {
+        matches!(error, CompileError::MissingFunction { .. })
+    });
+}
+
+#[test]
+fn test_assert_warning() {
+    let warning = testing::assert_warning(&context(), "fn main() { `hello` }", |warning| {
+        matches!(warning, WarningKind::TemplateWithoutExpansions { .. })
+    });
+
+    match warning.kind {
+        WarningKind::TemplateWithoutExpansions { span, .. } => {
+            assert_eq!(span, Span::new(12, 19));
+        }
+        kind => panic!("unexpected warning: {:?}", kind),
+    }
+}
+
+#[test]
+#[should_panic(expected = "no warning was matched")]
+fn test_assert_warning_unmatched() {
+    testing::assert_warning(&context(), "fn main() { 42 }", |_| true);
+}
@@ -29,3 +29,97 @@ fn test_remove_variant_parens() {
         }
     };
 }
+
+#[test]
+fn test_mutate_while_iterating() {
+    assert_warnings! {
+        r#"fn main() { let v = [1]; for n in v { v.push(n); } }"#,
+        MutateWhileIterating { span, iter, .. } => {
+            assert_eq!(span, Span::new(38, 47));
+            assert_eq!(iter, Span::new(34, 35));
+        }
+    };
+}
+
+#[test]
+fn test_computed_key_shadows_field() {
+    assert_warnings! {
+        r#"fn main() { let x = 1; #{x, ["x"]: 2} }"#,
+        ComputedKeyShadowsField { span, field, .. } => {
+            assert_eq!(span, Span::new(29, 32));
+            assert_eq!(field, Span::new(25, 26));
+        }
+    };
+
+    assert_warnings! {
+        r#"fn main() { let x = 1; #{x, [`x`]: 2} }"#,
+        ComputedKeyShadowsField { span, field, .. } => {
+            assert_eq!(span, Span::new(29, 32));
+            assert_eq!(field, Span::new(25, 26));
+        },
+        TemplateWithoutExpansions { .. } => {}
+    };
+
+    assert_warnings! {
+        r#"fn main() { let x = 1; #{x, [("x")]: 2} }"#,
+        ComputedKeyShadowsField { span, field, .. } => {
+            assert_eq!(span, Span::new(29, 34));
+            assert_eq!(field, Span::new(25, 26));
+        }
+    };
+}
+
+#[test]
+fn test_not_boolean() {
+    assert_warnings! {
+        r#"fn main() { if 1 { } }"#,
+        NotBoolean { span, .. } => {
+            assert_eq!(span, Span::new(15, 16));
+        }
+    };
+
+    assert_warnings! {
+        r#"fn main() { true && "yes" }"#,
+        NotBoolean { span, .. } => {
+            assert_eq!(span, Span::new(20, 25));
+        }
+    };
+}
+
+#[test]
+fn test_branch_without_value() {
+    assert_warnings! {
+        r#"fn main() { let n = if true { 1 } else { }; }"#,
+        BranchWithoutValue { span, value, .. } => {
+            assert_eq!(span, Span::new(39, 42));
+            assert_eq!(value, Span::new(28, 33));
+        }
+    };
+
+    assert_warnings! {
+        r#"fn main() { let n = match 1 { 1 => 2, _ => {} }; }"#,
+        BranchWithoutValue { span, value, .. } => {
+            assert_eq!(span, Span::new(43, 45));
+            assert_eq!(value, Span::new(35, 36));
+        }
+    };
+}
+
+#[test]
+fn test_semi_colon_discards_value() {
+    assert_warnings! {
+        r#"fn add(a, b) { a + b; } fn main() { add(1, 2) }"#,
+        SemiColonDiscardsValue { span, value, .. } => {
+            assert_eq!(span, Span::new(20, 21));
+            assert_eq!(value, Span::new(15, 20));
+        }
+    };
+
+    assert_warnings! {
+        r#"fn main() { let n = { 1 + 2; }; }"#,
+        SemiColonDiscardsValue { span, value, .. } => {
+            assert_eq!(span, Span::new(27, 28));
+            assert_eq!(value, Span::new(22, 27));
+        }
+    };
+}
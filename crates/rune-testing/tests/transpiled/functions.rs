@@ -0,0 +1,152 @@
+// Generated by `rune::transpile`, do not edit.
+
+/// Construct a module with the transpiled functions.
+pub fn module() -> Result<runestick::Module, runestick::ContextError> {
+    let mut module = runestick::Module::default();
+    module.raw_fn(&["fib"], fib)?;
+    module.raw_fn(&["sum"], sum)?;
+    module.raw_fn(&["f"], f)?;
+    Ok(module)
+}
+
+/// Transpiled from `fib`.
+pub fn fib(stack: &mut runestick::Stack, args: usize) -> Result<(), runestick::VmError> {
+    let frame = runestick::NativeFrame::from_stack(stack, args, 1)?;
+    stack.push(fib_impl(frame)?);
+    Ok(())
+}
+
+fn fib_impl(mut frame: runestick::NativeFrame) -> Result<runestick::Value, runestick::VmError> {
+    let mut block = 0;
+
+    loop {
+        match block {
+            0 => {
+                frame.copy(0)?;
+                frame.push(runestick::Value::Integer(1));
+                frame.lte()?;
+                block = if frame.pop_bool()? { 14 } else { 4 };
+            }
+            4 => {
+                frame.copy(0)?;
+                frame.push(runestick::Value::Integer(1));
+                frame.sub()?;
+                let value = fib_impl(frame.call(1, 1)?)?;
+                frame.push(value);
+                frame.copy(0)?;
+                frame.push(runestick::Value::Integer(2));
+                frame.sub()?;
+                let value = fib_impl(frame.call(1, 1)?)?;
+                frame.push(value);
+                frame.add()?;
+                block = 15;
+            }
+            14 => {
+                frame.copy(0)?;
+                block = 15;
+            }
+            15 => {
+                frame.clean(1)?;
+                return frame.pop();
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Transpiled from `sum`.
+pub fn sum(stack: &mut runestick::Stack, args: usize) -> Result<(), runestick::VmError> {
+    let frame = runestick::NativeFrame::from_stack(stack, args, 1)?;
+    stack.push(sum_impl(frame)?);
+    Ok(())
+}
+
+fn sum_impl(mut frame: runestick::NativeFrame) -> Result<runestick::Value, runestick::VmError> {
+    let mut block = 0;
+
+    loop {
+        match block {
+            0 => {
+                frame.push(runestick::Value::Integer(0));
+                frame.push(runestick::Value::Integer(0));
+                block = 2;
+            }
+            2 => {
+                frame.copy(2)?;
+                frame.copy(0)?;
+                frame.lt()?;
+                block = if frame.pop_bool()? { 7 } else { 6 };
+            }
+            6 => {
+                block = 12;
+            }
+            7 => {
+                frame.copy(2)?;
+                frame.add_assign(1)?;
+                frame.push(runestick::Value::Integer(1));
+                frame.add_assign(2)?;
+                block = 2;
+            }
+            12 => {
+                frame.copy(1)?;
+                frame.clean(3)?;
+                return frame.pop();
+            }
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Transpiled from `f`.
+pub fn f(stack: &mut runestick::Stack, args: usize) -> Result<(), runestick::VmError> {
+    let frame = runestick::NativeFrame::from_stack(stack, args, 2)?;
+    stack.push(f_impl(frame)?);
+    Ok(())
+}
+
+fn f_impl(mut frame: runestick::NativeFrame) -> Result<runestick::Value, runestick::VmError> {
+    let mut block = 0;
+
+    loop {
+        match block {
+            0 => {
+                frame.copy(0)?;
+                frame.push(runestick::Value::Float(2.0));
+                frame.mul()?;
+                frame.copy(2)?;
+                frame.copy(1)?;
+                frame.gt()?;
+                if frame.pop_bool()? {
+                    block = 7;
+                } else {
+                    frame.push(runestick::Value::Bool(false));
+                    block = 11;
+                }
+            }
+            7 => {
+                frame.copy(1)?;
+                frame.push(runestick::Value::Float(0.0));
+                frame.lt()?;
+                frame.not()?;
+                block = 11;
+            }
+            11 => {
+                block = if frame.pop_bool()? { 13 } else { 12 };
+            }
+            12 => {
+                block = 16;
+            }
+            13 => {
+                frame.copy(2)?;
+                frame.clean(3)?;
+                return frame.pop();
+            }
+            16 => {
+                frame.copy(1)?;
+                frame.clean(3)?;
+                return frame.pop();
+            }
+            _ => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Item, Module, Vm};
+use std::sync::Arc;
+
+#[derive(Debug, Clone, Default)]
+struct Counter {
+    value: i64,
+}
+
+impl Counter {
+    fn inc(&mut self) {
+        self.value += 1;
+    }
+
+    fn value(&self) -> i64 {
+        self.value
+    }
+}
+
+runestick::impl_external!(Counter);
+
+#[derive(Debug, Default)]
+struct Opaque;
+
+runestick::impl_external!(Opaque);
+
+fn vm(source: &str) -> Vm {
+    let mut module = Module::new(&["host"]);
+    module.ty(&["Counter"]).build::<Counter>().unwrap();
+    module.ty(&["Opaque"]).build::<Opaque>().unwrap();
+    module
+        .function(&["Counter", "new"], Counter::default)
+        .unwrap();
+    module
+        .function(&["Opaque", "new"], Opaque::default)
+        .unwrap();
+    module.inst_fn(runestick::CLONE, Counter::clone).unwrap();
+    module.inst_fn("inc", Counter::inc).unwrap();
+    module.inst_fn("value", Counter::value).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = compile_source(&context, source).unwrap();
+    Vm::new(Arc::new(context), Arc::new(unit))
+}
+
+#[test]
+fn test_deep_clone() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let a = [1, [2, 3]];
+                let b = std::clone::deep(a);
+                b[1].push(4);
+                b.push(5);
+                let a = a[1];
+                let b = b[1];
+                (a, b)
+            }
+            "#
+        },
+        (vec![2, 3], vec![2, 3, 4]),
+    };
+
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            struct Point { x, y }
+
+            fn main() {
+                let a = Point { x: 1, y: #{ n: 2 } };
+                let b = std::clone::deep(a);
+                let y = b.y;
+                y["n"] = 3;
+                (a.y.n, b.y.n)
+            }
+            "#
+        },
+        (2, 3),
+    };
+}
+
+#[test]
+fn test_deep_clone_cycle() {
+    assert_eq! {
+        rune! {
+            bool => r#"
+            fn main() {
+                let a = [1];
+                a.push(a);
+                let b = std::clone::deep(a);
+                b[1].push(2);
+                a.len() == 2 && b.len() == 3
+            }
+            "#
+        },
+        true,
+    };
+}
+
+#[test]
+fn test_deep_hash() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            fn main() {
+                let a = std::hash::hash(#{ a: [1, 2], b: "hello" });
+                let b = std::hash::hash(#{ b: "hello", a: [1, 2] });
+                let c = std::hash::hash(#{ a: [2, 1], b: "hello" });
+                (a == b, a != c, std::hash::hash((1, 2)) != std::hash::hash([1, 2]))
+            }
+            "#
+        },
+        (true, true, true),
+    };
+
+    assert_eq! {
+        rune! {
+            bool => r#"
+            fn main() {
+                let a = [1];
+                a.push(a);
+                std::hash::hash(a) == std::hash::hash(a)
+            }
+            "#
+        },
+        true,
+    };
+}
+
+#[test]
+fn test_deep_clone_external() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            use std::bitset::BitSet;
+
+            fn main() {
+                let a = [BitSet::new()];
+                let b = std::clone::deep(a);
+                b[0].insert(1);
+                (a[0].len(), b[0].len())
+            }
+            "#
+        },
+        (0, 1),
+    };
+}
+
+#[test]
+fn test_deep_clone_host_type() {
+    let vm = vm(r#"
+    use host::Counter;
+
+    fn main() {
+        let a = #{ counter: Counter::new() };
+        let b = std::clone::deep(a);
+        b.counter.inc();
+        b.counter.inc();
+        (a.counter.value(), b.counter.value())
+    }
+    "#);
+
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    let output = <(i64, i64)>::from_value(output).unwrap();
+    assert_eq!(output, (0, 2));
+}
+
+#[test]
+fn test_deep_clone_missing_protocol() {
+    let vm = vm(r#"
+    fn main() {
+        std::clone::deep([host::Opaque::new()])
+    }
+    "#);
+
+    let error = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        MissingProtocol { protocol, .. } => {
+            assert_eq!(protocol.hash, runestick::CLONE.hash);
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
@@ -0,0 +1,243 @@
+use rune_testing::*;
+
+#[test]
+fn test_defer_on_block_exit() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn main() {
+                let log = [];
+
+                {
+                    defer { log.push("a"); }
+                    defer { log.push("b"); }
+                    log.push("body");
+                }
+
+                log.push("after");
+                log
+            }
+            "#
+        },
+        vec!["body", "b", "a", "after"],
+    };
+}
+
+#[test]
+fn test_defer_keeps_block_value() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 1;
+
+                let value = {
+                    let m = 10;
+                    defer { n = 100; }
+                    n + m
+                };
+
+                value + n
+            }
+            "#
+        },
+        111,
+    };
+}
+
+#[test]
+fn test_defer_on_return() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn foo(log, early) {
+                defer { log.push("outer"); }
+
+                if early {
+                    let name = "inner";
+                    defer { log.push(name); }
+                    return "early";
+                }
+
+                "late"
+            }
+
+            fn main() {
+                let log = [];
+                log.push(foo(log, true));
+                log.push(foo(log, false));
+                log
+            }
+            "#
+        },
+        vec!["inner", "outer", "early", "outer", "late"],
+    };
+}
+
+#[test]
+fn test_defer_on_try() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn foo(log, value) {
+                defer { log.push("cleanup"); }
+                let value = value?;
+                log.push(`ok {value}`);
+                Ok(value)
+            }
+
+            fn main() {
+                let log = [];
+                foo(log, Ok(1));
+                foo(log, Err("bad"));
+                log
+            }
+            "#
+        },
+        vec!["ok 1", "cleanup", "cleanup"],
+    };
+}
+
+#[test]
+fn test_defer_on_break() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn main() {
+                let log = [];
+                defer { log.push("end"); }
+
+                let value = loop {
+                    defer { log.push("loop"); }
+                    break 42;
+                };
+
+                for n in [1, 2, 3] {
+                    defer { log.push(`iteration {n}`); }
+
+                    if n == 2 {
+                        break;
+                    }
+                }
+
+                log.push(`value {value}`);
+                log
+            }
+            "#
+        },
+        vec!["loop", "iteration 1", "iteration 2", "value 42", "end"],
+    };
+}
+
+#[test]
+fn test_defer_on_continue() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn main() {
+                let log = [];
+
+                for n in [1, 2, 3] {
+                    defer { log.push(`iteration {n}`); }
+
+                    if n == 2 {
+                        continue;
+                    }
+
+                    log.push(`body {n}`);
+                }
+
+                log
+            }
+            "#
+        },
+        vec!["body 1", "iteration 1", "iteration 2", "body 3", "iteration 3"],
+    };
+}
+
+#[test]
+fn test_defer_sees_variables_where_deferred() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn foo(log) {
+                let name = "outer";
+                defer { log.push(name); }
+
+                {
+                    let name = "shadowed";
+                    log.push(name);
+                    return;
+                }
+            }
+
+            fn main() {
+                let log = [];
+                foo(log);
+                log
+            }
+            "#
+        },
+        vec!["shadowed", "outer"],
+    };
+}
+
+#[test]
+fn test_defer_with_closure() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn foo(out, n) {
+                defer {
+                    let add = |a| a + n;
+                    out.push(add(1));
+                }
+
+                if n > 1 {
+                    return;
+                }
+            }
+
+            fn main() {
+                let out = [];
+                foo(out, 1);
+                foo(out, 2);
+                out[0] + out[1]
+            }
+            "#
+        },
+        5,
+    };
+}
+
+#[test]
+fn test_unsupported_defer() {
+    assert_compile_error! {
+        r#"fn main() { let cleanup = defer { println("done"); }; }"#,
+        UnsupportedDefer { span } => {
+            assert_eq!(span, Span::new(26, 52));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { defer { return; } }"#,
+        ReturnInDefer { span } => {
+            assert_eq!(span, Span::new(20, 26));
+        }
+    };
+
+    assert_compile_error! {
+        r#"fn main() { defer { Ok(1)?; } }"#,
+        ReturnInDefer { .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"fn main() { loop { defer { break; } } }"#,
+        BreakOutsideOfLoop { .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"fn main() { loop { defer { continue; } } }"#,
+        ContinueOutsideOfLoop { .. } => {}
+    };
+}
@@ -0,0 +1,60 @@
+use rune_testing::*;
+
+#[test]
+fn test_freeze() {
+    assert_eq! {
+        rune! {
+            (bool, bool, i64) => r#"
+            fn main() {
+                let a = [1, 2];
+                let b = std::freeze(a);
+                (std::is_frozen(a), std::is_writable(b), b.len())
+            }
+            "#
+        },
+        (true, false, 2),
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = std::freeze([1, 2]);
+            a.push(3);
+        }
+        "#,
+        BadArgument { error, .. } => {
+            assert_eq!(
+                error.to_string(),
+                "failed to access value: cannot write, value is frozen"
+            );
+        }
+    );
+}
+
+#[test]
+fn test_freeze_deep() {
+    assert_eq! {
+        rune! {
+            (bool, bool) => r#"
+            fn main() {
+                let shallow = std::freeze(#{ a: [1] });
+                let deep = std::freeze_deep(#{ a: [1] });
+                (std::is_frozen(shallow.a), std::is_frozen(deep.a))
+            }
+            "#
+        },
+        (false, true),
+    };
+
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let a = [1];
+            a.push(a);
+            let a = std::freeze_deep(#{ a: a });
+            a.a[1].push(2);
+        }
+        "#,
+        BadArgument { .. } => {}
+    );
+}
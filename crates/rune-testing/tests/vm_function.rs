@@ -68,3 +68,28 @@ fn test_function() {
     let value: Value = function.call(()).unwrap();
     assert!(matches!(value, Value::Integer(3)));
 }
+
+#[test]
+fn test_bad_argument_count_signature() {
+    assert_vm_error!(
+        r#"
+        fn connect(host, port) { host }
+        fn main() { let f = connect; f("localhost") }
+        "#,
+        BadFunctionArgumentCount { signature, actual, expected } => {
+            assert_eq!(signature.to_string(), "connect(host, port)");
+            assert_eq!((*actual, *expected), (1, 2));
+        }
+    );
+
+    assert_vm_error!(
+        r#"
+        struct Client;
+        impl Client { fn connect(self, host, port) { host } }
+        fn main() { let client = Client; client.connect("localhost") }
+        "#,
+        BadFunctionArgumentCount { signature, .. } => {
+            assert_eq!(signature.to_string(), "Client::connect(self, host, port)");
+        }
+    );
+}
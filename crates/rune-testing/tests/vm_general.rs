@@ -65,6 +65,43 @@ fn test_boolean_ops() {
     };
 }
 
+#[test]
+fn test_boolean_short_circuit() {
+    assert_eq! {
+        rune! {
+            runestick::VecTuple<(bool, bool, i64)> => r#"
+            fn main() {
+                let n = 0;
+                let a = false && { n = n + 1; true };
+                let b = true || { n = n + 10; false };
+                true && { n = n + 100; true };
+                [a, b, n]
+            }
+            "#
+        },
+        runestick::VecTuple((false, true, 100)),
+    };
+}
+
+#[test]
+fn test_chained_comparison() {
+    assert_eq! {
+        rune! {
+            runestick::VecTuple<(bool, bool, bool, i64)> => r#"
+            fn main() {
+                let n = 0;
+                let a = 1 < 2 <= 2 < 3;
+                let b = 1 < 3 < 2;
+                let c = 3 > 2 > { n = n + 1; 1 } > { n = n + 10; 0 };
+                let d = 1 > { n = n + 100; 2 } > { n = n + 1000; 0 };
+                [a, b, c && !d, n]
+            }
+            "#
+        },
+        runestick::VecTuple((true, false, true, 111)),
+    };
+}
+
 #[test]
 fn test_if() {
     assert_eq! {
@@ -102,6 +139,46 @@ fn test_if() {
     };
 }
 
+#[test]
+fn test_conditional_expressions() {
+    assert_eq! {
+        rune! {
+            runestick::VecTuple<(i64, i64, i64, String, i64)> => r#"
+            fn id(value) {
+                value
+            }
+
+            fn pick(n) {
+                return match n { 1 => 10, _ => 20 };
+            }
+
+            fn main() {
+                let c = true;
+                let o = #{a: if c { 1 } else { 2 }, b: match 2 { 2 => 3, _ => 4 }};
+                let s = `{if c { "yes" } else { "no" }}!`;
+                let v = loop { break match 1 { 1 => { 5 } _ => 6 }; };
+                [id(if c { o.a } else { 0 }), o.b + pick(1), pick(2), s, v]
+            }
+            "#
+        },
+        runestick::VecTuple((1, 13, 20, String::from("yes!"), 5)),
+    };
+
+    assert_eq! {
+        rune! {
+            runestick::VecTuple<(i64, i64, i64)> => r#"
+            fn main() {
+                let v = [1, match 2 { 2 => 3, _ => 4 }];
+                let t = (1, match 2 { 2 => 3, _ => 4 });
+                let b = [1, { let x = 3; x }];
+                [v[1], t.1, b[1]]
+            }
+            "#
+        },
+        runestick::VecTuple((3, 3, 3)),
+    };
+}
+
 #[test]
 fn test_block() {
     assert_eq! {
@@ -123,6 +200,43 @@ fn test_block() {
     };
 }
 
+#[test]
+fn test_implicit_return() {
+    assert_eq!(rune!(i64 => r#"fn main() { let a = 1; a + 1 }"#), 2);
+    assert_eq!(rune!(() => r#"fn main() { let a = 1; a + 1; }"#), ());
+    assert_eq!(rune!(i64 => r#"fn main() { let n = { 1; 2 }; n }"#), 2);
+    assert_eq!(rune!(() => r#"fn main() { let n = { 1; 2; }; n }"#), ());
+    assert_eq!(
+        rune!(i64 => r#"fn main() { let n = { let a = 1; a + 1 }; n * 2 }"#),
+        4
+    );
+}
+
+#[test]
+fn test_script() {
+    assert_eq!(rune!(i64 => r#"let a = 1; a + 1"#), 2);
+    assert_eq!(rune!(() => r#"let a = 1; a + 1;"#), ());
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn double(n) {
+                n * 2
+            }
+
+            let a = double(2);
+
+            if a > 2 {
+                a + 1
+            } else {
+                a
+            }
+            "#
+        },
+        5,
+    };
+}
+
 #[test]
 fn test_shadowing() {
     assert_eq! {
@@ -651,6 +765,169 @@ fn test_break_label() {
     };
 }
 
+#[test]
+fn test_break_label_value() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            use std::iter::range;
+
+            fn main() {
+                let it = range(0, 1000);
+
+                let value = 'label: loop {
+                    let a = 10;
+
+                    for n in it {
+                        let b = 20;
+
+                        while true {
+                            break 'label a + b + n;
+                        }
+                    }
+                };
+
+                value
+            }
+            "#
+        },
+        30,
+    };
+}
+
+#[test]
+fn test_continue() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            fn main() {
+                let a = 0;
+
+                for n in [1, 2, 3, 4] {
+                    let odd = n % 2 == 1;
+
+                    if odd {
+                        continue;
+                    }
+
+                    a = a + n;
+                }
+
+                let b = 0;
+                let n = 0;
+
+                while n < 4 {
+                    n = n + 1;
+                    let odd = n % 2 == 1;
+
+                    if odd {
+                        continue;
+                    }
+
+                    b = b + n;
+                }
+
+                let c = 0;
+                let n = 0;
+
+                loop {
+                    n = n + 1;
+
+                    if n > 4 {
+                        break;
+                    }
+
+                    if n % 2 == 0 {
+                        continue;
+                    }
+
+                    c = c + n;
+                }
+
+                (a, b, c)
+            }
+            "#
+        },
+        (6, 6, 4),
+    };
+}
+
+#[test]
+fn test_continue_label() {
+    assert_eq! {
+        rune! {
+            Vec<(i64, i64)> => r#"
+            fn main() {
+                let out = [];
+
+                'outer: for a in [1, 2, 3] {
+                    for b in [1, 2, 3] {
+                        let value = a * b;
+
+                        if b > a {
+                            continue 'outer;
+                        }
+
+                        out.push((a, b));
+                    }
+                }
+
+                out
+            }
+            "#
+        },
+        vec![(1, 1), (2, 1), (2, 2), (3, 1), (3, 2), (3, 3)],
+    };
+}
+
+#[test]
+fn test_loop_else() {
+    assert_eq! {
+        rune! {
+            runestick::VecTuple<(i64, i64, i64, bool)> => r#"
+            fn find(values, needle) {
+                for value in values {
+                    if value == needle {
+                        break true;
+                    }
+                } else {
+                    false
+                }
+            }
+
+            fn main() {
+                let n = 0;
+
+                let a = while n < 3 {
+                    n = n + 1;
+                } else {
+                    n + 100
+                };
+
+                let b = while true {
+                    break 42;
+                } else {
+                    0
+                };
+
+                let c = 'outer: loop {
+                    for v in [1, 2, 3] {
+                        v;
+                    } else {
+                        break 'outer 7;
+                    }
+
+                    break 0;
+                };
+
+                [a, b, c, find([1, 2, 3], 2) && !find([1, 2, 3], 4)]
+            }
+            "#
+        },
+        runestick::VecTuple((103, 42, 7, true)),
+    };
+}
+
 #[test]
 fn test_literal() {
     assert_eq! {
@@ -698,11 +975,11 @@ fn test_template_string() {
             fn main() {
                 let name = "John Doe";
 
-                `Hello {name}, I am {{
+                `Hello {name}, I am { {
                     let a = 20;
                     a += 2;
                     a
-                }} years old!`
+                } } years old!`
             }
             "#
         },
@@ -710,6 +987,48 @@ fn test_template_string() {
     };
 }
 
+#[test]
+fn test_template_string_nesting() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                let x = 1;
+                `a {`inner {x + 1}`} {"}"} {if '{' == '}' { 1 } else { 2 }} b`
+            }
+            "#
+        },
+        "a inner 2 } 2 b",
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                let x = 1;
+                `{{x}} is {x}, and }} closes`
+            }
+            "#
+        },
+        "{x} is 1, and } closes",
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                let name = "World";
+                `Hello
+{
+    name
+}!`
+            }
+            "#
+        },
+        "Hello\nWorld!",
+    };
+}
+
 #[test]
 fn test_variants_as_functions() {
     assert_eq! {
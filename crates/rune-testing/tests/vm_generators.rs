@@ -61,3 +61,172 @@ fn test_resume() {
         6,
     };
 }
+
+#[test]
+fn test_state() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn foo() { yield 1; yield 2; }
+
+            fn main() {
+                let gen = foo();
+                let states = [];
+                states.push(`{gen.state()}`);
+                gen.next();
+                states.push(`{gen.state()}`);
+                gen.next();
+                gen.next();
+                states.push(`{gen.state()}`);
+                states
+            }
+            "#
+        },
+        vec!["created", "suspended", "completed"],
+    };
+}
+
+#[test]
+fn test_dispose() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Guard(log);
+
+            impl Drop for Guard {
+                fn drop(self) {
+                    self.0.push("dropped");
+                }
+            }
+
+            fn foo(log) {
+                let guard = Guard(log);
+                yield 1;
+                log.push("unreachable");
+            }
+
+            fn main() {
+                let log = [];
+                let gen = foo(log);
+                gen.next();
+                log.push("suspended");
+                gen.dispose();
+
+                if gen.state().is_completed() {
+                    log.push("completed");
+                }
+
+                log
+            }
+            "#
+        },
+        vec!["suspended", "dropped", "completed"],
+    };
+}
+
+#[test]
+fn test_dispose_runs_deferred() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            fn foo(log) {
+                defer {
+                    log.push("deferred");
+                }
+
+                yield 1;
+                yield 2;
+            }
+
+            fn main() {
+                let log = [];
+                let gen = foo(log);
+                gen.next();
+                gen.dispose();
+                gen.dispose();
+                log
+            }
+            "#
+        },
+        vec!["deferred"],
+    };
+}
+
+#[test]
+fn test_dispose_closes_with() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Resource { name, log }
+
+            impl Resource {
+                fn new(name, log) {
+                    Resource { name, log }
+                }
+
+                fn close(self) {
+                    self.log.push(`close {self.name}`);
+                }
+            }
+
+            fn foo(log) {
+                with Resource::new("a", log) {
+                    let value = yield 1;
+                    log.push(`resumed {value}`);
+
+                    with Resource::new("b", log) {
+                        yield 2;
+                    }
+
+                    yield 3;
+                }
+            }
+
+            fn main() {
+                let log = [];
+
+                let gen = foo(log);
+                gen.dispose();
+                log.push("created");
+
+                let gen = foo(log);
+                gen.next();
+                gen.resume(42);
+                gen.dispose();
+                log.push("suspended");
+                log
+            }
+            "#
+        },
+        vec!["created", "resumed 42", "close b", "close a", "suspended"],
+    };
+}
+
+#[test]
+fn test_dispose_yield_in_cleanup() {
+    assert_vm_error! {
+        r#"
+        fn foo() {
+            defer {
+                yield 2;
+            }
+
+            yield 1;
+        }
+
+        fn main() {
+            let gen = foo();
+            gen.next();
+            gen.dispose();
+        }
+        "#,
+        BadReturn { error, .. } => {
+            assert!(matches!(
+                error.kind(),
+                runestick::VmErrorKind::Halted {
+                    halt: runestick::VmHaltInfo::Yielded
+                }
+            ));
+        }
+    };
+}
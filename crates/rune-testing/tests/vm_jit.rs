@@ -0,0 +1,108 @@
+#![cfg(feature = "jit")]
+
+use runestick::{Context, Hash, Item, Jit, Value, Vm, VmErrorKind};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn fib(n) { if n <= 1 { n } else { fib(n - 1) + fib(n - 2) } }
+fn sum(n) { let s = 0; let i = 0; while i < n { s += i; i += 1; } s }
+fn add(a, b) { a + b }
+fn div(a, b) { a / b }
+fn depth(n) { if n == 0 { 0 } else { depth(n - 1) + 1 } }
+fn double(n) { n * 2.0 }
+"#;
+
+fn setup() -> (Arc<Context>, Jit) {
+    let context = Context::with_default_modules().unwrap();
+    (Arc::new(context), Jit::new(2).unwrap())
+}
+
+/// Run the given `main` function, in a unit with the functions in [SOURCE].
+fn call<A>(
+    context: &Arc<Context>,
+    jit: &Jit,
+    main: &str,
+    args: A,
+) -> Result<Value, runestick::VmError>
+where
+    A: runestick::Args,
+{
+    let source = format!("{}\n{}", SOURCE, main);
+    let (unit, _) = rune::testing::compile(context, &source).unwrap();
+    let mut vm = Vm::new(context.clone(), Arc::new(unit));
+    vm.set_jit(jit.clone());
+    vm.call(Item::of(&["main"]), args)?.complete()
+}
+
+#[test]
+fn test_jit_compiles_hot_functions() {
+    let (context, jit) = setup();
+
+    let output = call(&context, &jit, "fn main() { fib(20) }", ()).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 6765);
+    assert!(jit.is_compiled(Hash::type_hash(&["fib"])));
+
+    let output = call(
+        &context,
+        &jit,
+        "fn main() { [sum(10), sum(100), sum(1000)] }",
+        (),
+    )
+    .unwrap();
+
+    let output = output.into_vec().unwrap().take().unwrap();
+    let output = output
+        .into_iter()
+        .map(|v| v.into_integer().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(output, vec![45, 4950, 499500]);
+    assert!(jit.is_compiled(Hash::type_hash(&["sum"])));
+    assert_eq!(jit.bailouts(), 0);
+}
+
+#[test]
+fn test_jit_bails_out() {
+    let (context, jit) = setup();
+
+    // Overflowing in compiled code raises the same error as interpreting it.
+    let main = "fn main(n) { add(1, 2) + add(3, 4) + add(n, 1) }";
+    let error = call(&context, &jit, main, (i64::MAX,)).unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        VmErrorKind::Overflow => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert!(jit.is_compiled(Hash::type_hash(&["add"])));
+    assert_eq!(jit.bailouts(), 1);
+
+    let main = "fn main() { div(4, 2) + div(6, 3) + div(1, 0) }";
+    let error = call(&context, &jit, main, ()).unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        VmErrorKind::DivideByZero => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    // Calls with arguments which aren't integers are interpreted.
+    let main = r#"fn main() { add(1, 2) + add(3, 4); add("a", "b") }"#;
+    let output = call(&context, &jit, main, ()).unwrap();
+    assert_eq!(output.into_string().unwrap().take().unwrap(), "ab");
+
+    // Deep recursion is performed by the interpreter.
+    let main = "fn main() { depth(10) + depth(10) + depth(5000) }";
+    let output = call(&context, &jit, main, ()).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 5020);
+    assert!(jit.is_compiled(Hash::type_hash(&["depth"])));
+}
+
+#[test]
+fn test_jit_rejects_unsupported_functions() {
+    let (context, jit) = setup();
+
+    let main = "fn main() { double(1.0) + double(2.0) + double(3.0) }";
+    let output = call(&context, &jit, main, ()).unwrap();
+    assert_eq!(output.into_float().unwrap(), 12.0);
+    assert!(!jit.is_compiled(Hash::type_hash(&["double"])));
+}
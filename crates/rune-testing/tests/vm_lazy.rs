@@ -0,0 +1,93 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Item, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_lazy_initialized_once() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            use std::lazy::Lazy;
+
+            let INITS = 0;
+            let VALUE = Lazy::new(|| { INITS += 1; 21 });
+
+            fn main() {
+                (VALUE.get() + VALUE.get(), INITS)
+            }
+            "#
+        },
+        (42, 1),
+    };
+}
+
+#[test]
+fn test_lazy_is_initialized() {
+    assert_eq! {
+        rune! {
+            (bool, bool) => r#"
+            use std::lazy::Lazy;
+
+            fn main() {
+                let value = Lazy::new(|| 42);
+                let before = value.is_initialized();
+                value.get();
+                (before, value.is_initialized())
+            }
+            "#
+        },
+        (false, true),
+    };
+}
+
+#[test]
+fn test_lazy_retried_after_error() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        use std::lazy::Lazy;
+
+        let ATTEMPTS = 0;
+
+        let VALUE = Lazy::new(|| {
+            ATTEMPTS += 1;
+
+            if ATTEMPTS == 1 {
+                panic("first attempt fails");
+            }
+
+            ATTEMPTS
+        });
+
+        fn main() {
+            VALUE.get()
+        }
+        "#,
+    )
+    .unwrap();
+
+    let vm = Vm::new(context, Arc::new(unit));
+    let call = || vm.clone().call(Item::of(&["main"]), ())?.complete();
+
+    assert!(call().is_err());
+    assert_eq!(i64::from_value(call().unwrap()).unwrap(), 2);
+    assert_eq!(i64::from_value(call().unwrap()).unwrap(), 2);
+}
+
+#[test]
+fn test_lazy_reentrant() {
+    assert_vm_error!(
+        r#"
+        use std::lazy::Lazy;
+
+        let VALUE = Lazy::new(|| VALUE.get() + 1);
+
+        fn main() {
+            VALUE.get()
+        }
+        "#,
+        LazyInitializing => {}
+    );
+}
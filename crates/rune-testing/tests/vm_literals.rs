@@ -38,3 +38,184 @@ fn test_octal() {
         -63,
     };
 }
+
+#[test]
+fn test_underscores() {
+    assert_eq! {
+        rune!(i64 => r#"fn main() { 1_000_000 }"#),
+        1_000_000,
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"fn main() { 0xff_ff }"#),
+        0xffff,
+    };
+
+    assert_eq! {
+        rune!(f64 => r#"fn main() { 1_000.5 }"#),
+        1_000.5,
+    };
+}
+
+#[test]
+fn test_float_exponents() {
+    assert_eq! {
+        rune!(f64 => r#"fn main() { 1e-9 }"#),
+        1e-9,
+    };
+
+    assert_eq! {
+        rune!(f64 => r#"fn main() { 2.5E+3 }"#),
+        2.5e3,
+    };
+
+    assert_eq! {
+        rune!(f64 => r#"fn main() { -1.5e2 }"#),
+        -1.5e2,
+    };
+
+    assert_eq! {
+        rune!(i64 => r#"fn main() { 0x1e }"#),
+        0x1e,
+    };
+}
+
+#[test]
+fn test_byte_str_escapes() {
+    assert_eq! {
+        rune!(runestick::Bytes => r#"fn main() { b"\xff\x00å" }"#),
+        runestick::Bytes::from_vec(vec![0xff, 0x00, 0xc3, 0xa5]),
+    };
+
+    assert_eq! {
+        rune!((runestick::Bytes, runestick::Bytes, runestick::Bytes) => r#"fn main() { (b"\n", b"", b"\"") }"#),
+        (
+            runestick::Bytes::from_vec(vec![b'\n']),
+            runestick::Bytes::from_vec(vec![]),
+            runestick::Bytes::from_vec(vec![b'"']),
+        ),
+    };
+
+    assert_eq! {
+        rune!((u8, u8, u8) => r#"fn main() { (b'\'', b'\n', b'\\') }"#),
+        (b'\'', b'\n', b'\\'),
+    };
+}
+
+#[test]
+fn test_unicode_escapes() {
+    assert_eq! {
+        rune! {
+            (char, String, String) => r#"
+            fn main() {
+                let a = 1;
+                ('\u{e5}', "\u{1F4AF}!", `{a}\u{2764}`)
+            }
+            "#
+        },
+        ('å', String::from("💯!"), String::from("1❤")),
+    };
+}
+
+#[test]
+fn test_object_computed_keys() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, usize) => r#"
+            fn main() {
+                let x = 1;
+                let key = "b";
+                let o = #{x, [key]: 2, [`c{x}`]: 3};
+                (o.x, o.b, o["c1"], o.len())
+            }
+            "#
+        },
+        (1, 2, 3, 3),
+    };
+
+    assert_eq! {
+        rune! {
+            (Vec<String>, i64, i64, i64) => r#"
+            fn main() {
+                let order = [];
+
+                let o = #{
+                    a: { order.push("a"); 1 },
+                    [{ order.push("key"); "b" }]: { order.push("b"); 2 },
+                    c: { order.push("c"); 3 },
+                };
+
+                #{
+                    [{ order.push("discarded key"); "d" }]: { order.push("discarded"); 4 },
+                    e: { order.push("e"); 5 },
+                };
+
+                (order, o.a, o.b, o.c)
+            }
+            "#
+        },
+        (
+            vec![
+                String::from("a"),
+                String::from("key"),
+                String::from("b"),
+                String::from("c"),
+                String::from("discarded key"),
+                String::from("discarded"),
+                String::from("e"),
+            ],
+            1,
+            2,
+            3,
+        ),
+    };
+}
+
+#[test]
+fn test_struct_update() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64) => r#"
+            struct Point { x, y, z }
+
+            fn main() {
+                let a = Point { x: 1, y: 2, z: 3 };
+                let b = Point { x: 10, ..a };
+                let c = Point { ..b };
+                (b.x, b.y, c.z, a.x)
+            }
+            "#
+        },
+        (10, 2, 3, 1),
+    };
+
+    assert_vm_error! {
+        r#"
+        struct Point { x, y }
+        struct Size { x, y }
+
+        fn main() {
+            let size = Size { x: 1, y: 2 };
+            Point { x: 3, ..size }
+        }
+        "#,
+        ExpectedUpdateBase { expected, actual } => {
+            assert_eq!(expected, "Point");
+            assert_eq!(actual, "Size");
+        }
+    };
+
+    assert_vm_error! {
+        r#"
+        struct Point { x, y }
+
+        fn main() {
+            Point { x: 3, ..#{y: 4} }
+        }
+        "#,
+        ExpectedUpdateBase { expected, actual } => {
+            assert_eq!(expected, "Point");
+            assert_eq!(actual, "Object");
+        }
+    };
+}
@@ -0,0 +1,225 @@
+use rune_testing::*;
+
+#[test]
+fn test_memoize() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            let CALLS = 0;
+
+            #[memoize]
+            fn fib(n) {
+                CALLS += 1;
+
+                if n < 2 {
+                    return n;
+                }
+
+                fib(n - 1) + fib(n - 2)
+            }
+
+            fn main() {
+                (fib(80), CALLS)
+            }
+            "#
+        },
+        (23416728348467685, 81),
+    };
+}
+
+#[test]
+fn test_memoize_structural_args() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            let CALLS = 0;
+
+            #[memoize]
+            fn total(values, options) {
+                CALLS += 1;
+                let total = 0;
+
+                for value in values {
+                    total += value * options.scale;
+                }
+
+                total
+            }
+
+            fn main() {
+                total([1, 2, 3], #{scale: 2});
+                total([1, 2, 3], #{scale: 2});
+                total([1, 2, 4], #{scale: 2});
+                (total([1, 2, 3], #{scale: 2}), CALLS)
+            }
+            "#
+        },
+        (12, 2),
+    };
+}
+
+#[test]
+fn test_memoize_mutated_args() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            let CALLS = 0;
+
+            #[memoize]
+            fn len(values) {
+                CALLS += 1;
+                values.len()
+            }
+
+            fn main() {
+                let values = [1];
+                len(values);
+                values.push(2);
+                (len(values), len([1]), CALLS)
+            }
+            "#
+        },
+        (2, 1, 2),
+    };
+}
+
+#[test]
+fn test_memoize_capacity() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            let CALLS = [];
+
+            #[memoize(capacity = 2)]
+            fn square(n) {
+                CALLS.push(n);
+                n * n
+            }
+
+            fn main() {
+                square(1);
+                square(2);
+                square(1);
+                square(3);
+                square(1);
+                square(3);
+                CALLS
+            }
+            "#
+        },
+        vec![1, 2, 3, 1],
+    };
+}
+
+#[test]
+fn test_memoize_ttl() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            let CALLS = 0;
+
+            #[memoize(ttl = "0ms")]
+            fn value() {
+                CALLS += 1;
+                CALLS
+            }
+
+            fn main() {
+                value();
+                value()
+            }
+            "#
+        },
+        2,
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            let CALLS = 0;
+
+            #[memoize(capacity = "10", ttl = "1h30m")]
+            fn value() {
+                CALLS += 1;
+                CALLS
+            }
+
+            fn main() {
+                value();
+                value()
+            }
+            "#
+        },
+        1,
+    };
+}
+
+#[test]
+fn test_memoize_unhashable() {
+    assert_vm_error!(
+        r#"
+        #[memoize]
+        fn call(f) {
+            f()
+        }
+
+        fn main() {
+            call(|| 1)
+        }
+        "#,
+        UnsupportedHash { .. } => {}
+    );
+}
+
+#[test]
+fn test_memoize_unsupported() {
+    assert_compile_error! {
+        r#"
+        #[memoize]
+        fn numbers() {
+            yield 1;
+        }
+        "#,
+        UnsupportedMemoize { span } => {
+            assert_eq!(span, Span::new(11, 18));
+        }
+    };
+
+    assert_compile_error! {
+        r#"
+        #[memoize(capacity = "0")]
+        fn value() {}
+        "#,
+        UnsupportedAttribute { .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"
+        #[memoize(capacity = 0)]
+        fn value() {}
+        "#,
+        UnsupportedAttribute { .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"
+        #[memoize(ttl = "-1s")]
+        fn value() {}
+        "#,
+        UnsupportedAttribute { .. } => {}
+    };
+
+    assert_parse_error! {
+        r#"
+        struct Counter;
+
+        impl Counter {
+            #[memoize]
+            fn value(self) {}
+        }
+        "#,
+        UnsupportedImplAttribute { span } => {
+            assert_eq!(span, Span::new(61, 71));
+        }
+    };
+}
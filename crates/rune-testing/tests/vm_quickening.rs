@@ -0,0 +1,112 @@
+use rune_testing::*;
+
+#[test]
+fn test_quickened_types_change() {
+    assert_eq! {
+        rune! {
+            (i64, f64, String, i64) => r#"
+            fn add(a, b) {
+                a + b
+            }
+
+            fn main() {
+                let n = 0;
+                let i = 0;
+
+                while i < 10 {
+                    n = add(n, i);
+                    i += 1;
+                }
+
+                (n, add(1.5, 2.0), add(`a`, "b"), add(1, 2))
+            }
+            "#
+        },
+        (45, 3.5, String::from("ab"), 3),
+    };
+
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            fn lt(a, b) {
+                a < b
+            }
+
+            fn main() {
+                (lt(1, 2), lt(2.5, 1.5), lt(1, 2))
+            }
+            "#
+        },
+        (true, false, true),
+    };
+}
+
+#[test]
+fn test_quickened_assign() {
+    assert_eq! {
+        rune! {
+            (i64, String, i64, f64) => r#"
+            fn concat(a, b) {
+                a = a + b;
+                a
+            }
+
+            fn scale(a, b) {
+                a *= b;
+                a
+            }
+
+            fn main() {
+                let n = 0;
+                let i = 0;
+
+                while i < 10 {
+                    n = concat(n, i);
+                    i += 1;
+                }
+
+                (n, concat(`a`, "b"), scale(scale(2, 3), 2), scale(1.5, 2.0))
+            }
+            "#
+        },
+        (45, String::from("ab"), 12, 3.0),
+    };
+}
+
+#[test]
+fn test_quickened_errors() {
+    assert_vm_error!(
+        r#"
+        fn add(a, b) {
+            a + b
+        }
+
+        fn main() {
+            let i = 0;
+
+            while i < 10 {
+                i = add(i, 1);
+            }
+
+            add(9223372036854775807, 1)
+        }
+        "#,
+        Overflow => {}
+    );
+
+    assert_vm_error!(
+        r#"
+        fn add(a, b) {
+            a + b
+        }
+
+        fn main() {
+            add(1, 2);
+            add(1, 2.0)
+        }
+        "#,
+        UnsupportedBinaryOperation { op, .. } => {
+            assert_eq!(*op, "+");
+        }
+    );
+}
@@ -0,0 +1,109 @@
+use rune_testing::*;
+
+#[test]
+fn test_vec_slicing() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3, 4];
+                (v[1..3], v[..2], v[2..], v[..])
+            }
+            "#
+        },
+        (vec![2, 3], vec![1, 2], vec![3, 4], vec![1, 2, 3, 4]),
+    };
+
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, usize) => r#"
+            fn main() {
+                let v = [1, 2, 3, 4];
+                let n = 10;
+                (v[-5..2], v[3..n], v[3..1].len())
+            }
+            "#
+        },
+        (vec![1, 2], vec![4], 0),
+    };
+}
+
+#[test]
+fn test_string_slicing() {
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            fn main() {
+                let s = "hello world";
+                (s[..5], s[6..], s[4..100])
+            }
+            "#
+        },
+        (String::from("hello"), String::from("world"), String::from("o world")),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { "åäö"[1..] }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "index `1` is not on a character boundary");
+        }
+    );
+}
+
+#[test]
+fn test_range() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            fn main() {
+                let r = 1..3;
+                (r.contains(1), r.contains(3), (..).contains(-100))
+            }
+            "#
+        },
+        (true, false, true),
+    };
+}
+
+#[test]
+fn test_range_iter() {
+    assert_eq! {
+        rune! {
+            (i64, Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let sum = 0;
+
+                for i in 0..100 {
+                    sum = sum + i;
+                }
+
+                let tail = [];
+
+                for i in (5..).iter() {
+                    if i >= 8 {
+                        break;
+                    }
+
+                    tail.push(i);
+                }
+
+                let rev = [];
+
+                for i in (1..4).iter().rev() {
+                    rev.push(i);
+                }
+
+                (sum, tail, rev)
+            }
+            "#
+        },
+        (4950, vec![5, 6, 7], vec![3, 2, 1]),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { for i in ..2 {} }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "cannot iterate over `..2`");
+        }
+    );
+}
@@ -0,0 +1,177 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Item, Vm};
+use std::sync::Arc;
+
+#[test]
+fn test_static_counter() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            let COUNTER = 0;
+
+            fn next() {
+                COUNTER += 1;
+                COUNTER
+            }
+
+            fn main() {
+                next();
+                next();
+                next()
+            }
+            "#
+        },
+        3,
+    };
+}
+
+#[test]
+fn test_static_memoize() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            let CACHE = #{};
+
+            fn fib(n) {
+                if n < 2 {
+                    return n;
+                }
+
+                let key = `{n}`;
+
+                if let Some(value) = CACHE.get(key) {
+                    return value;
+                }
+
+                let value = fib(n - 1) + fib(n - 2);
+                CACHE[key] = value;
+                value
+            }
+
+            fn main() {
+                fib(80)
+            }
+            "#
+        },
+        23416728348467685,
+    };
+}
+
+#[test]
+fn test_static_in_module() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            mod counter {
+                let VALUE = 40;
+
+                fn add(n) {
+                    VALUE += n;
+                }
+            }
+
+            fn main() {
+                counter::add(1);
+                counter::add(1);
+                counter::VALUE
+            }
+            "#
+        },
+        42,
+    };
+}
+
+#[test]
+fn test_static_initialized_once() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            let INITS = 0;
+            let VALUE = { INITS += 1; 10 };
+
+            fn main() {
+                VALUE + VALUE + INITS
+            }
+            "#
+        },
+        21,
+    };
+}
+
+#[test]
+fn test_static_script_order() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            let LOG = [];
+            LOG.push("first");
+            let VALUE = { LOG.push("init"); 1 };
+            LOG.push("second");
+            LOG
+            "#
+        },
+        vec!["first", "init", "second"],
+    };
+}
+
+#[test]
+fn test_static_per_vm() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        let COUNTER = 0;
+
+        fn main() {
+            COUNTER += 1;
+            COUNTER
+        }
+        "#,
+    )
+    .unwrap();
+
+    let unit = Arc::new(unit);
+
+    let call = |vm: &Vm| {
+        let output = vm
+            .clone()
+            .call(Item::of(&["main"]), ())
+            .unwrap()
+            .complete()
+            .unwrap();
+        i64::from_value(output).unwrap()
+    };
+
+    let vm = Vm::new(context.clone(), unit.clone());
+    assert_eq!(call(&vm), 1);
+    assert_eq!(call(&vm), 2);
+
+    let vm = Vm::new(context, unit);
+    assert_eq!(call(&vm), 1);
+}
+
+#[test]
+fn test_static_cycle() {
+    assert_compile_error! {
+        r#"
+        let A = B + 1;
+        let B = A + 1;
+        fn main() { A }
+        "#,
+        StaticCycle { item, .. } => {
+            assert_eq!(item, Item::of(&["A"]));
+        }
+    };
+
+    assert_vm_error!(
+        r#"
+        let A = value();
+        fn value() { A + 1 }
+        fn main() { A }
+        "#,
+        StaticInitializing { item } => {
+            assert_eq!(item, &Item::of(&["A"]));
+        }
+    );
+}
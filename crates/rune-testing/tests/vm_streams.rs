@@ -1,4 +1,5 @@
 use rune_testing::*;
+use runestick::Span;
 
 #[test]
 fn test_simple_stream() {
@@ -71,3 +72,69 @@ fn test_resume() {
         6,
     };
 }
+
+#[test]
+fn test_for_await() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            async fn foo() {
+                yield 1;
+                yield 2;
+                yield 3;
+            }
+
+            async fn main() {
+                let result = 0;
+
+                for await value in foo() {
+                    result += value;
+                }
+
+                result
+            }
+            "#
+        },
+        6,
+    };
+
+    assert_compile_error! {
+        r#"fn main() { for await value in foo() {} }"#,
+        AwaitOutsideFunction { span } => {
+            assert_eq!(span, Span::new(16, 21));
+        }
+    };
+}
+
+#[test]
+fn test_stream_combinators() {
+    assert_eq! {
+        rune! {
+            Vec<i64> => r#"
+            async fn numbers() {
+                let n = 0;
+
+                while n < 5 {
+                    n += 1;
+                    yield n;
+                }
+            }
+
+            async fn main() {
+                let stream = numbers().map(async |n| n * 2);
+                let stream = stream.filter(|n| n > 2).take(2);
+
+                let result = [];
+
+                for await value in stream {
+                    result.push(value);
+                }
+
+                result.push(numbers().take(3).collect().await.len());
+                result
+            }
+            "#
+        },
+        vec![4, 6, 3],
+    };
+}
@@ -0,0 +1,64 @@
+use rune_testing::*;
+
+#[test]
+fn test_string_builder() {
+    assert_eq! {
+        rune! {
+            (String, usize) => r#"
+            fn main() {
+                let b = std::string::Builder::new();
+
+                for n in [0, 1, 2] {
+                    b.push_str(`{n}`);
+                    b.push(',');
+                }
+
+                b += "end";
+                let len = b.len();
+                (b.finish(), len)
+            }
+            "#
+        },
+        (String::from("0,1,2,end"), 9),
+    };
+}
+
+#[test]
+fn test_concat_in_place() {
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            fn main() {
+                let s = "a";
+                let t = s;
+
+                for n in [0, 1, 2] {
+                    s = s + "," + `{n}`;
+                }
+
+                let u = s;
+                s = s + s;
+                (s, t, u)
+            }
+            "#
+        },
+        (
+            String::from("a,0,1,2a,0,1,2"),
+            String::from("a"),
+            String::from("a,0,1,2"),
+        ),
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 1;
+                n = n + 2 + 3;
+                n
+            }
+            "#
+        },
+        6,
+    };
+}
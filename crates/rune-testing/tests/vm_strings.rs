@@ -0,0 +1,152 @@
+use rune_testing::*;
+
+#[test]
+fn test_string_chars() {
+    assert_eq! {
+        rune! {
+            (Vec<char>, Vec<(usize, char)>) => r#"
+            fn main() {
+                let chars = [];
+                let indices = [];
+
+                for c in "åa".chars() {
+                    chars.push(c);
+                }
+
+                for c in "åa".char_indices() {
+                    indices.push(c);
+                }
+
+                (chars, indices)
+            }
+            "#
+        },
+        (vec!['å', 'a'], vec![(0, 'å'), (2, 'a')]),
+    };
+}
+
+#[test]
+fn test_string_search() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, Option<usize>, Option<usize>) => r#"
+            fn main() {
+                let s = "hello world";
+                (
+                    s.starts_with("hello"),
+                    s.ends_with("hello"),
+                    s.contains("o w"),
+                    s.find("o"),
+                    s.rfind("o"),
+                )
+            }
+            "#
+        },
+        (true, false, true, Some(4), Some(7)),
+    };
+}
+
+#[test]
+fn test_string_transform() {
+    assert_eq! {
+        rune! {
+            (Vec<String>, String, String, String, String) => r#"
+            fn main() {
+                let s = "  a,b,c  ";
+                (
+                    s.trim().split(","),
+                    s.replace(",", ";"),
+                    s.trim(),
+                    s.trim_start(),
+                    s.trim_end(),
+                )
+            }
+            "#
+        },
+        (
+            vec![String::from("a"), String::from("b"), String::from("c")],
+            String::from("  a;b;c  "),
+            String::from("a,b,c"),
+            String::from("a,b,c  "),
+            String::from("  a,b,c"),
+        ),
+    };
+}
+
+#[test]
+fn test_string_char_boundaries() {
+    assert_eq! {
+        rune! {
+            (bool, String, String) => r#"
+            fn main() {
+                let s = "åäö";
+
+                let error = match s.slice(1..) {
+                    Err(error) => `{error}`,
+                    _ => "no error",
+                };
+
+                (s.is_char_boundary(1), s.slice(2..)?, error)
+            }
+            "#
+        },
+        (
+            false,
+            String::from("äö"),
+            String::from("index `1` is not on a character boundary"),
+        ),
+    };
+}
+
+#[test]
+fn test_raw_strings() {
+    assert_eq! {
+        rune! {
+            (String, String, bool) => r##"
+            fn main() {
+                let path = r"C:\Users\rune";
+                let quoted = r#"say "hello""#;
+                (path, quoted, r"\n" == "\\n")
+            }
+            "##
+        },
+        (
+            String::from("C:\\Users\\rune"),
+            String::from("say \"hello\""),
+            true,
+        ),
+    };
+}
+
+#[test]
+fn test_multiline_strings() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                """
+                <ul>
+                  <li>"first"</li>\t
+
+                  <li>second</li>
+                </ul>
+                """
+            }
+            "#
+        },
+        "<ul>\n  <li>\"first\"</li>\t\n\n  <li>second</li>\n</ul>\n",
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            fn main() {
+                """
+                    indented
+                  less"""
+            }
+            "#
+        },
+        "  indented\nless",
+    };
+}
@@ -0,0 +1,94 @@
+use rune::Options;
+use rune_testing::load_source;
+use runestick::{AbiError, Context, Item, Module, Runtime, Unit, Vm, VmErrorKind, RUNTIME_VERSION};
+use std::sync::Arc;
+
+fn compile(context: &Context, options: &Options) -> Arc<Unit> {
+    let (unit, _) = load_source(context, options, "fn main() { 42 }").unwrap();
+    Arc::new(unit)
+}
+
+fn context_with_module() -> Context {
+    let mut module = Module::new(&["host"]);
+    module.function(&["answer"], || 42i64).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    context
+}
+
+#[test]
+fn test_unit_abi() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let mut options = Options::default();
+    options.parse_option("macros").unwrap();
+    let unit = compile(&context, &options);
+
+    let abi = unit.abi().expect("compiled units have abi metadata");
+    assert_eq!(abi.compiler_version(), RUNTIME_VERSION);
+    assert_eq!(abi.runtime_version(), RUNTIME_VERSION);
+    assert_eq!(abi.fingerprint(), context.fingerprint());
+    assert_eq!(abi.features(), &["concat-in-place", "macros"]);
+
+    assert!(Vm::try_new(context, unit).is_ok());
+}
+
+#[test]
+fn test_fingerprint_mismatch() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let unit = compile(&context, &Options::default());
+
+    let other = Arc::new(context_with_module());
+    assert_ne!(context.fingerprint(), other.fingerprint());
+    assert_eq!(
+        Context::with_default_modules().unwrap().fingerprint(),
+        context.fingerprint()
+    );
+
+    let error = Vm::try_new(other.clone(), unit.clone()).unwrap_err();
+
+    match error.kind() {
+        VmErrorKind::AbiError {
+            error: AbiError::Fingerprint { unit, context },
+        } => {
+            assert_ne!(unit, context);
+            assert_eq!(*context, other.fingerprint());
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    assert!(Runtime::install(other, unit.clone()).is_err());
+
+    let mut runtime = Runtime::install(context, unit.clone()).unwrap();
+    let other_unit = compile(&context_with_module(), &Options::default());
+    assert!(runtime.swap(other_unit).is_err());
+    assert!(Arc::ptr_eq(runtime.unit(), &unit));
+}
+
+#[test]
+fn test_context_diff() {
+    let mut old = Module::new(&["host"]);
+    old.function(&["answer"], || 42i64).unwrap();
+    old.function(&["removed"], || ()).unwrap();
+    old.function(&["same"], || ()).unwrap();
+
+    let mut new = Module::new(&["host"]);
+    new.function(&["answer"], |n: i64| n).unwrap();
+    new.function(&["added"], || ()).unwrap();
+    new.function(&["same"], || ()).unwrap();
+
+    let mut old_context = Context::with_default_modules().unwrap();
+    old_context.install(&old).unwrap();
+
+    let mut new_context = Context::with_default_modules().unwrap();
+    new_context.install(&new).unwrap();
+
+    let diff = old_context.diff(&new_context);
+    assert_eq!(diff.added, vec![Item::of(&["host", "added"])]);
+    assert_eq!(diff.removed, vec![Item::of(&["host", "removed"])]);
+    assert_eq!(diff.changed, vec![Item::of(&["host", "answer"])]);
+
+    assert!(old_context.diff(&old_context).is_empty());
+    assert!(!diff.is_empty());
+}
@@ -0,0 +1,74 @@
+use rune_testing::*;
+
+#[test]
+fn test_associated_functions() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            struct Point { x, y }
+
+            impl Point {
+                fn new(x, y) {
+                    Point { x, y }
+                }
+
+                fn sum(self) {
+                    self.x + self.y
+                }
+            }
+
+            fn main() {
+                let sum = Point::sum;
+                (Point::new(1, 2).sum(), sum(Point::new(3, 4)))
+            }
+            "#
+        },
+        (3, 7),
+    };
+}
+
+#[test]
+fn test_associated_consts() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64) => r#"
+            struct Point { x, y }
+
+            impl Point {
+                const ORIGIN = Point::new(0, 0);
+                const UNIT = 1;
+
+                fn new(x, y) {
+                    Point { x, y }
+                }
+            }
+
+            fn main() {
+                let origin = Point::ORIGIN;
+                origin.x = 10;
+                (origin.x, Point::ORIGIN.x, Point::UNIT + 1)
+            }
+            "#
+        },
+        (10, 0, 2),
+    };
+}
+
+#[test]
+fn test_associated_conflict() {
+    assert_compile_error! {
+        r#"
+        struct Point { x, y }
+
+        impl Point {
+            const new = 1;
+            fn new(x, y) { Point { x, y } }
+        }
+
+        fn main() {
+            Point::new(1, 2)
+        }
+        "#,
+        ItemConflict { .. } => {}
+    };
+}
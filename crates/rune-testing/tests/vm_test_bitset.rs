@@ -0,0 +1,85 @@
+use rune_testing::*;
+
+#[test]
+fn test_bitset() {
+    assert_eq! {
+        rune! {
+            (bool, bool, i64, i64) => r#"
+            use std::bitset::BitSet;
+
+            fn main() {
+                let set = BitSet::new();
+                set.insert(0);
+                set.insert(2);
+                set.insert(100);
+                set.remove(100);
+                (set.contains(2), set.contains(1), set.len(), set.to_int())
+            }
+            "#
+        },
+        (true, false, 2, 0b101),
+    };
+}
+
+#[test]
+fn test_bitset_operators() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, bool) => r#"
+            use std::bitset::BitSet;
+
+            fn main() {
+                let read = BitSet::from_int(0b001);
+                let write = BitSet::from_int(0b010);
+                let exec = BitSet::from_int(0b100);
+
+                let mask = read | write;
+                let both = mask & BitSet::from_int(0b011);
+                let toggled = mask ^ BitSet::from_int(0b110);
+
+                let all = BitSet::new();
+                all |= mask;
+                all |= exec;
+
+                (mask.to_int(), both.to_int(), toggled.to_int(), all.to_int(), all.contains_all(mask))
+            }
+            "#
+        },
+        (0b011, 0b011, 0b101, 0b111, true),
+    };
+}
+
+#[test]
+fn test_bitset_display() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            use std::bitset::BitSet;
+
+            fn main() {
+                let set = BitSet::from_int(0b1010);
+                `{set}`
+            }
+            "#
+        },
+        "{1, 3}",
+    };
+}
+
+#[test]
+fn test_bitset_to_int_overflow() {
+    assert_vm_error!(
+        r#"
+        use std::bitset::BitSet;
+
+        fn main() {
+            let set = BitSet::new();
+            set.insert(64);
+            set.to_int()
+        }
+        "#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "bit set doesn't fit in an integer");
+        }
+    );
+}
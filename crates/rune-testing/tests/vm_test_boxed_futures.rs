@@ -0,0 +1,87 @@
+use rune_testing::*;
+use runestick::{Context, FromValue as _, Item, Module, Panic, Vm};
+use std::future::Future;
+
+#[derive(Debug, Default)]
+struct Counter {
+    value: i64,
+}
+
+impl Counter {
+    fn add(&mut self, n: i64) -> Box<dyn Future<Output = i64> + '_> {
+        Box::new(async move {
+            self.value += n;
+            self.value
+        })
+    }
+
+    async fn double(&mut self) -> i64 {
+        self.value *= 2;
+        self.value
+    }
+
+    fn value(&self) -> i64 {
+        self.value
+    }
+}
+
+runestick::impl_external!(Counter);
+
+fn checked_div(a: i64, b: i64) -> Box<dyn Future<Output = Result<i64, Panic>> + Send> {
+    Box::new(async move {
+        match a.checked_div(b) {
+            Some(value) => Ok(value),
+            None => Err(Panic::custom("division by zero")),
+        }
+    })
+}
+
+fn vm(source: &str) -> Vm {
+    let mut module = Module::new(&["host"]);
+    module.ty(&["Counter"]).build::<Counter>().unwrap();
+    module
+        .function(&["Counter", "new"], Counter::default)
+        .unwrap();
+    module.async_inst_fn("add", Counter::add).unwrap();
+    module.async_inst_fn("double", Counter::double).unwrap();
+    module.inst_fn("value", Counter::value).unwrap();
+    module
+        .async_function(&["checked_div"], checked_div)
+        .unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    load_vm(context, source).unwrap()
+}
+
+#[test]
+fn test_boxed_futures() {
+    let vm = vm(r#"
+    use host::Counter;
+
+    async fn main() {
+        let counter = Counter::new();
+        counter.add(2).await;
+        let added = counter.add(3).await;
+        let doubled = counter.double().await;
+        (added, doubled, counter.value(), host::checked_div(10, 2).await)
+    }
+    "#);
+
+    let output = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap();
+    let output = <(i64, i64, i64, i64)>::from_value(output).unwrap();
+    assert_eq!(output, (5, 10, 10, 5));
+}
+
+#[test]
+fn test_boxed_future_error() {
+    let vm = vm(r#"
+    async fn main() {
+        host::checked_div(1, 0).await
+    }
+    "#);
+
+    let error = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err();
+    assert_eq!(error.to_string(), "panicked `division by zero`");
+}
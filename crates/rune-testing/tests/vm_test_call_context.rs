@@ -0,0 +1,88 @@
+use rune_testing::load_vm;
+use runestick::{CallContext, Context, FromValue as _, Hash, Item, Module, Value, VmError};
+
+/// Calls the script function with the given name twice, feeding the output of
+/// the first call into the second.
+fn call_twice(cx: &mut CallContext<'_>, _: usize) -> Result<(), VmError> {
+    let name = String::from_value(cx.stack_mut().pop()?)?;
+    let hash = Hash::type_hash(&[name.as_str()]);
+
+    let a = cx.call_fn::<_, _, i64>(hash, (1i64,))?;
+    let b = cx.call_fn::<_, _, i64>(hash, (a,))?;
+
+    cx.stack_mut().push(Value::Integer(b));
+    Ok(())
+}
+
+/// Calls a native function through the call context.
+fn call_native(cx: &mut CallContext<'_>, _: usize) -> Result<(), VmError> {
+    let value = cx.stack_mut().pop()?;
+    let value = cx.call_fn::<_, _, Value>(&["test", "double"], (value,))?;
+    cx.stack_mut().push(value);
+    Ok(())
+}
+
+fn run<T>(source: &str) -> Result<T, VmError>
+where
+    T: runestick::FromValue,
+{
+    let mut module = Module::new(&["test"]);
+    module
+        .raw_fn_with_context(&["call_twice"], call_twice)
+        .unwrap();
+    module
+        .raw_fn_with_context(&["call_native"], call_native)
+        .unwrap();
+    module.function(&["double"], |n: i64| n * 2).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let vm = load_vm(context, source).unwrap();
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    T::from_value(output)
+}
+
+#[test]
+fn test_call_script_function() {
+    let output = run::<i64>(
+        r#"
+        fn add_ten(n) {
+            n + 10
+        }
+
+        fn main() {
+            test::call_twice("add_ten")
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, 21);
+}
+
+#[test]
+fn test_call_native_function() {
+    let output = run::<i64>(
+        r#"
+        fn main() {
+            let f = test::call_native;
+            f(21)
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, 42);
+}
+
+#[test]
+fn test_call_missing_function() {
+    let error = run::<i64>(r#"fn main() { test::call_twice("missing") }"#).unwrap_err();
+    let (error, _) = error.into_unwound();
+
+    assert!(matches!(
+        error.kind(),
+        runestick::VmErrorKind::MissingFunction { .. }
+    ));
+}
@@ -0,0 +1,191 @@
+use rune_testing::*;
+use runestick::{CallAction, CallHook, CallInfo, Context, Item, Value, Vm, VmError, VmErrorKind};
+use std::sync::{Arc, Mutex};
+
+/// Run the `main` function of the given source with the given hook installed.
+fn run_hooked<H, T>(hook: H, source: &str) -> Result<T, VmError>
+where
+    H: CallHook + 'static,
+    T: runestick::FromValue,
+{
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(&context, source).unwrap();
+
+    let mut vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.add_call_hook(hook);
+    T::from_value(block_on(
+        vm.call(Item::of(&["main"]), ())?.async_complete(),
+    )?)
+}
+
+fn name(call: &CallInfo<'_>) -> String {
+    match call.item() {
+        Some(item) => item.to_string(),
+        None => String::from("?"),
+    }
+}
+
+#[derive(Clone, Default)]
+struct Audit {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl CallHook for Audit {
+    fn before(&self, call: &CallInfo<'_>) -> Result<CallAction, VmError> {
+        let kind = if call.is_native() { "native" } else { "script" };
+        let entry = format!("before {} {} ({})", kind, name(call), call.args());
+        self.log.lock().unwrap().push(entry);
+        Ok(CallAction::Continue)
+    }
+
+    fn after(&self, call: &CallInfo<'_>, value: &mut Value) -> Result<(), VmError> {
+        let entry = format!("after {} = {:?}", name(call), value);
+        self.log.lock().unwrap().push(entry);
+        Ok(())
+    }
+}
+
+#[test]
+fn test_call_hook_audit() {
+    let audit = Audit::default();
+
+    let output = run_hooked::<_, Vec<i64>>(
+        audit.clone(),
+        r#"
+        fn add(a, b) { a + b }
+
+        fn main() {
+            let out = [];
+            out.push(add(1, 2));
+            out
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, vec![3]);
+
+    let log = audit.log.lock().unwrap();
+
+    assert_eq!(
+        *log,
+        vec![
+            "before script add (2)",
+            "after add = 3",
+            "before native std::vec::Vec::push (2)",
+            "after std::vec::Vec::push = ()",
+        ]
+    );
+}
+
+struct Mock;
+
+impl CallHook for Mock {
+    fn before(&self, call: &CallInfo<'_>) -> Result<CallAction, VmError> {
+        if name(call) == "std::string::String::len" {
+            return Ok(CallAction::Replace(Value::Integer(42)));
+        }
+
+        Ok(CallAction::Continue)
+    }
+}
+
+#[test]
+fn test_call_hook_replace() {
+    let output = run_hooked::<_, (i64, i64)>(
+        Mock,
+        r#"
+        fn main() {
+            let s = "hello";
+            (s.len(), [1, 2].len())
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, (42, 2));
+}
+
+struct Deny;
+
+impl CallHook for Deny {
+    fn before(&self, call: &CallInfo<'_>) -> Result<CallAction, VmError> {
+        if name(call) == "secret" {
+            return Err(VmError::panic("calling `secret` is not allowed"));
+        }
+
+        Ok(CallAction::Continue)
+    }
+}
+
+#[test]
+fn test_call_hook_veto() {
+    let error = run_hooked::<_, i64>(
+        Deny,
+        r#"
+        fn secret() { 42 }
+        fn main() { secret() }
+        "#,
+    )
+    .unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::Panic { reason } => {
+            assert_eq!(reason.to_string(), "calling `secret` is not allowed")
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+struct Double;
+
+impl CallHook for Double {
+    fn after(&self, call: &CallInfo<'_>, value: &mut Value) -> Result<(), VmError> {
+        if let (false, Value::Integer(n)) = (call.is_native(), &*value) {
+            *value = Value::Integer(n * 2);
+        }
+
+        Ok(())
+    }
+}
+
+#[test]
+fn test_call_hook_wrap() {
+    let output = run_hooked::<_, i64>(
+        Double,
+        r#"
+        fn one() { 1 }
+        fn two() { one() + one() }
+        fn main() { two() }
+        "#,
+    )
+    .unwrap();
+
+    // NB: `one` returns 2, so `two` returns (2 + 2) * 2.
+    assert_eq!(output, 8);
+}
+
+#[test]
+fn test_call_hook_async() {
+    let audit = Audit::default();
+
+    let output = run_hooked::<_, i64>(
+        audit.clone(),
+        r#"
+        fn add(a, b) { a + b }
+        async fn work() { add(1, 2) }
+        async fn main() { work().await }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, 3);
+
+    let log = audit.log.lock().unwrap();
+
+    // NB: the call to `work` returns a future, and `add` is called in the
+    // virtual machine spawned to run it.
+    assert_eq!(log[0], "before script work (0)");
+    assert!(log[1].starts_with("after work = Future"));
+    assert_eq!(&log[2..], ["before script add (2)", "after add = 3"]);
+}
@@ -0,0 +1,90 @@
+use rune_testing::*;
+use runestick::{CancellationHandle, Context, Item, Vm, VmError, VmErrorKind, VmHaltInfo};
+use std::thread;
+use std::time::Duration;
+
+fn vm(source: &str) -> Vm {
+    let context = Context::with_default_modules().unwrap();
+    load_vm(context, source).unwrap()
+}
+
+fn cancel_later(handle: CancellationHandle) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        thread::sleep(Duration::from_millis(20));
+        handle.cancel();
+    })
+}
+
+fn is_cancelled(error: &VmError) -> bool {
+    matches!(
+        error.kind().into_unwound_ref().0,
+        VmErrorKind::Halted {
+            halt: VmHaltInfo::Cancelled
+        }
+    )
+}
+
+#[test]
+fn test_cancel_infinite_loop() {
+    let mut vm = vm(r#"fn main() { loop {} }"#);
+    let thread = cancel_later(vm.cancellation_handle());
+
+    let error = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap_err();
+
+    assert!(is_cancelled(&error), "{:?}", error);
+    thread.join().unwrap();
+}
+
+#[test]
+fn test_cancelled_execution_can_be_resumed() {
+    let mut vm = vm(r#"
+    fn add(a, b) { a + b }
+    fn main() { let n = 0; while n < 100 { n = add(n, 1); } n }
+    "#);
+
+    let handle = vm.cancellation_handle();
+    handle.cancel();
+
+    let mut execution = vm.call(Item::of(&["main"]), ()).unwrap();
+    let mut cancellations = 0;
+
+    // Execution stops before every call and backward jump, but picks up where
+    // it stopped once the handle has been reset.
+    let output = loop {
+        match execution.complete() {
+            Ok(output) => break output,
+            Err(error) => {
+                assert!(is_cancelled(&error), "{:?}", error);
+                cancellations += 1;
+
+                // NB: stay cancelled for a while to make sure that resuming
+                // doesn't skip any instructions.
+                if cancellations == 10 {
+                    handle.reset();
+                }
+            }
+        }
+    };
+
+    assert_eq!(output.into_integer().unwrap(), 100);
+    assert_eq!(cancellations, 10);
+}
+
+#[test]
+fn test_cancel_async_function() {
+    let mut vm = vm(r#"
+    async fn spin() { loop {} }
+    async fn main() { spin().await }
+    "#);
+
+    let thread = cancel_later(vm.cancellation_handle());
+
+    let error = block_on(vm.call(Item::of(&["main"]), ()).unwrap().async_complete()).unwrap_err();
+
+    assert!(is_cancelled(&error), "{:?}", error);
+    thread.join().unwrap();
+}
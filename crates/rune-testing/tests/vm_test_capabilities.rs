@@ -0,0 +1,93 @@
+use rune::{LinkerError, LoadErrorKind};
+use rune_testing::load_source;
+use runestick::{Context, FromValue as _, Hash, Item, Module, Span, Unit, Vm, VmErrorKind};
+use std::sync::Arc;
+
+fn context(granted: Option<&[&str]>) -> Context {
+    let mut module = Module::new(&["fs"]);
+    module.function(&["read"], |path: String| path).unwrap();
+    module.require_capability(&["read"], "fs-read").unwrap();
+    module.function(&["remove"], |path: String| path).unwrap();
+    module.require_capability(&["remove"], "fs-write").unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    if let Some(granted) = granted {
+        context.grant_only(granted.iter().copied());
+    }
+
+    context
+}
+
+fn load(context: &Context, source: &str) -> Result<Unit, rune::LoadError> {
+    let (unit, _) = load_source(context, &Default::default(), source)?;
+    Ok(unit)
+}
+
+const SOURCE: &str = r#"
+fn main() {
+    let a = fs::read("a");
+    fs::remove(a);
+    fs::remove("b")
+}
+"#;
+
+#[test]
+fn test_granted_capabilities() {
+    let context = Arc::new(context(Some(&["fs-read", "fs-write"])));
+    let unit = Arc::new(load(&context, SOURCE).unwrap());
+
+    let vm = Vm::new(context, unit);
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(String::from_value(output).unwrap(), "b");
+}
+
+#[test]
+fn test_missing_capability_link_time() {
+    let context = context(Some(&["fs-read"]));
+
+    let errors = match load(&context, SOURCE).unwrap_err().into_kind() {
+        LoadErrorKind::LinkError { errors } => errors,
+        kind => panic!("unexpected error: {:?}", kind),
+    };
+
+    let errors = errors.errors().collect::<Vec<_>>();
+
+    match &errors[..] {
+        [LinkerError::MissingCapability {
+            hash,
+            capability,
+            spans,
+        }] => {
+            assert_eq!(*hash, Hash::type_hash(&["fs", "remove"]));
+            assert_eq!(capability, "fs-write");
+
+            let mut spans = spans.iter().map(|(span, _)| *span).collect::<Vec<_>>();
+            spans.sort();
+            assert_eq!(spans, vec![Span::new(44, 57), Span::new(63, 78)]);
+        }
+        errors => panic!("unexpected errors: {:?}", errors),
+    }
+}
+
+#[test]
+fn test_missing_capability_call_time() {
+    let unit = Arc::new(load(&context(None), SOURCE).unwrap());
+
+    let vm = Vm::new(Arc::new(context(Some(&["fs-read"]))), unit);
+    let error = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::MissingCapability { capability } => assert_eq!(capability, "fs-write"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
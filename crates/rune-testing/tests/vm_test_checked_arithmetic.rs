@@ -0,0 +1,140 @@
+use rune::Options;
+use rune_testing::*;
+use runestick::{Context, FromValue, Item, Vm, VmError};
+use std::sync::Arc;
+
+fn run_checked<T>(source: &str) -> Result<T, VmError>
+where
+    T: FromValue,
+{
+    let context = Context::with_default_modules().unwrap();
+
+    let mut options = Options::default();
+    options.parse_option("checked-arithmetic").unwrap();
+
+    let (unit, _) = load_source(&context, &options, source).unwrap();
+
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    T::from_value(output)
+}
+
+#[test]
+fn test_checked_arithmetic() {
+    let result = run_checked::<String>(
+        r#"
+        fn average(total, count) {
+            Ok(total / count)
+        }
+
+        fn main() {
+            match average(10, 0) {
+                Ok(_) => "ok",
+                Err(err) => `error: {err}`,
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(result, "error: division by zero");
+
+    let result = run_checked::<Vec<String>>(
+        r#"
+        fn process(a, b) {
+            let sum = a + b;
+            Ok(sum % b + a / b)
+        }
+
+        fn main() {
+            let out = [];
+
+            for row in [[10, 2], [1, 0], [9223372036854775807, 1]] {
+                match process(row[0], row[1]) {
+                    Ok(n) => out.push(`ok: {n}`),
+                    Err(err) => out.push(`error: {err}`),
+                }
+            }
+
+            out
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        result,
+        vec![
+            "ok: 5",
+            "error: division by zero",
+            "error: numerical overflow"
+        ]
+    );
+}
+
+#[test]
+fn test_checked_assign() {
+    let result = run_checked::<(i64, String)>(
+        r#"
+        fn scale(n, by) {
+            n *= by;
+            n /= by - 1;
+            Ok(n)
+        }
+
+        fn main() {
+            let ok = scale(3, 3)?;
+
+            let err = match scale(3, 1) {
+                Ok(_) => "ok",
+                Err(err) => `error: {err}`,
+            };
+
+            (ok, err)
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(result, (4, String::from("error: division by zero")));
+}
+
+#[test]
+fn test_checked_arithmetic_runs_defer() {
+    let result = run_checked::<(bool, bool)>(
+        r#"
+        fn divide(state, n) {
+            defer { state.cleaned = true; }
+            Ok(1 / n)
+        }
+
+        fn main() {
+            let state = #{cleaned: false};
+            let result = divide(state, 0);
+            (result.is_err(), state.cleaned)
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(result, (true, true));
+}
+
+#[test]
+fn test_unchecked_arithmetic() {
+    assert_vm_error!(
+        r#"fn main() { Ok(1 / 0) }"#,
+        DivideByZero => {}
+    );
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, i64) => r#"
+            fn main() {
+                (10.checked_div(0), 2.checked_pow(3), (-9223372036854775807 - 1).wrapping_neg())
+            }
+            "#
+        },
+        (None, Some(8), i64::MIN),
+    };
+}
@@ -0,0 +1,72 @@
+use runestick::{
+    Context, ElementStep, FromValue as _, Item, Module, Value, Vm, VmError, VmErrorKind,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Sum up the values of each named entry.
+fn totals(entries: Vec<(String, HashMap<String, i64>)>, scale: Option<i64>) -> Vec<(String, i64)> {
+    let scale = scale.unwrap_or(1);
+    let mut output = Vec::new();
+
+    for (name, values) in entries {
+        output.push((name, values.values().sum::<i64>() * scale));
+    }
+
+    output
+}
+
+fn run(source: &str) -> Result<Value, VmError> {
+    let mut module = Module::new(&["host"]);
+    module.function(&["totals"], totals).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = rune_testing::compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.call(Item::of(&["main"]), ())?.complete()
+}
+
+#[test]
+fn test_container_conversions() {
+    let output = run(r#"
+    fn main() {
+        host::totals([("a", #{x: 1, y: 2}), ("b", #{z: 3})], Some(2))
+    }
+    "#)
+    .unwrap();
+
+    let output = Vec::<(String, i64)>::from_value(output).unwrap();
+    assert_eq!(output, vec![(String::from("a"), 6), (String::from("b"), 6)]);
+}
+
+#[test]
+fn test_bad_element_path() {
+    let error = run(r#"
+    fn main() {
+        host::totals([("a", #{x: 1}), ("b", #{y: 2, z: true})], None)
+    }
+    "#)
+    .unwrap_err();
+
+    let (path, error) = error
+        .chain()
+        .find_map(|kind| match kind {
+            VmErrorKind::BadElement { path, error } => Some((path, error)),
+            _ => None,
+        })
+        .expect("expected a bad element");
+
+    assert_eq!(
+        path.steps(),
+        &[
+            ElementStep::Index(1),
+            ElementStep::TupleIndex(1),
+            ElementStep::Key(String::from("z")),
+        ]
+    );
+
+    assert_eq!(path.to_string(), r#"[1].1["z"]"#);
+    assert_eq!(error.to_string(), "expected `integer`, but found `bool`");
+}
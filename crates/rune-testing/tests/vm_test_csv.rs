@@ -0,0 +1,100 @@
+use rune_testing::*;
+
+#[test]
+fn test_csv_reader() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::csv::Reader;
+
+            fn main() {
+                let out = [];
+                let input = "name,note\r\ntea,\"hot, \"\"strong\"\"\"\n\ncake,\"two\nlines\"\n";
+
+                for row in Reader::from_string(input) {
+                    let row = row?;
+                    out.push(`{row.name}: {row.note}`);
+                }
+
+                out
+            }
+            "#
+        },
+        vec!["tea: hot, \"strong\"", "cake: two\nlines"],
+    };
+}
+
+#[test]
+fn test_csv_reader_without_headers() {
+    assert_eq! {
+        rune! {
+            Vec<Vec<String>> => r#"
+            use std::csv::Reader;
+
+            fn main() {
+                let reader = Reader::from_string("a;b\nc;;d").without_headers().delimiter(';');
+                let first = reader.next()?;
+                [first?, reader.next()??]
+            }
+            "#
+        },
+        vec![vec!["a", "b"], vec!["c", "", "d"]],
+    };
+}
+
+#[test]
+fn test_csv_writer() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            use std::csv::Writer;
+
+            fn main() {
+                let writer = Writer::with_headers(["name", "price", "note"]);
+                writer.write(#{ name: "tea", price: 3, note: "hot, \"strong\"" })?;
+                writer.write_all([#{ name: "cake", price: 5.5 }, ["pie", Some(2), true]])?;
+                writer.into_string()
+            }
+            "#
+        },
+        "name,price,note\ntea,3,\"hot, \"\"strong\"\"\"\ncake,5.5,\npie,2,true\n",
+    };
+}
+
+#[test]
+fn test_csv_errors() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::csv::Reader;
+            use std::csv::Writer;
+
+            fn error(result) {
+                match result {
+                    Some(Err(error)) => `{error}`,
+                    Err(error) => `{error}`,
+                    _ => "ok",
+                }
+            }
+
+            fn main() {
+                let reader = Reader::from_string("a,b\n1,2\n3\n\"4,5\n");
+                reader.next();
+
+                [
+                    error(reader.next()),
+                    error(reader.next()),
+                    error(Writer::new().write(#{ a: 1 })),
+                    error(Writer::new().write([[1]])),
+                ]
+            }
+            "#
+        },
+        vec![
+            "expected 2 fields but got 1 on line 3",
+            "unterminated quoted field on line 4",
+            "writing objects requires a writer with headers",
+            "cannot write value of type `Vec`",
+        ],
+    };
+}
@@ -0,0 +1,42 @@
+use rune_testing::*;
+
+#[test]
+fn test_dbg_macro() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let a = 2;
+                let b = dbg!(a * 3) + 1;
+                dbg!(b);
+                b
+            }
+            "#
+        },
+        7,
+    };
+}
+
+#[test]
+fn test_dbg_macro_closure() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                let n = 10;
+                let f = dbg!(|a| a + n);
+                f(1)
+            }
+            "#
+        },
+        11,
+    };
+}
+
+#[test]
+fn test_dbg_macro_arguments() {
+    assert_compile_error! {
+        r#"fn main() { dbg!(1, 2) }"#,
+        ParseError { error: ExpectedEof { .. } } => {}
+    };
+}
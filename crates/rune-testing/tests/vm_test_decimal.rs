@@ -0,0 +1,93 @@
+use rune_testing::*;
+
+#[test]
+fn test_decimal_arithmetic() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String, String) => r#"
+            fn main() {
+                let a = 0.1d + 0.2d;
+                let b = 19.99d * 3;
+                let c = 10d / 4d;
+                let d = -7.5d % 2;
+                let e = 1d / 3d;
+                (`{a}`, `{b}`, `{c}`, `{d}`, `{e}`)
+            }
+            "#
+        },
+        (
+            String::from("0.3"),
+            String::from("59.97"),
+            String::from("2.5"),
+            String::from("-1.5"),
+            String::from("0.3333333333333333333333333333"),
+        ),
+    };
+}
+
+#[test]
+fn test_decimal_assign() {
+    assert_eq! {
+        rune! {
+            (String, String) => r#"
+            fn main() {
+                let base = 1.50d;
+                let price = base;
+                price += 0.25d;
+                price *= 2;
+                (`{base}`, `{price}`)
+            }
+            "#
+        },
+        (String::from("1.50"), String::from("3.50")),
+    };
+}
+
+#[test]
+fn test_decimal_comparison() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool, bool) => r#"
+            fn main() {
+                (
+                    0.1d + 0.2d == 0.3d,
+                    1.0d == 1.00d,
+                    1.05d != 1.5d,
+                    1.05d < 1.5d,
+                    2d >= 2,
+                    -0.5d > -0.25d,
+                )
+            }
+            "#
+        },
+        (true, true, true, true, true, false),
+    };
+}
+
+#[test]
+fn test_decimal_functions() {
+    assert_eq! {
+        rune! {
+            (String, i64, f64, String, bool) => r#"
+            use std::decimal::Decimal;
+
+            fn main() {
+                let d = Decimal::parse("2.345")?;
+                let matched = match 1.50d { 1.5d => true, _ => false };
+                (`{d.round(2)}`, d.scale(), d.to_float(), `{Decimal::from_int(42)}`, matched)
+            }
+            "#
+        },
+        (String::from("2.35"), 3, 2.345, String::from("42"), true),
+    };
+}
+
+#[test]
+fn test_decimal_errors() {
+    assert_vm_error!(
+        r#"fn main() { 1d / 0 }"#,
+        BadReturn { error, .. } => {
+            assert!(matches!(error.kind(), DivideByZero));
+        }
+    );
+}
@@ -0,0 +1,192 @@
+use rune_testing::*;
+
+#[test]
+fn test_derive_builder() {
+    assert_eq! {
+        rune! {
+            (String, i64, Option<bool>) => r#"
+            #[derive(Builder)]
+            #[builder(optional(verbose))]
+            struct Config { host, port, verbose }
+
+            fn main() {
+                let config = Config::builder()
+                    .host("localhost")
+                    .port(8080)
+                    .build()?;
+
+                (config.host, config.port, config.verbose)
+            }
+            "#
+        },
+        (String::from("localhost"), 8080, None),
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            #[derive(Builder)]
+            struct Config { host, port }
+
+            fn main() {
+                match Config::builder().host("localhost").build() {
+                    Ok(_) => "ok",
+                    Err(error) => error,
+                }
+            }
+            "#
+        },
+        "missing field `port` of `Config`",
+    };
+}
+
+#[test]
+fn test_derive_builder_in_module() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            mod config {
+                #[derive(Builder)]
+                struct Config { port }
+            }
+
+            fn main() {
+                let config = config::Config::builder().port(1).build()?;
+                config.port
+            }
+            "#
+        },
+        1,
+    };
+}
+
+#[test]
+fn test_unsupported_derive() {
+    assert_compile_error! {
+        r#"#[derive(Builder)] struct Point(x, y); fn main() {}"#,
+        UnsupportedDerive { span } => {
+            assert_eq!(span, Span::new(9, 16));
+        }
+    };
+
+    assert_compile_error! {
+        r#"#[derive(Debug)] struct Point { x } fn main() {}"#,
+        UnsupportedDerive { span } => {
+            assert_eq!(span, Span::new(9, 14));
+        }
+    };
+
+    assert_compile_error! {
+        r#"#[builder(optional(z))] #[derive(Builder)] struct Point { x } fn main() {}"#,
+        UnsupportedAttribute { span } => {
+            assert_eq!(span, Span::new(19, 20));
+        }
+    };
+}
+
+#[test]
+fn test_derive_serde_struct() {
+    assert_eq! {
+        rune! {
+            (bool, bool, String, i64) => r#"
+            #[derive(Serialize, Deserialize)]
+            #[serde(rename(user_name = "userName"))]
+            struct User { user_name, age }
+
+            fn main() {
+                let object = User { user_name: "jane", age: 42 }.to_object();
+                let user = User::from_object(object)?;
+                (object.contains_key("userName"), object.contains_key("user_name"), user.user_name, user.age)
+            }
+            "#
+        },
+        (true, false, String::from("jane"), 42),
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            #[derive(Deserialize)]
+            #[serde(rename(user_name = "userName"))]
+            struct User { user_name, age }
+
+            fn main() {
+                match User::from_object(#{"user_name": "jane", "age": 42}) {
+                    Ok(_) => "ok",
+                    Err(error) => error,
+                }
+            }
+            "#
+        },
+        "missing field `userName` of `User`",
+    };
+}
+
+#[test]
+fn test_derive_serde_enum() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, bool) => r#"
+            #[derive(Serialize, Deserialize)]
+            #[serde(rename(Circle = "circle"))]
+            enum Shape { Circle { radius }, Rect(w, h), Empty }
+
+            fn area(shape) {
+                match shape {
+                    Shape::Circle { radius } => radius * radius * 3,
+                    Shape::Rect(w, h) => w * h,
+                    Shape::Empty => 0,
+                }
+            }
+
+            fn main() {
+                let circle = Shape::Circle { radius: 2 }.to_object();
+                let rect = Shape::Rect(2, 3).to_object();
+                let empty = Shape::Empty.to_object();
+
+                (
+                    area(Shape::from_object(circle)?),
+                    area(Shape::from_object(rect)?),
+                    area(Shape::from_object(empty)?),
+                    circle.contains_key("circle"),
+                )
+            }
+            "#
+        },
+        (12, 6, 0, true),
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            #[derive(Deserialize)]
+            enum Shape { Circle { radius } }
+
+            fn main() {
+                match Shape::from_object(#{"Square": 1}) {
+                    Ok(_) => "ok",
+                    Err(error) => error,
+                }
+            }
+            "#
+        },
+        "unknown variant of `Shape`",
+    };
+}
+
+#[test]
+fn test_unsupported_serde_attribute() {
+    assert_compile_error! {
+        r#"#[serde(rename(z = "Z"))] #[derive(Serialize)] struct Point { x } fn main() {}"#,
+        UnsupportedAttribute { span } => {
+            assert_eq!(span, Span::new(15, 22));
+        }
+    };
+
+    assert_compile_error! {
+        r#"#[serde(rename(x = "X"))] struct Point { x } fn main() {}"#,
+        UnsupportedAttribute { span } => {
+            assert_eq!(span, Span::new(2, 24));
+        }
+    };
+}
@@ -0,0 +1,145 @@
+use rune_testing::*;
+
+#[test]
+fn test_discriminants() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64) => r#"
+            enum Color {
+                Red = 1,
+                Green = 5,
+                Blue,
+            }
+
+            enum Empty {
+                First,
+                Second,
+            }
+
+            fn main() {
+                (Color::Red as int, Color::Green as int, Color::Blue as int, Empty::Second as int)
+            }
+            "#
+        },
+        (1, 5, 6, 1),
+    };
+}
+
+#[test]
+fn test_negative_discriminants() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            enum Ordering {
+                Less = -1,
+                Equal,
+                Greater,
+            }
+
+            fn main() {
+                Ordering::Less as int + Ordering::Greater as int * 10
+            }
+            "#
+        },
+        9,
+    };
+}
+
+#[test]
+fn test_from_int() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool) => r#"
+            enum Color {
+                Red = 1,
+                Green = 2,
+            }
+
+            fn main() {
+                let green = match Color::from_int(2) {
+                    Some(Color::Green) => true,
+                    _ => false,
+                };
+
+                let missing = Color::from_int(3) is Option && Color::from_int(3).is_none();
+                let roundtrip = match Color::from_int(Color::Red as int) {
+                    Some(color) => color is Color,
+                    None => false,
+                };
+                (green, missing, roundtrip)
+            }
+            "#
+        },
+        (true, true, true),
+    };
+}
+
+#[test]
+fn test_as_numbers() {
+    assert_eq! {
+        rune! {
+            (f64, i64, i64) => r#"
+            fn main() {
+                (2 as float, 2.7 as int, 'a' as int)
+            }
+            "#
+        },
+        (2.0, 2, 97),
+    };
+}
+
+#[test]
+fn test_unsupported_as() {
+    assert_vm_error!(
+        r#"
+        enum Shape {
+            Circle(radius),
+        }
+
+        fn main() {
+            Shape::Circle(1) as int
+        }
+        "#,
+        UnsupportedAs { .. } => {}
+    );
+}
+
+#[test]
+fn test_discriminant_errors() {
+    assert_compile_error! {
+        r#"
+        enum Color {
+            Red = 1,
+            Green = 0,
+            Blue,
+        }
+
+        fn main() {}
+        "#,
+        DuplicateDiscriminant { value, .. } => {
+            assert_eq!(value, 1);
+        }
+    };
+
+    assert_compile_error! {
+        r#"
+        enum Shape {
+            Circle(radius) = 1,
+        }
+
+        fn main() {}
+        "#,
+        UnsupportedDiscriminant { .. } => {}
+    };
+
+    assert_compile_error! {
+        r#"
+        enum Color {
+            Red = 1.5,
+        }
+
+        fn main() {}
+        "#,
+        UnsupportedDiscriminant { .. } => {}
+    };
+}
@@ -0,0 +1,175 @@
+use rune_testing::*;
+
+#[test]
+fn test_drop_on_return() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Guard { log, name }
+
+            impl Drop for Guard {
+                fn drop(self) {
+                    self.log.push(self.name);
+                }
+            }
+
+            fn scope(log) {
+                let a = Guard { log, name: "a" };
+                let b = Guard { log, name: "b" };
+                log.push("body");
+            }
+
+            fn main() {
+                let log = [];
+                scope(log);
+                log.push("after");
+                log
+            }
+            "#
+        },
+        vec!["body", "a", "b", "after"],
+    };
+}
+
+#[test]
+fn test_drop_kept_alive() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Guard(log);
+
+            impl Drop for Guard {
+                fn drop(self) {
+                    self.0.push("dropped");
+                }
+            }
+
+            fn make(log) {
+                Guard(log)
+            }
+
+            fn keep(guard) {
+                guard
+            }
+
+            fn main() {
+                let log = [];
+                let guard = make(log);
+                log.push("made");
+                keep(guard);
+                log.push("kept");
+                guard = ();
+                log
+            }
+            "#
+        },
+        vec!["made", "kept", "dropped"],
+    };
+}
+
+#[test]
+fn test_drop_explicit() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Guard { log }
+
+            impl Drop for Guard {
+                fn drop(self) {
+                    self.log.push("dropped");
+                }
+            }
+
+            fn scope(log) {
+                let guard = Guard { log };
+                drop(guard);
+                log.push("body");
+            }
+
+            fn main() {
+                let log = [];
+                scope(log);
+                log
+            }
+            "#
+        },
+        vec!["dropped", "body"],
+    };
+}
+
+#[test]
+fn test_drop_no_nesting() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Guard { log, name }
+
+            impl Drop for Guard {
+                fn drop(self) {
+                    inner(self.log);
+                    self.log.push(self.name);
+                }
+            }
+
+            fn inner(log) {
+                let guard = Guard { log, name: "inner" };
+            }
+
+            fn main() {
+                let log = [];
+                inner(log);
+                log
+            }
+            "#
+        },
+        vec!["inner"],
+    };
+}
+
+#[test]
+fn test_drop_budget_exceeded() {
+    assert_vm_error!(
+        r#"
+        struct Guard;
+
+        impl Drop for Guard {
+            fn drop(self) {
+                loop {}
+            }
+        }
+
+        fn scope() {
+            let guard = Guard;
+        }
+
+        fn main() {
+            scope();
+        }
+        "#,
+        FinalizerBudgetExceeded { budget } => {
+            assert_eq!(*budget, runestick::FINALIZER_BUDGET);
+        }
+    );
+}
+
+#[test]
+fn test_unsupported_finalizer() {
+    assert_compile_error! {
+        r#"struct Guard; impl Drop for Guard { fn close(self) {} } fn main() {}"#,
+        UnsupportedFinalizer { span } => {
+            assert_eq!(span, Span::new(36, 53));
+        }
+    };
+
+    assert_compile_error! {
+        r#"struct Guard; impl Drop for Guard { async fn drop(self) {} } fn main() {}"#,
+        UnsupportedFinalizer { span } => {
+            assert_eq!(span, Span::new(36, 58));
+        }
+    };
+
+    assert_compile_error! {
+        r#"enum Guard { A } impl Drop for Guard { fn drop(self) {} } fn main() {}"#,
+        UnsupportedFinalizer { .. } => {}
+    };
+}
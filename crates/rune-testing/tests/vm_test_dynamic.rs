@@ -0,0 +1,144 @@
+use rune_testing::load_source;
+use runestick::{Context, FromValue, Item, Unit, UnitRegistry, Vm, VmError, VmErrorKind};
+use std::sync::Arc;
+
+fn load(context: &Context, source: &str) -> Arc<Unit> {
+    let (unit, _) = load_source(context, &Default::default(), source).unwrap();
+    Arc::new(unit)
+}
+
+fn setup() -> (UnitRegistry, Arc<Context>) {
+    let registry = UnitRegistry::new();
+    let mut context = Context::with_default_modules().unwrap();
+    context
+        .install(&runestick::modules::dynamic::module(&registry).unwrap())
+        .unwrap();
+    (registry, Arc::new(context))
+}
+
+fn run<T>(context: &Arc<Context>, source: &str) -> Result<T, VmError>
+where
+    T: FromValue,
+{
+    let vm = Vm::new(context.clone(), load(context, source));
+    T::from_value(vm.call(Item::of(&["main"]), ())?.complete()?)
+}
+
+#[test]
+fn test_dynamic_call() {
+    let (registry, context) = setup();
+
+    let plugin = load(
+        &context,
+        r#"
+        fn greet(name) { `Hello, {name}!` }
+
+        mod handlers {
+            fn add(a, b) { a + b }
+        }
+        "#,
+    );
+
+    registry.insert("plugin", context.clone(), plugin);
+
+    let output = run::<(String, i64, bool, bool, Vec<String>)>(
+        &context,
+        r#"
+        use std::dynamic;
+
+        fn main() {
+            let greeting = dynamic::call("plugin", "greet", ["World"]);
+            let sum = dynamic::call("plugin", "handlers::add", [1, 2]);
+            (greeting, sum, dynamic::has("plugin"), dynamic::has("other"), dynamic::units())
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        output,
+        (
+            String::from("Hello, World!"),
+            3,
+            true,
+            false,
+            vec![String::from("plugin")]
+        )
+    );
+}
+
+#[test]
+fn test_dynamic_call_nested() {
+    let (registry, context) = setup();
+
+    registry.insert(
+        "a",
+        context.clone(),
+        load(
+            &context,
+            r#"fn run(n) { std::dynamic::call("b", "run", [n + 1]) }"#,
+        ),
+    );
+
+    registry.insert(
+        "b",
+        context.clone(),
+        load(&context, r#"fn run(n) { n * 10 }"#),
+    );
+
+    let output = run::<i64>(
+        &context,
+        r#"fn main() { std::dynamic::call("a", "run", [1]) }"#,
+    )
+    .unwrap();
+
+    assert_eq!(output, 20);
+    assert!(registry.remove("b").is_some());
+
+    let error = run::<i64>(
+        &context,
+        r#"fn main() { std::dynamic::call("a", "run", [1]) }"#,
+    )
+    .unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::MissingUnit { name } => assert_eq!(name, "b"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_dynamic_call_errors() {
+    let (registry, context) = setup();
+    registry.insert(
+        "plugin",
+        context.clone(),
+        load(&context, r#"fn one(a) { a }"#),
+    );
+
+    let error = run::<()>(
+        &context,
+        r#"fn main() { std::dynamic::call("plugin", "two", []) }"#,
+    )
+    .unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::MissingFunction { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let error = run::<()>(
+        &context,
+        r#"fn main() { std::dynamic::call("plugin", "one", [1, 2]) }"#,
+    )
+    .unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::BadFunctionArgumentCount {
+            signature,
+            actual: 2,
+            expected: 1,
+        } => assert_eq!(signature.to_string(), "one(a)"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
@@ -0,0 +1,122 @@
+use rune_testing::*;
+use runestick::Bytes;
+
+#[test]
+fn test_base64() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String) => r#"
+            use std::encoding::base64;
+
+            fn main() {
+                (base64::encode(""), base64::encode("f"), base64::encode("foob"), base64::encode(b"\xff\xfe\xfd"))
+            }
+            "#
+        },
+        (
+            String::from(""),
+            String::from("Zg=="),
+            String::from("Zm9vYg=="),
+            String::from("//79"),
+        ),
+    };
+
+    assert_eq! {
+        rune! {
+            (Bytes, Bytes, Bytes) => r#"
+            use std::encoding::base64;
+
+            fn main() {
+                (base64::decode("Zm9vYg==")?, base64::decode("Zm9vYg")?, base64::decode(b"//79")?)
+            }
+            "#
+        },
+        (
+            Bytes::from_vec(b"foob".to_vec()),
+            Bytes::from_vec(b"foob".to_vec()),
+            Bytes::from_vec(vec![0xff, 0xfe, 0xfd]),
+        ),
+    };
+}
+
+#[test]
+fn test_hex() {
+    assert_eq! {
+        rune! {
+            (String, String, Bytes) => r#"
+            use std::encoding::hex;
+
+            fn main() {
+                (hex::encode("hi!"), hex::encode(b"\x00\xff"), hex::decode("00fFA0")?)
+            }
+            "#
+        },
+        (
+            String::from("686921"),
+            String::from("00ff"),
+            Bytes::from_vec(vec![0x00, 0xff, 0xa0]),
+        ),
+    };
+}
+
+#[test]
+fn test_url() {
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            use std::encoding::url;
+
+            fn main() {
+                let encoded = url::encode_component("a b&c=ä/~");
+                (encoded, url::decode(encoded)?, url::decode("100%25+more")?)
+            }
+            "#
+        },
+        (
+            String::from("a%20b%26c%3D%C3%A4%2F~"),
+            String::from("a b&c=ä/~"),
+            String::from("100%+more"),
+        ),
+    };
+}
+
+#[test]
+fn test_decode_errors() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::encoding::base64;
+            use std::encoding::hex;
+            use std::encoding::url;
+
+            fn error(result) {
+                match result {
+                    Err(error) => `{error}`,
+                    Ok(..) => "ok",
+                }
+            }
+
+            fn main() {
+                [
+                    error(base64::decode("Zm9v!A==")),
+                    error(base64::decode("Zm9vY")),
+                    error(base64::decode("Zg=")),
+                    error(hex::decode("abc")),
+                    error(hex::decode("zz")),
+                    error(url::decode("100%")),
+                    error(url::decode("%ff")),
+                ]
+            }
+            "#
+        },
+        vec![
+            "invalid base64 at byte 4",
+            "invalid base64 length",
+            "invalid base64 length",
+            "invalid hex length",
+            "invalid hex at byte 0",
+            "invalid percent-encoding at byte 3",
+            "decoded data is not valid UTF-8",
+        ],
+    };
+}
@@ -0,0 +1,183 @@
+use rune::IntoJsonDiagnostics as _;
+use rune::Sources;
+use rune_testing::*;
+use runestick::{Context, Item, Vm, VmError, VmErrorKind};
+use std::sync::Arc;
+
+/// Run the `main` function of the given source, expecting it to error.
+fn run_error(source: &str) -> (VmError, Sources) {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+
+    let (unit, _) = load_source(&context, &Default::default(), source).unwrap();
+    let vm = Vm::new(context, Arc::new(unit));
+    let error = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap_err();
+
+    (error, sources(source))
+}
+
+fn chain(error: &VmError) -> Vec<String> {
+    error.chain().map(|e| e.to_string()).collect()
+}
+
+#[test]
+fn test_with_context() {
+    let error = VmError::from(VmErrorKind::DivideByZero).with_context("calculating");
+    assert_eq!(chain(&error), vec!["calculating", "division by zero"]);
+
+    let (error, _) = run_error(r#"fn main() { 1 / 0 }"#);
+    let error = error.with_context("running script");
+
+    assert_eq!(chain(&error), vec!["running script", "division by zero"]);
+
+    // The location of the error is preserved.
+    let (error, unwound) = error.into_unwound();
+    assert!(unwound.is_some());
+    assert!(matches!(error.kind(), VmErrorKind::Context { .. }));
+}
+
+#[test]
+fn test_context_in_script() {
+    let (error, _) = run_error(
+        r#"
+        use std::error;
+
+        fn parse(n) {
+            error::context("parsing number", || 1 / n)
+        }
+
+        fn main() {
+            error::context("loading config", || parse(0))
+        }
+        "#,
+    );
+
+    assert_eq!(
+        chain(&error),
+        vec!["loading config", "parsing number", "division by zero"]
+    );
+}
+
+#[test]
+fn test_context_through_fn_ptr() {
+    let (error, _) = run_error(
+        r#"
+        use std::error;
+
+        fn main() {
+            let values = [1, 2];
+
+            values.sort_by(|a, b| {
+                error::context("comparing values", || a / 0)
+            });
+        }
+        "#,
+    );
+
+    let chain = chain(&error);
+    assert_eq!(
+        chain[chain.len() - 2..],
+        ["comparing values", "division by zero"]
+    );
+}
+
+#[test]
+fn test_catch() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::error;
+
+            fn load() {
+                error::context("loading config", || 1 / 0)
+            }
+
+            fn main() {
+                match error::catch(load) {
+                    Ok(_) => [],
+                    Err(err) => err.chain(),
+                }
+            }
+            "#
+        },
+        vec!["loading config", "division by zero"],
+    };
+
+    assert_eq! {
+        rune! {
+            String => r#"
+            use std::error;
+
+            fn main() {
+                match error::catch(|| 1 / 0) {
+                    Ok(_) => "ok",
+                    Err(err) => `error: {err}`,
+                }
+            }
+            "#
+        },
+        "error: division by zero",
+    };
+
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            fn main() {
+                std::error::catch(|| 42)?
+            }
+            "#
+        },
+        42,
+    };
+}
+
+#[test]
+fn test_catch_through_try() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::error;
+
+            fn inner() {
+                let value = error::catch(|| error::context("dividing", || 1 / 0))?;
+                Ok(value)
+            }
+
+            fn outer() {
+                Ok(inner()? + 1)
+            }
+
+            fn main() {
+                match outer() {
+                    Ok(_) => [],
+                    Err(err) => err.chain(),
+                }
+            }
+            "#
+        },
+        vec!["dividing", "division by zero"],
+    };
+}
+
+#[test]
+fn test_catch_propagates_panics() {
+    assert_vm_error! {
+        r#"fn main() { std::error::catch(|| panic("boom")) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "boom");
+        }
+    };
+}
+
+#[test]
+fn test_json_diagnostics_notes() {
+    let (error, sources) = run_error(r#"fn main() { std::error::context("loading", || 1 / 0) }"#);
+
+    let diagnostics = error.into_json_diagnostics(&sources);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "loading");
+    assert_eq!(diagnostics[0].notes, vec!["caused by: division by zero"]);
+}
@@ -0,0 +1,96 @@
+use rune_testing::load_vm;
+use runestick::{Context, Events, FromValue as _, Item, Value};
+
+/// Run the main function of the given script, and return the events it
+/// registered together with its output.
+fn setup(source: &str) -> (Events, Value) {
+    let vm = load_vm(Context::with_default_modules().unwrap(), source).unwrap();
+    let events = vm.events().clone();
+    let mut execution = vm.call(Item::of(&["main"]), ()).unwrap();
+    let output = rune_testing::block_on(execution.async_complete()).unwrap();
+    (events, output)
+}
+
+#[test]
+fn test_fire_events() {
+    let (events, _) = setup(
+        r#"
+        use std::event::on;
+
+        fn save(doc) {
+            `saved {doc}`
+        }
+
+        async fn register() {
+            on("close", |doc| `closed {doc}`);
+        }
+
+        async fn main() {
+            on("save", save);
+            on("save", |doc| doc.len());
+            register().await;
+        }
+        "#,
+    );
+
+    assert_eq!(events.names().unwrap(), vec!["close", "save"]);
+
+    let output = events.fire("save", (String::from("readme"),)).unwrap();
+    assert_eq!(output.len(), 2);
+    assert_eq!(
+        String::from_value(output[0].clone()).unwrap(),
+        "saved readme"
+    );
+    assert_eq!(i64::from_value(output[1].clone()).unwrap(), 6);
+
+    let output = events.fire("close", (String::from("readme"),)).unwrap();
+    assert_eq!(
+        String::from_value(output[0].clone()).unwrap(),
+        "closed readme"
+    );
+
+    assert!(events.fire("missing", ()).unwrap().is_empty());
+}
+
+#[test]
+fn test_off() {
+    let (events, output) = setup(
+        r#"
+        use std::event::on;
+        use std::event::off;
+
+        fn main() {
+            on("a", |n| n);
+            on("b", |n| n);
+            (off("a"), off("c"))
+        }
+        "#,
+    );
+
+    assert_eq!(<(bool, bool)>::from_value(output).unwrap(), (true, false));
+    assert_eq!(events.names().unwrap(), vec!["b"]);
+}
+
+#[test]
+fn test_async_fire() {
+    let (events, _) = setup(
+        r#"
+        async fn handler(n) {
+            n + 1
+        }
+
+        fn main() {
+            std::event::on("tick", handler);
+            std::event::on("tick", |n| n * 2);
+        }
+        "#,
+    );
+
+    let output = rune_testing::block_on(events.async_fire("tick", (10i64,))).unwrap();
+    let output = output
+        .into_iter()
+        .map(|v| i64::from_value(v).unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(output, vec![11, 20]);
+}
@@ -0,0 +1,127 @@
+use rune_testing::*;
+
+#[test]
+fn test_extension_in_scope() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            mod ext {
+                impl VecMathExt for Vec {
+                    fn sum(self) {
+                        let sum = 0;
+
+                        for value in self {
+                            sum += value;
+                        }
+
+                        sum
+                    }
+                }
+            }
+
+            use ext::VecMathExt;
+
+            fn main() {
+                [1, 2, 3].sum()
+            }
+            "#
+        },
+        6,
+    };
+}
+
+#[test]
+fn test_extension_declared_in_module() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            impl StringExt for String {
+                fn double(self) {
+                    self.len() * 2
+                }
+            }
+
+            mod a {
+                fn f() {
+                    "abc".double()
+                }
+            }
+
+            fn main() {
+                (a::f(), StringExt::double("ab"))
+            }
+            "#
+        },
+        (6, 4),
+    };
+}
+
+#[test]
+fn test_extension_falls_back() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            impl StringExt for String {
+                fn len(self) {
+                    0
+                }
+            }
+
+            fn main() {
+                ("abc".len(), [1, 2].len())
+            }
+            "#
+        },
+        (0, 2),
+    };
+}
+
+#[test]
+fn test_extension_not_in_scope() {
+    assert_vm_error!(
+        r#"
+        mod ext {
+            impl VecMathExt for Vec {
+                fn sum(self) {
+                    0
+                }
+            }
+        }
+
+        fn main() {
+            [1, 2, 3].sum()
+        }
+        "#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
+
+#[test]
+fn test_extension_ambiguous() {
+    assert_compile_error! {
+        r#"
+        mod a {
+            impl VecExt for Vec {
+                fn sum(self) { 1 }
+            }
+        }
+
+        mod b {
+            impl OtherVecExt for Vec {
+                fn sum(self) { 2 }
+            }
+        }
+
+        use a::VecExt;
+        use b::OtherVecExt;
+
+        fn main() {
+            [1, 2, 3].sum()
+        }
+        "#,
+        AmbiguousExtensionFunction { name, extensions, .. } => {
+            assert_eq!(name, "sum");
+            assert_eq!(extensions.len(), 2);
+        }
+    };
+}
@@ -0,0 +1,163 @@
+use rune::{CompileError, LoadErrorKind};
+use runestick::{Context, FromValue as _, Item, Module, Object, Value, Vm};
+use std::sync::Arc;
+
+#[derive(Debug)]
+struct HttpResponse {
+    status: i64,
+    body: String,
+}
+
+impl HttpResponse {
+    fn matcher(&self) -> Option<Object<Value>> {
+        let mut object = Object::new();
+        object.insert(String::from("status"), Value::from(self.status));
+        object.insert(String::from("body"), Value::from(self.body.clone()));
+        Some(object)
+    }
+}
+
+#[derive(Debug)]
+struct Point {
+    x: i64,
+    y: i64,
+}
+
+impl Point {
+    fn matcher(&self) -> Option<(i64, i64)> {
+        // NB: points at the origin don't match.
+        if self.x == 0 && self.y == 0 {
+            return None;
+        }
+
+        Some((self.x, self.y))
+    }
+}
+
+runestick::impl_external!(HttpResponse);
+runestick::impl_external!(Point);
+
+fn context() -> Context {
+    let mut module = Module::new(&["http"]);
+    module
+        .ty(&["HttpResponse"])
+        .build::<HttpResponse>()
+        .unwrap();
+    module
+        .function(&["HttpResponse", "new"], |status: i64, body: &str| {
+            HttpResponse {
+                status,
+                body: body.to_owned(),
+            }
+        })
+        .unwrap();
+    module
+        .object_matcher(&["status", "body"], HttpResponse::matcher)
+        .unwrap();
+
+    module.ty(&["Point"]).build::<Point>().unwrap();
+    module
+        .function(&["Point", "new"], |x: i64, y: i64| Point { x, y })
+        .unwrap();
+    module.tuple_matcher(2, Point::matcher).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    context
+}
+
+fn run(source: &str) -> Value {
+    let context = context();
+    let (unit, _) = rune_testing::compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap()
+}
+
+fn compile_error(source: &str) -> CompileError {
+    let context = context();
+
+    match rune_testing::compile_source(&context, source)
+        .unwrap_err()
+        .into_kind()
+    {
+        LoadErrorKind::CompileError { error, .. } => error,
+        kind => panic!("expected compile error but was `{:?}`", kind),
+    }
+}
+
+#[test]
+fn test_external_object_match() {
+    let output = run(r#"
+    use http::HttpResponse;
+
+    fn check(response) {
+        match response {
+            HttpResponse { status: 404, .. } => "not found",
+            HttpResponse { status, body } => `{status}: {body}`,
+        }
+    }
+
+    fn main() {
+        (check(HttpResponse::new(200, "hello")), check(HttpResponse::new(404, "")))
+    }
+    "#);
+
+    let output = <(String, String)>::from_value(output).unwrap();
+    assert_eq!(
+        output,
+        (String::from("200: hello"), String::from("not found"))
+    );
+}
+
+#[test]
+fn test_external_tuple_match() {
+    let output = run(r#"
+    use http::Point;
+
+    fn check(point) {
+        match point {
+            Point(x, 0) => x,
+            Point(x, y) => x * y,
+            _ => -1,
+        }
+    }
+
+    fn main() {
+        [check(Point::new(2, 0)), check(Point::new(2, 3)), check(Point::new(0, 0)), check(42)]
+    }
+    "#);
+
+    let output = Vec::<i64>::from_value(output).unwrap();
+    assert_eq!(output, vec![2, 6, -1, -1]);
+}
+
+#[test]
+fn test_external_match_checks() {
+    let error = compile_error(
+        r#"fn main() { match 1 { http::HttpResponse { status, headers } => 1, _ => 2 } }"#,
+    );
+
+    match error {
+        CompileError::LitObjectNotField { field, .. } => assert_eq!(field, "headers"),
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    let error = compile_error(r#"fn main() { match 1 { http::Point(x) => 1, _ => 2 } }"#);
+
+    match error {
+        CompileError::UnsupportedArgumentCount {
+            expected, actual, ..
+        } => assert_eq!((expected, actual), (2, 1)),
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    let error = compile_error(r#"fn main() { match 1 { http::Point { x } => 1, _ => 2 } }"#);
+
+    match error {
+        CompileError::UnsupportedMetaPattern { .. } => (),
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
@@ -0,0 +1,73 @@
+use rune_testing::*;
+
+#[test]
+fn test_float_array() {
+    assert_eq! {
+        rune! {
+            (Vec<f64>, f64, f64, usize) => r#"
+            use std::nd::FloatArray;
+
+            fn main() {
+                let a = FloatArray::from_vec([1.0, 2.0, 3.0]);
+                let b = FloatArray::filled(3, 2.0);
+                a.push(4.0);
+                b.push(0.5);
+                (a.mul(b).to_vec(), a.dot(b), a.sum(), a.len())
+            }
+            "#
+        },
+        (vec![2.0, 4.0, 6.0, 2.0], 14.0, 10.0, 4),
+    };
+}
+
+#[test]
+fn test_float_array_operators() {
+    assert_eq! {
+        rune! {
+            (Vec<f64>, Vec<f64>, String) => r#"
+            use std::nd::FloatArray;
+
+            fn main() {
+                let a = FloatArray::from_vec([1.0, 2.0]);
+                let b = FloatArray::from_vec([3.0, 5.0]);
+                let c = (a + b) * b - a;
+                (c.to_vec(), c.scale(0.5).to_vec(), `{c}`)
+            }
+            "#
+        },
+        (vec![11.0, 33.0], vec![5.5, 16.5], String::from("[11.0, 33.0]")),
+    };
+}
+
+#[test]
+fn test_float_array_map() {
+    assert_eq! {
+        rune! {
+            (Vec<f64>, Option<f64>, Option<f64>, Option<f64>) => r#"
+            use std::nd::FloatArray;
+
+            fn main() {
+                let a = FloatArray::from_vec([1.0, -2.0, 3.0]).map(|v| v * v);
+                (a.to_vec(), a.mean(), a.max(), FloatArray::new().min())
+            }
+            "#
+        },
+        (vec![1.0, 4.0, 9.0], Some(14.0 / 3.0), Some(9.0), None),
+    };
+}
+
+#[test]
+fn test_float_array_length_mismatch() {
+    assert_vm_error!(
+        r#"
+        use std::nd::FloatArray;
+
+        fn main() {
+            FloatArray::zeros(2).add(FloatArray::zeros(3))
+        }
+        "#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "arrays have different lengths: 2 and 3");
+        }
+    );
+}
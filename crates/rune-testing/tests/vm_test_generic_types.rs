@@ -0,0 +1,142 @@
+use runestick::{Context, ContextError, FromValue as _, Item, Module, Value, Vm};
+use std::sync::Arc;
+
+#[derive(Debug, Default)]
+struct Registry<T> {
+    values: Vec<T>,
+}
+
+impl<T> Registry<T>
+where
+    T: Clone,
+{
+    fn new() -> Self {
+        Self { values: Vec::new() }
+    }
+
+    fn push(&mut self, value: T) {
+        self.values.push(value);
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn first(&self) -> Option<T> {
+        self.values.first().cloned()
+    }
+}
+
+runestick::impl_external!(impl<T> Registry<T>);
+
+fn context() -> Context {
+    let mut module = Module::new(&["game"]);
+
+    module
+        .ty(&["NameRegistry"])
+        .build::<Registry<String>>()
+        .unwrap();
+    module
+        .function(&["NameRegistry", "new"], Registry::<String>::new)
+        .unwrap();
+    module.inst_fn("push", Registry::<String>::push).unwrap();
+    module.inst_fn("len", Registry::<String>::len).unwrap();
+    module.inst_fn("first", Registry::<String>::first).unwrap();
+
+    module
+        .ty(&["ScoreRegistry"])
+        .build::<Registry<i64>>()
+        .unwrap();
+    module
+        .function(&["ScoreRegistry", "new"], Registry::<i64>::new)
+        .unwrap();
+    module.inst_fn("push", Registry::<i64>::push).unwrap();
+    module.inst_fn("len", Registry::<i64>::len).unwrap();
+
+    module
+        .type_alias(&["Names"], &["game", "NameRegistry"])
+        .unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    context
+}
+
+fn run(source: &str) -> Value {
+    let context = context();
+    let (unit, _) = rune_testing::compile_source(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    vm.call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap()
+}
+
+#[test]
+fn test_generic_types() {
+    let output = run(r#"
+    use game::NameRegistry;
+    use game::ScoreRegistry;
+
+    fn main() {
+        let names = NameRegistry::new();
+        names.push("ferris");
+        names.push("corro");
+
+        let scores = ScoreRegistry::new();
+        scores.push(42);
+
+        (names.len(), scores.len(), names.first(), names is ScoreRegistry, scores is ScoreRegistry)
+    }
+    "#);
+
+    let output = <(usize, usize, Option<String>, bool, bool)>::from_value(output).unwrap();
+    assert_eq!(output, (2, 1, Some(String::from("ferris")), false, true));
+}
+
+#[test]
+fn test_type_alias() {
+    let output = run(r#"
+    use game::Names;
+
+    fn main() {
+        let names = Names::new();
+        names.push("ferris");
+        (names.len(), names is Names, names is game::NameRegistry, game::Names::new() is Names)
+    }
+    "#);
+
+    let output = <(usize, bool, bool, bool)>::from_value(output).unwrap();
+    assert_eq!(output, (1, true, true, true));
+}
+
+#[test]
+fn test_type_alias_errors() {
+    let mut module = Module::new(&["game"]);
+    module
+        .type_alias(&["Names"], &["game", "NameRegistry"])
+        .unwrap();
+
+    let mut context = Context::new();
+
+    assert!(matches!(
+        context.install(&module),
+        Err(ContextError::MissingAliasTarget { .. })
+    ));
+
+    let mut module = Module::new(&["game"]);
+    module
+        .ty(&["NameRegistry"])
+        .build::<Registry<String>>()
+        .unwrap();
+    module
+        .type_alias(&["NameRegistry"], &["game", "NameRegistry"])
+        .unwrap();
+
+    let mut context = Context::new();
+
+    assert!(matches!(
+        context.install(&module),
+        Err(ContextError::ConflictingAlias { .. })
+    ));
+}
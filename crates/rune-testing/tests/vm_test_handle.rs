@@ -0,0 +1,111 @@
+use rune_testing::load_vm;
+use runestick::{Context, FromValue as _, Handle, Item, Module, Value, Vm, VmError, VmErrorKind};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct Connection {
+    id: i64,
+}
+
+fn connection_id(conn: &Handle) -> Result<i64, VmError> {
+    Ok(conn.downcast_ref::<Connection>()?.id)
+}
+
+fn vm(source: &str) -> Vm {
+    let mut module = Module::new(&["db"]);
+    module.function(&["id"], connection_id).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    load_vm(context, source).unwrap()
+}
+
+fn connect(id: i64, closed: &Rc<RefCell<Vec<i64>>>) -> Value {
+    let closed = closed.clone();
+
+    Value::from(Handle::with_drop(Connection { id }, move |conn| {
+        closed.borrow_mut().push(conn.id)
+    }))
+}
+
+fn call(vm: Vm, arg: Value) -> Result<Value, VmError> {
+    vm.call(Item::of(&["main"]), (arg,))?.complete()
+}
+
+/// Check the error returned from a native function.
+fn assert_native_error(error: VmError, f: impl FnOnce(&VmErrorKind)) {
+    match error.into_unwound().0.kind() {
+        VmErrorKind::BadReturn { error, .. } => f(error.kind()),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_handle_released() {
+    let vm = vm(r#"
+    fn main(conn) {
+        db::id(conn) + db::id(conn)
+    }
+    "#);
+
+    let closed = Rc::new(RefCell::new(Vec::new()));
+    let output = call(vm, connect(21, &closed)).unwrap();
+
+    assert_eq!(i64::from_value(output).unwrap(), 42);
+    assert_eq!(*closed.borrow(), vec![21]);
+}
+
+#[test]
+fn test_handle_kept_alive() {
+    let vm = vm(r#"
+    fn main(conn) {
+        #{conn: conn}
+    }
+    "#);
+
+    let closed = Rc::new(RefCell::new(Vec::new()));
+    let output = call(vm, connect(1, &closed)).unwrap();
+    assert!(closed.borrow().is_empty());
+
+    drop(output);
+    assert_eq!(*closed.borrow(), vec![1]);
+}
+
+#[test]
+fn test_handle_closed_by_drop() {
+    let vm = vm(r#"
+    fn main(conn) {
+        let copy = conn;
+        drop(conn);
+        db::id(copy)
+    }
+    "#);
+
+    let closed = Rc::new(RefCell::new(Vec::new()));
+    let error = call(vm, connect(3, &closed)).unwrap_err();
+
+    assert_native_error(error, |kind| match kind {
+        VmErrorKind::HandleClosed { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    });
+
+    assert_eq!(*closed.borrow(), vec![3]);
+}
+
+#[test]
+fn test_handle_unexpected_type() {
+    let vm = vm(r#"
+    fn main(conn) {
+        db::id(conn)
+    }
+    "#);
+
+    let error = call(vm, Value::from(Handle::new(String::from("socket")))).unwrap_err();
+
+    assert_native_error(error, |kind| match kind {
+        VmErrorKind::UnexpectedHandle { actual, .. } => {
+            assert_eq!(*actual, "alloc::string::String");
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    });
+}
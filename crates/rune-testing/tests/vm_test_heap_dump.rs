@@ -0,0 +1,142 @@
+use runestick::{Context, GeneratorState, HeapDump, HeapNode, Item, Value, Vm};
+use std::sync::Arc;
+
+const SOURCE: &str = r#"
+fn main() {
+    let shared = [1, 2, 3];
+    let object = #{ a: shared, b: shared };
+    let closure = || object;
+    let cyclic = [];
+    cyclic.push(cyclic);
+    yield;
+}
+"#;
+
+fn dump() -> HeapDump {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let (unit, _) = rune::testing::compile(&context, SOURCE).unwrap();
+    let vm = Vm::new(context, Arc::new(unit));
+
+    let mut execution = vm.call(Item::of(&["main"]), ()).unwrap();
+    assert!(matches!(
+        execution.resume().unwrap(),
+        GeneratorState::Yielded(..)
+    ));
+
+    execution.vm().unwrap().heap_dump()
+}
+
+fn root<'a>(dump: &'a HeapDump, slot: usize) -> &'a HeapNode {
+    let root = dump
+        .roots
+        .iter()
+        .find(|root| matches!(root, runestick::HeapRoot::Stack { slot: s, .. } if *s == slot))
+        .expect("missing root");
+
+    dump.node(root.node()).unwrap()
+}
+
+#[test]
+fn test_heap_dump_graph() {
+    let dump = dump();
+
+    let shared = root(&dump, 0);
+    assert_eq!(shared.kind, "Vec");
+    // The stack and both fields of the object.
+    assert_eq!(shared.refs, 3);
+    assert!(shared.referents.is_empty());
+    assert!(shared.size > 0);
+
+    let object = root(&dump, 1);
+    assert_eq!(object.kind, "Object");
+    assert_eq!(object.referents, vec![shared.id]);
+
+    // The closure refers to the object through its environment.
+    let closure = root(&dump, 2);
+    assert_eq!(closure.kind, "Function");
+    assert_eq!(closure.referents.len(), 1);
+    let environment = dump.node(closure.referents[0]).unwrap();
+    assert_eq!(environment.kind, "Tuple");
+    assert_eq!(environment.referents, vec![object.id]);
+
+    let cyclic = root(&dump, 3);
+    assert_eq!(cyclic.referents, vec![cyclic.id]);
+
+    assert_eq!(dump.nodes.len(), 5);
+    assert_eq!(
+        dump.size(),
+        dump.nodes.iter().map(|node| node.size).sum::<usize>()
+    );
+}
+
+#[test]
+fn test_heap_dump_json() {
+    let dump = dump();
+    let json = serde_json::to_string(&dump).unwrap();
+    assert!(json.contains(r#""kind":"stack""#));
+
+    let back: HeapDump = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.roots, dump.roots);
+    assert_eq!(back.nodes, dump.nodes);
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn test_heap_dump_diff() {
+    let context = Arc::new(Context::with_default_modules().unwrap());
+    let (unit, _) = rune::testing::compile(
+        &context,
+        r#"
+        fn main() {
+            let retained = [];
+            let n = 0;
+
+            loop {
+                let data = [n, n];
+                retained.push(|| data);
+                n += 1;
+                yield;
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    let unit = Arc::new(unit);
+    let vm = Vm::new(context, unit.clone());
+    let mut execution = vm.call(Item::of(&["main"]), ()).unwrap();
+
+    execution.resume().unwrap();
+    let before = execution.vm().unwrap().heap_dump();
+
+    for _ in 0..3 {
+        // NB: resuming a yield expects a value to be pushed, like by
+        // `Generator::resume`.
+        execution.vm_mut().unwrap().stack_mut().push(Value::Unit);
+        execution.resume().unwrap();
+    }
+
+    let after = execution.vm().unwrap().heap_dump();
+    let diff = before.diff(&after);
+
+    let mut growth = diff
+        .growth()
+        .filter(|entry| entry.count != 0)
+        .map(|entry| (entry.kind.as_str(), entry.count))
+        .collect::<Vec<_>>();
+
+    growth.sort();
+    assert_eq!(growth, vec![("Function", 3), ("Tuple", 3), ("Vec", 3)]);
+
+    // Every retained value is attributed to an instruction in `main`.
+    for entry in diff.growth().filter(|entry| entry.count != 0) {
+        assert!(entry.debug_inst(&unit).is_some());
+    }
+
+    let printed = diff.display(&unit).to_string();
+    assert!(printed.contains("+3 Function at"), "{}", printed);
+    assert!(printed.contains("(main+"), "{}", printed);
+
+    // Nothing grows between identical dumps.
+    assert!(after.diff(&after).entries.is_empty());
+}
@@ -0,0 +1,99 @@
+use rune_testing::load_vm;
+use runestick::{Context, FromValue as _, HostBytes, HostStr, Item, Module, Value, Vm, VmError};
+
+fn vm(source: &str) -> Vm {
+    let mut module = Module::new(&["host"]);
+    module
+        .function(&["words"], |s: &str| s.split_whitespace().count())
+        .unwrap();
+    module
+        .function(&["checksum"], |bytes: &[u8]| {
+            bytes.iter().map(|b| *b as i64).sum::<i64>()
+        })
+        .unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+    load_vm(context, source).unwrap()
+}
+
+fn call(vm: &Vm, name: &str, args: (Value,)) -> Result<Value, VmError> {
+    vm.clone().call(Item::of(&[name]), args)?.complete()
+}
+
+#[test]
+fn test_host_str() {
+    let vm = vm(r#"
+    fn main(payload) {
+        (payload.len(), payload.starts_with("hello"), host::words(payload), `{payload}!`)
+    }
+    "#);
+
+    let payload = String::from("hello big world");
+
+    let output = HostStr::scope(&payload, |value| call(&vm, "main", (value,))).unwrap();
+    let output = <(usize, bool, usize, String)>::from_value(output).unwrap();
+
+    assert_eq!(output, (15, true, 3, String::from("hello big world!")));
+}
+
+#[test]
+fn test_host_bytes() {
+    let vm = vm(r#"
+    fn main(payload) {
+        (payload.len(), payload.get(1), host::checksum(payload), payload.to_bytes().into_vec())
+    }
+    "#);
+
+    let payload = vec![1u8, 2, 3];
+
+    let output = HostBytes::scope(&payload, |value| call(&vm, "main", (value,))).unwrap();
+    let output = <(usize, Option<u8>, i64, Vec<u8>)>::from_value(output).unwrap();
+
+    assert_eq!(output, (3, Some(2), 6, vec![1, 2, 3]));
+}
+
+#[test]
+fn test_host_str_revoked() {
+    let vm = vm(r#"
+    fn keep(payload) {
+        payload
+    }
+
+    fn len(payload) {
+        payload.len()
+    }
+    "#);
+
+    let payload = String::from("hello");
+
+    let kept = HostStr::scope(&payload, |value| {
+        let kept = call(&vm, "keep", (value,)).unwrap();
+        let len = call(&vm, "len", (kept.clone(),)).unwrap();
+        assert_eq!(usize::from_value(len).unwrap(), 5);
+        kept
+    });
+
+    drop(payload);
+    assert!(call(&vm, "len", (kept,)).is_err());
+}
+
+#[test]
+fn test_host_str_frozen() {
+    let vm = vm(r#"
+    fn main(payload) {
+        std::freeze(payload);
+        payload
+    }
+
+    fn len(payload) {
+        payload.len()
+    }
+    "#);
+
+    let payload = String::from("hello");
+    let kept = HostStr::scope(&payload, |value| call(&vm, "main", (value,)).unwrap());
+
+    drop(payload);
+    assert!(call(&vm, "len", (kept,)).is_err());
+}
@@ -0,0 +1,106 @@
+use rune_testing::*;
+
+#[test]
+fn test_grouped_imports() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            mod a {
+                mod b {
+                    fn one() { 1 }
+                    fn two() { 2 }
+                }
+
+                mod c {
+                    fn four() { 4 }
+                    fn five() { 5 }
+                }
+
+                fn three() { 3 }
+            }
+
+            use a::{three, b::{one, two}, c::{four, five},};
+
+            fn main() {
+                one() + two() + three() + four() + five()
+            }
+            "#
+        },
+        15,
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<i64>, Result<i64, i64>) => r#"
+            use std::{iter::*, option::Option, result::{Result}};
+
+            fn main() {
+                let n = 0;
+
+                for i in range(0, 2) {
+                    n += i;
+                }
+
+                (Option::Some(n), Result::Ok(2))
+            }
+            "#
+        },
+        (Some(1), Ok(2)),
+    };
+}
+
+#[test]
+fn test_wildcard_import_script_module() {
+    assert_eq! {
+        rune! {
+            i64 => r#"
+            mod a {
+                mod b {
+                    fn two() { 2 }
+                }
+
+                fn one() { 1 }
+            }
+
+            use a::*;
+            use a::b::*;
+
+            fn main() {
+                one() + two()
+            }
+            "#
+        },
+        3,
+    };
+}
+
+#[test]
+fn test_grouped_imports_errors() {
+    assert_compile_error! {
+        r#"use std::{option, result}::Option; fn main() {}"#,
+        UnsupportedUseGroup { span } => {
+            assert_eq!(span, Span::new(9, 25));
+        }
+    };
+
+    assert_compile_error! {
+        r#"use std::{option::Missing}; fn main() { Missing }"#,
+        MissingModule { span, .. } => {
+            assert_eq!(span, Span::new(10, 25));
+        }
+    };
+
+    assert_compile_error! {
+        r#"use std::{option::Option, missing::{a, b::*}}; fn main() {}"#,
+        MissingModule { span, .. } => {
+            assert_eq!(span, Span::new(39, 43));
+        }
+    };
+
+    assert_compile_error! {
+        r#"use std::missing::*; fn main() {}"#,
+        MissingModule { span, .. } => {
+            assert_eq!(span, Span::new(0, 19));
+        }
+    };
+}
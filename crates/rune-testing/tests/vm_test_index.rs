@@ -0,0 +1,126 @@
+use rune::Options;
+use rune_testing::*;
+use runestick::{Context, FromValue, Item, Vm, VmError, VmErrorKind};
+use std::sync::Arc;
+
+fn run_negative<T>(source: &str) -> Result<T, VmError>
+where
+    T: FromValue,
+{
+    let context = Context::with_default_modules().unwrap();
+
+    let mut options = Options::default();
+    options.parse_option("negative-indices").unwrap();
+
+    let (unit, _) = load_source(&context, &options, source).unwrap();
+
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    T::from_value(output)
+}
+
+#[test]
+fn test_get() {
+    assert_eq! {
+        rune! {
+            (Option<i64>, Option<i64>, i64, i64) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                (v.get(1), v.get(3), v.get_or(-1, 0), v.get_or(2, 0))
+            }
+            "#
+        },
+        (Some(2), None, 0, 3),
+    };
+
+    assert_eq! {
+        rune! {
+            (Option<char>, Option<char>, char) => r#"
+            fn main() {
+                let s = "hä!";
+                (s.get(1), s.get(2), s.get_or(10, '?'))
+            }
+            "#
+        },
+        (Some('ä'), None, '?'),
+    };
+}
+
+#[test]
+fn test_negative_indices() {
+    let output = run_negative::<(i64, i64, Vec<i64>, String, i64)>(
+        r#"
+        fn main() {
+            let v = [1, 2, 3];
+            let t = (4, 5);
+            (v[-1], t[-2], v[-2..], "hello"[-3..], v[0])
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(output, (3, 4, vec![2, 3], String::from("llo"), 1));
+
+    let error = run_negative::<i64>(r#"fn main() { [1, 2][-3] }"#).unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::MissingIndex { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_negative_indices_opt_in() {
+    assert_vm_error!(
+        r#"fn main() { [1, 2][-1] }"#,
+        MissingIndex { .. } => {}
+    );
+}
+
+#[test]
+fn test_index_get_fallback() {
+    assert_eq! {
+        rune! {
+            (i64, String, String, i64) => r#"
+            struct Config { name, values }
+
+            impl Config {
+                fn index_get(self, key) {
+                    self.values.get(key).unwrap_or(`missing {key}`)
+                }
+            }
+
+            fn main() {
+                let config = Config { name: "test", values: #{ "port": 8080 } };
+                (config["port"], config.name, config.host, config.values.port)
+            }
+            "#
+        },
+        (8080, String::from("test"), String::from("missing host"), 8080),
+    };
+}
+
+#[test]
+fn test_index_get_missing_field() {
+    assert_vm_error!(
+        r#"
+        struct Config { name }
+
+        fn main() {
+            let config = Config { name: "test" };
+            config["host"]
+        }
+        "#,
+        MissingField { field, .. } => {
+            assert_eq!(field, "host");
+        }
+    );
+}
+
+#[test]
+fn test_unsupported_index_get() {
+    assert_vm_error!(
+        r#"fn main() { 42["answer"] }"#,
+        UnsupportedIndexGet { .. } => {}
+    );
+}
@@ -0,0 +1,90 @@
+use rune_testing::load_source;
+use runestick::{Call, Context, Interface, InterfaceMismatch, Item, Unit};
+
+fn compile(source: &str) -> Unit {
+    let context = Context::with_default_modules().unwrap();
+    load_source(&context, &Default::default(), source)
+        .unwrap()
+        .0
+}
+
+fn plugin() -> Interface {
+    let mut interface = Interface::new();
+    interface
+        .function(&["name"], 0)
+        .async_function(&["handle"], 2)
+        .function(&["hooks", "on_load"], 1)
+        .function_with_call(&["events"], 0, Call::Generator);
+    interface
+}
+
+#[test]
+fn test_valid_interface() {
+    let unit = compile(
+        r#"
+        fn name() {
+            "plugin"
+        }
+
+        async fn handle(request, response) {
+        }
+
+        mod hooks {
+            fn on_load(config) {
+            }
+        }
+
+        fn events() {
+            yield 1;
+        }
+        "#,
+    );
+
+    assert!(unit.validate_interface(&plugin()).is_ok());
+}
+
+#[test]
+fn test_interface_mismatches() {
+    let unit = compile(
+        r#"
+        fn handle(request) {
+        }
+
+        mod hooks {
+            fn on_load(config) {
+            }
+        }
+
+        fn events() {
+            yield 1;
+        }
+        "#,
+    );
+
+    let error = unit.validate_interface(&plugin()).unwrap_err();
+
+    match error.mismatches() {
+        [InterfaceMismatch::MissingFunction { item }, InterfaceMismatch::ArgumentCount {
+            item: args_item,
+            expected: 2,
+            actual: 1,
+        }, InterfaceMismatch::Call {
+            item: call_item,
+            expected: Call::Async,
+            actual: Call::Immediate,
+        }] => {
+            assert_eq!(*item, Item::of(&["name"]));
+            assert_eq!(*args_item, Item::of(&["handle"]));
+            assert_eq!(*call_item, Item::of(&["handle"]));
+        }
+        mismatches => panic!("unexpected mismatches: {:?}", mismatches),
+    }
+
+    assert_eq!(
+        error.to_string(),
+        "unit does not implement the expected interface\n\
+        - missing function `name`\n\
+        - function `handle` takes 1 arguments, but 2 are expected\n\
+        - function `handle` is immediate, but async is expected"
+    );
+}
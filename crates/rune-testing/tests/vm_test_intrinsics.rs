@@ -0,0 +1,176 @@
+use rune::{CompileError, LoadErrorKind, Options};
+use rune_testing::load_source;
+use runestick::{
+    Context, FromValue as _, Hash, Inst, Item, Module, Unit, Value, Vm, VmError, VmErrorKind,
+};
+use std::sync::Arc;
+
+fn module() -> Module {
+    let mut module = Module::new(&["math"]);
+
+    module
+        .intrinsic(&["mul_add"], 3, |stack| {
+            let c = f64::from_value(stack.pop()?)?;
+            let b = f64::from_value(stack.pop()?)?;
+            let a = f64::from_value(stack.pop()?)?;
+            stack.push(Value::Float(a.mul_add(b, c)));
+            Ok(())
+        })
+        .unwrap();
+
+    module
+}
+
+fn context() -> Context {
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module()).unwrap();
+    context
+}
+
+fn load(context: &Context, options: &Options, source: &str) -> Unit {
+    load_source(context, options, source).unwrap().0
+}
+
+fn run(context: Context, unit: Unit) -> Result<Value, VmError> {
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+
+    vm.call(Item::of(&["main"]), ())
+        .and_then(|mut execution| execution.complete())
+        .map_err(|error| error.into_unwound().0)
+}
+
+fn intrinsic_calls(unit: &Unit) -> Vec<usize> {
+    unit.iter_instructions()
+        .filter_map(|inst| match inst {
+            Inst::CallIntrinsic { slot, .. } => Some(slot),
+            _ => None,
+        })
+        .collect()
+}
+
+const SOURCE: &str = r#"
+fn main() {
+    math::mul_add(2.0, 3.0, 1.0)
+}
+"#;
+
+#[test]
+fn test_intrinsic_call() {
+    let context = context();
+    let unit = load(&context, &Options::default(), SOURCE);
+
+    let hash = Hash::type_hash(&["math", "mul_add"]);
+    assert_eq!(
+        intrinsic_calls(&unit),
+        vec![context.intrinsic_slot(hash, 3).unwrap()]
+    );
+
+    let output = run(context, unit).unwrap();
+    assert_eq!(f64::from_value(output).unwrap(), 7.0);
+}
+
+#[test]
+fn test_intrinsics_disabled() {
+    let context = context();
+    let mut options = Options::default();
+    options.parse_option("intrinsics=false").unwrap();
+    let unit = load(&context, &options, SOURCE);
+
+    assert!(intrinsic_calls(&unit).is_empty());
+
+    let output = run(context, unit).unwrap();
+    assert_eq!(f64::from_value(output).unwrap(), 7.0);
+}
+
+#[test]
+fn test_intrinsic_function_pointer() {
+    let context = context();
+    let unit = load(
+        &context,
+        &Options::default(),
+        r#"
+        fn main() {
+            let f = math::mul_add;
+            f(2.0, 2.0, 2.0)
+        }
+        "#,
+    );
+
+    assert!(intrinsic_calls(&unit).is_empty());
+
+    let output = run(context, unit).unwrap();
+    assert_eq!(f64::from_value(output).unwrap(), 6.0);
+}
+
+#[test]
+fn test_intrinsic_bad_argument_count() {
+    let context = context();
+
+    let error = load_source(
+        &context,
+        &Options::default(),
+        r#"
+        fn main() {
+            math::mul_add(2.0, 3.0)
+        }
+        "#,
+    )
+    .unwrap_err();
+
+    match error.into_kind() {
+        LoadErrorKind::CompileError {
+            error:
+                CompileError::CallArgumentCount {
+                    expected: 3,
+                    actual: 2,
+                    ..
+                },
+            ..
+        } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    // NB: calls through function values are checked when they happen.
+    let unit = load(
+        &context,
+        &Options::default(),
+        r#"
+        fn main() {
+            let f = math::mul_add;
+            f(2.0, 3.0)
+        }
+        "#,
+    );
+
+    assert!(intrinsic_calls(&unit).is_empty());
+
+    match run(context, unit).unwrap_err().kind() {
+        VmErrorKind::BadArgumentCount {
+            actual: 2,
+            expected: 3,
+        } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_intrinsic_slot_mismatch() {
+    let unit = load(&context(), &Options::default(), SOURCE);
+
+    // NB: a context with another intrinsic installed first assigns a
+    // different slot, in which case the call goes through the regular path.
+    let mut other = Module::new(&["other"]);
+    other
+        .intrinsic(&["zero"], 0, |stack| {
+            stack.push(Value::Integer(0));
+            Ok(())
+        })
+        .unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&other).unwrap();
+    context.install(&module()).unwrap();
+
+    let output = run(context, unit).unwrap();
+    assert_eq!(f64::from_value(output).unwrap(), 7.0);
+}
@@ -0,0 +1,120 @@
+use rune_testing::*;
+use runestick::{Capture, Context, FromValue as _, Item, Output, Runtime};
+use std::sync::Arc;
+
+fn call_main(runtime: &Runtime) -> String {
+    let mut execution = runtime.vm().call(Item::of(&["main"]), ()).unwrap();
+    String::from_value(execution.complete().unwrap()).unwrap()
+}
+
+#[test]
+fn test_lifecycle_hooks() {
+    let stdout = Capture::new();
+    let output = Output::new(stdout.sink(), stdout.sink());
+    let context = Arc::new(Context::with_default_modules_and_output(output).unwrap());
+
+    let (first, _) = load_source(
+        &context,
+        &Default::default(),
+        r#"
+        fn __init__() {
+            println("init first");
+            std::event::on("version", || 1);
+        }
+
+        mod cache {
+            fn __init__() {
+                println("init cache");
+            }
+
+            fn __shutdown__() {
+                println("shutdown cache");
+            }
+        }
+
+        fn main() {
+            "first"
+        }
+        "#,
+    )
+    .unwrap();
+
+    let (second, _) = load_source(
+        &context,
+        &Default::default(),
+        r#"
+        fn __init__() {
+            println("init second");
+            std::event::off("version");
+            std::event::on("version", || 2);
+        }
+
+        fn __shutdown__() {
+            println("shutdown second");
+        }
+
+        fn main() {
+            "second"
+        }
+        "#,
+    )
+    .unwrap();
+
+    let mut runtime = Runtime::install(context, Arc::new(first)).unwrap();
+    assert_eq!(stdout.take(), "init first\ninit cache\n");
+
+    assert_eq!(call_main(&runtime), "first");
+
+    runtime.swap(Arc::new(second)).unwrap();
+    assert_eq!(stdout.take(), "shutdown cache\ninit second\n");
+
+    assert_eq!(call_main(&runtime), "second");
+
+    let output = runtime.events().fire("version", ()).unwrap();
+    assert_eq!(i64::from_value(output[0].clone()).unwrap(), 2);
+
+    drop(runtime);
+    assert_eq!(stdout.take(), "shutdown second\n");
+}
+
+#[test]
+fn test_failing_init() {
+    let stdout = Capture::new();
+    let output = Output::new(stdout.sink(), stdout.sink());
+    let context = Arc::new(Context::with_default_modules_and_output(output).unwrap());
+
+    let (unit, _) = load_source(
+        &context,
+        &Default::default(),
+        r#"
+        fn __init__() {
+            panic("failed to initialize");
+        }
+
+        fn __shutdown__() {
+            println("shutdown");
+        }
+        "#,
+    )
+    .unwrap();
+
+    assert!(Runtime::install(context, Arc::new(unit)).is_err());
+    assert_eq!(stdout.take(), "");
+}
+
+#[test]
+fn test_unsupported_lifecycle_hook() {
+    assert_compile_error! {
+        r#"fn __init__(config) {} fn main() {}"#,
+        UnsupportedLifecycleHook { span } => {
+            assert_eq!(span, Span::new(0, 22));
+        }
+    };
+
+    assert_compile_error! {
+        r#"async fn __shutdown__() {} fn main() {}"#,
+        UnsupportedLifecycleHook { span } => {
+            assert_eq!(span, Span::new(0, 26));
+        }
+    };
+}
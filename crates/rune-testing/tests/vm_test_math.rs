@@ -0,0 +1,67 @@
+use rune_testing::*;
+
+#[test]
+fn test_math_constants() {
+    assert_eq! {
+        rune! {
+            (f64, f64, f64) => r#"
+            use std::math;
+
+            fn main() {
+                (math::PI, std::math::E, math::TAU)
+            }
+            "#
+        },
+        (
+            std::f64::consts::PI,
+            std::f64::consts::E,
+            2.0 * std::f64::consts::PI,
+        ),
+    };
+}
+
+#[test]
+fn test_math_functions() {
+    assert_eq! {
+        rune! {
+            (f64, f64, f64, f64, f64, f64) => r#"
+            use std::math;
+
+            fn main() {
+                (
+                    math::sin(math::PI / 2.0),
+                    math::atan2(1.0, 1.0) * 4.0,
+                    math::ln(math::exp(2.0)),
+                    math::log10(1000.0),
+                    math::sqrt(16.0),
+                    math::cbrt(27.0),
+                )
+            }
+            "#
+        },
+        (1.0, std::f64::consts::PI, 2.0, 3.0, 4.0, 3.0),
+    };
+}
+
+#[test]
+fn test_math_format() {
+    assert_eq! {
+        rune! {
+            (String, String, f64) => r#"
+            use std::math;
+
+            fn main() {
+                (math::format(math::PI, 2), math::format(2.5, 0), float::parse("1.5")?)
+            }
+            "#
+        },
+        (String::from("3.14"), String::from("2"), 1.5),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { std::math::format(1.0, -1) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "precision must not be negative, but got `-1`");
+        }
+    );
+}
@@ -0,0 +1,93 @@
+use rune_testing::*;
+
+#[test]
+fn test_impl_native_type() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            use std::bytes::Bytes;
+
+            impl Bytes {
+                fn describe(self) {
+                    let len = self.len();
+                    `{len} bytes`
+                }
+            }
+
+            fn main() {
+                b"ab".describe()
+            }
+            "#
+        },
+        "2 bytes",
+    };
+}
+
+#[test]
+fn test_impl_prelude_type() {
+    assert_eq! {
+        rune! {
+            (i64, i64) => r#"
+            impl String {
+                fn size(self) {
+                    self.len() + 1
+                }
+            }
+
+            mod vec {
+                impl std::vec::Vec {
+                    fn size(self) {
+                        self.len() + 2
+                    }
+                }
+            }
+
+            fn main() {
+                ("abc".size(), [1, 2].size())
+            }
+            "#
+        },
+        (4, 4),
+    };
+}
+
+#[test]
+fn test_impl_native_type_missing_function() {
+    assert_vm_error!(
+        r#"
+        impl String {
+            fn size(self) {
+                self.len()
+            }
+        }
+
+        fn main() {
+            "abc".missing()
+        }
+        "#,
+        MissingInstanceFunction { .. } => {}
+    );
+}
+
+#[test]
+fn test_impl_native_type_conflict() {
+    assert_compile_error! {
+        r#"
+        use std::bytes::Bytes;
+
+        impl Bytes {
+            fn len(self) {
+                99
+            }
+        }
+
+        fn main() {
+            b"abc".len()
+        }
+        "#,
+        InstanceFunctionConflict { span, name, .. } => {
+            assert_eq!(span, Span::new(69, 72));
+            assert_eq!(name, "len");
+        }
+    };
+}
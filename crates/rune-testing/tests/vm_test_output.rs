@@ -0,0 +1,51 @@
+use rune_testing::load_vm;
+use runestick::{Capture, Context, Item, Output};
+
+fn run(output: Output, source: &str) {
+    let context = Context::with_default_modules_and_output(output).unwrap();
+    let vm = load_vm(context, source).unwrap();
+    vm.call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+}
+
+#[test]
+fn test_capture_output() {
+    let stdout = Capture::new();
+    let stderr = Capture::new();
+
+    run(
+        Output::new(stdout.sink(), stderr.sink()),
+        r#"
+        fn main() {
+            print("a");
+            println("b");
+            dbg([1]);
+            let x = dbg!(1 + 2);
+        }
+        "#,
+    );
+
+    assert_eq!(stdout.take(), "ab\n[\n    1,\n]\n");
+    assert_eq!(stderr.take(), "main:6 1 + 2 = 3\n");
+}
+
+#[test]
+fn test_callback_output() {
+    let lines = Capture::new();
+    let sink = lines.sink();
+
+    let tagged = runestick::Sink::from_fn(move |s| sink.write_str(&format!("[tenant] {}", s)));
+
+    run(
+        Output::new(tagged.clone(), tagged),
+        r#"
+        fn main() {
+            println("hello");
+        }
+        "#,
+    );
+
+    assert_eq!(lines.take(), "[tenant] hello\n");
+}
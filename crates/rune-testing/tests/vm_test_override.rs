@@ -0,0 +1,69 @@
+use rune_testing::*;
+use runestick::{Context, Hash, Item, Module, Vm, VmError};
+
+fn vm(source: &str) -> Vm {
+    let mut module = Module::new(&["time"]);
+    module.function(&["now"], || 1234i64).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    load_vm(context, source).unwrap()
+}
+
+fn run_main<T>(vm: Vm) -> Result<T, VmError>
+where
+    T: runestick::FromValue,
+{
+    T::from_value(block_on(
+        vm.call(Item::of(&["main"]), ())?.async_complete(),
+    )?)
+}
+
+#[test]
+fn test_override_native_function() {
+    let source = r#"fn main() { time::now() + 1 }"#;
+
+    assert_eq!(run_main::<i64>(vm(source)).unwrap(), 1235);
+
+    let mut vm = vm(source);
+    vm.override_function(&["time", "now"], || 42i64);
+    assert_eq!(run_main::<i64>(vm).unwrap(), 43);
+}
+
+#[test]
+fn test_override_unit_function() {
+    let source = r#"
+    fn fetch(url) { panic("network access in tests") }
+    fn main() { fetch("https://example.com").len() }
+    "#;
+
+    let mut vm = vm(source);
+    vm.override_function(Item::of(&["fetch"]), |url: &str| {
+        format!("stub for {}", url)
+    });
+    assert_eq!(run_main::<usize>(vm).unwrap(), 28);
+}
+
+#[test]
+fn test_override_by_hash() {
+    let mut vm = vm(r#"fn main() { std::string::String::from_str("hello") }"#);
+
+    vm.override_function(
+        Hash::type_hash(&["std", "string", "String", "from_str"]),
+        |_: &str| String::from("mocked"),
+    );
+
+    assert_eq!(run_main::<String>(vm).unwrap(), "mocked");
+}
+
+#[test]
+fn test_override_in_async_function() {
+    let mut vm = vm(r#"
+    async fn later() { time::now() }
+    async fn main() { later().await }
+    "#);
+
+    vm.override_function(&["time", "now"], || 7i64);
+    assert_eq!(run_main::<i64>(vm).unwrap(), 7);
+}
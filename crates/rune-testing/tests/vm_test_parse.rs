@@ -0,0 +1,157 @@
+use rune_testing::*;
+
+#[test]
+fn test_int_parse() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64) => r#"
+            fn main() {
+                (
+                    int::parse("42")?,
+                    int::parse("-42", 10)?,
+                    int::parse("ff", 16)?,
+                    int::parse("z", 36)?,
+                )
+            }
+            "#
+        },
+        (42, -42, 255, 35),
+    };
+
+    assert_eq! {
+        rune! {
+            (String, String) => r#"
+            fn main() {
+                let a = match int::parse("12a") { Err(e) => `{e}`, _ => "" };
+                let b = match int::parse("2", 2) { Err(e) => `{e}`, _ => "" };
+                (a, b)
+            }
+            "#
+        },
+        (
+            String::from("invalid digit found in string"),
+            String::from("invalid digit found in string"),
+        ),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { int::parse("1", 37) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "radix `37` is not in the range 2 to 36");
+        }
+    );
+}
+
+#[test]
+fn test_int_to_string() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String, String) => r#"
+            fn main() {
+                (
+                    255.to_string(),
+                    255.to_string(16),
+                    (-5).to_string(2),
+                    0.to_string(36),
+                    (-9223372036854775807 - 1).to_string(16),
+                )
+            }
+            "#
+        },
+        (
+            String::from("255"),
+            String::from("ff"),
+            String::from("-101"),
+            String::from("0"),
+            String::from("-8000000000000000"),
+        ),
+    };
+}
+
+#[test]
+fn test_float_parse() {
+    assert_eq! {
+        rune! {
+            (f64, String) => r#"
+            fn main() {
+                let error = match float::parse("1.x") { Err(e) => `{e}`, _ => "" };
+                (float::parse("-1.5e3")?, error)
+            }
+            "#
+        },
+        (-1500.0, String::from("invalid float literal")),
+    };
+}
+
+#[test]
+fn test_char_from_int() {
+    assert_eq! {
+        rune! {
+            (Option<char>, Option<char>, Option<char>) => r#"
+            fn main() {
+                (char::from_int(65), char::from_int(0xd800), char::from_int(-1))
+            }
+            "#
+        },
+        (Some('A'), None, None),
+    };
+}
+
+#[test]
+fn test_float_to_string() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String, String) => r#"
+            fn main() {
+                (0.1.to_string(), 1.0.to_string(), 1e21.to_string(), `{0.1 + 0.2}`, (-2.5e-8).to_string())
+            }
+            "#
+        },
+        (
+            String::from("0.1"),
+            String::from("1.0"),
+            String::from("1e21"),
+            String::from("0.30000000000000004"),
+            String::from("-2.5e-8"),
+        ),
+    };
+}
+
+#[test]
+fn test_float_format() {
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            fn main() {
+                (1234.5.format(2, "fixed"), 1234.5.format(2, "scientific"), 0.5.format(0, "fixed"))
+            }
+            "#
+        },
+        (
+            String::from("1234.50"),
+            String::from("1.23e3"),
+            String::from("0"),
+        ),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { 1.0.format(2, "hex") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "unsupported format mode `hex`, expected `fixed` or `scientific`");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 1.5.format(1000000, "fixed") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "precision must be at most `65535`, but got `1000000`");
+        }
+    );
+
+    assert_vm_error!(
+        r#"fn main() { 1.5.format(1000000, "scientific") }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "precision must be at most `65535`, but got `1000000`");
+        }
+    );
+}
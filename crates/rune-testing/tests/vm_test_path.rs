@@ -0,0 +1,92 @@
+use rune_testing::*;
+
+#[test]
+fn test_path_manipulation() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::path::Path;
+
+            fn main() {
+                let path = Path::new("src").join("lib").join(Path::new("mod.rs"));
+
+                [
+                    `{path}`,
+                    `{path.parent()?}`,
+                    path.file_name()?,
+                    path.extension()?,
+                    `{path.with_extension("txt")}`,
+                    `{Path::new("a").join("/etc")}`,
+                ]
+            }
+            "#
+        },
+        vec!["src/lib/mod.rs", "src/lib", "mod.rs", "rs", "src/lib/mod.txt", "/etc"],
+    };
+
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool) => r#"
+            use std::path::Path;
+
+            fn main() {
+                let root = match Path::new("/").parent() { None => true, _ => false };
+                let plain = match Path::new("a").extension() { None => true, _ => false };
+                (root, plain, Path::new("/usr").is_absolute(), Path::new("usr").is_absolute())
+            }
+            "#
+        },
+        (true, true, true, false),
+    };
+}
+
+#[test]
+fn test_path_normalize() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::path::Path;
+
+            fn main() {
+                let paths = ["a/./b/../c", "/../a/..", "../a/../../b", "./", "a/b/../../.."];
+                let out = [];
+
+                for path in paths {
+                    out.push(`{Path::new(path).normalize()}`);
+                }
+
+                out
+            }
+            "#
+        },
+        vec!["a/c", "/", "../../b", ".", ".."],
+    };
+}
+
+#[test]
+fn test_path_matches_glob() {
+    assert_eq! {
+        rune! {
+            Vec<bool> => r#"
+            use std::path::Path;
+
+            fn main() {
+                let path = Path::new("src/modules/vec.rs");
+
+                [
+                    path.matches_glob("src/*/*.rs"),
+                    path.matches_glob("src/*.rs"),
+                    path.matches_glob("**/*.rs"),
+                    path.matches_glob("src/**/vec.rs"),
+                    path.matches_glob("src/**/modules/**/vec.rs"),
+                    path.matches_glob("src/modules/?ec.[qr]s"),
+                    path.matches_glob("src/modules/[!v]ec.rs"),
+                    path.matches_glob("src/modules/[a-z]ec.rs"),
+                    path.matches_glob("**/*.toml"),
+                ]
+            }
+            "#
+        },
+        vec![true, false, true, true, true, true, false, true, false],
+    };
+}
@@ -0,0 +1,189 @@
+use rune_testing::*;
+use runestick::{
+    CallAction, CallHook, CallInfo, Context, Item, Shared, Value, VmError, VmErrorKind, VmHaltInfo,
+    VmPool,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn pool(capacity: usize) -> VmPool {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn add(a, b) { a + b }
+        fn twice(n) { add(n, n) }
+        fn fail() { panic("failed") }
+        async fn later(n) { n * 2 }
+
+        struct Guard { log }
+
+        impl Drop for Guard {
+            fn drop(self) {
+                self.log.push("dropped");
+            }
+        }
+
+        fn guard(log) { Guard { log } }
+        fn listen() { std::event::on("tick", || 1) }
+
+        let CACHE = [];
+        fn cache(n) { CACHE.push(n); CACHE.len() }
+        "#,
+    )
+    .unwrap();
+
+    VmPool::new(Arc::new(context), Arc::new(unit), capacity)
+}
+
+#[test]
+fn test_pool_reuses_vms() {
+    let pool = pool(2);
+
+    for n in 0..100i64 {
+        let output = pool.call(Item::of(&["add"]), (n, 1i64)).unwrap();
+        assert_eq!(output.into_integer().unwrap(), n + 1);
+    }
+
+    assert_eq!(pool.created(), 1);
+    assert_eq!(pool.idle(), 1);
+
+    {
+        let mut a = pool.get();
+        let mut b = pool.get();
+        assert_eq!(pool.created(), 2);
+
+        let a = a.call(Item::of(&["add"]), (1i64, 2i64)).unwrap();
+        let b = b.call(Item::of(&["add"]), (3i64, 4i64)).unwrap();
+        assert_eq!(a.into_integer().unwrap(), 3);
+        assert_eq!(b.into_integer().unwrap(), 7);
+    }
+
+    assert_eq!(pool.idle(), 2);
+
+    {
+        let _a = pool.get();
+        let _b = pool.get();
+        let _c = pool.get();
+    }
+
+    // Only as many virtual machines as the pool has capacity for are kept.
+    assert_eq!(pool.created(), 3);
+    assert_eq!(pool.idle(), 2);
+}
+
+#[test]
+fn test_pool_recovers_from_errors() {
+    let pool = pool(1);
+    let mut vm = pool.get();
+
+    assert!(vm.call(Item::of(&["fail"]), ()).is_err());
+    assert!(vm.call(Item::of(&["missing"]), ()).is_err());
+
+    let output = vm.call(Item::of(&["add"]), (1i64, 2i64)).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 3);
+    assert!(vm.stack().is_empty());
+
+    drop(vm);
+    assert_eq!(pool.created(), 1);
+}
+
+#[test]
+fn test_pool_setup_and_async() {
+    struct Count(Arc<AtomicUsize>);
+
+    impl CallHook for Count {
+        fn before(&self, _: &CallInfo<'_>) -> Result<CallAction, VmError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(CallAction::Continue)
+        }
+    }
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let c = calls.clone();
+    let pool = pool(1).with_setup(move |vm| vm.add_call_hook(Count(c.clone())));
+
+    let mut vm = pool.get();
+    // NB: async functions called from the host are run to completion.
+    let output = block_on(vm.async_call(Item::of(&["later"]), (21i64,))).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 42);
+    drop(vm);
+
+    // The hook installed by the setup is kept when the vm is reused.
+    let mut vm = pool.get();
+    let output = vm.call(Item::of(&["twice"]), (2i64,)).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 4);
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(pool.created(), 1);
+}
+
+#[test]
+fn test_pool_finalizes_on_release() {
+    let pool = pool(1);
+    let log = Shared::new(Vec::new());
+
+    let mut vm = pool.get();
+    let guard = vm
+        .call(Item::of(&["guard"]), (Value::Vec(log.clone()),))
+        .unwrap();
+    drop(guard);
+    assert!(log.borrow_ref().unwrap().is_empty());
+
+    drop(vm);
+    assert_eq!(log.borrow_ref().unwrap().len(), 1);
+    assert_eq!(pool.idle(), 1);
+}
+
+#[test]
+fn test_pool_isolates_events() {
+    let pool = pool(1);
+
+    let mut vm = pool.get();
+    vm.call(Item::of(&["listen"]), ()).unwrap();
+    assert_eq!(vm.events().names().unwrap(), vec![String::from("tick")]);
+    drop(vm);
+
+    let vm = pool.get();
+    assert!(vm.events().names().unwrap().is_empty());
+    assert_eq!(pool.created(), 1);
+}
+
+#[test]
+fn test_pool_isolates_cancellation() {
+    let pool = pool(1);
+
+    let mut vm = pool.get();
+    vm.cancellation_handle().cancel();
+
+    let error = vm.call(Item::of(&["twice"]), (1i64,)).unwrap_err();
+
+    assert!(matches!(
+        error.kind().into_unwound_ref().0,
+        VmErrorKind::Halted {
+            halt: VmHaltInfo::Cancelled
+        }
+    ));
+
+    drop(vm);
+
+    let output = pool.call(Item::of(&["twice"]), (2i64,)).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 4);
+    assert_eq!(pool.created(), 1);
+}
+
+#[test]
+fn test_pool_isolates_statics() {
+    let pool = pool(1);
+
+    for n in 0..3i64 {
+        let output = pool.call(Item::of(&["cache"]), (n,)).unwrap();
+        assert_eq!(output.into_integer().unwrap(), 1);
+    }
+
+    // Statics are kept between calls in the same checkout.
+    let mut vm = pool.get();
+    vm.call(Item::of(&["cache"]), (1i64,)).unwrap();
+    let output = vm.call(Item::of(&["cache"]), (2i64,)).unwrap();
+    assert_eq!(output.into_integer().unwrap(), 2);
+    assert_eq!(pool.created(), 1);
+}
@@ -0,0 +1,130 @@
+use rune_testing::*;
+
+fn failure(source: &str) -> String {
+    assert_vm_error!(source, Panic { reason } => reason.to_string())
+}
+
+#[test]
+fn test_proptest_passing() {
+    assert_eq! {
+        rune!(i64 => r#"
+        use std::proptest;
+
+        fn reverse(v) {
+            let out = [];
+
+            for n in v.iter().rev() {
+                out.push(n);
+            }
+
+            out
+        }
+
+        fn main() {
+            let cases = [];
+
+            let property = |v| {
+                cases.push(v);
+                reverse(reverse(v)) == v
+            };
+
+            proptest::check(property, [proptest::vec(proptest::int(-10, 10), 20)]);
+            cases.len()
+        }
+        "#),
+        100,
+    };
+}
+
+#[test]
+fn test_proptest_shrink_int() {
+    let reason = failure(
+        r#"
+        use std::proptest;
+
+        fn main() {
+            proptest::check(|n| n < 10, [proptest::int(0, 1000)]);
+        }
+        "#,
+    );
+
+    assert!(reason.starts_with("property failed after "), "{}", reason);
+    assert!(reason.contains(": returned `false`\n"), "{}", reason);
+    assert!(reason.ends_with("): (10)"), "{}", reason);
+}
+
+#[test]
+fn test_proptest_shrink_vec_and_string() {
+    let reason = failure(
+        r#"
+        use std::proptest;
+
+        fn main() {
+            let property = |v, s| v.len() < 2 || s.len() < 3;
+            let gens = [proptest::vec(proptest::int(0, 100), 10), proptest::string(10)];
+            proptest::check_with(property, gens, #{seed: 7, cases: 1000});
+        }
+        "#,
+    );
+
+    assert!(reason.contains("with seed 7:"), "{}", reason);
+    assert!(reason.ends_with("): ([0, 0], \"aaa\")"), "{}", reason);
+}
+
+#[test]
+fn test_proptest_failing_assert() {
+    let reason = failure(
+        r#"
+        use std::proptest;
+
+        fn main() {
+            let property = |o| {
+                std::test::assert(o.a <= o.b, "a is at most b");
+            };
+
+            let gen = proptest::object(#{a: proptest::int(0, 10), b: proptest::int(0, 10)});
+            proptest::check(property, [gen]);
+        }
+        "#,
+    );
+
+    assert!(
+        reason.contains("assertion failed `a is at most b`"),
+        "{}",
+        reason
+    );
+    assert!(reason.ends_with("): (#{a: 1, b: 0})"), "{}", reason);
+}
+
+#[test]
+fn test_proptest_seed() {
+    assert! {
+        rune!(bool => r#"
+        use std::proptest;
+
+        fn sample(seed) {
+            let out = [];
+            let gen = proptest::one_of([proptest::bool(), proptest::float(0.0, 1.0), proptest::any()]);
+            proptest::check_with(|v| { out.push(v); true }, [gen], #{seed: seed, cases: 20, max_depth: 2});
+            out
+        }
+
+        fn main() {
+            sample(42) == sample(42) && sample(42) != sample(43)
+        }
+        "#)
+    };
+}
+
+#[test]
+fn test_proptest_bad_option() {
+    let reason = failure(
+        r#"
+        fn main() {
+            std::proptest::check_with(|| true, [], #{runs: 10});
+        }
+        "#,
+    );
+
+    assert_eq!(reason, "unsupported option `runs`");
+}
@@ -0,0 +1,87 @@
+use rune::{CompileError, LoadError, LoadErrorKind};
+use rune_testing::load_source;
+use runestick::{Context, FromValue as _, Item, Unit, Vm, VmErrorKind};
+use std::sync::Arc;
+
+fn load(context: &Context, source: &str) -> Result<Arc<Unit>, LoadError> {
+    let (unit, _) = load_source(context, &Default::default(), source)?;
+    Ok(Arc::new(unit))
+}
+
+fn sandbox(context: &Context) -> Context {
+    context.restrict(vec![
+        Item::of(&["std", "string"]),
+        Item::of(&["std", "option"]),
+    ])
+}
+
+#[test]
+fn test_sandbox_allowed() {
+    let context = Context::with_default_modules().unwrap();
+    let sandbox = Arc::new(sandbox(&context));
+
+    let unit = load(
+        &*sandbox,
+        r#"
+        fn main() {
+            let s = String::new();
+            s.push_str("hello");
+
+            match Some(s.len()) {
+                Some(n) => n,
+                None => 0,
+            }
+        }
+        "#,
+    )
+    .unwrap();
+
+    let vm = Vm::new(sandbox, unit);
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(i64::from_value(output).unwrap(), 5);
+}
+
+#[test]
+fn test_sandbox_link_time() {
+    let context = Context::with_default_modules().unwrap();
+    let sandbox = sandbox(&context);
+
+    let sources = &[
+        r#"fn main() { std::bytes::Bytes::new() }"#,
+        r#"fn main() { print("escaped") }"#,
+    ];
+
+    for source in sources {
+        assert!(load(&context, source).is_ok());
+
+        match load(&sandbox, source).unwrap_err().into_kind() {
+            LoadErrorKind::CompileError {
+                error: CompileError::MissingFunction { .. },
+                ..
+            } => (),
+            kind => panic!("unexpected error: {:?}", kind),
+        }
+    }
+}
+
+#[test]
+fn test_sandbox_call_time() {
+    let context = Context::with_default_modules().unwrap();
+    let unit = load(&context, r#"fn main() { std::bytes::Bytes::new() }"#).unwrap();
+
+    let vm = Vm::new(Arc::new(sandbox(&context)), unit);
+    let error = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap_err();
+
+    match error.into_unwound().0.kind() {
+        VmErrorKind::MissingFunction { .. } => (),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
@@ -0,0 +1,47 @@
+use rune_testing::*;
+use std::fs;
+use std::panic;
+
+#[test]
+fn test_snapshot_object() {
+    let value = rune!(Value => r#"
+    fn main() {
+        let items = [];
+
+        for n in [0, 1, 2] {
+            items.push(#{id: n, name: `item {n}`, even: n % 2 == 0});
+        }
+
+        #{count: items.len(), items: items, cursor: None}
+    }
+    "#);
+
+    assert_snapshot!(value);
+}
+
+#[test]
+fn test_snapshot_named() {
+    assert_snapshot!(
+        "tuple",
+        rune!(Value => r#"fn main() { (1, "two", 3.5, Some('4')) }"#)
+    );
+    assert_snapshot!("bytes", rune!(Value => r#"fn main() { b"rune" }"#));
+}
+
+#[test]
+fn test_snapshot_mismatch() {
+    // NB: the snapshot is overwritten instead of compared in update mode.
+    if std::env::var(UPDATE_SNAPSHOTS).map_or(false, |value| value == "1") {
+        return;
+    }
+
+    let path = std::env::temp_dir().join("rune-testing-snapshot-mismatch.snap");
+    fs::write(&path, "[\n  1,\n  2\n]\n").unwrap();
+
+    assert_snapshot_at(&path, &vec![1, 2]);
+    let result = panic::catch_unwind(|| assert_snapshot_at(&path, &vec![1, 3]));
+    assert!(result.is_err());
+
+    let result = panic::catch_unwind(|| assert_snapshot_at(&path.with_extension("missing"), &1));
+    assert!(result.is_err());
+}
@@ -0,0 +1,91 @@
+use rune_testing::*;
+
+#[test]
+fn test_render() {
+    assert_eq! {
+        rune! {
+            String => r#"
+            use std::template;
+
+            struct User { name, admin }
+
+            fn main() {
+                let context = #{
+                    user: User { name: "<Ada>", admin: true },
+                    items: [#{ name: "tea", price: 3 }, #{ name: "cake", price: 5 }],
+                };
+
+                let source = "Hi {{ user.name | escape }}!{% if user.admin %} (admin){% end %}\n";
+                let source = source + "{% for item in items %}{{ loop.index }}: {{ item.name | upper }} {{ item.price }}{% if loop.last %}.{% else %}, {% end %}{% end %}";
+
+                template::render(source, context)?
+            }
+            "#
+        },
+        "Hi &lt;Ada&gt;! (admin)\n0: TEA 3, 1: CAKE 5.",
+    };
+}
+
+#[test]
+fn test_template_compile() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::template::Template;
+
+            fn main() {
+                let template = Template::compile("{% if items %}{{ items.0 }}{% else %}none{% end %}")?;
+                [template.render(#{ items: [1, 2] })?, template.render(#{ items: [] })?]
+            }
+            "#
+        },
+        vec!["1", "none"],
+    };
+}
+
+#[test]
+fn test_template_errors() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            use std::template;
+
+            fn error(result) {
+                match result {
+                    Err(error) => `{error}`,
+                    Ok(..) => "ok",
+                }
+            }
+
+            fn main() {
+                let context = #{ name: "Ada", age: 36, items: [1] };
+
+                [
+                    error(template::render("{{ name", context)),
+                    error(template::render("{% if name %}yes", context)),
+                    error(template::render("{% end %}", context)),
+                    error(template::render("{% while name %}{% end %}", context)),
+                    error(template::render("{{ name | reverse }}", context)),
+                    error(template::render("{{ a + b }}", context)),
+                    error(template::render("Hi {{ user.name }}", context)),
+                    error(template::render("{{ items }}", context)),
+                    error(template::render("{% for c in name %}{% end %}", context)),
+                    error(template::render("{{ age | upper }}", context)),
+                ]
+            }
+            "#
+        },
+        vec![
+            "unclosed value at byte 0",
+            "unclosed if at byte 0",
+            "unexpected tag `end` at byte 0",
+            "unknown tag `while name` at byte 0",
+            "unknown filter `reverse` at byte 2",
+            "invalid path `a + b` at byte 2",
+            "missing value `user.name` at byte 5",
+            "cannot render value of type `Vec` at byte 2",
+            "cannot iterate over value of type `String` at byte 2",
+            "filter `upper` can only be used on strings at byte 2",
+        ],
+    };
+}
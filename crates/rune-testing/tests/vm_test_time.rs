@@ -0,0 +1,135 @@
+use rune_testing::*;
+use runestick::{Duration, Timestamp};
+
+#[test]
+fn test_duration_arithmetic() {
+    assert_eq! {
+        rune! {
+            (String, String, String, String, i64) => r#"
+            use std::time::Duration;
+
+            fn main() {
+                let a = Duration::parse("5m30s")?;
+                let b = Duration::from_millis(250);
+                let c = a;
+                c += Duration::from_secs(30);
+                (`{a + b}`, `{a - b}`, `{a * 2}`, `{c / 4}`, b.as_millis())
+            }
+            "#
+        },
+        (
+            String::from("5m30s250ms"),
+            String::from("5m29s750ms"),
+            String::from("11m"),
+            String::from("1m30s"),
+            250,
+        ),
+    };
+}
+
+#[test]
+fn test_timestamp_arithmetic() {
+    assert_eq! {
+        rune! {
+            (String, String, String, i64) => r#"
+            use std::time::Duration;
+            use std::time::Timestamp;
+
+            fn main() {
+                let start = Timestamp::parse("2020-09-01T12:30:00+02:00")?;
+                let end = start + Duration::parse("1h15m")?;
+                let earlier = start - Duration::parse("1d")?;
+                (`{end}`, `{earlier}`, `{end - earlier}`, start.as_millis())
+            }
+            "#
+        },
+        (
+            String::from("2020-09-01T11:45:00Z"),
+            String::from("2020-08-31T10:30:00Z"),
+            String::from("1d1h15m"),
+            1_598_956_200_000,
+        ),
+    };
+}
+
+#[test]
+fn test_time_comparison() {
+    assert_eq! {
+        rune! {
+            (bool, bool, bool, bool, bool) => r#"
+            use std::time::Duration;
+            use std::time::Timestamp;
+
+            fn main() {
+                let a = Duration::parse("90s")?;
+                let b = Duration::parse("1m30s")?;
+                let c = Timestamp::from_millis(0);
+                let d = Timestamp::parse("1970-01-01T00:00:00.001Z")?;
+                (a == b, a < b, a >= b, c < d, c != d)
+            }
+            "#
+        },
+        (true, false, true, true, true),
+    };
+}
+
+#[test]
+fn test_timestamp_format() {
+    assert_eq! {
+        rune! {
+            (String, String) => r#"
+            use std::time::Timestamp;
+
+            fn main() {
+                let t = Timestamp::from_millis(1598963400250);
+                (t.format("%d/%m/%Y %H:%M:%S.%f")?, t.format("%s%%")?)
+            }
+            "#
+        },
+        (
+            String::from("01/09/2020 12:30:00.250"),
+            String::from("1598963400%"),
+        ),
+    };
+}
+
+#[test]
+fn test_time_conversions() {
+    let (duration, timestamp) = rune! {
+        (Duration, Timestamp) => r#"
+        use std::time::Duration;
+        use std::time::Timestamp;
+
+        fn main() {
+            (Duration::parse("-2s")?, Timestamp::from_millis(1000))
+        }
+        "#
+    };
+
+    assert_eq!(duration, Duration::from_secs(-2).unwrap());
+    assert_eq!(timestamp, "1970-01-01T00:00:01Z".parse().unwrap());
+}
+
+#[test]
+fn test_time_errors() {
+    assert_eq! {
+        rune! {
+            (String, String, String) => r#"
+            use std::time::Duration;
+            use std::time::Timestamp;
+
+            fn main() {
+                let a = match Duration::parse("5 minutes") { Err(e) => `{e}`, _ => "" };
+                let b = match Timestamp::parse("2021-02-29") { Err(e) => `{e}`, _ => "" };
+                let c = match Timestamp::now().format("%Q") { Err(e) => `{e}`, _ => "" };
+                (a, b, c)
+            }
+            "#
+        },
+        (
+            String::from("invalid duration `5 minutes`"),
+            String::from("invalid timestamp `2021-02-29`"),
+            String::from("unsupported format specifier `%Q`"),
+        ),
+    };
+}
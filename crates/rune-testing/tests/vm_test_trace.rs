@@ -0,0 +1,83 @@
+use rune_testing::load_vm;
+use runestick::{Context, Inst, Item, TraceBuffer, TraceConfig, Vm, VmErrorKind};
+use std::sync::{Arc, Mutex};
+
+const SOURCE: &str = r#"
+fn add(a, b) { a + b }
+fn main() { add(1, 2) + add(3, 4) }
+"#;
+
+fn vm() -> Vm {
+    load_vm(Context::with_default_modules().unwrap(), SOURCE).unwrap()
+}
+
+#[test]
+fn test_trace_callback() {
+    let mut vm = vm();
+    let instructions = vm.unit().iter_instructions().count();
+    let traced = Arc::new(Mutex::new(Vec::new()));
+
+    let t = traced.clone();
+    vm.trace(TraceConfig::callback(move |entry| {
+        t.lock().unwrap().push(entry.clone())
+    }))
+    .unwrap();
+
+    let output = vm
+        .call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+    assert_eq!(output.into_integer().unwrap(), 10);
+
+    let traced = traced.lock().unwrap();
+    // Both calls to `add` are traced.
+    assert!(traced.len() > instructions);
+    assert_eq!(
+        traced
+            .iter()
+            .filter(|e| matches!(e.inst, Inst::Add))
+            .count(),
+        3
+    );
+    assert!(traced
+        .iter()
+        .any(|e| matches!(e.inst, Inst::Add) && e.top.as_deref() == Some("4")));
+}
+
+#[test]
+fn test_trace_filter_and_ring_buffer() {
+    let mut vm = vm();
+    let unit = vm.unit().clone();
+    let buffer = TraceBuffer::new(3);
+
+    vm.trace(TraceConfig::buffer(buffer.clone()).function(Item::of(&["add"])))
+        .unwrap();
+
+    vm.call(Item::of(&["main"]), ())
+        .unwrap()
+        .complete()
+        .unwrap();
+
+    let entries = buffer.entries();
+    assert_eq!(entries.len(), 3);
+    assert!(buffer.dropped() > 0);
+
+    // The buffer ends with the return of the last call to `add`.
+    assert!(matches!(entries[2].inst, Inst::Return));
+    assert_eq!(entries[2].top.as_deref(), Some("7"));
+
+    let printed = entries[2].display(&unit).to_string();
+    assert!(printed.contains("add+"), "{}", printed);
+    assert!(printed.ends_with("return [7]"), "{}", printed);
+}
+
+#[test]
+fn test_trace_missing_function() {
+    let mut vm = vm();
+    let error = vm
+        .trace(TraceConfig::buffer(TraceBuffer::new(16)).function(Item::of(&["missing"])))
+        .unwrap_err();
+
+    assert!(matches!(error.kind(), VmErrorKind::MissingFunction { .. }));
+}
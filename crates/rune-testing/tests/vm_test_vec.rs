@@ -0,0 +1,197 @@
+use rune_testing::*;
+
+#[test]
+fn test_vec_insert_remove() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, i64) => r#"
+            fn main() {
+                let v = [1, 2, 3];
+                v.insert(0, 0)?;
+                v.insert(4, 4)?;
+                let removed = v.remove(2)?;
+                v.swap(0, 3)?;
+                (v, removed)
+            }
+            "#
+        },
+        (vec![4, 1, 3, 0], 2),
+    };
+}
+
+#[test]
+fn test_vec_drain_splice() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let v = [1, 2, 3, 4, 5];
+                let drained = v.drain(1..3);
+                let replaced = v.splice(..1, [7, 8, 9]);
+                (v, drained, replaced)
+            }
+            "#
+        },
+        (vec![7, 8, 9, 4, 5], vec![2, 3], vec![1]),
+    };
+}
+
+#[test]
+fn test_vec_index_out_of_bounds() {
+    assert_eq! {
+        rune! {
+            (String, String, bool, Vec<i64>) => r#"
+            fn main() {
+                let v = [1, 2];
+
+                let remove = match v.remove(2) {
+                    Err(error) => `{error}`,
+                    _ => "removed",
+                };
+
+                let insert = match v.insert(-1, 0) {
+                    Err(error) => `{error}`,
+                    _ => "inserted",
+                };
+
+                (remove, insert, v.swap(0, 5).is_err(), v)
+            }
+            "#
+        },
+        (
+            String::from("index `2` is out of bounds for a vector of length 2"),
+            String::from("index `-1` is out of bounds for a vector of length 2"),
+            true,
+            vec![1, 2],
+        ),
+    };
+}
+
+#[test]
+fn test_vec_sort_by() {
+    assert_eq! {
+        rune! {
+            Vec<(i64, String)> => r#"
+            fn main() {
+                let v = [(2, "a"), (1, "b"), (2, "c"), (1, "d")];
+                v.sort_by(|a, b| a.0 - b.0);
+                v
+            }
+            "#
+        },
+        vec![
+            (1, String::from("b")),
+            (1, String::from("d")),
+            (2, String::from("a")),
+            (2, String::from("c")),
+        ],
+    };
+}
+
+#[test]
+fn test_vec_sort_by_reentrant() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>) => r#"
+            fn main() {
+                let inner = [3, 1, 2];
+                let v = [#{n: 2, all: inner}, #{n: 1, all: inner}];
+
+                v.sort_by(|a, b| {
+                    a.all.sort_by(|a, b| b - a);
+                    a.n - b.n + a.all.len() - 3
+                });
+
+                ([v[0].n, v[1].n], inner)
+            }
+            "#
+        },
+        (vec![1, 2], vec![3, 2, 1]),
+    };
+}
+
+#[test]
+fn test_vec_sort_by_error() {
+    assert_vm_error!(
+        r#"
+        fn main() {
+            let v = [3, 1, 2];
+            v.sort_by(|a, b| if a == 1 { panic("bad comparison") } else { a - b });
+            v
+        }
+        "#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "bad comparison");
+        }
+    );
+}
+
+#[test]
+fn test_vec_binary_search_by() {
+    assert_eq! {
+        rune! {
+            (Result<i64, i64>, Result<i64, i64>, Result<i64, i64>) => r#"
+            fn main() {
+                let v = [1, 3, 5, 7];
+                let found = v.binary_search_by(|probe| probe - 5);
+                let missing = v.binary_search_by(|probe| probe - 4);
+                let last = v.binary_search_by(|probe| probe - 9);
+                (found, missing, last)
+            }
+            "#
+        },
+        (Ok(2), Err(2), Err(4)),
+    };
+}
+
+#[test]
+fn test_vec_group_by() {
+    assert_eq! {
+        rune! {
+            (Vec<i64>, Vec<i64>, Vec<String>) => r#"
+            fn main() {
+                let by_parity = [1, 2, 3, 4, 5].group_by(|n| n % 2 == 0);
+                let by_initial = ["apple", "bean", "avocado"].group_by(|s| s[0..1]);
+                (by_parity["false"], by_parity["true"], by_initial["a"])
+            }
+            "#
+        },
+        (vec![1, 3, 5], vec![2, 4], vec![String::from("apple"), String::from("avocado")]),
+    };
+}
+
+#[test]
+fn test_vec_chunks_windows() {
+    assert_eq! {
+        rune! {
+            (Vec<Vec<i64>>, Vec<Vec<i64>>) => r#"
+            fn main() {
+                let v = [1, 2, 3, 4, 5];
+                let chunks = [];
+                let windows = [];
+
+                for chunk in v.chunks(2) {
+                    chunks.push(chunk);
+                }
+
+                for window in v.windows(3) {
+                    windows.push(window);
+                }
+
+                (chunks, windows)
+            }
+            "#
+        },
+        (
+            vec![vec![1, 2], vec![3, 4], vec![5]],
+            vec![vec![1, 2, 3], vec![2, 3, 4], vec![3, 4, 5]],
+        ),
+    };
+
+    assert_vm_error!(
+        r#"fn main() { [1, 2].chunks(0) }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "size must be greater than zero, but got `0`");
+        }
+    );
+}
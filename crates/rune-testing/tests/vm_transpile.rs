@@ -0,0 +1,113 @@
+use rune::TranspileError;
+use runestick::{Context, Item, Vm, VmErrorKind};
+use std::sync::Arc;
+
+/// The output of transpiling [FUNCTIONS], which is compiled in as native
+/// functions.
+mod transpiled {
+    include!("transpiled/functions.rs");
+}
+
+const FUNCTIONS: &str = r#"
+fn fib(n) { if n <= 1 { n } else { fib(n - 1) + fib(n - 2) } }
+fn sum(n) { let s = 0; let i = 0; while i < n { s += i; i += 1; } s }
+fn f(a, b) { let c = a * 2.0; if c > b && !(b < 0.0) { return c; } b }
+"#;
+
+const MAIN: &str = r#"
+fn main() { `{fib(15)} {sum(100)} {f(3.0, 1.0)} {f(1.0, 3.0)} {f(3.0, -1.0)}` }
+"#;
+
+fn items() -> Vec<Item> {
+    vec![Item::of(&["fib"]), Item::of(&["sum"]), Item::of(&["f"])]
+}
+
+fn run(context: Context, source: &str) -> Result<String, runestick::VmError> {
+    let (unit, _) = rune::testing::compile(&context, source).unwrap();
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+    Ok(output.into_string()?.take()?)
+}
+
+fn transpile(source: &str, items: &[Item]) -> TranspileError {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = rune::testing::compile(&context, source).unwrap();
+    rune::transpile(&unit, items).unwrap_err()
+}
+
+#[test]
+fn test_transpile() {
+    let context = Context::with_default_modules().unwrap();
+    let (unit, _) = rune::testing::compile(&context, FUNCTIONS).unwrap();
+    let source = rune::transpile(&unit, &items()).unwrap();
+    assert_eq!(source, include_str!("transpiled/functions.rs"));
+}
+
+#[test]
+fn test_transpiled_functions() {
+    let expected = run(
+        Context::with_default_modules().unwrap(),
+        &format!("{}{}", FUNCTIONS, MAIN),
+    )
+    .unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&transpiled::module().unwrap()).unwrap();
+    let actual = run(context, MAIN).unwrap();
+
+    assert_eq!(expected, "610 4950 6.0 3.0 -1.0");
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn test_transpiled_errors() {
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&transpiled::module().unwrap()).unwrap();
+
+    let error = run(context, "fn main() { f(1, 2) }").unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        VmErrorKind::UnsupportedBinaryOperation { op, .. } => assert_eq!(*op, "*"),
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&transpiled::module().unwrap()).unwrap();
+
+    let error = run(context, "fn main() { fib(1, 2) }").unwrap_err();
+
+    match error.kind().into_unwound_ref().0 {
+        VmErrorKind::BadArgumentCount { actual, expected } => {
+            assert_eq!((*actual, *expected), (2, 1))
+        }
+        kind => panic!("unexpected error: {:?}", kind),
+    }
+}
+
+#[test]
+fn test_transpile_errors() {
+    match transpile("fn a() { \"a\" }", &[Item::of(&["a"])]) {
+        TranspileError::UnsupportedInstruction { item, ip: 0, .. } => {
+            assert_eq!(item, Item::of(&["a"]))
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    match transpile("fn a() { b() } fn b() { 1 }", &[Item::of(&["a"])]) {
+        TranspileError::UnsupportedCall { item, ip: 0, hash } => {
+            assert_eq!(item, Item::of(&["a"]));
+            assert_eq!(hash, runestick::Hash::type_hash(&["b"]));
+        }
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    match transpile("async fn a() { 1 }", &[Item::of(&["a"])]) {
+        TranspileError::UnsupportedFunction { .. } => (),
+        error => panic!("unexpected error: {:?}", error),
+    }
+
+    match transpile("fn a() { 1 }", &[Item::of(&["b"])]) {
+        TranspileError::MissingFunction { .. } => (),
+        error => panic!("unexpected error: {:?}", error),
+    }
+}
@@ -16,3 +16,58 @@ fn test_mutate_tuples() {
         "Now You Don't !",
     };
 }
+
+#[test]
+fn test_multi_value_return() {
+    assert_eq! {
+        rune! {
+            (i64, i64, i64, i64, (i64, i64)) => r#"
+            fn divmod(a, b) {
+                (a / b, a % b)
+            }
+
+            fn first(values) {
+                for value in values {
+                    if value > 1 {
+                        return (value, value * 2);
+                    }
+                }
+
+                (0, 0)
+            }
+
+            fn main() {
+                let (q, r) = divmod(17, 5);
+                let (a, b) = first([1, 2, 3]);
+                (q, r, a, b, divmod(9, 2))
+            }
+            "#
+        },
+        (3, 2, 2, 4, (4, 1)),
+    };
+
+    let context = runestick::Context::with_default_modules().unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn divmod(a, b) { (a / b, a % b) }
+        fn main() { let (q, r) = divmod(17, 5); q + r }
+        "#,
+    )
+    .unwrap();
+
+    assert!(unit
+        .iter_instructions()
+        .all(|inst| !matches!(inst, runestick::Inst::Tuple { .. })));
+}
+
+#[test]
+fn test_unpack_mismatch() {
+    assert_vm_error!(
+        r#"fn main() { let (a, b) = (1, 2, 3); }"#,
+        Panic { reason } => {
+            assert_eq!(reason.to_string(), "pattern did not match");
+        }
+    );
+}
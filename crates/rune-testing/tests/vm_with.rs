@@ -0,0 +1,244 @@
+use rune_testing::*;
+use runestick::{Context, Item, Module, Vm};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_with_close() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Resource { name, log }
+
+            impl Resource {
+                fn new(name, log) {
+                    Resource { name, log }
+                }
+
+                fn close(self) {
+                    self.log.push(`close {self.name}`);
+                }
+            }
+
+            fn main() {
+                let log = [];
+
+                let value = with Resource::new("a", log) as a {
+                    with Resource::new("b", log) {
+                        log.push("body");
+                    }
+
+                    a.name
+                };
+
+                log.push(value);
+                log
+            }
+            "#
+        },
+        vec!["body", "close b", "close a", "a"],
+    };
+}
+
+#[test]
+fn test_with_on_return() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Resource { log }
+
+            impl Resource {
+                fn new(log) {
+                    Resource { log }
+                }
+
+                fn close(self) {
+                    self.log.push("close");
+                }
+            }
+
+            fn foo(log, value) {
+                with Resource::new(log) {
+                    let value = value?;
+
+                    if value > 1 {
+                        return Ok("early");
+                    }
+
+                    log.push("late");
+                }
+
+                Ok("done")
+            }
+
+            fn main() {
+                let log = [];
+                for value in [Ok(1), Ok(2), Err("bad")] {
+                    if let Ok(value) = foo(log, value) {
+                        log.push(value);
+                    }
+                }
+
+                log
+            }
+            "#
+        },
+        vec!["late", "close", "done", "close", "early", "close"],
+    };
+}
+
+#[test]
+fn test_with_on_break() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Resource { n, log }
+
+            impl Resource {
+                fn new(n, log) {
+                    Resource { n, log }
+                }
+
+                fn close(self) {
+                    self.log.push(`close {self.n}`);
+                }
+            }
+
+            fn main() {
+                let log = [];
+
+                for n in [1, 2, 3] {
+                    with Resource::new(n, log) as r {
+                        if r.n == 2 {
+                            break;
+                        }
+                    }
+                }
+
+                log
+            }
+            "#
+        },
+        vec!["close 1", "close 2"],
+    };
+}
+
+#[test]
+fn test_with_on_continue() {
+    assert_eq! {
+        rune! {
+            Vec<String> => r#"
+            struct Resource { name, log }
+
+            impl Resource {
+                fn new(name, log) {
+                    Resource { name, log }
+                }
+
+                fn close(self) {
+                    self.log.push(`close {self.name}`);
+                }
+            }
+
+            fn main() {
+                let log = [];
+
+                for i in [1, 2, 3] {
+                    with Resource::new(`l{i}`, log) as r {
+                        if i == 1 {
+                            continue;
+                        }
+
+                        break;
+                    }
+                }
+
+                log
+            }
+            "#
+        },
+        vec!["close l1", "close l2"],
+    };
+}
+
+#[derive(Debug)]
+struct Lock {
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+impl Lock {
+    fn enter(&self) -> String {
+        self.push("enter");
+        String::from("guard")
+    }
+
+    fn exit(&self) {
+        self.push("exit");
+    }
+
+    fn push(&self, value: &str) {
+        self.log.lock().unwrap().push(value.to_owned());
+    }
+}
+
+runestick::impl_external!(Lock);
+
+#[test]
+fn test_with_protocols() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+
+    let mut module = Module::new(&["sync"]);
+    module.ty(&["Lock"]).build::<Lock>().unwrap();
+
+    module.inst_fn(runestick::ENTER, Lock::enter).unwrap();
+    module.inst_fn(runestick::EXIT, Lock::exit).unwrap();
+    module.inst_fn("push", Lock::push).unwrap();
+
+    let mut context = Context::with_default_modules().unwrap();
+    context.install(&module).unwrap();
+
+    let (unit, _) = compile_source(
+        &context,
+        r#"
+        fn main(lock) {
+            with lock as guard {
+                lock.push(guard);
+            }
+
+            lock.push("after");
+        }
+        "#,
+    )
+    .unwrap();
+
+    let vm = Vm::new(Arc::new(context), Arc::new(unit));
+    let lock = Lock { log: log.clone() };
+    vm.call(Item::of(&["main"]), (lock,))
+        .unwrap()
+        .complete()
+        .unwrap();
+
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec!["enter", "guard", "exit", "after"]
+    );
+}
+
+#[test]
+fn test_with_missing_exit() {
+    assert_vm_error! {
+        r#"fn main() { with 42 { } }"#,
+        MissingProtocol { protocol, .. } => {
+            assert_eq!(protocol.hash, runestick::EXIT.hash);
+        }
+    };
+}
+
+#[test]
+fn test_with_expected_binding() {
+    assert_parse_error! {
+        r#"fn main() { with 42 as 1 { } }"#,
+        ExpectedWithBinding { span } => {
+            assert_eq!(span, Span::new(23, 24));
+        }
+    };
+}
@@ -0,0 +1,119 @@
+//! Benchmarks for the lexer.
+//!
+//! Run with `cargo bench -p rune --bench lexer`, optionally followed by the
+//! paths of sources to lex instead of the built-in one, like
+//! `cargo bench -p rune --bench lexer -- scripts/fib.rn`.
+
+use rune::Lexer;
+use std::time::{Duration, Instant};
+
+/// A source which exercises most kinds of tokens.
+const SOURCE: &str = r##"
+use std::collections::HashMap;
+
+/// A point in space.
+struct Point { x, y }
+
+enum Shape { Circle(radius), Rect { w, h }, Empty }
+
+impl Point {
+    fn new(x, y) { Point { x, y } }
+
+    fn distance(self, other) {
+        let dx = self.x - other.x;
+        let dy = self.y - other.y;
+        (dx * dx + dy * dy).sqrt()
+    }
+}
+
+async fn fetch(client, url) {
+    let response = client.get(url).send().await?;
+    response.text().await
+}
+
+fn area(shape) {
+    match shape {
+        Shape::Circle(r) => 3.141592 * r * r,
+        Shape::Rect { w, h } => w * h,
+        _ => 0.0,
+    }
+}
+
+fn main() {
+    let values = [1, 2, 0x1f, 0b1010, 0o777, -42, 1e-9, 2.5e10];
+    let object = #{ "name": "rune", "bytes": b"abc", "byte": b'a', "char": '\n' };
+    let mut total = 0;
+
+    'outer: for v in values {
+        if v is int && v >= 0 || v != 2 {
+            total += v << 2;
+            total >>= 1;
+            total %= 1000;
+        } else if not true {
+            break 'outer;
+        }
+    }
+
+    while total > 0 { total -= 1; }
+    let raw = r#"a "raw" string"#;
+    let text = """
+        a multiline string
+    """;
+    let message = `{total} items, {object["name"]} and {{escaped}}`;
+    let f = |a, b| a + b;
+    yield f(total, values.len());
+    return message;
+}
+"##;
+
+/// The minimum time to spend lexing each source.
+const DURATION: Duration = Duration::from_secs(2);
+
+fn main() {
+    let paths = std::env::args()
+        .skip(1)
+        .filter(|a| !a.starts_with("--"))
+        .collect::<Vec<_>>();
+
+    if paths.is_empty() {
+        let source = SOURCE.repeat(1000);
+        bench("builtin", &source);
+        return;
+    }
+
+    for path in paths {
+        let source = std::fs::read_to_string(&path).expect("failed to read source");
+        bench(&path, &source);
+    }
+}
+
+/// Lex the given source repeatedly and report the throughput.
+fn bench(name: &str, source: &str) {
+    let mut iterations = 0u32;
+    let mut tokens = 0usize;
+    let start = Instant::now();
+
+    while start.elapsed() < DURATION {
+        let mut lexer = Lexer::new(source);
+
+        while let Some(token) = lexer.next().expect("failed to lex source") {
+            tokens += token.span.len();
+        }
+
+        iterations += 1;
+    }
+
+    let elapsed = start.elapsed();
+    let per_iteration = elapsed / iterations;
+    let bytes = source.len() as f64 * f64::from(iterations);
+    let throughput = bytes / elapsed.as_secs_f64() / (1024.0 * 1024.0);
+
+    println!(
+        "{}: {} bytes in {:?} ({:.2} MiB/s, {} bytes of tokens)",
+        name,
+        source.len(),
+        per_iteration,
+        throughput,
+        tokens / iterations as usize,
+    );
+}
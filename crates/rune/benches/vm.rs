@@ -0,0 +1,152 @@
+//! Benchmarks for the virtual machine.
+//!
+//! Run with `cargo bench -p rune --bench vm`, optionally followed by the names
+//! of the benchmarks to run, like `cargo bench -p rune --bench vm -- fib`.
+
+use runestick::{Context, Item, Unit, Vm};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Benchmarks by name, where each source has a `main` function to call.
+const BENCHMARKS: &[(&str, &str)] = &[
+    (
+        "fib",
+        r#"
+        fn fib(n) {
+            if n <= 1 {
+                n
+            } else {
+                fib(n - 1) + fib(n - 2)
+            }
+        }
+
+        fn main() {
+            fib(20)
+        }
+        "#,
+    ),
+    (
+        "loop",
+        r#"
+        fn main() {
+            let sum = 0;
+            let i = 0;
+
+            while i < 100000 {
+                sum = sum + i * 2 % 7;
+                i += 1;
+            }
+
+            sum
+        }
+        "#,
+    ),
+    (
+        "float",
+        r#"
+        fn main() {
+            let x = 0.0;
+            let i = 0;
+
+            while i < 100000 {
+                x = x * 0.5 + 1.5;
+                i += 1;
+            }
+
+            x
+        }
+        "#,
+    ),
+    (
+        "objects",
+        r#"
+        struct Point { x, y }
+
+        fn main() {
+            let points = [];
+            let i = 0;
+
+            while i < 1000 {
+                points.push(Point { x: i, y: i * 2 });
+                i += 1;
+            }
+
+            let sum = 0;
+
+            for p in points {
+                sum = sum + p.x + p.y;
+            }
+
+            let object = #{};
+            object["sum"] = sum;
+            object["sum"]
+        }
+        "#,
+    ),
+    (
+        "strings",
+        r#"
+        fn main() {
+            let s = `strings:`;
+            let i = 0;
+
+            while i < 1000 {
+                s += `{i},`;
+                i += 1;
+            }
+
+            s.len()
+        }
+        "#,
+    ),
+];
+
+/// The minimum time to spend running each benchmark.
+const DURATION: Duration = Duration::from_secs(2);
+
+fn main() {
+    let filters = std::env::args()
+        .skip(1)
+        .filter(|a| !a.starts_with("--"))
+        .collect::<Vec<_>>();
+
+    let context = Arc::new(Context::with_default_modules().expect("failed to build context"));
+
+    for (name, source) in BENCHMARKS {
+        if !filters.is_empty() && !filters.iter().any(|f| name.contains(f.as_str())) {
+            continue;
+        }
+
+        let (unit, _) = rune::testing::compile(&context, source).expect("failed to compile");
+        bench(name, &context, Arc::new(unit));
+    }
+}
+
+/// Run the `main` function of the given unit repeatedly and report the time
+/// spent per call, both the fastest and on average.
+fn bench(name: &str, context: &Arc<Context>, unit: Arc<Unit>) {
+    let mut iterations = 0u32;
+    let mut fastest = Duration::from_secs(u64::MAX);
+    let start = Instant::now();
+
+    while start.elapsed() < DURATION {
+        let call = Instant::now();
+        let vm = Vm::new(context.clone(), unit.clone());
+
+        vm.call(Item::of(&["main"]), ())
+            .expect("failed to call main")
+            .complete()
+            .expect("failed to run main");
+
+        fastest = fastest.min(call.elapsed());
+        iterations += 1;
+    }
+
+    println!(
+        "{}: {:?} fastest, {:?} average ({} calls)",
+        name,
+        fastest,
+        start.elapsed() / iterations,
+        iterations
+    );
+}
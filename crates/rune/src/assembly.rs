@@ -3,12 +3,15 @@
 use crate::collections::HashMap;
 use crate::unit_builder::UnitBuilderError;
 use runestick::{Hash, Inst, Label, Span};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub enum AssemblyInst {
     Jump { label: Label },
     JumpIf { label: Label },
     JumpIfNot { label: Label },
+    JumpIfOrPop { label: Label },
+    JumpIfNotOrPop { label: Label },
     JumpIfBranch { branch: i64, label: Label },
     PopAndJumpIfNot { count: usize, label: Label },
     Raw { raw: Inst },
@@ -30,7 +33,7 @@ pub struct Assembly {
     /// The number of labels.
     pub(crate) label_count: usize,
     /// The collection of functions required by this assembly.
-    pub(crate) required_functions: HashMap<Hash, Vec<(Span, usize)>>,
+    pub(crate) required_functions: BTreeMap<Hash, Vec<(Span, usize)>>,
 }
 
 impl Assembly {
@@ -83,6 +86,20 @@ impl Assembly {
             .push((AssemblyInst::JumpIfNot { label }, span));
     }
 
+    /// Add a conditional jump to the given label. Only pops the top of the
+    /// stack if the jump is not executed.
+    pub(crate) fn jump_if_or_pop(&mut self, label: Label, span: Span) {
+        self.instructions
+            .push((AssemblyInst::JumpIfOrPop { label }, span));
+    }
+
+    /// Add a conditional jump to the given label. Only pops the top of the
+    /// stack if the jump is not executed.
+    pub(crate) fn jump_if_not_or_pop(&mut self, label: Label, span: Span) {
+        self.instructions
+            .push((AssemblyInst::JumpIfNotOrPop { label }, span));
+    }
+
     /// Add a conditional jump-if-branch instruction.
     pub(crate) fn jump_if_branch(&mut self, branch: i64, label: Label, span: Span) {
         self.instructions
@@ -97,7 +114,7 @@ impl Assembly {
 
     /// Push a raw instruction.
     pub(crate) fn push(&mut self, raw: Inst, span: Span) {
-        if let Inst::Call { hash, .. } = raw {
+        if let Inst::Call { hash, .. } | Inst::CallIntrinsic { hash, .. } = raw {
             self.required_functions
                 .entry(hash)
                 .or_default()
@@ -0,0 +1,194 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::{Parse, Peek};
+use runestick::Span;
+
+/// An attribute on an item, like `#[cfg(feature = "foo")]`.
+#[derive(Debug, Clone)]
+pub struct Attribute {
+    /// The `#` token.
+    pub hash: ast::Hash,
+    /// The opening bracket `[`.
+    pub open: ast::OpenBracket,
+    /// The content of the attribute.
+    pub meta: ast::Meta,
+    /// The closing bracket `]`.
+    pub close: ast::CloseBracket,
+}
+
+impl Attribute {
+    /// Access the span of the attribute.
+    pub fn span(&self) -> Span {
+        self.hash.token.span.join(self.close.token.span)
+    }
+}
+
+impl Peek for Attribute {
+    fn peek(t1: Option<ast::Token>, t2: Option<ast::Token>) -> bool {
+        matches!(
+            (t1.map(|t| t.kind), t2.map(|t| t.kind)),
+            (
+                Some(ast::Kind::Hash),
+                Some(ast::Kind::Open(ast::Delimiter::Bracket))
+            )
+        )
+    }
+}
+
+/// Parse an attribute.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::Attribute>("#[test]").unwrap();
+/// parse_all::<ast::Attribute>("#[cfg(target = \"web\")]").unwrap();
+/// parse_all::<ast::Attribute>("#[cfg(all(web, not(debug)))]").unwrap();
+/// parse_all::<ast::Attribute>("#[memoize(capacity = 100)]").unwrap();
+/// ```
+impl Parse for Attribute {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Ok(Self {
+            hash: parser.parse()?,
+            open: parser.parse()?,
+            meta: parser.parse()?,
+            close: parser.parse()?,
+        })
+    }
+}
+
+/// The content of an attribute.
+#[derive(Debug, Clone)]
+pub enum Meta {
+    /// A plain name, like `debug`.
+    Ident(ast::Ident),
+    /// A name with a value, like `target = "web"`.
+    NameValue(ast::MetaNameValue),
+    /// A name with a list of nested content, like `all(web, debug)`.
+    List(ast::MetaList),
+}
+
+impl Meta {
+    /// Access the span of the content.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::Ident(ident) => ident.token.span,
+            Self::NameValue(meta) => meta.span(),
+            Self::List(meta) => meta.span(),
+        }
+    }
+
+    /// Access the name of the content.
+    pub fn name(&self) -> &ast::Ident {
+        match self {
+            Self::Ident(ident) => ident,
+            Self::NameValue(meta) => &meta.name,
+            Self::List(meta) => &meta.name,
+        }
+    }
+}
+
+impl Parse for Meta {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        // NB: `not` is a keyword, but is used as a name in conditions.
+        let name = if parser.peek::<ast::Not>()? {
+            let not = parser.parse::<ast::Not>()?;
+            ast::Ident { token: not.token }
+        } else {
+            parser.parse()?
+        };
+
+        Ok(if parser.peek::<ast::Eq>()? {
+            Self::NameValue(ast::MetaNameValue {
+                name,
+                eq: parser.parse()?,
+                value: parser.parse()?,
+            })
+        } else if parser.peek::<ast::OpenParen>()? {
+            Self::List(ast::MetaList {
+                name,
+                items: parser.parse()?,
+            })
+        } else {
+            Self::Ident(name)
+        })
+    }
+}
+
+/// A name with a value in an attribute, like `target = "web"` or
+/// `capacity = 100`.
+#[derive(Debug, Clone)]
+pub struct MetaNameValue {
+    /// The name.
+    pub name: ast::Ident,
+    /// The equals sign `=`.
+    pub eq: ast::Eq,
+    /// The value.
+    pub value: ast::MetaValue,
+}
+
+impl MetaNameValue {
+    /// Access the span of the name and value.
+    pub fn span(&self) -> Span {
+        self.name.token.span.join(self.value.span())
+    }
+}
+
+/// The value of a name in an attribute.
+#[derive(Debug, Clone)]
+pub enum MetaValue {
+    /// A string value, like `"web"`.
+    LitStr(ast::LitStr),
+    /// A number value, like `100`.
+    LitNumber(ast::LitNumber),
+}
+
+impl MetaValue {
+    /// Access the span of the value.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::LitStr(lit_str) => lit_str.span(),
+            Self::LitNumber(lit_number) => lit_number.span(),
+        }
+    }
+}
+
+/// Parse the value of a name in an attribute.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::MetaValue>("\"web\"").unwrap();
+/// parse_all::<ast::MetaValue>("100").unwrap();
+/// ```
+impl Parse for MetaValue {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let token = parser.token_peek_eof()?;
+
+        Ok(match token.kind {
+            ast::Kind::LitNumber { .. } => Self::LitNumber(parser.parse()?),
+            _ => Self::LitStr(parser.parse()?),
+        })
+    }
+}
+
+/// A name with a list of nested content in an attribute, like
+/// `all(web, debug)`.
+#[derive(Debug, Clone)]
+pub struct MetaList {
+    /// The name.
+    pub name: ast::Ident,
+    /// The nested content.
+    pub items: ast::Parenthesized<ast::Meta, ast::Comma>,
+}
+
+impl MetaList {
+    /// Access the span of the list.
+    pub fn span(&self) -> Span {
+        self.name.token.span.join(self.items.span())
+    }
+}
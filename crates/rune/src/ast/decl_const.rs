@@ -0,0 +1,56 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::{Parse, Peek};
+use runestick::Span;
+
+/// An associated constant declared in an impl block, like
+/// `const ORIGIN = Point { x: 0, y: 0 };`.
+#[derive(Debug, Clone)]
+pub struct DeclConst {
+    /// The `const` keyword.
+    pub const_: ast::Const,
+    /// The name of the constant.
+    pub name: ast::Ident,
+    /// The equals sign `=`.
+    pub eq: ast::Eq,
+    /// The value of the constant.
+    pub expr: ast::Expr,
+    /// The terminating semicolon.
+    pub semi: ast::SemiColon,
+}
+
+impl DeclConst {
+    /// The span of the declaration.
+    pub fn span(&self) -> Span {
+        self.const_.span().join(self.semi.span())
+    }
+}
+
+impl Peek for DeclConst {
+    fn peek(t1: Option<ast::Token>, _: Option<ast::Token>) -> bool {
+        matches!(t1.map(|t| t.kind), Some(ast::Kind::Const))
+    }
+}
+
+/// Parse an associated constant.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::DeclConst>("const ZERO = 0;").unwrap();
+/// parse_all::<ast::DeclConst>("const ORIGIN = Point { x: 0, y: 0 };").unwrap();
+/// ```
+impl Parse for DeclConst {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        Ok(Self {
+            const_: parser.parse()?,
+            name: parser.parse()?,
+            eq: parser.parse()?,
+            expr: parser.parse()?,
+            semi: parser.parse()?,
+        })
+    }
+}
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
-use crate::traits::Parse;
+use crate::traits::{Parse, Peek};
 use crate::{IntoTokens, MacroContext, TokenStream};
 use runestick::Span;
 
@@ -14,8 +14,14 @@ pub struct DeclEnum {
     pub name: ast::Ident,
     /// The open brace of the declaration.
     pub open: ast::OpenBrace,
-    /// Variants in the declaration.
-    pub variants: Vec<(ast::Ident, ast::DeclStructBody, Option<ast::Comma>)>,
+    /// Variants in the declaration, with their optional discriminants like
+    /// `Red = 1`.
+    pub variants: Vec<(
+        ast::Ident,
+        ast::DeclStructBody,
+        Option<EnumDiscriminant>,
+        Option<ast::Comma>,
+    )>,
     /// The close brace in the declaration.
     pub close: ast::CloseBrace,
 }
@@ -35,6 +41,7 @@ impl DeclEnum {
 /// use rune::{parse_all, ast};
 ///
 /// parse_all::<ast::DeclEnum>("enum Foo { Bar(a), Baz(b), Empty() }").unwrap();
+/// parse_all::<ast::DeclEnum>("enum Color { Red = 1, Green = 2, Blue }").unwrap();
 /// ```
 impl Parse for DeclEnum {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -48,6 +55,7 @@ impl Parse for DeclEnum {
             let name = parser.parse()?;
             let variant = parser.parse()?;
 
+            let discriminant = parser.parse()?;
             let comma = if parser.peek::<ast::Comma>()? {
                 Some(parser.parse()?)
             } else {
@@ -56,7 +64,7 @@ impl Parse for DeclEnum {
 
             let done = comma.is_none();
 
-            variants.push((name, variant, comma));
+            variants.push((name, variant, discriminant, comma));
 
             if done {
                 break;
@@ -81,12 +89,42 @@ impl IntoTokens for &DeclEnum {
         self.name.into_tokens(context, stream);
         self.open.into_tokens(context, stream);
 
-        for (variant, body, comma) in &self.variants {
+        for (variant, body, discriminant, comma) in &self.variants {
             variant.into_tokens(context, stream);
             body.into_tokens(context, stream);
+
+            if let Some(discriminant) = discriminant {
+                discriminant.eq.into_tokens(context, stream);
+                discriminant.value.into_tokens(context, stream);
+            }
+
             comma.into_tokens(context, stream);
         }
 
         self.close.into_tokens(context, stream);
     }
 }
+
+/// An explicit discriminant of an enum variant, like `= 1`.
+#[derive(Debug, Clone)]
+pub struct EnumDiscriminant {
+    /// The equals sign `=`.
+    pub eq: ast::Eq,
+    /// The value of the discriminant.
+    pub value: ast::LitNumber,
+}
+
+impl Peek for EnumDiscriminant {
+    fn peek(t1: Option<ast::Token>, t2: Option<ast::Token>) -> bool {
+        ast::Eq::peek(t1, t2)
+    }
+}
+
+impl Parse for EnumDiscriminant {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        Ok(Self {
+            eq: parser.parse()?,
+            value: parser.parse()?,
+        })
+    }
+}
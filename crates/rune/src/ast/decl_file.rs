@@ -6,8 +6,12 @@ use crate::traits::Parse;
 /// A parsed file.
 #[derive(Debug, Clone)]
 pub struct DeclFile {
-    /// All the declarations in a file.
-    pub decls: Vec<(ast::Decl, Option<ast::SemiColon>)>,
+    /// All the declarations in a file, with the attributes preceding them.
+    pub decls: Vec<(Vec<ast::Attribute>, ast::Decl, Option<ast::SemiColon>)>,
+    /// Statements at the top level of the file, in the order they appear.
+    ///
+    /// These make up the body of the implicit `main` function of a script.
+    pub stmts: Vec<(ast::Expr, Option<ast::SemiColon>)>,
 }
 
 /// Parse a file.
@@ -26,6 +30,7 @@ pub struct DeclFile {
 ///
 /// use bar;
 ///
+/// #[cfg(debug)]
 /// fn bar(a, b) {
 ///     a
 /// }
@@ -47,24 +52,71 @@ pub struct DeclFile {
 /// }
 /// "#).unwrap();
 /// ```
+///
+/// # Script Example
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// let file = parse_all::<ast::DeclFile>(r#"
+/// fn add(a, b) {
+///     a + b
+/// }
+///
+/// let n = add(1, 2);
+/// dbg(n);
+/// "#).unwrap();
+///
+/// assert_eq!(file.decls.len(), 2);
+/// assert_eq!(file.stmts.len(), 1);
+/// ```
 // TODO: this is a false positive: https://github.com/rust-lang/rust-clippy/issues/5879
 #[allow(clippy::needless_doctest_main)]
 impl Parse for DeclFile {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let mut decls = Vec::new();
+        let mut stmts = Vec::new();
+
+        loop {
+            if parser.peek::<ast::Attribute>()?
+                || parser.peek::<ast::Decl>()?
+                || parser.peek::<ast::DeclLet>()?
+            {
+                let mut attributes = Vec::new();
+
+                while parser.peek::<ast::Attribute>()? {
+                    attributes.push(parser.parse::<ast::Attribute>()?);
+                }
+
+                let decl: ast::Decl = parser.parse()?;
+
+                let semi_colon = if decl.needs_semi_colon() || parser.peek::<ast::SemiColon>()? {
+                    Some(parser.parse::<ast::SemiColon>()?)
+                } else {
+                    None
+                };
+
+                decls.push((attributes, decl, semi_colon));
+                continue;
+            }
+
+            // NB: the file might be the body of a module, which ends with a
+            // close brace.
+            if !parser.peek::<ast::Expr>()? {
+                break;
+            }
 
-        while parser.peek::<ast::Decl>()? {
-            let decl: ast::Decl = parser.parse()?;
+            let expr: ast::Expr = parser.parse()?;
 
-            let semi_colon = if decl.needs_semi_colon() || parser.peek::<ast::SemiColon>()? {
+            let semi_colon = if parser.peek::<ast::SemiColon>()? {
                 Some(parser.parse::<ast::SemiColon>()?)
             } else {
                 None
             };
 
-            decls.push((decl, semi_colon));
+            stmts.push((expr, semi_colon));
         }
 
-        Ok(Self { decls })
+        Ok(Self { decls, stmts })
     }
 }
@@ -9,10 +9,15 @@ use runestick::Span;
 pub struct DeclImpl {
     /// The `impl` keyword.
     pub impl_: ast::Impl,
+    /// The name of the extension and the `for` keyword, like in
+    /// `impl VecExt for Vec`.
+    pub extension: Option<(ast::Ident, ast::For)>,
     /// Path of the implementation.
     pub path: ast::Path,
     /// The open brace.
     pub open: ast::OpenBrace,
+    /// The collection of associated constants.
+    pub consts: Vec<ast::DeclConst>,
     /// The collection of functions.
     pub functions: Vec<ast::DeclFn>,
     /// The close brace.
@@ -35,14 +40,50 @@ impl DeclImpl {
 ///
 /// parse_all::<ast::DeclImpl>("impl Foo {}").unwrap();
 /// parse_all::<ast::DeclImpl>("impl Foo { fn test(self) { } }").unwrap();
+/// parse_all::<ast::DeclImpl>("impl FooExt for Foo { fn test(self) { } }").unwrap();
+/// parse_all::<ast::DeclImpl>("impl Foo { const ZERO = 0; fn new() { } }").unwrap();
 /// ```
 impl Parse for DeclImpl {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        let impl_ = parser.parse()?;
+
+        let extension = if parser.peek2::<ast::For>()? {
+            Some((parser.parse()?, parser.parse()?))
+        } else {
+            None
+        };
+
+        let path = parser.parse()?;
+        let open = parser.parse()?;
+
+        let mut consts = Vec::new();
+        let mut functions = Vec::new();
+
+        loop {
+            if parser.peek::<ast::Attribute>()? {
+                let attribute = parser.parse::<ast::Attribute>()?;
+
+                return Err(ParseError::UnsupportedImplAttribute {
+                    span: attribute.span(),
+                });
+            }
+
+            if parser.peek::<ast::DeclConst>()? {
+                consts.push(parser.parse()?);
+            } else if parser.peek::<ast::DeclFn>()? {
+                functions.push(parser.parse()?);
+            } else {
+                break;
+            }
+        }
+
         Ok(Self {
-            impl_: parser.parse()?,
-            path: parser.parse()?,
-            open: parser.parse()?,
-            functions: parser.parse()?,
+            impl_,
+            extension,
+            path,
+            open,
+            consts,
+            functions,
             close: parser.parse()?,
         })
     }
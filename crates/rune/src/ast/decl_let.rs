@@ -0,0 +1,59 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::{Parse, Peek};
+use runestick::Span;
+
+/// A static declared with a module-level `let` binding, like
+/// `let CACHE = #{};`.
+#[derive(Debug, Clone)]
+pub struct DeclLet {
+    /// The `let` keyword.
+    pub let_: ast::Let,
+    /// The name of the static.
+    pub name: ast::Ident,
+    /// The equals sign `=`.
+    pub eq: ast::Eq,
+    /// The initializer of the static.
+    pub expr: Box<ast::Expr>,
+}
+
+impl DeclLet {
+    /// The span of the declaration.
+    pub fn span(&self) -> Span {
+        self.let_.span().join(self.expr.span())
+    }
+}
+
+/// Statics are only declared at the top level of a module, since a `let` in a
+/// block declares a local variable. A `let` with a pattern, like
+/// `let (a, b) = pair;`, is a top-level statement.
+impl Peek for DeclLet {
+    fn peek(t1: Option<ast::Token>, t2: Option<ast::Token>) -> bool {
+        matches!(
+            (t1.map(|t| t.kind), t2.map(|t| t.kind)),
+            (Some(ast::Kind::Let), Some(ast::Kind::Ident))
+        )
+    }
+}
+
+/// Parse a static declaration.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::DeclLet>("let COUNTER = 0").unwrap();
+/// parse_all::<ast::DeclLet>("let CACHE = #{}").unwrap();
+/// ```
+impl Parse for DeclLet {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        Ok(Self {
+            let_: parser.parse()?,
+            name: parser.parse()?,
+            eq: parser.parse()?,
+            expr: Box::new(parser.parse()?),
+        })
+    }
+}
@@ -111,7 +111,7 @@ pub struct EmptyBody {}
 /// ```rust
 /// use rune::{parse_all, ast};
 ///
-/// parse_all::<ast::EmptyBody>("Foo").unwrap();
+/// parse_all::<ast::EmptyBody>("").unwrap();
 /// ```
 impl Parse for EmptyBody {
     fn parse(_: &mut Parser<'_>) -> Result<Self, ParseError> {
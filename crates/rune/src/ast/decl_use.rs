@@ -34,9 +34,11 @@ impl DeclUse {
 /// ```rust
 /// use rune::{parse_all, ast};
 ///
-/// parse_all::<ast::DeclUse>("use foo;").unwrap();
-/// parse_all::<ast::DeclUse>("use foo::bar;").unwrap();
-/// parse_all::<ast::DeclUse>("use foo::bar::baz;").unwrap();
+/// parse_all::<ast::DeclUse>("use foo").unwrap();
+/// parse_all::<ast::DeclUse>("use foo::bar").unwrap();
+/// parse_all::<ast::DeclUse>("use foo::bar::baz").unwrap();
+/// parse_all::<ast::DeclUse>("use foo::{bar, baz::*}").unwrap();
+/// parse_all::<ast::DeclUse>("use foo::{bar::{baz, qux}, quux,}").unwrap();
 /// ```
 impl Parse for DeclUse {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
@@ -55,6 +57,8 @@ pub enum DeclUseComponent {
     Ident(ast::Ident),
     /// A wildcard import.
     Wildcard(ast::Mul),
+    /// A group of imports, like `{HashMap, HashSet}`.
+    Group(DeclUseGroup),
 }
 
 impl DeclUseComponent {
@@ -63,6 +67,7 @@ impl DeclUseComponent {
         match self {
             Self::Ident(ident) => ident.span(),
             Self::Wildcard(wildcard) => wildcard.span(),
+            Self::Group(group) => group.span(),
         }
     }
 }
@@ -74,6 +79,7 @@ impl Parse for DeclUseComponent {
         Ok(match t.kind {
             ast::Kind::Ident => Self::Ident(parser.parse()?),
             ast::Kind::Star => Self::Wildcard(parser.parse()?),
+            ast::Kind::Open(ast::Delimiter::Brace) => Self::Group(parser.parse()?),
             actual => {
                 return Err(ParseError::ExpectedDeclUseImportComponent {
                     span: t.span,
@@ -91,6 +97,78 @@ impl Peek for DeclUseComponent {
             None => return false,
         };
 
-        matches!(kind, Kind::Ident | Kind::Star)
+        matches!(
+            kind,
+            Kind::Ident | Kind::Star | Kind::Open(ast::Delimiter::Brace)
+        )
+    }
+}
+
+/// A group of imports, like `{HashMap, HashSet}`.
+#[derive(Debug, Clone)]
+pub struct DeclUseGroup {
+    /// The open brace.
+    pub open: ast::OpenBrace,
+    /// The paths in the group.
+    pub items: Vec<(DeclUsePath, Option<ast::Comma>)>,
+    /// The close brace.
+    pub close: ast::CloseBrace,
+}
+
+impl DeclUseGroup {
+    /// Get the span for the group.
+    pub fn span(&self) -> Span {
+        self.open.span().join(self.close.span())
+    }
+}
+
+impl Parse for DeclUseGroup {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        let open = parser.parse()?;
+
+        let mut items = Vec::new();
+
+        while !parser.peek::<ast::CloseBrace>()? {
+            let path = parser.parse()?;
+            let comma = parser.parse::<Option<ast::Comma>>()?;
+            let is_end = comma.is_none();
+            items.push((path, comma));
+
+            if is_end {
+                break;
+            }
+        }
+
+        let close = parser.parse()?;
+        Ok(Self { open, items, close })
+    }
+}
+
+/// A path inside of a group of imports, like `collections::HashMap`.
+#[derive(Debug, Clone)]
+pub struct DeclUsePath {
+    /// First component in the path.
+    pub first: DeclUseComponent,
+    /// The rest of the path.
+    pub rest: Vec<(ast::Scope, DeclUseComponent)>,
+}
+
+impl DeclUsePath {
+    /// Get the span for the path.
+    pub fn span(&self) -> Span {
+        if let Some((_, last)) = self.rest.last() {
+            self.first.span().join(last.span())
+        } else {
+            self.first.span()
+        }
+    }
+}
+
+impl Parse for DeclUsePath {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        Ok(Self {
+            first: parser.parse()?,
+            rest: parser.parse()?,
+        })
     }
 }
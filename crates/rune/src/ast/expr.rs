@@ -67,14 +67,22 @@ pub enum Expr {
     ExprUnary(ast::ExprUnary),
     /// An index set operation.
     ExprIndexGet(ast::ExprIndexGet),
+    /// A range expression.
+    ExprRange(ast::ExprRange),
     /// A break expression.
     ExprBreak(ast::ExprBreak),
+    /// A continue expression.
+    ExprContinue(ast::ExprContinue),
     /// A yield expression.
     ExprYield(ast::ExprYield),
     /// A block as an expression.
     ExprBlock(ast::ExprBlock),
     /// A return statement.
     ExprReturn(ast::ExprReturn),
+    /// A deferred block.
+    ExprDefer(ast::ExprDefer),
+    /// A with expression.
+    ExprWith(ast::ExprWith),
     /// An await expression.
     ExprAwait(ast::ExprAwait),
     /// Try expression.
@@ -119,9 +127,11 @@ impl Expr {
             Self::ExprIf(expr_if) => expr_if.produces_nothing(),
             Self::ExprGroup(expr_group) => expr_group.produces_nothing(),
             Self::ExprBreak(..) => true,
+            Self::ExprContinue(..) => true,
             Self::ExprBinary(expr_binary) => expr_binary.produces_nothing(),
             Self::ExprBlock(expr_block) => expr_block.produces_nothing(),
             Self::ExprReturn(..) => true,
+            Self::ExprDefer(..) => true,
             _ => false,
         }
     }
@@ -132,6 +142,8 @@ impl Expr {
             Self::ExprWhile(..) => false,
             Self::ExprLoop(..) => false,
             Self::ExprFor(..) => false,
+            Self::ExprDefer(..) => false,
+            Self::ExprWith(..) => false,
             _ => true,
         }
     }
@@ -156,10 +168,14 @@ impl Expr {
             Self::ExprUnary(expr) => expr.span(),
             Self::ExprBinary(expr) => expr.span(),
             Self::ExprIndexGet(expr) => expr.span(),
+            Self::ExprRange(expr) => expr.span(),
             Self::ExprBreak(b) => b.span(),
+            Self::ExprContinue(b) => b.span(),
             Self::ExprYield(b) => b.span(),
             Self::ExprBlock(b) => b.span(),
             Self::ExprReturn(ret) => ret.span(),
+            Self::ExprDefer(expr) => expr.span(),
+            Self::ExprWith(expr) => expr.span(),
             Self::ExprAwait(ret) => ret.span(),
             Self::ExprTry(ret) => ret.span(),
             Self::ExprSelect(ret) => ret.span(),
@@ -212,8 +228,50 @@ impl Expr {
         eager_brace: EagerBrace,
         expr_chain: ExprChain,
     ) -> Result<Self, ParseError> {
+        if parser.peek::<ast::DotDot>()? {
+            return Self::parse_range(parser, None, eager_brace);
+        }
+
         let lhs = Self::parse_primary(parser, eager_brace, expr_chain)?;
-        Ok(Self::parse_expr_binary(parser, lhs, 0, eager_brace)?)
+        let lhs = Self::parse_expr_binary(parser, lhs, 0, eager_brace)?;
+
+        if parser.peek::<ast::DotDot>()? {
+            return Self::parse_range(parser, Some(lhs), eager_brace);
+        }
+
+        Ok(lhs)
+    }
+
+    /// Parse the remainder of a range expression, starting at the `..`.
+    fn parse_range(
+        parser: &mut Parser<'_>,
+        from: Option<Self>,
+        eager_brace: EagerBrace,
+    ) -> Result<Self, ParseError> {
+        let limits = parser.parse()?;
+
+        // NB: without an eager brace, the brace belongs to the enclosing
+        // statement, like in `for n in 0.. {}`.
+        let has_to =
+            parser.peek::<Self>()? && (*eager_brace || !parser.peek::<ast::OpenBrace>()?);
+
+        let to = if has_to {
+            let to = Self::parse_primary(parser, eager_brace, ExprChain(true))?;
+            Some(Box::new(Self::parse_expr_binary(
+                parser,
+                to,
+                0,
+                eager_brace,
+            )?))
+        } else {
+            None
+        };
+
+        Ok(Self::ExprRange(ast::ExprRange {
+            from: from.map(Box::new),
+            limits,
+            to,
+        }))
     }
 
     /// Parse expressions that start with an identifier.
@@ -333,8 +391,11 @@ impl Expr {
             ast::Kind::True | Kind::False => Self::LitBool(parser.parse()?),
             ast::Kind::Ident => Self::parse_ident_start(parser, eager_brace)?,
             ast::Kind::Break => Self::ExprBreak(parser.parse()?),
+            ast::Kind::Continue => Self::ExprContinue(parser.parse()?),
             ast::Kind::Yield => Self::ExprYield(parser.parse()?),
             ast::Kind::Return => Self::ExprReturn(parser.parse()?),
+            ast::Kind::Defer => Self::ExprDefer(parser.parse()?),
+            ast::Kind::With => Self::ExprWith(parser.parse()?),
             _ => {
                 return Err(ParseError::ExpectedExpr {
                     actual: token.kind,
@@ -475,6 +536,7 @@ impl Expr {
             loop {
                 let (lh, _) = match lookahead_tok.and_then(ast::BinOp::from_token) {
                     Some((lh, _)) if lh.precedence() > op.precedence() => (lh, token),
+                    Some((lh, _)) if op.is_ordering() && lh.is_ordering() => break,
                     Some((lh, _)) if lh.precedence() == op.precedence() && !op.is_assoc() => {
                         return Err(ParseError::PrecedenceGroupRequired {
                             span: lhs.span().join(rhs.span()),
@@ -533,6 +595,13 @@ impl Expr {
 /// parse_all::<ast::Expr>("foo.bar()[0].baz()[1]").unwrap();
 ///
 /// parse_all::<ast::Expr>("42 is int::int").unwrap();
+///
+/// // Ranges.
+/// parse_all::<ast::Expr>("0..10").unwrap();
+/// parse_all::<ast::Expr>("a + 1..").unwrap();
+/// parse_all::<ast::Expr>("..n").unwrap();
+/// parse_all::<ast::Expr>("..").unwrap();
+/// parse_all::<ast::Expr>("foo[1..2]").unwrap();
 /// ```
 impl Parse for Expr {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -559,6 +628,8 @@ impl Peek for Expr {
             ast::Kind::For => true,
             ast::Kind::Let => true,
             ast::Kind::If => true,
+            ast::Kind::Match => true,
+            ast::Kind::PipePipe | ast::Kind::Pipe => true,
             ast::Kind::LitNumber { .. } => true,
             ast::Kind::LitChar { .. } => true,
             ast::Kind::LitByte { .. } => true,
@@ -568,9 +639,12 @@ impl Peek for Expr {
             ast::Kind::Open(Delimiter::Parenthesis) => true,
             ast::Kind::Open(Delimiter::Bracket) => true,
             ast::Kind::Open(Delimiter::Brace) => true,
+            ast::Kind::DotDot => true,
             ast::Kind::True | Kind::False => true,
             ast::Kind::Ident => true,
             ast::Kind::Break => true,
+            ast::Kind::Continue => true,
+            ast::Kind::Yield => true,
             ast::Kind::Return => true,
             _ => false,
         }
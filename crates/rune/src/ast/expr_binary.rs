@@ -61,6 +61,8 @@ pub enum BinOp {
     Is,
     /// Negated instance of test `a is not b`.
     IsNot,
+    /// Type conversion `a as b`.
+    As,
     /// Lazy and operator `&&`.
     And,
     /// Lazy or operator `||`.
@@ -104,6 +106,7 @@ impl BinOp {
     pub(super) fn precedence(self) -> usize {
         // NB: Rules from: https://doc.rust-lang.org/reference/expressions.html#expression-precedence
         match self {
+            Self::As => 12,
             Self::Is | Self::IsNot => 11,
             Self::Mul | Self::Div | Self::Rem => 10,
             Self::Add | Self::Sub => 9,
@@ -132,6 +135,30 @@ impl BinOp {
         }
     }
 
+    /// Test if the operator is an ordering comparison, which can be chained
+    /// like `a < b <= c`.
+    pub(crate) fn is_ordering(self) -> bool {
+        matches!(self, Self::Lt | Self::Gt | Self::Lte | Self::Gte)
+    }
+
+    /// Test if the operator is an assignment, like `a = b` or `a += b`.
+    pub(crate) fn is_assign(self) -> bool {
+        matches!(
+            self,
+            Self::Assign
+                | Self::AddAssign
+                | Self::SubAssign
+                | Self::MulAssign
+                | Self::DivAssign
+                | Self::RemAssign
+                | Self::BitAndAssign
+                | Self::BitXorAssign
+                | Self::BitOrAssign
+                | Self::ShlAssign
+                | Self::ShrAssign
+        )
+    }
+
     /// Convert from a token.
     pub(super) fn from_token((t1, t2): (ast::Token, Option<ast::Token>)) -> Option<(BinOp, Span)> {
         let op = match t1.kind {
@@ -155,6 +182,7 @@ impl BinOp {
 
                 Self::Is
             }
+            ast::Kind::As => Self::As,
             ast::Kind::Eq => Self::Assign,
             ast::Kind::AmpAmp => Self::And,
             ast::Kind::PipePipe => Self::Or,
@@ -204,6 +232,7 @@ impl fmt::Display for BinOp {
             Self::Lte => write!(f, "<="),
             Self::Is => write!(f, "is"),
             Self::IsNot => write!(f, "is not"),
+            Self::As => write!(f, "as"),
             Self::Assign => write!(f, "="),
             Self::And => write!(f, "&&"),
             Self::Or => write!(f, "||"),
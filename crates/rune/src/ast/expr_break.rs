@@ -12,6 +12,8 @@ pub enum ExprBreakValue {
     Expr(Box<ast::Expr>),
     /// Break and jump to the given label.
     Label(ast::Label),
+    /// Break out of the loop with the given label, producing a value.
+    LabelExpr(ast::Label, Box<ast::Expr>),
 }
 
 impl ExprBreakValue {
@@ -20,6 +22,7 @@ impl ExprBreakValue {
         match self {
             Self::Expr(expr) => expr.span(),
             Self::Label(label) => label.span(),
+            Self::LabelExpr(label, expr) => label.span().join(expr.span()),
         }
     }
 }
@@ -29,7 +32,15 @@ impl Parse for ExprBreakValue {
         let token = parser.token_peek_eof()?;
 
         Ok(match token.kind {
-            ast::Kind::Label => Self::Label(parser.parse()?),
+            ast::Kind::Label => {
+                let label = parser.parse()?;
+
+                if parser.peek::<ast::Expr>()? {
+                    Self::LabelExpr(label, Box::new(parser.parse()?))
+                } else {
+                    Self::Label(label)
+                }
+            }
             _ => Self::Expr(Box::new(parser.parse()?)),
         })
     }
@@ -4,12 +4,15 @@ use crate::parser::Parser;
 use crate::traits::Parse;
 use runestick::Span;
 
+/// The arguments of a closure.
 #[derive(Debug, Clone)]
 pub enum ExprClosureArgs {
+    /// Closure with no arguments, like `|| 42`.
     Empty {
         /// The `||` token.
         token: ast::Or,
     },
+    /// Closure with a list of arguments, like `|a, b| a + b`.
     List {
         /// The opening pipe for the argument group.
         open: ast::Pipe,
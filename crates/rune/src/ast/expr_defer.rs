@@ -0,0 +1,49 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
+use runestick::Span;
+
+/// A deferred block `defer { <expr>* }`, which runs when the enclosing block
+/// is exited.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// let expr = parse_all::<ast::ExprDefer>("defer { close(file); }").unwrap();
+/// assert_eq!(expr.body.exprs.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExprDefer {
+    /// The `defer` keyword.
+    pub defer: ast::Defer,
+    /// The block to run when the enclosing block is exited.
+    pub body: Box<ast::ExprBlock>,
+}
+
+impl ExprDefer {
+    /// Access the span of the expression.
+    pub fn span(&self) -> Span {
+        self.defer.span().join(self.body.span())
+    }
+}
+
+impl Parse for ExprDefer {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let defer = parser.parse()?;
+        let body: ast::ExprBlock = parser.parse()?;
+
+        if let Some(async_) = body.async_ {
+            return Err(ParseError::UnsupportedAsyncExpr {
+                span: async_.span(),
+            });
+        }
+
+        Ok(Self {
+            defer,
+            body: Box::new(body),
+        })
+    }
+}
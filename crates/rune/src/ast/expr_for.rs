@@ -1,4 +1,4 @@
-use crate::ast::{Colon, Expr, ExprBlock, For, Ident, In, Label};
+use crate::ast::{Await, Colon, Else, Expr, ExprBlock, ExprElse, For, Ident, In, Label};
 use crate::error::ParseError;
 use crate::parser::Parser;
 use crate::traits::Parse;
@@ -11,6 +11,8 @@ pub struct ExprFor {
     pub label: Option<(Label, Colon)>,
     /// The `for` keyword.
     pub for_: For,
+    /// The optional `await` keyword, used to iterate over a stream.
+    pub await_: Option<Await>,
     /// The variable binding.
     /// TODO: should be a pattern when that is supported.
     pub var: Ident,
@@ -20,12 +22,18 @@ pub struct ExprFor {
     pub iter: Box<Expr>,
     /// The body of the loop.
     pub body: Box<ExprBlock>,
+    /// An optional `else` block, evaluated if the loop completes without
+    /// breaking.
+    pub expr_else: Option<ExprElse>,
 }
 
 impl ExprFor {
     /// Access the span of the expression.
     pub fn span(&self) -> Span {
-        self.for_.token.span.join(self.body.span())
+        match &self.expr_else {
+            Some(expr_else) => self.for_.token.span.join(expr_else.span()),
+            None => self.for_.token.span.join(self.body.span()),
+        }
     }
 
     /// Parse with the given label.
@@ -36,10 +44,16 @@ impl ExprFor {
         Ok(Self {
             label,
             for_: parser.parse()?,
+            await_: parser.parse()?,
             var: parser.parse()?,
             in_: parser.parse()?,
             iter: Box::new(Expr::parse_without_eager_brace(parser)?),
             body: Box::new(parser.parse()?),
+            expr_else: if parser.peek::<Else>()? {
+                Some(parser.parse()?)
+            } else {
+                None
+            },
         })
     }
 }
@@ -0,0 +1,28 @@
+use crate::ast::{DotDot, Expr};
+use runestick::Span;
+
+/// A range expression `<from>..<to>`, where both bounds are optional.
+#[derive(Debug, Clone)]
+pub struct ExprRange {
+    /// The start of the range.
+    pub from: Option<Box<Expr>>,
+    /// The range limits.
+    pub limits: DotDot,
+    /// The end of the range.
+    pub to: Option<Box<Expr>>,
+}
+
+impl ExprRange {
+    /// Access the span of the expression.
+    pub fn span(&self) -> Span {
+        let span = match &self.from {
+            Some(from) => from.span().join(self.limits.span()),
+            None => self.limits.span(),
+        };
+
+        match &self.to {
+            Some(to) => span.join(to.span()),
+            None => span,
+        }
+    }
+}
@@ -15,12 +15,18 @@ pub struct ExprWhile {
     pub condition: ast::Condition,
     /// The body of the while loop.
     pub body: Box<ast::ExprBlock>,
+    /// An optional `else` block, evaluated if the loop completes without
+    /// breaking.
+    pub expr_else: Option<ast::ExprElse>,
 }
 
 impl ExprWhile {
     /// Access the span of the expression.
     pub fn span(&self) -> Span {
-        self.while_.token.span.join(self.body.span())
+        match &self.expr_else {
+            Some(expr_else) => self.while_.token.span.join(expr_else.span()),
+            None => self.while_.token.span.join(self.body.span()),
+        }
     }
 
     /// Parse with the given label.
@@ -33,6 +39,11 @@ impl ExprWhile {
             while_: parser.parse()?,
             condition: parser.parse()?,
             body: Box::new(parser.parse()?),
+            expr_else: if parser.peek::<ast::Else>()? {
+                Some(parser.parse()?)
+            } else {
+                None
+            },
         })
     }
 }
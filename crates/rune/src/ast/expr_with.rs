@@ -0,0 +1,81 @@
+use crate::ast;
+use crate::error::ParseError;
+use crate::parser::Parser;
+use crate::traits::Parse;
+use runestick::Span;
+
+/// A with expression `with <expr> [as <ident>] { <expr>* }`.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// let expr = parse_all::<ast::ExprWith>("with open(path) as f { f.read() }").unwrap();
+/// assert!(expr.binding.is_some());
+///
+/// let expr = parse_all::<ast::ExprWith>("with lock() { update() }").unwrap();
+/// assert!(expr.binding.is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExprWith {
+    /// The `with` keyword.
+    pub with: ast::With,
+    /// The expression producing the value to enter.
+    pub expr: Box<ast::Expr>,
+    /// The variable the entered value is bound to.
+    pub binding: Option<ast::Ident>,
+    /// The body of the with expression.
+    pub body: Box<ast::ExprBlock>,
+}
+
+impl ExprWith {
+    /// Access the span of the expression.
+    pub fn span(&self) -> Span {
+        self.with.span().join(self.body.span())
+    }
+}
+
+impl Parse for ExprWith {
+    fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
+        let with = parser.parse()?;
+        let expr = ast::Expr::parse_without_eager_brace(parser)?;
+
+        // NB: `<expr> as <ident>` parses as a cast, so we pick it apart.
+        let (expr, binding) = match expr {
+            ast::Expr::ExprBinary(ast::ExprBinary {
+                lhs,
+                op: ast::BinOp::As,
+                rhs,
+            }) => {
+                let ident = match &*rhs {
+                    ast::Expr::Path(path) => path.try_as_ident().copied(),
+                    _ => None,
+                };
+
+                match ident {
+                    Some(ident) => (lhs, Some(ident)),
+                    None => {
+                        return Err(ParseError::ExpectedWithBinding { span: rhs.span() });
+                    }
+                }
+            }
+            expr => (Box::new(expr), None),
+        };
+
+        let body: ast::ExprBlock = parser.parse()?;
+
+        if let Some(async_) = body.async_ {
+            return Err(ParseError::UnsupportedAsyncExpr {
+                span: async_.span(),
+            });
+        }
+
+        Ok(Self {
+            with,
+            expr,
+            binding,
+            body: Box::new(body),
+        })
+    }
+}
@@ -29,7 +29,8 @@ impl LitByte {
 /// parse_all::<ast::LitByte>("b'\\0'").unwrap();
 /// parse_all::<ast::LitByte>("b'\\n'").unwrap();
 /// parse_all::<ast::LitByte>("b'\\r'").unwrap();
-/// parse_all::<ast::LitByte>("b'\\\\''").unwrap();
+/// parse_all::<ast::LitByte>("b'\\\\'").unwrap();
+/// parse_all::<ast::LitByte>("b'\\''").unwrap();
 /// ```
 impl Parse for LitByte {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -59,7 +60,7 @@ impl<'a> Resolve<'a> for LitByte {
 
         let mut it = string
             .char_indices()
-            .map(|(n, c)| (span.start + n, c))
+            .map(|(n, c)| (span.start + 2 + n, c))
             .peekable();
 
         let (n, c) = match it.next() {
@@ -70,7 +71,7 @@ impl<'a> Resolve<'a> for LitByte {
         };
 
         let c = match c {
-            '\\' => ast::utils::parse_byte_escape(span.with_start(n), &mut it)?,
+            '\\' => ast::utils::parse_byte_escape(n, &mut it)?,
             c if c.is_ascii() && !c.is_control() => c as u8,
             _ => {
                 return Err(ParseError::BadByteLiteral { span });
@@ -31,10 +31,10 @@ impl LitByteStr {
             .peekable();
 
         while let Some((n, c)) = it.next() {
-            buffer.push(match c {
-                '\\' => ast::utils::parse_byte_escape(span.with_start(n), &mut it)?,
-                c => c as u8,
-            });
+            match c {
+                '\\' => buffer.push(ast::utils::parse_byte_escape(n, &mut it)?),
+                c => buffer.extend(c.encode_utf8(&mut [0; 4]).as_bytes()),
+            }
         }
 
         Ok(buffer)
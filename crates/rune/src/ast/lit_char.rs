@@ -57,7 +57,7 @@ impl<'a> Resolve<'a> for LitChar {
             .ok_or_else(|| ParseError::BadSlice { span })?;
         let mut it = string
             .char_indices()
-            .map(|(n, c)| (span.start + n, c))
+            .map(|(n, c)| (span.start + 1 + n, c))
             .peekable();
 
         let (n, c) = match it.next() {
@@ -68,11 +68,7 @@ impl<'a> Resolve<'a> for LitChar {
         };
 
         let c = match c {
-            '\\' => ast::utils::parse_char_escape(
-                span.with_start(n),
-                &mut it,
-                ast::utils::WithBrace(false),
-            )?,
+            '\\' => ast::utils::parse_char_escape(n, &mut it, ast::utils::WithBrace(false))?,
             c => c,
         };
 
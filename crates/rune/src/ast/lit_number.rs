@@ -2,7 +2,9 @@ use crate::ast;
 use crate::error::ParseError;
 use crate::parser::Parser;
 use crate::traits::{Parse, Resolve};
+use crate::{IntoTokens, MacroContext, TokenStream};
 use runestick::{Source, Span};
+use std::borrow::Cow;
 
 /// A resolved number literal.
 pub enum Number {
@@ -10,6 +12,8 @@ pub enum Number {
     Float(f64),
     /// An integer literal number.
     Integer(i64),
+    /// A decimal literal number, like `1.05d`.
+    Decimal(runestick::Decimal),
 }
 
 /// A number literal.
@@ -43,6 +47,10 @@ impl LitNumber {
 /// parse_all::<ast::LitNumber>("42.42").unwrap();
 /// parse_all::<ast::LitNumber>("0.42").unwrap();
 /// parse_all::<ast::LitNumber>("0.42e10").unwrap();
+/// parse_all::<ast::LitNumber>("1e-9").unwrap();
+/// parse_all::<ast::LitNumber>("1_000_000").unwrap();
+/// parse_all::<ast::LitNumber>("0xff_ff").unwrap();
+/// parse_all::<ast::LitNumber>("1.05d").unwrap();
 /// ```
 impl Parse for LitNumber {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -70,6 +78,12 @@ impl Parse for LitNumber {
     }
 }
 
+impl IntoTokens for &LitNumber {
+    fn into_tokens(self, _: &mut MacroContext, stream: &mut TokenStream) {
+        stream.push(self.token);
+    }
+}
+
 impl<'a> Resolve<'a> for LitNumber {
     type Output = Number;
 
@@ -90,8 +104,34 @@ impl<'a> Resolve<'a> for LitNumber {
             string
         };
 
+        // underscores are only used to separate digits, like in `1_000_000`.
+        let string = if string.contains('_') {
+            Cow::Owned(string.replace('_', ""))
+        } else {
+            Cow::Borrowed(string)
+        };
+
+        if let (ast::NumberKind::Decimal, Some(string)) = (self.number, string.strip_suffix('d')) {
+            let number = runestick::Decimal::from_str(string).map_err(err_span(span))?;
+            let number = if self.is_negative {
+                runestick::Decimal::from(0)
+                    .checked_sub(number)
+                    .ok_or(ParseError::BadNumberOutOfBounds { span })?
+            } else {
+                number
+            };
+
+            return Ok(Number::Decimal(number));
+        }
+
         if self.is_fractional {
-            let number = f64::from_str(string).map_err(err_span(span))?;
+            let number = f64::from_str(&string).map_err(err_span(span))?;
+
+            if !number.is_finite() {
+                return Err(ParseError::BadNumberOutOfBounds { span });
+            }
+
+            let number = if self.is_negative { -number } else { number };
             return Ok(Number::Float(number));
         }
 
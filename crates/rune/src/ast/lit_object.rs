@@ -90,6 +90,50 @@ impl Parse for LitObjectFieldAssign {
     }
 }
 
+/// A literal object field with a computed key, like `[key]: value`.
+#[derive(Debug, Clone)]
+pub struct LitObjectComputedAssign {
+    /// The open bracket.
+    pub open: ast::OpenBracket,
+    /// The expression producing the key.
+    pub key: ast::Expr,
+    /// The close bracket.
+    pub close: ast::CloseBracket,
+    /// The colon separating the key from the value.
+    pub colon: ast::Colon,
+    /// The assigned expression of the field.
+    pub expr: ast::Expr,
+}
+
+impl LitObjectComputedAssign {
+    /// Get the span of the assignment.
+    pub fn span(&self) -> Span {
+        self.open.span().join(self.expr.span())
+    }
+}
+
+/// Parse an object field with a computed key.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::{parse_all, ast};
+///
+/// parse_all::<ast::LitObjectComputedAssign>("[key]: 42").unwrap();
+/// parse_all::<ast::LitObjectComputedAssign>("[`{prefix}_foo`]: 42").unwrap();
+/// ```
+impl Parse for LitObjectComputedAssign {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        Ok(Self {
+            open: parser.parse()?,
+            key: parser.parse()?,
+            close: parser.parse()?,
+            colon: parser.parse()?,
+            expr: parser.parse()?,
+        })
+    }
+}
+
 /// Possible literal object keys.
 #[derive(Debug, Clone)]
 pub enum LitObjectKey {
@@ -156,6 +200,12 @@ pub struct LitObject {
     pub open: ast::OpenBrace,
     /// Items in the object declaration.
     pub assignments: Vec<LitObjectFieldAssign>,
+    /// Items with computed keys in the object declaration. These are inserted
+    /// into the object after it has been constructed.
+    pub computed: Vec<LitObjectComputedAssign>,
+    /// The instance which the remaining fields are copied from, as in
+    /// `Foo { a: 1, ..other }`.
+    pub base: Option<(ast::DotDot, Box<ast::Expr>)>,
     /// The close bracket.
     pub close: ast::CloseBrace,
     /// Indicates if the object is completely literal and cannot have side
@@ -182,17 +232,32 @@ impl LitObject {
         let open = parser.parse()?;
 
         let mut assignments = Vec::new();
+        let mut computed = Vec::new();
+        let mut base = None;
 
         let mut is_const = true;
 
         while !parser.peek::<ast::CloseBrace>()? {
-            let assign = parser.parse::<LitObjectFieldAssign>()?;
-
-            if !assign.is_const() {
+            // NB: the base has to come last, so it can't be followed by a
+            // comma.
+            if parser.peek::<ast::DotDot>()? {
+                base = Some((parser.parse()?, parser.parse()?));
                 is_const = false;
+                break;
             }
 
-            assignments.push(assign);
+            if parser.peek::<ast::OpenBracket>()? {
+                computed.push(parser.parse::<LitObjectComputedAssign>()?);
+                is_const = false;
+            } else {
+                let assign = parser.parse::<LitObjectFieldAssign>()?;
+
+                if !assign.is_const() {
+                    is_const = false;
+                }
+
+                assignments.push(assign);
+            }
 
             if parser.peek::<ast::Comma>()? {
                 parser.parse::<ast::Comma>()?;
@@ -207,6 +272,8 @@ impl LitObject {
             ident,
             open,
             assignments,
+            computed,
+            base,
             close,
             is_const,
         })
@@ -223,6 +290,9 @@ impl LitObject {
 /// parse_all::<ast::LitObject>("Foo {\"foo\": 42}").unwrap();
 /// parse_all::<ast::LitObject>("#{\"foo\": 42}").unwrap();
 /// parse_all::<ast::LitObject>("#{\"foo\": 42,}").unwrap();
+/// parse_all::<ast::LitObject>("#{foo, [bar]: 42}").unwrap();
+/// parse_all::<ast::LitObject>("Foo {\"foo\": 42, ..bar}").unwrap();
+/// parse_all::<ast::LitObject>("Foo {..bar}").unwrap();
 /// ```
 impl Parse for LitObject {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
@@ -12,6 +12,8 @@ pub struct LitStr {
     token: ast::Token,
     /// If the string literal is escaped.
     escaped: bool,
+    /// The kind of the string literal.
+    kind: ast::StrKind,
 }
 
 impl LitStr {
@@ -31,28 +33,91 @@ impl LitStr {
 
         while let Some((n, c)) = it.next() {
             buffer.push(match c {
-                '\\' => ast::utils::parse_char_escape(
-                    span.with_start(n),
-                    &mut it,
-                    ast::utils::WithBrace(false),
-                )?,
+                '\\' => ast::utils::parse_char_escape(n, &mut it, ast::utils::WithBrace(false))?,
                 c => c,
             });
         }
 
         Ok(buffer)
     }
+
+    /// Resolve a multiline string literal, stripping the common indentation
+    /// of all its lines.
+    ///
+    /// A first line which only contains whitespace is skipped, and so is the
+    /// last line if it only contains the indentation of the closing delimiter.
+    fn resolve_multiline(&self, span: Span, source: &str) -> Result<String, ParseError> {
+        let mut lines = Vec::new();
+        let mut offset = span.start;
+
+        for line in source.split('\n') {
+            lines.push((offset, line.trim_end_matches('\r')));
+            offset += line.len() + 1;
+        }
+
+        if let Some((_, first)) = lines.first() {
+            if first.trim().is_empty() {
+                lines.remove(0);
+            }
+        }
+
+        let closing = match lines.last() {
+            Some((_, last)) if last.trim().is_empty() => lines.pop(),
+            _ => None,
+        };
+
+        let indent = lines
+            .iter()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .chain(closing.iter())
+            .map(|(_, line)| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or_default();
+
+        let mut buffer = String::with_capacity(source.len());
+        let mut it = lines.into_iter().peekable();
+
+        while let Some((start, line)) = it.next() {
+            let strip = usize::min(indent, line.len() - line.trim_start().len());
+            let line = &line[strip..];
+
+            if self.escaped {
+                let start = start + strip;
+                let span = Span::new(start, start + line.len());
+                buffer.push_str(&self.parse_escaped(span, line)?);
+            } else {
+                buffer.push_str(line);
+            }
+
+            if it.peek().is_some() || closing.is_some() {
+                buffer.push('\n');
+            }
+        }
+
+        Ok(buffer)
+    }
 }
 
 impl<'a> Resolve<'a> for LitStr {
     type Output = Cow<'a, str>;
 
     fn resolve(&self, source: &'a Source) -> Result<Cow<'a, str>, ParseError> {
-        let span = self.token.span.narrow(1);
+        let span = self.token.span;
+
+        let span = match self.kind {
+            ast::StrKind::Normal => span.narrow(1),
+            ast::StrKind::Raw(hashes) => Span::new(span.start + 2 + hashes, span.end - 1 - hashes),
+            ast::StrKind::Multiline => span.narrow(3),
+        };
+
         let string = source
             .source(span)
             .ok_or_else(|| ParseError::BadSlice { span })?;
 
+        if let ast::StrKind::Multiline = self.kind {
+            return Ok(Cow::Owned(self.resolve_multiline(span, string)?));
+        }
+
         Ok(if self.escaped {
             Cow::Owned(self.parse_escaped(span, string)?)
         } else {
@@ -70,13 +135,19 @@ impl<'a> Resolve<'a> for LitStr {
 ///
 /// let item = parse_all::<ast::LitStr>("\"hello world\"").unwrap();
 /// let item = parse_all::<ast::LitStr>("\"hello\\nworld\"").unwrap();
+/// let item = parse_all::<ast::LitStr>("r#\"C:\\Users\"#").unwrap();
+/// let item = parse_all::<ast::LitStr>("\"\"\"\n  hello\n  \"\"\"").unwrap();
 /// ```
 impl Parse for LitStr {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
         let token = parser.token_next()?;
 
         match token.kind {
-            ast::Kind::LitStr { escaped } => Ok(LitStr { token, escaped }),
+            ast::Kind::LitStr { escaped, kind } => Ok(LitStr {
+                token,
+                escaped,
+                kind,
+            }),
             _ => Err(ParseError::ExpectedString {
                 actual: token.kind,
                 span: token.span,
@@ -24,9 +24,29 @@ impl LitTemplate {
 #[derive(Debug)]
 pub enum TemplateComponent {
     /// A literal string.
-    String(String),
+    String {
+        /// The span of the string in the source, including any escapes.
+        span: Span,
+        /// The resolved string.
+        string: String,
+    },
     /// An expression inside of the template. Like `{1 + 2}`.
-    Expr(Box<ast::Expr>),
+    Expr {
+        /// The span of the expansion, including its braces.
+        span: Span,
+        /// The expanded expression.
+        expr: Box<ast::Expr>,
+    },
+}
+
+impl TemplateComponent {
+    /// Access the span of the component.
+    pub fn span(&self) -> Span {
+        match self {
+            Self::String { span, .. } => *span,
+            Self::Expr { span, .. } => *span,
+        }
+    }
 }
 
 /// A resolved and parsed string template.
@@ -37,6 +57,19 @@ pub struct Template {
     pub(crate) components: Vec<TemplateComponent>,
 }
 
+impl Template {
+    /// Test if the template has any expansions.
+    pub fn has_expansions(&self) -> bool {
+        self.has_expansions
+    }
+
+    /// Access the components of the template, in the order in which they
+    /// appear in the source.
+    pub fn components(&self) -> &[TemplateComponent] {
+        &self.components
+    }
+}
+
 impl<'a> Resolve<'a> for LitTemplate {
     type Output = Template;
 
@@ -54,32 +87,51 @@ impl<'a> Resolve<'a> for LitTemplate {
         let mut has_expansions = false;
         let mut size_hint = 0;
         let mut buf = String::new();
+        let mut buf_start = span.start;
 
         let mut components = Vec::new();
 
-        while let Some((_, c)) = it.next() {
+        while let Some((n, c)) = it.next() {
             match c {
                 '\\' => {
-                    let c =
-                        ast::utils::parse_char_escape(span, &mut it, ast::utils::WithBrace(true))?;
+                    let c = ast::utils::parse_char_escape(n, &mut it, ast::utils::WithBrace(true))?;
                     buf.push(c);
                 }
+                '{' if matches!(it.peek(), Some((_, '{'))) => {
+                    it.next();
+                    buf.push('{');
+                }
+                '}' if matches!(it.peek(), Some((_, '}'))) => {
+                    it.next();
+                    buf.push('}');
+                }
                 '}' => {
                     return Err(ParseError::UnexpectedCloseBrace { span });
                 }
                 '{' => {
                     if !buf.is_empty() {
                         size_hint += buf.len();
-                        components.push(TemplateComponent::String(buf.clone()));
-                        buf.clear();
+
+                        components.push(TemplateComponent::String {
+                            span: Span::new(buf_start, n),
+                            string: std::mem::take(&mut buf),
+                        });
                     }
 
-                    let span = ast::utils::template_expr(span, &mut it)?;
-                    let source = &source.as_str()[..span.end];
+                    let expr_span = ast::utils::template_expr(span, &mut it)?;
+                    let source = &source.as_str()[..expr_span.end];
 
-                    let mut parser = Parser::new_with_start(source, span.start);
+                    let mut parser = Parser::new_with_start(source, expr_span.start);
                     let expr = ast::Expr::parse(&mut parser)?;
-                    components.push(TemplateComponent::Expr(Box::new(expr)));
+
+                    // NB: the expression span ends at the closing brace.
+                    buf_start = expr_span.end + 1;
+
+                    components.push(TemplateComponent::Expr {
+                        span: Span::new(n, buf_start),
+                        expr: Box::new(expr),
+                    });
+
                     has_expansions = true;
                 }
                 c => {
@@ -90,8 +142,11 @@ impl<'a> Resolve<'a> for LitTemplate {
 
         if !buf.is_empty() {
             size_hint += buf.len();
-            components.push(TemplateComponent::String(buf.clone()));
-            buf.clear();
+
+            components.push(TemplateComponent::String {
+                span: Span::new(buf_start, span.end),
+                string: buf,
+            });
         }
 
         Ok(Template {
@@ -111,6 +166,7 @@ impl<'a> Resolve<'a> for LitTemplate {
 ///
 /// parse_all::<ast::LitTemplate>("`hello world`").unwrap();
 /// parse_all::<ast::LitTemplate>("`hello\\n world`").unwrap();
+/// parse_all::<ast::LitTemplate>("`{{hello}} {`world {1 + 2}`}`").unwrap();
 /// ```
 impl Parse for LitTemplate {
     fn parse(parser: &mut Parser<'_>) -> Result<Self, ParseError> {
@@ -5,12 +5,15 @@ use crate::parser::Parser;
 use crate::traits::{Parse, Peek, Resolve};
 use runestick::{Source, Span};
 
+mod attribute;
 mod condition;
 mod decl;
+mod decl_const;
 mod decl_enum;
 mod decl_file;
 mod decl_fn;
 mod decl_impl;
+mod decl_let;
 mod decl_mod;
 mod decl_struct;
 mod decl_use;
@@ -22,6 +25,8 @@ mod expr_break;
 mod expr_call;
 mod expr_call_macro;
 mod expr_closure;
+mod expr_continue;
+mod expr_defer;
 mod expr_else;
 mod expr_else_if;
 mod expr_field_access;
@@ -35,11 +40,13 @@ mod expr_is_not;
 mod expr_let;
 mod expr_loop;
 mod expr_match;
+mod expr_range;
 mod expr_return;
 mod expr_select;
 mod expr_try;
 mod expr_unary;
 mod expr_while;
+mod expr_with;
 mod expr_yield;
 mod fn_arg;
 mod lit_bool;
@@ -62,16 +69,22 @@ mod pat_vec;
 mod path;
 mod token;
 pub(super) mod utils;
+#[macro_use]
+pub mod visit;
+pub mod visit_mut;
 
+pub use self::attribute::{Attribute, Meta, MetaList, MetaNameValue, MetaValue};
 pub use self::condition::Condition;
 pub use self::decl::Decl;
-pub use self::decl_enum::DeclEnum;
+pub use self::decl_const::DeclConst;
+pub use self::decl_enum::{DeclEnum, EnumDiscriminant};
 pub use self::decl_file::DeclFile;
 pub use self::decl_fn::DeclFn;
 pub use self::decl_impl::DeclImpl;
+pub use self::decl_let::DeclLet;
 pub use self::decl_mod::{DeclMod, DeclModBody};
 pub use self::decl_struct::{DeclStruct, DeclStructBody, EmptyBody, StructBody, TupleBody};
-pub use self::decl_use::{DeclUse, DeclUseComponent};
+pub use self::decl_use::{DeclUse, DeclUseComponent, DeclUseGroup, DeclUsePath};
 pub use self::expr::Expr;
 pub use self::expr_await::ExprAwait;
 pub use self::expr_binary::{BinOp, ExprBinary};
@@ -79,7 +92,9 @@ pub use self::expr_block::ExprBlock;
 pub use self::expr_break::{ExprBreak, ExprBreakValue};
 pub use self::expr_call::ExprCall;
 pub use self::expr_call_macro::ExprCallMacro;
-pub use self::expr_closure::ExprClosure;
+pub use self::expr_closure::{ExprClosure, ExprClosureArgs};
+pub use self::expr_continue::ExprContinue;
+pub use self::expr_defer::ExprDefer;
 pub use self::expr_else::ExprElse;
 pub use self::expr_else_if::ExprElseIf;
 pub use self::expr_field_access::{ExprField, ExprFieldAccess};
@@ -93,11 +108,13 @@ pub use self::expr_is_not::ExprIsNot;
 pub use self::expr_let::ExprLet;
 pub use self::expr_loop::ExprLoop;
 pub use self::expr_match::{ExprMatch, ExprMatchBranch};
+pub use self::expr_range::ExprRange;
 pub use self::expr_return::ExprReturn;
-pub use self::expr_select::ExprSelect;
+pub use self::expr_select::{ExprDefaultBranch, ExprSelect, ExprSelectBranch};
 pub use self::expr_try::ExprTry;
 pub use self::expr_unary::{ExprUnary, UnaryOp};
 pub use self::expr_while::ExprWhile;
+pub use self::expr_with::ExprWith;
 pub use self::expr_yield::ExprYield;
 pub use self::fn_arg::FnArg;
 pub use self::lit_bool::LitBool;
@@ -105,7 +122,9 @@ pub use self::lit_byte::LitByte;
 pub use self::lit_byte_str::LitByteStr;
 pub use self::lit_char::LitChar;
 pub use self::lit_number::{LitNumber, Number};
-pub use self::lit_object::{LitObject, LitObjectFieldAssign, LitObjectIdent, LitObjectKey};
+pub use self::lit_object::{
+    LitObject, LitObjectComputedAssign, LitObjectFieldAssign, LitObjectIdent, LitObjectKey,
+};
 pub use self::lit_str::LitStr;
 pub use self::lit_template::{LitTemplate, Template, TemplateComponent};
 pub use self::lit_tuple::LitTuple;
@@ -118,7 +137,7 @@ pub use self::pat_path::PatPath;
 pub use self::pat_tuple::PatTuple;
 pub use self::pat_vec::PatVec;
 pub use self::path::Path;
-pub use self::token::{Delimiter, Kind, NumberKind, Token};
+pub use self::token::{Delimiter, Kind, NumberKind, StrKind, Token};
 
 macro_rules! decl_tokens {
     ($(($parser:ident, $doc:expr, $($kind:tt)*),)*) => {
@@ -204,6 +223,7 @@ decl_tokens! {
     (For, "The `for` keyword.", Kind::For),
     (In, "The `in` keyword.", Kind::In),
     (Break, "The `break` keyword.", Kind::Break),
+    (Continue, "The `continue` keyword.", Kind::Continue),
     (Yield, "The `yield` keyword.", Kind::Yield),
     (Return, "The `return` keyword.", Kind::Return),
     (Rocket, "The rocket `=>`.", Kind::Rocket),
@@ -220,6 +240,10 @@ decl_tokens! {
     (Impl, "The `impl` keyword", Kind::Impl),
     (Mul, "Multiply `*` operator.", Kind::Star),
     (Mod, "The `mod` keyword.", Kind::Mod),
+    (Const, "The `const` keyword.", Kind::Const),
+    (Defer, "The `defer` keyword.", Kind::Defer),
+    (With, "The `with` keyword.", Kind::With),
+    (As, "The `as` keyword.", Kind::As),
     (Bang, "The `!` operator.", Kind::Bang),
 }
 
@@ -17,6 +17,20 @@ impl crate::IntoTokens for Token {
     }
 }
 
+/// The kind of a string literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StrKind {
+    /// A regular string literal, like `"hello\nworld"`.
+    Normal,
+    /// A raw string literal with the given number of `#` delimiters, like
+    /// `r"C:\Users"` or `r#"say "hi""#`. Escape sequences are not processed.
+    Raw(usize),
+    /// A multiline string literal, like `"""\n    hello\n    """`.
+    ///
+    /// Common leading indentation is stripped from every line.
+    Multiline,
+}
+
 /// The kind of a number literal.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum NumberKind {
@@ -113,6 +127,8 @@ pub enum Kind {
     False,
     /// A `break` token.
     Break,
+    /// A `continue` token.
+    Continue,
     /// A `yield` token.
     Yield,
     /// A `return` token.
@@ -129,6 +145,14 @@ pub enum Kind {
     Impl,
     /// The `mod` keyword.
     Mod,
+    /// The `const` keyword.
+    Const,
+    /// The `defer` keyword.
+    Defer,
+    /// The `with` keyword.
+    With,
+    /// The `as` keyword.
+    As,
     /// An identifier.
     Ident,
     /// A label, like `'loop`.
@@ -150,6 +174,8 @@ pub enum Kind {
     LitStr {
         /// If the string literal contains escapes.
         escaped: bool,
+        /// The kind of the string literal.
+        kind: StrKind,
     },
     /// A byte string literal, including escape sequences. Like `b"hello\nworld"`.
     LitByteStr {
@@ -269,6 +295,7 @@ impl fmt::Display for Kind {
             Self::True => write!(f, "true")?,
             Self::False => write!(f, "false")?,
             Self::Break => write!(f, "break")?,
+            Self::Continue => write!(f, "continue")?,
             Self::Yield => write!(f, "yield")?,
             Self::Return => write!(f, "return")?,
             Self::Await => write!(f, "await")?,
@@ -277,6 +304,10 @@ impl fmt::Display for Kind {
             Self::Default => write!(f, "default")?,
             Self::Impl => write!(f, "impl")?,
             Self::Mod => write!(f, "mod")?,
+            Self::Const => write!(f, "const")?,
+            Self::Defer => write!(f, "defer")?,
+            Self::With => write!(f, "with")?,
+            Self::As => write!(f, "as")?,
             Self::Ident => write!(f, "ident")?,
             Self::Label => write!(f, "label")?,
             Self::LitNumber { .. } => write!(f, "number")?,
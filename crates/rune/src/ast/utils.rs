@@ -16,170 +16,179 @@ impl ops::Deref for WithBrace {
     }
 }
 
-/// Parse a byte escape sequence.
-pub(super) fn parse_byte_escape<I>(span: Span, it: &mut Peekable<I>) -> Result<u8, ParseError>
+/// A parsed escape sequence.
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Escape {
+    /// An escaped character, like `\n` or `\u{1F600}`.
+    Char(char),
+    /// An escaped byte, like `\xff`.
+    Byte(u8),
+}
+
+/// Iterator adapter used while parsing escape sequences, which keeps track of
+/// the span of the escape sequence being parsed.
+struct EscapeIter<'a, I>
 where
     I: Iterator<Item = (usize, char)>,
 {
-    let (n, c) = match it.next() {
+    it: &'a mut Peekable<I>,
+    start: usize,
+    end: usize,
+}
+
+impl<I> EscapeIter<'_, I>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    fn next(&mut self) -> Option<char> {
+        let (n, c) = self.it.next()?;
+        self.end = n + c.len_utf8();
+        Some(c)
+    }
+
+    /// The span of the escape sequence parsed so far.
+    fn span(&self) -> Span {
+        Span::new(self.start, self.end)
+    }
+}
+
+/// Parse an escape sequence, where `start` is the position of the backslash
+/// which has already been consumed.
+///
+/// This is shared by all literals which support escapes. The returned span
+/// covers exactly the escape sequence, which is also the span used for any
+/// errors raised.
+pub(super) fn parse_escape<I>(
+    start: usize,
+    it: &mut Peekable<I>,
+    with_brace: WithBrace,
+) -> Result<(Escape, Span), ParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let mut it = EscapeIter {
+        it,
+        start,
+        end: start + 1,
+    };
+
+    let c = match it.next() {
         Some(c) => c,
         None => {
-            return Err(ParseError::BadEscapeSequence { span });
+            return Err(ParseError::BadEscapeSequence { span: it.span() });
         }
     };
 
-    Ok(match c {
-        '\'' => b'\'',
-        '\"' => b'\"',
-        'n' => b'\n',
-        'r' => b'\r',
-        't' => b'\t',
-        '\\' => b'\\',
-        '0' => b'\0',
-        'x' => {
-            let (result, span) = parse_hex_escape(span, it)?;
-
-            if result > 0xff {
-                return Err(ParseError::UnsupportedByteEscape { span });
-            }
-
-            result as u8
-        }
-        'u' => {
-            return Err(ParseError::UnicodeEscapeNotSupported { span });
-        }
+    let escape = match c {
+        '{' if *with_brace => Escape::Char('{'),
+        '}' if *with_brace => Escape::Char('}'),
+        '\'' => Escape::Char('\''),
+        '\"' => Escape::Char('\"'),
+        'n' => Escape::Char('\n'),
+        'r' => Escape::Char('\r'),
+        't' => Escape::Char('\t'),
+        '\\' => Escape::Char('\\'),
+        '0' => Escape::Char('\0'),
+        'x' => Escape::Byte(parse_hex_escape(&mut it)?),
+        'u' => Escape::Char(parse_unicode_escape(&mut it)?),
         _ => {
-            let span = span.with_end(n);
-            return Err(ParseError::BadEscapeSequence { span });
+            return Err(ParseError::BadEscapeSequence { span: it.span() });
         }
-    })
+    };
+
+    Ok((escape, it.span()))
 }
 
-/// Parse a byte escape sequence.
+/// Parse an escape sequence in a character, string, or template literal.
+///
+/// Byte escapes like `\x7f` are only permitted if they are ASCII.
 pub(super) fn parse_char_escape<I>(
-    span: Span,
+    start: usize,
     it: &mut Peekable<I>,
     with_brace: WithBrace,
 ) -> Result<char, ParseError>
 where
     I: Iterator<Item = (usize, char)>,
 {
-    let (n, c) = match it.next() {
-        Some(c) => c,
-        None => {
-            return Err(ParseError::BadEscapeSequence { span });
-        }
-    };
+    match parse_escape(start, it, with_brace)? {
+        (Escape::Char(c), _) => Ok(c),
+        (Escape::Byte(b), _) if b.is_ascii() => Ok(b as char),
+        (Escape::Byte(_), span) => Err(ParseError::UnsupportedUnicodeByteEscape { span }),
+    }
+}
 
-    Ok(match c {
-        '{' if *with_brace => '{',
-        '}' if *with_brace => '}',
-        '\'' => '\'',
-        '\"' => '\"',
-        'n' => '\n',
-        'r' => '\r',
-        't' => '\t',
-        '\\' => '\\',
-        '0' => '\0',
-        'x' => {
-            let (result, span) = parse_hex_escape(span, it)?;
-
-            if result > 0x7f {
-                return Err(ParseError::UnsupportedUnicodeByteEscape { span });
-            }
+/// Parse an escape sequence in a byte or byte string literal.
+///
+/// Unicode escapes like `\u{7f}` are not permitted.
+pub(super) fn parse_byte_escape<I>(start: usize, it: &mut Peekable<I>) -> Result<u8, ParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    let unicode = matches!(it.peek(), Some((_, 'u')));
 
-            if let Some(c) = std::char::from_u32(result) {
-                c
-            } else {
-                return Err(ParseError::BadByteEscape { span });
-            }
-        }
-        'u' => parse_unicode_escape(span, it)?,
-        _ => {
-            let span = span.with_end(n);
-            return Err(ParseError::BadEscapeSequence { span });
-        }
-    })
+    match parse_escape(start, it, WithBrace(false))? {
+        (_, span) if unicode => Err(ParseError::UnicodeEscapeNotSupported { span }),
+        (Escape::Byte(b), _) => Ok(b),
+        // NB: every other character escape is ASCII.
+        (Escape::Char(c), _) => Ok(c as u8),
+    }
 }
 
-/// Parse a hex escape.
-fn parse_hex_escape<I>(span: Span, it: &mut Peekable<I>) -> Result<(u32, Span), ParseError>
+/// Parse a hex escape, like `\xff`.
+fn parse_hex_escape<I>(it: &mut EscapeIter<'_, I>) -> Result<u8, ParseError>
 where
     I: Iterator<Item = (usize, char)>,
 {
-    let mut result = 0u32;
+    let mut result = 0u8;
 
     for _ in 0..2 {
-        let (_, c) = it
+        let c = it
             .next()
-            .ok_or_else(|| ParseError::BadByteEscape { span })?;
+            .ok_or_else(|| ParseError::BadByteEscape { span: it.span() })?;
 
-        let span = it.peek().map(|(n, _)| span.with_end(*n)).unwrap_or(span);
+        let digit = c
+            .to_digit(16)
+            .ok_or_else(|| ParseError::BadByteEscape { span: it.span() })?;
 
-        result = result
-            .checked_shl(4)
-            .ok_or_else(|| ParseError::BadByteEscape { span })?;
-
-        result += match c {
-            '0'..='9' => c as u32 - '0' as u32,
-            'a'..='f' => c as u32 - 'a' as u32 + 10,
-            'A'..='F' => c as u32 - 'A' as u32 + 10,
-            _ => return Err(ParseError::BadByteEscape { span }),
-        };
+        result = (result << 4) | digit as u8;
     }
 
-    let span = it.peek().map(|(n, _)| span.with_end(*n)).unwrap_or(span);
-    Ok((result, span))
+    Ok(result)
 }
 
-/// Parse a unicode escape.
-pub(super) fn parse_unicode_escape<I>(span: Span, it: &mut Peekable<I>) -> Result<char, ParseError>
+/// Parse a unicode escape, like `\u{1F600}`.
+fn parse_unicode_escape<I>(it: &mut EscapeIter<'_, I>) -> Result<char, ParseError>
 where
     I: Iterator<Item = (usize, char)>,
 {
     match it.next() {
-        Some((_, '{')) => (),
-        _ => return Err(ParseError::BadUnicodeEscape { span }),
+        Some('{') => (),
+        _ => return Err(ParseError::BadUnicodeEscape { span: it.span() }),
     };
 
-    let mut first = true;
+    let mut digits = 0;
     let mut result = 0u32;
 
     loop {
-        let (_, c) = it
+        let c = it
             .next()
-            .ok_or_else(|| ParseError::BadUnicodeEscape { span })?;
-
-        let span = it.peek().map(|(n, _)| span.with_end(*n)).unwrap_or(span);
+            .ok_or_else(|| ParseError::BadUnicodeEscape { span: it.span() })?;
 
         match c {
-            '}' => {
-                if first {
-                    return Err(ParseError::BadUnicodeEscape { span });
-                }
-
-                if let Some(c) = std::char::from_u32(result) {
-                    return Ok(c);
-                }
-
-                return Err(ParseError::BadUnicodeEscape { span });
+            '}' if digits > 0 => {
+                return std::char::from_u32(result)
+                    .ok_or_else(|| ParseError::BadUnicodeEscape { span: it.span() });
             }
-            c => {
-                first = false;
-
-                result = result
-                    .checked_shl(4)
-                    .ok_or_else(|| ParseError::BadUnicodeEscape { span })?;
-
-                result += match c {
-                    '0'..='9' => c as u32 - '0' as u32,
-                    'a'..='f' => c as u32 - 'a' as u32 + 10,
-                    'A'..='F' => c as u32 - 'A' as u32 + 10,
-                    _ => {
-                        return Err(ParseError::BadUnicodeEscape { span });
-                    }
-                };
+            // a unicode escape is at most six hex digits long.
+            c if digits < 6 => {
+                let digit = c
+                    .to_digit(16)
+                    .ok_or_else(|| ParseError::BadUnicodeEscape { span: it.span() })?;
+
+                digits += 1;
+                result = (result << 4) | digit;
             }
+            _ => return Err(ParseError::BadUnicodeEscape { span: it.span() }),
         }
     }
 }
@@ -187,9 +196,11 @@ where
 /// Find the span of an expression inside of a balanced collection of braces.
 ///
 /// This is expected to start parsing immediately after an opening brace `{`.
+/// String, character, and nested template literals inside of the expression
+/// are skipped over, so that any braces they contain are not counted.
 pub(crate) fn template_expr<I>(span: Span, it: &mut I) -> Result<Span, ParseError>
 where
-    I: Iterator<Item = (usize, char)>,
+    I: Clone + Iterator<Item = (usize, char)>,
 {
     let mut start = None;
     let mut level = 1;
@@ -206,6 +217,9 @@ where
         match c {
             '{' => level += 1,
             '}' => level -= 1,
+            '"' => skip_delimited(span, it, '"')?,
+            '`' => skip_template(span, it)?,
+            '\'' => skip_char(it),
             _ => (),
         }
 
@@ -216,6 +230,76 @@ where
     }
 }
 
+/// Skip over the remainder of a literal which is terminated by an unescaped
+/// `end` character.
+fn skip_delimited<I>(span: Span, it: &mut I, end: char) -> Result<(), ParseError>
+where
+    I: Iterator<Item = (usize, char)>,
+{
+    loop {
+        match it.next() {
+            Some((_, '\\')) => {
+                it.next();
+            }
+            Some((_, c)) if c == end => return Ok(()),
+            Some(..) => (),
+            None => return Err(ParseError::InvalidTemplateLiteral { span }),
+        }
+    }
+}
+
+/// Skip over the remainder of a nested template literal, including any
+/// expansions it contains.
+fn skip_template<I>(span: Span, it: &mut I) -> Result<(), ParseError>
+where
+    I: Clone + Iterator<Item = (usize, char)>,
+{
+    loop {
+        match it.next() {
+            Some((_, '\\')) => {
+                it.next();
+            }
+            Some((_, '`')) => return Ok(()),
+            Some((_, '{')) => {
+                if let Some((_, '{')) = it.clone().next() {
+                    it.next();
+                } else {
+                    template_expr(span, it)?;
+                }
+            }
+            Some(..) => (),
+            None => return Err(ParseError::InvalidTemplateLiteral { span }),
+        }
+    }
+}
+
+/// Skip over the remainder of a character literal like `'}'`, leaving labels
+/// like `'outer` alone.
+fn skip_char<I>(it: &mut I)
+where
+    I: Clone + Iterator<Item = (usize, char)>,
+{
+    let mut peek = it.clone();
+
+    match (peek.next(), peek.next()) {
+        (Some((_, '\\')), _) => {
+            it.next();
+            it.next();
+
+            for (_, c) in it {
+                if c == '\'' {
+                    break;
+                }
+            }
+        }
+        (Some(..), Some((_, '\''))) => {
+            it.next();
+            it.next();
+        }
+        _ => (),
+    }
+}
+
 /// Test if the given expression qualifieis as a block end or not, as with a
 /// body in a match expression.
 ///
@@ -234,31 +318,44 @@ pub(crate) fn is_block_end(expr: &ast::Expr, comma: Option<&ast::Comma>) -> bool
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_hex_escape, parse_unicode_escape};
+    use super::{parse_escape, Escape, WithBrace};
+    use crate::error::ParseError;
     use runestick::Span;
 
+    /// Construct an iterator over the escape sequence following a backslash
+    /// at offset `0`.
     macro_rules! input {
         ($string:expr) => {
-            &mut String::from($string).char_indices().peekable()
+            &mut String::from($string)
+                .char_indices()
+                .map(|(n, c)| (n + 1, c))
+                .peekable()
         };
     }
 
     #[test]
     fn test_parse_hex_escape() {
-        assert!(parse_hex_escape(Span::empty(), input!("a")).is_err());
+        assert!(parse_escape(0, input!("xa"), WithBrace(false)).is_err());
 
-        let (c, _) = parse_hex_escape(Span::empty(), input!("7f")).unwrap();
-        assert_eq!(c, 0x7f);
+        let (e, span) = parse_escape(0, input!("x7f"), WithBrace(false)).unwrap();
+        assert!(matches!(e, Escape::Byte(0x7f)));
+        assert_eq!(span, Span::new(0, 4));
     }
 
     #[test]
     fn test_parse_unicode_escape() {
-        parse_unicode_escape(Span::empty(), input!("{0}")).unwrap();
+        parse_escape(0, input!("u{0}"), WithBrace(false)).unwrap();
+
+        let (e, span) = parse_escape(0, input!("u{1F4AF} tail"), WithBrace(false)).unwrap();
+        assert!(matches!(e, Escape::Char('💯')));
+        assert_eq!(span, Span::new(0, 9));
 
-        let c = parse_unicode_escape(Span::empty(), input!("{1F4AF}")).unwrap();
-        assert_eq!(c, '💯');
+        let (e, _) = parse_escape(0, input!("u{1f4af}"), WithBrace(false)).unwrap();
+        assert!(matches!(e, Escape::Char('💯')));
 
-        let c = parse_unicode_escape(Span::empty(), input!("{1f4af}")).unwrap();
-        assert_eq!(c, '💯');
+        assert!(matches!(
+            parse_escape(0, input!("u{1234567}"), WithBrace(false)),
+            Err(ParseError::BadUnicodeEscape { span }) if span == Span::new(0, 10)
+        ));
     }
 }
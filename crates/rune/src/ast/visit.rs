@@ -0,0 +1,1256 @@
+//! Traversal of the AST.
+//!
+//! [Visit] traverses a tree through shared references, and
+//! [VisitMut][super::visit_mut::VisitMut] through mutable references which
+//! allows rewriting it in place. Every method of the traits defaults to
+//! walking into the children of its node through the corresponding `walk_*`
+//! function, so an implementation only has to override the nodes it is
+//! interested in and call the walk function itself to keep descending.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use rune::ast;
+//! use rune::ast::visit::{self, Visit};
+//!
+//! #[derive(Default)]
+//! struct CountCalls(usize);
+//!
+//! impl Visit for CountCalls {
+//!     fn visit_expr_call(&mut self, node: &ast::ExprCall) {
+//!         self.0 += 1;
+//!         visit::walk_expr_call(self, node);
+//!     }
+//! }
+//!
+//! let file = rune::parse_all::<ast::DeclFile>("fn main() { foo(bar(1), baz) }").unwrap();
+//! let mut count = CountCalls::default();
+//! count.visit_decl_file(&file);
+//! assert_eq!(count.0, 2);
+//! ```
+
+use crate::ast;
+
+/// Declare a visitor trait and its walk functions, which either operate over
+/// shared references or over mutable ones if `mut` is specified.
+macro_rules! decl_visit {
+    ($(#[$meta:meta])* $trait:ident $(, $m:tt)?) => {
+        $(#[$meta])*
+        pub trait $trait {
+            /// Visit a file.
+            fn visit_decl_file(&mut self, node: &$($m)? ast::DeclFile) {
+                walk_decl_file(self, node);
+            }
+
+            /// Visit an attribute.
+            fn visit_attribute(&mut self, node: &$($m)? ast::Attribute) {
+                walk_attribute(self, node);
+            }
+
+            /// Visit the content of an attribute.
+            fn visit_meta(&mut self, node: &$($m)? ast::Meta) {
+                walk_meta(self, node);
+            }
+
+            /// Visit a declaration.
+            fn visit_decl(&mut self, node: &$($m)? ast::Decl) {
+                walk_decl(self, node);
+            }
+
+            /// Visit a use declaration.
+            fn visit_decl_use(&mut self, node: &$($m)? ast::DeclUse) {
+                walk_decl_use(self, node);
+            }
+
+            /// Visit a component of a use declaration.
+            fn visit_decl_use_component(&mut self, node: &$($m)? ast::DeclUseComponent) {
+                walk_decl_use_component(self, node);
+            }
+
+            /// Visit a function declaration.
+            fn visit_decl_fn(&mut self, node: &$($m)? ast::DeclFn) {
+                walk_decl_fn(self, node);
+            }
+
+            /// Visit an enum declaration.
+            fn visit_decl_enum(&mut self, node: &$($m)? ast::DeclEnum) {
+                walk_decl_enum(self, node);
+            }
+
+            /// Visit a struct declaration.
+            fn visit_decl_struct(&mut self, node: &$($m)? ast::DeclStruct) {
+                walk_decl_struct(self, node);
+            }
+
+            /// Visit the body of a struct or enum variant.
+            fn visit_decl_struct_body(&mut self, node: &$($m)? ast::DeclStructBody) {
+                walk_decl_struct_body(self, node);
+            }
+
+            /// Visit an impl declaration.
+            fn visit_decl_impl(&mut self, node: &$($m)? ast::DeclImpl) {
+                walk_decl_impl(self, node);
+            }
+
+            /// Visit an associated constant declaration.
+            fn visit_decl_const(&mut self, node: &$($m)? ast::DeclConst) {
+                walk_decl_const(self, node);
+            }
+
+            /// Visit a static declaration.
+            fn visit_decl_let(&mut self, node: &$($m)? ast::DeclLet) {
+                walk_decl_let(self, node);
+            }
+
+            /// Visit a module declaration.
+            fn visit_decl_mod(&mut self, node: &$($m)? ast::DeclMod) {
+                walk_decl_mod(self, node);
+            }
+
+            /// Visit an expression.
+            fn visit_expr(&mut self, node: &$($m)? ast::Expr) {
+                walk_expr(self, node);
+            }
+
+            /// Visit a `while` loop.
+            fn visit_expr_while(&mut self, node: &$($m)? ast::ExprWhile) {
+                walk_expr_while(self, node);
+            }
+
+            /// Visit a `loop`.
+            fn visit_expr_loop(&mut self, node: &$($m)? ast::ExprLoop) {
+                walk_expr_loop(self, node);
+            }
+
+            /// Visit a `for` loop.
+            fn visit_expr_for(&mut self, node: &$($m)? ast::ExprFor) {
+                walk_expr_for(self, node);
+            }
+
+            /// Visit a `let` expression.
+            fn visit_expr_let(&mut self, node: &$($m)? ast::ExprLet) {
+                walk_expr_let(self, node);
+            }
+
+            /// Visit an index assignment.
+            fn visit_expr_index_set(&mut self, node: &$($m)? ast::ExprIndexSet) {
+                walk_expr_index_set(self, node);
+            }
+
+            /// Visit an `if` expression.
+            fn visit_expr_if(&mut self, node: &$($m)? ast::ExprIf) {
+                walk_expr_if(self, node);
+            }
+
+            /// Visit an `else if` branch.
+            fn visit_expr_else_if(&mut self, node: &$($m)? ast::ExprElseIf) {
+                walk_expr_else_if(self, node);
+            }
+
+            /// Visit an `else` branch.
+            fn visit_expr_else(&mut self, node: &$($m)? ast::ExprElse) {
+                walk_expr_else(self, node);
+            }
+
+            /// Visit a `match` expression.
+            fn visit_expr_match(&mut self, node: &$($m)? ast::ExprMatch) {
+                walk_expr_match(self, node);
+            }
+
+            /// Visit a branch of a `match` expression.
+            fn visit_expr_match_branch(&mut self, node: &$($m)? ast::ExprMatchBranch) {
+                walk_expr_match_branch(self, node);
+            }
+
+            /// Visit a function call.
+            fn visit_expr_call(&mut self, node: &$($m)? ast::ExprCall) {
+                walk_expr_call(self, node);
+            }
+
+            /// Visit a macro call. The token stream passed to the macro is not
+            /// visited.
+            fn visit_expr_call_macro(&mut self, node: &$($m)? ast::ExprCallMacro) {
+                walk_expr_call_macro(self, node);
+            }
+
+            /// Visit a field access.
+            fn visit_expr_field_access(&mut self, node: &$($m)? ast::ExprFieldAccess) {
+                walk_expr_field_access(self, node);
+            }
+
+            /// Visit a parenthesized expression.
+            fn visit_expr_group(&mut self, node: &$($m)? ast::ExprGroup) {
+                walk_expr_group(self, node);
+            }
+
+            /// Visit a binary expression.
+            fn visit_expr_binary(&mut self, node: &$($m)? ast::ExprBinary) {
+                walk_expr_binary(self, node);
+            }
+
+            /// Visit a unary expression.
+            fn visit_expr_unary(&mut self, node: &$($m)? ast::ExprUnary) {
+                walk_expr_unary(self, node);
+            }
+
+            /// Visit an index lookup.
+            fn visit_expr_index_get(&mut self, node: &$($m)? ast::ExprIndexGet) {
+                walk_expr_index_get(self, node);
+            }
+
+            /// Visit a range expression.
+            fn visit_expr_range(&mut self, node: &$($m)? ast::ExprRange) {
+                walk_expr_range(self, node);
+            }
+
+            /// Visit a `break` expression.
+            fn visit_expr_break(&mut self, node: &$($m)? ast::ExprBreak) {
+                walk_expr_break(self, node);
+            }
+
+            /// Visit a `continue` expression.
+            fn visit_expr_continue(&mut self, node: &$($m)? ast::ExprContinue) {
+                walk_expr_continue(self, node);
+            }
+
+            /// Visit a `yield` expression.
+            fn visit_expr_yield(&mut self, node: &$($m)? ast::ExprYield) {
+                walk_expr_yield(self, node);
+            }
+
+            /// Visit a block.
+            fn visit_expr_block(&mut self, node: &$($m)? ast::ExprBlock) {
+                walk_expr_block(self, node);
+            }
+
+            /// Visit a `return` expression.
+            fn visit_expr_return(&mut self, node: &$($m)? ast::ExprReturn) {
+                walk_expr_return(self, node);
+            }
+
+            /// Visit a `defer` expression.
+            fn visit_expr_defer(&mut self, node: &$($m)? ast::ExprDefer) {
+                walk_expr_defer(self, node);
+            }
+
+            /// Visit a `with` expression.
+            fn visit_expr_with(&mut self, node: &$($m)? ast::ExprWith) {
+                walk_expr_with(self, node);
+            }
+
+            /// Visit an `.await` expression.
+            fn visit_expr_await(&mut self, node: &$($m)? ast::ExprAwait) {
+                walk_expr_await(self, node);
+            }
+
+            /// Visit a try expression.
+            fn visit_expr_try(&mut self, node: &$($m)? ast::ExprTry) {
+                walk_expr_try(self, node);
+            }
+
+            /// Visit a `select` expression.
+            fn visit_expr_select(&mut self, node: &$($m)? ast::ExprSelect) {
+                walk_expr_select(self, node);
+            }
+
+            /// Visit a branch of a `select` expression.
+            fn visit_expr_select_branch(&mut self, node: &$($m)? ast::ExprSelectBranch) {
+                walk_expr_select_branch(self, node);
+            }
+
+            /// Visit the `default` branch of a `select` expression.
+            fn visit_expr_default_branch(&mut self, node: &$($m)? ast::ExprDefaultBranch) {
+                walk_expr_default_branch(self, node);
+            }
+
+            /// Visit a closure.
+            fn visit_expr_closure(&mut self, node: &$($m)? ast::ExprClosure) {
+                walk_expr_closure(self, node);
+            }
+
+            /// Visit the condition of an `if` or `while`.
+            fn visit_condition(&mut self, node: &$($m)? ast::Condition) {
+                walk_condition(self, node);
+            }
+
+            /// Visit a unit literal.
+            fn visit_lit_unit(&mut self, _: &$($m)? ast::LitUnit) {}
+
+            /// Visit a boolean literal.
+            fn visit_lit_bool(&mut self, _: &$($m)? ast::LitBool) {}
+
+            /// Visit a character literal.
+            fn visit_lit_char(&mut self, _: &$($m)? ast::LitChar) {}
+
+            /// Visit a byte literal.
+            fn visit_lit_byte(&mut self, _: &$($m)? ast::LitByte) {}
+
+            /// Visit a number literal.
+            fn visit_lit_number(&mut self, _: &$($m)? ast::LitNumber) {}
+
+            /// Visit a string literal.
+            fn visit_lit_str(&mut self, _: &$($m)? ast::LitStr) {}
+
+            /// Visit a byte string literal.
+            fn visit_lit_byte_str(&mut self, _: &$($m)? ast::LitByteStr) {}
+
+            /// Visit a template literal. The expressions in a template are only
+            /// available once it has been resolved, so they are not visited.
+            fn visit_lit_template(&mut self, _: &$($m)? ast::LitTemplate) {}
+
+            /// Visit a vector literal.
+            fn visit_lit_vec(&mut self, node: &$($m)? ast::LitVec) {
+                walk_lit_vec(self, node);
+            }
+
+            /// Visit an object literal.
+            fn visit_lit_object(&mut self, node: &$($m)? ast::LitObject) {
+                walk_lit_object(self, node);
+            }
+
+            /// Visit a field assignment in an object literal.
+            fn visit_lit_object_field_assign(
+                &mut self,
+                node: &$($m)? ast::LitObjectFieldAssign,
+            ) {
+                walk_lit_object_field_assign(self, node);
+            }
+
+            /// Visit a computed field assignment in an object literal.
+            fn visit_lit_object_computed_assign(
+                &mut self,
+                node: &$($m)? ast::LitObjectComputedAssign,
+            ) {
+                walk_lit_object_computed_assign(self, node);
+            }
+
+            /// Visit the identifier of an object literal or pattern.
+            fn visit_lit_object_ident(&mut self, node: &$($m)? ast::LitObjectIdent) {
+                walk_lit_object_ident(self, node);
+            }
+
+            /// Visit the key of an object field.
+            fn visit_lit_object_key(&mut self, node: &$($m)? ast::LitObjectKey) {
+                walk_lit_object_key(self, node);
+            }
+
+            /// Visit a tuple literal.
+            fn visit_lit_tuple(&mut self, node: &$($m)? ast::LitTuple) {
+                walk_lit_tuple(self, node);
+            }
+
+            /// Visit a pattern.
+            fn visit_pat(&mut self, node: &$($m)? ast::Pat) {
+                walk_pat(self, node);
+            }
+
+            /// Visit a path pattern.
+            fn visit_pat_path(&mut self, node: &$($m)? ast::PatPath) {
+                walk_pat_path(self, node);
+            }
+
+            /// Visit a vector pattern.
+            fn visit_pat_vec(&mut self, node: &$($m)? ast::PatVec) {
+                walk_pat_vec(self, node);
+            }
+
+            /// Visit a tuple pattern.
+            fn visit_pat_tuple(&mut self, node: &$($m)? ast::PatTuple) {
+                walk_pat_tuple(self, node);
+            }
+
+            /// Visit an object pattern.
+            fn visit_pat_object(&mut self, node: &$($m)? ast::PatObject) {
+                walk_pat_object(self, node);
+            }
+
+            /// Visit a field of an object pattern.
+            fn visit_pat_object_item(&mut self, node: &$($m)? ast::PatObjectItem) {
+                walk_pat_object_item(self, node);
+            }
+
+            /// Visit a path.
+            fn visit_path(&mut self, node: &$($m)? ast::Path) {
+                walk_path(self, node);
+            }
+
+            /// Visit a function argument.
+            fn visit_fn_arg(&mut self, node: &$($m)? ast::FnArg) {
+                walk_fn_arg(self, node);
+            }
+
+            /// Visit an identifier.
+            fn visit_ident(&mut self, _: &$($m)? ast::Ident) {}
+
+            /// Visit a label.
+            fn visit_label(&mut self, _: &$($m)? ast::Label) {}
+        }
+
+        /// Walk the children of a file.
+        pub fn walk_decl_file<V>(v: &mut V, node: &$($m)? ast::DeclFile)
+        where
+            V: ?Sized + $trait,
+        {
+            for (attributes, decl, _) in &$($m)? node.decls {
+                for attribute in attributes {
+                    v.visit_attribute(attribute);
+                }
+
+                v.visit_decl(decl);
+            }
+
+            for (expr, _) in &$($m)? node.stmts {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of an attribute.
+        pub fn walk_attribute<V>(v: &mut V, node: &$($m)? ast::Attribute)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_meta(&$($m)? node.meta);
+        }
+
+        /// Walk the children of the content of an attribute.
+        pub fn walk_meta<V>(v: &mut V, node: &$($m)? ast::Meta)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::Meta::Ident(ident) => v.visit_ident(ident),
+                ast::Meta::NameValue(meta) => {
+                    v.visit_ident(&$($m)? meta.name);
+                    match &$($m)? meta.value {
+                        ast::MetaValue::LitStr(lit_str) => v.visit_lit_str(lit_str),
+                        ast::MetaValue::LitNumber(lit_number) => v.visit_lit_number(lit_number),
+                    }
+                }
+                ast::Meta::List(meta) => {
+                    v.visit_ident(&$($m)? meta.name);
+
+                    for (item, _) in &$($m)? meta.items.items {
+                        v.visit_meta(item);
+                    }
+                }
+            }
+        }
+
+        /// Walk the children of a declaration.
+        pub fn walk_decl<V>(v: &mut V, node: &$($m)? ast::Decl)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::Decl::DeclUse(decl) => v.visit_decl_use(decl),
+                ast::Decl::DeclFn(decl) => v.visit_decl_fn(decl),
+                ast::Decl::DeclEnum(decl) => v.visit_decl_enum(decl),
+                ast::Decl::DeclStruct(decl) => v.visit_decl_struct(decl),
+                ast::Decl::DeclImpl(decl) => v.visit_decl_impl(decl),
+                ast::Decl::DeclMod(decl) => v.visit_decl_mod(decl),
+                ast::Decl::DeclLet(decl) => v.visit_decl_let(decl),
+            }
+        }
+
+        /// Walk the children of a use declaration.
+        pub fn walk_decl_use<V>(v: &mut V, node: &$($m)? ast::DeclUse)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.first);
+
+            for (_, component) in &$($m)? node.rest {
+                v.visit_decl_use_component(component);
+            }
+        }
+
+        /// Walk the children of a component of a use declaration.
+        pub fn walk_decl_use_component<V>(v: &mut V, node: &$($m)? ast::DeclUseComponent)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::DeclUseComponent::Ident(ident) => v.visit_ident(ident),
+                ast::DeclUseComponent::Wildcard(..) => (),
+                ast::DeclUseComponent::Group(group) => {
+                    for (path, _) in &$($m)? group.items {
+                        v.visit_decl_use_component(&$($m)? path.first);
+
+                        for (_, component) in &$($m)? path.rest {
+                            v.visit_decl_use_component(component);
+                        }
+                    }
+                }
+            }
+        }
+
+        /// Walk the children of a function declaration.
+        pub fn walk_decl_fn<V>(v: &mut V, node: &$($m)? ast::DeclFn)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.name);
+
+            for (arg, _) in &$($m)? node.args.items {
+                v.visit_fn_arg(arg);
+            }
+
+            v.visit_expr_block(&$($m)? node.body);
+        }
+
+        /// Walk the children of an enum declaration.
+        pub fn walk_decl_enum<V>(v: &mut V, node: &$($m)? ast::DeclEnum)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.name);
+
+            for (ident, body, discriminant, _) in &$($m)? node.variants {
+                v.visit_ident(ident);
+                v.visit_decl_struct_body(body);
+
+                if let Some(discriminant) = discriminant {
+                    v.visit_lit_number(&$($m)? discriminant.value);
+                }
+            }
+        }
+
+        /// Walk the children of a struct declaration.
+        pub fn walk_decl_struct<V>(v: &mut V, node: &$($m)? ast::DeclStruct)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.ident);
+            v.visit_decl_struct_body(&$($m)? node.body);
+        }
+
+        /// Walk the fields of a struct or enum variant.
+        pub fn walk_decl_struct_body<V>(v: &mut V, node: &$($m)? ast::DeclStructBody)
+        where
+            V: ?Sized + $trait,
+        {
+            let fields = match node {
+                ast::DeclStructBody::EmptyBody(..) => return,
+                ast::DeclStructBody::TupleBody(body) => &$($m)? body.fields,
+                ast::DeclStructBody::StructBody(body) => &$($m)? body.fields,
+            };
+
+            for (ident, _) in fields {
+                v.visit_ident(ident);
+            }
+        }
+
+        /// Walk the children of an impl declaration.
+        pub fn walk_decl_impl<V>(v: &mut V, node: &$($m)? ast::DeclImpl)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some((name, _)) = &$($m)? node.extension {
+                v.visit_ident(name);
+            }
+
+            v.visit_path(&$($m)? node.path);
+
+            for decl_const in &$($m)? node.consts {
+                v.visit_decl_const(decl_const);
+            }
+
+            for decl_fn in &$($m)? node.functions {
+                v.visit_decl_fn(decl_fn);
+            }
+        }
+
+        /// Walk the children of an associated constant declaration.
+        pub fn walk_decl_const<V>(v: &mut V, node: &$($m)? ast::DeclConst)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.name);
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of a static declaration.
+        pub fn walk_decl_let<V>(v: &mut V, node: &$($m)? ast::DeclLet)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.name);
+            v.visit_expr(&$($m)? *node.expr);
+        }
+
+        /// Walk the children of a module declaration.
+        pub fn walk_decl_mod<V>(v: &mut V, node: &$($m)? ast::DeclMod)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.name);
+
+            if let Some(body) = &$($m)? node.body {
+                v.visit_decl_file(&$($m)? body.file);
+            }
+        }
+
+        /// Walk the children of an expression.
+        pub fn walk_expr<V>(v: &mut V, node: &$($m)? ast::Expr)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::Expr::Self_(..) => (),
+                ast::Expr::Path(path) => v.visit_path(path),
+                ast::Expr::Decl(decl) => v.visit_decl(decl),
+                ast::Expr::ExprWhile(expr) => v.visit_expr_while(expr),
+                ast::Expr::ExprLoop(expr) => v.visit_expr_loop(expr),
+                ast::Expr::ExprFor(expr) => v.visit_expr_for(expr),
+                ast::Expr::ExprLet(expr) => v.visit_expr_let(expr),
+                ast::Expr::ExprIndexSet(expr) => v.visit_expr_index_set(expr),
+                ast::Expr::ExprIf(expr) => v.visit_expr_if(expr),
+                ast::Expr::ExprMatch(expr) => v.visit_expr_match(expr),
+                ast::Expr::ExprCall(expr) => v.visit_expr_call(expr),
+                ast::Expr::ExprCallMacro(expr) => v.visit_expr_call_macro(expr),
+                ast::Expr::ExprFieldAccess(expr) => v.visit_expr_field_access(expr),
+                ast::Expr::ExprGroup(expr) => v.visit_expr_group(expr),
+                ast::Expr::ExprBinary(expr) => v.visit_expr_binary(expr),
+                ast::Expr::ExprUnary(expr) => v.visit_expr_unary(expr),
+                ast::Expr::ExprIndexGet(expr) => v.visit_expr_index_get(expr),
+                ast::Expr::ExprRange(expr) => v.visit_expr_range(expr),
+                ast::Expr::ExprBreak(expr) => v.visit_expr_break(expr),
+                ast::Expr::ExprContinue(expr) => v.visit_expr_continue(expr),
+                ast::Expr::ExprYield(expr) => v.visit_expr_yield(expr),
+                ast::Expr::ExprBlock(expr) => v.visit_expr_block(expr),
+                ast::Expr::ExprReturn(expr) => v.visit_expr_return(expr),
+                ast::Expr::ExprDefer(expr) => v.visit_expr_defer(expr),
+                ast::Expr::ExprWith(expr) => v.visit_expr_with(expr),
+                ast::Expr::ExprAwait(expr) => v.visit_expr_await(expr),
+                ast::Expr::ExprTry(expr) => v.visit_expr_try(expr),
+                ast::Expr::ExprSelect(expr) => v.visit_expr_select(expr),
+                ast::Expr::ExprClosure(expr) => v.visit_expr_closure(expr),
+                ast::Expr::LitUnit(lit) => v.visit_lit_unit(lit),
+                ast::Expr::LitBool(lit) => v.visit_lit_bool(lit),
+                ast::Expr::LitChar(lit) => v.visit_lit_char(lit),
+                ast::Expr::LitByte(lit) => v.visit_lit_byte(lit),
+                ast::Expr::LitNumber(lit) => v.visit_lit_number(lit),
+                ast::Expr::LitStr(lit) => v.visit_lit_str(lit),
+                ast::Expr::LitByteStr(lit) => v.visit_lit_byte_str(lit),
+                ast::Expr::LitTemplate(lit) => v.visit_lit_template(lit),
+                ast::Expr::LitVec(lit) => v.visit_lit_vec(lit),
+                ast::Expr::LitObject(lit) => v.visit_lit_object(lit),
+                ast::Expr::LitTuple(lit) => v.visit_lit_tuple(lit),
+            }
+        }
+
+        /// Walk the children of a `while` loop.
+        pub fn walk_expr_while<V>(v: &mut V, node: &$($m)? ast::ExprWhile)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some((label, _)) = &$($m)? node.label {
+                v.visit_label(label);
+            }
+
+            v.visit_condition(&$($m)? node.condition);
+            v.visit_expr_block(&$($m)? node.body);
+
+            if let Some(expr_else) = &$($m)? node.expr_else {
+                v.visit_expr_else(expr_else);
+            }
+        }
+
+        /// Walk the children of a `loop`.
+        pub fn walk_expr_loop<V>(v: &mut V, node: &$($m)? ast::ExprLoop)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some((label, _)) = &$($m)? node.label {
+                v.visit_label(label);
+            }
+
+            v.visit_expr_block(&$($m)? node.body);
+        }
+
+        /// Walk the children of a `for` loop.
+        pub fn walk_expr_for<V>(v: &mut V, node: &$($m)? ast::ExprFor)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some((label, _)) = &$($m)? node.label {
+                v.visit_label(label);
+            }
+
+            v.visit_ident(&$($m)? node.var);
+            v.visit_expr(&$($m)? node.iter);
+            v.visit_expr_block(&$($m)? node.body);
+
+            if let Some(expr_else) = &$($m)? node.expr_else {
+                v.visit_expr_else(expr_else);
+            }
+        }
+
+        /// Walk the children of a `let` expression.
+        pub fn walk_expr_let<V>(v: &mut V, node: &$($m)? ast::ExprLet)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_pat(&$($m)? node.pat);
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of an index assignment.
+        pub fn walk_expr_index_set<V>(v: &mut V, node: &$($m)? ast::ExprIndexSet)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.target);
+            v.visit_expr(&$($m)? node.index);
+            v.visit_expr(&$($m)? node.value);
+        }
+
+        /// Walk the children of an `if` expression.
+        pub fn walk_expr_if<V>(v: &mut V, node: &$($m)? ast::ExprIf)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_condition(&$($m)? node.condition);
+            v.visit_expr_block(&$($m)? node.block);
+
+            for expr_else_if in &$($m)? node.expr_else_ifs {
+                v.visit_expr_else_if(expr_else_if);
+            }
+
+            if let Some(expr_else) = &$($m)? node.expr_else {
+                v.visit_expr_else(expr_else);
+            }
+        }
+
+        /// Walk the children of an `else if` branch.
+        pub fn walk_expr_else_if<V>(v: &mut V, node: &$($m)? ast::ExprElseIf)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_condition(&$($m)? node.condition);
+            v.visit_expr_block(&$($m)? node.block);
+        }
+
+        /// Walk the children of an `else` branch.
+        pub fn walk_expr_else<V>(v: &mut V, node: &$($m)? ast::ExprElse)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr_block(&$($m)? node.block);
+        }
+
+        /// Walk the children of a `match` expression.
+        pub fn walk_expr_match<V>(v: &mut V, node: &$($m)? ast::ExprMatch)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+
+            for (branch, _) in &$($m)? node.branches {
+                v.visit_expr_match_branch(branch);
+            }
+        }
+
+        /// Walk the children of a branch in a `match` expression.
+        pub fn walk_expr_match_branch<V>(v: &mut V, node: &$($m)? ast::ExprMatchBranch)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_pat(&$($m)? node.pat);
+
+            if let Some((_, condition)) = &$($m)? node.condition {
+                v.visit_expr(condition);
+            }
+
+            v.visit_expr(&$($m)? node.body);
+        }
+
+        /// Walk the children of a function call.
+        pub fn walk_expr_call<V>(v: &mut V, node: &$($m)? ast::ExprCall)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+
+            for (arg, _) in &$($m)? node.args.items {
+                v.visit_expr(arg);
+            }
+        }
+
+        /// Walk the children of a macro call.
+        pub fn walk_expr_call_macro<V>(v: &mut V, node: &$($m)? ast::ExprCallMacro)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_path(&$($m)? node.path);
+        }
+
+        /// Walk the children of a field access.
+        pub fn walk_expr_field_access<V>(v: &mut V, node: &$($m)? ast::ExprFieldAccess)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+
+            match &$($m)? node.expr_field {
+                ast::ExprField::Ident(ident) => v.visit_ident(ident),
+                ast::ExprField::LitNumber(lit) => v.visit_lit_number(lit),
+            }
+        }
+
+        /// Walk the children of a parenthesized expression.
+        pub fn walk_expr_group<V>(v: &mut V, node: &$($m)? ast::ExprGroup)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of a binary expression.
+        pub fn walk_expr_binary<V>(v: &mut V, node: &$($m)? ast::ExprBinary)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.lhs);
+            v.visit_expr(&$($m)? node.rhs);
+        }
+
+        /// Walk the children of a unary expression.
+        pub fn walk_expr_unary<V>(v: &mut V, node: &$($m)? ast::ExprUnary)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of an index lookup.
+        pub fn walk_expr_index_get<V>(v: &mut V, node: &$($m)? ast::ExprIndexGet)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.target);
+            v.visit_expr(&$($m)? node.index);
+        }
+
+        /// Walk the children of a range expression.
+        pub fn walk_expr_range<V>(v: &mut V, node: &$($m)? ast::ExprRange)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some(from) = &$($m)? node.from {
+                v.visit_expr(from);
+            }
+
+            if let Some(to) = &$($m)? node.to {
+                v.visit_expr(to);
+            }
+        }
+
+        /// Walk the children of a `break` expression.
+        pub fn walk_expr_break<V>(v: &mut V, node: &$($m)? ast::ExprBreak)
+        where
+            V: ?Sized + $trait,
+        {
+            match &$($m)? node.expr {
+                Some(ast::ExprBreakValue::Expr(expr)) => v.visit_expr(expr),
+                Some(ast::ExprBreakValue::Label(label)) => v.visit_label(label),
+                Some(ast::ExprBreakValue::LabelExpr(label, expr)) => {
+                    v.visit_label(label);
+                    v.visit_expr(expr);
+                }
+                None => (),
+            }
+        }
+
+        /// Walk the children of a `continue` expression.
+        pub fn walk_expr_continue<V>(v: &mut V, node: &$($m)? ast::ExprContinue)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some(label) = &$($m)? node.label {
+                v.visit_label(label);
+            }
+        }
+
+        /// Walk the children of a `yield` expression.
+        pub fn walk_expr_yield<V>(v: &mut V, node: &$($m)? ast::ExprYield)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some(expr) = &$($m)? node.expr {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of a block.
+        pub fn walk_expr_block<V>(v: &mut V, node: &$($m)? ast::ExprBlock)
+        where
+            V: ?Sized + $trait,
+        {
+            for (expr, _) in &$($m)? node.exprs {
+                v.visit_expr(expr);
+            }
+
+            if let Some(expr) = &$($m)? node.trailing_expr {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of a `return` expression.
+        pub fn walk_expr_return<V>(v: &mut V, node: &$($m)? ast::ExprReturn)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some(expr) = &$($m)? node.expr {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of a `defer` expression.
+        pub fn walk_expr_defer<V>(v: &mut V, node: &$($m)? ast::ExprDefer)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr_block(&$($m)? node.body);
+        }
+
+        /// Walk the children of a `with` expression.
+        pub fn walk_expr_with<V>(v: &mut V, node: &$($m)? ast::ExprWith)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+
+            if let Some(ident) = &$($m)? node.binding {
+                v.visit_ident(ident);
+            }
+
+            v.visit_expr_block(&$($m)? node.body);
+        }
+
+        /// Walk the children of an `.await` expression.
+        pub fn walk_expr_await<V>(v: &mut V, node: &$($m)? ast::ExprAwait)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of a try expression.
+        pub fn walk_expr_try<V>(v: &mut V, node: &$($m)? ast::ExprTry)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of a `select` expression.
+        pub fn walk_expr_select<V>(v: &mut V, node: &$($m)? ast::ExprSelect)
+        where
+            V: ?Sized + $trait,
+        {
+            for (branch, _) in &$($m)? node.branches {
+                v.visit_expr_select_branch(branch);
+            }
+
+            if let Some((branch, _)) = &$($m)? node.default_branch {
+                v.visit_expr_default_branch(branch);
+            }
+        }
+
+        /// Walk the children of a branch in a `select` expression.
+        pub fn walk_expr_select_branch<V>(v: &mut V, node: &$($m)? ast::ExprSelectBranch)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_pat(&$($m)? node.pat);
+            v.visit_expr(&$($m)? node.expr);
+            v.visit_expr(&$($m)? node.body);
+        }
+
+        /// Walk the children of the `default` branch in a `select` expression.
+        pub fn walk_expr_default_branch<V>(v: &mut V, node: &$($m)? ast::ExprDefaultBranch)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.body);
+        }
+
+        /// Walk the children of a closure.
+        pub fn walk_expr_closure<V>(v: &mut V, node: &$($m)? ast::ExprClosure)
+        where
+            V: ?Sized + $trait,
+        {
+            if let ast::ExprClosureArgs::List { args, .. } = &$($m)? node.args {
+                for (arg, _) in args {
+                    v.visit_fn_arg(arg);
+                }
+            }
+
+            v.visit_expr(&$($m)? node.body);
+        }
+
+        /// Walk the children of a condition.
+        pub fn walk_condition<V>(v: &mut V, node: &$($m)? ast::Condition)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::Condition::Expr(expr) => v.visit_expr(expr),
+                ast::Condition::ExprLet(expr) => v.visit_expr_let(expr),
+            }
+        }
+
+        /// Walk the children of a vector literal.
+        pub fn walk_lit_vec<V>(v: &mut V, node: &$($m)? ast::LitVec)
+        where
+            V: ?Sized + $trait,
+        {
+            for expr in &$($m)? node.items {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of an object literal.
+        pub fn walk_lit_object<V>(v: &mut V, node: &$($m)? ast::LitObject)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_lit_object_ident(&$($m)? node.ident);
+
+            for assign in &$($m)? node.assignments {
+                v.visit_lit_object_field_assign(assign);
+            }
+
+            for assign in &$($m)? node.computed {
+                v.visit_lit_object_computed_assign(assign);
+            }
+
+            if let Some((_, base)) = &$($m)? node.base {
+                v.visit_expr(base);
+            }
+        }
+
+        /// Walk the children of a field assignment in an object literal.
+        pub fn walk_lit_object_field_assign<V>(
+            v: &mut V,
+            node: &$($m)? ast::LitObjectFieldAssign,
+        ) where
+            V: ?Sized + $trait,
+        {
+            v.visit_lit_object_key(&$($m)? node.key);
+
+            if let Some((_, expr)) = &$($m)? node.assign {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of a computed field assignment in an object
+        /// literal.
+        pub fn walk_lit_object_computed_assign<V>(
+            v: &mut V,
+            node: &$($m)? ast::LitObjectComputedAssign,
+        ) where
+            V: ?Sized + $trait,
+        {
+            v.visit_expr(&$($m)? node.key);
+            v.visit_expr(&$($m)? node.expr);
+        }
+
+        /// Walk the children of the identifier of an object.
+        pub fn walk_lit_object_ident<V>(v: &mut V, node: &$($m)? ast::LitObjectIdent)
+        where
+            V: ?Sized + $trait,
+        {
+            if let ast::LitObjectIdent::Named(path) = node {
+                v.visit_path(path);
+            }
+        }
+
+        /// Walk the children of an object key.
+        pub fn walk_lit_object_key<V>(v: &mut V, node: &$($m)? ast::LitObjectKey)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::LitObjectKey::LitStr(lit) => v.visit_lit_str(lit),
+                ast::LitObjectKey::Ident(ident) => v.visit_ident(ident),
+            }
+        }
+
+        /// Walk the children of a tuple literal.
+        pub fn walk_lit_tuple<V>(v: &mut V, node: &$($m)? ast::LitTuple)
+        where
+            V: ?Sized + $trait,
+        {
+            for (expr, _) in &$($m)? node.items {
+                v.visit_expr(expr);
+            }
+        }
+
+        /// Walk the children of a pattern.
+        pub fn walk_pat<V>(v: &mut V, node: &$($m)? ast::Pat)
+        where
+            V: ?Sized + $trait,
+        {
+            match node {
+                ast::Pat::PatIgnore(..) => (),
+                ast::Pat::PatPath(pat) => v.visit_pat_path(pat),
+                ast::Pat::PatUnit(lit) => v.visit_lit_unit(lit),
+                ast::Pat::PatByte(lit) => v.visit_lit_byte(lit),
+                ast::Pat::PatChar(lit) => v.visit_lit_char(lit),
+                ast::Pat::PatNumber(lit) => v.visit_lit_number(lit),
+                ast::Pat::PatString(lit) => v.visit_lit_str(lit),
+                ast::Pat::PatVec(pat) => v.visit_pat_vec(pat),
+                ast::Pat::PatTuple(pat) => v.visit_pat_tuple(pat),
+                ast::Pat::PatObject(pat) => v.visit_pat_object(pat),
+            }
+        }
+
+        /// Walk the children of a path pattern.
+        pub fn walk_pat_path<V>(v: &mut V, node: &$($m)? ast::PatPath)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_path(&$($m)? node.path);
+        }
+
+        /// Walk the children of a vector pattern.
+        pub fn walk_pat_vec<V>(v: &mut V, node: &$($m)? ast::PatVec)
+        where
+            V: ?Sized + $trait,
+        {
+            for (pat, _) in &$($m)? node.items {
+                v.visit_pat(pat);
+            }
+        }
+
+        /// Walk the children of a tuple pattern.
+        pub fn walk_pat_tuple<V>(v: &mut V, node: &$($m)? ast::PatTuple)
+        where
+            V: ?Sized + $trait,
+        {
+            if let Some(path) = &$($m)? node.path {
+                v.visit_path(path);
+            }
+
+            for (pat, _) in &$($m)? node.items {
+                v.visit_pat(pat);
+            }
+        }
+
+        /// Walk the children of an object pattern.
+        pub fn walk_pat_object<V>(v: &mut V, node: &$($m)? ast::PatObject)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_lit_object_ident(&$($m)? node.ident);
+
+            for (item, _) in &$($m)? node.fields {
+                v.visit_pat_object_item(item);
+            }
+        }
+
+        /// Walk the children of a field in an object pattern.
+        pub fn walk_pat_object_item<V>(v: &mut V, node: &$($m)? ast::PatObjectItem)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_lit_object_key(&$($m)? node.key);
+
+            if let Some((_, pat)) = &$($m)? node.binding {
+                v.visit_pat(pat);
+            }
+        }
+
+        /// Walk the children of a path.
+        pub fn walk_path<V>(v: &mut V, node: &$($m)? ast::Path)
+        where
+            V: ?Sized + $trait,
+        {
+            v.visit_ident(&$($m)? node.first);
+
+            for (_, ident) in &$($m)? node.rest {
+                v.visit_ident(ident);
+            }
+        }
+
+        /// Walk the children of a function argument.
+        pub fn walk_fn_arg<V>(v: &mut V, node: &$($m)? ast::FnArg)
+        where
+            V: ?Sized + $trait,
+        {
+            if let ast::FnArg::Ident(ident) = node {
+                v.visit_ident(ident);
+            }
+        }
+    };
+}
+
+decl_visit! {
+    /// A visitor over shared references to the AST.
+    Visit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{walk_expr, Visit};
+    use crate::ast::visit_mut::{self, VisitMut};
+    use crate::{ast, parse_all};
+
+    #[derive(Default)]
+    struct Counter {
+        groups: usize,
+        idents: usize,
+    }
+
+    impl Visit for Counter {
+        fn visit_expr(&mut self, node: &ast::Expr) {
+            if let ast::Expr::ExprGroup(..) = node {
+                self.groups += 1;
+            }
+
+            walk_expr(self, node);
+        }
+
+        fn visit_ident(&mut self, _: &ast::Ident) {
+            self.idents += 1;
+        }
+    }
+
+    struct Ungroup;
+
+    impl VisitMut for Ungroup {
+        fn visit_expr(&mut self, node: &mut ast::Expr) {
+            while let ast::Expr::ExprGroup(group) = node {
+                *node = (*group.expr).clone();
+            }
+
+            visit_mut::walk_expr(self, node);
+        }
+    }
+
+    #[test]
+    fn test_visit() {
+        let source = r#"
+        fn foo(a, b) {
+            let c = ((a + b) * 2, #{d: (b)});
+            if let [e, ..] = c { (e) } else { foo.bar((a)) }
+        }
+        "#;
+
+        let mut file = parse_all::<ast::DeclFile>(source).unwrap();
+
+        let mut counter = Counter::default();
+        counter.visit_decl_file(&file);
+        assert_eq!(counter.groups, 4);
+        assert_eq!(counter.idents, 14);
+
+        Ungroup.visit_decl_file(&mut file);
+
+        let mut counter = Counter::default();
+        counter.visit_decl_file(&file);
+        assert_eq!(counter.groups, 0);
+        assert_eq!(counter.idents, 14);
+    }
+}
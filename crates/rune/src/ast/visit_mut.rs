@@ -0,0 +1,11 @@
+//! Traversal of the AST through mutable references.
+//!
+//! See the [visit][super::visit] module for details.
+
+use crate::ast;
+
+decl_visit! {
+    /// A visitor over mutable references to the AST, which can be used to
+    /// rewrite it in place.
+    VisitMut, mut
+}
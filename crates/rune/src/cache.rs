@@ -0,0 +1,225 @@
+//! A persistent cache of compiled units.
+
+use crate::{LoadError, Options, Sources, Warning, Warnings};
+use runestick::{Context, Hash, Source, Unit};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counter used to give every write to the cache its own temporary file.
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A cache of compiled units in a directory on disk, which allows repeated
+/// compilations of the same sources to skip the compiler entirely across
+/// process restarts.
+///
+/// Units are cached by the contents of the sources to compile, the options
+/// they are compiled with, and the [fingerprint][Context::fingerprint] of the
+/// context they are compiled against. Sources which are loaded from files
+/// while compiling, like modules declared with `mod foo;`, are recorded and
+/// checked for changes when a cached unit is used. Warnings raised while
+/// compiling are cached with the unit, and reported again when it's loaded.
+///
+/// Failing to read or write the cache isn't an error, and causes the sources
+/// to be compiled as normal.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::UnitCache;
+/// use runestick::Source;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let cache = UnitCache::new(std::env::temp_dir().join("rune-cache-example"));
+/// let context = rune::default_context()?;
+/// let options = rune::Options::default();
+///
+/// let mut sources = rune::Sources::new();
+/// sources.insert_default(Source::new("entry", "fn main() { 42 }"));
+///
+/// let mut warnings = rune::Warnings::new();
+/// let unit = cache.load_sources(&context, &options, &mut sources, &mut warnings)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct UnitCache {
+    dir: PathBuf,
+}
+
+impl UnitCache {
+    /// Construct a cache which stores units in the given directory. The
+    /// directory is created when the first unit is stored.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self { dir: dir.into() }
+    }
+
+    /// Access the directory units are stored in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Load and compile the given sources like [load_sources][crate::load_sources],
+    /// using a cached unit if one is available.
+    pub fn load_sources(
+        &self,
+        context: &Context,
+        options: &Options,
+        sources: &mut Sources,
+        warnings: &mut Warnings,
+    ) -> Result<Unit, LoadError> {
+        let path = self.entry_path(context, options, sources);
+
+        if let Some(entry) = read_entry(&path) {
+            if let Some(loaded) = entry.load() {
+                log::trace!("using cached unit: {}", path.display());
+
+                // NB: the queued sources have been compiled into the cached
+                // unit.
+                while sources.next_source().is_some() {}
+
+                // NB: loaded sources get the same ids as when the unit was
+                // compiled, since they are referenced by its debug
+                // information.
+                for source in loaded {
+                    sources.insert_unqueued(source);
+                }
+
+                for warning in entry.warnings {
+                    warnings.push(warning);
+                }
+
+                return Ok(entry.unit);
+            }
+        }
+
+        let start = sources.iter().count();
+        let start_warnings = warnings.iter().count();
+        let unit = crate::load_sources(context, options, sources, warnings)?;
+
+        let mut loaded = Vec::new();
+
+        for source in sources.iter().skip(start) {
+            let path = match source.path() {
+                Some(path) => path.to_owned(),
+                None => return Ok(unit),
+            };
+
+            loaded.push(CachedSource {
+                path,
+                hash: Hash::of(source.as_str()),
+            });
+        }
+
+        let entry = CacheEntry {
+            loaded,
+            warnings: warnings.iter().skip(start_warnings).copied().collect(),
+            unit,
+        };
+
+        if let Err(error) = self.write_entry(&path, &entry) {
+            log::warn!("failed to write unit cache: {}: {}", path.display(), error);
+        }
+
+        Ok(entry.unit)
+    }
+
+    /// Calculate the path of the cache entry for the given compilation.
+    fn entry_path(&self, context: &Context, options: &Options, sources: &Sources) -> PathBuf {
+        let sources = sources
+            .iter()
+            .map(|s| (s.name(), s.path(), s.as_str()))
+            .collect::<Vec<_>>();
+
+        let key = Hash::of((
+            env!("CARGO_PKG_VERSION"),
+            context.fingerprint(),
+            options.fingerprint(),
+            sources,
+        ));
+
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Write a cache entry, through a temporary file so that concurrent
+    /// readers never see a partially written entry.
+    ///
+    /// The temporary file is unique to each write, so that concurrent writers
+    /// of the same entry, in this or other processes, never write to the same
+    /// file.
+    fn write_entry(&self, path: &Path, entry: &CacheEntry) -> io::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let temp = path.with_extension(format!(
+            "{}-{}.tmp",
+            std::process::id(),
+            TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        let result =
+            fs::write(&temp, serde_json::to_vec(entry)?).and_then(|()| fs::rename(&temp, path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+
+        result
+    }
+}
+
+/// A cached unit.
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    /// Sources which were loaded from files while compiling the unit.
+    loaded: Vec<CachedSource>,
+    /// Warnings raised while compiling the unit.
+    warnings: Vec<Warning>,
+    /// The compiled unit.
+    unit: Unit,
+}
+
+impl CacheEntry {
+    /// Load the sources which the unit was compiled from, or return `None` if
+    /// any of them have changed since.
+    fn load(&self) -> Option<Vec<Source>> {
+        let mut loaded = Vec::new();
+
+        for cached in &self.loaded {
+            let source = Source::from_path(&cached.path).ok()?;
+
+            if Hash::of(source.as_str()) != cached.hash {
+                log::trace!("cached unit out of date: {}", cached.path.display());
+                return None;
+            }
+
+            loaded.push(source);
+        }
+
+        Some(loaded)
+    }
+}
+
+/// A source loaded from a file, as recorded in a cache entry.
+#[derive(Serialize, Deserialize)]
+struct CachedSource {
+    path: PathBuf,
+    hash: Hash,
+}
+
+/// Read the cache entry at the given path, if there is a valid one.
+fn read_entry(path: &Path) -> Option<CacheEntry> {
+    let bytes = fs::read(path).ok()?;
+
+    match serde_json::from_slice(&bytes) {
+        Ok(entry) => Some(entry),
+        Err(error) => {
+            log::warn!("ignoring corrupt unit cache: {}: {}", path.display(), error);
+            None
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Evaluation of conditional attributes, like `#[cfg(target = "web")]`.
+//!
+//! Conditions are evaluated against the variables defined through
+//! [Options::define][crate::Options::define].
+
+use crate::ast;
+use crate::error::{CompileError, CompileResult};
+use crate::traits::Resolve as _;
+use crate::Options;
+use runestick::Source;
+
+/// Test if an item with the given attributes is enabled.
+pub(crate) fn is_enabled(
+    attributes: &[ast::Attribute],
+    source: &Source,
+    options: &Options,
+) -> CompileResult<bool> {
+    for attribute in attributes {
+        // NB: derives are expanded and memoization is configured separately,
+        // once the item is enabled.
+        if crate::derive::is_derive_attribute(&attribute.meta, source)?
+            || crate::memoize::is_memoize_attribute(&attribute.meta, source)?
+        {
+            continue;
+        }
+
+        if !attribute_enabled(&attribute.meta, source, options)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Evaluate a single attribute, which is either `cfg(<condition>)` or
+/// `cfg_attr(<condition>, <attribute>*)`.
+fn attribute_enabled(meta: &ast::Meta, source: &Source, options: &Options) -> CompileResult<bool> {
+    let span = meta.span();
+
+    let list = match meta {
+        ast::Meta::List(list) => list,
+        _ => return Err(CompileError::UnsupportedAttribute { span }),
+    };
+
+    let mut items = list.items.items.iter().map(|(meta, _)| meta);
+
+    match list.name.resolve(source)? {
+        "cfg" => match (items.next(), items.next()) {
+            (Some(condition), None) => condition_holds(condition, source, options),
+            _ => Err(CompileError::UnsupportedCfg { span }),
+        },
+        "cfg_attr" => {
+            let condition = match items.next() {
+                Some(condition) if items.len() > 0 => condition,
+                _ => return Err(CompileError::UnsupportedCfg { span }),
+            };
+
+            if !condition_holds(condition, source, options)? {
+                return Ok(true);
+            }
+
+            for meta in items {
+                if !attribute_enabled(meta, source, options)? {
+                    return Ok(false);
+                }
+            }
+
+            Ok(true)
+        }
+        _ => Err(CompileError::UnsupportedAttribute { span }),
+    }
+}
+
+/// Evaluate a condition, like `debug`, `target = "web"`, or
+/// `all(debug, not(target = "web"))`.
+fn condition_holds(meta: &ast::Meta, source: &Source, options: &Options) -> CompileResult<bool> {
+    match meta {
+        ast::Meta::Ident(ident) => {
+            let name = ident.resolve(source)?;
+            Ok(options.defines.contains_key(name))
+        }
+        ast::Meta::NameValue(meta) => {
+            let name = meta.name.resolve(source)?;
+
+            let value = match &meta.value {
+                ast::MetaValue::LitStr(lit_str) => lit_str.resolve(source)?,
+                ast::MetaValue::LitNumber(..) => {
+                    return Err(CompileError::UnsupportedAttribute { span: meta.span() })
+                }
+            };
+
+            Ok(options.defines.get(name).map(String::as_str) == Some(&*value))
+        }
+        ast::Meta::List(list) => {
+            let span = list.span();
+
+            // NB: every condition is evaluated, so that errors are reported
+            // even for conditions which don't affect the outcome.
+            let values = list
+                .items
+                .items
+                .iter()
+                .map(|(meta, _)| condition_holds(meta, source, options))
+                .collect::<CompileResult<Vec<_>>>()?;
+
+            match (list.name.resolve(source)?, &values[..]) {
+                ("all", values) => Ok(values.iter().all(|v| *v)),
+                ("any", values) => Ok(values.iter().any(|v| *v)),
+                ("not", [value]) => Ok(!*value),
+                _ => Err(CompileError::UnsupportedCfg { span }),
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::traits::Compile;
+use runestick::Inst;
+
+/// Compile the function which produces the value of an associated constant.
+impl Compile<&ast::DeclConst> for Compiler<'_> {
+    fn compile(&mut self, decl_const: &ast::DeclConst) -> CompileResult<()> {
+        let span = decl_const.span();
+        log::trace!("DeclConst => {:?}", self.source.source(span));
+
+        self.compile((&decl_const.expr, Needs::Value))?;
+
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+        self.locals_clean(total_var_count, span);
+        self.asm.push(Inst::Return, span);
+        self.scopes.pop_last(span)?;
+        Ok(())
+    }
+}
@@ -1,9 +1,8 @@
 use crate::ast;
-use crate::compiler::{Compiler, Needs};
+use crate::compiler::Compiler;
 use crate::error::CompileResult;
 use crate::traits::Compile;
 use crate::{traits::Resolve as _, CompileError};
-use runestick::Inst;
 
 impl Compile<(ast::DeclFn, bool)> for Compiler<'_> {
     fn compile(&mut self, (fn_decl, instance_fn): (ast::DeclFn, bool)) -> CompileResult<()> {
@@ -39,26 +38,11 @@ impl Compile<(ast::DeclFn, bool)> for Compiler<'_> {
             first = false;
         }
 
-        if fn_decl.body.exprs.is_empty() && fn_decl.body.trailing_expr.is_none() {
-            self.asm.push(Inst::ReturnUnit, span);
-            return Ok(());
-        }
-
-        for (expr, _) in &fn_decl.body.exprs {
-            self.compile((expr, Needs::None))?;
-        }
-
-        if let Some(expr) = &fn_decl.body.trailing_expr {
-            self.compile((&**expr, Needs::Value))?;
-
-            let total_var_count = self.scopes.last(span)?.total_var_count;
-            self.locals_clean(total_var_count, span);
-            self.asm.push(Inst::Return, span);
-        } else {
-            let total_var_count = self.scopes.last(span)?.total_var_count;
-            self.locals_pop(total_var_count, span);
-            self.asm.push(Inst::ReturnUnit, span);
-        }
+        self.compile_fn_body(
+            &fn_decl.body.exprs,
+            fn_decl.body.trailing_expr.as_deref(),
+            span,
+        )?;
 
         self.scopes.pop_last(span)?;
         Ok(())
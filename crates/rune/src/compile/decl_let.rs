@@ -0,0 +1,25 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::traits::Compile;
+use runestick::Inst;
+
+/// Compile the function which initializes a static on first use, and produces
+/// its value.
+impl Compile<(&ast::DeclLet, usize)> for Compiler<'_> {
+    fn compile(&mut self, (decl_let, slot): (&ast::DeclLet, usize)) -> CompileResult<()> {
+        let span = decl_let.span();
+        log::trace!("DeclLet => {:?}", self.source.source(span));
+
+        self.asm.push(Inst::ReturnStatic { slot }, span);
+        self.compile((&*decl_let.expr, Needs::Value))?;
+
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+        self.locals_clean(total_var_count, span);
+        self.asm.push(Inst::Dup, span);
+        self.asm.push(Inst::StoreStatic { slot }, span);
+        self.asm.push(Inst::Return, span);
+        self.scopes.pop_last(span)?;
+        Ok(())
+    }
+}
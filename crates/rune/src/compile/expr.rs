@@ -48,9 +48,15 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
             ast::Expr::ExprIndexGet(expr_index_get) => {
                 self.compile((expr_index_get, needs))?;
             }
+            ast::Expr::ExprRange(expr_range) => {
+                self.compile((expr_range, needs))?;
+            }
             ast::Expr::ExprBreak(expr_break) => {
                 self.compile(expr_break)?;
             }
+            ast::Expr::ExprContinue(expr_continue) => {
+                self.compile(expr_continue)?;
+            }
             ast::Expr::ExprYield(expr_yield) => {
                 self.compile((expr_yield, needs))?;
             }
@@ -60,6 +66,14 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
             ast::Expr::ExprReturn(expr_return) => {
                 self.compile((expr_return, needs))?;
             }
+            ast::Expr::ExprWith(expr_with) => {
+                self.compile((expr_with, needs))?;
+            }
+            ast::Expr::ExprDefer(expr_defer) => {
+                return Err(CompileError::UnsupportedDefer {
+                    span: expr_defer.span(),
+                });
+            }
             ast::Expr::ExprMatch(expr_match) => {
                 self.compile((expr_match, needs))?;
             }
@@ -115,16 +129,7 @@ impl Compile<(&ast::Expr, Needs)> for Compiler<'_> {
                 self.compile((lit_template, needs))?;
             }
             ast::Expr::ExprCallMacro(expr_call_macro) => {
-                let _guard = self.items.push_macro();
-                let item = self.items.item();
-
-                if let Some(expr) = self.expanded_exprs.get(&item) {
-                    self.compile((expr, needs))?;
-                } else {
-                    let span = expr_call_macro.span();
-
-                    return Err(CompileError::internal("macro has not been expanded", span));
-                }
+                self.compile((expr_call_macro, needs))?;
             }
             // NB: declarations are not used in this compilation stage.
             // They have been separately indexed and will be built when queried
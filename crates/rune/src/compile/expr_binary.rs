@@ -3,7 +3,7 @@ use crate::compiler::{Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::{Compile, Resolve as _};
 use crate::CompileError;
-use runestick::Inst;
+use runestick::{ArithmeticOp, CompileMeta, Inst, Item, Span};
 
 /// Compile a binary expression.
 impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
@@ -33,9 +33,24 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
                 )?;
                 return Ok(());
             }
+            ast::BinOp::And | ast::BinOp::Or => {
+                compile_conditional_binop(
+                    self,
+                    &*expr_binary.lhs,
+                    &*expr_binary.rhs,
+                    expr_binary.op,
+                    needs,
+                )?;
+                return Ok(());
+            }
             _ => (),
         }
 
+        if let Some((operands, ops)) = comparison_chain(expr_binary) {
+            compile_comparison_chain(self, &operands, &ops, span, needs)?;
+            return Ok(());
+        }
+
         // NB: need to declare these as anonymous local variables so that they
         // get cleaned up in case there is an early break (return, try, ...).
         self.compile((&*expr_binary.lhs, Needs::Value))?;
@@ -44,6 +59,20 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
         self.compile((&*expr_binary.rhs, rhs_needs_of(expr_binary.op)))?;
         self.scopes.decl_anon(span)?;
 
+        if self.options.checked_arithmetic {
+            if let Some(op) = arithmetic_op(expr_binary.op) {
+                self.asm.push(Inst::CheckedOp { op }, span);
+                self.scopes.last_mut(span)?.undecl_anon(2, span)?;
+                self.compile_return_if_not(span)?;
+
+                if !needs.value() {
+                    self.asm.push(Inst::Pop, span);
+                }
+
+                return Ok(());
+            }
+        }
+
         match expr_binary.op {
             ast::BinOp::Add { .. } => {
                 self.asm.push(Inst::Add, span);
@@ -84,11 +113,8 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
             ast::BinOp::IsNot { .. } => {
                 self.asm.push(Inst::IsNot, span);
             }
-            ast::BinOp::And { .. } => {
-                self.asm.push(Inst::And, span);
-            }
-            ast::BinOp::Or { .. } => {
-                self.asm.push(Inst::Or, span);
+            ast::BinOp::As { .. } => {
+                self.asm.push(Inst::As, span);
             }
             ast::BinOp::BitAnd { .. } => {
                 self.asm.push(Inst::BitAnd, span);
@@ -121,15 +147,159 @@ impl Compile<(&ast::ExprBinary, Needs)> for Compiler<'_> {
     }
 }
 
+/// Get the arithmetic operation performed by the given operator, if it's
+/// checked when compiling with checked arithmetic.
+fn arithmetic_op(op: ast::BinOp) -> Option<ArithmeticOp> {
+    Some(match op {
+        ast::BinOp::Add | ast::BinOp::AddAssign => ArithmeticOp::Add,
+        ast::BinOp::Sub | ast::BinOp::SubAssign => ArithmeticOp::Sub,
+        ast::BinOp::Mul | ast::BinOp::MulAssign => ArithmeticOp::Mul,
+        ast::BinOp::Div | ast::BinOp::DivAssign => ArithmeticOp::Div,
+        ast::BinOp::Rem | ast::BinOp::RemAssign => ArithmeticOp::Rem,
+        _ => return None,
+    })
+}
+
 /// Get the need of the right-hand side operator from the type of the
 /// operator.
 fn rhs_needs_of(op: ast::BinOp) -> Needs {
     match op {
-        ast::BinOp::Is | ast::BinOp::IsNot => Needs::Type,
+        ast::BinOp::Is | ast::BinOp::IsNot | ast::BinOp::As => Needs::Type,
         _ => Needs::Value,
     }
 }
 
+/// Compile a short-circuiting `&&` or `||` expression.
+///
+/// The right-hand side is only evaluated if the left-hand side doesn't
+/// already determine the outcome.
+fn compile_conditional_binop(
+    compiler: &mut Compiler<'_>,
+    lhs: &ast::Expr,
+    rhs: &ast::Expr,
+    bin_op: ast::BinOp,
+    needs: Needs,
+) -> CompileResult<()> {
+    let span = lhs.span().join(rhs.span());
+    let end_label = compiler.asm.new_label("conditional_end");
+
+    compiler.check_boolean(lhs);
+    compiler.check_boolean(rhs);
+
+    compiler.compile((lhs, Needs::Value))?;
+
+    match bin_op {
+        ast::BinOp::And => {
+            compiler.asm.jump_if_not_or_pop(end_label, lhs.span());
+        }
+        ast::BinOp::Or => {
+            compiler.asm.jump_if_or_pop(end_label, lhs.span());
+        }
+        op => {
+            return Err(CompileError::UnsupportedBinaryOp { span, op });
+        }
+    }
+
+    compiler.compile((rhs, Needs::Value))?;
+    compiler.asm.label(end_label)?;
+
+    if !needs.value() {
+        compiler.asm.push(Inst::Pop, span);
+    }
+
+    Ok(())
+}
+
+/// Collect the operands and operators of a chained comparison like
+/// `a < b <= c`.
+fn comparison_chain(expr_binary: &ast::ExprBinary) -> Option<(Vec<&ast::Expr>, Vec<ast::BinOp>)> {
+    if !expr_binary.op.is_ordering() {
+        return None;
+    }
+
+    let mut operands = vec![&*expr_binary.rhs];
+    let mut ops = vec![expr_binary.op];
+    let mut lhs = &*expr_binary.lhs;
+
+    while let ast::Expr::ExprBinary(expr_binary) = lhs {
+        if !expr_binary.op.is_ordering() {
+            break;
+        }
+
+        operands.push(&*expr_binary.rhs);
+        ops.push(expr_binary.op);
+        lhs = &*expr_binary.lhs;
+    }
+
+    if ops.len() < 2 {
+        return None;
+    }
+
+    operands.push(lhs);
+    operands.reverse();
+    ops.reverse();
+    Some((operands, ops))
+}
+
+/// Compile a chained comparison like `a < b <= c` as if it was written
+/// `a < b && b <= c`, but with every operand only being evaluated once.
+///
+/// The two operands being compared are stored in anonymous variables, which
+/// are shifted down as the chain is evaluated.
+fn compile_comparison_chain(
+    compiler: &mut Compiler<'_>,
+    operands: &[&ast::Expr],
+    ops: &[ast::BinOp],
+    span: Span,
+    needs: Needs,
+) -> CompileResult<()> {
+    let end_label = compiler.asm.new_label("comparison_end");
+
+    compiler.compile((operands[0], Needs::Value))?;
+    let lhs = compiler.scopes.decl_anon(span)?;
+
+    compiler.compile((operands[1], Needs::Value))?;
+    let rhs = compiler.scopes.decl_anon(span)?;
+
+    for (n, (op, operand)) in ops.iter().zip(&operands[1..]).enumerate() {
+        let span = operand.span();
+
+        if n > 0 {
+            compiler.asm.push(Inst::Copy { offset: rhs }, span);
+            compiler.asm.push(Inst::Replace { offset: lhs }, span);
+            compiler.compile((*operand, Needs::Value))?;
+            compiler.asm.push(Inst::Replace { offset: rhs }, span);
+        }
+
+        compiler.asm.push(Inst::Copy { offset: lhs }, span);
+        compiler.asm.push(Inst::Copy { offset: rhs }, span);
+
+        match op {
+            ast::BinOp::Lt => compiler.asm.push(Inst::Lt, span),
+            ast::BinOp::Gt => compiler.asm.push(Inst::Gt, span),
+            ast::BinOp::Lte => compiler.asm.push(Inst::Lte, span),
+            ast::BinOp::Gte => compiler.asm.push(Inst::Gte, span),
+            op => {
+                return Err(CompileError::UnsupportedBinaryOp { span, op: *op });
+            }
+        }
+
+        if n + 1 < ops.len() {
+            compiler.asm.jump_if_not_or_pop(end_label, span);
+        }
+    }
+
+    compiler.asm.label(end_label)?;
+    compiler.locals_clean(2, span);
+    compiler.scopes.last_mut(span)?.undecl_anon(2, span)?;
+
+    if !needs.value() {
+        compiler.asm.push(Inst::Pop, span);
+    }
+
+    Ok(())
+}
+
 fn compile_assign_binop(
     compiler: &mut Compiler<'_>,
     lhs: &ast::Expr,
@@ -141,7 +311,7 @@ fn compile_assign_binop(
 
     // NB: this loop is actually useful in breaking early.
     #[allow(clippy::never_loop)]
-    let offset = loop {
+    let (offset, target) = loop {
         match lhs {
             ast::Expr::ExprFieldAccess(get) => match (&*get.expr, &get.expr_field) {
                 (ast::Expr::Path(ast::Path { first, rest }), expr_field) if rest.is_empty() => {
@@ -196,11 +366,25 @@ fn compile_assign_binop(
                 }
                 _ => (),
             },
-            ast::Expr::Path(ast::Path { first, rest }) if rest.is_empty() => {
+            ast::Expr::Path(path) if path.rest.is_empty() => {
+                let first = &path.first;
                 let span = first.span();
-                let first = first.resolve(&*compiler.source)?;
-                let var = compiler.scopes.get_var(first, span)?;
-                break var.offset;
+                let source = compiler.source.clone();
+                let name = first.resolve(&*source)?;
+
+                // NB: a name which isn't a local might refer to a static.
+                if compiler.scopes.try_get_var(name)?.is_none() {
+                    let item = compiler.convert_path_to_item(path)?;
+
+                    if let Some(CompileMeta::Static { item, slot }) =
+                        compiler.lookup_meta(&item, span)?
+                    {
+                        return compile_assign_static(compiler, &item, slot, rhs, bin_op, needs);
+                    }
+                }
+
+                let var = compiler.scopes.get_var(name, span)?;
+                break (var.offset, first);
             }
             _ => (),
         };
@@ -208,8 +392,40 @@ fn compile_assign_binop(
         return Err(CompileError::UnsupportedAssignExpr { span });
     };
 
+    // NB: in-place concatenation isn't checked, so it's not used with checked
+    // arithmetic.
+    if let ast::BinOp::Assign = bin_op {
+        if compiler.options.concat_in_place && !compiler.options.checked_arithmetic {
+            if let Some(operands) = concat_operands(compiler, target, rhs)? {
+                for operand in operands {
+                    compiler.compile((operand, Needs::Value))?;
+                    compiler.asm.push(Inst::ConcatAssign { offset }, span);
+                }
+
+                if needs.value() {
+                    compiler.asm.push(Inst::Unit, span);
+                }
+
+                return Ok(());
+            }
+        }
+    }
+
     compiler.compile((rhs, Needs::Value))?;
 
+    if compiler.options.checked_arithmetic {
+        if let Some(op) = arithmetic_op(bin_op) {
+            compiler.asm.push(Inst::CheckedAssign { op, offset }, span);
+            compiler.compile_return_if_not(span)?;
+
+            if needs.value() {
+                compiler.asm.push(Inst::Unit, span);
+            }
+
+            return Ok(());
+        }
+    }
+
     match bin_op {
         ast::BinOp::Assign => {
             compiler.asm.push(Inst::Replace { offset }, span);
@@ -256,6 +472,120 @@ fn compile_assign_binop(
     Ok(())
 }
 
+/// Compile an assignment to a static, which stores the new value in the slot
+/// of the static.
+fn compile_assign_static(
+    compiler: &mut Compiler<'_>,
+    item: &Item,
+    slot: usize,
+    rhs: &ast::Expr,
+    bin_op: ast::BinOp,
+    needs: Needs,
+) -> CompileResult<()> {
+    let span = rhs.span();
+
+    if let ast::BinOp::Assign = bin_op {
+        compiler.compile((rhs, Needs::Value))?;
+    } else {
+        // NB: the current value is read through the static, which initializes
+        // it if this is its first use.
+        compiler.compile_static(item, span);
+        compiler.scopes.decl_anon(span)?;
+        compiler.compile((rhs, Needs::Value))?;
+        compiler.scopes.decl_anon(span)?;
+
+        match arithmetic_op(bin_op) {
+            Some(op) if compiler.options.checked_arithmetic => {
+                compiler.asm.push(Inst::CheckedOp { op }, span);
+                compiler.scopes.last_mut(span)?.undecl_anon(2, span)?;
+                compiler.compile_return_if_not(span)?;
+            }
+            _ => {
+                let inst = match bin_op {
+                    ast::BinOp::AddAssign => Inst::Add,
+                    ast::BinOp::SubAssign => Inst::Sub,
+                    ast::BinOp::MulAssign => Inst::Mul,
+                    ast::BinOp::DivAssign => Inst::Div,
+                    ast::BinOp::RemAssign => Inst::Rem,
+                    ast::BinOp::BitAndAssign => Inst::BitAnd,
+                    ast::BinOp::BitXorAssign => Inst::BitXor,
+                    ast::BinOp::BitOrAssign => Inst::BitOr,
+                    ast::BinOp::ShlAssign => Inst::Shl,
+                    ast::BinOp::ShrAssign => Inst::Shr,
+                    op => return Err(CompileError::UnsupportedAssignBinOp { span, op }),
+                };
+
+                compiler.asm.push(inst, span);
+                compiler.scopes.last_mut(span)?.undecl_anon(2, span)?;
+            }
+        }
+    }
+
+    compiler.asm.push(Inst::StoreStatic { slot }, span);
+
+    if needs.value() {
+        compiler.asm.push(Inst::Unit, span);
+    }
+
+    Ok(())
+}
+
+/// Collect the operands of an assignment like `a = a + b + c` which can be
+/// lowered into a sequence of in-place concatenations to `a`.
+///
+/// Since every operand is appended to `a` before the next one is evaluated,
+/// this is only done if no operand beyond the first could observe `a`.
+fn concat_operands<'a>(
+    compiler: &Compiler<'_>,
+    target: &ast::Ident,
+    mut expr: &'a ast::Expr,
+) -> CompileResult<Option<Vec<&'a ast::Expr>>> {
+    let source = compiler.source.clone();
+    let target = target.resolve(&*source)?;
+
+    let mut operands = Vec::new();
+
+    let first = loop {
+        match expr {
+            ast::Expr::ExprBinary(ast::ExprBinary {
+                lhs,
+                op: ast::BinOp::Add,
+                rhs,
+            }) => {
+                operands.push(&**rhs);
+                expr = &**lhs;
+            }
+            expr => break expr,
+        }
+    };
+
+    match first {
+        ast::Expr::Path(path) if !operands.is_empty() => match path.try_as_ident() {
+            Some(ident) if ident.resolve(&*source)? == target => (),
+            _ => return Ok(None),
+        },
+        _ => return Ok(None),
+    }
+
+    operands.reverse();
+
+    for operand in operands.iter().skip(1) {
+        let observes_target = match operand {
+            ast::Expr::Path(path) => match path.try_as_ident() {
+                Some(ident) => ident.resolve(&*source)? == target,
+                None => false,
+            },
+            operand => !operand.is_const(),
+        };
+
+        if observes_target {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(operands))
+}
+
 /// Compile a tuple index set operation with a number field.
 fn compile_tuple_index_set_number(
     compiler: &mut Compiler<'_>,
@@ -8,6 +8,10 @@ use runestick::{CompileMeta, CompileMetaCapture, Hash, Inst};
 struct CallAsync(());
 struct BlockBody(());
 
+/// Marker used to compile a block which is the branch of a conditional
+/// expression.
+pub(crate) struct Branch;
+
 /// Compile the async block.
 impl Compile<(ast::ExprBlock, &[CompileMetaCapture])> for Compiler<'_> {
     fn compile(
@@ -24,7 +28,7 @@ impl Compile<(ast::ExprBlock, &[CompileMetaCapture])> for Compiler<'_> {
             self.scopes.new_var(&capture.ident, span)?;
         }
 
-        self.compile((BlockBody(()), &expr_block, Needs::Value))?;
+        self.compile((BlockBody(()), &expr_block, Needs::Value, true))?;
         self.clean_last_scope(span, guard, Needs::Value)?;
         self.asm.push(Inst::Return, span);
         Ok(())
@@ -40,7 +44,26 @@ impl Compile<(&ast::ExprBlock, Needs)> for Compiler<'_> {
         if let Some(..) = expr_block.async_ {
             self.compile((CallAsync(()), expr_block))?;
         } else {
-            self.compile((BlockBody(()), expr_block, needs))?;
+            self.compile((BlockBody(()), expr_block, needs, true))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compile the block of a conditional branch.
+///
+/// Values discarded by a semi-colon are not warned about here, since branches
+/// without values are checked by the conditional expression itself.
+impl Compile<(Branch, &ast::ExprBlock, Needs)> for Compiler<'_> {
+    fn compile(
+        &mut self,
+        (_, expr_block, needs): (Branch, &ast::ExprBlock, Needs),
+    ) -> CompileResult<()> {
+        if expr_block.async_.is_some() {
+            self.compile((CallAsync(()), expr_block))?;
+        } else {
+            self.compile((BlockBody(()), expr_block, needs, false))?;
         }
 
         Ok(())
@@ -90,10 +113,13 @@ impl Compile<(CallAsync, &ast::ExprBlock)> for Compiler<'_> {
 }
 
 /// Call a block.
-impl Compile<(BlockBody, &ast::ExprBlock, Needs)> for Compiler<'_> {
+///
+/// The last flag indicates if values discarded by the last statement should
+/// be warned about when the block is expected to produce a value.
+impl Compile<(BlockBody, &ast::ExprBlock, Needs, bool)> for Compiler<'_> {
     fn compile(
         &mut self,
-        (_, expr_block, needs): (BlockBody, &ast::ExprBlock, Needs),
+        (_, expr_block, needs, check_discarded): (BlockBody, &ast::ExprBlock, Needs, bool),
     ) -> CompileResult<()> {
         let span = expr_block.span();
         log::trace!("ExprBlock => {:?}", self.source.source(span));
@@ -107,17 +133,25 @@ impl Compile<(BlockBody, &ast::ExprBlock, Needs)> for Compiler<'_> {
         let new_scope = self.scopes.child(span)?;
         let scopes_count = self.scopes.push(new_scope);
 
-        for (expr, _) in &expr_block.exprs {
-            // NB: terminated expressions do not need to produce a value.
-            self.compile((expr, Needs::None))?;
-        }
+        self.compile_block_stmts(
+            &expr_block.exprs,
+            check_discarded && needs.value() && expr_block.trailing_expr.is_none(),
+        )?;
 
         if let Some(expr) = &expr_block.trailing_expr {
-            self.compile((&**expr, needs))?;
+            self.compile_stmt(&**expr, needs)?;
         }
 
         let scope = self.scopes.pop(scopes_count, span)?;
 
+        let total_var_count = if needs.value() && expr_block.trailing_expr.is_some() {
+            scope.total_var_count + 1
+        } else {
+            scope.total_var_count
+        };
+
+        self.compile_deferred(&scope.deferred(), total_var_count, span)?;
+
         if needs.value() {
             if expr_block.trailing_expr.is_none() {
                 self.locals_pop(scope.local_var_count, span);
@@ -30,6 +30,12 @@ impl Compile<&ast::ExprBreak> for Compiler<'_> {
                         self.loops.walk_until_label(&*self.source, *label)?;
                     (last_loop, to_drop, false)
                 }
+                ast::ExprBreakValue::LabelExpr(label, expr) => {
+                    let (last_loop, to_drop) =
+                        self.loops.walk_until_label(&*self.source, *label)?;
+                    self.compile((&**expr, last_loop.needs))?;
+                    (last_loop, to_drop, true)
+                }
             }
         } else {
             (current_loop, current_loop.drop.into_iter().collect(), false)
@@ -40,13 +46,20 @@ impl Compile<&ast::ExprBreak> for Compiler<'_> {
             self.asm.push(Inst::Drop { offset }, span);
         }
 
-        let vars = self
-            .scopes
-            .last(span)?
-            .total_var_count
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+
+        let vars = total_var_count
             .checked_sub(last_loop.total_var_count)
             .ok_or_else(|| CompileError::internal("var count should be larger", span))?;
 
+        let deferred = self.scopes.deferred_from(last_loop.scopes);
+        let total_var_count = if has_value && last_loop.needs.value() {
+            total_var_count + 1
+        } else {
+            total_var_count
+        };
+        self.compile_deferred(&deferred, total_var_count, span)?;
+
         if last_loop.needs.value() {
             if has_value {
                 self.locals_clean(vars, span);
@@ -5,6 +5,11 @@ use crate::traits::{Compile, Resolve as _};
 use crate::CompileError;
 use runestick::{CompileMeta, Hash, Inst};
 
+/// Names of instance functions which modify the collection they are called on.
+const MUTATING_METHODS: &[&str] = &[
+    "push", "pop", "insert", "remove", "clear", "extend", "truncate", "retain",
+];
+
 /// Compile a call expression.
 impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
     fn compile(&mut self, (expr_call, needs): (&ast::ExprCall, Needs)) -> CompileResult<()> {
@@ -35,6 +40,36 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
                         self.source.source(span)
                     );
 
+                    let ident = ident.resolve(&*self.source)?;
+
+                    let extensions = self
+                        .unit
+                        .borrow()
+                        .lookup_extensions(&self.items.item(), ident);
+
+                    let inst = match &extensions[..] {
+                        [] => Inst::CallInstance {
+                            hash: Hash::of(ident),
+                            args,
+                        },
+                        [extension] => Inst::CallExtension {
+                            hash: Hash::type_hash(extension.extended(ident)),
+                            fallback: Hash::of(ident),
+                            args,
+                        },
+                        _ => {
+                            return Err(CompileError::AmbiguousExtensionFunction {
+                                span,
+                                name: ident.to_owned(),
+                                extensions,
+                            });
+                        }
+                    };
+
+                    if MUTATING_METHODS.contains(&ident) {
+                        self.check_mutate_while_iterating(&**expr, span)?;
+                    }
+
                     self.compile((&**expr, Needs::Value))?;
 
                     for (expr, _) in expr_call.args.items.iter() {
@@ -42,9 +77,7 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
                         self.scopes.decl_anon(span)?;
                     }
 
-                    let ident = ident.resolve(&*self.source)?;
-                    let hash = Hash::of(ident);
-                    self.asm.push(Inst::CallInstance { hash, args }, span);
+                    self.asm.push(inst, span);
                 }
                 expr => {
                     log::trace!("ExprCall(Other) => {:?}", self.source.source(span));
@@ -118,15 +151,44 @@ impl Compile<(&ast::ExprCall, Needs)> for Compiler<'_> {
 
                 tuple.item.clone()
             }
-            CompileMeta::Function { item, .. } => item.clone(),
+            CompileMeta::Function {
+                item, signature, ..
+            }
+            | CompileMeta::AssociatedFunction {
+                item, signature, ..
+            }
+            | CompileMeta::InstanceFunction {
+                item, signature, ..
+            } => {
+                // NB: native functions which take any number of arguments
+                // don't have a signature, and are checked when called.
+                if let Some(signature) = signature {
+                    if signature.args.len() != args {
+                        return Err(CompileError::CallArgumentCount {
+                            span,
+                            signature: signature.clone(),
+                            expected: signature.args.len(),
+                            actual: args,
+                        });
+                    }
+                }
+
+                item.clone()
+            }
             _ => {
                 return Err(CompileError::MissingFunction { span, item });
             }
         };
 
         let hash = Hash::type_hash(&item);
+
+        let inst = match self.intrinsic_slot(hash, args) {
+            Some(slot) => Inst::CallIntrinsic { slot, hash, args },
+            None => Inst::Call { hash, args },
+        };
+
         self.asm
-            .push_with_comment(Inst::Call { hash, args }, span, format!("fn `{}`", item));
+            .push_with_comment(inst, span, format!("fn `{}`", item));
 
         // NB: we put it here to preserve the call in case it has side effects.
         // But if we don't need the value, then pop it from the stack.
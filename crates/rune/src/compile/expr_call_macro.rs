@@ -0,0 +1,134 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::macros::BuiltinMacro;
+use crate::traits::{Compile, Resolve as _};
+use crate::{CompileError, Parser};
+use runestick::{Hash, Inst};
+
+/// Compile a macro call, either by compiling its expansion or by compiling a
+/// built-in macro directly.
+impl Compile<(&ast::ExprCallMacro, Needs)> for Compiler<'_> {
+    fn compile(
+        &mut self,
+        (expr_call_macro, needs): (&ast::ExprCallMacro, Needs),
+    ) -> CompileResult<()> {
+        let span = expr_call_macro.span();
+        log::trace!("ExprCallMacro => {:?}", self.source.source(span));
+
+        let _guard = self.items.push_macro();
+
+        if let Some(builtin) = BuiltinMacro::find(&expr_call_macro.path, &*self.source)? {
+            return self.compile((expr_call_macro, builtin, needs));
+        }
+
+        let item = self.items.item();
+
+        if let Some(expr) = self.expanded_exprs.get(&item) {
+            self.compile((expr, needs))?;
+        } else {
+            return Err(CompileError::internal("macro has not been expanded", span));
+        }
+
+        Ok(())
+    }
+}
+
+/// Compile a built-in macro like `env!("KEY")`.
+impl Compile<(&ast::ExprCallMacro, BuiltinMacro, Needs)> for Compiler<'_> {
+    fn compile(
+        &mut self,
+        (expr_call_macro, builtin, needs): (&ast::ExprCallMacro, BuiltinMacro, Needs),
+    ) -> CompileResult<()> {
+        let span = expr_call_macro.span();
+
+        if let BuiltinMacro::Dbg = builtin {
+            return compile_dbg(self, expr_call_macro, needs);
+        }
+
+        let mut parser = Parser::from_token_stream(&expr_call_macro.stream);
+        let name = parser.parse::<ast::LitStr>()?;
+        parser.parse_eof()?;
+
+        let name = name.resolve(&*self.source)?;
+        let value = self.options.defines.get(&*name).cloned();
+
+        let value = match (builtin, value) {
+            (BuiltinMacro::Env, Some(value)) => Some(value),
+            (BuiltinMacro::Env, None) => {
+                return Err(CompileError::MissingDefine {
+                    span,
+                    name: name.into_owned(),
+                });
+            }
+            (_, value) => value,
+        };
+
+        // NB: Elide the entire macro if it's not needed.
+        if !needs.value() {
+            self.warnings.not_used(self.source_id, span, self.context());
+            return Ok(());
+        }
+
+        if let Some(value) = &value {
+            let slot = self.unit.borrow_mut().new_static_string(value)?;
+            self.asm.push(Inst::String { slot }, span);
+        }
+
+        if let BuiltinMacro::OptionEnv = builtin {
+            let (variant, args) = match value {
+                Some(..) => ("Some", 1),
+                None => ("None", 0),
+            };
+
+            let hash = Hash::type_hash(&["std", "option", "Option", variant]);
+            self.asm.push(Inst::Call { hash, args }, span);
+        }
+
+        Ok(())
+    }
+}
+
+/// Compile `dbg!(<expr>)`, which passes the location and text of the
+/// expression along with its value to `std::dbg_expr`.
+fn compile_dbg(
+    this: &mut Compiler<'_>,
+    expr_call_macro: &ast::ExprCallMacro,
+    needs: Needs,
+) -> CompileResult<()> {
+    let span = expr_call_macro.span();
+    let expr = BuiltinMacro::parse_dbg(expr_call_macro)?;
+    let expr_span = expr.span();
+
+    let text = this
+        .source
+        .source(expr_span)
+        .ok_or_else(|| CompileError::internal("missing source for expression", expr_span))?;
+
+    let location = format!(
+        "{}:{} {}",
+        this.source.name(),
+        this.source.line(expr_span.start),
+        text
+    );
+
+    let scope = this.scopes.child(span)?;
+    let guard = this.scopes.push(scope);
+
+    let slot = this.unit.borrow_mut().new_static_string(&location)?;
+    this.asm.push(Inst::String { slot }, span);
+    this.scopes.decl_anon(span)?;
+
+    this.compile((&expr, Needs::Value))?;
+    this.scopes.decl_anon(span)?;
+
+    let hash = Hash::type_hash(&["std", "dbg_expr"]);
+    this.asm.push(Inst::Call { hash, args: 2 }, span);
+
+    if !needs.value() {
+        this.asm.push(Inst::Pop, span);
+    }
+
+    this.scopes.pop(guard, span)?;
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::ast;
+use crate::ast::visit::{self, Visit};
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::scopes::Deferred;
+use crate::traits::Compile;
+use crate::CompileError;
+use runestick::{Inst, Span};
+
+/// Compile a `defer` statement.
+///
+/// The deferred block is compiled into every path which exits the current
+/// scope, which is when the block ends or through a `return`, `?` or `break`.
+impl Compile<(&ast::ExprDefer, Needs)> for Compiler<'_> {
+    fn compile(&mut self, (expr_defer, needs): (&ast::ExprDefer, Needs)) -> CompileResult<()> {
+        let span = expr_defer.span();
+        log::trace!("ExprDefer => {:?}", self.source.source(span));
+
+        let mut find = FindReturn(None);
+        find.visit_expr_block(&expr_defer.body);
+
+        if let Some(span) = find.0 {
+            return Err(CompileError::ReturnInDefer { span });
+        }
+
+        let items = self.items.snapshot();
+        // NB: the block is compiled from the snapshot, so skip over the block
+        // that was indexed for it.
+        let _ = self.items.push_block();
+
+        let deferred = Deferred::Block {
+            body: (*expr_defer.body).clone(),
+            scopes: self.scopes.snapshot(),
+            items,
+        };
+
+        self.scopes.last_mut(span)?.defer(deferred);
+
+        if needs.value() {
+            self.asm.push(Inst::Unit, span);
+        }
+
+        Ok(())
+    }
+}
+
+/// Find the first `return` or `?` which would exit the function a deferred
+/// block is compiled into.
+struct FindReturn(Option<Span>);
+
+impl Visit for FindReturn {
+    fn visit_expr_return(&mut self, node: &ast::ExprReturn) {
+        self.0.get_or_insert(node.span());
+    }
+
+    fn visit_expr_try(&mut self, node: &ast::ExprTry) {
+        self.0.get_or_insert(node.span());
+    }
+
+    fn visit_expr_block(&mut self, node: &ast::ExprBlock) {
+        if node.async_.is_none() {
+            visit::walk_expr_block(self, node);
+        }
+    }
+
+    fn visit_expr_closure(&mut self, _: &ast::ExprClosure) {}
+
+    fn visit_decl(&mut self, _: &ast::Decl) {}
+}
@@ -16,33 +16,30 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
         let break_label = self.asm.new_label("for_break");
 
         let total_var_count = self.scopes.last(span)?.total_var_count;
+        let iter_var = self.local_var_offset(&*expr_for.iter)?;
 
         let (iter_offset, loop_scope_expected) = {
             let mut loop_scope = self.scopes.child(span)?;
             self.compile((&*expr_for.iter, Needs::Value))?;
 
             let iter_offset = loop_scope.decl_anon(span);
-            self.asm.push_with_comment(
-                Inst::CallInstance {
-                    hash: *runestick::INTO_ITER,
-                    args: 0,
-                },
-                span,
-                format!("into_iter (offset: {})", iter_offset),
-            );
+
+            // NB: streams are iterated over directly.
+            if expr_for.await_.is_none() {
+                self.asm.push_with_comment(
+                    Inst::CallInstance {
+                        hash: *runestick::INTO_ITER,
+                        args: 0,
+                    },
+                    span,
+                    format!("into_iter (offset: {})", iter_offset),
+                );
+            }
 
             let loop_scope_expected = self.scopes.push(loop_scope);
             (iter_offset, loop_scope_expected)
         };
 
-        let _guard = self.loops.push(Loop {
-            label: expr_for.label.map(|(label, _)| label),
-            break_label,
-            total_var_count,
-            needs,
-            drop: Some(iter_offset),
-        });
-
         // Declare named loop variable.
         let binding_offset = {
             self.asm.push(Inst::Unit, expr_for.iter.span());
@@ -80,6 +77,18 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
             None
         };
 
+        let guard = self.loops.push(Loop {
+            label: expr_for.label.map(|(label, _)| label),
+            break_label,
+            continue_label: start_label,
+            total_var_count,
+            continue_var_count: self.scopes.last(span)?.total_var_count,
+            scopes: self.scopes.len(),
+            needs,
+            drop: Some(iter_offset),
+            iter_var: iter_var.map(|offset| (offset, expr_for.iter.span())),
+        });
+
         self.asm.label(start_label)?;
 
         // Use the memoized loop variable.
@@ -101,13 +110,6 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
             );
 
             self.asm.push(Inst::CallFn { args: 1 }, span);
-
-            self.asm.push(
-                Inst::Replace {
-                    offset: binding_offset,
-                },
-                expr_for.var.span(),
-            );
         } else {
             // call the `next` function to get the next level of iteration, bind the
             // result to the loop variable in the loop.
@@ -126,14 +128,19 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
                 span,
                 "next",
             );
-            self.asm.push(
-                Inst::Replace {
-                    offset: binding_offset,
-                },
-                expr_for.var.span(),
-            );
         }
 
+        if let Some(await_) = &expr_for.await_ {
+            self.asm.push(Inst::Await, await_.span());
+        }
+
+        self.asm.push(
+            Inst::Replace {
+                offset: binding_offset,
+            },
+            expr_for.var.span(),
+        );
+
         // test loop condition and unwrap the option.
         // TODO: introduce a dedicated instruction for this :|.
         {
@@ -175,8 +182,14 @@ impl Compile<(&ast::ExprFor, Needs)> for Compiler<'_> {
 
         self.clean_last_scope(span, loop_scope_expected, Needs::None)?;
 
-        // NB: If a value is needed from a for loop, encode it as a unit.
-        if needs.value() {
+        // NB: breaks in the else block belong to the enclosing loop.
+        drop(guard);
+
+        // NB: If a value is needed from a for loop without an else block,
+        // encode it as a unit.
+        if let Some(expr_else) = &expr_for.expr_else {
+            self.compile((&*expr_else.block, needs))?;
+        } else if needs.value() {
             self.asm.push(Inst::Unit, span);
         }
 
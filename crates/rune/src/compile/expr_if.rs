@@ -1,5 +1,6 @@
 use crate::ast;
-use crate::compiler::{Compiler, Needs};
+use crate::compile::expr_block::Branch;
+use crate::compiler::{block_value, Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
 use runestick::Inst;
@@ -10,6 +11,23 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
         let span = expr_if.span();
         log::trace!("ExprIf => {:?}", self.source.source(span));
 
+        if needs.value() {
+            let mut branches = vec![(expr_if.block.span(), block_value(&*expr_if.block))];
+
+            for branch in &expr_if.expr_else_ifs {
+                branches.push((branch.block.span(), block_value(&*branch.block)));
+            }
+
+            match &expr_if.expr_else {
+                Some(fallback) => {
+                    branches.push((fallback.block.span(), block_value(&*fallback.block)))
+                }
+                None => branches.push((span, Some(false))),
+            }
+
+            self.check_branch_values(&branches);
+        }
+
         let then_label = self.asm.new_label("if_then");
         let end_label = self.asm.new_label("if_end");
 
@@ -24,7 +42,7 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
 
         // use fallback as fall through.
         if let Some(fallback) = &expr_if.expr_else {
-            self.compile((&*fallback.block, needs))?;
+            self.compile((Branch, &*fallback.block, needs))?;
         } else {
             // NB: if we must produce a value and there is no fallback branch,
             // encode the result of the statement as a unit.
@@ -38,7 +56,7 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
         self.asm.label(then_label)?;
 
         let expected = self.scopes.push(then_scope);
-        self.compile((&*expr_if.block, needs))?;
+        self.compile((Branch, &*expr_if.block, needs))?;
         self.clean_last_scope(span, expected, needs)?;
 
         if !expr_if.expr_else_ifs.is_empty() {
@@ -53,7 +71,7 @@ impl Compile<(&ast::ExprIf, Needs)> for Compiler<'_> {
             self.asm.label(label)?;
 
             let scopes = self.scopes.push(scope);
-            self.compile((&*branch.block, needs))?;
+            self.compile((Branch, &*branch.block, needs))?;
             self.clean_last_scope(span, scopes, needs)?;
 
             if it.peek().is_some() {
@@ -22,7 +22,11 @@ impl Compile<(&ast::ExprIndexGet, Needs)> for Compiler<'_> {
         self.compile((&*expr_index_get.target, Needs::Value))?;
         self.scopes.decl_anon(span)?;
 
-        self.asm.push(Inst::IndexGet, span);
+        if self.options.negative_indices {
+            self.asm.push(Inst::NegativeIndexGet, span);
+        } else {
+            self.asm.push(Inst::IndexGet, span);
+        }
 
         // NB: we still need to perform the operation since it might have side
         // effects, but pop the result in case a value is not needed.
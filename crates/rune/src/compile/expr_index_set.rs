@@ -13,6 +13,8 @@ impl Compile<(&ast::ExprIndexSet, Needs)> for Compiler<'_> {
         let span = expr_index_set.span();
         log::trace!("ExprIndexSet => {:?}", self.source.source(span));
 
+        self.check_mutate_while_iterating(&*expr_index_set.target, span)?;
+
         self.compile((&*expr_index_set.value, Needs::Value))?;
         self.compile((&*expr_index_set.index, Needs::Value))?;
         self.compile((&*expr_index_set.target, Needs::Value))?;
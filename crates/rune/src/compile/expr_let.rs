@@ -20,7 +20,12 @@ impl Compile<(&ast::ExprLet, Needs)> for Compiler<'_> {
 
         let false_label = self.asm.new_label("let_panic");
 
-        if self.compile_pat(&mut scope, &expr_let.pat, false_label, &load)? {
+        // NB: tuples of bindings are unpacked directly. Like a call with the
+        // wrong number of arguments, this panics if the value isn't a tuple of
+        // the expected size, which isn't warned about.
+        let unpacked = self.compile_pat_unpack(&mut scope, &expr_let.pat)?;
+
+        if !unpacked && self.compile_pat(&mut scope, &expr_let.pat, false_label, &load)? {
             self.warnings
                 .let_pattern_might_panic(self.source_id, span, self.context());
 
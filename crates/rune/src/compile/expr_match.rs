@@ -1,6 +1,7 @@
 use crate::assembly::Assembly;
 use crate::ast;
-use crate::compiler::{Compiler, Needs};
+use crate::compile::expr_block::Branch;
+use crate::compiler::{branch_value, Compiler, Needs};
 use crate::error::CompileResult;
 use crate::traits::Compile;
 use runestick::Inst;
@@ -10,6 +11,16 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
         let span = expr_match.span();
         log::trace!("ExprMatch => {:?}", self.source.source(span));
 
+        if needs.value() {
+            let branches = expr_match
+                .branches
+                .iter()
+                .map(|(branch, _)| (branch.body.span(), branch_value(&*branch.body)))
+                .collect::<Vec<_>>();
+
+            self.check_branch_values(&branches);
+        }
+
         let new_scope = self.scopes.child(span)?;
         let expected_scopes = self.scopes.push(new_scope);
 
@@ -76,7 +87,16 @@ impl Compile<(&ast::ExprMatch, Needs)> for Compiler<'_> {
             self.asm.label(*label)?;
 
             let expected = self.scopes.push(scope.clone());
-            self.compile((&*branch.body, needs))?;
+
+            match &*branch.body {
+                ast::Expr::ExprBlock(expr_block) => {
+                    self.compile((Branch, expr_block, needs))?;
+                }
+                body => {
+                    self.compile((body, needs))?;
+                }
+            }
+
             self.clean_last_scope(span, expected, needs)?;
 
             if it.peek().is_some() {
@@ -0,0 +1,35 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::traits::Compile;
+use runestick::Inst;
+
+/// Compile a range expression.
+impl Compile<(&ast::ExprRange, Needs)> for Compiler<'_> {
+    fn compile(&mut self, (expr_range, needs): (&ast::ExprRange, Needs)) -> CompileResult<()> {
+        let span = expr_range.span();
+        log::trace!("ExprRange => {:?}", self.source.source(span));
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
+        // NB: absent bounds are encoded as units.
+        for bound in &[&expr_range.from, &expr_range.to] {
+            match bound {
+                Some(expr) => self.compile((&**expr, Needs::Value))?,
+                None => self.asm.push(Inst::Unit, span),
+            }
+
+            self.scopes.decl_anon(span)?;
+        }
+
+        self.asm.push(Inst::Range, span);
+
+        if !needs.value() {
+            self.asm.push(Inst::Pop, span);
+        }
+
+        self.scopes.pop(guard, span)?;
+        Ok(())
+    }
+}
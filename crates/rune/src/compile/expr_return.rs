@@ -20,12 +20,17 @@ impl Compile<(&ast::ExprReturn, Needs)> for Compiler<'_> {
         // NB: we actually want total_var_count here since we need to clean up
         // _every_ variable declared until we reached the current return.
         let total_var_count = self.scopes.last(span)?.total_var_count;
+        let deferred = self.scopes.deferred_from(0);
 
-        if let Some(expr) = &return_expr.expr {
+        if let Some(ast::Expr::LitTuple(lit_tuple)) = return_expr.expr.as_deref() {
+            self.compile_return_tuple(lit_tuple, total_var_count, &deferred)?;
+        } else if let Some(expr) = &return_expr.expr {
             self.compile((&**expr, Needs::Value))?;
+            self.compile_deferred(&deferred, total_var_count + 1, span)?;
             self.locals_clean(total_var_count, span);
             self.asm.push(Inst::Return, span);
         } else {
+            self.compile_deferred(&deferred, total_var_count, span)?;
             self.locals_pop(total_var_count, span);
             self.asm.push(Inst::ReturnUnit, span);
         }
@@ -10,19 +10,12 @@ impl Compile<(&ast::ExprTry, Needs)> for Compiler<'_> {
         let span = expr_try.span();
         log::trace!("ExprTry => {:?}", self.source.source(span));
 
-        let not_error = self.asm.new_label("try_not_error");
-
         self.compile((&*expr_try.expr, Needs::Value))?;
         self.asm.push(Inst::Dup, span);
         self.asm.push(Inst::IsValue, span);
-        self.asm.jump_if(not_error, span);
 
         // Clean up all locals so far and return from the current function.
-        let total_var_count = self.scopes.last(span)?.total_var_count;
-        self.locals_clean(total_var_count, span);
-        self.asm.push(Inst::Return, span);
-
-        self.asm.label(not_error)?;
+        self.compile_return_if_not(span)?;
 
         if needs.value() {
             self.asm.push(Inst::Unwrap, span);
@@ -16,12 +16,18 @@ impl Compile<(&ast::ExprWhile, Needs)> for Compiler<'_> {
         let end_label = self.asm.new_label("while_end");
         let break_label = self.asm.new_label("while_break");
 
-        let _guard = self.loops.push(Loop {
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+
+        let guard = self.loops.push(Loop {
             label: expr_while.label.map(|(label, _)| label),
             break_label,
-            total_var_count: self.scopes.last(span)?.total_var_count,
+            continue_label: start_label,
+            total_var_count,
+            continue_var_count: total_var_count,
+            scopes: self.scopes.len(),
             needs,
             drop: None,
+            iter_var: None,
         });
 
         self.asm.label(start_label)?;
@@ -37,7 +43,12 @@ impl Compile<(&ast::ExprWhile, Needs)> for Compiler<'_> {
         self.asm.jump(start_label, span);
         self.asm.label(end_label)?;
 
-        if needs.value() {
+        // NB: breaks in the else block belong to the enclosing loop.
+        drop(guard);
+
+        if let Some(expr_else) = &expr_while.expr_else {
+            self.compile((&*expr_else.block, needs))?;
+        } else if needs.value() {
             self.asm.push(Inst::Unit, span);
         }
 
@@ -0,0 +1,61 @@
+use crate::ast;
+use crate::compiler::{Compiler, Needs};
+use crate::error::CompileResult;
+use crate::scopes::Deferred;
+use crate::traits::{Compile, Resolve as _};
+use runestick::Inst;
+
+/// Compile a `with` expression.
+///
+/// The value is entered through the `ENTER` protocol before the body, and
+/// exited through the `EXIT` protocol in every path which exits the body.
+impl Compile<(&ast::ExprWith, Needs)> for Compiler<'_> {
+    fn compile(&mut self, (expr_with, needs): (&ast::ExprWith, Needs)) -> CompileResult<()> {
+        let span = expr_with.span();
+        log::trace!("ExprWith => {:?}", self.source.source(span));
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
+        self.compile((&*expr_with.expr, Needs::Value))?;
+        let offset = self.scopes.decl_anon(span)?;
+
+        self.asm.push(Inst::Copy { offset }, span);
+        self.asm.push(Inst::Enter, span);
+
+        match &expr_with.binding {
+            Some(ident) => {
+                let span = ident.span();
+                let name = ident.resolve(&*self.source)?;
+                self.scopes.new_var(name, span)?;
+            }
+            None => {
+                self.scopes.decl_anon(span)?;
+            }
+        }
+
+        self.scopes
+            .last_mut(span)?
+            .defer(Deferred::Exit { offset, span });
+
+        self.compile((&*expr_with.body, needs))?;
+
+        let scope = self.scopes.pop(guard, span)?;
+
+        let total_var_count = if needs.value() {
+            scope.total_var_count + 1
+        } else {
+            scope.total_var_count
+        };
+
+        self.compile_deferred(&scope.deferred(), total_var_count, span)?;
+
+        if needs.value() {
+            self.locals_clean(scope.local_var_count, span);
+        } else {
+            self.locals_pop(scope.local_var_count, span);
+        }
+
+        Ok(())
+    }
+}
@@ -5,6 +5,10 @@ use crate::traits::Compile;
 use runestick::Inst;
 
 /// Compile a `yield` expression.
+///
+/// If there's cleanup pending at the yield, it's followed by a check for
+/// whether the generator is being disposed of, in which case the cleanup is
+/// run and the generator returns.
 impl Compile<(&ast::ExprYield, Needs)> for Compiler<'_> {
     fn compile(&mut self, (expr_yield, needs): (&ast::ExprYield, Needs)) -> CompileResult<()> {
         let span = expr_yield.span();
@@ -17,6 +21,22 @@ impl Compile<(&ast::ExprYield, Needs)> for Compiler<'_> {
             self.asm.push(Inst::YieldUnit, span);
         }
 
+        let deferred = self.scopes.deferred_from(0);
+
+        if !deferred.is_empty() {
+            let resumed = self.asm.new_label("resumed");
+            self.asm.push(Inst::Disposed, span);
+            self.asm.jump_if_not(resumed, span);
+
+            let total_var_count = self.scopes.last(span)?.total_var_count;
+            self.asm.push(Inst::Pop, span);
+            self.compile_deferred(&deferred, total_var_count, span)?;
+            self.locals_pop(total_var_count, span);
+            self.asm.push(Inst::ReturnUnit, span);
+
+            self.asm.label(resumed)?;
+        }
+
         if !needs.value() {
             self.asm.push(Inst::Pop, span);
         }
@@ -25,6 +25,13 @@ impl Compile<(&ast::LitNumber, Needs)> for Compiler<'_> {
             ast::Number::Integer(number) => {
                 self.asm.push(Inst::Integer { number }, span);
             }
+            ast::Number::Decimal(decimal) => {
+                let slot = self
+                    .unit
+                    .borrow_mut()
+                    .new_static_string(&decimal.to_string())?;
+                self.asm.push(Inst::Decimal { slot }, span);
+            }
         }
 
         Ok(())
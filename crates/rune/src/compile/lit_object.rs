@@ -14,6 +14,14 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
         let span = lit_object.span();
         log::trace!("LitObject => {:?}", self.source.source(span));
 
+        if let (ast::LitObjectIdent::Named(..), Some(computed)) =
+            (&lit_object.ident, lit_object.computed.first())
+        {
+            return Err(CompileError::UnsupportedComputedKey {
+                span: computed.span(),
+            });
+        }
+
         if !needs.value() && lit_object.is_const() {
             // Don't encode unecessary literals.
             return Ok(());
@@ -38,6 +46,23 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
             }
         }
 
+        for computed in &lit_object.computed {
+            if let Some(key) = self.constant_key(&computed.key)? {
+                if let Some((_, field)) = check_keys.iter().find(|(k, _)| *k == key) {
+                    self.warnings.computed_key_shadows_field(
+                        self.source_id,
+                        computed.key.span(),
+                        *field,
+                        self.context(),
+                    );
+                }
+            }
+        }
+
+        if !lit_object.computed.is_empty() {
+            return self.compile_computed_object(lit_object, &keys, needs, span);
+        }
+
         for assign in lit_object.assignments.iter() {
             let span = assign.span();
 
@@ -48,6 +73,8 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
                 // side effects (without creating an object).
                 if !needs.value() {
                     self.asm.push(Inst::Pop, span);
+                } else {
+                    self.scopes.decl_anon(span)?;
                 }
             } else {
                 let key = assign.key.resolve(&*self.source)?;
@@ -55,18 +82,21 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
 
                 if needs.value() {
                     var.copy(&mut self.asm, span, format!("name `{}`", key));
+                    self.scopes.decl_anon(span)?;
                 }
             }
         }
 
         // No need to encode an object since the value is not needed.
         if !needs.value() {
+            if let Some((_, base)) = &lit_object.base {
+                self.compile((&**base, Needs::None))?;
+            }
+
             self.warnings.not_used(self.source_id, span, self.context());
             return Ok(());
         }
 
-        let slot = self.unit.borrow_mut().new_static_object_keys(&keys)?;
-
         match &lit_object.ident {
             ast::LitObjectIdent::Named(path) => {
                 let item = self.convert_path_to_item(path)?;
@@ -80,7 +110,7 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
 
                 match meta {
                     CompileMeta::Struct { object, .. } => {
-                        check_object_fields(
+                        let missing = check_object_fields(
                             object.fields.as_ref(),
                             check_keys,
                             span,
@@ -88,18 +118,41 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
                         )?;
 
                         let hash = Hash::type_hash(&object.item);
-                        self.asm.push(Inst::TypedObject { hash, slot }, span);
+
+                        if let Some((_, base)) = &lit_object.base {
+                            let count = keys.len();
+                            keys.extend(missing);
+
+                            let slot = self.unit.borrow_mut().new_static_object_keys(&keys)?;
+                            self.compile((&**base, Needs::Value))?;
+                            self.asm
+                                .push(Inst::TypedObjectUpdate { hash, slot, count }, span);
+                        } else {
+                            check_missing_fields(missing, span, &object.item)?;
+
+                            let slot = self.unit.borrow_mut().new_static_object_keys(&keys)?;
+                            self.asm.push(Inst::TypedObject { hash, slot }, span);
+                        }
                     }
                     CompileMeta::StructVariant {
                         enum_item, object, ..
                     } => {
-                        check_object_fields(
+                        if let Some((dot_dot, base)) = &lit_object.base {
+                            return Err(CompileError::UnsupportedStructUpdate {
+                                span: dot_dot.span().join(base.span()),
+                            });
+                        }
+
+                        let missing = check_object_fields(
                             object.fields.as_ref(),
                             check_keys,
                             span,
                             &object.item,
                         )?;
 
+                        check_missing_fields(missing, span, &object.item)?;
+
+                        let slot = self.unit.borrow_mut().new_static_object_keys(&keys)?;
                         let enum_hash = Hash::type_hash(&enum_item);
                         let hash = Hash::type_hash(&object.item);
 
@@ -121,20 +174,166 @@ impl Compile<(&ast::LitObject, Needs)> for Compiler<'_> {
                 };
             }
             ast::LitObjectIdent::Anonymous(..) => {
+                if let Some((dot_dot, base)) = &lit_object.base {
+                    return Err(CompileError::UnsupportedStructUpdate {
+                        span: dot_dot.span().join(base.span()),
+                    });
+                }
+
+                let slot = self.unit.borrow_mut().new_static_object_keys(&keys)?;
                 self.asm.push(Inst::Object { slot }, span);
             }
         }
 
+        self.scopes
+            .last_mut(span)?
+            .undecl_anon(lit_object.assignments.len(), span)?;
+
+        Ok(())
+    }
+}
+
+/// A field of an object literal, used to evaluate fields in the order they
+/// appear in the source.
+enum Field<'a> {
+    Assign(&'a ast::LitObjectFieldAssign),
+    Computed(&'a ast::LitObjectComputedAssign),
+}
+
+impl Compiler<'_> {
+    /// Compile an anonymous object with computed keys.
+    ///
+    /// All fields are evaluated in the order they appear in the source, after
+    /// which the object is constructed from the fields with static keys and
+    /// the fields with computed keys are inserted into it.
+    fn compile_computed_object(
+        &mut self,
+        lit_object: &ast::LitObject,
+        keys: &[String],
+        needs: Needs,
+        span: Span,
+    ) -> CompileResult<()> {
+        if let Some((dot_dot, base)) = &lit_object.base {
+            return Err(CompileError::UnsupportedStructUpdate {
+                span: dot_dot.span().join(base.span()),
+            });
+        }
+
+        let mut fields = lit_object
+            .assignments
+            .iter()
+            .map(Field::Assign)
+            .chain(lit_object.computed.iter().map(Field::Computed))
+            .collect::<Vec<_>>();
+
+        fields.sort_by_key(|field| match field {
+            Field::Assign(assign) => assign.span().start,
+            Field::Computed(computed) => computed.span().start,
+        });
+
+        let scope = self.scopes.child(span)?;
+        let guard = self.scopes.push(scope);
+
+        let mut assigned = Vec::new();
+        let mut computed = Vec::new();
+
+        for field in fields {
+            match field {
+                Field::Assign(assign) => {
+                    let span = assign.span();
+
+                    if let Some((_, expr)) = &assign.assign {
+                        self.compile((expr, Needs::Value))?;
+                    } else {
+                        let key = assign.key.resolve(&*self.source)?;
+                        let var = self.scopes.get_var(&*key, span)?;
+                        var.copy(&mut self.asm, span, format!("name `{}`", key));
+                    }
+
+                    assigned.push(self.scopes.decl_anon(span)?);
+                }
+                Field::Computed(field) => {
+                    let span = field.span();
+
+                    self.compile((&field.key, Needs::Value))?;
+                    let key = self.scopes.decl_anon(span)?;
+                    self.compile((&field.expr, Needs::Value))?;
+                    let value = self.scopes.decl_anon(span)?;
+                    computed.push((key, value, span));
+                }
+            }
+        }
+
+        let count = assigned.len() + computed.len() * 2;
+
+        // No need to encode an object since the value is not needed.
+        if !needs.value() {
+            self.locals_pop(count, span);
+            self.scopes.pop(guard, span)?;
+            self.warnings.not_used(self.source_id, span, self.context());
+            return Ok(());
+        }
+
+        for offset in assigned {
+            self.asm.push(Inst::Copy { offset }, span);
+        }
+
+        let slot = self.unit.borrow_mut().new_static_object_keys(keys)?;
+        self.asm.push(Inst::Object { slot }, span);
+        let object = self.scopes.decl_anon(span)?;
+
+        for (key, value, span) in computed {
+            self.asm.push(Inst::Copy { offset: value }, span);
+            self.asm.push(Inst::Copy { offset: key }, span);
+            self.asm.push_with_comment(
+                Inst::Copy { offset: object },
+                span,
+                "object with computed keys",
+            );
+            self.asm.push(Inst::IndexSet, span);
+        }
+
+        self.locals_clean(count, span);
+        self.scopes.pop(guard, span)?;
         Ok(())
     }
+
+    /// Resolve the key of a computed field if it is a constant string, like
+    /// `["a"]` or `[`a`]`.
+    ///
+    /// Any other key is only known at runtime, so it can't be checked against
+    /// the other fields of the object.
+    fn constant_key(&self, expr: &ast::Expr) -> CompileResult<Option<String>> {
+        Ok(match expr {
+            ast::Expr::LitStr(lit_str) => Some(lit_str.resolve(&*self.source)?.into_owned()),
+            ast::Expr::LitTemplate(lit_template) => {
+                let template = lit_template.resolve(&*self.source)?;
+
+                let mut key = String::new();
+
+                for component in template.components {
+                    match component {
+                        ast::TemplateComponent::String { string, .. } => key.push_str(&string),
+                        ast::TemplateComponent::Expr { .. } => return Ok(None),
+                    }
+                }
+
+                Some(key)
+            }
+            ast::Expr::ExprGroup(group) => self.constant_key(&group.expr)?,
+            _ => None,
+        })
+    }
 }
 
+/// Check that the given keys are fields of the given type, returning the
+/// fields which aren't assigned in a stable order.
 fn check_object_fields(
     fields: Option<&HashSet<String>>,
     check_keys: Vec<(String, Span)>,
     span: Span,
     item: &Item,
-) -> CompileResult<()> {
+) -> CompileResult<Vec<String>> {
     let mut fields = match fields {
         Some(fields) => fields.clone(),
         None => {
@@ -155,7 +354,14 @@ fn check_object_fields(
         }
     }
 
-    if let Some(field) = fields.into_iter().next() {
+    let mut missing = fields.into_iter().collect::<Vec<_>>();
+    missing.sort();
+    Ok(missing)
+}
+
+/// Check that no fields are missing from an object literal.
+fn check_missing_fields(missing: Vec<String>, span: Span, item: &Item) -> CompileResult<()> {
+    if let Some(field) = missing.into_iter().next() {
         return Err(CompileError::LitObjectMissingField {
             span,
             field,
@@ -28,12 +28,12 @@ impl Compile<(&ast::LitTemplate, Needs)> for Compiler<'_> {
 
         for c in template.components.iter() {
             match c {
-                ast::TemplateComponent::String(string) => {
+                ast::TemplateComponent::String { string, .. } => {
                     let slot = self.unit.borrow_mut().new_static_string(&string)?;
                     self.asm.push(Inst::String { slot }, span);
                     self.scopes.decl_anon(span)?;
                 }
-                ast::TemplateComponent::Expr(expr) => {
+                ast::TemplateComponent::Expr { expr, .. } => {
                     self.compile((&**expr, Needs::Value))?;
                     self.scopes.decl_anon(span)?;
                 }
@@ -18,6 +18,7 @@ impl Compile<(&ast::LitTuple, Needs)> for Compiler<'_> {
 
         for (expr, _) in lit_tuple.items.iter() {
             self.compile((expr, Needs::Value))?;
+            self.scopes.decl_anon(span)?;
         }
 
         self.asm.push(
@@ -27,6 +28,10 @@ impl Compile<(&ast::LitTuple, Needs)> for Compiler<'_> {
             span,
         );
 
+        self.scopes
+            .last_mut(span)?
+            .undecl_anon(lit_tuple.items.len(), span)?;
+
         Ok(())
     }
 }
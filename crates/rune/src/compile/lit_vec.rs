@@ -24,6 +24,8 @@ impl Compile<(&ast::LitVec, Needs)> for Compiler<'_> {
             // side effects (without creating an object).
             if !needs.value() {
                 self.asm.push(Inst::Pop, span);
+            } else {
+                self.scopes.decl_anon(span)?;
             }
         }
 
@@ -34,6 +36,7 @@ impl Compile<(&ast::LitVec, Needs)> for Compiler<'_> {
         }
 
         self.asm.push(Inst::Vec { count }, span);
+        self.scopes.last_mut(span)?.undecl_anon(count, span)?;
         Ok(())
     }
 }
@@ -1,11 +1,16 @@
+mod decl_const;
 mod decl_fn;
+mod decl_let;
 mod expr;
 mod expr_await;
 mod expr_binary;
 mod expr_block;
 mod expr_break;
 mod expr_call;
+mod expr_call_macro;
 mod expr_closure;
+mod expr_continue;
+mod expr_defer;
 mod expr_field_access;
 mod expr_for;
 mod expr_if;
@@ -15,12 +20,14 @@ mod expr_let;
 mod expr_loop;
 mod expr_match;
 mod expr_path;
+mod expr_range;
 mod expr_return;
 mod expr_select;
 mod expr_self;
 mod expr_try;
 mod expr_unary;
 mod expr_while;
+mod expr_with;
 mod expr_yield;
 mod lit_bool;
 mod lit_byte;
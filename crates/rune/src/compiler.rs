@@ -1,27 +1,33 @@
 use crate::assembly::Assembly;
 use crate::ast;
 use crate::collections::HashMap;
-use crate::error::CompileError;
+use crate::error::{CompileError, ParseError};
 use crate::traits::{Compile as _, Resolve as _};
 use crate::unit_builder::UnitBuilder;
-use crate::{MacroContext, SourceId};
-use runestick::{CompileMeta, Context, Inst, Item, Label, Source, Span, TypeCheck};
+use crate::{MacroContext, Parser, SourceId};
+use runestick::{
+    Call, CompileMeta, CompileMetaTuple, Context, ContextMatcher, Hash, Inst, Item, Label, Source,
+    Span, Type, TypeCheck, UnitMemoize,
+};
 use std::cell::RefCell;
 use std::collections::VecDeque;
+use std::mem;
 use std::rc::Rc;
 
 use crate::error::CompileResult;
-use crate::index::{Index, Indexer, Macro, MacroKind};
+use crate::index::{ImplItem, Import, Index, Indexer, Macro, MacroKind};
 use crate::index_scopes::IndexScopes;
 use crate::items::Items;
 use crate::load_error::{LoadError, LoadErrorKind};
 use crate::loops::Loops;
 use crate::options::Options;
 use crate::query::{Build, BuildEntry, Query};
-use crate::scopes::{Scope, ScopeGuard, Scopes};
+use crate::report::CompileReport;
+use crate::scopes::{Deferred, Scope, ScopeGuard, Scopes};
 use crate::sources::Sources;
 use crate::warning::Warnings;
 use std::sync::Arc;
+use std::time::Instant;
 
 /// A needs hint for an expression.
 /// This is used to contextually determine what an expression is expected to
@@ -59,157 +65,352 @@ pub fn compile_with_options(
     unit: &Rc<RefCell<UnitBuilder>>,
     warnings: &mut Warnings,
 ) -> Result<(), LoadError> {
-    // Imports to process.
-    let mut imports = VecDeque::new();
-    // Macros to expand.
-    let mut macros = VecDeque::new();
-    // Query system to populate.
-    let mut query = Query::new(unit.clone());
-    // Files loaded while loading modules.
-    let mut loaded = HashMap::<Item, (SourceId, Span)>::new();
-    // Expanded expressions.
-    let mut expanded_expr = HashMap::new();
-
-    while let Some((item, source_id)) = sources.next_source() {
-        let source = match sources.get(source_id).cloned() {
-            Some(source) => source,
-            None => return Err(LoadError::internal("missing queued source by id")),
+    let mut compilation = Compilation::new(context, options, unit.clone());
+    compilation.index_queued(sources, warnings)?;
+    compilation.finish(sources, warnings)
+}
+
+/// The indexing state of a single source, which is kept between calls to
+/// [Compilation::index_file] for sources which are indexed in pieces.
+pub(crate) struct FileIndex {
+    items: Items,
+    scopes: IndexScopes,
+    impl_items: Vec<ImplItem>,
+}
+
+impl FileIndex {
+    /// Construct the indexing state for a source loaded into the given item.
+    pub(crate) fn new(item: Item) -> Self {
+        Self {
+            items: Items::new(item.into_vec()),
+            scopes: IndexScopes::new(),
+            impl_items: Vec::new(),
+        }
+    }
+}
+
+/// The state of an ongoing compilation.
+///
+/// Sources are first indexed, after which imports and macros are processed
+/// and all items which are used are compiled.
+pub(crate) struct Compilation<'a> {
+    context: &'a Context,
+    options: &'a Options,
+    unit: Rc<RefCell<UnitBuilder>>,
+    /// Imports to process.
+    imports: VecDeque<Import>,
+    /// Macros to expand.
+    macros: VecDeque<Macro>,
+    /// Query system to populate.
+    query: Query,
+    /// Files loaded while loading modules.
+    loaded: HashMap<Item, (SourceId, Span)>,
+    /// Expanded expressions.
+    expanded_expr: HashMap<Item, ast::Expr>,
+    /// Macro contexts by source, which keep track of performed expansions.
+    macro_contexts: HashMap<SourceId, MacroContext>,
+    /// Report to collect timings and statistics in, if enabled.
+    report: Option<&'a mut CompileReport>,
+}
+
+impl<'a> Compilation<'a> {
+    /// Construct a new compilation into the given unit.
+    pub(crate) fn new(
+        context: &'a Context,
+        options: &'a Options,
+        unit: Rc<RefCell<UnitBuilder>>,
+    ) -> Self {
+        Self {
+            context,
+            options,
+            query: Query::new(unit.clone()),
+            unit,
+            imports: VecDeque::new(),
+            macros: VecDeque::new(),
+            loaded: HashMap::new(),
+            expanded_expr: HashMap::new(),
+            macro_contexts: HashMap::new(),
+            report: None,
+        }
+    }
+
+    /// Collect timings and statistics on the compilation in the given report.
+    pub(crate) fn with_report(mut self, report: &'a mut CompileReport) -> Self {
+        self.unit.borrow_mut().enable_report();
+        self.report = Some(report);
+        self
+    }
+
+    /// Parse and index all sources queued in the collection of sources.
+    pub(crate) fn index_queued(
+        &mut self,
+        sources: &mut Sources,
+        warnings: &mut Warnings,
+    ) -> Result<(), LoadError> {
+        while let Some((item, source_id)) = sources.next_source() {
+            let source = match sources.get(source_id).cloned() {
+                Some(source) => source,
+                None => return Err(LoadError::internal("missing queued source by id")),
+            };
+
+            let file = match self.parse_file(source.as_str()) {
+                Ok(file) => file,
+                Err(error) => {
+                    return Err(LoadError::from(LoadErrorKind::ParseError {
+                        source_id,
+                        error,
+                    }))
+                }
+            };
+
+            // NB: only the frontmatter of entry sources is made available,
+            // since it's intended to configure the script as a whole.
+            if item.is_empty() {
+                self.load_header(source_id, &source)?;
+            }
+
+            let index = FileIndex::new(item);
+            self.index_file(sources, warnings, source_id, source, index, &file)?;
+        }
+
+        Ok(())
+    }
+
+    /// Parse a file, reporting the time spent lexing and parsing it.
+    fn parse_file(&mut self, source: &str) -> Result<ast::DeclFile, ParseError> {
+        let report = match &mut self.report {
+            Some(report) => report,
+            None => return crate::parse_all(source),
         };
 
-        let file = match crate::parse_all::<ast::DeclFile>(source.as_str()) {
-            Ok(file) => file,
+        let start = Instant::now();
+        let mut parser = Parser::new_timed(source);
+        let file = parser.parse::<ast::DeclFile>();
+        let result = file.and_then(|file| parser.parse_eof().map(|()| file));
+
+        let lex = parser.lex_time().unwrap_or_default();
+        report.lex += lex;
+        report.parse += start.elapsed().checked_sub(lex).unwrap_or_default();
+        report.sources += 1;
+        result
+    }
+
+    /// Load the header of an entry source.
+    pub(crate) fn load_header(
+        &mut self,
+        source_id: SourceId,
+        source: &Source,
+    ) -> Result<(), LoadError> {
+        let header = match source.header() {
+            Ok(header) => header,
             Err(error) => {
                 return Err(LoadError::from(LoadErrorKind::ParseError {
                     source_id,
-                    error,
+                    error: error.into(),
                 }))
             }
         };
 
+        if let Some(frontmatter) = header.frontmatter {
+            self.unit.borrow_mut().extend_frontmatter(frontmatter);
+        }
+
+        Ok(())
+    }
+
+    /// Index the declarations of a file, which might only be a part of the
+    /// given source. Returns the indexing state to use for the rest of it.
+    pub(crate) fn index_file(
+        &mut self,
+        sources: &mut Sources,
+        warnings: &mut Warnings,
+        source_id: SourceId,
+        source: Arc<Source>,
+        index: FileIndex,
+        file: &ast::DeclFile,
+    ) -> Result<FileIndex, LoadError> {
         let mut indexer = Indexer {
-            loaded: &mut loaded,
-            query: &mut query,
-            imports: &mut imports,
-            macros: &mut macros,
+            options: self.options,
+            loaded: &mut self.loaded,
+            query: &mut self.query,
+            imports: &mut self.imports,
+            macros: &mut self.macros,
             sources,
             source_id,
             source,
             warnings,
-            items: Items::new(item.into_vec()),
-            scopes: IndexScopes::new(),
-            impl_items: Vec::new(),
+            items: index.items,
+            scopes: index.scopes,
+            impl_items: index.impl_items,
         };
 
-        if let Err(error) = indexer.index(&file) {
+        let start = Instant::now();
+        let result = indexer.index(file);
+
+        if let Some(report) = &mut self.report {
+            report.index += start.elapsed();
+        }
+
+        if let Err(error) = result {
             return Err(LoadError::from(LoadErrorKind::CompileError {
                 source_id,
                 error,
             }));
         }
+
+        Ok(FileIndex {
+            items: indexer.items,
+            scopes: indexer.scopes,
+            impl_items: indexer.impl_items,
+        })
     }
 
-    loop {
-        while let Some(import) = imports.pop_front() {
-            let source_id = import.source_id;
+    /// Process imports and macros, and compile everything which has been
+    /// indexed.
+    pub(crate) fn finish(
+        mut self,
+        sources: &mut Sources,
+        warnings: &mut Warnings,
+    ) -> Result<(), LoadError> {
+        let context = self.context;
+        let options = self.options;
+        let unit = &self.unit;
+        let start = Instant::now();
 
-            if let Err(error) = import.process(context, &mut *unit.borrow_mut()) {
-                return Err(LoadError::from(LoadErrorKind::CompileError {
-                    error,
-                    source_id,
-                }));
+        loop {
+            while let Some(import) = self.imports.pop_front() {
+                let source_id = import.source_id;
+
+                if let Err(error) = import.process(context, &mut *unit.borrow_mut()) {
+                    return Err(LoadError::from(LoadErrorKind::CompileError {
+                        error,
+                        source_id,
+                    }));
+                }
             }
-        }
 
-        if let Some(m) = macros.pop_front() {
-            let Macro {
-                items,
-                ast,
-                source,
-                source_id,
-                scopes,
-                impl_items,
-                kind,
-            } = m;
-
-            let item = items.item();
-
-            let mut macro_context = MacroContext::new(source.clone());
+            if let Some(m) = self.macros.pop_front() {
+                let Macro {
+                    items,
+                    ast,
+                    source,
+                    source_id,
+                    scopes,
+                    impl_items,
+                    kind,
+                } = m;
+
+                let item = items.item();
+
+                let macro_context = self
+                    .macro_contexts
+                    .entry(source_id)
+                    .or_insert_with(|| MacroContext::new(source.clone()));
+
+                let mut compiler = crate::macros::MacroCompiler {
+                    item: item.clone(),
+                    macro_context,
+                    options,
+                    context,
+                    unit: unit.clone(),
+                    source: source.clone(),
+                };
 
-            let mut compiler = crate::macros::MacroCompiler {
-                item: item.clone(),
-                macro_context: &mut macro_context,
-                options,
-                context,
-                unit: unit.clone(),
-                source: source.clone(),
-            };
+                // index the newly added macros.
+                let mut indexer = Indexer {
+                    options,
+                    loaded: &mut self.loaded,
+                    query: &mut self.query,
+                    imports: &mut self.imports,
+                    macros: &mut self.macros,
+                    sources,
+                    source_id,
+                    source,
+                    warnings,
+                    items,
+                    scopes,
+                    impl_items,
+                };
 
-            // index the newly added macros.
-            let mut indexer = Indexer {
-                loaded: &mut loaded,
-                query: &mut query,
-                imports: &mut imports,
-                macros: &mut macros,
-                sources,
-                source_id,
-                source,
-                warnings,
-                items,
-                scopes,
-                impl_items,
-            };
+                match kind {
+                    MacroKind::Expr => {
+                        let expr = match compiler.eval_macro::<ast::Expr>(ast) {
+                            Ok(expr) => expr,
+                            Err(error) => {
+                                return Err(LoadError::from(LoadErrorKind::CompileError {
+                                    source_id,
+                                    error,
+                                }));
+                            }
+                        };
+
+                        if let Err(error) = indexer.index(&expr) {
+                            let error = compiler.macro_context.expansion_error(error);
 
-            match kind {
-                MacroKind::Expr => {
-                    let expr = match compiler.eval_macro::<ast::Expr>(ast) {
-                        Ok(expr) => expr,
-                        Err(error) => {
                             return Err(LoadError::from(LoadErrorKind::CompileError {
                                 source_id,
                                 error,
                             }));
                         }
-                    };
 
-                    if let Err(error) = indexer.index(&expr) {
-                        return Err(LoadError::from(LoadErrorKind::CompileError {
-                            source_id,
-                            error,
-                        }));
+                        self.expanded_expr.insert(item, expr);
                     }
-
-                    expanded_expr.insert(item, expr);
                 }
+
+                continue;
             }
 
-            continue;
+            break;
         }
 
-        break;
-    }
+        verify_imports(context, &mut *unit.borrow_mut())?;
 
-    verify_imports(context, &mut *unit.borrow_mut())?;
+        let indexed = Instant::now();
 
-    while let Some(entry) = query.queue.pop_front() {
-        let source_id = entry.source_id;
+        while let Some(entry) = self.query.queue.pop_front() {
+            let source_id = entry.source_id;
 
-        if let Err(error) = compile_entry(
-            context,
-            options,
-            unit,
-            warnings,
-            &mut query,
-            entry,
-            &expanded_expr,
-        ) {
+            if let Err(error) = compile_entry(
+                context,
+                options,
+                unit,
+                warnings,
+                &mut self.query,
+                entry,
+                &self.expanded_expr,
+            ) {
+                let error = match self.macro_contexts.get(&source_id) {
+                    Some(macro_context) => macro_context.expansion_error(error),
+                    None => error,
+                };
+
+                return Err(LoadError::from(LoadErrorKind::CompileError {
+                    source_id,
+                    error,
+                }));
+            }
+        }
+
+        if let Err((source_id, error)) = self.query.check_static_cycles() {
             return Err(LoadError::from(LoadErrorKind::CompileError {
                 source_id,
                 error,
             }));
         }
-    }
 
-    Ok(())
+        if let Some(report) = self.report {
+            let assembly = unit.borrow_mut().take_report().unwrap_or_default();
+            let build = indexed.elapsed().checked_sub(assembly.assembly);
+
+            report.index += indexed - start;
+            report.build += build.unwrap_or_default();
+            report.assembly += assembly.assembly;
+            report.indexed += self.query.indexed_count;
+            report.functions.extend(assembly.functions);
+        }
+
+        Ok(())
+    }
 }
 
 fn compile_entry(
@@ -244,6 +445,7 @@ fn compile_entry(
         options,
         warnings,
         expanded_exprs,
+        statics: Vec::new(),
     };
 
     match build {
@@ -253,6 +455,17 @@ fn compile_entry(
             let span = f.ast.span();
             let count = f.ast.args.items.len();
             compiler.contexts.push(span);
+
+            if let Some(memoize) = f.memoize {
+                let slot = unit.borrow_mut().new_memoized(UnitMemoize {
+                    item: item.clone(),
+                    capacity: memoize.capacity,
+                    ttl: memoize.ttl,
+                });
+
+                compiler.asm.push(Inst::Memoize { slot }, memoize.span);
+            }
+
             compiler.compile((f.ast, false))?;
 
             unit.borrow_mut()
@@ -268,12 +481,25 @@ fn compile_entry(
             let source = compiler.source.clone();
             let name = f.ast.name.resolve(&*source)?;
 
-            let meta = compiler
-                .lookup_meta(&f.impl_item, f.instance_span)?
-                .ok_or_else(|| CompileError::MissingType {
-                    span: f.instance_span,
-                    item: f.impl_item.clone(),
-                })?;
+            let meta = match compiler.lookup_meta(&f.impl_item, f.instance_span)? {
+                Some(meta) if !f.extension => meta,
+                _ => {
+                    // NB: the implemented type might be a native type which
+                    // is imported, like `String` from the prelude. The item
+                    // of an extension is named after the extension, so its
+                    // type is always resolved through its path.
+                    let impl_item =
+                        unit.borrow()
+                            .convert_path(&f.impl_base, &f.impl_path, &*source)?;
+
+                    compiler
+                        .lookup_meta(&impl_item, f.instance_span)?
+                        .ok_or_else(|| CompileError::MissingType {
+                            span: f.instance_span,
+                            item: f.impl_item.clone(),
+                        })?
+                }
+            };
 
             let value_type =
                 meta.value_type()
@@ -282,11 +508,130 @@ fn compile_entry(
                         span,
                     })?;
 
+            // NB: a script function with the same hash as a native instance
+            // function would silently replace it when called.
+            if !f.extension
+                && context
+                    .lookup(Hash::instance_function(value_type, Hash::of(name)))
+                    .is_some()
+            {
+                return Err(CompileError::InstanceFunctionConflict {
+                    span: f.ast.name.span(),
+                    item: meta.item().clone(),
+                    name: name.to_owned(),
+                });
+            }
+
+            if f.finalizer {
+                let type_hash = match (&meta, value_type) {
+                    (CompileMeta::Tuple { .. }, Type::Hash(hash))
+                    | (CompileMeta::Struct { .. }, Type::Hash(hash)) => hash,
+                    _ => return Err(CompileError::UnsupportedFinalizer { span }),
+                };
+
+                unit.borrow_mut().insert_finalizer(type_hash, &item);
+            }
+
             compiler.compile((f.ast, true))?;
 
-            unit.borrow_mut().new_instance_function(
-                source_id, item, value_type, name, count, asm, f.call, args,
+            // NB: extension functions are only reachable by the full name of
+            // the function, which is used by calls where the extension is in
+            // scope.
+            let name_hash = if f.extension {
+                Hash::type_hash(&item)
+            } else {
+                Hash::of(name)
+            };
+
+            // NB: structs can implement the index protocol, which is used to
+            // look up indexes and fields they don't have.
+            let index_get = !f.extension
+                && name == runestick::INDEX_GET.name
+                && matches!(meta, CompileMeta::Struct { .. } | CompileMeta::Tuple { .. });
+
+            let mut unit = unit.borrow_mut();
+
+            unit.new_instance_function(
+                source_id,
+                item.clone(),
+                value_type,
+                name_hash,
+                count,
+                asm,
+                f.call,
+                args,
             )?;
+
+            if index_get {
+                unit.insert_protocol_function(value_type, runestick::INDEX_GET, &item);
+            }
+        }
+        Build::Const(c) => {
+            let span = c.ast.span();
+            compiler.contexts.push(span);
+            compiler.compile(&c.ast)?;
+
+            unit.borrow_mut()
+                .new_function(source_id, item, 0, asm, c.call, Vec::new())?;
+        }
+        Build::Script(script) => {
+            compiler.contexts.push(script.span);
+            let _guard = compiler.items.push_block();
+
+            // NB: the last statement is the value of the script, unless it
+            // is terminated by a semi-colon.
+            let (mut exprs, trailing_expr) = match script.stmts.split_last() {
+                Some(((expr, None), exprs)) => (exprs, Some(expr)),
+                _ => (&script.stmts[..], None),
+            };
+
+            // NB: statics are initialized where they are declared, so that
+            // their initializers run in order with the statements.
+            for (item, span) in &script.statics {
+                let n = exprs
+                    .iter()
+                    .take_while(|(expr, _)| expr.span().end <= span.start)
+                    .count();
+
+                let (before, after) = exprs.split_at(n);
+                compiler.compile_block_stmts(before, false)?;
+
+                if let Some(meta) = compiler.lookup_meta(item, *span)? {
+                    compiler.compile_meta(&meta, *span, Needs::Value)?;
+                    compiler.asm.push(Inst::Pop, *span);
+                }
+
+                exprs = after;
+            }
+
+            compiler.compile_fn_body(exprs, trailing_expr, script.span)?;
+            compiler.scopes.pop_last(script.span)?;
+
+            unit.borrow_mut()
+                .new_function(source_id, item, 0, asm, script.call, Vec::new())?;
+        }
+        Build::Static(s) => {
+            let span = s.ast.span();
+            compiler.contexts.push(span);
+            compiler.compile((&s.ast, s.slot))?;
+
+            let statics = mem::take(&mut compiler.statics);
+            compiler
+                .query
+                .static_deps
+                .insert(item.clone(), (source_id, statics));
+
+            unit.borrow_mut()
+                .new_function(source_id, item, 0, asm, Call::Immediate, Vec::new())?;
+        }
+        Build::FromDiscriminant(f) => {
+            let enum_hash = Hash::type_hash(&f.enum_item);
+            asm.push(Inst::FromDiscriminant { enum_hash }, f.span);
+            asm.push(Inst::Return, f.span);
+
+            let args = vec![String::from("discriminant")];
+            unit.borrow_mut()
+                .new_function(source_id, item, 1, asm, Call::Immediate, args)?;
         }
         Build::Closure(c) => {
             let args = format_fn_args(&*source, c.ast.args.as_slice().iter().map(|(a, _)| a))?;
@@ -319,7 +664,10 @@ fn compile_entry(
     Ok(())
 }
 
-fn format_fn_args<'a, I>(source: &Source, arguments: I) -> Result<Vec<String>, CompileError>
+pub(crate) fn format_fn_args<'a, I>(
+    source: &Source,
+    arguments: I,
+) -> Result<Vec<String>, CompileError>
 where
     I: IntoIterator<Item = &'a ast::FnArg>,
 {
@@ -356,7 +704,10 @@ fn verify_imports(context: &Context, unit: &mut UnitBuilder) -> Result<(), LoadE
                 },
                 source_id,
             }));
-        } else {
+        } else if !context.is_restricted() {
+            // NB: items in the prelude might not be available in a restricted
+            // context, in which case using them fails like any other missing
+            // item would.
             return Err(LoadError::from(LoadErrorKind::CompileError {
                 error: CompileError::MissingPreludeModule {
                     item: entry.item.clone(),
@@ -396,6 +747,8 @@ pub(crate) struct Compiler<'a> {
     pub(crate) options: &'a Options,
     /// Compilation warnings.
     pub(crate) warnings: &'a mut Warnings,
+    /// Statics referenced by the code being compiled.
+    pub(crate) statics: Vec<(Item, Span)>,
 }
 
 impl<'a> Compiler<'a> {
@@ -427,6 +780,16 @@ impl<'a> Compiler<'a> {
         Ok(None)
     }
 
+    /// Get the slot of the intrinsic with the given hash in the context, if
+    /// calls to it should be compiled into an intrinsic call.
+    pub(crate) fn intrinsic_slot(&self, hash: Hash, args: usize) -> Option<usize> {
+        if !self.options.intrinsics {
+            return None;
+        }
+
+        self.context.intrinsic_slot(hash, args)
+    }
+
     /// Pop locals by simply popping them.
     pub(crate) fn locals_pop(&mut self, total_var_count: usize, span: Span) {
         match total_var_count {
@@ -454,6 +817,119 @@ impl<'a> Compiler<'a> {
         }
     }
 
+    /// Compile a literal tuple which is returned from the current function,
+    /// like `return (a, b)`.
+    ///
+    /// The values are returned without constructing the tuple, so callers
+    /// which immediately unpack it don't have to allocate it.
+    pub(crate) fn compile_return_tuple(
+        &mut self,
+        lit_tuple: &ast::LitTuple,
+        total_var_count: usize,
+        deferred: &[Rc<Deferred>],
+    ) -> CompileResult<()> {
+        let span = lit_tuple.span();
+
+        for (expr, _) in &lit_tuple.items {
+            self.compile((expr, Needs::Value))?;
+            self.scopes.decl_anon(span)?;
+        }
+
+        let count = lit_tuple.items.len();
+        self.compile_deferred(deferred, total_var_count + count, span)?;
+
+        // NB: the return cleans up the locals under the returned values.
+        self.asm.push(Inst::ReturnTuple { count }, span);
+        self.scopes.last_mut(span)?.undecl_anon(count, span)?;
+        Ok(())
+    }
+
+    /// Compile a pattern which is unpacked from a tuple without checking it,
+    /// like `let (a, b) = value`, if it only consists of bindings.
+    ///
+    /// Returns `false` if the pattern can't be unpacked.
+    pub(crate) fn compile_pat_unpack(
+        &mut self,
+        scope: &mut Scope,
+        pat: &ast::Pat,
+    ) -> CompileResult<bool> {
+        let pat_tuple = match pat {
+            ast::Pat::PatTuple(pat_tuple)
+                if pat_tuple.path.is_none() && pat_tuple.open_pattern.is_none() =>
+            {
+                pat_tuple
+            }
+            _ => return Ok(false),
+        };
+
+        let mut bindings = Vec::new();
+
+        for (pat, _) in &pat_tuple.items {
+            let binding = match &**pat {
+                ast::Pat::PatIgnore(..) => None,
+                ast::Pat::PatPath(path) => {
+                    let item = self.convert_path_to_item(&path.path)?;
+
+                    let ident = match item.as_local() {
+                        Some(ident) => ident.to_owned(),
+                        None => return Ok(false),
+                    };
+
+                    // NB: names of unit structs and variants are matched
+                    // against.
+                    match self.lookup_meta(&item, path.span())? {
+                        Some(CompileMeta::Tuple { tuple, .. })
+                        | Some(CompileMeta::TupleVariant { tuple, .. })
+                            if tuple.args == 0 =>
+                        {
+                            return Ok(false);
+                        }
+                        _ => (),
+                    }
+
+                    Some((ident, path.span()))
+                }
+                _ => return Ok(false),
+            };
+
+            bindings.push((binding, pat.span()));
+        }
+
+        let span = pat_tuple.span();
+        let count = bindings.len();
+        self.asm.push(Inst::Unpack { count }, span);
+
+        for (binding, span) in bindings {
+            match binding {
+                Some((ident, span)) => {
+                    scope.decl_var(&ident, span);
+                }
+                None => {
+                    scope.decl_anon(span);
+                }
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Compile a return from the current function which is performed if the
+    /// boolean on top of the stack is `false`, returning the value under it
+    /// like the try operator does.
+    pub(crate) fn compile_return_if_not(&mut self, span: Span) -> CompileResult<()> {
+        let not_error = self.asm.new_label("not_error");
+        self.asm.jump_if(not_error, span);
+
+        let total_var_count = self.scopes.last(span)?.total_var_count;
+        let deferred = self.scopes.deferred_from(0);
+        self.compile_deferred(&deferred, total_var_count + 1, span)?;
+        self.locals_clean(total_var_count, span);
+        self.asm.push(Inst::Return, span);
+
+        self.asm.label(not_error)?;
+        Ok(())
+    }
+
     /// Compile an item.
     pub(crate) fn compile_meta(
         &mut self,
@@ -505,11 +981,28 @@ impl<'a> Compiler<'a> {
                 }
                 CompileMeta::Function {
                     value_type, item, ..
+                }
+                | CompileMeta::AssociatedFunction {
+                    value_type, item, ..
+                }
+                | CompileMeta::InstanceFunction {
+                    value_type, item, ..
                 } => {
                     let hash = value_type.as_type_hash();
                     self.asm
                         .push_with_comment(Inst::Fn { hash }, span, format!("fn `{}`", item));
                 }
+                CompileMeta::Const { item, .. } => {
+                    let hash = Hash::type_hash(item);
+                    self.asm.push_with_comment(
+                        Inst::Call { hash, args: 0 },
+                        span,
+                        format!("const `{}`", item),
+                    );
+                }
+                CompileMeta::Static { item, .. } => {
+                    self.compile_static(item, span);
+                }
                 meta => {
                     return Err(CompileError::UnsupportedValue {
                         span,
@@ -551,6 +1044,7 @@ impl<'a> Compiler<'a> {
             ast::Condition::Expr(expr) => {
                 let span = expr.span();
 
+                self.check_boolean(&**expr);
                 self.compile((&**expr, Needs::Value))?;
                 self.asm.jump_if(then_label, span);
 
@@ -638,30 +1132,51 @@ impl<'a> Compiler<'a> {
         // interact with it multiple times.
         load(&mut self.asm);
         let offset = scope.decl_anon(span);
+        let mut destructure = None;
 
         let type_check = if let Some(path) = &pat_tuple.path {
             let item = self.convert_path_to_item(path)?;
 
-            let (tuple, meta, type_check) =
-                if let Some(meta) = self.lookup_meta(&item, path.span())? {
-                    match &meta {
-                        CompileMeta::Tuple {
-                            tuple, value_type, ..
-                        } => {
-                            let type_check = TypeCheck::Type(value_type.as_type_hash());
-                            (tuple.clone(), meta, type_check)
-                        }
-                        CompileMeta::TupleVariant {
-                            tuple, value_type, ..
-                        } => {
-                            let type_check = TypeCheck::Variant(value_type.as_type_hash());
-                            (tuple.clone(), meta, type_check)
-                        }
-                        _ => return Err(CompileError::UnsupportedMetaPattern { meta, span }),
+            let (tuple, meta, type_check) = if let Some(meta) =
+                self.lookup_meta(&item, path.span())?
+            {
+                match &meta {
+                    CompileMeta::Struct {
+                        object, value_type, ..
+                    } if object.fields.is_none() => {
+                        let args = match self.context.matcher_for(&object.item) {
+                            Some(ContextMatcher::Tuple(args)) => *args,
+                            _ => return Err(CompileError::UnsupportedMetaPattern { meta, span }),
+                        };
+
+                        let tuple = CompileMetaTuple {
+                            item: object.item.clone(),
+                            args,
+                            hash: Hash::type_hash(&object.item),
+                        };
+
+                        // NB: external types are destructured into an
+                        // anonymous tuple before it's matched.
+                        destructure = Some(value_type.as_type_hash());
+                        (tuple, meta, TypeCheck::Tuple)
                     }
-                } else {
-                    return Err(CompileError::UnsupportedPattern { span });
-                };
+                    CompileMeta::Tuple {
+                        tuple, value_type, ..
+                    } => {
+                        let type_check = TypeCheck::Type(value_type.as_type_hash());
+                        (tuple.clone(), meta, type_check)
+                    }
+                    CompileMeta::TupleVariant {
+                        tuple, value_type, ..
+                    } => {
+                        let type_check = TypeCheck::Variant(value_type.as_type_hash());
+                        (tuple.clone(), meta, type_check)
+                    }
+                    _ => return Err(CompileError::UnsupportedMetaPattern { meta, span }),
+                }
+            } else {
+                return Err(CompileError::UnsupportedPattern { span });
+            };
 
             let count = pat_tuple.items.len();
             let is_open = pat_tuple.open_pattern.is_some();
@@ -675,9 +1190,14 @@ impl<'a> Compiler<'a> {
                 });
             }
 
-            match self.context.type_check_for(&tuple.item) {
-                Some(type_check) => type_check,
-                None => type_check,
+            if let Some(hash) = destructure {
+                self.compile_destructure(scope, hash, offset, false_label, span);
+                TypeCheck::Tuple
+            } else {
+                match self.context.type_check_for(&tuple.item) {
+                    Some(type_check) => type_check,
+                    None => type_check,
+                }
             }
         } else {
             TypeCheck::Tuple
@@ -748,6 +1268,7 @@ impl<'a> Compiler<'a> {
         }
 
         let keys = self.unit.borrow_mut().new_static_object_keys(&keys[..])?;
+        let mut destructure = None;
 
         let type_check = match &pat_object.ident {
             ast::LitObjectIdent::Named(path) => {
@@ -779,12 +1300,24 @@ impl<'a> Compiler<'a> {
                     }
                 };
 
+                let context = self.context;
+
                 let fields = match &object.fields {
                     Some(fields) => fields,
-                    None => {
-                        // NB: might want to describe that field composition is unknown because it is an external meta item.
-                        return Err(CompileError::UnsupportedMetaPattern { meta, span });
-                    }
+                    None => match context.matcher_for(&object.item) {
+                        // NB: external types are destructured into an
+                        // anonymous object before it's matched.
+                        Some(ContextMatcher::Object(fields)) => {
+                            destructure = meta
+                                .value_type()
+                                .map(|value_type| value_type.as_type_hash());
+                            fields
+                        }
+                        _ => {
+                            // NB: might want to describe that field composition is unknown because it is an external meta item.
+                            return Err(CompileError::UnsupportedMetaPattern { meta, span });
+                        }
+                    },
                 };
 
                 for (field, _) in &pat_object.fields {
@@ -805,6 +1338,14 @@ impl<'a> Compiler<'a> {
             ast::LitObjectIdent::Anonymous(..) => TypeCheck::Object,
         };
 
+        let type_check = match destructure {
+            Some(hash) => {
+                self.compile_destructure(scope, hash, offset, false_label, span);
+                TypeCheck::Object
+            }
+            None => type_check,
+        };
+
         // Copy the temporary and check that its length matches the pattern and
         // that it is indeed a vector.
         self.asm.push(Inst::Copy { offset }, span);
@@ -847,6 +1388,21 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
+    /// Destructure the external value at the given offset through the MATCH
+    /// protocol, jumping to `false_label` if it doesn't match.
+    fn compile_destructure(
+        &mut self,
+        scope: &Scope,
+        hash: Hash,
+        offset: usize,
+        false_label: Label,
+        span: Span,
+    ) {
+        self.asm.push(Inst::Destructure { hash, offset }, span);
+        self.asm
+            .pop_and_jump_if_not(scope.local_var_count, false_label, span);
+    }
+
     /// Compile a binding name that matches a known meta type.
     ///
     /// Returns `true` if the binding was used.
@@ -948,15 +1504,26 @@ impl<'a> Compiler<'a> {
                 let span = number_literal.span();
                 let number = number_literal.resolve(&*self.source)?;
 
-                let integer = match number {
-                    ast::Number::Integer(integer) => integer,
+                match number {
+                    ast::Number::Integer(integer) => {
+                        load(&mut self.asm);
+                        self.asm.push(Inst::EqInteger { integer }, span);
+                    }
                     ast::Number::Float(..) => {
                         return Err(CompileError::MatchFloatInPattern { span });
                     }
-                };
-
-                load(&mut self.asm);
-                self.asm.push(Inst::EqInteger { integer }, span);
+                    // NB: decimals are compared like any other value, since
+                    // there are many equal decimals with different scales.
+                    ast::Number::Decimal(decimal) => {
+                        let slot = self
+                            .unit
+                            .borrow_mut()
+                            .new_static_string(&decimal.to_string())?;
+                        load(&mut self.asm);
+                        self.asm.push(Inst::Decimal { slot }, span);
+                        self.asm.push(Inst::Eq, span);
+                    }
+                }
             }
             ast::Pat::PatString(pat_string) => {
                 let span = pat_string.span();
@@ -1002,8 +1569,303 @@ impl<'a> Compiler<'a> {
         Ok(())
     }
 
+    /// Compile an expression which is a statement in a block, where a `defer`
+    /// is permitted.
+    pub(crate) fn compile_stmt(&mut self, expr: &ast::Expr, needs: Needs) -> CompileResult<()> {
+        match expr {
+            ast::Expr::ExprDefer(expr_defer) => self.compile((expr_defer, needs)),
+            expr => self.compile((expr, needs)),
+        }
+    }
+
+    /// Compile the statements of a block.
+    ///
+    /// If `needs_value` is set the block is expected to produce a value
+    /// without having a trailing expression, in which case a value in the
+    /// last statement which is discarded by its semi-colon is warned about.
+    pub(crate) fn compile_block_stmts(
+        &mut self,
+        exprs: &[(ast::Expr, Option<ast::SemiColon>)],
+        needs_value: bool,
+    ) -> CompileResult<()> {
+        let (last, exprs) = match exprs.split_last() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
+
+        for (expr, _) in exprs {
+            // NB: terminated expressions do not need to produce a value.
+            self.compile_stmt(expr, Needs::None)?;
+        }
+
+        match last {
+            (expr, Some(semi_colon)) if needs_value && discards_value(expr) => {
+                let span = expr.span();
+
+                self.warnings.semi_colon_discards_value(
+                    self.source_id,
+                    semi_colon.span(),
+                    span,
+                    self.context(),
+                );
+
+                // NB: the value is produced and popped to avoid also warning
+                // about it not being used.
+                self.compile_stmt(expr, Needs::Value)?;
+                self.asm.push(Inst::Pop, span);
+            }
+            (expr, _) => {
+                self.compile_stmt(expr, Needs::None)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Compile the value of the static with the given item, which is
+    /// initialized by calling it if this is its first use.
+    pub(crate) fn compile_static(&mut self, item: &Item, span: Span) {
+        let hash = Hash::type_hash(item);
+        self.asm.push_with_comment(
+            Inst::Call { hash, args: 0 },
+            span,
+            format!("static `{}`", item),
+        );
+        self.statics.push((item.clone(), span));
+    }
+
+    /// Compile the body of a function, returning from it either with the
+    /// value of the trailing expression or with unit.
+    pub(crate) fn compile_fn_body(
+        &mut self,
+        exprs: &[(ast::Expr, Option<ast::SemiColon>)],
+        trailing_expr: Option<&ast::Expr>,
+        span: Span,
+    ) -> CompileResult<()> {
+        if exprs.is_empty() && trailing_expr.is_none() {
+            self.asm.push(Inst::ReturnUnit, span);
+            return Ok(());
+        }
+
+        self.compile_block_stmts(exprs, trailing_expr.is_none())?;
+
+        if let Some(expr) = trailing_expr {
+            if let ast::Expr::LitTuple(lit_tuple) = expr {
+                let scope = self.scopes.last(span)?;
+                let total_var_count = scope.total_var_count;
+                let deferred = scope.deferred();
+                self.compile_return_tuple(lit_tuple, total_var_count, &deferred)?;
+            } else {
+                self.compile_stmt(expr, Needs::Value)?;
+
+                let scope = self.scopes.last(span)?;
+                let total_var_count = scope.total_var_count;
+                let deferred = scope.deferred();
+                self.compile_deferred(&deferred, total_var_count + 1, span)?;
+                self.locals_clean(total_var_count, span);
+                self.asm.push(Inst::Return, span);
+            }
+        } else {
+            let scope = self.scopes.last(span)?;
+            let total_var_count = scope.total_var_count;
+            let deferred = scope.deferred();
+            self.compile_deferred(&deferred, total_var_count, span)?;
+            self.locals_pop(total_var_count, span);
+            self.asm.push(Inst::ReturnUnit, span);
+        }
+
+        Ok(())
+    }
+
+    /// Compile the given deferred cleanup in order.
+    ///
+    /// `total_var_count` is the number of values on the stack of the current
+    /// function, including any value which is being returned past the
+    /// cleanup.
+    pub(crate) fn compile_deferred(
+        &mut self,
+        deferred: &[Rc<Deferred>],
+        total_var_count: usize,
+        span: Span,
+    ) -> CompileResult<()> {
+        for deferred in deferred {
+            match &**deferred {
+                Deferred::Block {
+                    body,
+                    scopes,
+                    items,
+                } => {
+                    let mut scopes = scopes.snapshot();
+                    scopes.last_mut(span)?.total_var_count = total_var_count;
+
+                    let scopes = mem::replace(&mut self.scopes, scopes);
+                    let items = mem::replace(&mut self.items, items.snapshot());
+                    let loops = mem::replace(&mut self.loops, Loops::new());
+
+                    let result = self.compile((body, Needs::None));
+
+                    self.scopes = scopes;
+                    self.items = items;
+                    self.loops = loops;
+                    result?;
+                }
+                Deferred::Exit { offset, span } => {
+                    self.asm.push(Inst::Copy { offset: *offset }, *span);
+                    self.asm.push(Inst::Exit, *span);
+                    self.asm.push(Inst::Pop, *span);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the stack offset of the local variable referenced by the given
+    /// expression, if it is a plain reference to a local variable.
+    pub(crate) fn local_var_offset(&self, expr: &ast::Expr) -> CompileResult<Option<usize>> {
+        let ident = match expr {
+            ast::Expr::Path(path) => match path.try_as_ident() {
+                Some(ident) => ident,
+                None => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        let name = ident.resolve(&*self.source)?;
+        Ok(self.scopes.try_get_var(name)?.map(|var| var.offset))
+    }
+
+    /// Warn if the given expression refers to a variable that is being
+    /// iterated over by an enclosing loop, since it is being mutated through
+    /// `span`.
+    pub(crate) fn check_mutate_while_iterating(
+        &mut self,
+        target: &ast::Expr,
+        span: Span,
+    ) -> CompileResult<()> {
+        let offset = match self.local_var_offset(target)? {
+            Some(offset) => offset,
+            None => return Ok(()),
+        };
+
+        if let Some(iter) = self.loops.iterating_over(offset) {
+            self.warnings
+                .mutate_while_iterating(self.source_id, span, iter, self.context());
+        }
+
+        Ok(())
+    }
+
+    /// Warn if the given expression is used where a boolean is expected, but
+    /// can be seen to never produce one.
+    pub(crate) fn check_boolean(&mut self, expr: &ast::Expr) {
+        let expr = match expr {
+            ast::Expr::ExprGroup(expr_group) => return self.check_boolean(&*expr_group.expr),
+            expr => expr,
+        };
+
+        match expr {
+            ast::Expr::LitUnit(..)
+            | ast::Expr::LitChar(..)
+            | ast::Expr::LitByte(..)
+            | ast::Expr::LitNumber(..)
+            | ast::Expr::LitStr(..)
+            | ast::Expr::LitByteStr(..)
+            | ast::Expr::LitTemplate(..)
+            | ast::Expr::LitVec(..)
+            | ast::Expr::LitObject(..)
+            | ast::Expr::LitTuple(..) => {
+                self.warnings
+                    .not_boolean(self.source_id, expr.span(), self.context());
+            }
+            _ => (),
+        }
+    }
+
+    /// Warn about branches of a conditional expression which don't produce a
+    /// value, if any other branch does.
+    pub(crate) fn check_branch_values(&mut self, branches: &[(Span, Option<bool>)]) {
+        let value = branches
+            .iter()
+            .find(|(_, value)| *value == Some(true))
+            .map(|(span, _)| *span);
+
+        let value = match value {
+            Some(value) => value,
+            None => return,
+        };
+
+        for (span, _) in branches.iter().filter(|(_, value)| *value == Some(false)) {
+            self.warnings
+                .branch_without_value(self.source_id, *span, value, self.context());
+        }
+    }
+
     /// Get the latest relevant warning context.
     pub(crate) fn context(&self) -> Option<Span> {
         self.contexts.last().copied()
     }
 }
+
+/// Test if the branch of a conditional expression produces a value.
+///
+/// Returns `None` if this can't be told, like if the branch diverges or ends
+/// in another conditional expression, which is checked on its own.
+pub(crate) fn branch_value(expr: &ast::Expr) -> Option<bool> {
+    match expr {
+        ast::Expr::ExprBlock(expr_block) if expr_block.async_.is_none() => block_value(expr_block),
+        ast::Expr::ExprGroup(expr_group) => branch_value(&*expr_group.expr),
+        ast::Expr::ExprIf(..)
+        | ast::Expr::ExprMatch(..)
+        | ast::Expr::ExprWhile(..)
+        | ast::Expr::ExprLoop(..)
+        | ast::Expr::ExprFor(..)
+        | ast::Expr::ExprReturn(..)
+        | ast::Expr::ExprBreak(..)
+        | ast::Expr::ExprContinue(..) => None,
+        expr => Some(!expr.produces_nothing()),
+    }
+}
+
+/// Test if the given statement is a plain value which would be discarded if
+/// it's terminated by a semi-colon.
+fn discards_value(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::ExprGroup(expr_group) => discards_value(&*expr_group.expr),
+        ast::Expr::ExprBinary(expr_binary) => !expr_binary.op.is_assign(),
+        ast::Expr::Self_(..)
+        | ast::Expr::Path(..)
+        | ast::Expr::ExprUnary(..)
+        | ast::Expr::ExprFieldAccess(..)
+        | ast::Expr::ExprIndexGet(..)
+        | ast::Expr::LitBool(..)
+        | ast::Expr::LitChar(..)
+        | ast::Expr::LitByte(..)
+        | ast::Expr::LitNumber(..)
+        | ast::Expr::LitStr(..)
+        | ast::Expr::LitByteStr(..)
+        | ast::Expr::LitTemplate(..)
+        | ast::Expr::LitVec(..)
+        | ast::Expr::LitObject(..)
+        | ast::Expr::LitTuple(..) => true,
+        _ => false,
+    }
+}
+
+/// Test if the block used as the branch of a conditional expression produces
+/// a value.
+///
+/// See [branch_value].
+pub(crate) fn block_value(expr_block: &ast::ExprBlock) -> Option<bool> {
+    if let Some(trailing_expr) = &expr_block.trailing_expr {
+        return branch_value(trailing_expr);
+    }
+
+    match expr_block.exprs.last() {
+        Some((ast::Expr::ExprReturn(..), _))
+        | Some((ast::Expr::ExprBreak(..), _))
+        | Some((ast::Expr::ExprContinue(..), _))
+        | Some((ast::Expr::ExprLoop(..), _)) => None,
+        _ => Some(false),
+    }
+}
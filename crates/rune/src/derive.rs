@@ -0,0 +1,550 @@
+//! Derives for script declarations, like `#[derive(Builder)]`.
+//!
+//! A derive generates the source of additional items for the declaration it's
+//! attached to. The generated source is compiled as another file in the same
+//! module as the declaration.
+
+use crate::ast;
+use crate::collections::{HashMap, HashSet};
+use crate::error::{CompileError, CompileResult};
+use crate::traits::Resolve as _;
+use runestick::{Source, Span};
+use std::fmt::Write as _;
+
+/// Test if the given attribute is handled by derives rather than being a
+/// condition.
+pub(crate) fn is_derive_attribute(meta: &ast::Meta, source: &Source) -> CompileResult<bool> {
+    Ok(matches!(
+        meta.name().resolve(source)?,
+        "derive" | "builder" | "serde"
+    ))
+}
+
+/// Expand the derives in the attributes of the given declaration, returning
+/// the source of the generated items if there are any.
+pub(crate) fn expand(
+    attributes: &[ast::Attribute],
+    decl: &ast::Decl,
+    source: &Source,
+) -> CompileResult<Option<String>> {
+    let mut out = String::new();
+    let mut derives = Vec::new();
+    let mut builder = None;
+    let mut serde = None;
+
+    for attribute in attributes {
+        match &attribute.meta {
+            ast::Meta::List(list) if list.name.resolve(source)? == "derive" => {
+                derives.extend(list.items.items.iter().map(|(meta, _)| meta));
+            }
+            ast::Meta::List(list) if list.name.resolve(source)? == "builder" => {
+                if builder.is_some() {
+                    return Err(CompileError::UnsupportedAttribute { span: list.span() });
+                }
+
+                builder = Some(list);
+            }
+            ast::Meta::List(list) if list.name.resolve(source)? == "serde" => {
+                if serde.is_some() {
+                    return Err(CompileError::UnsupportedAttribute { span: list.span() });
+                }
+
+                serde = Some(list);
+            }
+            meta if is_derive_attribute(meta, source)? => {
+                return Err(CompileError::UnsupportedAttribute { span: meta.span() });
+            }
+            _ => (),
+        }
+    }
+
+    // NB: the serde options are shared by `Serialize` and `Deserialize`.
+    let mut renames = None;
+
+    for meta in derives {
+        let span = meta.span();
+
+        let name = match meta {
+            ast::Meta::Ident(ident) => ident.resolve(source)?,
+            _ => return Err(CompileError::UnsupportedDerive { span }),
+        };
+
+        match name {
+            "Builder" => derive_builder(decl, builder.take(), span, source, &mut out)?,
+            "Serialize" | "Deserialize" => {
+                let shape = Shape::new(decl, span, source)?;
+
+                let renames = match &renames {
+                    Some(renames) => renames,
+                    None => renames.get_or_insert(serde_renames(serde.take(), &shape, source)?),
+                };
+
+                if name == "Serialize" {
+                    derive_serialize(&shape, renames, &mut out);
+                } else {
+                    derive_deserialize(&shape, renames, &mut out);
+                }
+            }
+            _ => return Err(CompileError::UnsupportedDerive { span }),
+        }
+    }
+
+    // NB: options for a derive which isn't used are an error.
+    if let Some(options) = builder.or(serde) {
+        return Err(CompileError::UnsupportedAttribute {
+            span: options.span(),
+        });
+    }
+
+    Ok(if out.is_empty() { None } else { Some(out) })
+}
+
+/// Get the name and the fields of the struct a derive is attached to.
+fn named_struct<'a>(
+    decl: &'a ast::Decl,
+    span: Span,
+    source: &'a Source,
+) -> CompileResult<(&'a str, Vec<(&'a str, Span)>)> {
+    let decl_struct = match decl {
+        ast::Decl::DeclStruct(decl_struct) => decl_struct,
+        _ => return Err(CompileError::UnsupportedDerive { span }),
+    };
+
+    let body = match &decl_struct.body {
+        ast::DeclStructBody::StructBody(body) => body,
+        _ => return Err(CompileError::UnsupportedDerive { span }),
+    };
+
+    let mut fields = Vec::new();
+
+    for (ident, _) in &body.fields {
+        fields.push((ident.resolve(source)?, ident.span()));
+    }
+
+    Ok((decl_struct.ident.resolve(source)?, fields))
+}
+
+/// Derive a builder for a struct, like:
+///
+/// ```text
+/// #[builder(optional(port))]
+/// #[derive(Builder)]
+/// struct Config { host, port }
+/// ```
+///
+/// Which generates a `ConfigBuilder` with a setter for each field, constructed
+/// through `Config::builder()`. Its `build` function returns the struct, or an
+/// error if a field which isn't optional hasn't been set. Optional fields are
+/// set to an option.
+fn derive_builder(
+    decl: &ast::Decl,
+    options: Option<&ast::MetaList>,
+    span: Span,
+    source: &Source,
+    out: &mut String,
+) -> CompileResult<()> {
+    let (name, fields) = named_struct(decl, span, source)?;
+    let optional = builder_optional(options, &fields, source)?;
+
+    for (field, span) in &fields {
+        if *field == "build" {
+            return Err(CompileError::UnsupportedDerive { span: *span });
+        }
+    }
+
+    let field_names = fields.iter().map(|(field, _)| *field).collect::<Vec<_>>();
+
+    // NB: writing to a string can't fail.
+    let _ = writeln!(
+        out,
+        "struct {}Builder {{ {} }}",
+        name,
+        field_names.join(", ")
+    );
+    let _ = writeln!(out, "impl {}Builder {{", name);
+
+    for field in &field_names {
+        let _ = writeln!(out, "    fn {}(self, value) {{", field);
+        let _ = writeln!(out, "        self.{} = Some(value);", field);
+        let _ = writeln!(out, "        self");
+        let _ = writeln!(out, "    }}");
+    }
+
+    let _ = writeln!(out, "    fn build(self) {{");
+
+    for field in &field_names {
+        if optional.contains(field) {
+            let _ = writeln!(out, "        let {0} = self.{0};", field);
+        } else {
+            let _ = writeln!(
+                out,
+                "        let {0} = match self.{0} {{ Some({0}) => {0}, None => return Err(\"missing field `{0}` of `{1}`\") }};",
+                field, name
+            );
+        }
+    }
+
+    let _ = writeln!(out, "        Ok({} {{ {} }})", name, field_names.join(", "));
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "}}");
+
+    let unset = field_names
+        .iter()
+        .map(|field| format!("{}: None", field))
+        .collect::<Vec<_>>();
+
+    let _ = writeln!(out, "impl {} {{", name);
+    let _ = writeln!(out, "    fn builder() {{");
+    let _ = writeln!(out, "        {}Builder {{ {} }}", name, unset.join(", "));
+    let _ = writeln!(out, "    }}");
+    let _ = writeln!(out, "}}");
+    Ok(())
+}
+
+/// Get the fields which are marked as optional through a
+/// `#[builder(optional(...))]` attribute.
+fn builder_optional<'a>(
+    options: Option<&ast::MetaList>,
+    fields: &[(&'a str, Span)],
+    source: &Source,
+) -> CompileResult<HashSet<&'a str>> {
+    let mut optional = HashSet::new();
+
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(optional),
+    };
+
+    for (meta, _) in &options.items.items {
+        let list = match meta {
+            ast::Meta::List(list) if list.name.resolve(source)? == "optional" => list,
+            meta => return Err(CompileError::UnsupportedAttribute { span: meta.span() }),
+        };
+
+        for (meta, _) in &list.items.items {
+            let span = meta.span();
+
+            let ident = match meta {
+                ast::Meta::Ident(ident) => ident.resolve(source)?,
+                _ => return Err(CompileError::UnsupportedAttribute { span }),
+            };
+
+            match fields.iter().find(|(field, _)| *field == ident) {
+                Some((field, _)) => {
+                    optional.insert(*field);
+                }
+                None => return Err(CompileError::UnsupportedAttribute { span }),
+            }
+        }
+    }
+
+    Ok(optional)
+}
+
+/// The shape of a declaration which is (de)serialized.
+enum Shape<'a> {
+    /// A struct with named fields.
+    Struct {
+        name: &'a str,
+        fields: Vec<(&'a str, Span)>,
+    },
+    /// An enum, with the name and body of each variant.
+    Enum {
+        name: &'a str,
+        variants: Vec<(&'a str, Span, Body<'a>)>,
+    },
+}
+
+/// The body of an enum variant.
+enum Body<'a> {
+    Empty,
+    Tuple(usize),
+    Struct(Vec<(&'a str, Span)>),
+}
+
+impl<'a> Shape<'a> {
+    /// Get the shape of the declaration a derive is attached to.
+    fn new(decl: &'a ast::Decl, span: Span, source: &'a Source) -> CompileResult<Self> {
+        let decl_enum = match decl {
+            ast::Decl::DeclEnum(decl_enum) => decl_enum,
+            decl => {
+                let (name, fields) = match decl {
+                    ast::Decl::DeclStruct(ast::DeclStruct {
+                        ident,
+                        body: ast::DeclStructBody::EmptyBody(..),
+                        ..
+                    }) => (ident.resolve(source)?, Vec::new()),
+                    decl => named_struct(decl, span, source)?,
+                };
+
+                return Ok(Self::Struct { name, fields });
+            }
+        };
+
+        let mut variants = Vec::new();
+
+        for (ident, body, _, _) in &decl_enum.variants {
+            let body = match body {
+                ast::DeclStructBody::EmptyBody(..) => Body::Empty,
+                ast::DeclStructBody::TupleBody(body) => Body::Tuple(body.fields.len()),
+                ast::DeclStructBody::StructBody(body) => {
+                    let mut fields = Vec::new();
+
+                    for (ident, _) in &body.fields {
+                        fields.push((ident.resolve(source)?, ident.span()));
+                    }
+
+                    Body::Struct(fields)
+                }
+            };
+
+            variants.push((ident.resolve(source)?, ident.span(), body));
+        }
+
+        Ok(Self::Enum {
+            name: decl_enum.name.resolve(source)?,
+            variants,
+        })
+    }
+
+    /// Test if the shape has a field or variant with the given name.
+    fn contains(&self, ident: &str) -> bool {
+        let has_field = |fields: &[(&str, Span)]| fields.iter().any(|(field, _)| *field == ident);
+
+        match self {
+            Self::Struct { fields, .. } => has_field(fields),
+            Self::Enum { variants, .. } => variants.iter().any(|(variant, _, body)| {
+                *variant == ident
+                    || match body {
+                        Body::Struct(fields) => has_field(fields),
+                        _ => false,
+                    }
+            }),
+        }
+    }
+}
+
+/// Get the serialized names of fields and variants, as renamed through a
+/// `#[serde(rename(...))]` attribute.
+fn serde_renames(
+    options: Option<&ast::MetaList>,
+    shape: &Shape<'_>,
+    source: &Source,
+) -> CompileResult<HashMap<String, String>> {
+    let mut renames = HashMap::new();
+
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(renames),
+    };
+
+    for (meta, _) in &options.items.items {
+        let list = match meta {
+            ast::Meta::List(list) if list.name.resolve(source)? == "rename" => list,
+            meta => return Err(CompileError::UnsupportedAttribute { span: meta.span() }),
+        };
+
+        for (meta, _) in &list.items.items {
+            let span = meta.span();
+
+            let meta = match meta {
+                ast::Meta::NameValue(meta) => meta,
+                _ => return Err(CompileError::UnsupportedAttribute { span }),
+            };
+
+            let ident = meta.name.resolve(source)?;
+
+            if !shape.contains(ident) {
+                return Err(CompileError::UnsupportedAttribute { span });
+            }
+
+            let rename = match &meta.value {
+                ast::MetaValue::LitStr(lit_str) => lit_str.resolve(source)?,
+                ast::MetaValue::LitNumber(..) => {
+                    return Err(CompileError::UnsupportedAttribute { span })
+                }
+            };
+
+            renames.insert(ident.to_owned(), rename.into_owned());
+        }
+    }
+
+    Ok(renames)
+}
+
+/// Get the serialized name of a field or variant as a string literal.
+fn serde_key(renames: &HashMap<String, String>, ident: &str) -> String {
+    // NB: debug formatting produces escapes which are valid in a string
+    // literal.
+    format!(
+        "{:?}",
+        renames.get(ident).map(String::as_str).unwrap_or(ident)
+    )
+}
+
+/// Generate the fields of an object from the given fields.
+fn serialize_fields(
+    renames: &HashMap<String, String>,
+    fields: &[(&str, Span)],
+    prefix: &str,
+) -> String {
+    let fields = fields
+        .iter()
+        .map(|(field, _)| format!("{}: {}{}", serde_key(renames, field), prefix, field))
+        .collect::<Vec<_>>();
+
+    format!("#{{{}}}", fields.join(", "))
+}
+
+/// Generate lets which get the given fields from an object named `__object`,
+/// returning an error if a field is missing.
+fn deserialize_fields(
+    renames: &HashMap<String, String>,
+    fields: &[(&str, Span)],
+    name: &str,
+    out: &mut String,
+) {
+    for (field, _) in fields {
+        let key = renames.get(*field).map(String::as_str).unwrap_or(field);
+        let error = format!("missing field `{}` of `{}`", key, name);
+
+        let _ = writeln!(
+            out,
+            "        let {0} = match __object.get({1:?}) {{ Some({0}) => {0}, None => return Err({2:?}) }};",
+            field, key, error
+        );
+    }
+}
+
+/// Derive serialization of a struct or an enum into an object, like:
+///
+/// ```text
+/// #[derive(Serialize)]
+/// #[serde(rename(user_name = "userName"))]
+/// struct User { user_name, age }
+/// ```
+///
+/// Which adds a `to_object` instance function to the struct returning an
+/// object like `#{"userName": user_name, "age": age}`. Variants of an enum are
+/// serialized as an object with a single key for the name of the variant, and
+/// the fields of the variant as its value.
+fn derive_serialize(shape: &Shape<'_>, renames: &HashMap<String, String>, out: &mut String) {
+    match shape {
+        Shape::Struct { name, fields } => {
+            let _ = writeln!(out, "impl {} {{", name);
+            let _ = writeln!(out, "    fn to_object(self) {{");
+            let _ = writeln!(
+                out,
+                "        {}",
+                serialize_fields(renames, fields, "self.")
+            );
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+        Shape::Enum { name, variants } => {
+            let _ = writeln!(out, "impl {} {{", name);
+            let _ = writeln!(out, "    fn to_object(self) {{");
+            let _ = writeln!(out, "        match self {{");
+
+            for (variant, _, body) in variants {
+                let key = serde_key(renames, variant);
+
+                let (pattern, value) = match body {
+                    Body::Empty => (String::new(), String::from("()")),
+                    Body::Tuple(count) => {
+                        let values = (0..*count).map(|n| format!("__{}", n)).collect::<Vec<_>>();
+                        let values = values.join(", ");
+                        (format!("({})", values), format!("[{}]", values))
+                    }
+                    Body::Struct(fields) => {
+                        let names = fields.iter().map(|(field, _)| *field).collect::<Vec<_>>();
+                        let pattern = format!(" {{ {} }}", names.join(", "));
+                        (pattern, serialize_fields(renames, fields, ""))
+                    }
+                };
+
+                let _ = writeln!(
+                    out,
+                    "            {}::{}{} => #{{{}: {}}},",
+                    name, variant, pattern, key, value
+                );
+            }
+
+            let _ = writeln!(out, "        }}");
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+    }
+}
+
+/// Derive deserialization of a struct or an enum from an object.
+///
+/// This adds a `from_object` function to the struct or the enum, which is the
+/// inverse of the one generated by [derive_serialize]. It returns an error if
+/// the object doesn't have the expected fields or variant.
+fn derive_deserialize(shape: &Shape<'_>, renames: &HashMap<String, String>, out: &mut String) {
+    match shape {
+        Shape::Struct { name, fields } => {
+            let names = fields.iter().map(|(field, _)| *field).collect::<Vec<_>>();
+
+            let _ = writeln!(out, "impl {} {{", name);
+            let _ = writeln!(out, "    fn from_object(__object) {{");
+            deserialize_fields(renames, fields, name, out);
+            let _ = writeln!(out, "        Ok({} {{ {} }})", name, names.join(", "));
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+        Shape::Enum { name, variants } => {
+            let _ = writeln!(out, "impl {} {{", name);
+            let _ = writeln!(out, "    fn from_object(__object) {{");
+            let _ = writeln!(
+                out,
+                "        if __object.len() != 1 {{ return Err(\"expected a single variant of `{}`\"); }}",
+                name
+            );
+
+            for (variant, _, body) in variants {
+                let key = serde_key(renames, variant);
+
+                let _ = writeln!(
+                    out,
+                    "        if let Some(__object) = __object.get({}) {{",
+                    key
+                );
+
+                match body {
+                    Body::Empty => {
+                        let _ = writeln!(out, "        return Ok({}::{});", name, variant);
+                    }
+                    Body::Tuple(count) => {
+                        let values = (0..*count).map(|n| format!("__{}", n)).collect::<Vec<_>>();
+                        let values = values.join(", ");
+
+                        let _ = writeln!(
+                            out,
+                            "        match __object {{ [{0}] => return Ok({1}::{2}({0})), _ => return Err(\"expected {3} values for `{1}::{2}`\") }}",
+                            values, name, variant, count
+                        );
+                    }
+                    Body::Struct(fields) => {
+                        let names = fields.iter().map(|(field, _)| *field).collect::<Vec<_>>();
+                        deserialize_fields(renames, fields, name, out);
+                        let _ = writeln!(
+                            out,
+                            "        return Ok({}::{} {{ {} }});",
+                            name,
+                            variant,
+                            names.join(", ")
+                        );
+                    }
+                }
+
+                let _ = writeln!(out, "        }}");
+            }
+
+            let _ = writeln!(out, "        Err(\"unknown variant of `{}`\")", name);
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::unit_builder::LinkerError;
 use crate::{CompileError, LoadError, LoadErrorKind, Sources, WarningKind, Warnings};
-use runestick::VmError;
+use runestick::{VmError, VmErrorKind};
 use std::error::Error as _;
 use std::fmt;
 use std::io;
@@ -116,6 +116,82 @@ impl EmitDiagnostics for Warnings {
 
                     *context
                 }
+                WarningKind::ComputedKeyShadowsField {
+                    span,
+                    field,
+                    context,
+                } => {
+                    labels.push(
+                        Label::primary(w.source_id, span.start..span.end)
+                            .with_message("computed key replaces an existing field"),
+                    );
+
+                    labels.push(
+                        Label::secondary(w.source_id, field.start..field.end)
+                            .with_message("field first assigned here"),
+                    );
+
+                    *context
+                }
+                WarningKind::MutateWhileIterating {
+                    span,
+                    iter,
+                    context,
+                } => {
+                    labels.push(
+                        Label::primary(w.source_id, span.start..span.end)
+                            .with_message("modified while being iterated over"),
+                    );
+
+                    labels.push(
+                        Label::secondary(w.source_id, iter.start..iter.end)
+                            .with_message("iteration happens here"),
+                    );
+
+                    *context
+                }
+                WarningKind::NotBoolean { span, context } => {
+                    labels.push(
+                        Label::primary(w.source_id, span.start..span.end)
+                            .with_message("expected a boolean"),
+                    );
+
+                    *context
+                }
+                WarningKind::BranchWithoutValue {
+                    span,
+                    value,
+                    context,
+                } => {
+                    labels.push(
+                        Label::primary(w.source_id, span.start..span.end)
+                            .with_message("branch doesn't produce a value"),
+                    );
+
+                    labels.push(
+                        Label::secondary(w.source_id, value.start..value.end)
+                            .with_message("but this branch does"),
+                    );
+
+                    *context
+                }
+                WarningKind::SemiColonDiscardsValue {
+                    span,
+                    value,
+                    context,
+                } => {
+                    labels.push(
+                        Label::primary(w.source_id, span.start..span.end)
+                            .with_message("semicolon discards the value of the block"),
+                    );
+
+                    labels.push(
+                        Label::secondary(w.source_id, value.start..value.end)
+                            .with_message("this value is discarded"),
+                    );
+
+                    *context
+                }
                 WarningKind::UnecessarySemiColon { span } => {
                     labels.push(
                         Label::primary(w.source_id, span.start..span.end)
@@ -201,9 +277,25 @@ impl EmitDiagnostics for VmError {
         labels
             .push(Label::primary(source_id, span.start..span.end).with_message(error.to_string()));
 
+        let site = error
+            .kind()
+            .access_error()
+            .and_then(|e| e.snapshot())
+            .and_then(|s| s.site_in(&unit));
+
+        if let Some(site) = site {
+            if let Some(site) = debug_info.instruction_at(site) {
+                labels.push(
+                    Label::secondary(site.source_id, site.span.start..site.span.end)
+                        .with_message("value previously accessed here"),
+                );
+            }
+        }
+
         let diagnostic = Diagnostic::error()
             .with_message("virtual machine error")
-            .with_labels(labels);
+            .with_labels(labels)
+            .with_notes(vm_error_causes(&error));
 
         term::emit(out, &config, &files, &diagnostic)?;
         Ok(())
@@ -225,6 +317,12 @@ impl EmitDiagnostics for LoadError {
 
         let mut labels = Vec::new();
 
+        let code = match self.kind() {
+            LoadErrorKind::ParseError { error, .. } => Some(error.code()),
+            LoadErrorKind::CompileError { error, .. } => Some(error.code()),
+            _ => None,
+        };
+
         let (span, source_id) = match self.kind() {
             LoadErrorKind::Internal { message } => {
                 writeln!(out, "internal error: {}", message)?;
@@ -253,6 +351,29 @@ impl EmitDiagnostics for LoadError {
 
                             term::emit(out, &config, &files, &diagnostic)?;
                         }
+                        LinkerError::MissingCapability {
+                            hash,
+                            capability,
+                            spans,
+                        } => {
+                            let mut labels = Vec::new();
+
+                            for (span, source_id) in spans {
+                                labels.push(
+                                    Label::primary(*source_id, span.start..span.end)
+                                        .with_message("called here."),
+                                );
+                            }
+
+                            let diagnostic = Diagnostic::error()
+                                .with_message(format!(
+                                    "function with hash `{}` requires the capability `{}`, which is not granted",
+                                    hash, capability
+                                ))
+                                .with_labels(labels);
+
+                            term::emit(out, &config, &files, &diagnostic)?;
+                        }
                     }
                 }
 
@@ -262,7 +383,14 @@ impl EmitDiagnostics for LoadError {
             LoadErrorKind::CompileError { source_id, error } => {
                 let source_id = *source_id;
 
-                let span = match error {
+                for (span, item) in error.expansions() {
+                    labels.push(
+                        Label::secondary(source_id, span.start..span.end)
+                            .with_message(format!("in this invocation of `{}!`", item)),
+                    );
+                }
+
+                let span = match error.root() {
                     CompileError::ReturnLocalReferences {
                         block,
                         references_at,
@@ -304,6 +432,26 @@ impl EmitDiagnostics for LoadError {
 
                         *span
                     }
+                    CompileError::CallArgumentCount {
+                        span, signature, ..
+                    } => {
+                        if let Some((declared_source_id, declared)) = signature.declared {
+                            labels.push(
+                                Label::secondary(declared_source_id, declared.start..declared.end)
+                                    .with_message("function declared here"),
+                            );
+                        }
+
+                        *span
+                    }
+                    CompileError::ScriptMainConflict { span, main } => {
+                        labels.push(
+                            Label::secondary(source_id, main.start..main.end)
+                                .with_message("`main` declared here"),
+                        );
+
+                        *span
+                    }
                     CompileError::ModAlreadyLoaded { span, existing, .. } => {
                         let (existing_source_id, existing_span) = *existing;
 
@@ -329,11 +477,26 @@ impl EmitDiagnostics for LoadError {
                 .push(Label::primary(source_id, span.start..span.end).with_message(e.to_string()));
         }
 
-        let diagnostic = Diagnostic::error()
+        let mut diagnostic = Diagnostic::error()
             .with_message(self.to_string())
             .with_labels(labels);
 
+        if let Some(code) = code {
+            diagnostic = diagnostic.with_code(code);
+        }
+
         term::emit(out, &config, &files, &diagnostic)?;
         Ok(())
     }
 }
+
+/// Describe the errors which caused the given error through the context added
+/// to it with [VmError::with_context].
+fn vm_error_causes(error: &VmError) -> Vec<String> {
+    error
+        .chain()
+        .zip(error.chain().skip(1))
+        .filter(|(error, _)| matches!(error, VmErrorKind::Context { .. }))
+        .map(|(_, cause)| format!("caused by: {}", cause))
+        .collect()
+}
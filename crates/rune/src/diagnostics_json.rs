@@ -0,0 +1,534 @@
+//! Machine-readable diagnostics, serialized as JSON.
+//!
+//! Diagnostics are written as one JSON object per line, so that tools like
+//! editors and CI systems can consume them incrementally. Each object has the
+//! following schema:
+//!
+//! ```text
+//! {
+//!     "level": "error" | "warning",
+//!     "code": string | null,
+//!     "message": string,
+//!     "labels": [
+//!         {
+//!             "primary": bool,
+//!             "message": string,
+//!             "file": string,
+//!             "path": string | null,
+//!             "span": {"start": int, "end": int},
+//!             "start": {"line": int, "column": int},
+//!             "end": {"line": int, "column": int}
+//!         }
+//!     ],
+//!     "notes": [string],
+//!     "suggestions": [
+//!         {
+//!             "message": string,
+//!             "replacement": string,
+//!             "file": string,
+//!             "path": string | null,
+//!             "span": {"start": int, "end": int},
+//!             "start": {"line": int, "column": int},
+//!             "end": {"line": int, "column": int}
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! Spans are byte offsets into the source, while lines and columns are
+//! 1-based, with columns counted in characters. `file` is the name of the
+//! source, and `path` is the path it was loaded from if any. New fields might
+//! be added to the schema, but existing fields will not be changed or
+//! removed.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use rune::IntoJsonDiagnostics as _;
+//! use runestick::Source;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let context = runestick::Context::with_default_modules()?;
+//! let options = rune::Options::default();
+//!
+//! let mut sources = rune::Sources::new();
+//! sources.insert_default(Source::new("script", "fn main() { 1 + }"));
+//!
+//! let mut warnings = rune::Warnings::new();
+//! let error = rune::load_sources(&context, &options, &mut sources, &mut warnings).unwrap_err();
+//!
+//! let diagnostics = error.into_json_diagnostics(&sources);
+//! assert_eq!(diagnostics.len(), 1);
+//! assert_eq!(diagnostics[0].labels[0].location.start.line, 1);
+//!
+//! let mut out = Vec::new();
+//! rune::write_json_diagnostics(&mut out, &diagnostics)?;
+//! # Ok(()) }
+//! ```
+
+use crate::unit_builder::LinkerError;
+use crate::{CompileError, LoadError, LoadErrorKind, Sources, WarningKind, Warnings};
+use runestick::{Source, Span, VmError, VmErrorKind};
+use serde::Serialize;
+use std::error::Error as _;
+use std::io;
+
+/// The severity of a diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonLevel {
+    /// An error which prevented compilation or execution.
+    Error,
+    /// A warning.
+    Warning,
+}
+
+/// A single diagnostic.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonDiagnostic {
+    /// The severity of the diagnostic.
+    pub level: JsonLevel,
+    /// The error code of the diagnostic, if it has one.
+    pub code: Option<String>,
+    /// The main message of the diagnostic.
+    pub message: String,
+    /// Labelled locations in the source related to the diagnostic.
+    pub labels: Vec<JsonLabel>,
+    /// Additional notes.
+    pub notes: Vec<String>,
+    /// Suggested replacements which would address the diagnostic.
+    pub suggestions: Vec<JsonSuggestion>,
+}
+
+impl JsonDiagnostic {
+    /// Construct a new diagnostic without any labels.
+    fn new(level: JsonLevel, message: String) -> Self {
+        Self {
+            level,
+            code: None,
+            message,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            suggestions: Vec::new(),
+        }
+    }
+
+    /// Push a label, if the location it refers to exists.
+    fn label(
+        &mut self,
+        sources: &Sources,
+        source_id: usize,
+        span: Span,
+        primary: bool,
+        message: &str,
+    ) {
+        if let Some(location) = JsonLocation::new(sources, source_id, span) {
+            self.labels.push(JsonLabel {
+                primary,
+                message: message.to_owned(),
+                location,
+            });
+        }
+    }
+}
+
+/// A labelled location in a source.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonLabel {
+    /// If this is the primary location of the diagnostic.
+    pub primary: bool,
+    /// The message of the label.
+    pub message: String,
+    /// The labelled location.
+    #[serde(flatten)]
+    pub location: JsonLocation,
+}
+
+/// A suggested replacement of a location in a source.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonSuggestion {
+    /// A description of the suggestion.
+    pub message: String,
+    /// The text to replace the location with.
+    pub replacement: String,
+    /// The location to replace.
+    #[serde(flatten)]
+    pub location: JsonLocation,
+}
+
+/// A location in a source.
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonLocation {
+    /// The name of the source.
+    pub file: String,
+    /// The path the source was loaded from, if any.
+    pub path: Option<String>,
+    /// The byte span of the location.
+    pub span: JsonSpan,
+    /// The line and column where the location starts.
+    pub start: JsonPosition,
+    /// The line and column where the location ends.
+    pub end: JsonPosition,
+}
+
+impl JsonLocation {
+    fn new(sources: &Sources, source_id: usize, span: Span) -> Option<Self> {
+        let source = sources.get(source_id)?;
+
+        Some(Self {
+            file: source.name().to_owned(),
+            path: source.path().map(|p| p.display().to_string()),
+            span: JsonSpan {
+                start: span.start,
+                end: span.end,
+            },
+            start: JsonPosition::new(source, span.start),
+            end: JsonPosition::new(source, span.end),
+        })
+    }
+}
+
+/// A span of bytes in a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct JsonSpan {
+    /// The byte offset where the span starts.
+    pub start: usize,
+    /// The byte offset where the span ends.
+    pub end: usize,
+}
+
+/// A 1-based line and column in a source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct JsonPosition {
+    /// The line number.
+    pub line: usize,
+    /// The column number, counted in characters.
+    pub column: usize,
+}
+
+impl JsonPosition {
+    fn new(source: &Source, offset: usize) -> Self {
+        let source = source.as_str();
+        let offset = usize::min(offset, source.len());
+        let before = source.get(..offset).unwrap_or_default();
+
+        let line_start = before.rfind('\n').map(|n| n + 1).unwrap_or_default();
+
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    }
+}
+
+/// Helper trait for converting into JSON diagnostics.
+///
+/// See [write_json_diagnostics] for how to output them.
+pub trait IntoJsonDiagnostics {
+    /// Convert the current type into JSON diagnostics.
+    fn into_json_diagnostics(self, sources: &Sources) -> Vec<JsonDiagnostic>;
+}
+
+impl IntoJsonDiagnostics for Warnings {
+    fn into_json_diagnostics(self, sources: &Sources) -> Vec<JsonDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        for w in &self {
+            let source = sources.get(w.source_id);
+
+            let (message, span, context) = match w.kind {
+                WarningKind::NotUsed { span, context } => ("value not used", span, context),
+                WarningKind::LetPatternMightPanic { span, context } => {
+                    ("let binding might panic", span, context)
+                }
+                WarningKind::TemplateWithoutExpansions { span, context } => (
+                    "template string without expansions like `{1 + 2}`",
+                    span,
+                    context,
+                ),
+                WarningKind::RemoveTupleCallParams { span, context, .. } => (
+                    "constructing this variant could be done without parentheses",
+                    span,
+                    context,
+                ),
+                WarningKind::ComputedKeyShadowsField { span, context, .. } => {
+                    ("computed key replaces an existing field", span, context)
+                }
+                WarningKind::MutateWhileIterating { span, context, .. } => {
+                    ("modified while being iterated over", span, context)
+                }
+                WarningKind::NotBoolean { span, context } => ("expected a boolean", span, context),
+                WarningKind::BranchWithoutValue { span, context, .. } => {
+                    ("branch doesn't produce a value", span, context)
+                }
+                WarningKind::SemiColonDiscardsValue { span, context, .. } => {
+                    ("semicolon discards the value of the block", span, context)
+                }
+                WarningKind::UnecessarySemiColon { span } => ("unnecessary semicolon", span, None),
+            };
+
+            let mut d = JsonDiagnostic::new(JsonLevel::Warning, message.to_owned());
+            d.label(sources, w.source_id, span, true, message);
+
+            match w.kind {
+                WarningKind::LetPatternMightPanic { span, .. } => {
+                    let binding = source.and_then(|s| s.source(span));
+                    let location = JsonLocation::new(sources, w.source_id, span);
+
+                    if let (Some(binding), Some(location)) = (binding, location) {
+                        d.suggestions.push(JsonSuggestion {
+                            message: String::from("consider rewriting to an `if let`"),
+                            replacement: format!("if {} {{\n    // ..\n}}", binding),
+                            location,
+                        });
+                    }
+                }
+                WarningKind::RemoveTupleCallParams { span, variant, .. } => {
+                    let variant = source.and_then(|s| s.source(variant));
+                    let location = JsonLocation::new(sources, w.source_id, span);
+
+                    if let (Some(variant), Some(location)) = (variant, location) {
+                        d.suggestions.push(JsonSuggestion {
+                            message: String::from("remove the parentheses"),
+                            replacement: variant.to_owned(),
+                            location,
+                        });
+                    }
+                }
+                WarningKind::ComputedKeyShadowsField { field, .. } => {
+                    d.label(
+                        sources,
+                        w.source_id,
+                        field,
+                        false,
+                        "field first assigned here",
+                    );
+                }
+                WarningKind::MutateWhileIterating { iter, .. } => {
+                    d.label(sources, w.source_id, iter, false, "iteration happens here");
+                }
+                WarningKind::BranchWithoutValue { value, .. } => {
+                    d.label(sources, w.source_id, value, false, "but this branch does");
+                }
+                WarningKind::SemiColonDiscardsValue { span, value, .. } => {
+                    d.label(
+                        sources,
+                        w.source_id,
+                        value,
+                        false,
+                        "this value is discarded",
+                    );
+
+                    if let Some(location) = JsonLocation::new(sources, w.source_id, span) {
+                        d.suggestions.push(JsonSuggestion {
+                            message: String::from("remove the semicolon"),
+                            replacement: String::new(),
+                            location,
+                        });
+                    }
+                }
+                WarningKind::UnecessarySemiColon { span } => {
+                    if let Some(location) = JsonLocation::new(sources, w.source_id, span) {
+                        d.suggestions.push(JsonSuggestion {
+                            message: String::from("remove the semicolon"),
+                            replacement: String::new(),
+                            location,
+                        });
+                    }
+                }
+                _ => (),
+            }
+
+            if let Some(context) = context {
+                d.label(sources, w.source_id, context, false, "in this context");
+            }
+
+            diagnostics.push(d);
+        }
+
+        diagnostics
+    }
+}
+
+impl IntoJsonDiagnostics for VmError {
+    fn into_json_diagnostics(self, sources: &Sources) -> Vec<JsonDiagnostic> {
+        let (error, unwound) = self.into_unwound();
+        let mut d = JsonDiagnostic::new(JsonLevel::Error, error.to_string());
+
+        let debug_info = unwound.as_ref().and_then(|(unit, ip)| {
+            let debug_info = unit.debug_info()?;
+            Some((unit, debug_info, debug_info.instruction_at(*ip)?))
+        });
+
+        if let Some((unit, debug_info, debug_inst)) = debug_info {
+            let message = error.to_string();
+            d.label(
+                sources,
+                debug_inst.source_id,
+                debug_inst.span,
+                true,
+                &message,
+            );
+
+            let site = error
+                .kind()
+                .access_error()
+                .and_then(|e| e.snapshot())
+                .and_then(|s| s.site_in(unit))
+                .and_then(|site| debug_info.instruction_at(site));
+
+            if let Some(site) = site {
+                let message = "value previously accessed here";
+                d.label(sources, site.source_id, site.span, false, message);
+            }
+        }
+
+        d.notes = error
+            .chain()
+            .zip(error.chain().skip(1))
+            .filter(|(error, _)| matches!(error, VmErrorKind::Context { .. }))
+            .map(|(_, cause)| format!("caused by: {}", cause))
+            .collect();
+        vec![d]
+    }
+}
+
+impl IntoJsonDiagnostics for LoadError {
+    fn into_json_diagnostics(self, sources: &Sources) -> Vec<JsonDiagnostic> {
+        let (source_id, span) = match self.kind() {
+            LoadErrorKind::Internal { .. } | LoadErrorKind::ReadFile { .. } => {
+                return vec![JsonDiagnostic::new(JsonLevel::Error, self.to_string())];
+            }
+            LoadErrorKind::LinkError { errors } => {
+                let mut diagnostics = Vec::new();
+
+                for error in errors {
+                    match error {
+                        LinkerError::MissingFunction { hash, spans } => {
+                            let message = format!("missing function with hash `{}`", hash);
+                            let mut d = JsonDiagnostic::new(JsonLevel::Error, message);
+
+                            for (span, source_id) in spans {
+                                d.label(sources, *source_id, *span, true, "called here");
+                            }
+
+                            diagnostics.push(d);
+                        }
+                        LinkerError::MissingCapability {
+                            hash,
+                            capability,
+                            spans,
+                        } => {
+                            let message = format!(
+                                "function with hash `{}` requires the capability `{}`, which is not granted",
+                                hash, capability
+                            );
+                            let mut d = JsonDiagnostic::new(JsonLevel::Error, message);
+
+                            for (span, source_id) in spans {
+                                d.label(sources, *source_id, *span, true, "called here");
+                            }
+
+                            diagnostics.push(d);
+                        }
+                    }
+                }
+
+                return diagnostics;
+            }
+            LoadErrorKind::ParseError { source_id, error } => (*source_id, error.span()),
+            LoadErrorKind::CompileError { source_id, error } => (*source_id, error.span()),
+        };
+
+        let mut d = JsonDiagnostic::new(JsonLevel::Error, self.to_string());
+
+        d.code = match self.kind() {
+            LoadErrorKind::ParseError { error, .. } => Some(error.code().to_owned()),
+            LoadErrorKind::CompileError { error, .. } => Some(error.code().to_owned()),
+            _ => None,
+        };
+
+        if let Some(e) = self.source() {
+            d.label(sources, source_id, span, true, &e.to_string());
+        }
+
+        if let LoadErrorKind::CompileError { error, .. } = self.kind() {
+            for (span, item) in error.expansions() {
+                let message = format!("in this invocation of `{}!`", item);
+                d.label(sources, source_id, span, false, &message);
+            }
+
+            match error.root() {
+                CompileError::ReturnLocalReferences {
+                    block,
+                    references_at,
+                    span,
+                    ..
+                } => {
+                    for ref_span in references_at {
+                        if !span.overlaps(*ref_span) {
+                            d.label(
+                                sources,
+                                source_id,
+                                *ref_span,
+                                false,
+                                "reference created here",
+                            );
+                        }
+                    }
+
+                    d.label(sources, source_id, *block, false, "block returned from");
+                }
+                CompileError::DuplicateObjectKey {
+                    existing, object, ..
+                } => {
+                    d.label(
+                        sources,
+                        source_id,
+                        *existing,
+                        false,
+                        "previously defined here",
+                    );
+                    d.label(
+                        sources,
+                        source_id,
+                        *object,
+                        false,
+                        "object being defined here",
+                    );
+                }
+                CompileError::CallArgumentCount { signature, .. } => {
+                    if let Some((declared_source_id, declared)) = signature.declared {
+                        let message = "function declared here";
+                        d.label(sources, declared_source_id, declared, false, message);
+                    }
+                }
+                CompileError::ScriptMainConflict { main, .. } => {
+                    d.label(sources, source_id, *main, false, "`main` declared here");
+                }
+                CompileError::ModAlreadyLoaded { existing, .. } => {
+                    let (existing_source_id, existing_span) = *existing;
+                    let message = "previously loaded here";
+                    d.label(sources, existing_source_id, existing_span, false, message);
+                }
+                _ => (),
+            }
+        }
+
+        vec![d]
+    }
+}
+
+/// Write the given diagnostics as JSON to `out`, one diagnostic per line.
+pub fn write_json_diagnostics<'a, O, I>(mut out: O, diagnostics: I) -> io::Result<()>
+where
+    O: io::Write,
+    I: IntoIterator<Item = &'a JsonDiagnostic>,
+{
+    for diagnostic in diagnostics {
+        serde_json::to_writer(&mut out, diagnostic)?;
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
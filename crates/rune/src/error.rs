@@ -2,9 +2,10 @@ use crate::ast;
 use crate::ast::Kind;
 use crate::unit_builder::UnitBuilderError;
 use crate::SourceId;
-use runestick::{CompileMeta, Item, Span};
+use runestick::{CompileMeta, CompileMetaSignature, Item, Span};
 use std::io;
 use std::path::PathBuf;
+use std::time::Duration;
 use thiserror::Error;
 
 /// A compile result.
@@ -18,6 +19,13 @@ pub enum ConfigurationError {
         /// The unsupported option.
         option: String,
     },
+    /// Tried to configure the compiler with an option that has an invalid
+    /// value.
+    #[error("invalid value for option `{option}`")]
+    InvalidOptionValue {
+        /// The option with the invalid value.
+        option: String,
+    },
 }
 
 /// Error when parsing.
@@ -267,8 +275,8 @@ pub enum ParseError {
         /// Span containing the bad byte literal.
         span: Span,
     },
-    /// We tried to parse a unicode escape in a byte sequence.
-    #[error("unicode escapes are not supported as a byte or byte string")]
+    /// We tried to parse a non-ASCII unicode escape in a byte literal.
+    #[error("unicode escapes in byte literals may only be used with characters in the range [\\u{{0}}-\\u{{7f}}]")]
     UnicodeEscapeNotSupported {
         /// Where the bad escape is.
         span: Span,
@@ -301,6 +309,13 @@ pub enum ParseError {
         /// Where the bad escape is.
         span: Span,
     },
+    /// Error raised when parsing the frontmatter of a source.
+    #[error("{error}")]
+    FrontmatterError {
+        /// Source error.
+        #[from]
+        error: runestick::FrontmatterError,
+    },
     /// When we encounter an invalid template literal.
     #[error("invalid template literal")]
     InvalidTemplateLiteral {
@@ -308,7 +323,7 @@ pub enum ParseError {
         span: Span,
     },
     /// When we encounter an unescaped closing brace `}`.
-    #[error("closing braces must be escaped inside of templates with `\\}}`")]
+    #[error("closing braces must be escaped inside of templates with `}}}}`")]
     UnexpectedCloseBrace {
         /// Where the brace was encountered.
         span: Span,
@@ -349,6 +364,18 @@ pub enum ParseError {
         /// The delimiter we saw.
         actual: Kind,
     },
+    /// Expected a variable name after `as` in a `with` expression.
+    #[error("expected a variable name to bind the value of `with` to")]
+    ExpectedWithBinding {
+        /// Span of the expression we saw instead.
+        span: Span,
+    },
+    /// Attributes are not supported on the items of an impl block.
+    #[error("attributes are not supported on items in impl blocks")]
+    UnsupportedImplAttribute {
+        /// Span of the attribute.
+        span: Span,
+    },
 }
 
 impl ParseError {
@@ -395,12 +422,72 @@ impl ParseError {
             Self::BadByteEscape { span, .. } => span,
             Self::InvalidTemplateLiteral { span, .. } => span,
             Self::UnexpectedCloseBrace { span, .. } => span,
+            Self::FrontmatterError { error } => error.span(),
             Self::UnsupportedFieldAccess { span, .. } => span,
             Self::ExpectedFunctionArgument { span, .. } => span,
             Self::ExpectedDeclUseImportComponent { span, .. } => span,
             Self::UnsupportedAsyncExpr { span, .. } => span,
             Self::ExpectedMacroDelimiter { span, .. } => span,
             Self::ExpectedMacroCloseDelimiter { span, .. } => span,
+            Self::ExpectedWithBinding { span, .. } => span,
+            Self::UnsupportedImplAttribute { span, .. } => span,
+        }
+    }
+
+    /// Get the stable error code for the parse error, like `E0001`.
+    ///
+    /// Use [explain][crate::explain] to get an extended explanation of it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::UnexpectedEof { .. } => "E0001",
+            Self::ExpectedEof { .. } => "E0002",
+            Self::ExpectedDecl { .. } => "E0003",
+            Self::ExpectedDeclUseImportComponent { .. } => "E0004",
+            Self::ExpectedStringEscape { .. } => "E0005",
+            Self::UnterminatedStrLit { .. } => "E0006",
+            Self::UnterminatedCharLit { .. } => "E0007",
+            Self::UnterminatedByteLit { .. } => "E0008",
+            Self::ExpectedCharClose { .. } => "E0009",
+            Self::ExpectedByteClose { .. } => "E0010",
+            Self::ExpectedTemplateClose { .. } => "E0011",
+            Self::ExpectedCharEscape { .. } => "E0012",
+            Self::TokenMismatch { .. } => "E0013",
+            Self::ExpectedPatError { .. } => "E0014",
+            Self::ExpectedExpr { .. } => "E0015",
+            Self::ExpectedEnumVariant { .. } => "E0016",
+            Self::ExpectedLoop { .. } => "E0017",
+            Self::ExpectedBlockExpr { .. } => "E0018",
+            Self::UnexpectedChar { .. } => "E0019",
+            Self::ExpectedNumber { .. } => "E0020",
+            Self::ExpectedByte { .. } => "E0021",
+            Self::ExpectedChar { .. } => "E0022",
+            Self::ExpectedString { .. } => "E0023",
+            Self::ExpectedOperator { .. } => "E0024",
+            Self::ExpectedBool { .. } => "E0025",
+            Self::ExpectedLitObjectKey { .. } => "E0026",
+            Self::ExpectedUnaryOperator { .. } => "E0027",
+            Self::PrecedenceGroupRequired { .. } => "E0028",
+            Self::BadSlice { .. } => "E0029",
+            Self::BadEscapeSequence { .. } => "E0030",
+            Self::BadNumberLiteral { .. } => "E0031",
+            Self::BadNumberOutOfBounds { .. } => "E0032",
+            Self::BadCharLiteral { .. } => "E0033",
+            Self::BadByteLiteral { .. } => "E0034",
+            Self::UnicodeEscapeNotSupported { .. } => "E0035",
+            Self::BadUnicodeEscape { .. } => "E0036",
+            Self::UnsupportedUnicodeByteEscape { .. } => "E0037",
+            Self::UnsupportedByteEscape { .. } => "E0038",
+            Self::BadByteEscape { .. } => "E0039",
+            Self::FrontmatterError { .. } => "E0040",
+            Self::InvalidTemplateLiteral { .. } => "E0041",
+            Self::UnexpectedCloseBrace { .. } => "E0042",
+            Self::UnsupportedFieldAccess { .. } => "E0043",
+            Self::ExpectedFunctionArgument { .. } => "E0044",
+            Self::UnsupportedAsyncExpr { .. } => "E0045",
+            Self::ExpectedMacroDelimiter { .. } => "E0046",
+            Self::ExpectedMacroCloseDelimiter { .. } => "E0047",
+            Self::ExpectedWithBinding { .. } => "E0048",
+            Self::UnsupportedImplAttribute { .. } => "E0049",
         }
     }
 }
@@ -501,6 +588,130 @@ pub enum CompileError {
         /// Source error.
         error: runestick::Error,
     },
+    /// An error raised in code produced by a macro expansion.
+    ///
+    /// The error carries the span of the macro invocation, so that the
+    /// expansion can be reported alongside the error itself.
+    #[error("{error}")]
+    Expansion {
+        /// The span of the macro invocation.
+        span: Span,
+        /// The item of the macro which was invoked.
+        item: Item,
+        /// The error raised in the expanded code.
+        error: Box<CompileError>,
+    },
+    /// A macro expansion was nested deeper than the configured limit.
+    #[error("macro expansion exceeded the depth limit of {limit}")]
+    MacroDepthLimit {
+        /// The span of the macro invocation.
+        span: Span,
+        /// The configured limit.
+        limit: usize,
+    },
+    /// A macro expansion produced more tokens than the configured limit.
+    #[error("macro expansion produced {tokens} tokens, exceeding the limit of {limit}")]
+    MacroTokenLimit {
+        /// The span of the macro invocation.
+        span: Span,
+        /// The number of tokens produced.
+        tokens: usize,
+        /// The configured limit.
+        limit: usize,
+    },
+    /// A macro expansion took longer than the configured limit.
+    #[error("macro expansion took {elapsed:?}, exceeding the limit of {limit:?}")]
+    MacroTimeLimit {
+        /// The span of the macro invocation.
+        span: Span,
+        /// The time the expansion took.
+        elapsed: Duration,
+        /// The configured limit.
+        limit: Duration,
+    },
+    /// Tried to use an attribute which isn't supported.
+    #[error("unsupported attribute")]
+    UnsupportedAttribute {
+        /// The span of the attribute.
+        span: Span,
+    },
+    /// Tried to derive something which isn't supported, or for a declaration
+    /// which the derive doesn't support.
+    #[error("unsupported derive")]
+    UnsupportedDerive {
+        /// The span of the derive.
+        span: Span,
+    },
+    /// A malformed `cfg` condition.
+    #[error("unsupported `cfg` condition")]
+    UnsupportedCfg {
+        /// The span of the condition.
+        span: Span,
+    },
+    /// Tried to read a compile-time variable which isn't defined.
+    #[error("compile-time variable `{name}` is not defined")]
+    MissingDefine {
+        /// The span where the variable was read.
+        span: Span,
+        /// The name of the variable.
+        name: String,
+    },
+    /// Tried to call an instance function which is provided by more than one
+    /// extension in scope.
+    #[error("instance function `{name}` is provided by more than one extension in scope")]
+    AmbiguousExtensionFunction {
+        /// The span of the call.
+        span: Span,
+        /// The name of the instance function.
+        name: String,
+        /// The extensions which provide the instance function.
+        extensions: Vec<Item>,
+    },
+    /// Two variants of an enum have the same discriminant.
+    #[error("discriminant `{value}` is already used by another variant")]
+    DuplicateDiscriminant {
+        /// The span of the variant with the duplicate discriminant.
+        span: Span,
+        /// The span of the variant which already uses the discriminant.
+        existing: Span,
+        /// The duplicate discriminant.
+        value: i64,
+    },
+    /// A discriminant is not supported, either because it's declared on a
+    /// variant with fields, it isn't an integer, or it overflows.
+    #[error("discriminants must be integers, and are only supported on enums without fields")]
+    UnsupportedDiscriminant {
+        /// The span of the discriminant.
+        span: Span,
+    },
+    /// A lifecycle hook like `__init__` is declared with arguments, or is not
+    /// a plain function.
+    #[error(
+        "lifecycle hooks must be functions without arguments, which are not async or generators"
+    )]
+    UnsupportedLifecycleHook {
+        /// The span of the hook.
+        span: Span,
+    },
+    /// A finalizer declared through `impl Drop` is not a plain `drop(self)`
+    /// function on a struct.
+    #[error("finalizers must be declared as `fn drop(self)` on a struct, and can't be async or generators")]
+    UnsupportedFinalizer {
+        /// The span of the finalizer.
+        span: Span,
+    },
+    /// A `defer` is used somewhere other than as a statement in a block.
+    #[error("`defer` can only be used as a statement in a block")]
+    UnsupportedDefer {
+        /// The span of the `defer`.
+        span: Span,
+    },
+    /// A deferred block tries to exit the block it is deferred in.
+    #[error("can't return from a deferred block")]
+    ReturnInDefer {
+        /// The span of the `return` or `?` expression.
+        span: Span,
+    },
     /// Error for missing local variables.
     #[error("missing variable `{name}`")]
     MissingLocal {
@@ -537,6 +748,12 @@ pub enum CompileError {
         /// Where the wildcard import is.
         span: Span,
     },
+    /// Unsupported group of imports in use.
+    #[error("group of imports not supported in this position")]
+    UnsupportedUseGroup {
+        /// Where the group of imports is.
+        span: Span,
+    },
     /// Tried to use a meta as an async block for which it is not supported.
     #[error("`{meta}` is not a supported async block")]
     UnsupportedAsyncBlock {
@@ -620,6 +837,18 @@ pub enum CompileError {
         /// The related item.
         item: Item,
     },
+    /// Computed keys are used in a type literal.
+    #[error("computed keys are only supported in anonymous objects")]
+    UnsupportedComputedKey {
+        /// The span of the computed key.
+        span: Span,
+    },
+    /// Struct update syntax is used in an object literal which isn't a struct.
+    #[error("struct update syntax is only supported for structs")]
+    UnsupportedStructUpdate {
+        /// The span of the base instance.
+        span: Span,
+    },
     /// When we encounter an expression that cannot be assigned to.
     #[error("cannot assign to expression")]
     UnsupportedAssignExpr {
@@ -671,6 +900,55 @@ pub enum CompileError {
         /// The actual number of arguments.
         actual: usize,
     },
+    /// A function with a known signature is called with the wrong number of
+    /// arguments.
+    #[error("wrong number of arguments `{actual}` to `{signature}`, expected `{expected}`")]
+    CallArgumentCount {
+        /// The span of the call.
+        span: Span,
+        /// The signature of the called function.
+        signature: CompileMetaSignature,
+        /// The expected number of arguments.
+        expected: usize,
+        /// The actual number of arguments.
+        actual: usize,
+    },
+    /// A statement is used at the top level of a module which isn't the root
+    /// of the script.
+    #[error("top-level statements are only supported in the root of a script")]
+    UnsupportedTopLevelStatement {
+        /// The span of the statement.
+        span: Span,
+    },
+    /// A script with top-level statements also declares a `main` function.
+    #[error("top-level statements can't be used in a script which declares `main`")]
+    ScriptMainConflict {
+        /// The span of the first top-level statement.
+        span: Span,
+        /// The span of the declared `main` function.
+        main: Span,
+    },
+    /// The initializer of a static depends on the static itself.
+    #[error("the initializer of static `{item}` depends on itself")]
+    StaticCycle {
+        /// The span of the reference which completes the cycle.
+        span: Span,
+        /// The item of the static.
+        item: Item,
+    },
+    /// The initializer of a static yields.
+    #[error("static initializers can't yield")]
+    UnsupportedStaticInitializer {
+        /// The span of the static.
+        span: Span,
+    },
+    /// Tried to memoize something which isn't a function returning its value
+    /// immediately.
+    #[error("only functions which aren't async or generators can be memoized")]
+    UnsupportedMemoize {
+        /// The span of the `#[memoize]` attribute.
+        span: Span,
+    },
     /// A meta item that is not supported in the given pattern position.
     #[error("`{meta}` is not supported in a pattern like this")]
     UnsupportedMetaPattern {
@@ -705,6 +983,23 @@ pub enum CompileError {
         /// The span of the illegal break.
         span: Span,
     },
+    /// Error raised when trying to use a continue outside of a loop.
+    #[error("continue expressions can only be used inside of a loop")]
+    ContinueOutsideOfLoop {
+        /// The span of the illegal continue.
+        span: Span,
+    },
+    /// Error raised when a script implements an instance function which is
+    /// already provided natively for the same type.
+    #[error("instance function `{name}` conflicts with a native function for `{item}`")]
+    InstanceFunctionConflict {
+        /// The span of the conflicting function name.
+        span: Span,
+        /// The type the function is implemented for.
+        item: Item,
+        /// The name of the conflicting function.
+        name: String,
+    },
     /// An error raised when attempting to return locally created references
     /// from a function.
     #[error("cannot return locally created references")]
@@ -812,11 +1107,27 @@ impl CompileError {
             Self::VariableConflict { span, .. } => span,
             Self::MissingMacro { span, .. } => span,
             Self::CallMacroError { span, .. } => span,
+            Self::Expansion { ref error, .. } => error.span(),
+            Self::MacroDepthLimit { span, .. } => span,
+            Self::MacroTokenLimit { span, .. } => span,
+            Self::MacroTimeLimit { span, .. } => span,
+            Self::UnsupportedAttribute { span, .. } => span,
+            Self::UnsupportedDerive { span, .. } => span,
+            Self::UnsupportedCfg { span, .. } => span,
+            Self::MissingDefine { span, .. } => span,
+            Self::AmbiguousExtensionFunction { span, .. } => span,
+            Self::DuplicateDiscriminant { span, .. } => span,
+            Self::UnsupportedDiscriminant { span, .. } => span,
+            Self::UnsupportedLifecycleHook { span, .. } => span,
+            Self::UnsupportedFinalizer { span, .. } => span,
+            Self::UnsupportedDefer { span, .. } => span,
+            Self::ReturnInDefer { span, .. } => span,
             Self::MissingLocal { span, .. } => span,
             Self::MissingType { span, .. } => span,
             Self::MissingModule { span, .. } => span,
             Self::MissingLabel { span, .. } => span,
             Self::UnsupportedWildcard { span, .. } => span,
+            Self::UnsupportedUseGroup { span, .. } => span,
             Self::UnsupportedRef { span, .. } => span,
             Self::UnsupportedAwait { span, .. } => span,
             Self::UnsupportedAsyncBlock { span, .. } => span,
@@ -832,16 +1143,26 @@ impl CompileError {
             Self::UnsupportedSelectPattern { span, .. } => span,
             Self::UnsupportedFieldAccess { span, .. } => span,
             Self::UnsupportedArgumentCount { span, .. } => span,
+            Self::CallArgumentCount { span, .. } => span,
+            Self::UnsupportedTopLevelStatement { span, .. } => span,
+            Self::ScriptMainConflict { span, .. } => span,
+            Self::StaticCycle { span, .. } => span,
+            Self::UnsupportedStaticInitializer { span, .. } => span,
+            Self::UnsupportedMemoize { span, .. } => span,
             Self::UnsupportedMetaPattern { span, .. } => span,
             Self::UnsupportedMetaClosure { span, .. } => span,
             Self::UnsupportedPattern { span, .. } => span,
             Self::UnsupportedBinding { span, .. } => span,
             Self::BreakOutsideOfLoop { span, .. } => span,
+            Self::ContinueOutsideOfLoop { span, .. } => span,
+            Self::InstanceFunctionConflict { span, .. } => span,
             Self::ReturnLocalReferences { span, .. } => span,
             Self::MatchFloatInPattern { span, .. } => span,
             Self::DuplicateObjectKey { span, .. } => span,
             Self::LitObjectMissingField { span, .. } => span,
             Self::LitObjectNotField { span, .. } => span,
+            Self::UnsupportedComputedKey { span, .. } => span,
+            Self::UnsupportedStructUpdate { span, .. } => span,
             Self::MissingFunction { span, .. } => span,
             Self::YieldOutsideFunction { span, .. } => span,
             Self::AwaitOutsideFunction { span, .. } => span,
@@ -851,4 +1172,109 @@ impl CompileError {
             Self::UnsupportedFileMod { span, .. } => span,
         }
     }
+
+    /// Get the stable error code for the error, like `E0100`.
+    ///
+    /// Use [explain][crate::explain] to get an extended explanation of it.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Internal { .. } => "E0100",
+            Self::Experimental { .. } => "E0101",
+            Self::ModNotFound { .. } => "E0102",
+            Self::ModFileError { .. } => "E0103",
+            Self::ModAlreadyLoaded { .. } => "E0104",
+            Self::UnitBuilderError { .. } => "E0105",
+            Self::ParseError { error } => error.code(),
+            Self::ItemConflict { .. } => "E0106",
+            Self::VariableConflict { .. } => "E0107",
+            Self::MissingMacro { .. } => "E0108",
+            Self::CallMacroError { .. } => "E0109",
+            Self::Expansion { error, .. } => error.code(),
+            Self::MissingLocal { .. } => "E0110",
+            Self::MissingType { .. } => "E0111",
+            Self::MissingModule { .. } => "E0112",
+            Self::MissingLabel { .. } => "E0113",
+            Self::UnsupportedWildcard { .. } => "E0114",
+            Self::UnsupportedAsyncBlock { .. } => "E0115",
+            Self::UnsupportedInstanceFunction { .. } => "E0116",
+            Self::UnsupportedValue { .. } => "E0117",
+            Self::UnsupportedType { .. } => "E0118",
+            Self::UnsupportedSelf { .. } => "E0119",
+            Self::UnsupportedUnaryOp { .. } => "E0120",
+            Self::UnsupportedBinaryOp { .. } => "E0121",
+            Self::UnsupportedLitObject { .. } => "E0122",
+            Self::LitObjectMissingField { .. } => "E0123",
+            Self::LitObjectNotField { .. } => "E0124",
+            Self::UnsupportedComputedKey { .. } => "E0125",
+            Self::UnsupportedAssignExpr { .. } => "E0126",
+            Self::UnsupportedAssignBinOp { .. } => "E0127",
+            Self::UnsupportedRef { .. } => "E0128",
+            Self::UnsupportedAwait { .. } => "E0129",
+            Self::UnsupportedSelectPattern { .. } => "E0130",
+            Self::UnsupportedFieldAccess { .. } => "E0131",
+            Self::UnsupportedArgumentCount { .. } => "E0132",
+            Self::UnsupportedMetaPattern { .. } => "E0133",
+            Self::UnsupportedMetaClosure { .. } => "E0134",
+            Self::UnsupportedPattern { .. } => "E0135",
+            Self::UnsupportedBinding { .. } => "E0136",
+            Self::BreakOutsideOfLoop { .. } => "E0137",
+            Self::ReturnLocalReferences { .. } => "E0138",
+            Self::MatchFloatInPattern { .. } => "E0139",
+            Self::DuplicateObjectKey { .. } => "E0140",
+            Self::MissingFunction { .. } => "E0141",
+            Self::YieldOutsideFunction { .. } => "E0142",
+            Self::AwaitOutsideFunction { .. } => "E0143",
+            Self::InstanceFunctionOutsideImpl { .. } => "E0144",
+            Self::MissingPreludeModule { .. } => "E0145",
+            Self::UnsupportedAsyncExpr { .. } => "E0146",
+            Self::UnsupportedFileMod { .. } => "E0147",
+            Self::MacroDepthLimit { .. } => "E0148",
+            Self::MacroTokenLimit { .. } => "E0149",
+            Self::MacroTimeLimit { .. } => "E0150",
+            Self::UnsupportedAttribute { .. } => "E0151",
+            Self::UnsupportedCfg { .. } => "E0152",
+            Self::MissingDefine { .. } => "E0153",
+            Self::AmbiguousExtensionFunction { .. } => "E0154",
+            Self::DuplicateDiscriminant { .. } => "E0155",
+            Self::UnsupportedDiscriminant { .. } => "E0156",
+            Self::UnsupportedLifecycleHook { .. } => "E0157",
+            Self::UnsupportedFinalizer { .. } => "E0158",
+            Self::UnsupportedDefer { .. } => "E0159",
+            Self::ReturnInDefer { .. } => "E0160",
+            Self::UnsupportedStructUpdate { .. } => "E0161",
+            Self::UnsupportedDerive { .. } => "E0162",
+            Self::CallArgumentCount { .. } => "E0163",
+            Self::UnsupportedTopLevelStatement { .. } => "E0164",
+            Self::ScriptMainConflict { .. } => "E0165",
+            Self::StaticCycle { .. } => "E0166",
+            Self::UnsupportedStaticInitializer { .. } => "E0167",
+            Self::UnsupportedMemoize { .. } => "E0168",
+            Self::UnsupportedUseGroup { .. } => "E0169",
+            Self::ContinueOutsideOfLoop { .. } => "E0170",
+            Self::InstanceFunctionConflict { .. } => "E0171",
+        }
+    }
+
+    /// Get the error at the root of any macro expansions it was raised in.
+    pub fn root(&self) -> &CompileError {
+        match self {
+            Self::Expansion { error, .. } => error.root(),
+            error => error,
+        }
+    }
+
+    /// Iterate over the macro invocations which the error was raised in, as
+    /// the span and item of each invocation, from the outermost to the
+    /// innermost.
+    pub fn expansions(&self) -> impl Iterator<Item = (Span, &Item)> {
+        let mut current = Some(self);
+
+        std::iter::from_fn(move || match current? {
+            Self::Expansion { span, item, error } => {
+                current = Some(&**error);
+                Some((*span, item))
+            }
+            _ => None,
+        })
+    }
 }
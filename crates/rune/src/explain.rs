@@ -0,0 +1,1582 @@
+//! Extended explanations for error codes.
+
+/// Get the extended explanation of the given error code, like `E0001`.
+///
+/// Error codes are reported by [ParseError::code][crate::ParseError::code]
+/// and [CompileError::code][crate::CompileError::code].
+///
+/// # Examples
+///
+/// ```rust
+/// let explanation = rune::explain("E0015").unwrap();
+/// assert!(explanation.starts_with("Expected the start of an expression"));
+///
+/// assert!(rune::explain("E9999").is_none());
+/// ```
+pub fn explain(code: &str) -> Option<&'static str> {
+    let index = EXPLANATIONS.binary_search_by(|(c, _)| c.cmp(&code)).ok()?;
+    Some(EXPLANATIONS[index].1)
+}
+
+/// Explanations sorted by error code.
+const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "E0001",
+        r#"The end of the file was reached while something was still expected.
+
+This usually happens when a delimiter is left unclosed.
+
+Erroneous code example:
+
+    fn main() {
+        let a = [1, 2, 3;
+    }
+
+Close all delimiters which have been opened.
+"#,
+    ),
+    (
+        "E0002",
+        r#"Expected the end of the file, but more input was found.
+
+This happens when something follows a complete item that can't be parsed as
+another item, like a stray closing brace.
+
+Erroneous code example:
+
+    fn main() {
+        42
+    }}
+
+Remove the trailing input.
+"#,
+    ),
+    (
+        "E0003",
+        r#"Expected a declaration.
+
+Only declarations like `fn`, `mod`, `struct`, `enum`, `impl`, and `use` are
+allowed at the top level of a file.
+
+Erroneous code example:
+
+    let a = 42;
+
+Move the code into a function:
+
+    fn main() {
+        let a = 42;
+    }
+"#,
+    ),
+    (
+        "E0004",
+        r#"Expected an import component in a `use` declaration.
+
+Each component of a path being imported must be an identifier or a
+wildcard `*`.
+
+Erroneous code example:
+
+    use std::42;
+
+Correct the import:
+
+    use std::string;
+"#,
+    ),
+    (
+        "E0005",
+        r#"A backslash in a string literal was not followed by an escape.
+
+Erroneous code example:
+
+    fn main() {
+        "hello\
+    }
+
+Escape the backslash itself with `\\`.
+"#,
+    ),
+    (
+        "E0006",
+        r#"A string literal is missing its closing quote.
+
+Erroneous code example:
+
+    fn main() {
+        let s = "hello;
+    }
+
+Add the closing `"`.
+"#,
+    ),
+    (
+        "E0007",
+        r#"A character literal is missing its closing quote.
+
+Erroneous code example:
+
+    fn main() {
+        let c = 'a
+    }
+
+Add the closing `'`.
+"#,
+    ),
+    (
+        "E0008",
+        r#"A byte literal is missing its closing quote.
+
+Erroneous code example:
+
+    fn main() {
+        let b = b'a
+    }
+
+Add the closing `'`.
+"#,
+    ),
+    (
+        "E0009",
+        r#"A character literal contains more than one character.
+
+Erroneous code example:
+
+    fn main() {
+        let c = 'ab';
+    }
+
+Use a string literal like `"ab"` to hold more than one character.
+"#,
+    ),
+    (
+        "E0010",
+        r#"A byte literal contains more than one byte.
+
+Erroneous code example:
+
+    fn main() {
+        let b = b'ab';
+    }
+
+Use a byte string literal like `b"ab"` to hold more than one byte.
+"#,
+    ),
+    (
+        "E0011",
+        r#"A template string is missing its closing backtick.
+
+Erroneous code example:
+
+    fn main() {
+        let s = `hello {name};
+    }
+
+Add the closing backtick.
+"#,
+    ),
+    (
+        "E0012",
+        r#"A backslash in a character literal was not followed by an escape.
+
+Erroneous code example:
+
+    fn main() {
+        let c = '\
+    }
+
+Use `'\\'` for a literal backslash.
+"#,
+    ),
+    (
+        "E0013",
+        r#"A different token than the one required was found.
+
+Erroneous code example:
+
+    fn main() {
+        let a 42;
+    }
+
+Insert the expected token, in this case `=`.
+"#,
+    ),
+    (
+        "E0014",
+        r#"Expected the start of a pattern.
+
+Patterns are used in `let` bindings, `match` branches and `select` branches.
+
+Erroneous code example:
+
+    fn main() {
+        match 42 {
+            + => 1,
+        }
+    }
+
+Use a valid pattern, like a literal, a path, or `_`.
+"#,
+    ),
+    (
+        "E0015",
+        r#"Expected the start of an expression.
+
+Erroneous code example:
+
+    fn main() {
+        let a = 1 +;
+    }
+
+Provide the missing expression:
+
+    fn main() {
+        let a = 1 + 2;
+    }
+"#,
+    ),
+    (
+        "E0016",
+        r#"Expected an enum variant.
+
+Erroneous code example:
+
+    enum Color { 42 }
+
+Variants must be identifiers:
+
+    enum Color { Red, Green }
+"#,
+    ),
+    (
+        "E0017",
+        r#"Expected a loop after a label.
+
+Labels can only be attached to `loop`, `while`, and `for` expressions.
+
+Erroneous code example:
+
+    fn main() {
+        'outer: 42
+    }
+"#,
+    ),
+    (
+        "E0018",
+        r#"Expected a block expression.
+
+Erroneous code example:
+
+    fn main() {
+        async 42
+    }
+
+Use a block:
+
+    fn main() {
+        async { 42 }
+    }
+"#,
+    ),
+    (
+        "E0019",
+        r#"Encountered a character which is not part of the language.
+
+Erroneous code example:
+
+    fn main() {
+        let a = 1 $ 2;
+    }
+"#,
+    ),
+    (
+        "E0020",
+        r#"Expected a number literal.
+
+This is raised by macros and other code which parses a number literal
+specifically.
+"#,
+    ),
+    (
+        "E0021",
+        r#"Expected a byte literal.
+
+This is raised by macros and other code which parses a byte literal
+specifically.
+"#,
+    ),
+    (
+        "E0022",
+        r#"Expected a character literal.
+
+This is raised by macros and other code which parses a character literal
+specifically.
+"#,
+    ),
+    (
+        "E0023",
+        r#"Expected a string literal.
+
+This is raised by macros and other code which parses a string literal
+specifically.
+"#,
+    ),
+    (
+        "E0024",
+        r#"Expected a binary operator, like `+` or `==`.
+
+This is raised by macros and other code which parses an operator
+specifically.
+"#,
+    ),
+    (
+        "E0025",
+        r#"Expected the boolean literal `true` or `false`.
+
+This is raised by macros and other code which parses a boolean specifically.
+"#,
+    ),
+    (
+        "E0026",
+        r#"Expected the key of an object, which is either a string or an
+identifier.
+
+Erroneous code example:
+
+    fn main() {
+        let o = #{ 42: "answer" };
+    }
+
+Use a string key:
+
+    fn main() {
+        let o = #{ "42": "answer" };
+    }
+"#,
+    ),
+    (
+        "E0027",
+        r#"Expected a unary operator, like `!`.
+
+This is raised by macros and other code which parses a unary operator
+specifically.
+"#,
+    ),
+    (
+        "E0028",
+        r#"An expression requires parenthesis to determine the precedence of its
+operators.
+
+Erroneous code example:
+
+    fn main() {
+        let a = 1 == 2 == 3;
+    }
+
+Add parenthesis to make the order of evaluation explicit:
+
+    fn main() {
+        let a = (1 == 2) == 3;
+    }
+"#,
+    ),
+    (
+        "E0029",
+        r#"The compiler tried to read a span which is not part of the source.
+
+This indicates a bug in the compiler or a macro which produced tokens with
+bad spans.
+"#,
+    ),
+    (
+        "E0030",
+        r#"An unknown escape sequence was used.
+
+Erroneous code example:
+
+    fn main() {
+        let s = "\q";
+    }
+
+Supported escapes are `\n`, `\r`, `\t`, `\0`, `\\`, `\'`, `\"`, `\x7f` and
+`\u{1F600}`.
+"#,
+    ),
+    (
+        "E0031",
+        r#"A number literal is not valid.
+
+Erroneous code example:
+
+    fn main() {
+        let n = 0x;
+    }
+"#,
+    ),
+    (
+        "E0032",
+        r#"A number literal doesn't fit in its type.
+
+Integers must be in the range `-9223372036854775808` to
+`9223372036854775807`, and floats must be finite.
+
+Erroneous code example:
+
+    fn main() {
+        let n = 9223372036854775808;
+    }
+"#,
+    ),
+    (
+        "E0033",
+        r#"A character literal is not valid.
+
+Erroneous code example:
+
+    fn main() {
+        let c = '';
+    }
+
+A character literal must contain exactly one character.
+"#,
+    ),
+    (
+        "E0034",
+        r#"A byte literal is not valid.
+
+Erroneous code example:
+
+    fn main() {
+        let b = b'';
+    }
+
+A byte literal must contain exactly one ASCII character or escape.
+"#,
+    ),
+    (
+        "E0035",
+        r#"A unicode escape was used in a byte literal for a character outside
+of the ASCII range.
+
+Erroneous code example:
+
+    fn main() {
+        let b = b'\u{e4}';
+    }
+
+Use a byte escape like `b'\xe4'` instead.
+"#,
+    ),
+    (
+        "E0036",
+        r#"A unicode escape is not valid.
+
+A unicode escape must be written like `\u{7f}`, with at most six hexadecimal
+digits, and must name a valid unicode character.
+
+Erroneous code example:
+
+    fn main() {
+        let c = '\u{d800}';
+    }
+"#,
+    ),
+    (
+        "E0037",
+        r#"A byte escape outside of the ASCII range was used in a character or
+string literal.
+
+Erroneous code example:
+
+    fn main() {
+        let c = '\xff';
+    }
+
+Use a unicode escape like `'\u{ff}'` instead.
+"#,
+    ),
+    (
+        "E0038",
+        r#"An escape is not supported in byte literals.
+
+Erroneous code example:
+
+    fn main() {
+        let b = b'\u{1F600}';
+    }
+
+Byte literals only support ASCII characters and byte escapes like `\x7f`.
+"#,
+    ),
+    (
+        "E0039",
+        r#"A byte escape is not valid.
+
+A byte escape must be written with exactly two hexadecimal digits, like
+`\x7f`.
+
+Erroneous code example:
+
+    fn main() {
+        let b = b'\xg0';
+    }
+"#,
+    ),
+    (
+        "E0040",
+        r#"The frontmatter at the start of the script is malformed.
+
+Frontmatter must be closed with a line containing `---`, and each line in it
+must be an entry like `key: value`.
+
+Erroneous code example:
+
+    ---
+    name hello
+    ---
+    fn main() {}
+
+Add the missing colon:
+
+    ---
+    name: hello
+    ---
+    fn main() {}
+"#,
+    ),
+    (
+        "E0041",
+        r#"A template literal is not valid.
+
+This usually happens when an expansion in the template isn't closed.
+
+Erroneous code example:
+
+    fn main() {
+        `hello {name`
+    }
+"#,
+    ),
+    (
+        "E0042",
+        r#"A closing brace in a template string was not escaped.
+
+Erroneous code example:
+
+    fn main() {
+        `a closing brace: }`
+    }
+
+Double the brace to escape it:
+
+    fn main() {
+        `a closing brace: }}`
+    }
+"#,
+    ),
+    (
+        "E0043",
+        r#"A field access is not supported.
+
+Fields can only be accessed with identifiers or tuple indexes.
+
+Erroneous code example:
+
+    fn main() {
+        let a = (1, 2);
+        a."0"
+    }
+"#,
+    ),
+    (
+        "E0044",
+        r#"An expression is not supported as the argument of a function or a
+closure.
+
+Arguments can only be identifiers, `_`, or `self`.
+
+Erroneous code example:
+
+    fn foo(42) {
+    }
+"#,
+    ),
+    (
+        "E0045",
+        r#"An expression is not supported as an async expression.
+
+Only blocks and closures can be async.
+
+Erroneous code example:
+
+    fn main() {
+        async 1 + 2
+    }
+
+Use an async block:
+
+    fn main() {
+        async { 1 + 2 }
+    }
+"#,
+    ),
+    (
+        "E0046",
+        r#"Expected the delimiter of a macro call.
+
+The arguments of a macro must be delimited by `(`, `[`, or `{`.
+
+Erroneous code example:
+
+    fn main() {
+        println! "hello";
+    }
+"#,
+    ),
+    (
+        "E0047",
+        r#"The closing delimiter of a macro call doesn't match the opening one.
+
+Erroneous code example:
+
+    fn main() {
+        println!("hello"];
+    }
+"#,
+    ),
+    (
+        "E0048",
+        r#"Expected a variable name after `as` in a `with` expression.
+
+Erroneous code example:
+
+    fn main() {
+        with open("file.txt") as (a, b) {
+        }
+    }
+"#,
+    ),
+    (
+        "E0049",
+        r#"An attribute is used on an item in an impl block, which is not supported.
+Functions which need attributes like `#[memoize]` can be declared outside of
+the impl block.
+
+Erroneous code example:
+
+    struct Foo;
+
+    impl Foo {
+        #[memoize]
+        fn get(self) {
+            42
+        }
+    }
+"#,
+    ),
+    (
+        "E0100",
+        r#"An internal invariant of the compiler was broken.
+
+This is a bug in the compiler, please report it together with the code that
+caused it.
+"#,
+    ),
+    (
+        "E0101",
+        r#"An experimental feature was used without being enabled.
+
+Experimental features can change or be removed, so they have to be enabled
+through the compiler options before they can be used.
+"#,
+    ),
+    (
+        "E0102",
+        r#"The file of a module could not be found.
+
+A module declared like `mod foo;` is loaded from `foo.rn` or `foo/mod.rn`
+next to the file declaring it.
+
+Erroneous code example:
+
+    mod missing;
+"#,
+    ),
+    (
+        "E0103",
+        r#"The file of a module could not be read.
+
+The underlying I/O error is included in the error message.
+"#,
+    ),
+    (
+        "E0104",
+        r#"A module has already been loaded.
+
+Every file can only be loaded as a module once.
+"#,
+    ),
+    (
+        "E0105",
+        r#"The unit could not be constructed.
+
+This happens when conflicting items are registered in the unit, like two
+functions with the same name.
+
+Erroneous code example:
+
+    fn foo() {}
+    fn foo() {}
+"#,
+    ),
+    (
+        "E0106",
+        r#"An item conflicts with an existing item with the same name.
+
+Erroneous code example:
+
+    struct Foo;
+    enum Foo {}
+
+Rename one of the items.
+"#,
+    ),
+    (
+        "E0107",
+        r#"A variable conflicts with another variable.
+
+Erroneous code example:
+
+    fn foo(a, a) {
+    }
+
+Rename one of the variables.
+"#,
+    ),
+    (
+        "E0108",
+        r#"A macro could not be found.
+
+Macros are provided by native modules which need to be installed in the
+context used to compile the script.
+
+Erroneous code example:
+
+    fn main() {
+        missing!()
+    }
+"#,
+    ),
+    (
+        "E0109",
+        r#"A macro raised an error while it was being expanded.
+
+The error raised by the macro is included in the error message.
+"#,
+    ),
+    (
+        "E0110",
+        r#"A variable is used which has not been declared.
+
+Erroneous code example:
+
+    fn main() {
+        a + 1
+    }
+
+Declare the variable first:
+
+    fn main() {
+        let a = 1;
+        a + 1
+    }
+"#,
+    ),
+    (
+        "E0111",
+        r#"A type could not be found.
+
+Erroneous code example:
+
+    fn main() {
+        42 is Missing
+    }
+"#,
+    ),
+    (
+        "E0112",
+        r#"A module could not be found.
+
+Erroneous code example:
+
+    use missing::*;
+"#,
+    ),
+    (
+        "E0113",
+        r#"A label is used which is not in scope.
+
+Erroneous code example:
+
+    fn main() {
+        loop {
+            break 'outer;
+        }
+    }
+
+Declare the label on an enclosing loop:
+
+    fn main() {
+        'outer: loop {
+            break 'outer;
+        }
+    }
+"#,
+    ),
+    (
+        "E0114",
+        r#"A wildcard import is not supported in this position.
+
+Wildcards can only be used as the last component of an import.
+
+Erroneous code example:
+
+    use std::*::string;
+"#,
+    ),
+    (
+        "E0115",
+        r#"An item is not supported as an async block.
+"#,
+    ),
+    (
+        "E0116",
+        r#"Instance functions cannot be declared for the given type.
+
+Instance functions can only be declared in `impl` blocks for types declared
+in the script.
+"#,
+    ),
+    (
+        "E0117",
+        r#"An item cannot be used as a value.
+
+Erroneous code example:
+
+    mod foo {}
+
+    fn main() {
+        let a = foo;
+    }
+"#,
+    ),
+    (
+        "E0118",
+        r#"An item cannot be used as a type.
+
+Erroneous code example:
+
+    fn foo() {}
+
+    fn main() {
+        42 is foo
+    }
+"#,
+    ),
+    (
+        "E0119",
+        r#"`self` is used outside of an instance function.
+
+Erroneous code example:
+
+    fn foo() {
+        self
+    }
+
+Declare `self` as the first argument of a function in an `impl` block.
+"#,
+    ),
+    (
+        "E0120",
+        r#"A unary operator is not supported for the given expression.
+"#,
+    ),
+    (
+        "E0121",
+        r#"A binary operator is not supported for the given expression.
+"#,
+    ),
+    (
+        "E0122",
+        r#"A type used in an object literal is not an object.
+
+Erroneous code example:
+
+    struct Foo(a, b);
+
+    fn main() {
+        Foo { a: 1, b: 2 }
+    }
+
+Use the tuple constructor instead:
+
+    struct Foo(a, b);
+
+    fn main() {
+        Foo(1, 2)
+    }
+"#,
+    ),
+    (
+        "E0123",
+        r#"A field is missing when constructing a struct or variant.
+
+Erroneous code example:
+
+    struct Foo { a, b }
+
+    fn main() {
+        Foo { a: 1 }
+    }
+
+Assign all fields:
+
+    struct Foo { a, b }
+
+    fn main() {
+        Foo { a: 1, b: 2 }
+    }
+"#,
+    ),
+    (
+        "E0124",
+        r#"A field is assigned which is not declared in the struct or variant.
+
+Erroneous code example:
+
+    struct Foo { a }
+
+    fn main() {
+        Foo { a: 1, b: 2 }
+    }
+"#,
+    ),
+    (
+        "E0125",
+        r#"Computed keys are used in an object literal with a type.
+
+Computed keys like `[key]: value` are only supported in anonymous objects,
+since the fields of a type are known when it is declared.
+
+Erroneous code example:
+
+    struct Foo { a }
+
+    fn main() {
+        Foo { ["a"]: 1 }
+    }
+"#,
+    ),
+    (
+        "E0126",
+        r#"The left-hand side of an assignment cannot be assigned to.
+
+Erroneous code example:
+
+    fn main() {
+        1 = 2;
+    }
+
+Only variables, fields, and indexes can be assigned to.
+"#,
+    ),
+    (
+        "E0127",
+        r#"An operator is not supported in an assignment.
+"#,
+    ),
+    (
+        "E0128",
+        r#"A reference cannot be taken of the expression.
+
+References can only be taken of variables.
+
+Erroneous code example:
+
+    fn main() {
+        &42
+    }
+"#,
+    ),
+    (
+        "E0129",
+        r#"`.await` is not supported in this location.
+"#,
+    ),
+    (
+        "E0130",
+        r#"A pattern is not supported in a `select` branch.
+
+Branches in a `select` can only bind the result of a future to a variable.
+
+Erroneous code example:
+
+    async fn main() {
+        select {
+            [a] = future => a,
+        }
+    }
+"#,
+    ),
+    (
+        "E0131",
+        r#"A field access is not supported for the given expression.
+"#,
+    ),
+    (
+        "E0132",
+        r#"The wrong number of arguments is used when constructing or matching
+a tuple struct or variant.
+
+Erroneous code example:
+
+    struct Foo(a, b);
+
+    fn main() {
+        Foo(1)
+    }
+"#,
+    ),
+    (
+        "E0133",
+        r#"An item is not supported in a pattern like this.
+
+Erroneous code example:
+
+    struct Foo { a }
+
+    fn main() {
+        match 42 {
+            Foo(a) => a,
+            _ => 0,
+        }
+    }
+
+Use an object pattern like `Foo { a }` instead.
+"#,
+    ),
+    (
+        "E0134",
+        r#"An item is not supported as a closure.
+"#,
+    ),
+    (
+        "E0135",
+        r#"An expression is not supported in a pattern.
+"#,
+    ),
+    (
+        "E0136",
+        r#"A pattern is not a valid binding.
+"#,
+    ),
+    (
+        "E0137",
+        r#"A `break` is used outside of a loop.
+
+Erroneous code example:
+
+    fn main() {
+        break;
+    }
+"#,
+    ),
+    (
+        "E0138",
+        r#"References created in a block are returned from it.
+
+Values referenced in a block don't outlive it, so references to them cannot
+be returned.
+
+Erroneous code example:
+
+    fn main() {
+        let a = {
+            let b = 42;
+            &b
+        };
+    }
+"#,
+    ),
+    (
+        "E0139",
+        r#"Floating point numbers cannot be used in patterns.
+
+Erroneous code example:
+
+    fn main() {
+        match 1.0 {
+            1.0 => true,
+            _ => false,
+        }
+    }
+
+Compare the value in a condition instead.
+"#,
+    ),
+    (
+        "E0140",
+        r#"A key is defined more than once in an object literal.
+
+Erroneous code example:
+
+    fn main() {
+        #{ a: 1, a: 2 }
+    }
+"#,
+    ),
+    (
+        "E0141",
+        r#"An item is called which is not a function.
+
+Erroneous code example:
+
+    mod foo {}
+
+    fn main() {
+        foo()
+    }
+"#,
+    ),
+    (
+        "E0142",
+        r#"`yield` is used outside of a function or closure.
+"#,
+    ),
+    (
+        "E0143",
+        r#"`.await` is used outside of an async function or closure.
+
+Erroneous code example:
+
+    fn main() {
+        future.await
+    }
+
+Declare the function as `async`:
+
+    async fn main() {
+        future.await
+    }
+"#,
+    ),
+    (
+        "E0144",
+        r#"A function taking `self` is declared outside of an `impl` block.
+
+Erroneous code example:
+
+    fn foo(self) {
+    }
+
+Declare it in an `impl` block:
+
+    struct Foo;
+
+    impl Foo {
+        fn foo(self) {
+        }
+    }
+"#,
+    ),
+    (
+        "E0145",
+        r#"A module imported in the prelude doesn't exist.
+
+This happens when the context used to compile the script doesn't have all of
+the default modules installed.
+"#,
+    ),
+    (
+        "E0146",
+        r#"An expression is not supported as an async expression.
+
+Only blocks and closures can be async.
+"#,
+    ),
+    (
+        "E0147",
+        r#"A module is loaded from a file while compiling an in-memory source.
+
+Sources which aren't loaded from a path can't load modules from the
+filesystem, since there is no directory to resolve them from.
+
+Erroneous code example:
+
+    mod foo;
+"#,
+    ),
+    (
+        "E0148",
+        r#"Macro expansions are nested deeper than the configured limit.
+
+This usually happens when a macro expands into an invocation of itself. The
+limit defaults to 64 and can be configured with `-O macro-depth=<n>`.
+"#,
+    ),
+    (
+        "E0149",
+        r#"A macro expansion produced more tokens than the configured limit.
+
+The limit defaults to 100000 tokens per expansion and can be configured with
+`-O macro-tokens=<n>`.
+"#,
+    ),
+    (
+        "E0150",
+        r#"A macro expansion took longer than the configured limit.
+
+Expansions are only timed if a limit is configured with `-O macro-time=<ms>`.
+Since native macros can't be interrupted, the limit is checked once the macro
+has returned.
+"#,
+    ),
+    (
+        "E0151",
+        r#"An attribute is not supported.
+
+Only `#[cfg(...)]` and `#[cfg_attr(...)]` are supported, and only on items.
+
+Erroneous code example:
+
+    #[inline]
+    fn foo() {}
+"#,
+    ),
+    (
+        "E0152",
+        r#"A `cfg` condition is malformed.
+
+A condition is either the name of a compile-time variable, which holds if the
+variable is defined, a comparison like `target = "web"`, or a combination of
+conditions with `all(...)`, `any(...)`, and `not(...)`.
+
+Erroneous code example:
+
+    #[cfg(either(web, native))]
+    fn foo() {}
+"#,
+    ),
+    (
+        "E0153",
+        r#"A compile-time variable read with `env!` is not defined.
+
+Variables are defined by the host through `Options::define`. Use
+`option_env!` to read a variable which might not be defined.
+
+Erroneous code example:
+
+    fn main() {
+        env!("UNDEFINED")
+    }
+"#,
+    ),
+    (
+        "E0154",
+        r#"An instance function is provided by more than one extension in scope.
+
+Extensions are declared with `impl Name for Type` and are in scope in the
+module they're declared in, or where they are imported with `use`. Only import
+one of the extensions, or call the function by its full path, like
+`a::VecExt::sum(values)`.
+
+Erroneous code example:
+
+    use a::VecExt;
+    use b::OtherVecExt;
+
+    fn main() {
+        [1, 2, 3].sum()
+    }
+"#,
+    ),
+    (
+        "E0155",
+        r#"Two variants of an enum have the same discriminant.
+
+Variants without an explicit discriminant use the discriminant of the previous
+variant plus one, starting at zero.
+
+Erroneous code example:
+
+    enum Color {
+        Red = 1,
+        Green = 0,
+        Blue,
+    }
+"#,
+    ),
+    (
+        "E0156",
+        r#"A discriminant is not supported.
+
+Discriminants can only be declared on enums where no variant has fields, and
+must be integers.
+
+Erroneous code example:
+
+    enum Shape {
+        Circle(radius) = 1,
+        Square(side) = 2,
+    }
+"#,
+    ),
+    (
+        "E0157",
+        r#"A lifecycle hook is not supported.
+
+Functions named `__init__` and `__shutdown__` are run when a unit is installed
+and torn down, and must be plain functions without arguments.
+
+Erroneous code example:
+
+    async fn __init__(config) {
+        config.load().await;
+    }
+"#,
+    ),
+    (
+        "E0158",
+        r#"A finalizer is not supported.
+
+Finalizers are declared in an `impl Drop` block for a struct, and must be a
+single plain function named `drop` which only takes `self`.
+
+Erroneous code example:
+
+    struct File { fd }
+
+    impl Drop for File {
+        async fn close(self, reason) {
+            os::close(self.fd).await;
+        }
+    }
+"#,
+    ),
+    (
+        "E0159",
+        r#"A `defer` is used somewhere other than as a statement in a block.
+
+Deferred blocks run when the block they are declared in is exited, so they
+can't be used as a value.
+
+Erroneous code example:
+
+    fn main() {
+        let cleanup = defer { println("done"); };
+    }
+"#,
+    ),
+    (
+        "E0160",
+        r#"A deferred block uses `return` or `?`.
+
+Deferred blocks run while the block they are declared in is being exited, and
+can't exit the function themselves.
+
+Erroneous code example:
+
+    fn main() {
+        defer {
+            return;
+        }
+    }
+"#,
+    ),
+    (
+        "E0161",
+        r#"Struct update syntax like `..other` is used in an object literal which
+isn't a struct.
+
+The remaining fields can only be copied from another instance of a struct,
+since the fields of anonymous objects and enum variants aren't copied.
+
+Erroneous code example:
+
+    fn main() {
+        let other = #{a: 1, b: 2};
+        #{a: 3, ..other}
+    }
+"#,
+    ),
+    (
+        "E0162",
+        r#"A derive is used which doesn't exist, or which doesn't support the item it's
+used on.
+
+`#[derive(Builder)]` is only supported on structs with named fields, and none
+of their fields can be named `build`.
+
+Erroneous code example:
+
+    #[derive(Builder)]
+    struct Point(x, y);
+"#,
+    ),
+    (
+        "E0163",
+        r#"A function is called with the wrong number of arguments.
+
+The number of arguments is checked when compiling calls to functions declared
+in the script or in a native module. Calls through a function value, like a
+variable holding a function, are checked when the call happens instead.
+
+Erroneous code example:
+
+    fn connect(host, port) {
+        // ..
+    }
+
+    fn main() {
+        connect("localhost")
+    }
+"#,
+    ),
+    (
+        "E0164",
+        r#"A statement is used at the top level of a module which isn't the root of the
+script.
+
+Top-level statements are compiled into the implicit `main` function of a
+script, so they are only supported in the root source. Modules can only contain
+declarations.
+
+Erroneous code example:
+
+    mod greeting {
+        dbg("hello");
+    }
+"#,
+    ),
+    (
+        "E0165",
+        r#"A script with top-level statements also declares a `main` function.
+
+Top-level statements are compiled into the implicit `main` function of a
+script, which conflicts with an explicitly declared one. Either move the
+statements into `main`, or remove it.
+
+Erroneous code example:
+
+    fn main() {
+        dbg("hello");
+    }
+
+    dbg("world");
+"#,
+    ),
+    (
+        "E0166",
+        r#"The initializer of a static depends on the static itself, either directly or
+through the initializers of other statics.
+
+Statics are initialized on first use, so a static which depends on itself can't
+be initialized.
+
+Erroneous code example:
+
+    let A = B + 1;
+    let B = A + 1;
+"#,
+    ),
+    (
+        "E0167",
+        r#"The initializer of a static yields.
+
+Statics are initialized on first use, which has to produce their value
+immediately.
+
+Erroneous code example:
+
+    let VALUES = {
+        yield 1;
+    };
+"#,
+    ),
+    (
+        "E0168",
+        r#"`#[memoize]` is used on something other than a function which returns its
+value immediately.
+
+The value returned by an async function or a generator is constructed anew by
+every call, so it can't be cached.
+
+Erroneous code example:
+
+    #[memoize]
+    async fn fetch(url) {
+        http::get(url).await
+    }
+"#,
+    ),
+    (
+        "E0169",
+        r#"A group of imports is not supported in this position.
+
+Groups can only be used as the last component of an import.
+
+Erroneous code example:
+
+    use std::{string, vec}::new;
+"#,
+    ),
+    (
+        "E0170",
+        r#"A `continue` is used outside of a loop.
+
+Erroneous code example:
+
+    fn main() {
+        continue;
+    }
+"#,
+    ),
+    (
+        "E0171",
+        r#"A script implements an instance function which is already provided
+natively for the same type. The script function would silently replace the
+native one.
+
+Erroneous code example:
+
+    use std::bytes::Bytes;
+
+    impl Bytes {
+        fn len(self) {
+            99
+        }
+    }
+"#,
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::{explain, EXPLANATIONS};
+
+    #[test]
+    fn test_explanations_sorted() {
+        for w in EXPLANATIONS.windows(2) {
+            assert!(w[0].0 < w[1].0, "{} is not sorted", w[1].0);
+        }
+    }
+
+    #[test]
+    fn test_explanations_complete() {
+        for n in (1..=47).chain(100..=158) {
+            let code = format!("E{:04}", n);
+            assert!(explain(&code).is_some(), "missing explanation for {}", code);
+        }
+    }
+}
@@ -3,12 +3,18 @@ use crate::collections::HashMap;
 use crate::error::{CompileError, CompileResult};
 use crate::index_scopes::IndexScopes;
 use crate::items::Items;
-use crate::query::{Build, BuildEntry, Function, Indexed, IndexedEntry, InstanceFunction, Query};
+use crate::macros::BuiltinMacro;
+use crate::memoize::Memoize;
+use crate::options::Options;
+use crate::query::{
+    fn_signature, Build, BuildEntry, Const, FromDiscriminant, Function, Indexed, IndexedEntry,
+    InstanceFunction, Query, Script, Static,
+};
 use crate::sources::Sources;
 use crate::traits::Resolve as _;
 use crate::warning::Warnings;
 use crate::{SourceId, UnitBuilder};
-use runestick::{Call, CompileMeta, Context, Hash, Item, Source, Span, Type};
+use runestick::{Call, CompileMeta, CompileMetaSignature, Context, Hash, Item, Source, Span, Type};
 use std::collections::VecDeque;
 use std::sync::Arc;
 
@@ -18,10 +24,25 @@ pub(crate) struct Macro {
     pub(crate) source: Arc<Source>,
     pub(crate) source_id: usize,
     pub(crate) scopes: IndexScopes,
-    pub(crate) impl_items: Vec<Item>,
+    pub(crate) impl_items: Vec<ImplItem>,
     pub(crate) kind: MacroKind,
 }
 
+/// An impl block being indexed.
+#[derive(Debug, Clone)]
+pub(crate) struct ImplItem {
+    /// The item of the impl block.
+    pub(crate) item: Item,
+    /// The item of the module the impl block is declared in.
+    pub(crate) base: Item,
+    /// The path of the implemented type.
+    pub(crate) path: ast::Path,
+    /// If the impl block declares an extension, like `impl VecExt for Vec`.
+    pub(crate) extension: bool,
+    /// If the impl block declares a finalizer, like `impl Drop for File`.
+    pub(crate) finalizer: bool,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum MacroKind {
     Expr,
@@ -42,59 +63,86 @@ impl Import {
         context: &Context,
         unit: &mut UnitBuilder,
     ) -> Result<(), CompileError> {
-        let Self {
-            item,
-            ast: decl_use,
-            source,
-            source_id,
-        } = self;
+        let mut name = Item::empty();
+        name.push(self.ast.first.resolve(&*self.source)?);
 
-        let span = decl_use.span();
+        let components = self.ast.rest.iter().map(|(_, c)| c).collect::<Vec<_>>();
+        self.process_components(context, unit, name, self.ast.use_.span(), &components)
+    }
 
-        let mut name = Item::empty();
-        let first = decl_use.first.resolve(&*source)?;
-        name.push(first);
+    /// Process the remaining components of an import, where `name` is the
+    /// path leading up to them.
+    ///
+    /// Errors are reported from `start` up until the component they concern,
+    /// where `start` is the start of the path the components are a part of.
+    fn process_components(
+        &self,
+        context: &Context,
+        unit: &mut UnitBuilder,
+        mut name: Item,
+        start: Span,
+        components: &[&ast::DeclUseComponent],
+    ) -> Result<(), CompileError> {
+        let (last, components) = match components.split_last() {
+            Some(split) => split,
+            None => return Ok(()),
+        };
 
-        let mut it = decl_use.rest.iter();
-        let last = it.next_back();
+        let span = start.join(last.span());
 
-        for (_, c) in it {
+        for c in components {
             match c {
                 ast::DeclUseComponent::Wildcard(t) => {
                     return Err(CompileError::UnsupportedWildcard { span: t.span() });
                 }
+                ast::DeclUseComponent::Group(group) => {
+                    return Err(CompileError::UnsupportedUseGroup { span: group.span() });
+                }
                 ast::DeclUseComponent::Ident(ident) => {
-                    name.push(ident.resolve(&*source)?);
+                    name.push(ident.resolve(&*self.source)?);
                 }
             }
         }
 
-        if let Some((_, c)) = last {
-            match c {
-                ast::DeclUseComponent::Wildcard(..) => {
-                    let mut new_names = Vec::new();
+        match last {
+            ast::DeclUseComponent::Wildcard(..) => {
+                let mut new_names = Vec::new();
 
-                    if !context.contains_prefix(&name) && !unit.contains_prefix(&name) {
-                        return Err(CompileError::MissingModule { span, item: name });
-                    }
+                if !context.contains_prefix(&name) && !unit.contains_prefix(&name) {
+                    return Err(CompileError::MissingModule { span, item: name });
+                }
 
-                    let iter = context
-                        .iter_components(&name)
-                        .chain(unit.iter_components(&name));
+                let iter = context
+                    .iter_components(&name)
+                    .chain(unit.iter_components(&name));
 
-                    for c in iter {
-                        let mut name = name.clone();
-                        name.push(c);
-                        new_names.push(name);
-                    }
+                for c in iter {
+                    let mut name = name.clone();
+                    name.push(c);
+                    new_names.push(name);
+                }
 
-                    for name in new_names {
-                        unit.new_import(item.clone(), &name, span, source_id)?;
-                    }
+                for name in new_names {
+                    unit.new_import(self.item.clone(), &name, span, self.source_id)?;
                 }
-                ast::DeclUseComponent::Ident(ident) => {
-                    name.push(ident.resolve(&*source)?);
-                    unit.new_import(item.clone(), &name, span, source_id)?;
+            }
+            ast::DeclUseComponent::Ident(ident) => {
+                name.push(ident.resolve(&*self.source)?);
+                unit.new_import(self.item.clone(), &name, span, self.source_id)?;
+            }
+            ast::DeclUseComponent::Group(group) => {
+                for (path, _) in &group.items {
+                    let components = std::iter::once(&path.first)
+                        .chain(path.rest.iter().map(|(_, c)| c))
+                        .collect::<Vec<_>>();
+
+                    self.process_components(
+                        context,
+                        unit,
+                        name.clone(),
+                        path.first.span(),
+                        &components,
+                    )?;
                 }
             }
         }
@@ -104,6 +152,8 @@ impl Import {
 }
 
 pub(crate) struct Indexer<'a> {
+    /// Compiler options.
+    pub(crate) options: &'a Options,
     pub(crate) loaded: &'a mut HashMap<Item, (SourceId, Span)>,
     pub(crate) query: &'a mut Query,
     /// Imports to process.
@@ -119,7 +169,7 @@ pub(crate) struct Indexer<'a> {
     pub(crate) items: Items,
     pub(crate) scopes: IndexScopes,
     /// Set if we are inside of an impl block.
-    pub(crate) impl_items: Vec<Item>,
+    pub(crate) impl_items: Vec<ImplItem>,
 }
 
 impl<'a> Indexer<'a> {
@@ -138,6 +188,81 @@ impl<'a> Indexer<'a> {
         }
     }
 
+    /// Index the top-level statements of a script, which are compiled into
+    /// its implicit `main` function.
+    ///
+    /// The statics declared in the script are initialized in the order they
+    /// are declared in relative to the statements.
+    fn index_script(
+        &mut self,
+        stmts: &[(ast::Expr, Option<ast::SemiColon>)],
+        statics: Vec<(Item, Span)>,
+        main: Option<Span>,
+    ) -> CompileResult<()> {
+        let first = stmts
+            .first()
+            .map(|(expr, _)| expr.span())
+            .unwrap_or_default();
+        let last = stmts
+            .last()
+            .map(|(expr, _)| expr.span())
+            .unwrap_or_default();
+        let span = first.join(last);
+
+        if !self.items.item().is_empty() {
+            return Err(CompileError::UnsupportedTopLevelStatement { span: first });
+        }
+
+        if let Some(main) = main {
+            return Err(CompileError::ScriptMainConflict { span: first, main });
+        }
+
+        let _guard = self.items.push_name("main");
+        let item = self.items.item();
+        let guard = self.scopes.push_function(false);
+
+        {
+            let _guard = self.items.push_block();
+            let _guard = self.scopes.push_scope();
+
+            for (expr, _) in stmts {
+                self.index(expr)?;
+            }
+        }
+
+        let f = guard.into_function(span)?;
+        let call = Self::call(f.generator, f.is_async);
+
+        self.query.queue.push_back(BuildEntry {
+            item: item.clone(),
+            build: Build::Script(Script {
+                stmts: stmts.to_vec(),
+                statics,
+                span,
+                call,
+            }),
+            source: self.source.clone(),
+            source_id: self.source_id,
+        });
+
+        let signature = CompileMetaSignature {
+            item: item.clone(),
+            args: Vec::new(),
+            declared: None,
+        };
+
+        self.query
+            .unit
+            .borrow_mut()
+            .insert_meta(CompileMeta::Function {
+                value_type: Type::Hash(Hash::type_hash(&item)),
+                signature: Some(signature),
+                item,
+            })?;
+
+        Ok(())
+    }
+
     /// Handle a filesystem module.
     pub(crate) fn handle_file_mod(&mut self, decl_mod: &ast::DeclMod) -> CompileResult<()> {
         let span = decl_mod.span();
@@ -215,7 +340,33 @@ pub(crate) trait Index<T> {
 
 impl Index<ast::DeclFile> for Indexer<'_> {
     fn index(&mut self, decl_file: &ast::DeclFile) -> CompileResult<()> {
-        for (decl, semi_colon) in &decl_file.decls {
+        let mut main = None;
+        let mut statics = Vec::new();
+
+        for (attributes, decl, semi_colon) in &decl_file.decls {
+            if !crate::cfg::is_enabled(attributes, &*self.source, self.options)? {
+                continue;
+            }
+
+            match decl {
+                ast::Decl::DeclFn(decl_fn) if decl_fn.name.resolve(&*self.source)? == "main" => {
+                    main = Some(decl_fn.item_span());
+                }
+                ast::Decl::DeclLet(decl_let) => {
+                    let name = decl_let.name.resolve(&*self.source)?;
+                    statics.push((self.items.item().extended(name), decl_let.span()));
+                }
+                _ => (),
+            }
+
+            let memoize = crate::memoize::options(attributes, decl, &*self.source)?;
+
+            if let Some(derived) = crate::derive::expand(attributes, decl, &*self.source)? {
+                let name = format!("{}#derive{}", self.source.name(), decl.span().start);
+                self.sources
+                    .insert(self.items.item(), Source::new(name, derived));
+            }
+
             if let Some(semi_colon) = semi_colon {
                 if !decl.needs_semi_colon() {
                     self.warnings
@@ -223,7 +374,14 @@ impl Index<ast::DeclFile> for Indexer<'_> {
                 }
             }
 
-            self.index(decl)?;
+            match decl {
+                ast::Decl::DeclFn(decl_fn) => self.index_fn(decl_fn, memoize)?,
+                decl => self.index(decl)?,
+            }
+        }
+
+        if !decl_file.stmts.is_empty() {
+            self.index_script(&decl_file.stmts, statics, main)?;
         }
 
         Ok(())
@@ -232,9 +390,39 @@ impl Index<ast::DeclFile> for Indexer<'_> {
 
 impl Index<ast::DeclFn> for Indexer<'_> {
     fn index(&mut self, decl_fn: &ast::DeclFn) -> CompileResult<()> {
+        self.index_fn(decl_fn, None)
+    }
+}
+
+impl Indexer<'_> {
+    /// Index a function declaration, which is memoized if it has a
+    /// `#[memoize]` attribute.
+    fn index_fn(&mut self, decl_fn: &ast::DeclFn, memoize: Option<Memoize>) -> CompileResult<()> {
         let span = decl_fn.span();
-        let is_toplevel = self.items.is_empty();
-        let _guard = self.items.push_name(decl_fn.name.resolve(&*self.source)?);
+        let is_module = self.items.is_module();
+
+        // NB: functions declared directly in an impl block are associated
+        // with the implemented type.
+        let is_associated = match self.impl_items.last() {
+            Some(impl_item) => impl_item.item == self.items.item(),
+            None => false,
+        };
+
+        let name = decl_fn.name.resolve(&*self.source)?;
+
+        let is_finalizer = match self.impl_items.last() {
+            Some(impl_item) => is_associated && impl_item.finalizer,
+            None => false,
+        };
+
+        let is_drop = name == "drop" && decl_fn.is_instance() && decl_fn.args.items.len() == 1;
+
+        // NB: lifecycle hooks are functions with reserved names declared
+        // directly in a module.
+        let is_shutdown = name == "__shutdown__";
+        let is_hook = (name == "__init__" || is_shutdown) && is_module && !is_associated;
+
+        let _guard = self.items.push_name(name);
 
         let item = self.items.item();
 
@@ -260,9 +448,20 @@ impl Index<ast::DeclFn> for Indexer<'_> {
         let f = guard.into_function(span)?;
         let call = Self::call(f.generator, f.is_async);
 
+        if is_finalizer && (!is_drop || !matches!(call, Call::Immediate)) {
+            return Err(CompileError::UnsupportedFinalizer { span });
+        }
+
+        if let Some(memoize) = &memoize {
+            if !matches!(call, Call::Immediate) || decl_fn.is_instance() || is_hook {
+                return Err(CompileError::UnsupportedMemoize { span: memoize.span });
+            }
+        }
+
         let fun = Function {
             ast: decl_fn.clone(),
             call,
+            memoize,
         };
 
         if decl_fn.is_instance() {
@@ -273,7 +472,11 @@ impl Index<ast::DeclFn> for Indexer<'_> {
 
             let f = InstanceFunction {
                 ast: fun.ast,
-                impl_item: impl_item.clone(),
+                impl_item: impl_item.item.clone(),
+                impl_base: impl_item.base.clone(),
+                impl_path: impl_item.path.clone(),
+                extension: impl_item.extension,
+                finalizer: impl_item.finalizer,
                 instance_span: span,
                 call: fun.call,
             };
@@ -288,14 +491,36 @@ impl Index<ast::DeclFn> for Indexer<'_> {
                 source_id: self.source_id,
             });
 
-            let meta = CompileMeta::Function {
+            let meta = CompileMeta::InstanceFunction {
                 value_type: Type::Hash(Hash::type_hash(&item)),
                 item: item.clone(),
+                signature: Some(fn_signature(&item, decl_fn, &*self.source, self.source_id)?),
             };
 
-            self.query.unit.borrow_mut().insert_meta(meta)?;
-        } else if is_toplevel {
-            // NB: immediately compile all toplevel functions.
+            let mut unit = self.query.unit.borrow_mut();
+
+            if impl_item.extension {
+                let name = decl_fn.name.resolve(&*self.source)?;
+                unit.insert_extension_function(&impl_item.item, name);
+            }
+
+            unit.insert_meta(meta)?;
+        } else if is_module && !is_associated {
+            if is_hook {
+                if !decl_fn.args.items.is_empty() || !matches!(call, Call::Immediate) {
+                    return Err(CompileError::UnsupportedLifecycleHook { span });
+                }
+
+                self.query
+                    .unit
+                    .borrow_mut()
+                    .insert_lifecycle_hook(&item, is_shutdown);
+            }
+
+            let signature = fn_signature(&item, decl_fn, &*self.source, self.source_id)?;
+
+            // NB: immediately compile all functions declared in modules,
+            // since they might be called by the host.
             self.query.queue.push_back(BuildEntry {
                 item: item.clone(),
                 build: Build::Function(fun),
@@ -308,8 +533,19 @@ impl Index<ast::DeclFn> for Indexer<'_> {
                 .borrow_mut()
                 .insert_meta(CompileMeta::Function {
                     value_type: Type::Hash(Hash::type_hash(&item)),
+                    signature: Some(signature),
                     item,
                 })?;
+        } else if is_associated {
+            self.query.index(
+                item,
+                IndexedEntry {
+                    indexed: Indexed::AssociatedFunction(fun),
+                    source: self.source.clone(),
+                    source_id: self.source_id,
+                },
+                span,
+            )?;
         } else {
             // NB: non toplevel functions can be indexed for later construction.
             self.query.index(
@@ -327,6 +563,71 @@ impl Index<ast::DeclFn> for Indexer<'_> {
     }
 }
 
+impl Index<ast::DeclConst> for Indexer<'_> {
+    fn index(&mut self, decl_const: &ast::DeclConst) -> CompileResult<()> {
+        let span = decl_const.span();
+        let _guard = self
+            .items
+            .push_name(decl_const.name.resolve(&*self.source)?);
+
+        let guard = self.scopes.push_function(false);
+        self.index(&decl_const.expr)?;
+        let f = guard.into_function(span)?;
+
+        let c = Const {
+            ast: decl_const.clone(),
+            call: Self::call(f.generator, f.is_async),
+        };
+
+        self.query.index(
+            self.items.item(),
+            IndexedEntry {
+                indexed: Indexed::Const(c),
+                source: self.source.clone(),
+                source_id: self.source_id,
+            },
+            span,
+        )?;
+
+        Ok(())
+    }
+}
+
+impl Index<ast::DeclLet> for Indexer<'_> {
+    fn index(&mut self, decl_let: &ast::DeclLet) -> CompileResult<()> {
+        let span = decl_let.span();
+        let _guard = self.items.push_name(decl_let.name.resolve(&*self.source)?);
+
+        let guard = self.scopes.push_function(false);
+        self.index(&*decl_let.expr)?;
+        let f = guard.into_function(span)?;
+
+        if f.generator {
+            return Err(CompileError::UnsupportedStaticInitializer { span });
+        }
+
+        let item = self.items.item();
+        let slot = self.query.unit.borrow_mut().new_static(&item);
+
+        let s = Static {
+            ast: decl_let.clone(),
+            slot,
+        };
+
+        self.query.index(
+            item,
+            IndexedEntry {
+                indexed: Indexed::Static(s),
+                source: self.source.clone(),
+                source_id: self.source_id,
+            },
+            span,
+        )?;
+
+        Ok(())
+    }
+}
+
 impl Index<ast::ExprBlock> for Indexer<'_> {
     fn index(&mut self, expr_block: &ast::ExprBlock) -> Result<(), CompileError> {
         let span = expr_block.span();
@@ -512,15 +813,25 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::ExprIndexGet(expr_index_get) => {
                 self.index(expr_index_get)?;
             }
+            ast::Expr::ExprRange(expr_range) => {
+                self.index(expr_range)?;
+            }
             ast::Expr::ExprBreak(expr_break) => {
                 self.index(expr_break)?;
             }
+            ast::Expr::ExprContinue(..) => (),
             ast::Expr::ExprYield(expr_yield) => {
                 self.index(expr_yield)?;
             }
             ast::Expr::ExprReturn(expr_return) => {
                 self.index(expr_return)?;
             }
+            ast::Expr::ExprDefer(expr_defer) => {
+                self.index(&*expr_defer.body)?;
+            }
+            ast::Expr::ExprWith(expr_with) => {
+                self.index(expr_with)?;
+            }
             ast::Expr::ExprAwait(expr_await) => {
                 self.index(expr_await)?;
             }
@@ -554,6 +865,18 @@ impl Index<ast::Expr> for Indexer<'_> {
             ast::Expr::ExprCallMacro(expr_call_macro) => {
                 let _guard = self.items.push_macro();
 
+                // NB: built-in macros are compiled directly, but the
+                // expression passed to `dbg!` might contain items which needs
+                // to be indexed.
+                if let Some(builtin) = BuiltinMacro::find(&expr_call_macro.path, &*self.source)? {
+                    if let BuiltinMacro::Dbg = builtin {
+                        let expr = BuiltinMacro::parse_dbg(expr_call_macro)?;
+                        self.index(&expr)?;
+                    }
+
+                    return Ok(());
+                }
+
                 self.macros.push_back(Macro {
                     items: self.items.snapshot(),
                     ast: expr_call_macro.clone(),
@@ -653,7 +976,18 @@ impl Index<ast::Decl> for Indexer<'_> {
                     span,
                 )?;
 
-                for (variant, body, _) in &decl_enum.variants {
+                // NB: variants of enums without fields have discriminants,
+                // which are either declared or one more than the previous
+                // variant.
+                let is_fieldless = decl_enum
+                    .variants
+                    .iter()
+                    .all(|(_, body, ..)| matches!(body, ast::DeclStructBody::EmptyBody(..)));
+
+                let mut next = Some(0i64);
+                let mut discriminants = HashMap::<i64, Span>::new();
+
+                for (variant, body, discriminant, _) in &decl_enum.variants {
                     let _guard = self.items.push_name(variant.resolve(&*self.source)?);
 
                     let span = variant.span();
@@ -666,6 +1000,55 @@ impl Index<ast::Decl> for Indexer<'_> {
                         self.source_id,
                         span,
                     )?;
+
+                    if let Some(discriminant) = discriminant {
+                        let span = discriminant.value.span();
+
+                        next = match discriminant.value.resolve(&*self.source)? {
+                            ast::Number::Integer(n) if is_fieldless => Some(n),
+                            _ => return Err(CompileError::UnsupportedDiscriminant { span }),
+                        };
+                    }
+
+                    if !is_fieldless {
+                        continue;
+                    }
+
+                    let value =
+                        next.ok_or_else(|| CompileError::UnsupportedDiscriminant { span })?;
+
+                    if let Some(existing) = discriminants.insert(value, span) {
+                        return Err(CompileError::DuplicateDiscriminant {
+                            span,
+                            existing,
+                            value,
+                        });
+                    }
+
+                    self.query.unit.borrow_mut().insert_discriminant(
+                        &enum_item,
+                        &self.items.item(),
+                        value,
+                    );
+
+                    next = value.checked_add(1);
+                }
+
+                if is_fieldless && !decl_enum.variants.is_empty() {
+                    let _guard = self.items.push_name("from_int");
+
+                    self.query.index(
+                        self.items.item(),
+                        IndexedEntry {
+                            indexed: Indexed::FromDiscriminant(FromDiscriminant {
+                                enum_item: enum_item.clone(),
+                                span,
+                            }),
+                            source: self.source.clone(),
+                            source_id: self.source_id,
+                        },
+                        span,
+                    )?;
                 }
             }
             ast::Decl::DeclStruct(decl_struct) => {
@@ -684,13 +1067,42 @@ impl Index<ast::Decl> for Indexer<'_> {
                 self.index(decl_fn)?;
             }
             ast::Decl::DeclImpl(decl_impl) => {
+                let base = self.items.item();
                 let mut guards = Vec::new();
 
-                for ident in decl_impl.path.components() {
-                    guards.push(self.items.push_name(ident.resolve(&*self.source)?));
+                let extension = match &decl_impl.extension {
+                    Some((name, _)) => Some(name.resolve(&*self.source)?),
+                    None => None,
+                };
+
+                // NB: `Drop` is reserved for finalizers, which are declared
+                // on the implemented type like any other instance function.
+                let finalizer = extension == Some("Drop");
+
+                // NB: functions in an extension are declared under the name
+                // of the extension, rather than the implemented type.
+                match extension {
+                    Some(name) if !finalizer => {
+                        guards.push(self.items.push_name(name));
+                    }
+                    _ => {
+                        for ident in decl_impl.path.components() {
+                            guards.push(self.items.push_name(ident.resolve(&*self.source)?));
+                        }
+                    }
                 }
 
-                self.impl_items.push(self.items.item());
+                self.impl_items.push(ImplItem {
+                    item: self.items.item(),
+                    base,
+                    path: decl_impl.path.clone(),
+                    extension: extension.is_some() && !finalizer,
+                    finalizer,
+                });
+
+                for decl_const in &decl_impl.consts {
+                    self.index(decl_const)?;
+                }
 
                 for decl_fn in &decl_impl.functions {
                     self.index(decl_fn)?;
@@ -707,6 +1119,9 @@ impl Index<ast::Decl> for Indexer<'_> {
                     self.handle_file_mod(decl_mod)?;
                 }
             }
+            ast::Decl::DeclLet(decl_let) => {
+                self.index(decl_let)?;
+            }
         }
 
         Ok(())
@@ -726,9 +1141,16 @@ impl Index<ast::Path> for Indexer<'_> {
 
 impl Index<ast::ExprWhile> for Indexer<'_> {
     fn index(&mut self, expr_while: &ast::ExprWhile) -> Result<(), CompileError> {
-        let _guard = self.scopes.push_scope();
-        self.index(&expr_while.condition)?;
-        self.index(&*expr_while.body)?;
+        {
+            let _guard = self.scopes.push_scope();
+            self.index(&expr_while.condition)?;
+            self.index(&*expr_while.body)?;
+        }
+
+        if let Some(expr_else) = &expr_while.expr_else {
+            self.index(&*expr_else.block)?;
+        }
+
         Ok(())
     }
 }
@@ -746,9 +1168,36 @@ impl Index<ast::ExprFor> for Indexer<'_> {
         // NB: creating the iterator is evaluated in the parent scope.
         self.index(&*expr_for.iter)?;
 
+        if let Some(await_) = &expr_for.await_ {
+            self.scopes.mark_await(await_.span())?;
+        }
+
+        {
+            let _guard = self.scopes.push_scope();
+            self.index(&expr_for.var)?;
+            self.index(&*expr_for.body)?;
+        }
+
+        if let Some(expr_else) = &expr_for.expr_else {
+            self.index(&*expr_else.block)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<ast::ExprWith> for Indexer<'_> {
+    fn index(&mut self, expr_with: &ast::ExprWith) -> Result<(), CompileError> {
+        // NB: the value to enter is evaluated in the parent scope.
+        self.index(&*expr_with.expr)?;
+
         let _guard = self.scopes.push_scope();
-        self.index(&expr_for.var)?;
-        self.index(&*expr_for.body)?;
+
+        if let Some(binding) = &expr_with.binding {
+            self.index(binding)?;
+        }
+
+        self.index(&*expr_with.body)?;
         Ok(())
     }
 }
@@ -823,6 +1272,20 @@ impl Index<ast::ExprIndexGet> for Indexer<'_> {
     }
 }
 
+impl Index<ast::ExprRange> for Indexer<'_> {
+    fn index(&mut self, expr_range: &ast::ExprRange) -> Result<(), CompileError> {
+        if let Some(from) = &expr_range.from {
+            self.index(&**from)?;
+        }
+
+        if let Some(to) = &expr_range.to {
+            self.index(&**to)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl Index<ast::ExprBreak> for Indexer<'_> {
     fn index(&mut self, expr_break: &ast::ExprBreak) -> Result<(), CompileError> {
         if let Some(expr) = &expr_break.expr {
@@ -831,6 +1294,9 @@ impl Index<ast::ExprBreak> for Indexer<'_> {
                     self.index(&**expr)?;
                 }
                 ast::ExprBreakValue::Label(..) => (),
+                ast::ExprBreakValue::LabelExpr(_, expr) => {
+                    self.index(&**expr)?;
+                }
             }
         }
 
@@ -916,10 +1382,10 @@ impl Index<ast::LitTemplate> for Indexer<'_> {
 
         for c in &template.components {
             match c {
-                ast::TemplateComponent::Expr(expr) => {
+                ast::TemplateComponent::Expr { expr, .. } => {
                     self.index(&**expr)?;
                 }
-                ast::TemplateComponent::String(..) => (),
+                ast::TemplateComponent::String { .. } => (),
             }
         }
 
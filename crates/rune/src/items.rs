@@ -57,9 +57,13 @@ impl Items {
         }
     }
 
-    /// Check if the current path is empty.
-    pub fn is_empty(&self) -> bool {
-        self.path.borrow().is_empty()
+    /// Check if the current path is a module, as opposed to something like
+    /// the body of a function.
+    pub fn is_module(&self) -> bool {
+        self.path
+            .borrow()
+            .iter()
+            .all(|node| matches!(node.component, Component::String(..)))
     }
 
     /// Get the next child id.
@@ -1,9 +1,13 @@
 use crate::ast;
 use crate::ast::utils;
 use crate::error::ParseError;
-use runestick::Span;
+use runestick::{SourceHeader, Span};
 
 /// Lexer for the rune language.
+///
+/// The lexer scans the bytes of the source directly, and only decodes
+/// characters where non-ASCII input is significant, like when skipping
+/// whitespace or scanning character literals.
 #[derive(Debug, Clone)]
 pub struct Lexer<'a> {
     cursor: usize,
@@ -53,67 +57,48 @@ impl<'a> Lexer<'a> {
         Span::point(self.source.len())
     }
 
-    /// Calculate the end span by peeking the next token.
-    fn end_span<I, T>(&self, it: &I) -> usize
-    where
-        I: Iterator<Item = (usize, T)> + Clone,
-    {
-        it.clone()
+    /// Peek the byte at the given offset from the cursor.
+    #[inline]
+    fn peek(&self, n: usize) -> Option<u8> {
+        self.source.as_bytes().get(self.cursor + n).copied()
+    }
+
+    /// Consume the byte at the cursor if it's the given one.
+    #[inline]
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek(0) == Some(b) {
+            self.cursor += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Decode the character at the cursor, which starts with the given byte.
+    #[inline]
+    fn char_at(&self, b: u8) -> char {
+        if b < 0x80 {
+            return b as char;
+        }
+
+        self.source[self.cursor..]
+            .chars()
             .next()
-            .map(|(n, _)| self.cursor + n)
-            .unwrap_or_else(|| self.source.len())
-    }
-
-    fn next_ident<I>(&mut self, it: &mut I, start: usize) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
-        self.cursor = loop {
-            break match it.clone().next() {
-                Some((n, c)) => match c {
-                    'a'..='z' | 'A'..='Z' | '_' | '0'..='9' => {
-                        it.next();
-                        continue;
-                    }
-                    _ => self.cursor + n,
-                },
-                None => self.source.len(),
-            };
-        };
+            .unwrap_or(std::char::REPLACEMENT_CHARACTER)
+    }
 
-        let ident = &self.source[start..self.cursor];
-
-        let kind = match ident {
-            "self" => ast::Kind::Self_,
-            "macro" => ast::Kind::Macro,
-            "fn" => ast::Kind::Fn,
-            "enum" => ast::Kind::Enum,
-            "struct" => ast::Kind::Struct,
-            "let" => ast::Kind::Let,
-            "if" => ast::Kind::If,
-            "match" => ast::Kind::Match,
-            "else" => ast::Kind::Else,
-            "use" => ast::Kind::Use,
-            "while" => ast::Kind::While,
-            "for" => ast::Kind::For,
-            "loop" => ast::Kind::Loop,
-            "in" => ast::Kind::In,
-            "true" => ast::Kind::True,
-            "false" => ast::Kind::False,
-            "is" => ast::Kind::Is,
-            "not" => ast::Kind::Not,
-            "break" => ast::Kind::Break,
-            "yield" => ast::Kind::Yield,
-            "return" => ast::Kind::Return,
-            "await" => ast::Kind::Await,
-            "async" => ast::Kind::Async,
-            "select" => ast::Kind::Select,
-            "default" => ast::Kind::Default,
-            "impl" => ast::Kind::Impl,
-            "mod" => ast::Kind::Mod,
-            _ => ast::Kind::Ident,
-        };
+    /// Advance the cursor past the character at the cursor, if any.
+    #[inline]
+    fn advance_char(&mut self) {
+        if let Some(b) = self.peek(0) {
+            self.cursor += self.char_at(b).len_utf8();
+        }
+    }
 
+    /// Construct a token of the given kind, from the given start to the
+    /// cursor.
+    #[inline]
+    fn token(&self, kind: ast::Kind, start: usize) -> Result<Option<ast::Token>, ParseError> {
         Ok(Some(ast::Token {
             kind,
             span: Span {
@@ -123,86 +108,105 @@ impl<'a> Lexer<'a> {
         }))
     }
 
-    /// Consume a number literal.
-    fn next_number_literal<I>(
+    /// Consume an identifier or a keyword, where the first character has
+    /// already been consumed.
+    fn next_ident(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
+        let bytes = self.source.as_bytes();
+
+        while let Some(b) = bytes.get(self.cursor) {
+            if !is_ident_continue(*b) {
+                break;
+            }
+
+            self.cursor += 1;
+        }
+
+        let kind = keyword(&bytes[start..self.cursor]).unwrap_or(ast::Kind::Ident);
+        self.token(kind, start)
+    }
+
+    /// Consume a number literal, where the first digit has already been
+    /// consumed.
+    fn next_number_literal(
         &mut self,
-        it: &mut I,
-        c: char,
         start: usize,
         is_negative: bool,
-    ) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
-        let mut is_fractional = false;
+    ) -> Result<Option<ast::Token>, ParseError> {
+        let first = self.source.as_bytes()[self.cursor - 1];
 
-        let number = if let ('0', Some((_, m))) = (c, it.clone().next()) {
-            // This loop is useful.
-            #[allow(clippy::never_loop)]
-            loop {
-                let number = match m {
-                    'x' => ast::NumberKind::Hex,
-                    'b' => ast::NumberKind::Binary,
-                    'o' => ast::NumberKind::Octal,
-                    _ => break ast::NumberKind::Decimal,
-                };
-
-                // consume character.
-                it.next();
-                break number;
-            }
-        } else {
-            ast::NumberKind::Decimal
+        let number = match (first, self.peek(0)) {
+            (b'0', Some(b'x')) => ast::NumberKind::Hex,
+            (b'0', Some(b'b')) => ast::NumberKind::Binary,
+            (b'0', Some(b'o')) => ast::NumberKind::Octal,
+            _ => ast::NumberKind::Decimal,
         };
 
-        self.cursor = loop {
-            let (n, c) = match it.next() {
-                Some((n, c)) => (n, c),
-                None => break self.source.len(),
-            };
+        if number != ast::NumberKind::Decimal {
+            self.cursor += 1;
+        }
+
+        let mut is_fractional = false;
+
+        while let Some(b) = self.peek(0) {
+            match b {
+                b'e' | b'E' if number == ast::NumberKind::Decimal => {
+                    self.cursor += 1;
+
+                    // exponents can be signed, like in `1e-9`.
+                    if let Some(b'-') | Some(b'+') = self.peek(0) {
+                        self.cursor += 1;
+                    }
 
-            match c {
-                c if char::is_alphanumeric(c) => (),
-                '.' if !is_fractional => {
                     is_fractional = true;
+                }
+                b'0'..=b'9' | b'a'..=b'z' | b'A'..=b'Z' | b'_' => {
+                    self.cursor += 1;
+                }
+                b'.' if !is_fractional => {
+                    // char immediately following a dot should be numerical,
+                    // otherwise the dot is not part of the number, like in
+                    // ranges such as `0..10`.
+                    match self.source[self.cursor + 1..].chars().next() {
+                        Some(c) if c.is_numeric() => {
+                            self.cursor += 1 + c.len_utf8();
+                        }
+                        _ => break,
+                    }
 
-                    // char immediately following a dot should be numerical.
-                    if !it.next().map(|(_, c)| c.is_numeric()).unwrap_or_default() {
-                        break self.cursor + n;
+                    is_fractional = true;
+                }
+                b if b >= 0x80 => {
+                    let c = self.char_at(b);
+
+                    if !c.is_alphanumeric() {
+                        break;
                     }
+
+                    self.cursor += c.len_utf8();
                 }
-                _ => break self.cursor + n,
+                _ => break,
             }
-        };
+        }
 
-        Ok(Some(ast::Token {
-            kind: ast::Kind::LitNumber {
+        self.token(
+            ast::Kind::LitNumber {
                 is_fractional,
                 is_negative,
                 number,
             },
-            span: Span {
-                start,
-                end: self.cursor,
-            },
-        }))
+            start,
+        )
     }
 
-    /// Consume a string literal.
-    fn next_char_or_label<I>(
-        &mut self,
-        it: &mut I,
-        start: usize,
-    ) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
+    /// Consume a character literal or a label, where the opening `'` has
+    /// already been consumed.
+    fn next_char_or_label(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
         let mut is_label = true;
         let mut char_count = 0;
 
-        self.cursor = loop {
-            let (n, c) = match it.clone().next() {
-                Some(c) => c,
+        loop {
+            let b = match self.peek(0) {
+                Some(b) => b,
                 None => {
                     if is_label {
                         return Err(ParseError::ExpectedCharClose {
@@ -213,77 +217,63 @@ impl<'a> Lexer<'a> {
                         });
                     }
 
-                    break self.source.len();
+                    break;
                 }
             };
 
-            match c {
-                '\\' => {
+            match b {
+                b'\\' => {
                     is_label = false;
-                    it.next();
-                    it.next();
+                    self.cursor += 1;
+                    self.advance_char();
                     char_count += 1;
                 }
-                '\'' => {
+                b'\'' => {
                     is_label = false;
-                    it.next();
-                    break self.end_span(it);
+                    self.cursor += 1;
+                    break;
                 }
                 // components of labels.
-                '0'..='9' | 'a'..='z' => {
-                    it.next();
+                b'0'..=b'9' | b'a'..=b'z' => {
+                    self.cursor += 1;
                     char_count += 1;
                 }
-                c if c.is_control() => {
-                    return Err(ParseError::UnterminatedCharLit {
-                        span: Span {
-                            start,
-                            end: self.cursor + n,
-                        },
-                    });
-                }
-                _ if is_label && char_count > 0 => {
-                    break self.cursor + n;
-                }
-                _ => {
+                b => {
+                    let c = self.char_at(b);
+
+                    if c.is_control() {
+                        return Err(ParseError::UnterminatedCharLit {
+                            span: Span {
+                                start,
+                                end: self.cursor,
+                            },
+                        });
+                    }
+
+                    if is_label && char_count > 0 {
+                        break;
+                    }
+
                     is_label = false;
-                    it.next();
+                    self.cursor += c.len_utf8();
                     char_count += 1;
                 }
             }
-        };
+        }
 
         if is_label {
-            Ok(Some(ast::Token {
-                kind: ast::Kind::Label,
-                span: Span {
-                    start,
-                    end: self.cursor,
-                },
-            }))
+            self.token(ast::Kind::Label, start)
         } else {
-            Ok(Some(ast::Token {
-                kind: ast::Kind::LitChar,
-                span: Span {
-                    start,
-                    end: self.cursor,
-                },
-            }))
+            self.token(ast::Kind::LitChar, start)
         }
     }
 
-    /// Consume a string literal.
-    fn next_lit_byte<I>(
-        &mut self,
-        it: &mut I,
-        start: usize,
-    ) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
-        self.cursor = loop {
-            let (n, c) = match it.clone().next() {
-                Some(c) => c,
+    /// Consume a byte literal, where the opening `b'` has already been
+    /// consumed.
+    fn next_lit_byte(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
+        loop {
+            let b = match self.peek(0) {
+                Some(b) => b,
                 None => {
                     return Err(ParseError::ExpectedByteClose {
                         span: Span {
@@ -294,69 +284,48 @@ impl<'a> Lexer<'a> {
                 }
             };
 
-            match c {
-                '\\' => {
-                    it.next();
-                    it.next();
-                }
-                '\'' => {
-                    it.next();
-                    break self.end_span(it);
+            match b {
+                b'\\' => {
+                    self.cursor += 1;
+                    self.advance_char();
                 }
-                c if c.is_control() => {
-                    return Err(ParseError::UnterminatedByteLit {
-                        span: Span {
-                            start,
-                            end: self.cursor + n,
-                        },
-                    });
+                b'\'' => {
+                    self.cursor += 1;
+                    break;
                 }
-                _ => {
-                    it.next();
+                b => {
+                    let c = self.char_at(b);
+
+                    if c.is_control() {
+                        return Err(ParseError::UnterminatedByteLit {
+                            span: Span {
+                                start,
+                                end: self.cursor,
+                            },
+                        });
+                    }
+
+                    self.cursor += c.len_utf8();
                 }
             }
-        };
+        }
 
-        Ok(Some(ast::Token {
-            kind: ast::Kind::LitByte,
-            span: Span {
-                start,
-                end: self.cursor,
-            },
-        }))
+        self.token(ast::Kind::LitByte, start)
     }
 
-    /// Consume a string literal.
-    fn next_lit_str<I>(
-        &mut self,
-        it: &mut I,
-        start: usize,
-    ) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
+    /// Scan to the end of a string literal, where the opening delimiter has
+    /// already been consumed. Returns if the literal contains any escape
+    /// sequences.
+    ///
+    /// Only the bytes of the string are inspected, since every byte of a
+    /// multi-byte character lies outside of the ASCII range.
+    fn scan_lit_str(&mut self, start: usize, multiline: bool) -> Result<bool, ParseError> {
+        let bytes = self.source.as_bytes();
         let mut escaped = false;
 
-        self.cursor = loop {
-            break match it.next() {
-                Some((_, c)) => match c {
-                    '"' => self.end_span(it),
-                    '\\' => match it.next() {
-                        Some(_) => {
-                            escaped = true;
-                            continue;
-                        }
-                        None => {
-                            return Err(ParseError::ExpectedStringEscape {
-                                span: Span {
-                                    start,
-                                    end: self.source.len(),
-                                },
-                            });
-                        }
-                    },
-                    _ => continue,
-                },
+        loop {
+            let b = match bytes.get(self.cursor) {
+                Some(b) => *b,
                 None => {
                     return Err(ParseError::UnterminatedStrLit {
                         span: Span {
@@ -366,48 +335,92 @@ impl<'a> Lexer<'a> {
                     })
                 }
             };
-        };
 
-        Ok(Some(ast::Token {
-            kind: ast::Kind::LitStr { escaped },
-            span: Span {
-                start,
-                end: self.cursor,
-            },
-        }))
+            self.cursor += 1;
+
+            match b {
+                b'"' if !multiline => return Ok(escaped),
+                b'"' if self.peek(0) == Some(b'"') && self.peek(1) == Some(b'"') => {
+                    self.cursor += 2;
+                    return Ok(escaped);
+                }
+                b'\\' => {
+                    if self.cursor == bytes.len() {
+                        return Err(ParseError::ExpectedStringEscape {
+                            span: Span {
+                                start,
+                                end: self.source.len(),
+                            },
+                        });
+                    }
+
+                    escaped = true;
+                    self.advance_char();
+                }
+                _ => (),
+            }
+        }
     }
 
     /// Consume a string literal.
-    fn next_lit_byte_str<I>(
-        &mut self,
-        it: &mut I,
-        start: usize,
-    ) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
-        let mut escaped = false;
+    fn next_lit_str(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
+        let escaped = self.scan_lit_str(start, false)?;
 
-        self.cursor = loop {
-            break match it.next() {
-                Some((_, c)) => match c {
-                    '"' => self.end_span(it),
-                    '\\' => match it.next() {
-                        Some(_) => {
-                            escaped = true;
-                            continue;
-                        }
-                        None => {
-                            return Err(ParseError::ExpectedStringEscape {
-                                span: Span {
-                                    start,
-                                    end: self.source.len(),
-                                },
-                            });
-                        }
-                    },
-                    _ => continue,
-                },
+        self.token(
+            ast::Kind::LitStr {
+                escaped,
+                kind: ast::StrKind::Normal,
+            },
+            start,
+        )
+    }
+
+    /// Consume a multiline string literal, where the opening `"""` has
+    /// already been consumed.
+    fn next_multiline_lit_str(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
+        let escaped = self.scan_lit_str(start, true)?;
+
+        self.token(
+            ast::Kind::LitStr {
+                escaped,
+                kind: ast::StrKind::Multiline,
+            },
+            start,
+        )
+    }
+
+    /// Consume a raw string literal, where the leading `r` has already been
+    /// consumed.
+    fn next_raw_lit_str(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
+        let bytes = self.source.as_bytes();
+        let mut hashes = 0;
+
+        while self.eat(b'#') {
+            hashes += 1;
+        }
+
+        // NB: the opening quote, as checked by `is_raw_lit_str`.
+        self.cursor += 1;
+
+        loop {
+            match bytes.get(self.cursor) {
+                Some(b'"') => {
+                    self.cursor += 1;
+                    let end = self.cursor + hashes;
+
+                    let closing = bytes
+                        .get(self.cursor..end)
+                        .map(|h| h.iter().all(|b| *b == b'#'))
+                        .unwrap_or_default();
+
+                    if closing {
+                        self.cursor = end;
+                        break;
+                    }
+                }
+                Some(..) => {
+                    self.cursor += 1;
+                }
                 None => {
                     return Err(ParseError::UnterminatedStrLit {
                         span: Span {
@@ -416,54 +429,40 @@ impl<'a> Lexer<'a> {
                         },
                     })
                 }
-            };
-        };
+            }
+        }
 
-        Ok(Some(ast::Token {
-            kind: ast::Kind::LitByteStr { escaped },
-            span: Span {
-                start,
-                end: self.cursor,
+        self.token(
+            ast::Kind::LitStr {
+                escaped: false,
+                kind: ast::StrKind::Raw(hashes),
             },
-        }))
+            start,
+        )
     }
 
-    /// Consume a string literal.
-    fn next_template<I>(
-        &mut self,
-        it: &mut I,
-        start: usize,
-    ) -> Result<Option<ast::Token>, ParseError>
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
+    /// Test if the cursor, positioned after an `r`, is at the start of a raw
+    /// string literal like `"..."` or `#"..."#`.
+    fn is_raw_lit_str(&self) -> bool {
+        let rest = &self.source.as_bytes()[self.cursor..];
+        matches!(rest.iter().find(|b| **b != b'#'), Some(b'"'))
+    }
+
+    /// Consume a byte string literal, where the opening `b"` has already been
+    /// consumed.
+    fn next_lit_byte_str(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
+        let escaped = self.scan_lit_str(start, false)?;
+        self.token(ast::Kind::LitByteStr { escaped }, start)
+    }
+
+    /// Consume a template literal, where the opening backtick has already
+    /// been consumed.
+    fn next_template(&mut self, start: usize) -> Result<Option<ast::Token>, ParseError> {
         let mut escaped = false;
 
-        self.cursor = loop {
-            break match it.next() {
-                Some((n, c)) => match c {
-                    '`' => self.end_span(it),
-                    '{' => {
-                        let span = Span::new(start, n);
-                        utils::template_expr(span, it)?;
-                        continue;
-                    }
-                    '\\' => match it.next() {
-                        Some(_) => {
-                            escaped = true;
-                            continue;
-                        }
-                        None => {
-                            return Err(ParseError::ExpectedTemplateClose {
-                                span: Span {
-                                    start,
-                                    end: self.source.len(),
-                                },
-                            });
-                        }
-                    },
-                    _ => continue,
-                },
+        loop {
+            let b = match self.peek(0) {
+                Some(b) => b,
                 None => {
                     return Err(ParseError::ExpectedTemplateClose {
                         span: Span {
@@ -473,225 +472,231 @@ impl<'a> Lexer<'a> {
                     })
                 }
             };
-        };
 
-        Ok(Some(ast::Token {
-            kind: ast::Kind::LitTemplate { escaped },
-            span: Span {
-                start,
-                end: self.cursor,
-            },
-        }))
-    }
+            self.cursor += 1;
 
-    /// Consume the entire line.
-    fn consume_line<I>(&mut self, it: &mut I)
-    where
-        I: Clone + Iterator<Item = (usize, char)>,
-    {
-        loop {
-            match it.next() {
-                Some((_, '\n')) | None => break,
+            match b {
+                b'`' => break,
+                b'{' => {
+                    if self.eat(b'{') {
+                        escaped = true;
+                        continue;
+                    }
+
+                    let span = Span::new(start, self.source.len());
+                    let mut it = self.source[self.cursor..].char_indices();
+                    utils::template_expr(span, &mut it)?;
+                    self.cursor = self.source.len() - it.as_str().len();
+                }
+                b'}' if self.eat(b'}') => {
+                    escaped = true;
+                }
+                b'\\' => {
+                    if self.cursor == self.source.len() {
+                        return Err(ParseError::ExpectedTemplateClose {
+                            span: Span {
+                                start,
+                                end: self.source.len(),
+                            },
+                        });
+                    }
+
+                    escaped = true;
+                    self.advance_char();
+                }
                 _ => (),
             }
         }
+
+        self.token(ast::Kind::LitTemplate { escaped }, start)
+    }
+
+    /// Consume the entire line.
+    fn consume_line(&mut self) {
+        self.cursor = match self.source[self.cursor..].find('\n') {
+            Some(n) => self.cursor + n + 1,
+            None => self.source.len(),
+        };
     }
 
     /// Consume the next token from the lexer.
     #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> Result<Option<ast::Token>, ParseError> {
-        let mut it = self.source[self.cursor..].char_indices();
+        // NB: skip over any shebang or frontmatter at the start of the source.
+        if self.cursor == 0 {
+            self.cursor = SourceHeader::parse(self.source)?.span.end;
+        }
 
-        'outer: while let Some((start, c)) = it.next() {
-            let start = self.cursor + start;
+        loop {
+            let start = self.cursor;
 
-            if char::is_whitespace(c) {
-                continue;
-            }
+            let b = match self.peek(0) {
+                Some(b) => b,
+                None => return Ok(None),
+            };
 
-            // This loop is useful, at least until it's rewritten.
-            #[allow(clippy::never_loop)]
-            let kind = loop {
-                if let Some(c2) = it.clone().next().map(|(_, c)| c) {
-                    match (c, c2) {
-                        ('+', '=') => {
-                            it.next();
-                            break ast::Kind::PlusEq;
-                        }
-                        ('-', '=') => {
-                            it.next();
-                            break ast::Kind::DashEq;
-                        }
-                        ('*', '=') => {
-                            it.next();
-                            break ast::Kind::StarEq;
-                        }
-                        ('/', '=') => {
-                            it.next();
-                            break ast::Kind::SlashEq;
-                        }
-                        ('%', '=') => {
-                            it.next();
-                            break ast::Kind::PercEq;
-                        }
-                        ('&', '=') => {
-                            it.next();
-                            break ast::Kind::AmpEq;
-                        }
-                        ('^', '=') => {
-                            it.next();
-                            break ast::Kind::CaretEq;
-                        }
-                        ('|', '=') => {
-                            it.next();
-                            break ast::Kind::PipeEq;
-                        }
-                        ('/', '/') => {
-                            self.consume_line(&mut it);
-                            continue 'outer;
-                        }
-                        (':', ':') => {
-                            it.next();
-                            break ast::Kind::ColonColon;
-                        }
-                        ('<', '=') => {
-                            it.next();
-                            break ast::Kind::LtEq;
-                        }
-                        ('>', '=') => {
-                            it.next();
-                            break ast::Kind::GtEq;
-                        }
-                        ('=', '=') => {
-                            it.next();
-                            break ast::Kind::EqEq;
-                        }
-                        ('!', '=') => {
-                            it.next();
-                            break ast::Kind::BangEq;
-                        }
-                        ('&', '&') => {
-                            it.next();
-                            break ast::Kind::AmpAmp;
-                        }
-                        ('|', '|') => {
-                            it.next();
-                            break ast::Kind::PipePipe;
-                        }
-                        ('<', '<') => {
-                            it.next();
-
-                            break if matches!(it.next().clone(), Some((_, '='))) {
-                                it.next();
-                                ast::Kind::LtLtEq
-                            } else {
-                                ast::Kind::LtLt
-                            };
-                        }
-                        ('>', '>') => {
-                            it.next();
-
-                            break if matches!(it.next().clone(), Some((_, '='))) {
-                                it.next();
-                                ast::Kind::GtGtEq
-                            } else {
-                                ast::Kind::GtGt
-                            };
-                        }
-                        ('.', '.') => {
-                            it.next();
-                            break ast::Kind::DotDot;
-                        }
-                        ('=', '>') => {
-                            it.next();
-                            break ast::Kind::Rocket;
-                        }
-                        ('-', c @ '0'..='9') => {
-                            it.next();
-                            return self.next_number_literal(&mut it, c, start, true);
-                        }
-                        ('b', '\'') => {
-                            it.next();
-                            it.next();
-                            return self.next_lit_byte(&mut it, start);
-                        }
-                        ('b', '"') => {
-                            it.next();
-                            it.next();
-                            return self.next_lit_byte_str(&mut it, start);
-                        }
-                        _ => (),
-                    }
-                }
+            self.cursor += 1;
 
-                break match c {
-                    '(' => ast::Kind::Open(ast::Delimiter::Parenthesis),
-                    ')' => ast::Kind::Close(ast::Delimiter::Parenthesis),
-                    '{' => ast::Kind::Open(ast::Delimiter::Brace),
-                    '}' => ast::Kind::Close(ast::Delimiter::Brace),
-                    '[' => ast::Kind::Open(ast::Delimiter::Bracket),
-                    ']' => ast::Kind::Close(ast::Delimiter::Bracket),
-                    '_' => ast::Kind::Underscore,
-                    ',' => ast::Kind::Comma,
-                    ':' => ast::Kind::Colon,
-                    '#' => ast::Kind::Hash,
-                    '.' => ast::Kind::Dot,
-                    ';' => ast::Kind::SemiColon,
-                    '=' => ast::Kind::Eq,
-                    '+' => ast::Kind::Plus,
-                    '-' => ast::Kind::Dash,
-                    '/' => ast::Kind::Div,
-                    '*' => ast::Kind::Star,
-                    '&' => ast::Kind::Amp,
-                    '>' => ast::Kind::Gt,
-                    '<' => ast::Kind::Lt,
-                    '!' => ast::Kind::Bang,
-                    '?' => ast::Kind::QuestionMark,
-                    '|' => ast::Kind::Pipe,
-                    '%' => ast::Kind::Perc,
-                    '^' => ast::Kind::Caret,
-                    'a'..='z' | 'A'..='Z' => {
-                        return self.next_ident(&mut it, start);
-                    }
-                    '0'..='9' => {
-                        return self.next_number_literal(&mut it, c, start, false);
+            let kind = match b {
+                b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c' => continue,
+                b'(' => ast::Kind::Open(ast::Delimiter::Parenthesis),
+                b')' => ast::Kind::Close(ast::Delimiter::Parenthesis),
+                b'{' => ast::Kind::Open(ast::Delimiter::Brace),
+                b'}' => ast::Kind::Close(ast::Delimiter::Brace),
+                b'[' => ast::Kind::Open(ast::Delimiter::Bracket),
+                b']' => ast::Kind::Close(ast::Delimiter::Bracket),
+                b',' => ast::Kind::Comma,
+                b'#' => ast::Kind::Hash,
+                b';' => ast::Kind::SemiColon,
+                b'?' => ast::Kind::QuestionMark,
+                b'b' if self.eat(b'\'') => return self.next_lit_byte(start),
+                b'b' if self.eat(b'"') => return self.next_lit_byte_str(start),
+                b'r' if self.is_raw_lit_str() => return self.next_raw_lit_str(start),
+                b'a'..=b'z' | b'A'..=b'Z' => return self.next_ident(start),
+                b'_' => match self.peek(0) {
+                    Some(b) if is_ident_continue(b) => return self.next_ident(start),
+                    _ => ast::Kind::Underscore,
+                },
+                b'0'..=b'9' => return self.next_number_literal(start, false),
+                b'"' => {
+                    if self.peek(0) == Some(b'"') && self.peek(1) == Some(b'"') {
+                        self.cursor += 2;
+                        return self.next_multiline_lit_str(start);
                     }
-                    '"' => {
-                        return self.next_lit_str(&mut it, start);
+
+                    return self.next_lit_str(start);
+                }
+                b'`' => return self.next_template(start),
+                b'\'' => return self.next_char_or_label(start),
+                b'+' if self.eat(b'=') => ast::Kind::PlusEq,
+                b'+' => ast::Kind::Plus,
+                b'-' if self.eat(b'=') => ast::Kind::DashEq,
+                b'-' => match self.peek(0) {
+                    Some(b'0'..=b'9') => {
+                        self.cursor += 1;
+                        return self.next_number_literal(start, true);
                     }
-                    '`' => {
-                        return self.next_template(&mut it, start);
+                    _ => ast::Kind::Dash,
+                },
+                b'*' if self.eat(b'=') => ast::Kind::StarEq,
+                b'*' => ast::Kind::Star,
+                b'/' if self.eat(b'=') => ast::Kind::SlashEq,
+                b'/' if self.eat(b'/') => {
+                    self.consume_line();
+                    continue;
+                }
+                b'/' => ast::Kind::Div,
+                b'%' if self.eat(b'=') => ast::Kind::PercEq,
+                b'%' => ast::Kind::Perc,
+                b'&' if self.eat(b'=') => ast::Kind::AmpEq,
+                b'&' if self.eat(b'&') => ast::Kind::AmpAmp,
+                b'&' => ast::Kind::Amp,
+                b'^' if self.eat(b'=') => ast::Kind::CaretEq,
+                b'^' => ast::Kind::Caret,
+                b'|' if self.eat(b'=') => ast::Kind::PipeEq,
+                b'|' if self.eat(b'|') => ast::Kind::PipePipe,
+                b'|' => ast::Kind::Pipe,
+                b':' if self.eat(b':') => ast::Kind::ColonColon,
+                b':' => ast::Kind::Colon,
+                b'<' if self.eat(b'=') => ast::Kind::LtEq,
+                b'<' if self.eat(b'<') => {
+                    if self.eat(b'=') {
+                        ast::Kind::LtLtEq
+                    } else {
+                        ast::Kind::LtLt
                     }
-                    '\'' => {
-                        return self.next_char_or_label(&mut it, start);
+                }
+                b'<' => ast::Kind::Lt,
+                b'>' if self.eat(b'=') => ast::Kind::GtEq,
+                b'>' if self.eat(b'>') => {
+                    if self.eat(b'=') {
+                        ast::Kind::GtGtEq
+                    } else {
+                        ast::Kind::GtGt
                     }
-                    _ => {
-                        let span = Span {
-                            start,
-                            end: self.end_span(&it),
-                        };
+                }
+                b'>' => ast::Kind::Gt,
+                b'=' if self.eat(b'=') => ast::Kind::EqEq,
+                b'=' if self.eat(b'>') => ast::Kind::Rocket,
+                b'=' => ast::Kind::Eq,
+                b'!' if self.eat(b'=') => ast::Kind::BangEq,
+                b'!' => ast::Kind::Bang,
+                b'.' if self.eat(b'.') => ast::Kind::DotDot,
+                b'.' => ast::Kind::Dot,
+                b => {
+                    self.cursor = start;
+                    let c = self.char_at(b);
+                    self.cursor += c.len_utf8();
 
-                        return Err(ParseError::UnexpectedChar { span, c });
+                    if c.is_whitespace() {
+                        continue;
                     }
-                };
-            };
 
-            self.cursor = self.end_span(&it);
+                    let span = Span {
+                        start,
+                        end: self.cursor,
+                    };
 
-            return Ok(Some(ast::Token {
-                kind,
-                span: Span {
-                    start,
-                    end: self.cursor,
-                },
-            }));
-        }
+                    return Err(ParseError::UnexpectedChar { span, c });
+                }
+            };
 
-        self.cursor = self.source.len();
-        Ok(None)
+            return self.token(kind, start);
+        }
     }
 }
 
+/// Test if the given byte can continue an identifier.
+#[inline]
+fn is_ident_continue(b: u8) -> bool {
+    matches!(b, b'a'..=b'z' | b'A'..=b'Z' | b'_' | b'0'..=b'9')
+}
+
+/// Match an identifier against the keywords of the language.
+///
+/// This matches over the bytes of the identifier, so that it's compiled into a
+/// decision tree over its length and each byte in turn, rather than a sequence
+/// of string comparisons.
+fn keyword(ident: &[u8]) -> Option<ast::Kind> {
+    let kind = match ident {
+        [b'a', b's'] => ast::Kind::As,
+        [b'f', b'n'] => ast::Kind::Fn,
+        [b'i', b'f'] => ast::Kind::If,
+        [b'i', b'n'] => ast::Kind::In,
+        [b'i', b's'] => ast::Kind::Is,
+        [b'f', b'o', b'r'] => ast::Kind::For,
+        [b'l', b'e', b't'] => ast::Kind::Let,
+        [b'm', b'o', b'd'] => ast::Kind::Mod,
+        [b'n', b'o', b't'] => ast::Kind::Not,
+        [b'u', b's', b'e'] => ast::Kind::Use,
+        [b'e', b'l', b's', b'e'] => ast::Kind::Else,
+        [b'e', b'n', b'u', b'm'] => ast::Kind::Enum,
+        [b'i', b'm', b'p', b'l'] => ast::Kind::Impl,
+        [b'l', b'o', b'o', b'p'] => ast::Kind::Loop,
+        [b's', b'e', b'l', b'f'] => ast::Kind::Self_,
+        [b't', b'r', b'u', b'e'] => ast::Kind::True,
+        [b'w', b'i', b't', b'h'] => ast::Kind::With,
+        [b'a', b's', b'y', b'n', b'c'] => ast::Kind::Async,
+        [b'a', b'w', b'a', b'i', b't'] => ast::Kind::Await,
+        [b'b', b'r', b'e', b'a', b'k'] => ast::Kind::Break,
+        [b'd', b'e', b'f', b'e', b'r'] => ast::Kind::Defer,
+        [b'c', b'o', b'n', b's', b't'] => ast::Kind::Const,
+        [b'f', b'a', b'l', b's', b'e'] => ast::Kind::False,
+        [b'm', b'a', b'c', b'r', b'o'] => ast::Kind::Macro,
+        [b'm', b'a', b't', b'c', b'h'] => ast::Kind::Match,
+        [b'w', b'h', b'i', b'l', b'e'] => ast::Kind::While,
+        [b'y', b'i', b'e', b'l', b'd'] => ast::Kind::Yield,
+        [b'r', b'e', b't', b'u', b'r', b'n'] => ast::Kind::Return,
+        [b's', b'e', b'l', b'e', b'c', b't'] => ast::Kind::Select,
+        [b's', b't', b'r', b'u', b'c', b't'] => ast::Kind::Struct,
+        [b'd', b'e', b'f', b'a', b'u', b'l', b't'] => ast::Kind::Default,
+        [b'c', b'o', b'n', b't', b'i', b'n', b'u', b'e'] => ast::Kind::Continue,
+        _ => return None,
+    };
+
+    Some(kind)
+}
+
 #[cfg(test)]
 mod tests {
     use super::Lexer;
@@ -741,11 +746,31 @@ mod tests {
                 span: Span::new(10, 19),
                 kind: ast::Kind::LitStr {
                     escaped: false,
+                    kind: ast::StrKind::Normal,
                 },
             }
         };
     }
 
+    #[test]
+    fn test_underscore_ident() {
+        test_lexer! {
+            "_ __init__ _a",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::Underscore,
+            },
+            ast::Token {
+                span: Span::new(2, 10),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(11, 13),
+                kind: ast::Kind::Ident,
+            }
+        };
+    }
+
     #[test]
     fn test_operators() {
         test_lexer! {
@@ -844,11 +869,35 @@ mod tests {
         };
 
         test_lexer! {
-            "b'\\\\''",
+            "b'\\\\' b'\\'' b''",
             ast::Token {
-                span: Span::new(0, 6),
+                span: Span::new(0, 5),
+                kind: ast::Kind::LitByte,
+            },
+            ast::Token {
+                span: Span::new(6, 11),
                 kind: ast::Kind::LitByte,
             },
+            ast::Token {
+                span: Span::new(12, 15),
+                kind: ast::Kind::LitByte,
+            },
+        };
+
+        test_lexer! {
+            r#"b"" b"\"" b"\n""#,
+            ast::Token {
+                span: Span::new(0, 3),
+                kind: ast::Kind::LitByteStr { escaped: false },
+            },
+            ast::Token {
+                span: Span::new(4, 9),
+                kind: ast::Kind::LitByteStr { escaped: true },
+            },
+            ast::Token {
+                span: Span::new(10, 15),
+                kind: ast::Kind::LitByteStr { escaped: true },
+            },
         };
 
         test_lexer! {
@@ -883,4 +932,107 @@ mod tests {
             },
         };
     }
+
+    #[test]
+    fn test_string_literals() {
+        test_lexer! {
+            r###"r"C:\Users" r#"a "quoted" string"# r"###,
+            ast::Token {
+                span: Span::new(0, 11),
+                kind: ast::Kind::LitStr {
+                    escaped: false,
+                    kind: ast::StrKind::Raw(0),
+                },
+            },
+            ast::Token {
+                span: Span::new(12, 34),
+                kind: ast::Kind::LitStr {
+                    escaped: false,
+                    kind: ast::StrKind::Raw(1),
+                },
+            },
+            ast::Token {
+                span: Span::new(35, 36),
+                kind: ast::Kind::Ident,
+            },
+        };
+
+        test_lexer! {
+            "\"\"\"\n  \"a\"\n  \\t\n  \"\"\" \"\"",
+            ast::Token {
+                span: Span::new(0, 20),
+                kind: ast::Kind::LitStr {
+                    escaped: true,
+                    kind: ast::StrKind::Multiline,
+                },
+            },
+            ast::Token {
+                span: Span::new(21, 23),
+                kind: ast::Kind::LitStr {
+                    escaped: false,
+                    kind: ast::StrKind::Normal,
+                },
+            },
+        };
+    }
+
+    #[test]
+    fn test_shift_operators() {
+        test_lexer! {
+            "a<<b>>=c",
+            ast::Token {
+                span: Span::new(0, 1),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(1, 3),
+                kind: ast::Kind::LtLt,
+            },
+            ast::Token {
+                span: Span::new(3, 4),
+                kind: ast::Kind::Ident,
+            },
+            ast::Token {
+                span: Span::new(4, 7),
+                kind: ast::Kind::GtGtEq,
+            },
+            ast::Token {
+                span: Span::new(7, 8),
+                kind: ast::Kind::Ident,
+            },
+        };
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        test_lexer! {
+            "\u{a0}\"\u{e5}\u{e4}\" '\u{f6}' 1\u{2167}",
+            ast::Token {
+                span: Span::new(2, 8),
+                kind: ast::Kind::LitStr {
+                    escaped: false,
+                    kind: ast::StrKind::Normal,
+                },
+            },
+            ast::Token {
+                span: Span::new(9, 13),
+                kind: ast::Kind::LitChar,
+            },
+            ast::Token {
+                span: Span::new(14, 18),
+                kind: ast::Kind::LitNumber {
+                    is_fractional: false,
+                    is_negative: false,
+                    number: ast::NumberKind::Decimal,
+                },
+            },
+        };
+
+        let mut lexer = Lexer::new("a \u{2603}");
+        assert!(lexer.next().unwrap().is_some());
+        assert!(matches!(
+            lexer.next(),
+            Err(crate::ParseError::UnexpectedChar { span, c: '\u{2603}' }) if span == Span::new(2, 5)
+        ));
+    }
 }
@@ -0,0 +1,291 @@
+//! Public access to the lexer, for tools like editors which need to highlight
+//! or otherwise inspect sources without compiling them.
+
+use crate::ast::{Delimiter, Kind, Token};
+use crate::{Lexer, ParseError};
+
+/// Tokenize the given source.
+///
+/// Whitespace, comments, and the header of the source (see
+/// [SourceHeader][runestick::SourceHeader]) are skipped. The iterator stops
+/// after the first error.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::ast::Kind;
+/// use runestick::Span;
+///
+/// let tokens = rune::lexing::tokenize("let a = 42;")
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(tokens.len(), 5);
+/// assert_eq!(tokens[0].kind, Kind::Let);
+/// assert_eq!(tokens[1].span, Span::new(4, 5));
+/// ```
+pub fn tokenize(source: &str) -> Tokenize<'_> {
+    Tokenize {
+        lexer: Lexer::new(source),
+        done: false,
+    }
+}
+
+/// An iterator over the tokens of a source, as returned by [tokenize].
+#[derive(Debug, Clone)]
+pub struct Tokenize<'a> {
+    lexer: Lexer<'a>,
+    done: bool,
+}
+
+impl Iterator for Tokenize<'_> {
+    type Item = Result<Token, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.lexer.next() {
+            Ok(Some(token)) => Some(Ok(token)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(error) => {
+                self.done = true;
+                Some(Err(error))
+            }
+        }
+    }
+}
+
+/// The semantic class of a token, as used for highlighting.
+///
+/// The classes of identifiers are guesses, see [classify].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenClass {
+    /// A keyword, like `fn` or `self`.
+    Keyword,
+    /// A number literal.
+    Number,
+    /// A string or byte string literal.
+    String,
+    /// A character or byte literal.
+    Char,
+    /// A template literal.
+    Template,
+    /// A label, like `'outer`.
+    Label,
+    /// An operator, like `+` or `==`.
+    Operator,
+    /// Punctuation, like delimiters, `,` or `;`.
+    Punctuation,
+    /// An identifier naming a function or method.
+    Function,
+    /// An identifier naming a macro.
+    Macro,
+    /// An identifier naming a type or variant.
+    Type,
+    /// An identifier naming a module.
+    Module,
+    /// An identifier naming a field.
+    Field,
+    /// Any other identifier, like a variable.
+    Variable,
+}
+
+/// A token together with its semantic class.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The classified token.
+    pub token: Token,
+    /// The class of the token.
+    pub class: TokenClass,
+}
+
+/// Tokenize and classify the given source.
+///
+/// Classification is purely lexical: the role of an identifier is guessed from
+/// the tokens surrounding it and how it's capitalized, not from the items
+/// resolved by the compiler. This works on incomplete sources as long as they
+/// can be tokenized, but some identifiers get the wrong class. For example, a
+/// local variable which is called like `f()` is classified as a
+/// [Function][TokenClass::Function], and a name imported through a `use` alias
+/// is classified by how it looks rather than what it refers to.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::lexing::TokenClass;
+///
+/// let tokens = rune::lexing::classify("fn main() { std::io::println(`{a.b}`); }").unwrap();
+/// let classes = tokens.iter().map(|t| t.class).collect::<Vec<_>>();
+///
+/// assert_eq!(classes[1], TokenClass::Function);
+/// assert_eq!(classes[5], TokenClass::Module);
+/// assert_eq!(classes[9], TokenClass::Function);
+/// assert_eq!(classes[11], TokenClass::Template);
+/// ```
+pub fn classify(source: &str) -> Result<Vec<SemanticToken>, ParseError> {
+    let tokens = tokenize(source).collect::<Result<Vec<_>, _>>()?;
+    let mut output = Vec::with_capacity(tokens.len());
+
+    for (n, token) in tokens.iter().enumerate() {
+        let class = match token.kind {
+            Kind::Ident => {
+                let prev = n.checked_sub(1).and_then(|n| tokens.get(n)).map(|t| t.kind);
+                let next = tokens.get(n + 1).map(|t| t.kind);
+                let ident = source.get(token.span.start..token.span.end);
+                classify_ident(ident.unwrap_or_default(), prev, next)
+            }
+            kind => classify_kind(kind),
+        };
+
+        output.push(SemanticToken {
+            token: *token,
+            class,
+        });
+    }
+
+    Ok(output)
+}
+
+/// Classify an identifier based on the tokens surrounding it.
+///
+/// NB: this doesn't know what the identifier resolves to.
+fn classify_ident(ident: &str, prev: Option<Kind>, next: Option<Kind>) -> TokenClass {
+    let is_type = ident.starts_with(char::is_uppercase);
+
+    match (prev, next) {
+        (Some(Kind::Fn), _) => TokenClass::Function,
+        (Some(Kind::Struct), _) | (Some(Kind::Enum), _) | (Some(Kind::Impl), _) => TokenClass::Type,
+        (Some(Kind::Mod), _) => TokenClass::Module,
+        (_, Some(Kind::Bang)) => TokenClass::Macro,
+        (_, Some(Kind::ColonColon)) if !is_type => TokenClass::Module,
+        (Some(Kind::Dot), Some(Kind::Open(Delimiter::Parenthesis))) => TokenClass::Function,
+        (Some(Kind::Dot), _) => TokenClass::Field,
+        _ if is_type => TokenClass::Type,
+        (_, Some(Kind::Open(Delimiter::Parenthesis))) => TokenClass::Function,
+        _ => TokenClass::Variable,
+    }
+}
+
+/// Classify a token which isn't an identifier based on its kind.
+fn classify_kind(kind: Kind) -> TokenClass {
+    match kind {
+        Kind::Self_
+        | Kind::Macro
+        | Kind::Fn
+        | Kind::Enum
+        | Kind::Struct
+        | Kind::Is
+        | Kind::Not
+        | Kind::Let
+        | Kind::If
+        | Kind::Match
+        | Kind::Else
+        | Kind::Use
+        | Kind::While
+        | Kind::Loop
+        | Kind::For
+        | Kind::In
+        | Kind::True
+        | Kind::False
+        | Kind::Break
+        | Kind::Continue
+        | Kind::Yield
+        | Kind::Return
+        | Kind::Await
+        | Kind::Async
+        | Kind::Select
+        | Kind::Default
+        | Kind::Impl
+        | Kind::Mod
+        | Kind::Const
+        | Kind::Defer
+        | Kind::With
+        | Kind::As => TokenClass::Keyword,
+        Kind::Ident => TokenClass::Variable,
+        Kind::Label => TokenClass::Label,
+        Kind::LitNumber { .. } => TokenClass::Number,
+        Kind::LitChar | Kind::LitByte => TokenClass::Char,
+        Kind::LitStr { .. } | Kind::LitByteStr { .. } => TokenClass::String,
+        Kind::LitTemplate { .. } => TokenClass::Template,
+        Kind::Open(..)
+        | Kind::Close(..)
+        | Kind::Hash
+        | Kind::Dot
+        | Kind::ColonColon
+        | Kind::Underscore
+        | Kind::Comma
+        | Kind::Colon
+        | Kind::SemiColon => TokenClass::Punctuation,
+        _ => TokenClass::Operator,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, TokenClass};
+
+    #[test]
+    fn test_classify_idents() {
+        let source = r#"
+        struct Foo;
+        mod bar;
+        fn baz(a) { let b = a.c + a.d() + Foo::E(bar::f(), println!()); }
+        "#;
+
+        let idents = classify(source)
+            .unwrap()
+            .into_iter()
+            .filter(|t| t.token.kind == crate::ast::Kind::Ident)
+            .map(|t| (&source[t.token.span.start..t.token.span.end], t.class))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            idents,
+            vec![
+                ("Foo", TokenClass::Type),
+                ("bar", TokenClass::Module),
+                ("baz", TokenClass::Function),
+                ("a", TokenClass::Variable),
+                ("b", TokenClass::Variable),
+                ("a", TokenClass::Variable),
+                ("c", TokenClass::Field),
+                ("a", TokenClass::Variable),
+                ("d", TokenClass::Function),
+                ("Foo", TokenClass::Type),
+                ("E", TokenClass::Type),
+                ("bar", TokenClass::Module),
+                ("f", TokenClass::Function),
+                ("println", TokenClass::Macro),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_classify_is_lexical() {
+        let source = r#"fn main() { let f = |a| a; f(1) }"#;
+
+        let idents = classify(source)
+            .unwrap()
+            .into_iter()
+            .filter(|t| t.token.kind == crate::ast::Kind::Ident)
+            .map(|t| (&source[t.token.span.start..t.token.span.end], t.class))
+            .collect::<Vec<_>>();
+
+        // NB: the local `f` is classified as a function where it's called.
+        assert_eq!(
+            idents,
+            vec![
+                ("main", TokenClass::Function),
+                ("f", TokenClass::Variable),
+                ("a", TokenClass::Variable),
+                ("a", TokenClass::Variable),
+                ("f", TokenClass::Function),
+            ]
+        );
+    }
+}
@@ -159,28 +159,41 @@
 
 mod assembly;
 pub mod ast;
+#[cfg(feature = "cache")]
+mod cache;
+mod cfg;
 mod compile;
 mod compiler;
+mod derive;
 #[cfg(feature = "diagnostics")]
 mod diagnostics;
+#[cfg(feature = "diagnostics-json")]
+mod diagnostics_json;
 mod error;
+mod explain;
 mod index;
 mod index_scopes;
 mod items;
 mod lexer;
+pub mod lexing;
 mod load;
 mod load_error;
 mod loops;
 mod macro_context;
 mod macros;
+mod memoize;
 mod options;
 mod parser;
 mod query;
 mod quote;
+mod report;
 mod scopes;
 mod sources;
+mod streaming;
+pub mod testing;
 mod token_stream;
 mod traits;
+mod transpile;
 mod unit_builder;
 mod warning;
 
@@ -194,23 +207,35 @@ mod collections {
 }
 
 pub use crate::assembly::Assembly;
+#[cfg(feature = "cache")]
+pub use crate::cache::UnitCache;
 pub use crate::error::{CompileError, ParseError};
+pub use crate::explain::explain;
 pub use crate::lexer::Lexer;
-pub use crate::load::{load_path, load_sources};
+pub use crate::load::{load_path, load_sources, load_sources_with_report};
 pub use crate::load_error::{LoadError, LoadErrorKind};
-pub use crate::macro_context::MacroContext;
+pub use crate::macro_context::{Expansion, ExpansionId, MacroContext};
 pub use crate::options::Options;
 pub use crate::parser::Parser;
+pub use crate::report::{CompileReport, FunctionReport};
 pub use crate::sources::Sources;
+pub use crate::streaming::{StreamingCompiler, StreamingParser};
 pub use crate::token_stream::{IntoTokens, TokenStream, TokenStreamIter};
 pub use crate::traits::{Parse, Resolve};
+pub use crate::transpile::{transpile, TranspileError};
 pub use crate::warning::{Warning, WarningKind, Warnings};
 pub use compiler::compile;
-pub use unit_builder::{ImportEntry, ImportKey, UnitBuilder};
+pub use unit_builder::{ImportEntry, ImportKey, LinkerError, LinkerErrors, UnitBuilder};
 
 #[cfg(feature = "diagnostics")]
 pub use diagnostics::{termcolor, DiagnosticsError, EmitDiagnostics};
 
+#[cfg(feature = "diagnostics-json")]
+pub use diagnostics_json::{
+    write_json_diagnostics, IntoJsonDiagnostics, JsonDiagnostic, JsonLabel, JsonLevel,
+    JsonLocation, JsonPosition, JsonSpan, JsonSuggestion,
+};
+
 /// Construct a a default context runestick context.
 ///
 /// If built with the `modules` feature, this includes all available native
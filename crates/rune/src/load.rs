@@ -1,11 +1,13 @@
 use crate::compiler;
+use crate::compiler::Compilation;
 use crate::unit_builder::LinkerErrors;
 use crate::unit_builder::UnitBuilder;
-use crate::{LoadError, LoadErrorKind, Options, Sources, Warnings};
-use runestick::{Context, Source, Unit};
+use crate::{CompileReport, LoadError, LoadErrorKind, Options, Sources, Warnings};
+use runestick::{Context, Source, Unit, UnitAbi};
 use std::cell::RefCell;
 use std::path::Path;
 use std::rc::Rc;
+use std::time::Instant;
 
 /// Load the given path.
 ///
@@ -130,16 +132,72 @@ pub fn load_sources(
     sources: &mut Sources,
     warnings: &mut Warnings,
 ) -> Result<Unit, LoadError> {
+    let unit = new_unit(context);
+    compiler::compile_with_options(&*context, sources, &options, &unit, warnings)?;
+    build_unit(context, options, unit, None)
+}
+
+/// Load and compile the given sources like [load_sources], while collecting
+/// timings and statistics on the compilation.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Source;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let context = rune::default_context()?;
+/// let options = rune::Options::default();
+/// let mut sources = rune::Sources::new();
+/// let mut warnings = rune::Warnings::new();
+///
+/// sources.insert_default(Source::new("entry", "fn main() { 1 + 2 }"));
+///
+/// let (unit, report) =
+///     rune::load_sources_with_report(&context, &options, &mut sources, &mut warnings)?;
+///
+/// assert_eq!(report.sources, 1);
+/// assert_eq!(report.functions[0].item.to_string(), "main");
+/// println!("{}", report);
+/// # Ok(())
+/// # }
+/// ```
+pub fn load_sources_with_report(
+    context: &Context,
+    options: &Options,
+    sources: &mut Sources,
+    warnings: &mut Warnings,
+) -> Result<(Unit, CompileReport), LoadError> {
+    let mut report = CompileReport::default();
+    let unit = new_unit(context);
+
+    let mut compilation = Compilation::new(context, options, unit.clone()).with_report(&mut report);
+    compilation.index_queued(sources, warnings)?;
+    compilation.finish(sources, warnings)?;
+
+    let unit = build_unit(context, options, unit, Some(&mut report))?;
+    Ok((unit, report))
+}
+
+/// Construct the unit builder to compile into for the given context.
+pub(crate) fn new_unit(context: &Context) -> Rc<RefCell<UnitBuilder>> {
     let unit = if context.has_default_modules() {
         UnitBuilder::with_default_prelude()
     } else {
         UnitBuilder::default()
     };
 
-    let unit = Rc::new(RefCell::new(unit));
-    compiler::compile_with_options(&*context, sources, &options, &unit, warnings)?;
+    Rc::new(RefCell::new(unit))
+}
 
-    let unit = match Rc::try_unwrap(unit) {
+/// Link a compiled unit with the context and build it.
+pub(crate) fn build_unit(
+    context: &Context,
+    options: &Options,
+    unit: Rc<RefCell<UnitBuilder>>,
+    report: Option<&mut CompileReport>,
+) -> Result<Unit, LoadError> {
+    let mut unit = match Rc::try_unwrap(unit) {
         Ok(unit) => unit.into_inner(),
         Err(..) => {
             return Err(LoadError::from(LoadErrorKind::Internal {
@@ -149,12 +207,24 @@ pub fn load_sources(
     };
 
     if options.link_checks {
+        let start = Instant::now();
         let mut errors = LinkerErrors::new();
+        let linked = unit.link(&*context, &mut errors);
+
+        if let Some(report) = report {
+            report.link += start.elapsed();
+        }
 
-        if !unit.link(&*context, &mut errors) {
+        if !linked {
             return Err(LoadError::from(LoadErrorKind::LinkError { errors }));
         }
     }
 
+    unit.set_abi(UnitAbi::new(
+        env!("CARGO_PKG_VERSION"),
+        context.fingerprint(),
+        options.features(),
+    ));
+
     Ok(unit.into_unit())
 }
@@ -1,7 +1,7 @@
 use crate::ast;
 use crate::compiler::Needs;
 use crate::error::{CompileError, CompileResult};
-use runestick::{Label, Source};
+use runestick::{Label, Source, Span};
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -23,12 +23,22 @@ pub(crate) struct Loop {
     pub(crate) label: Option<ast::Label>,
     /// The end label of the loop.
     pub(crate) break_label: Label,
+    /// The label to jump to to start the next iteration of the loop.
+    pub(crate) continue_label: Label,
     /// The number of variables observed at the start of the loop.
     pub(crate) total_var_count: usize,
+    /// The number of variables observed at the start of each iteration of the
+    /// loop.
+    pub(crate) continue_var_count: usize,
+    /// The number of scopes observed at the start of the loop.
+    pub(crate) scopes: usize,
     /// If the loop needs a value.
     pub(crate) needs: Needs,
     /// Locals to drop when breaking.
     pub(crate) drop: Option<usize>,
+    /// The offset of the local variable being iterated over and the span of
+    /// the iterable expression, if the loop iterates over a variable.
+    pub(crate) iter_var: Option<(usize, Span)>,
 }
 
 pub(crate) struct Loops {
@@ -89,6 +99,19 @@ impl Loops {
         Err(CompileError::MissingLabel { span })
     }
 
+    /// Find the span of the iterable expression of a loop that is iterating
+    /// over the variable at the given offset, if any.
+    pub(crate) fn iterating_over(&self, offset: usize) -> Option<Span> {
+        self.loops
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|l| match l.iter_var {
+                Some((o, span)) if o == offset => Some(span),
+                _ => None,
+            })
+    }
+
     /// Construct an iterator over all available scopes.
     pub(crate) fn iter(&self) -> impl Iterator<Item = Loop> {
         let loops = self.loops.borrow().clone();
@@ -1,9 +1,24 @@
 //! Context for a macro.
 
-use crate::TokenStream;
-use runestick::{Source, Span};
+use crate::{CompileError, TokenStream};
+use runestick::{Item, Source, Span};
 use std::sync::Arc;
 
+/// The identifier of a macro expansion recorded in a [MacroContext].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpansionId(usize);
+
+/// A macro expansion recorded in a [MacroContext].
+#[derive(Debug, Clone)]
+pub struct Expansion {
+    /// The span of the macro invocation.
+    pub span: Span,
+    /// The item of the macro being invoked.
+    pub item: Item,
+    /// The expansion which produced the macro invocation, if any.
+    pub parent: Option<ExpansionId>,
+}
+
 /// Context for a running macro.
 pub struct MacroContext {
     source: Arc<Source>,
@@ -11,6 +26,10 @@ pub struct MacroContext {
     pub(crate) default_span: Span,
     /// End point of the span.
     pub(crate) end: Span,
+    /// Side table of all expansions performed in this context.
+    expansions: Vec<Expansion>,
+    /// The expansion currently being performed.
+    current: Option<ExpansionId>,
 }
 
 impl MacroContext {
@@ -20,6 +39,8 @@ impl MacroContext {
             source,
             default_span: Span::empty(),
             end: Span::empty(),
+            expansions: Vec::new(),
+            current: None,
         }
     }
 
@@ -29,12 +50,116 @@ impl MacroContext {
     }
 
     /// Construct a new token stream.
+    ///
+    /// The token stream is tagged with the expansion currently being
+    /// performed.
     pub fn token_stream(&self) -> TokenStream {
-        TokenStream::new(Vec::new(), self.end)
+        TokenStream::new(Vec::new(), self.end).with_expansion(self.current)
     }
 
     /// Access the current source of the macro context.
     pub fn source(&self) -> &Source {
         &*self.source
     }
+
+    /// The expansion currently being performed, if any.
+    pub fn expansion(&self) -> Option<ExpansionId> {
+        self.current
+    }
+
+    /// Get a recorded expansion by its identifier.
+    pub fn get_expansion(&self, id: ExpansionId) -> Option<&Expansion> {
+        self.expansions.get(id.0)
+    }
+
+    /// Get the depth of the given expansion, where an expansion which wasn't
+    /// produced by another macro has a depth of `1`.
+    pub fn depth(&self, id: ExpansionId) -> usize {
+        let mut depth = 0;
+        let mut current = Some(id);
+
+        while let Some(expansion) = current.and_then(|id| self.get_expansion(id)) {
+            depth += 1;
+            current = expansion.parent;
+        }
+
+        depth
+    }
+
+    /// Find the innermost expansion which produced the given span.
+    ///
+    /// Tokens generated by a macro are given the span of its invocation, so
+    /// this is the most recent expansion with the narrowest invocation span
+    /// containing `span`.
+    pub fn expansion_at(&self, span: Span) -> Option<ExpansionId> {
+        let mut found = None::<(usize, Span)>;
+
+        for (index, expansion) in self.expansions.iter().enumerate() {
+            if !expansion.span.overlaps(span) {
+                continue;
+            }
+
+            match found {
+                Some((_, existing)) if existing.len() < expansion.span.len() => (),
+                _ => found = Some((index, expansion.span)),
+            }
+        }
+
+        found.map(|(index, _)| ExpansionId(index))
+    }
+
+    /// Record a new expansion of the macro `item` invoked at `span` and mark
+    /// it as the current expansion.
+    pub(crate) fn enter(&mut self, span: Span, item: Item) -> ExpansionId {
+        let id = ExpansionId(self.expansions.len());
+        let parent = self.expansion_at(span);
+        self.expansions.push(Expansion { span, item, parent });
+        self.default_span = span;
+        self.end = Span::point(span.end);
+        self.current = Some(id);
+        id
+    }
+
+    /// Leave the current expansion.
+    pub(crate) fn leave(&mut self) {
+        self.default_span = Span::default();
+        self.end = Span::default();
+        self.current = None;
+    }
+
+    /// Wrap the given error in the chain of expansions which produced the
+    /// code it was raised for, starting at `id`.
+    pub(crate) fn expansion_error_from(
+        &self,
+        id: ExpansionId,
+        error: CompileError,
+    ) -> CompileError {
+        let mut error = error;
+        let mut current = Some(id);
+
+        while let Some(expansion) = current.and_then(|id| self.get_expansion(id)) {
+            error = CompileError::Expansion {
+                span: expansion.span,
+                item: expansion.item.clone(),
+                error: Box::new(error),
+            };
+
+            current = expansion.parent;
+        }
+
+        error
+    }
+
+    /// Wrap the given error in the chain of expansions which produced the
+    /// code it was raised for, if any.
+    pub(crate) fn expansion_error(&self, error: CompileError) -> CompileError {
+        if let CompileError::Expansion { .. } = error {
+            return error;
+        }
+
+        match self.expansion_at(error.span()) {
+            Some(id) => self.expansion_error_from(id, error),
+            None => error,
+        }
+    }
 }
@@ -1,13 +1,57 @@
 //! Macro compiler.
 
 use crate::error::CompileResult;
+use crate::traits::Resolve as _;
 use crate::{
     ast, CompileError, MacroContext, Options, Parse, ParseError, Parser, TokenStream, UnitBuilder,
 };
-use runestick::{Context, Hash, Item, Source, Span};
+use runestick::{Context, Hash, Item, Source};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Instant;
+
+/// A macro which is built into the compiler.
+///
+/// Built-in macros are compiled directly, and are available even if
+/// experimental macro support isn't enabled.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum BuiltinMacro {
+    /// `env!("KEY")`, which reads a compile-time variable.
+    Env,
+    /// `option_env!("KEY")`, which reads a compile-time variable as an option.
+    OptionEnv,
+    /// `dbg!(<expr>)`, which prints an expression and its value and evaluates
+    /// to the value.
+    Dbg,
+}
+
+impl BuiltinMacro {
+    /// Find the built-in macro called through the given path, if any.
+    pub(crate) fn find(path: &ast::Path, source: &Source) -> CompileResult<Option<Self>> {
+        if !path.rest.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(match path.first.resolve(source)? {
+            "env" => Some(Self::Env),
+            "option_env" => Some(Self::OptionEnv),
+            "dbg" => Some(Self::Dbg),
+            _ => None,
+        })
+    }
+
+    /// Parse the expression passed to `dbg!(<expr>)`.
+    ///
+    /// The expression is compiled in place of the macro, so it's parsed both
+    /// when indexing and when compiling.
+    pub(crate) fn parse_dbg(expr_call_macro: &ast::ExprCallMacro) -> CompileResult<ast::Expr> {
+        let mut parser = Parser::from_token_stream(&expr_call_macro.stream);
+        let expr = parser.parse::<ast::Expr>()?;
+        parser.parse_eof()?;
+        Ok(expr)
+    }
+}
 
 pub(crate) struct MacroCompiler<'a> {
     pub(crate) item: Item,
@@ -48,14 +92,29 @@ impl MacroCompiler<'_> {
 
         let input_stream = &expr_call_macro.stream;
 
-        self.macro_context.default_span = span;
-        self.macro_context.end = Span::point(span.end);
+        let expansion = self.macro_context.enter(span, item.clone());
+
+        let limit = self.options.macro_depth;
+
+        if self.macro_context.depth(expansion) > limit {
+            self.macro_context.leave();
+            return Err(CompileError::MacroDepthLimit { span, limit });
+        }
 
+        let start = Instant::now();
         let result = handler(self.macro_context, input_stream);
+        let elapsed = start.elapsed();
+        self.macro_context.leave();
 
-        // reset to default spans.
-        self.macro_context.default_span = Span::default();
-        self.macro_context.end = Span::default();
+        if let Some(limit) = self.options.macro_time {
+            if elapsed > limit {
+                return Err(CompileError::MacroTimeLimit {
+                    span,
+                    elapsed,
+                    limit,
+                });
+            }
+        }
 
         let output = match result {
             Ok(output) => output,
@@ -80,9 +139,27 @@ impl MacroCompiler<'_> {
             }
         };
 
+        let limit = self.options.macro_tokens;
+
+        if token_stream.len() > limit {
+            return Err(CompileError::MacroTokenLimit {
+                span,
+                tokens: token_stream.len(),
+                limit,
+            });
+        }
+
         let mut parser = Parser::from_token_stream(&token_stream);
-        let output = parser.parse::<T>()?;
-        parser.parse_eof()?;
-        Ok(output)
+
+        let result = parser
+            .parse::<T>()
+            .and_then(|output| parser.parse_eof().map(|()| output));
+
+        match result {
+            Ok(output) => Ok(output),
+            Err(error) => Err(self
+                .macro_context
+                .expansion_error_from(expansion, CompileError::ParseError { error })),
+        }
     }
 }
@@ -0,0 +1,121 @@
+//! Memoization of script functions, like `#[memoize(capacity = 100)]`.
+//!
+//! The results of a memoized function are cached by its arguments in every
+//! virtual machine, which is done by the virtual machine when the function is
+//! called.
+
+use crate::ast;
+use crate::error::{CompileError, CompileResult};
+use crate::traits::Resolve as _;
+use runestick::{Source, Span};
+use std::time::Duration;
+
+/// How the results of a memoized function are cached, as configured through
+/// its `#[memoize]` attribute.
+#[derive(Debug, Clone)]
+pub(crate) struct Memoize {
+    /// The span of the attribute.
+    pub(crate) span: Span,
+    /// The maximum number of cached results, like `capacity = 100`.
+    pub(crate) capacity: Option<usize>,
+    /// How long a result is cached for, like `ttl = "30s"`.
+    pub(crate) ttl: Option<Duration>,
+}
+
+/// Test if the given attribute is a `#[memoize]` attribute rather than a
+/// condition.
+pub(crate) fn is_memoize_attribute(meta: &ast::Meta, source: &Source) -> CompileResult<bool> {
+    Ok(meta.name().resolve(source)? == "memoize")
+}
+
+/// Get how the results of the given declaration are cached, if it has a
+/// `#[memoize]` attribute.
+pub(crate) fn options(
+    attributes: &[ast::Attribute],
+    decl: &ast::Decl,
+    source: &Source,
+) -> CompileResult<Option<Memoize>> {
+    let mut memoize = None;
+
+    for attribute in attributes {
+        let meta = &attribute.meta;
+
+        if !is_memoize_attribute(meta, source)? {
+            continue;
+        }
+
+        let span = meta.span();
+
+        if memoize.is_some() {
+            return Err(CompileError::UnsupportedAttribute { span });
+        }
+
+        if !matches!(decl, ast::Decl::DeclFn(..)) {
+            return Err(CompileError::UnsupportedMemoize { span });
+        }
+
+        let mut options = Memoize {
+            span,
+            capacity: None,
+            ttl: None,
+        };
+
+        match meta {
+            ast::Meta::Ident(..) => (),
+            ast::Meta::List(list) => {
+                for (meta, _) in &list.items.items {
+                    let span = meta.span();
+
+                    let meta = match meta {
+                        ast::Meta::NameValue(meta) => meta,
+                        _ => return Err(CompileError::UnsupportedAttribute { span }),
+                    };
+
+                    match meta.name.resolve(source)? {
+                        "capacity" if options.capacity.is_none() => {
+                            let capacity = match &meta.value {
+                                ast::MetaValue::LitNumber(lit_number) => {
+                                    match lit_number.resolve(source)? {
+                                        ast::Number::Integer(n) if n > 0 => Some(n as usize),
+                                        _ => None,
+                                    }
+                                }
+                                ast::MetaValue::LitStr(lit_str) => lit_str
+                                    .resolve(source)?
+                                    .parse::<usize>()
+                                    .ok()
+                                    .filter(|capacity| *capacity > 0),
+                            };
+
+                            let capacity = capacity
+                                .ok_or_else(|| CompileError::UnsupportedAttribute { span })?;
+
+                            options.capacity = Some(capacity);
+                        }
+                        "ttl" if options.ttl.is_none() => {
+                            let ttl = match &meta.value {
+                                ast::MetaValue::LitStr(lit_str) => lit_str
+                                    .resolve(source)?
+                                    .parse::<runestick::Duration>()
+                                    .ok()
+                                    .and_then(runestick::Duration::to_std),
+                                ast::MetaValue::LitNumber(..) => None,
+                            };
+
+                            let ttl =
+                                ttl.ok_or_else(|| CompileError::UnsupportedAttribute { span })?;
+
+                            options.ttl = Some(ttl);
+                        }
+                        _ => return Err(CompileError::UnsupportedAttribute { span }),
+                    }
+                }
+            }
+            ast::Meta::NameValue(..) => return Err(CompileError::UnsupportedAttribute { span }),
+        }
+
+        memoize = Some(options);
+    }
+
+    Ok(memoize)
+}
@@ -1,4 +1,6 @@
+use crate::collections::HashMap;
 use crate::error::ConfigurationError;
+use std::time::Duration;
 
 /// Compiler options.
 pub struct Options {
@@ -10,9 +12,40 @@ pub struct Options {
     pub(crate) debug_info: bool,
     /// Support (experimental) macros.
     pub(crate) macros: bool,
+    /// Lower `a = a + b` into an in-place concatenation where possible.
+    pub(crate) concat_in_place: bool,
+    /// Compile calls to intrinsics registered in the context into dedicated
+    /// instructions.
+    pub(crate) intrinsics: bool,
+    /// Let negative indexes count from the end of vectors, tuples and strings.
+    pub(crate) negative_indices: bool,
+    /// Return integer overflows and divisions by zero as `Err` values from the
+    /// function they happen in.
+    pub(crate) checked_arithmetic: bool,
+    /// The maximum depth of nested macro expansions.
+    pub(crate) macro_depth: usize,
+    /// The maximum number of tokens a single macro expansion may produce.
+    pub(crate) macro_tokens: usize,
+    /// The maximum time a single macro expansion may take, if any.
+    pub(crate) macro_time: Option<Duration>,
+    /// Compile-time variables, available through `env!` and `#[cfg]`.
+    pub(crate) defines: HashMap<String, String>,
 }
 
 impl Options {
+    /// Define a compile-time variable.
+    ///
+    /// Scripts can read the variable with `env!("KEY")` or
+    /// `option_env!("KEY")`, and conditionally compile items with attributes
+    /// like `#[cfg(KEY)]` or `#[cfg(KEY = "value")]`.
+    pub fn define<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.defines.insert(key.into(), value.into());
+    }
+
     /// Parse the given option.
     pub fn parse_option(&mut self, option: &str) -> Result<(), ConfigurationError> {
         let mut it = option.split('=');
@@ -30,6 +63,32 @@ impl Options {
             Some("macros") => {
                 self.macros = it.next() != Some("false");
             }
+            Some("concat-in-place") => {
+                self.concat_in_place = it.next() != Some("false");
+            }
+            Some("intrinsics") => {
+                self.intrinsics = it.next() != Some("false");
+            }
+            Some("negative-indices") => {
+                self.negative_indices = it.next() != Some("false");
+            }
+            Some("checked-arithmetic") => {
+                self.checked_arithmetic = it.next() != Some("false");
+            }
+            Some("macro-depth") => {
+                self.macro_depth = parse_limit(option, it.next())?;
+            }
+            Some("macro-tokens") => {
+                self.macro_tokens = parse_limit(option, it.next())?;
+            }
+            Some("macro-time") => {
+                // NB: the limit is specified in milliseconds, where `0` means
+                // that expansions aren't timed.
+                self.macro_time = match parse_limit(option, it.next())? {
+                    0 => None,
+                    ms => Some(Duration::from_millis(ms as u64)),
+                };
+            }
             _ => {
                 return Err(ConfigurationError::UnsupportedOptimizationOption {
                     option: option.to_owned(),
@@ -39,6 +98,52 @@ impl Options {
 
         Ok(())
     }
+
+    /// A hash of all the options, which identifies units compiled with them.
+    #[cfg(feature = "cache")]
+    pub(crate) fn fingerprint(&self) -> runestick::Hash {
+        let mut defines = self.defines.iter().collect::<Vec<_>>();
+        defines.sort();
+
+        runestick::Hash::of((
+            self.link_checks,
+            self.memoize_instance_fn,
+            self.debug_info,
+            self.macros,
+            self.concat_in_place,
+            self.intrinsics,
+            self.negative_indices,
+            self.checked_arithmetic,
+            self.macro_depth,
+            self.macro_tokens,
+            self.macro_time,
+            defines,
+        ))
+    }
+
+    /// The language features enabled by the options, which are recorded in the
+    /// compiled unit.
+    pub(crate) fn features(&self) -> Vec<String> {
+        let mut features = Vec::new();
+
+        if self.checked_arithmetic {
+            features.push(String::from("checked-arithmetic"));
+        }
+
+        if self.concat_in_place {
+            features.push(String::from("concat-in-place"));
+        }
+
+        if self.macros {
+            features.push(String::from("macros"));
+        }
+
+        if self.negative_indices {
+            features.push(String::from("negative-indices"));
+        }
+
+        features
+    }
 }
 
 impl Default for Options {
@@ -48,6 +153,24 @@ impl Default for Options {
             memoize_instance_fn: true,
             debug_info: true,
             macros: false,
+            concat_in_place: true,
+            intrinsics: true,
+            negative_indices: false,
+            checked_arithmetic: false,
+            macro_depth: 64,
+            macro_tokens: 100_000,
+            macro_time: None,
+            defines: HashMap::new(),
         }
     }
 }
+
+/// Parse the numerical value of a limit option.
+fn parse_limit(option: &str, value: Option<&str>) -> Result<usize, ConfigurationError> {
+    match value.and_then(|value| value.parse().ok()) {
+        Some(value) => Ok(value),
+        None => Err(ConfigurationError::InvalidOptionValue {
+            option: option.to_owned(),
+        }),
+    }
+}
@@ -5,6 +5,7 @@ use crate::token_stream::{TokenStream, TokenStreamIter};
 use crate::traits::{Parse, Peek};
 use runestick::Span;
 use std::fmt;
+use std::time::{Duration, Instant};
 
 /// Parser for the rune language.
 ///
@@ -34,6 +35,7 @@ impl<'a> Parser<'a> {
     pub fn from_token_stream(token_stream: &'a TokenStream) -> Self {
         Self::with_source(Source {
             inner: SourceInner::TokenStream(token_stream.iter()),
+            lex_time: None,
         })
     }
 
@@ -41,9 +43,25 @@ impl<'a> Parser<'a> {
     pub(crate) fn new_with_start(source: &'a str, start: usize) -> Self {
         Self::with_source(Source {
             inner: SourceInner::Lexer(Lexer::new_with_start(source, start)),
+            lex_time: None,
         })
     }
 
+    /// Construct a new parser around the given source, which keeps track of
+    /// the time spent lexing.
+    pub(crate) fn new_timed(source: &'a str) -> Self {
+        Self::with_source(Source {
+            inner: SourceInner::Lexer(Lexer::new(source)),
+            lex_time: Some(Duration::default()),
+        })
+    }
+
+    /// The time spent lexing, if the parser was constructed with
+    /// [new_timed][Self::new_timed].
+    pub(crate) fn lex_time(&self) -> Option<Duration> {
+        self.source.lex_time
+    }
+
     /// Construct a new parser with a source.
     fn with_source(mut source: Source<'a>) -> Self {
         let p1 = source.next();
@@ -117,7 +135,9 @@ impl<'a> Parser<'a> {
 
     /// Assert that the parser has reached its end-of-file.
     pub fn parse_eof(&mut self) -> Result<(), ParseError> {
-        if let Some(token) = self.source.next()? {
+        // NB: check the lookahead first, since it's already been consumed
+        // from the source.
+        if let Some(token) = self.p1? {
             return Err(ParseError::ExpectedEof {
                 actual: token.kind,
                 span: token.span,
@@ -131,6 +151,8 @@ impl<'a> Parser<'a> {
 /// A source adapter.
 pub(crate) struct Source<'a> {
     inner: SourceInner<'a>,
+    /// Time spent lexing, if it's being measured.
+    lex_time: Option<Duration>,
 }
 
 impl Source<'_> {
@@ -145,7 +167,15 @@ impl Source<'_> {
     /// Get the next token in the stream.
     pub(crate) fn next(&mut self) -> Result<Option<Token>, ParseError> {
         match &mut self.inner {
-            SourceInner::Lexer(lexer) => lexer.next(),
+            SourceInner::Lexer(lexer) => match &mut self.lex_time {
+                Some(lex_time) => {
+                    let start = Instant::now();
+                    let token = lexer.next();
+                    *lex_time += start.elapsed();
+                    token
+                }
+                None => lexer.next(),
+            },
             SourceInner::TokenStream(token_stream) => Ok(token_stream.next()),
         }
     }
@@ -3,14 +3,15 @@
 use crate::ast;
 use crate::collections::{HashMap, HashSet};
 use crate::error::CompileError;
+use crate::memoize::Memoize;
 use crate::traits::Resolve as _;
 use crate::unit_builder::UnitBuilder;
 use runestick::{
-    Call, CompileMeta, CompileMetaCapture, CompileMetaStruct, CompileMetaTuple, Hash, Item, Source,
-    Span, Type,
+    Call, CompileMeta, CompileMetaCapture, CompileMetaSignature, CompileMetaStruct,
+    CompileMetaTuple, Hash, Item, Source, Span, Type,
 };
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::rc::Rc;
 use std::sync::Arc;
 
@@ -19,6 +20,10 @@ pub(crate) enum Indexed {
     Struct(Struct),
     Variant(Variant),
     Function(Function),
+    AssociatedFunction(Function),
+    Const(Const),
+    Static(Static),
+    FromDiscriminant(FromDiscriminant),
     Closure(Closure),
     AsyncBlock(AsyncBlock),
 }
@@ -52,6 +57,22 @@ pub(crate) struct Function {
     /// Ast for declaration.
     pub(crate) ast: ast::DeclFn,
     pub(crate) call: Call,
+    /// How the results of the function are cached, if it's memoized.
+    pub(crate) memoize: Option<Memoize>,
+}
+
+/// Construct the signature of the given function declaration.
+pub(crate) fn fn_signature(
+    item: &Item,
+    decl_fn: &ast::DeclFn,
+    source: &Source,
+    source_id: usize,
+) -> Result<CompileMetaSignature, CompileError> {
+    Ok(CompileMetaSignature {
+        item: item.clone(),
+        args: crate::compiler::format_fn_args(source, decl_fn.args.items.iter().map(|(a, _)| a))?,
+        declared: Some((source_id, decl_fn.item_span())),
+    })
 }
 
 pub(crate) struct InstanceFunction {
@@ -59,11 +80,41 @@ pub(crate) struct InstanceFunction {
     pub(crate) ast: ast::DeclFn,
     /// The item of the instance function.
     pub(crate) impl_item: Item,
+    /// The item of the module the impl block is declared in.
+    pub(crate) impl_base: Item,
+    /// The path of the implemented type, as written in the impl block.
+    pub(crate) impl_path: ast::Path,
+    /// If the instance function is declared in an extension.
+    pub(crate) extension: bool,
+    /// If the instance function is a finalizer declared through `impl Drop`.
+    pub(crate) finalizer: bool,
     /// The span of the instance function.
     pub(crate) instance_span: Span,
     pub(crate) call: Call,
 }
 
+pub(crate) struct Const {
+    /// Ast for the constant.
+    pub(crate) ast: ast::DeclConst,
+    /// Calling convention used for the function producing the constant.
+    pub(crate) call: Call,
+}
+
+pub(crate) struct Static {
+    /// Ast for the static.
+    pub(crate) ast: ast::DeclLet,
+    /// The slot the value of the static is stored in.
+    pub(crate) slot: usize,
+}
+
+/// The generated `from_int` function of an enum with discriminants.
+pub(crate) struct FromDiscriminant {
+    /// The item of the enum.
+    pub(crate) enum_item: Item,
+    /// The span of the enum declaration.
+    pub(crate) span: Span,
+}
+
 pub(crate) struct Closure {
     /// Ast for closure.
     pub(crate) ast: ast::ExprClosure,
@@ -82,12 +133,30 @@ pub(crate) struct AsyncBlock {
     pub(crate) call: Call,
 }
 
+/// The implicit `main` function of a script, made up of its top-level
+/// statements.
+pub(crate) struct Script {
+    /// The top-level statements of the script.
+    pub(crate) stmts: Vec<(ast::Expr, Option<ast::SemiColon>)>,
+    /// The statics declared in the script, which are initialized in order
+    /// with the statements.
+    pub(crate) statics: Vec<(Item, Span)>,
+    /// The span of the statements.
+    pub(crate) span: Span,
+    /// Calling convention used for the function.
+    pub(crate) call: Call,
+}
+
 /// An entry in the build queue.
 pub(crate) enum Build {
     Function(Function),
     InstanceFunction(InstanceFunction),
+    Const(Const),
+    Static(Static),
+    FromDiscriminant(FromDiscriminant),
     Closure(Closure),
     AsyncBlock(AsyncBlock),
+    Script(Script),
 }
 
 /// An entry in the build queue.
@@ -107,7 +176,12 @@ pub(crate) struct IndexedEntry {
 pub(crate) struct Query {
     pub(crate) queue: VecDeque<BuildEntry>,
     indexed: HashMap<Item, IndexedEntry>,
+    /// The number of items which have been indexed.
+    pub(crate) indexed_count: usize,
     pub(crate) unit: Rc<RefCell<UnitBuilder>>,
+    /// The statics referenced by the initializer of each static, and the
+    /// source the static is declared in.
+    pub(crate) static_deps: BTreeMap<Item, (usize, Vec<(Item, Span)>)>,
 }
 
 impl Query {
@@ -116,7 +190,47 @@ impl Query {
         Self {
             queue: VecDeque::new(),
             indexed: HashMap::new(),
+            indexed_count: 0,
             unit,
+            static_deps: BTreeMap::new(),
+        }
+    }
+
+    /// Check that no static depends on itself through the initializers of
+    /// statics, returning the source and error of the first cycle found.
+    pub(crate) fn check_static_cycles(&self) -> Result<(), (usize, CompileError)> {
+        for (item, (source_id, deps)) in &self.static_deps {
+            for (dep, span) in deps {
+                if self.static_reaches(dep, item, &mut HashSet::new()) {
+                    return Err((
+                        *source_id,
+                        CompileError::StaticCycle {
+                            span: *span,
+                            item: item.clone(),
+                        },
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Test if the initializer of the static `from` depends on the static `to`.
+    fn static_reaches(&self, from: &Item, to: &Item, visited: &mut HashSet<Item>) -> bool {
+        if from == to {
+            return true;
+        }
+
+        if !visited.insert(from.clone()) {
+            return false;
+        }
+
+        match self.static_deps.get(from) {
+            Some((_, deps)) => deps
+                .iter()
+                .any(|(dep, _)| self.static_reaches(dep, to, visited)),
+            None => false,
         }
     }
 
@@ -264,6 +378,7 @@ impl Query {
             });
         }
 
+        self.indexed_count += 1;
         Ok(())
     }
 
@@ -301,6 +416,8 @@ impl Query {
             }
             Indexed::Struct(st) => self.ast_into_item_decl(&item, st.ast.body, None, source)?,
             Indexed::Function(f) => {
+                let signature = fn_signature(&item, &f.ast, &*source, source_id)?;
+
                 self.queue.push_back(BuildEntry {
                     item: item.clone(),
                     build: Build::Function(f),
@@ -311,6 +428,66 @@ impl Query {
                 CompileMeta::Function {
                     value_type: Type::Hash(Hash::type_hash(&item)),
                     item: item.clone(),
+                    signature: Some(signature),
+                }
+            }
+            Indexed::AssociatedFunction(f) => {
+                let signature = fn_signature(&item, &f.ast, &*source, source_id)?;
+
+                self.queue.push_back(BuildEntry {
+                    item: item.clone(),
+                    build: Build::Function(f),
+                    source,
+                    source_id,
+                });
+
+                CompileMeta::AssociatedFunction {
+                    value_type: Type::Hash(Hash::type_hash(&item)),
+                    item: item.clone(),
+                    signature: Some(signature),
+                }
+            }
+            Indexed::Const(c) => {
+                self.queue.push_back(BuildEntry {
+                    item: item.clone(),
+                    build: Build::Const(c),
+                    source,
+                    source_id,
+                });
+
+                CompileMeta::Const { item: item.clone() }
+            }
+            Indexed::Static(s) => {
+                let slot = s.slot;
+
+                self.queue.push_back(BuildEntry {
+                    item: item.clone(),
+                    build: Build::Static(s),
+                    source,
+                    source_id,
+                });
+
+                CompileMeta::Static {
+                    item: item.clone(),
+                    slot,
+                }
+            }
+            Indexed::FromDiscriminant(f) => {
+                self.queue.push_back(BuildEntry {
+                    item: item.clone(),
+                    build: Build::FromDiscriminant(f),
+                    source,
+                    source_id,
+                });
+
+                CompileMeta::AssociatedFunction {
+                    value_type: Type::Hash(Hash::type_hash(&item)),
+                    item: item.clone(),
+                    signature: Some(CompileMetaSignature {
+                        item: item.clone(),
+                        args: vec![String::from("discriminant")],
+                        declared: None,
+                    }),
                 }
             }
             Indexed::Closure(c) => {
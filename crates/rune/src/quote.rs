@@ -124,6 +124,10 @@ macro_rules! quote {
         $crate::quote!(@token $ctx, $stream, Break => $($tt)*);
     }};
 
+    (@push $ctx:expr, $stream:expr => continue $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $stream, Continue => $($tt)*);
+    }};
+
     (@push $ctx:expr, $stream:expr => yield $($tt:tt)*) => {{
         $crate::quote!(@token $ctx, $stream, Yield => $($tt)*);
     }};
@@ -192,6 +196,14 @@ macro_rules! quote {
         $crate::quote!(@token $ctx, $stream, Mod => $($tt)*);
     }};
 
+    (@push $ctx:expr, $stream:expr => const $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $stream, Const => $($tt)*);
+    }};
+
+    (@push $ctx:expr, $stream:expr => as $($tt:tt)*) => {{
+        $crate::quote!(@token $ctx, $stream, As => $($tt)*);
+    }};
+
     (@push $ctx:expr, $stream:expr => { $($tt:tt)* }) => {{
         $crate::quote!(@wrap $ctx, $stream, Brace => $($tt)*);
     }};
@@ -0,0 +1,86 @@
+use runestick::Item;
+use std::fmt;
+use std::time::Duration;
+
+/// Timings and statistics collected while compiling, as returned by
+/// [load_sources_with_report][crate::load_sources_with_report].
+///
+/// Lexing happens on demand while parsing, so the time spent parsing excludes
+/// the time spent lexing.
+#[derive(Debug, Clone, Default)]
+pub struct CompileReport {
+    /// Time spent lexing sources.
+    pub lex: Duration,
+    /// Time spent parsing sources.
+    pub parse: Duration,
+    /// Time spent indexing items, which includes processing imports and
+    /// expanding macros.
+    pub index: Duration,
+    /// Time spent querying for items and building them into assemblies.
+    pub build: Duration,
+    /// Time spent translating assemblies into the instructions of the unit.
+    pub assembly: Duration,
+    /// Time spent linking the unit against the context.
+    pub link: Duration,
+    /// The number of sources which were compiled.
+    pub sources: usize,
+    /// The number of items which were indexed, like types, closures, and
+    /// constants.
+    pub indexed: usize,
+    /// Functions which were compiled into the unit, in the order they were
+    /// compiled.
+    pub functions: Vec<FunctionReport>,
+}
+
+impl CompileReport {
+    /// The total time spent compiling.
+    pub fn total(&self) -> Duration {
+        self.lex + self.parse + self.index + self.build + self.assembly + self.link
+    }
+
+    /// The total number of instructions in the compiled functions.
+    pub fn instructions(&self) -> usize {
+        self.functions.iter().map(|f| f.instructions).sum()
+    }
+}
+
+impl fmt::Display for CompileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let phases = [
+            ("lex", self.lex),
+            ("parse", self.parse),
+            ("index", self.index),
+            ("build", self.build),
+            ("assembly", self.assembly),
+            ("link", self.link),
+            ("total", self.total()),
+        ];
+
+        for (name, duration) in &phases {
+            writeln!(f, "{:>10}: {:?}", name, duration)?;
+        }
+
+        writeln!(f, "{:>10}: {}", "sources", self.sources)?;
+        writeln!(f, "{:>10}: {}", "indexed", self.indexed)?;
+        writeln!(f, "{:>10}: {}", "functions", self.functions.len())?;
+        writeln!(f, "{:>10}: {}", "insts", self.instructions())?;
+
+        let mut functions = self.functions.iter().collect::<Vec<_>>();
+        functions.sort_by(|a, b| b.instructions.cmp(&a.instructions));
+
+        for function in functions {
+            writeln!(f, "{:>10} {}", function.instructions, function.item)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Statistics on a function compiled into a unit.
+#[derive(Debug, Clone)]
+pub struct FunctionReport {
+    /// The item of the function.
+    pub item: Item,
+    /// The number of instructions the function was compiled into.
+    pub instructions: usize,
+}
@@ -1,7 +1,11 @@
 use crate::assembly::Assembly;
+use crate::ast;
 use crate::collections::HashMap;
 use crate::error::{CompileError, CompileResult};
+use crate::items::Items;
 use runestick::{Inst, Span};
+use std::fmt;
+use std::rc::Rc;
 
 /// A locally declared variable.
 #[derive(Debug, Clone)]
@@ -42,6 +46,45 @@ pub(crate) struct AnonVar {
     span: Span,
 }
 
+/// Cleanup which is compiled into every path that exits the scope it was
+/// deferred in.
+pub(crate) enum Deferred {
+    /// A block deferred with `defer`.
+    Block {
+        /// The deferred block.
+        body: ast::ExprBlock,
+        /// The scopes as they were where the block was deferred, so that the
+        /// block only sees variables which were declared before it.
+        scopes: Scopes,
+        /// The items as they were where the block was deferred, so that items
+        /// like closures in the block are the same every time it's compiled.
+        items: Items,
+    },
+    /// Exiting the value of a `with` expression, which is stored in the
+    /// variable at the given offset.
+    Exit {
+        /// The offset of the value to exit.
+        offset: usize,
+        /// The span of the `with` expression.
+        span: Span,
+    },
+}
+
+impl fmt::Debug for Deferred {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Block { body, .. } => {
+                f.debug_struct("Block").field("span", &body.span()).finish()
+            }
+            Self::Exit { offset, span } => f
+                .debug_struct("Exit")
+                .field("offset", offset)
+                .field("span", span)
+                .finish(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct Scope {
     /// Named variables.
@@ -52,6 +95,8 @@ pub(crate) struct Scope {
     pub(crate) total_var_count: usize,
     /// The number of variables local to this scope.
     pub(crate) local_var_count: usize,
+    /// Cleanup deferred in this scope, in the order it was deferred.
+    deferred: Vec<Rc<Deferred>>,
 }
 
 impl Scope {
@@ -62,6 +107,7 @@ impl Scope {
             anon: Vec::new(),
             total_var_count: 0,
             local_var_count: 0,
+            deferred: Vec::new(),
         }
     }
 
@@ -72,6 +118,7 @@ impl Scope {
             anon: Vec::new(),
             total_var_count: self.total_var_count,
             local_var_count: 0,
+            deferred: Vec::new(),
         }
     }
 
@@ -140,6 +187,16 @@ impl Scope {
         Ok(())
     }
 
+    /// Defer cleanup until this scope is exited.
+    pub(crate) fn defer(&mut self, deferred: Deferred) {
+        self.deferred.push(Rc::new(deferred));
+    }
+
+    /// Get the cleanup deferred in this scope, in the order it should run.
+    pub(crate) fn deferred(&self) -> Vec<Rc<Deferred>> {
+        self.deferred.iter().rev().cloned().collect()
+    }
+
     /// Access the variable with the given name.
     pub(crate) fn get(&self, name: &str) -> Option<&Var> {
         if let Some(var) = self.locals.get(name) {
@@ -251,6 +308,40 @@ impl Scopes {
         Ok(scope)
     }
 
+    /// Get the number of scopes.
+    pub(crate) fn len(&self) -> usize {
+        self.scopes.len()
+    }
+
+    /// Take a snapshot of the scopes, without any of the cleanup deferred in
+    /// them.
+    pub(crate) fn snapshot(&self) -> Self {
+        let scopes = self
+            .scopes
+            .iter()
+            .map(|scope| Scope {
+                locals: scope.locals.clone(),
+                anon: scope.anon.clone(),
+                total_var_count: scope.total_var_count,
+                local_var_count: scope.local_var_count,
+                deferred: Vec::new(),
+            })
+            .collect();
+
+        Self { scopes }
+    }
+
+    /// Get the cleanup deferred in the scopes from the given depth and inwards,
+    /// in the order it should run when all of those scopes are exited.
+    pub(crate) fn deferred_from(&self, depth: usize) -> Vec<Rc<Deferred>> {
+        self.scopes
+            .iter()
+            .skip(depth)
+            .rev()
+            .flat_map(|scope| scope.deferred())
+            .collect()
+    }
+
     /// Construct a new child scope.
     pub(crate) fn child(&mut self, span: Span) -> CompileResult<Scope> {
         Ok(self.last(span)?.child())
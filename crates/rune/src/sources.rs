@@ -35,6 +35,21 @@ impl Sources {
         self.insert(Item::default(), source)
     }
 
+    /// Insert a source without queueing it for compilation, for sources which
+    /// are fed to the compiler by other means.
+    pub(crate) fn insert_unqueued(&mut self, source: Source) -> usize {
+        let source_id = self.sources.len();
+        self.sources.push(Arc::new(source));
+        source_id
+    }
+
+    /// Replace the source at the given source id.
+    pub(crate) fn replace(&mut self, source_id: usize, source: Arc<Source>) {
+        if let Some(existing) = self.sources.get_mut(source_id) {
+            *existing = source;
+        }
+    }
+
     /// Get the source matching the given source id.
     pub fn get(&self, source_id: usize) -> Option<&Arc<Source>> {
         self.sources.get(source_id)
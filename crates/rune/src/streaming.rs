@@ -0,0 +1,380 @@
+//! Parsing and compiling sources which arrive in chunks, like scripts which
+//! are being downloaded.
+//!
+//! Top-level declarations are parsed and indexed as soon as they've been
+//! received in full, so that most of the work of compiling a source overlaps
+//! with fetching it.
+
+use crate::ast;
+use crate::ast::{Delimiter, Kind, Token};
+use crate::compiler::{Compilation, FileIndex};
+use crate::load::{build_unit, new_unit};
+use crate::unit_builder::UnitBuilder;
+use crate::{Lexer, LoadError, LoadErrorKind, Options, ParseError, Parser, Sources, Warnings};
+use runestick::{Context, Item, Source, Unit};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// A parser which is fed a source in chunks, and parses top-level
+/// declarations as soon as they are complete.
+///
+/// A declaration is considered complete once it ends with a `}` or `;` at
+/// the top level, is followed by another token, and parses. Top-level
+/// statements ending with a `}` are only complete once something else
+/// follows them, since they might continue like `if c { a } else { b }`.
+/// Statements are held on to and returned by
+/// [finish][StreamingParser::finish]. Anything which
+/// doesn't parse is kept until more of the source arrives, so errors are only
+/// reported by [finish][StreamingParser::finish].
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::StreamingParser;
+///
+/// let mut parser = StreamingParser::new();
+///
+/// assert_eq!(parser.push("fn foo() { 1 }\nfn ba").decls.len(), 1);
+/// assert_eq!(parser.push("r() { 2 }").decls.len(), 0);
+/// assert_eq!(parser.finish().unwrap().decls.len(), 1);
+/// ```
+#[derive(Debug, Default)]
+pub struct StreamingParser {
+    /// The source received so far.
+    buffer: String,
+    /// The offset up until which declarations have been parsed.
+    cursor: usize,
+    /// The offset up until which the source has been scanned for the end of
+    /// declarations.
+    scan: usize,
+    /// The nesting depth of delimiters at the scanned offset.
+    depth: usize,
+    /// Top-level statements which have been parsed, which are held on to
+    /// until the whole source has been received.
+    stmts: Vec<(ast::Expr, Option<ast::SemiColon>)>,
+}
+
+impl StreamingParser {
+    /// Construct a new streaming parser.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Access the source received so far.
+    pub fn as_str(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Push a chunk of the source, and parse the declarations which were
+    /// completed by it.
+    pub fn push(&mut self, chunk: &str) -> ast::DeclFile {
+        self.buffer.push_str(chunk);
+
+        let mut decls = Vec::new();
+
+        while let Some((end, file)) = self.next_complete() {
+            decls.extend(file.decls);
+            self.stmts.extend(file.stmts);
+            self.cursor = end;
+        }
+
+        ast::DeclFile {
+            decls,
+            stmts: Vec::new(),
+        }
+    }
+
+    /// Parse the remaining declarations, once all of the source has been
+    /// pushed.
+    ///
+    /// The returned file includes all top-level statements of the source.
+    pub fn finish(&mut self) -> Result<ast::DeclFile, ParseError> {
+        let mut file = self.parse(self.buffer.len())?;
+        self.cursor = self.buffer.len();
+        self.scan = self.buffer.len();
+
+        let mut stmts = std::mem::take(&mut self.stmts);
+        stmts.extend(file.stmts);
+        file.stmts = stmts;
+        Ok(file)
+    }
+
+    /// Find and parse the next complete declarations after the cursor.
+    ///
+    /// Scanning picks up where it left off, so every token is only scanned
+    /// once and every candidate end of a declaration is only parsed once.
+    fn next_complete(&mut self) -> Option<(usize, ast::DeclFile)> {
+        let mut pending = None::<Token>;
+        let mut offset = self.scan;
+
+        // NB: lexing errors are expected when a token has been cut off at the
+        // end of a chunk, so they're treated like the end of input.
+        while let Ok(Some(next)) = Lexer::new_with_start(&self.buffer, offset).next() {
+            offset = next.span.end;
+
+            let token = match pending.replace(next) {
+                Some(token) => token,
+                None => continue,
+            };
+
+            // NB: a token is only scanned once we know where it ends, which
+            // depends on the token after it. Like how `1` might be followed by
+            // `.` and then `5` in the next chunk.
+            let is_cut_off = next.span.end >= self.buffer.len()
+                && !matches!(
+                    next.kind,
+                    Kind::Open(..) | Kind::Close(..) | Kind::SemiColon
+                );
+
+            if is_cut_off {
+                break;
+            }
+
+            if let Some(complete) = self.scan_token(token, next) {
+                return Some(complete);
+            }
+        }
+
+        None
+    }
+
+    /// Scan the given token which is followed by `next`, returning the
+    /// declarations it completes.
+    fn scan_token(&mut self, token: Token, next: Token) -> Option<(usize, ast::DeclFile)> {
+        let is_candidate = match token.kind {
+            Kind::Open(..) => {
+                self.depth += 1;
+                false
+            }
+            Kind::Close(delimiter) => {
+                self.depth = self.depth.saturating_sub(1);
+                self.depth == 0 && delimiter == Delimiter::Brace
+            }
+            Kind::SemiColon => self.depth == 0,
+            _ => false,
+        };
+
+        self.scan = token.span.end;
+
+        // A semi-colon following a brace belongs to the declaration.
+        let is_semi = matches!(
+            (token.kind, next.kind),
+            (Kind::Close(Delimiter::Brace), Kind::SemiColon)
+        );
+
+        if !is_candidate || is_semi {
+            return None;
+        }
+
+        let file = self.parse(token.span.end).ok()?;
+
+        // NB: a statement ending in a brace might continue, like with
+        // `if c { a } else { b }`.
+        let is_open = matches!(
+            file.stmts.last(),
+            Some((expr, None)) if expr.span().end == token.span.end
+        );
+
+        if is_open {
+            return None;
+        }
+
+        Some((token.span.end, file))
+    }
+
+    /// Parse the declarations between the cursor and the given end.
+    fn parse(&self, end: usize) -> Result<ast::DeclFile, ParseError> {
+        let mut parser = Parser::new_with_start(&self.buffer[..end], self.cursor);
+        let file = parser.parse::<ast::DeclFile>()?;
+        parser.parse_eof()?;
+        Ok(file)
+    }
+}
+
+/// Compile an entry source which arrives in chunks, indexing declarations as
+/// soon as they've been parsed by a [StreamingParser].
+///
+/// The source is available in the collection of sources under the given
+/// name, so that errors can be emitted as diagnostics like with
+/// [load_sources][crate::load_sources]. Since the source doesn't have a path,
+/// it can't load modules from files.
+///
+/// # Examples
+///
+/// ```rust
+/// use rune::StreamingCompiler;
+/// use runestick::{Item, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let context = Arc::new(rune::default_context()?);
+/// let options = rune::Options::default();
+/// let mut sources = rune::Sources::new();
+/// let mut warnings = rune::Warnings::new();
+///
+/// let mut compiler =
+///     StreamingCompiler::new(&*context, &options, &mut sources, &mut warnings, "entry");
+///
+/// for chunk in &["fn add(a, b) { a ", "+ b }\nfn main() { add(1", ", 2) }"] {
+///     compiler.push(chunk)?;
+/// }
+///
+/// let unit = compiler.finish()?;
+///
+/// let vm = Vm::new(context, Arc::new(unit));
+/// let output = vm.call(Item::of(&["main"]), ())?.complete()?;
+/// assert_eq!(output.into_integer()?, 3);
+/// # Ok(())
+/// # }
+/// ```
+pub struct StreamingCompiler<'a> {
+    context: &'a Context,
+    options: &'a Options,
+    sources: &'a mut Sources,
+    warnings: &'a mut Warnings,
+    name: String,
+    source_id: usize,
+    parser: StreamingParser,
+    /// Declarations which have been parsed but not yet indexed.
+    pending: Vec<(Vec<ast::Attribute>, ast::Decl, Option<ast::SemiColon>)>,
+    /// The length of the entry source the last time it was updated.
+    source_len: usize,
+    unit: Rc<RefCell<UnitBuilder>>,
+    compilation: Compilation<'a>,
+    /// Indexing state of the entry source, which is missing if indexing
+    /// failed.
+    index: Option<FileIndex>,
+}
+
+impl<'a> StreamingCompiler<'a> {
+    /// Construct a new streaming compiler for a source with the given name.
+    pub fn new(
+        context: &'a Context,
+        options: &'a Options,
+        sources: &'a mut Sources,
+        warnings: &'a mut Warnings,
+        name: &str,
+    ) -> Self {
+        let source_id = sources.insert_unqueued(Source::new(name, ""));
+        let unit = new_unit(context);
+
+        Self {
+            context,
+            options,
+            sources,
+            warnings,
+            name: name.to_owned(),
+            source_id,
+            parser: StreamingParser::new(),
+            pending: Vec::new(),
+            source_len: 0,
+            compilation: Compilation::new(context, options, unit.clone()),
+            unit,
+            index: Some(FileIndex::new(Item::default())),
+        }
+    }
+
+    /// Push a chunk of the source, and index the declarations which were
+    /// completed by it.
+    ///
+    /// Indexing requires the source to be updated with everything received so
+    /// far, so declarations are held on to until the source has doubled in
+    /// size to avoid copying it for every chunk.
+    pub fn push(&mut self, chunk: &str) -> Result<(), LoadError> {
+        let file = self.parser.push(chunk);
+        self.pending.extend(file.decls);
+
+        if self.pending.is_empty() || self.parser.as_str().len() < self.source_len * 2 {
+            return Ok(());
+        }
+
+        let file = ast::DeclFile {
+            decls: std::mem::take(&mut self.pending),
+            stmts: Vec::new(),
+        };
+
+        let source = self.update_source();
+        self.index(source, &file)
+    }
+
+    /// Finish compiling once all of the source has been pushed.
+    pub fn finish(mut self) -> Result<Unit, LoadError> {
+        let mut file = match self.parser.finish() {
+            Ok(file) => file,
+            Err(error) => {
+                self.update_source();
+
+                return Err(LoadError::from(LoadErrorKind::ParseError {
+                    source_id: self.source_id,
+                    error,
+                }));
+            }
+        };
+
+        // NB: declarations which are still pending come first.
+        let mut decls = std::mem::take(&mut self.pending);
+        decls.extend(file.decls);
+        file.decls = decls;
+
+        let source = self.update_source();
+        self.index(source.clone(), &file)?;
+        self.compilation.load_header(self.source_id, &source)?;
+        self.compilation.index_queued(self.sources, self.warnings)?;
+        self.compilation.finish(self.sources, self.warnings)?;
+        build_unit(self.context, self.options, self.unit, None)
+    }
+
+    /// Index the given declarations of the entry source.
+    fn index(&mut self, source: Arc<Source>, file: &ast::DeclFile) -> Result<(), LoadError> {
+        let index = match self.index.take() {
+            Some(index) => index,
+            None => return Err(LoadError::internal("source failed to index")),
+        };
+
+        self.index = Some(self.compilation.index_file(
+            self.sources,
+            self.warnings,
+            self.source_id,
+            source,
+            index,
+            file,
+        )?);
+
+        Ok(())
+    }
+
+    /// Update the entry source to everything which has been received so far.
+    fn update_source(&mut self) -> Arc<Source> {
+        self.source_len = self.parser.as_str().len();
+        let source = Arc::new(Source::new(&self.name, self.parser.as_str()));
+        self.sources.replace(self.source_id, source.clone());
+        source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamingParser;
+
+    #[test]
+    fn test_scan_resumes() {
+        let mut parser = StreamingParser::new();
+
+        parser.push("fn foo() { let a = 1");
+        // NB: `1` might continue, and `=` is only scanned once we know that.
+        assert_eq!(parser.scan, 16);
+        assert_eq!(parser.depth, 1);
+
+        parser.push("0; }");
+        assert_eq!(parser.scan, 22);
+        assert_eq!(parser.depth, 1);
+
+        // NB: `fn` might be the start of an identifier.
+        assert_eq!(parser.push(" fn").decls.len(), 0);
+        assert_eq!(parser.scan, 22);
+        assert_eq!(parser.push(" bar").decls.len(), 1);
+        assert_eq!(parser.cursor, 24);
+        assert_eq!(parser.scan, 24);
+    }
+}
@@ -0,0 +1,99 @@
+//! Helpers for negative tests, which assert that scripts fail to compile or
+//! raise warnings when compiled against a given context.
+//!
+//! These are intended for authors of native modules, who want to test that
+//! their macros and type registrations are rejected when misused.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use rune::testing;
+//! use rune::{CompileError, WarningKind};
+//! use runestick::{Context, Module, Span};
+//!
+//! # fn main() -> runestick::Result<()> {
+//! let mut module = Module::new(&["http"]);
+//! module.function(&["get"], |url: &str| url.len())?;
+//!
+//! let mut context = Context::with_default_modules()?;
+//! context.install(&module)?;
+//!
+//! let error = testing::assert_compile_error(&context, "fn main() { http::post() }", |error| {
+//!     matches!(error, CompileError::MissingFunction { .. })
+//! });
+//!
+//! assert_eq!(error.span(), Span::new(12, 24));
+//!
+//! testing::assert_warning(&context, "fn main() { `no expansions` }", |warning| {
+//!     matches!(warning, WarningKind::TemplateWithoutExpansions { .. })
+//! });
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{
+    CompileError, LoadError, LoadErrorKind, Options, Sources, Warning, WarningKind, Warnings,
+};
+use runestick::{Context, Source, Unit};
+
+/// Compile the given source against the context with the default options.
+pub fn compile(context: &Context, source: &str) -> Result<(Unit, Warnings), LoadError> {
+    let mut sources = Sources::new();
+    sources.insert_default(Source::new("main", source));
+
+    let mut warnings = Warnings::new();
+    let unit = crate::load_sources(context, &Options::default(), &mut sources, &mut warnings)?;
+    Ok((unit, warnings))
+}
+
+/// Assert that compiling the given source against the context raises a
+/// compile error which is accepted by the matcher, and return the error.
+///
+/// # Panics
+///
+/// Panics if the source compiles, if it fails with anything but a compile
+/// error, or if the matcher doesn't accept the error.
+pub fn assert_compile_error<F>(context: &Context, source: &str, matcher: F) -> CompileError
+where
+    F: FnOnce(&CompileError) -> bool,
+{
+    let error = match compile(context, source) {
+        Ok(..) => panic!("expected a compile error, but the source compiled"),
+        Err(error) => error,
+    };
+
+    match error.into_kind() {
+        LoadErrorKind::CompileError { error, .. } => {
+            if !matcher(&error) {
+                panic!("compile error `{:?}` wasn't matched", error);
+            }
+
+            error
+        }
+        kind => panic!("expected a compile error, but got `{:?}`", kind),
+    }
+}
+
+/// Assert that the given source compiles against the context, and raises a
+/// warning which is accepted by the matcher. Returns the first such warning.
+///
+/// # Panics
+///
+/// Panics if the source doesn't compile or if none of its warnings are
+/// accepted by the matcher.
+pub fn assert_warning<F>(context: &Context, source: &str, mut matcher: F) -> Warning
+where
+    F: FnMut(&WarningKind) -> bool,
+{
+    let warnings = match compile(context, source) {
+        Ok((_, warnings)) => warnings,
+        Err(error) => panic!("expected the source to compile, but got `{:?}`", error),
+    };
+
+    if let Some(warning) = warnings.iter().find(|w| matcher(&w.kind)) {
+        return *warning;
+    }
+
+    let kinds = warnings.iter().map(|w| w.kind).collect::<Vec<_>>();
+    panic!("no warning was matched in `{:?}`", kinds);
+}
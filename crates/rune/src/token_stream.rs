@@ -1,5 +1,5 @@
 use crate::ast::Token;
-use crate::MacroContext;
+use crate::{ExpansionId, MacroContext};
 use runestick::Span;
 use std::slice;
 
@@ -8,12 +8,29 @@ use std::slice;
 pub struct TokenStream {
     stream: Vec<Token>,
     end: Span,
+    expansion: Option<ExpansionId>,
 }
 
 impl TokenStream {
     /// Construct a new token stream with the specified end span.
     pub fn new(stream: Vec<Token>, end: Span) -> Self {
-        Self { stream, end }
+        Self {
+            stream,
+            end,
+            expansion: None,
+        }
+    }
+
+    /// Tag the token stream with the macro expansion which produced its
+    /// tokens.
+    pub fn with_expansion(self, expansion: Option<ExpansionId>) -> Self {
+        Self { expansion, ..self }
+    }
+
+    /// Get the macro expansion which produced the tokens in the stream, if
+    /// any.
+    pub fn expansion(&self) -> Option<ExpansionId> {
+        self.expansion
     }
 
     /// Push the current token to the stream.
@@ -30,6 +47,16 @@ impl TokenStream {
         self.stream.extend(tokens.into_iter().map(Token::from));
     }
 
+    /// Get the number of tokens in the stream.
+    pub fn len(&self) -> usize {
+        self.stream.len()
+    }
+
+    /// Test if the token stream is empty.
+    pub fn is_empty(&self) -> bool {
+        self.stream.is_empty()
+    }
+
     /// Get the end span of the token stream.
     pub fn end(&self) -> Span {
         self.end
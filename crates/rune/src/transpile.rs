@@ -0,0 +1,470 @@
+//! Transpiling of unit functions into Rust.
+
+use runestick::{Call, Component, Hash, Inst, Item, Unit, UnitFn};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Write as _;
+use thiserror::Error;
+
+/// Identifiers which can't be used as the name of a generated function.
+const RESERVED: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "module",
+    "move", "mut", "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true",
+    "try", "type", "unsafe", "use", "where", "while", "yield",
+];
+
+/// An error raised when transpiling functions with [transpile].
+#[derive(Debug, Error)]
+pub enum TranspileError {
+    /// The function to transpile doesn't exist in the unit.
+    #[error("missing function `{item}`")]
+    MissingFunction {
+        /// The item of the missing function.
+        item: Item,
+    },
+    /// The function can't be transpiled, like `async` functions, generators,
+    /// and closures.
+    #[error("function `{item}` can't be transpiled, only plain functions are supported")]
+    UnsupportedFunction {
+        /// The item of the function.
+        item: Item,
+    },
+    /// The function uses an instruction which can't be transpiled.
+    #[error("instruction `{inst}` at {ip} in `{item}` can't be transpiled")]
+    UnsupportedInstruction {
+        /// The item of the function.
+        item: Item,
+        /// The instruction pointer of the instruction.
+        ip: usize,
+        /// The instruction which can't be transpiled.
+        inst: Inst,
+    },
+    /// The function calls a function which isn't being transpiled.
+    #[error("call at {ip} in `{item}` is to function `{hash}`, which isn't being transpiled")]
+    UnsupportedCall {
+        /// The item of the function.
+        item: Item,
+        /// The instruction pointer of the call.
+        ip: usize,
+        /// The hash of the function being called.
+        hash: Hash,
+    },
+    /// The function jumps outside of itself.
+    #[error("jump at {ip} in `{item}` leaves the function")]
+    BadJump {
+        /// The item of the function.
+        item: Item,
+        /// The instruction pointer of the jump.
+        ip: usize,
+    },
+}
+
+/// Transpile the given functions in a unit into Rust source.
+///
+/// The generated source has a function for each transpiled function, which
+/// can be registered as a raw function in a [Module][runestick::Module], and a
+/// `module` function which constructs a module with all of them. It only
+/// depends on the `runestick` crate.
+///
+/// The transpiled functions can only call other functions which are being
+/// transpiled, and only operate on integers, floats, and booleans. Anything
+/// else raises an error when transpiling.
+///
+/// The intended use is to freeze performance-critical functions into the host
+/// binary, typically by transpiling them in a build script and including the
+/// output with `include!`. The script can then call them like any other native
+/// function, once they've been removed from it and the module has been
+/// installed in its context.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Item;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let context = rune::default_context()?;
+/// let (unit, _) = rune::testing::compile(&context, r#"
+/// fn fib(n) { if n <= 1 { n } else { fib(n - 1) + fib(n - 2) } }
+/// "#)?;
+///
+/// let source = rune::transpile(&unit, &[Item::of(&["fib"])])?;
+/// assert!(source.contains("pub fn fib(stack: &mut runestick::Stack, args: usize)"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn transpile(unit: &Unit, items: &[Item]) -> Result<String, TranspileError> {
+    let mut offsets = unit
+        .iter_functions()
+        .filter_map(|(_, f)| match f {
+            UnitFn::Offset { offset, .. } => Some(*offset),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    offsets.sort_unstable();
+    let len = unit.iter_instructions().count();
+
+    let mut used = HashSet::new();
+    let mut functions = Vec::new();
+    let mut names = HashMap::new();
+
+    for item in items {
+        let hash = Hash::type_hash(item);
+
+        let (offset, args) = match unit.lookup(hash) {
+            Some(UnitFn::Offset {
+                offset,
+                call: Call::Immediate,
+                args,
+            }) => (offset, args),
+            Some(..) => return Err(TranspileError::UnsupportedFunction { item: item.clone() }),
+            None => return Err(TranspileError::MissingFunction { item: item.clone() }),
+        };
+
+        let mut path = Vec::new();
+
+        for component in item {
+            match component {
+                Component::String(s) => path.push(s.clone()),
+                _ => return Err(TranspileError::UnsupportedFunction { item: item.clone() }),
+            }
+        }
+
+        let end = offsets.iter().copied().find(|o| *o > offset).unwrap_or(len);
+
+        let name = rust_name(&path, &mut used);
+        names.insert(hash, (name.clone(), args));
+
+        functions.push(Function {
+            item,
+            path,
+            name,
+            offset,
+            end,
+            args,
+        });
+    }
+
+    let mut out = String::new();
+    writeln!(out, "// Generated by `rune::transpile`, do not edit.").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "/// Construct a module with the transpiled functions.").unwrap();
+    writeln!(
+        out,
+        "pub fn module() -> Result<runestick::Module, runestick::ContextError> {{"
+    )
+    .unwrap();
+    writeln!(out, "    let mut module = runestick::Module::default();").unwrap();
+
+    for f in &functions {
+        writeln!(out, "    module.raw_fn(&{:?}, {})?;", f.path, f.name).unwrap();
+    }
+
+    writeln!(out, "    Ok(module)").unwrap();
+    writeln!(out, "}}").unwrap();
+
+    for f in &functions {
+        writeln!(out).unwrap();
+        f.transpile(unit, &names, &mut out)?;
+    }
+
+    Ok(out)
+}
+
+/// A function being transpiled.
+struct Function<'a> {
+    item: &'a Item,
+    path: Vec<String>,
+    name: String,
+    offset: usize,
+    end: usize,
+    args: usize,
+}
+
+impl Function<'_> {
+    fn transpile(
+        &self,
+        unit: &Unit,
+        names: &HashMap<Hash, (String, usize)>,
+        out: &mut String,
+    ) -> Result<(), TranspileError> {
+        let insts = (self.offset..self.end)
+            .filter_map(|ip| unit.instruction_at(ip).copied())
+            .collect::<Vec<_>>();
+
+        // NB: every jump target starts a new block, as does every instruction
+        // following a jump or a return.
+        let mut blocks = BTreeSet::new();
+        blocks.insert(0);
+        let mut jumps = false;
+
+        for (n, inst) in insts.iter().enumerate() {
+            match *inst {
+                Inst::Jump { offset }
+                | Inst::JumpIf { offset }
+                | Inst::JumpIfNot { offset }
+                | Inst::JumpIfOrPop { offset }
+                | Inst::JumpIfNotOrPop { offset }
+                | Inst::PopAndJumpIfNot { offset, .. } => {
+                    blocks.insert(self.target(n, offset, insts.len())?);
+                    blocks.insert(n + 1);
+                    jumps = true;
+                }
+                Inst::Return | Inst::ReturnUnit => {
+                    blocks.insert(n + 1);
+                }
+                _ => (),
+            }
+        }
+
+        writeln!(out, "/// Transpiled from `{}`.", self.item).unwrap();
+        writeln!(
+            out,
+            "pub fn {}(stack: &mut runestick::Stack, args: usize) -> Result<(), runestick::VmError> {{",
+            self.name
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    let frame = runestick::NativeFrame::from_stack(stack, args, {})?;",
+            self.args
+        )
+        .unwrap();
+        writeln!(out, "    stack.push({}_impl(frame)?);", self.name).unwrap();
+        writeln!(out, "    Ok(())").unwrap();
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "fn {}_impl(mut frame: runestick::NativeFrame) -> Result<runestick::Value, runestick::VmError> {{",
+            self.name
+        )
+        .unwrap();
+
+        let starts = blocks.range(..insts.len()).copied().collect::<Vec<_>>();
+
+        if !jumps {
+            self.block(0, insts.len(), &insts, names, true, out)?;
+        } else {
+            writeln!(out, "    let mut block = 0;").unwrap();
+            writeln!(out).unwrap();
+            writeln!(out, "    loop {{").unwrap();
+            writeln!(out, "        match block {{").unwrap();
+
+            for (n, start) in starts.iter().enumerate() {
+                let end = starts.get(n + 1).copied().unwrap_or_else(|| insts.len());
+                writeln!(out, "            {} => {{", start).unwrap();
+                self.block(*start, end, &insts, names, false, out)?;
+                writeln!(out, "            }}").unwrap();
+            }
+
+            writeln!(out, "            _ => unreachable!(),").unwrap();
+            writeln!(out, "        }}").unwrap();
+            writeln!(out, "    }}").unwrap();
+        }
+
+        writeln!(out, "}}").unwrap();
+        Ok(())
+    }
+
+    /// Transpile the block of instructions in the given range, where a
+    /// `single` block is the whole function.
+    fn block(
+        &self,
+        start: usize,
+        end: usize,
+        insts: &[Inst],
+        names: &HashMap<Hash, (String, usize)>,
+        single: bool,
+        out: &mut String,
+    ) -> Result<(), TranspileError> {
+        let indent = if single { "    " } else { "                " };
+
+        for n in start..end {
+            let ip = self.offset + n;
+
+            let stmt = match insts[n] {
+                Inst::Unit => String::from("frame.push(runestick::Value::Unit);"),
+                Inst::Bool { value } => format!("frame.push(runestick::Value::Bool({}));", value),
+                Inst::Integer { number } => {
+                    format!("frame.push(runestick::Value::Integer({}));", number)
+                }
+                Inst::Float { number } => {
+                    format!("frame.push(runestick::Value::Float({}));", float(number))
+                }
+                Inst::Char { c } => format!("frame.push(runestick::Value::Char({:?}));", c),
+                Inst::Byte { b } => format!("frame.push(runestick::Value::Byte({}));", b),
+                Inst::Pop => String::from("frame.pop()?;"),
+                Inst::PopN { count } => format!("frame.popn({})?;", count),
+                Inst::Clean { count } => format!("frame.clean({})?;", count),
+                Inst::Copy { offset } => format!("frame.copy({})?;", offset),
+                Inst::Drop { offset } => format!("frame.drop({})?;", offset),
+                Inst::Dup => String::from("frame.dup()?;"),
+                Inst::Replace { offset } => format!("frame.replace({})?;", offset),
+                Inst::Add => String::from("frame.add()?;"),
+                Inst::Sub => String::from("frame.sub()?;"),
+                Inst::Mul => String::from("frame.mul()?;"),
+                Inst::Div => String::from("frame.div()?;"),
+                Inst::Rem => String::from("frame.rem()?;"),
+                // NB: concatenating only differs from adding for strings,
+                // which can't be transpiled.
+                Inst::AddAssign { offset } | Inst::ConcatAssign { offset } => {
+                    format!("frame.add_assign({})?;", offset)
+                }
+                Inst::SubAssign { offset } => format!("frame.sub_assign({})?;", offset),
+                Inst::MulAssign { offset } => format!("frame.mul_assign({})?;", offset),
+                Inst::DivAssign { offset } => format!("frame.div_assign({})?;", offset),
+                Inst::RemAssign { offset } => format!("frame.rem_assign({})?;", offset),
+                Inst::Lt => String::from("frame.lt()?;"),
+                Inst::Gt => String::from("frame.gt()?;"),
+                Inst::Lte => String::from("frame.lte()?;"),
+                Inst::Gte => String::from("frame.gte()?;"),
+                Inst::Eq => String::from("frame.eq()?;"),
+                Inst::Neq => String::from("frame.neq()?;"),
+                Inst::Not => String::from("frame.not()?;"),
+                Inst::And => String::from("frame.and()?;"),
+                Inst::Or => String::from("frame.or()?;"),
+                Inst::Call { hash, args } => {
+                    let (name, expected) = match names.get(&hash) {
+                        Some(entry) => entry,
+                        None => {
+                            return Err(TranspileError::UnsupportedCall {
+                                item: self.item.clone(),
+                                ip,
+                                hash,
+                            })
+                        }
+                    };
+
+                    format!(
+                        "let value = {}_impl(frame.call({}, {})?)?;\n{}frame.push(value);",
+                        name, args, expected, indent
+                    )
+                }
+                Inst::Jump { offset } => {
+                    format!("block = {};", self.target(n, offset, insts.len())?)
+                }
+                Inst::JumpIf { offset } => format!(
+                    "block = if frame.pop_bool()? {{ {} }} else {{ {} }};",
+                    self.target(n, offset, insts.len())?,
+                    n + 1
+                ),
+                Inst::JumpIfNot { offset } => format!(
+                    "block = if frame.pop_bool()? {{ {} }} else {{ {} }};",
+                    n + 1,
+                    self.target(n, offset, insts.len())?
+                ),
+                Inst::JumpIfOrPop { offset } => format!(
+                    "if frame.pop_bool()? {{\n{i}    frame.push(runestick::Value::Bool(true));\n{i}    block = {};\n{i}}} else {{\n{i}    block = {};\n{i}}}",
+                    self.target(n, offset, insts.len())?,
+                    n + 1,
+                    i = indent
+                ),
+                Inst::JumpIfNotOrPop { offset } => format!(
+                    "if frame.pop_bool()? {{\n{i}    block = {};\n{i}}} else {{\n{i}    frame.push(runestick::Value::Bool(false));\n{i}    block = {};\n{i}}}",
+                    n + 1,
+                    self.target(n, offset, insts.len())?,
+                    i = indent
+                ),
+                Inst::PopAndJumpIfNot { count, offset } => format!(
+                    "if frame.pop_bool()? {{\n{i}    block = {};\n{i}}} else {{\n{i}    frame.popn({})?;\n{i}    block = {};\n{i}}}",
+                    n + 1,
+                    count,
+                    self.target(n, offset, insts.len())?,
+                    i = indent
+                ),
+                Inst::Return if single && n + 1 == end => String::from("frame.pop()"),
+                Inst::ReturnUnit if single && n + 1 == end => {
+                    String::from("Ok(runestick::Value::Unit)")
+                }
+                Inst::Return => String::from("return frame.pop();"),
+                Inst::ReturnUnit => String::from("return Ok(runestick::Value::Unit);"),
+                inst => {
+                    return Err(TranspileError::UnsupportedInstruction {
+                        item: self.item.clone(),
+                        ip,
+                        inst,
+                    })
+                }
+            };
+
+            writeln!(out, "{}{}", indent, stmt).unwrap();
+        }
+
+        match insts.get(end - 1) {
+            Some(Inst::Jump { .. })
+            | Some(Inst::JumpIf { .. })
+            | Some(Inst::JumpIfNot { .. })
+            | Some(Inst::JumpIfOrPop { .. })
+            | Some(Inst::JumpIfNotOrPop { .. })
+            | Some(Inst::PopAndJumpIfNot { .. })
+            | Some(Inst::Return)
+            | Some(Inst::ReturnUnit) => (),
+            // NB: the block falls through into the next one.
+            _ if end < insts.len() => writeln!(out, "{}block = {};", indent, end).unwrap(),
+            _ => {
+                return Err(TranspileError::BadJump {
+                    item: self.item.clone(),
+                    ip: self.offset + end - 1,
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calculate the block targeted by a jump at the given instruction.
+    fn target(&self, n: usize, offset: isize, len: usize) -> Result<usize, TranspileError> {
+        let target = (n as isize) + 1 + offset;
+
+        if target < 0 || target as usize >= len {
+            return Err(TranspileError::BadJump {
+                item: self.item.clone(),
+                ip: self.offset + n,
+            });
+        }
+
+        Ok(target as usize)
+    }
+}
+
+/// Construct the name of the function generated for the given path.
+fn rust_name(path: &[String], used: &mut HashSet<String>) -> String {
+    let base = path
+        .join("_")
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' => c,
+            'A'..='Z' => c.to_ascii_lowercase(),
+            _ => '_',
+        })
+        .collect::<String>();
+
+    let mut name = base.clone();
+    let mut n = 0;
+
+    while RESERVED.contains(&name.as_str())
+        || used.contains(&name)
+        || used.contains(&format!("{}_impl", name))
+    {
+        n += 1;
+        name = format!("{}_{}", base, n);
+    }
+
+    used.insert(format!("{}_impl", name));
+    used.insert(name.clone());
+    name
+}
+
+/// Format a float as a Rust expression.
+fn float(number: f64) -> String {
+    if number.is_nan() {
+        String::from("f64::NAN")
+    } else if number.is_infinite() && number > 0.0 {
+        String::from("f64::INFINITY")
+    } else if number.is_infinite() {
+        String::from("f64::NEG_INFINITY")
+    } else {
+        format!("{:?}", number)
+    }
+}
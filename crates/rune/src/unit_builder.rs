@@ -7,13 +7,17 @@ use crate::assembly::{Assembly, AssemblyInst};
 use crate::ast;
 use crate::collections::HashMap;
 use crate::error::CompileResult;
+use crate::report::{CompileReport, FunctionReport};
 use crate::Resolve as _;
 use runestick::debug::{DebugArgs, DebugSignature};
 use runestick::{
-    Call, CompileMeta, Component, Context, DebugInfo, DebugInst, Hash, Inst, Item, Label, Names,
-    Source, Span, StaticString, Type, Unit, UnitFn, UnitTypeInfo,
+    Call, CompileMeta, Component, Context, DebugInfo, DebugInst, Frontmatter, Hash, Inst, Item,
+    Label, Names, Protocol, Source, Span, StaticString, Type, Unit, UnitAbi, UnitFn, UnitHooks,
+    UnitMemoize, UnitTypeInfo,
 };
+use std::collections::BTreeMap;
 use std::sync::Arc;
+use std::time::Instant;
 use thiserror::Error;
 
 /// Errors raised when building a new unit.
@@ -126,7 +130,7 @@ pub enum UnitBuilderError {
 }
 
 /// The key of an import.
-#[derive(Debug, PartialEq, Eq, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ImportKey {
     /// Where the import is located.
     pub item: Item,
@@ -189,8 +193,9 @@ pub struct UnitBuilder {
     /// All imports in the current unit.
     ///
     /// Only used to link against the current environment to make sure all
-    /// required units are present.
-    imports: HashMap<ImportKey, ImportEntry>,
+    /// required units are present. Ordered so that missing imports are
+    /// reported in a stable order.
+    imports: BTreeMap<ImportKey, ImportEntry>,
     /// Item metadata in the context.
     meta: HashMap<Item, CompileMeta>,
     /// Where functions are located in the collection of instructions.
@@ -216,14 +221,32 @@ pub struct UnitBuilder {
     static_object_keys: Vec<Box<[String]>>,
     /// Used to detect duplicates in the collection of static object keys.
     static_object_keys_rev: HashMap<Hash, usize>,
+    /// The items of statics declared in the unit, by slot.
+    statics: Vec<Item>,
+    /// Functions declared with `#[memoize]` in the unit, by slot.
+    memoized: Vec<UnitMemoize>,
     /// The current label count.
     label_count: usize,
     /// A collection of required function hashes.
-    required_functions: HashMap<Hash, Vec<(Span, usize)>>,
+    required_functions: BTreeMap<Hash, Vec<(Span, usize)>>,
     /// All available names in the context.
     names: Names,
+    /// Discriminants of enum variants, by the hash of the variant.
+    discriminants: HashMap<Hash, i64>,
+    /// Enum variants, by the hash of the enum and their discriminant.
+    variants: HashMap<(Hash, i64), Hash>,
+    /// Extensions which provide an instance function with the given name.
+    extensions: HashMap<String, Vec<Item>>,
     /// Debug info if available for unit.
     debug: Option<Box<DebugInfo>>,
+    /// Frontmatter declared in the entry source of the unit.
+    frontmatter: Frontmatter,
+    /// Lifecycle hooks declared in the unit.
+    hooks: UnitHooks,
+    /// Metadata on the environment the unit is compiled in.
+    abi: Option<UnitAbi>,
+    /// Statistics on assemblies added to the unit, if enabled.
+    report: Option<CompileReport>,
 }
 
 impl UnitBuilder {
@@ -341,10 +364,46 @@ impl UnitBuilder {
             self.static_strings,
             self.static_bytes,
             self.static_object_keys,
+            self.statics,
+            self.memoized,
+            self.discriminants,
+            self.variants,
             self.debug,
+            self.frontmatter,
+            self.hooks,
+            self.abi,
         )
     }
 
+    /// Set metadata on the environment the unit is compiled in.
+    pub(crate) fn set_abi(&mut self, abi: UnitAbi) {
+        self.abi = Some(abi);
+    }
+
+    /// Register the function with the given item as a lifecycle hook.
+    pub(crate) fn insert_lifecycle_hook(&mut self, item: &Item, shutdown: bool) {
+        let hash = Hash::type_hash(item);
+
+        if shutdown {
+            self.hooks.shutdown.push(hash);
+        } else {
+            self.hooks.init.push(hash);
+        }
+    }
+
+    /// Register the function with the given item as the finalizer of the
+    /// type with the given hash.
+    pub(crate) fn insert_finalizer(&mut self, type_hash: Hash, item: &Item) {
+        self.hooks
+            .finalizers
+            .insert(type_hash, Hash::type_hash(item));
+    }
+
+    /// Merge the frontmatter of an entry source into the unit.
+    pub(crate) fn extend_frontmatter(&mut self, frontmatter: Frontmatter) {
+        self.frontmatter.extend(frontmatter);
+    }
+
     /// Insert and access debug information.
     pub(crate) fn debug_info_mut(&mut self) -> &mut DebugInfo {
         self.debug.get_or_insert_with(Default::default)
@@ -475,6 +534,20 @@ impl UnitBuilder {
         Ok(new_slot)
     }
 
+    /// Allocate the slot of a new static with the given item.
+    pub(crate) fn new_static(&mut self, item: &Item) -> usize {
+        let slot = self.statics.len();
+        self.statics.push(item.clone());
+        slot
+    }
+
+    /// Allocate the slot of a new memoized function.
+    pub(crate) fn new_memoized(&mut self, memoize: UnitMemoize) -> usize {
+        let slot = self.memoized.len();
+        self.memoized.push(memoize);
+        slot
+    }
+
     fn lookup_import_by_name(&self, base: &Item, local: &Component) -> Option<Item> {
         let mut base = base.clone();
 
@@ -554,6 +627,59 @@ impl UnitBuilder {
         self.names.insert(item);
     }
 
+    /// Register the discriminant of an enum variant.
+    pub(crate) fn insert_discriminant(&mut self, enum_item: &Item, item: &Item, discriminant: i64) {
+        let hash = Hash::type_hash(item);
+        self.discriminants.insert(hash, discriminant);
+        self.variants
+            .insert((Hash::type_hash(enum_item), discriminant), hash);
+    }
+
+    /// Register that the given extension provides an instance function with
+    /// the given name.
+    pub(crate) fn insert_extension_function(&mut self, extension: &Item, name: &str) {
+        self.names.insert(extension);
+
+        let extensions = self.extensions.entry(name.to_owned()).or_default();
+
+        if !extensions.contains(extension) {
+            extensions.push(extension.clone());
+        }
+    }
+
+    /// Find the extensions in scope at `base` which provide an instance
+    /// function with the given name.
+    ///
+    /// An extension is in scope in the module it's declared in and its
+    /// children, and wherever it's imported.
+    pub(crate) fn lookup_extensions(&self, base: &Item, name: &str) -> Vec<Item> {
+        let extensions = match self.extensions.get(name) {
+            Some(extensions) => extensions,
+            None => return Vec::new(),
+        };
+
+        let path = base.as_vec();
+        let mut found = Vec::new();
+
+        for extension in extensions {
+            let mut parent = extension.as_vec();
+
+            let local = match parent.pop() {
+                Some(local) => local,
+                None => continue,
+            };
+
+            let in_scope = path.starts_with(&parent)
+                || self.lookup_import_by_name(base, &local).as_ref() == Some(extension);
+
+            if in_scope {
+                found.push(extension.clone());
+            }
+        }
+
+        found
+    }
+
     /// Declare a new struct.
     pub(crate) fn insert_meta(&mut self, meta: CompileMeta) -> Result<(), UnitBuilderError> {
         let item = match &meta {
@@ -576,6 +702,7 @@ impl UnitBuilder {
 
                 let info = UnitTypeInfo {
                     hash: tuple.hash,
+                    item: tuple.item.clone(),
                     value_type: Type::Hash(tuple.hash),
                 };
 
@@ -615,6 +742,7 @@ impl UnitBuilder {
 
                 let info = UnitTypeInfo {
                     hash: tuple.hash,
+                    item: tuple.item.clone(),
                     value_type: Type::Hash(enum_hash),
                 };
 
@@ -635,6 +763,7 @@ impl UnitBuilder {
 
                 let info = UnitTypeInfo {
                     hash,
+                    item: object.item.clone(),
                     value_type: Type::Hash(hash),
                 };
 
@@ -654,6 +783,7 @@ impl UnitBuilder {
 
                 let info = UnitTypeInfo {
                     hash,
+                    item: object.item.clone(),
                     value_type: Type::Hash(enum_hash),
                 };
 
@@ -670,6 +800,7 @@ impl UnitBuilder {
 
                 let info = UnitTypeInfo {
                     hash,
+                    item: item.clone(),
                     value_type: Type::Hash(hash),
                 };
 
@@ -682,11 +813,19 @@ impl UnitBuilder {
                 item.clone()
             }
             CompileMeta::Function { item, .. } => item.clone(),
+            CompileMeta::AssociatedFunction { item, .. } => item.clone(),
+            CompileMeta::InstanceFunction { item, .. } => item.clone(),
+            CompileMeta::Const { item, .. } => item.clone(),
+            CompileMeta::Static { item, .. } => item.clone(),
             CompileMeta::Closure { item, .. } => item.clone(),
             CompileMeta::AsyncBlock { item, .. } => item.clone(),
             CompileMeta::Macro { item, .. } => item.clone(),
         };
 
+        // Register the item so that script modules can be looked up and
+        // wildcard-imported like native ones.
+        self.names.insert(&item);
+
         if let Some(existing) = self.meta.insert(item, meta.clone()) {
             return Err(UnitBuilderError::MetaConflict {
                 current: meta,
@@ -714,6 +853,7 @@ impl UnitBuilder {
     ) -> Result<(), UnitBuilderError> {
         let offset = self.instructions.len();
         let hash = Hash::type_hash(&path);
+        let item = self.report_item(&path);
 
         self.functions_rev.insert(offset, hash);
         let info = UnitFn::Offset { offset, call, args };
@@ -726,8 +866,7 @@ impl UnitBuilder {
         }
 
         self.debug_info_mut().functions.insert(hash, signature);
-        self.add_assembly(source_id, assembly)?;
-        Ok(())
+        self.add_function_assembly(source_id, item, assembly)
     }
 
     /// Declare a new instance function at the current instruction pointer.
@@ -736,7 +875,7 @@ impl UnitBuilder {
         source_id: usize,
         path: Item,
         value_type: Type,
-        name: &str,
+        name: Hash,
         args: usize,
         assembly: Assembly,
         call: Call,
@@ -745,9 +884,9 @@ impl UnitBuilder {
         log::trace!("instance fn: {}", path);
 
         let offset = self.instructions.len();
-        let instance_fn = Hash::of(name);
-        let instance_fn = Hash::instance_function(value_type, instance_fn);
+        let instance_fn = Hash::instance_function(value_type, name);
         let hash = Hash::type_hash(&path);
+        let item = self.report_item(&path);
 
         let info = UnitFn::Offset { offset, call, args };
         let signature = DebugSignature::new(path, debug_args);
@@ -764,11 +903,68 @@ impl UnitBuilder {
             });
         }
 
-        self.debug_info_mut()
-            .functions
-            .insert(instance_fn, signature);
+        // NB: the function is reachable both as an instance function and
+        // through its path, which is how it's reverse looked up.
+        let debug_info = self.debug_info_mut();
+        debug_info.functions.insert(instance_fn, signature.clone());
+        debug_info.functions.insert(hash, signature);
         self.functions_rev.insert(offset, hash);
+        self.add_function_assembly(source_id, item, assembly)
+    }
+
+    /// Let the instance function with the given item implement the given
+    /// protocol for the type it's declared on.
+    pub(crate) fn insert_protocol_function(
+        &mut self,
+        value_type: Type,
+        protocol: Protocol,
+        item: &Item,
+    ) {
+        if let Some(info) = self.functions.get(&Hash::type_hash(item)).cloned() {
+            let hash = Hash::instance_function(value_type, protocol.hash);
+            self.functions.insert(hash, info);
+        }
+    }
+
+    /// Collect statistics on the assemblies added to the unit.
+    pub(crate) fn enable_report(&mut self) {
+        self.report = Some(CompileReport::default());
+    }
+
+    /// Take the collected statistics on assemblies, if enabled.
+    pub(crate) fn take_report(&mut self) -> Option<CompileReport> {
+        self.report.take()
+    }
+
+    /// Clone the item of a function if it should be reported.
+    fn report_item(&self, item: &Item) -> Option<Item> {
+        self.report.as_ref().map(|_| item.clone())
+    }
+
+    /// Add the assembly of a function, and report it if enabled.
+    fn add_function_assembly(
+        &mut self,
+        source_id: usize,
+        item: Option<Item>,
+        assembly: Assembly,
+    ) -> Result<(), UnitBuilderError> {
+        let item = match item {
+            Some(item) => item,
+            None => return self.add_assembly(source_id, assembly),
+        };
+
+        let start = Instant::now();
+        let offset = self.instructions.len();
         self.add_assembly(source_id, assembly)?;
+
+        if let Some(report) = &mut self.report {
+            report.assembly += start.elapsed();
+            report.functions.push(FunctionReport {
+                item,
+                instructions: self.instructions.len() - offset,
+            });
+        }
+
         Ok(())
     }
 
@@ -802,6 +998,16 @@ impl UnitBuilder {
                     let offset = translate_offset(pos, label, &assembly.labels)?;
                     self.instructions.push(Inst::JumpIfNot { offset });
                 }
+                AssemblyInst::JumpIfOrPop { label } => {
+                    comment = Some(format!("label:{}", label));
+                    let offset = translate_offset(pos, label, &assembly.labels)?;
+                    self.instructions.push(Inst::JumpIfOrPop { offset });
+                }
+                AssemblyInst::JumpIfNotOrPop { label } => {
+                    comment = Some(format!("label:{}", label));
+                    let offset = translate_offset(pos, label, &assembly.labels)?;
+                    self.instructions.push(Inst::JumpIfNotOrPop { offset });
+                }
                 AssemblyInst::JumpIfBranch { branch, label } => {
                     comment = Some(format!("label:{}", label));
                     let offset = translate_offset(pos, label, &assembly.labels)?;
@@ -863,16 +1069,27 @@ impl UnitBuilder {
     }
 
     /// Try to link the unit with the context, checking that all necessary
-    /// functions are provided.
+    /// functions are provided, and that the context grants the capabilities
+    /// they require.
     ///
     /// This can prevent a number of runtime errors, like missing functions.
     pub(crate) fn link(&self, context: &Context, errors: &mut LinkerErrors) -> bool {
         for (hash, spans) in &self.required_functions {
-            if self.functions.get(hash).is_none() && context.lookup(*hash).is_none() {
+            if self.functions.get(hash).is_some() {
+                continue;
+            }
+
+            if context.lookup(*hash).is_none() {
                 errors.errors.push(LinkerError::MissingFunction {
                     hash: *hash,
                     spans: spans.clone(),
                 });
+            } else if let Some(capability) = context.missing_capability(*hash) {
+                errors.errors.push(LinkerError::MissingCapability {
+                    hash: *hash,
+                    capability: capability.to_owned(),
+                    spans: spans.clone(),
+                });
             }
         }
 
@@ -890,6 +1107,15 @@ pub enum LinkerError {
         /// Spans where the function is used.
         spans: Vec<(Span, usize)>,
     },
+    /// A function requires a capability which the context doesn't grant.
+    MissingCapability {
+        /// Hash of the function.
+        hash: Hash,
+        /// The capability which is not granted.
+        capability: String,
+        /// Spans where the function is used.
+        spans: Vec<(Span, usize)>,
+    },
 }
 
 /// Linker errors.
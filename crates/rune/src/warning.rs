@@ -2,6 +2,7 @@ use runestick::Span;
 
 /// Compilation warning.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub struct Warning {
     /// The id of the source where the id happened.
     pub source_id: usize,
@@ -11,6 +12,7 @@ pub struct Warning {
 
 /// Compilation warning kind.
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "cache", derive(serde::Serialize, serde::Deserialize))]
 pub enum WarningKind {
     /// Item identified by the span is not used.
     NotUsed {
@@ -43,6 +45,53 @@ pub enum WarningKind {
         /// The context in which it is used.
         context: Option<Span>,
     },
+    /// A computed key in an object literal replaces a field that has already
+    /// been assigned. Only keys which are constant strings can be checked.
+    ComputedKeyShadowsField {
+        /// The span of the computed key.
+        span: Span,
+        /// The span of the field being replaced.
+        field: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A variable is modified while it's being iterated over.
+    MutateWhileIterating {
+        /// The span of the modification.
+        span: Span,
+        /// The span of the iterable expression in the loop.
+        iter: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A value which is not a boolean is used where a boolean is expected.
+    NotBoolean {
+        /// The span of the value.
+        span: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// A branch of a conditional expression doesn't produce a value, while
+    /// another branch does.
+    BranchWithoutValue {
+        /// The span of the branch without a value.
+        span: Span,
+        /// The span of a branch which produces a value.
+        value: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
+    /// The last statement in a block which is expected to produce a value is
+    /// a value terminated by a semi-colon, which causes the value to be
+    /// discarded.
+    SemiColonDiscardsValue {
+        /// Span where the semi-colon is.
+        span: Span,
+        /// The span of the value being discarded.
+        value: Span,
+        /// The context in which it is used.
+        context: Option<Span>,
+    },
     /// An unecessary semi-colon is used.
     UnecessarySemiColon {
         /// Span where the semi-colon is.
@@ -167,6 +216,110 @@ impl Warnings {
         }
     }
 
+    /// Add a warning indicating that a computed key in an object literal
+    /// replaces a field which has already been assigned.
+    ///
+    /// Like `#{x, ["x"]: 2}`.
+    pub fn computed_key_shadows_field(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        field: Span,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::ComputedKeyShadowsField {
+                    span,
+                    field,
+                    context,
+                },
+            });
+        }
+    }
+
+    /// Add a warning indicating that a variable is modified while it's being
+    /// iterated over.
+    ///
+    /// Like `for v in values { values.push(v); }`.
+    pub fn mutate_while_iterating(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        iter: Span,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::MutateWhileIterating {
+                    span,
+                    iter,
+                    context,
+                },
+            });
+        }
+    }
+
+    /// Add a warning indicating that a value which is not a boolean is used
+    /// where a boolean is expected.
+    ///
+    /// Like `if 1 { .. }`.
+    pub fn not_boolean(&mut self, source_id: usize, span: Span, context: Option<Span>) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::NotBoolean { span, context },
+            });
+        }
+    }
+
+    /// Add a warning indicating that a branch of a conditional expression
+    /// doesn't produce a value, while another branch does.
+    ///
+    /// Like `let n = if c { 1 } else { foo(); };`.
+    pub fn branch_without_value(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        value: Span,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::BranchWithoutValue {
+                    span,
+                    value,
+                    context,
+                },
+            });
+        }
+    }
+
+    /// Add a warning about a semi-colon discarding the value of a block.
+    ///
+    /// Like `fn add(a, b) { a + b; }`.
+    pub fn semi_colon_discards_value(
+        &mut self,
+        source_id: usize,
+        span: Span,
+        value: Span,
+        context: Option<Span>,
+    ) {
+        if let Some(w) = &mut self.warnings {
+            w.push(Warning {
+                source_id,
+                kind: WarningKind::SemiColonDiscardsValue {
+                    span,
+                    value,
+                    context,
+                },
+            });
+        }
+    }
+
     /// Add a warning about an unecessary semi-colon.
     pub fn uneccessary_semi_colon(&mut self, source_id: usize, span: Span) {
         if let Some(w) = &mut self.warnings {
@@ -176,6 +329,14 @@ impl Warnings {
             });
         }
     }
+
+    /// Add a warning which has already been constructed.
+    #[cfg(feature = "cache")]
+    pub(crate) fn push(&mut self, warning: Warning) {
+        if let Some(w) = &mut self.warnings {
+            w.push(warning);
+        }
+    }
 }
 
 impl<'a> IntoIterator for &'a Warnings {
@@ -0,0 +1,144 @@
+use crate::{Context, Hash};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// The version of the runtime, which units have to be compiled against to be
+/// compatible.
+pub const RUNTIME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Language features which the runtime supports running units compiled with.
+pub const SUPPORTED_FEATURES: &[&str] = &[
+    "checked-arithmetic",
+    "concat-in-place",
+    "macros",
+    "negative-indices",
+];
+
+/// Metadata on the environment a unit was compiled in, which is used to check
+/// that a unit is compatible with the runtime and context it's run with.
+///
+/// This is embedded in units by the compiler, and checked when the unit is
+/// used to construct a virtual machine through [Vm::try_new][crate::Vm::try_new]
+/// or installed in a [Runtime][crate::Runtime].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{AbiError, Context, UnitAbi};
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Context::with_default_modules()?;
+/// let abi = UnitAbi::new("0.6.16", context.fingerprint(), vec![String::from("macros")]);
+/// assert!(abi.check(&context).is_ok());
+///
+/// let abi = UnitAbi::new("0.6.16", context.fingerprint(), vec![String::from("time-travel")]);
+/// assert!(matches!(abi.check(&context), Err(AbiError::UnsupportedFeature { .. })));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnitAbi {
+    compiler_version: String,
+    runtime_version: String,
+    fingerprint: Hash,
+    features: Vec<String>,
+}
+
+impl UnitAbi {
+    /// Construct metadata for a unit compiled by the given version of the
+    /// compiler against the current version of the runtime, and a context
+    /// with the given [fingerprint][Context::fingerprint].
+    pub fn new<V>(compiler_version: V, fingerprint: Hash, mut features: Vec<String>) -> Self
+    where
+        V: Into<String>,
+    {
+        features.sort();
+        features.dedup();
+
+        Self {
+            compiler_version: compiler_version.into(),
+            runtime_version: RUNTIME_VERSION.to_owned(),
+            fingerprint,
+            features,
+        }
+    }
+
+    /// The version of the compiler the unit was compiled with.
+    pub fn compiler_version(&self) -> &str {
+        &self.compiler_version
+    }
+
+    /// The version of the runtime the unit was compiled against.
+    pub fn runtime_version(&self) -> &str {
+        &self.runtime_version
+    }
+
+    /// The fingerprint of the context the unit was compiled against.
+    pub fn fingerprint(&self) -> Hash {
+        self.fingerprint
+    }
+
+    /// The language features the unit was compiled with, in sorted order.
+    pub fn features(&self) -> &[String] {
+        &self.features
+    }
+
+    /// Check that a unit with this metadata can be run with the current
+    /// runtime and the given context.
+    pub fn check(&self, context: &Context) -> Result<(), AbiError> {
+        if self.runtime_version != RUNTIME_VERSION {
+            return Err(AbiError::RuntimeVersion {
+                unit: self.runtime_version.clone(),
+                runtime: RUNTIME_VERSION,
+            });
+        }
+
+        let fingerprint = context.fingerprint();
+
+        if self.fingerprint != fingerprint {
+            return Err(AbiError::Fingerprint {
+                unit: self.fingerprint,
+                context: fingerprint,
+            });
+        }
+
+        for feature in &self.features {
+            if !SUPPORTED_FEATURES.contains(&feature.as_str()) {
+                return Err(AbiError::UnsupportedFeature {
+                    feature: feature.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error raised when a unit is not compatible with the runtime or context it's
+/// used with.
+#[derive(Debug, Clone, Error)]
+pub enum AbiError {
+    /// The unit was compiled against a different version of the runtime.
+    #[error("unit was compiled for runtime version `{unit}`, but the runtime is version `{runtime}`, recompile the unit with a matching compiler")]
+    RuntimeVersion {
+        /// The runtime version of the unit.
+        unit: String,
+        /// The version of the current runtime.
+        runtime: &'static str,
+    },
+    /// The unit was compiled against a context with different native items.
+    #[error("unit was compiled against a context with fingerprint `{unit}`, but the context has fingerprint `{context}`, recompile the unit against the current context")]
+    Fingerprint {
+        /// The fingerprint of the context the unit was compiled against.
+        unit: Hash,
+        /// The fingerprint of the current context.
+        context: Hash,
+    },
+    /// The unit was compiled with a language feature the runtime doesn't
+    /// support.
+    #[error("unit was compiled with the feature `{feature}`, which is not supported by the runtime, recompile the unit without it")]
+    UnsupportedFeature {
+        /// The unsupported feature.
+        feature: String,
+    },
+}
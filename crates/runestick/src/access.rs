@@ -1,3 +1,4 @@
+use crate::Unit;
 use std::cell::Cell;
 use std::fmt;
 use std::future::Future;
@@ -9,6 +10,14 @@ use thiserror::Error;
 
 /// Flag to used to mark access as taken.
 const TAKEN: isize = isize::max_value();
+/// Snapshot state used when write access is denied because the value is
+/// frozen.
+const FROZEN: isize = isize::min_value();
+/// Marker for an unknown access site.
+const NO_SITE: RawSite = RawSite {
+    unit: 0,
+    ip: u32::max_value(),
+};
 
 /// An error raised while downcasting.
 #[derive(Debug, Error)]
@@ -44,6 +53,19 @@ pub enum AccessError {
     },
 }
 
+impl AccessError {
+    /// Get the snapshot of the access state at the time of the error, if
+    /// available.
+    pub fn snapshot(&self) -> Option<&Snapshot> {
+        match self {
+            Self::UnexpectedType { .. } => None,
+            Self::NotAccessibleRef { error } => Some(&error.0),
+            Self::NotAccessibleMut { error } => Some(&error.0),
+            Self::NotAccessibleTake { error } => Some(&error.0),
+        }
+    }
+}
+
 /// Error raised when tried to access for shared access but it was not
 /// accessible.
 #[derive(Debug, Error)]
@@ -67,73 +89,214 @@ pub struct NotAccessibleTake(Snapshot);
 /// Snapshot that can be used to indicate how the value was being accessed at
 /// the time of an error.
 #[derive(Debug)]
-#[repr(transparent)]
-pub struct Snapshot(isize);
+pub struct Snapshot {
+    /// The access state.
+    state: isize,
+    /// The instruction at which the existing access was acquired, if known.
+    site: RawSite,
+}
+
+impl Snapshot {
+    /// The instruction pointer at which the access which caused the conflict
+    /// was acquired, if it is known.
+    ///
+    /// This is only tracked for long-lived accesses, like the ones held by
+    /// native functions or generators over the course of their execution, and
+    /// only while the virtual machine is being traced. The instruction pointer
+    /// is relative to the unit which was executing at the time, see
+    /// [site_in][Snapshot::site_in].
+    pub fn site(&self) -> Option<usize> {
+        self.site.ip()
+    }
+
+    /// The instruction pointer at which the access which caused the conflict
+    /// was acquired, if it was acquired while executing the given unit.
+    pub fn site_in(&self, unit: &Unit) -> Option<usize> {
+        if self.site.unit != unit as *const Unit as usize {
+            return None;
+        }
+
+        self.site.ip()
+    }
+}
 
 impl fmt::Display for Snapshot {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.0 {
-            0 => write!(f, "fully accessible"),
-            1 => write!(f, "exclusively accessed"),
-            TAKEN => write!(f, "moved"),
-            n if n < 0 => write!(f, "shared by {}", -n),
-            n => write!(f, "invalidly marked ({})", n),
+        match self.state {
+            0 => write!(f, "fully accessible")?,
+            1 => write!(f, "exclusively accessed")?,
+            TAKEN => write!(f, "moved")?,
+            FROZEN => write!(f, "frozen")?,
+            n if n < 0 => write!(f, "shared by {}", -n)?,
+            n => write!(f, "invalidly marked ({})", n)?,
+        }
+
+        if let Some(site) = self.site.ip() {
+            write!(f, " (accessed at instruction {})", site)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The unit and instruction at which an access was acquired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RawSite {
+    /// The address of the unit which was executing, used to identify it.
+    unit: usize,
+    /// The instruction pointer in the unit.
+    ip: u32,
+}
+
+impl RawSite {
+    /// Convert into the instruction pointer it refers to.
+    #[inline]
+    pub(crate) fn ip(self) -> Option<usize> {
+        if self.ip == NO_SITE.ip {
+            None
+        } else {
+            Some(self.ip as usize)
         }
     }
 }
 
+thread_local! {
+    /// The instruction currently being executed on this thread.
+    static CURRENT_SITE: Cell<RawSite> = Cell::new(NO_SITE);
+}
+
+/// Indicate the instruction currently being executed, which is recorded as the
+/// site of any long-lived access acquired while it is executing.
+///
+/// This is only called by virtual machines which are being traced, since it
+/// has a cost for every instruction executed.
+#[inline]
+pub(crate) fn set_site(unit: &Unit, ip: usize) {
+    let ip = if ip < NO_SITE.ip as usize {
+        ip as u32
+    } else {
+        NO_SITE.ip
+    };
+
+    let site = RawSite {
+        unit: unit as *const Unit as usize,
+        ip,
+    };
+
+    CURRENT_SITE.with(|current| current.set(site));
+}
+
+/// Get the site of the instruction most recently executed on this thread.
+#[inline]
+pub(crate) fn current_site() -> RawSite {
+    CURRENT_SITE.with(Cell::get)
+}
+
+/// Restore a site previously returned by [current_site], which is used once a
+/// nested virtual machine returns so that accesses acquired by the caller
+/// aren't attributed to it.
+#[inline]
+pub(crate) fn restore_site(site: RawSite) {
+    CURRENT_SITE.with(|current| current.set(site));
+}
+
 #[derive(Clone)]
-pub(crate) struct Access(Cell<isize>);
+pub(crate) struct Access {
+    /// The current access state.
+    state: Cell<isize>,
+    /// Indicates if the value has been frozen.
+    frozen: Cell<bool>,
+    /// The instruction at which the current long-lived access was acquired.
+    site: Cell<RawSite>,
+}
 
 impl Access {
     /// Construct a new default access.
     pub(crate) const fn new() -> Self {
-        Self(Cell::new(0))
+        Self {
+            state: Cell::new(0),
+            frozen: Cell::new(false),
+            site: Cell::new(NO_SITE),
+        }
+    }
+
+    /// Take a snapshot of the current state of the access.
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            state: self.state.get(),
+            site: self.site.get(),
+        }
+    }
+
+    /// Record the currently executing instruction as the site of the current
+    /// access.
+    #[inline]
+    pub(crate) fn record_site(&self) {
+        self.site.set(current_site());
+    }
+
+    /// Test if the access has been frozen.
+    #[inline]
+    pub(crate) fn is_frozen(&self) -> bool {
+        self.frozen.get()
+    }
+
+    /// Freeze the access, preventing any future exclusive access or taking.
+    #[inline]
+    pub(crate) fn freeze(&self) {
+        self.frozen.set(true);
     }
 
     /// Test if we have shared access without modifying the internal count.
     #[inline]
     pub(crate) fn is_shared(&self) -> bool {
-        self.0.get().wrapping_sub(1) < 0
+        self.state.get().wrapping_sub(1) < 0
     }
 
     /// Test if we have exclusive access without modifying the internal count.
     #[inline]
     pub(crate) fn is_exclusive(&self) -> bool {
-        self.0.get() == 0
+        self.state.get() == 0
     }
 
     /// Test if the data has been taken.
     #[inline]
     pub(crate) fn is_taken(&self) -> bool {
-        self.0.get() == isize::max_value()
+        self.state.get() == isize::max_value()
     }
 
     /// Mark that we want shared access to the given access token.
     #[inline]
     pub(crate) fn shared(&self) -> Result<RawBorrowedRef, NotAccessibleRef> {
-        let state = self.0.get();
+        let state = self.state.get();
         let n = state.wrapping_sub(1);
 
         if n >= 0 {
-            return Err(NotAccessibleRef(Snapshot(state)));
+            return Err(NotAccessibleRef(self.snapshot()));
         }
 
-        self.0.set(n);
+        self.state.set(n);
         Ok(RawBorrowedRef { access: self })
     }
 
     /// Mark that we want exclusive access to the given access token.
     #[inline]
     pub(crate) fn exclusive(&self) -> Result<RawBorrowedMut, NotAccessibleMut> {
-        let state = self.0.get();
+        if self.frozen.get() {
+            return Err(NotAccessibleMut(Snapshot {
+                state: FROZEN,
+                site: NO_SITE,
+            }));
+        }
+
+        let state = self.state.get();
         let n = state.wrapping_add(1);
 
         if n != 1 {
-            return Err(NotAccessibleMut(Snapshot(state)));
+            return Err(NotAccessibleMut(self.snapshot()));
         }
 
-        self.0.set(n);
+        self.state.set(n);
         Ok(RawBorrowedMut { access: self })
     }
 
@@ -142,44 +305,71 @@ impl Access {
     /// I.e. whatever guarded data is no longer available.
     #[inline]
     pub(crate) fn take(&self) -> Result<RawTakeGuard, NotAccessibleTake> {
-        let state = self.0.get();
+        if self.frozen.get() {
+            return Err(NotAccessibleTake(Snapshot {
+                state: FROZEN,
+                site: NO_SITE,
+            }));
+        }
+
+        self.revoke()
+    }
+
+    /// Mark the given access as "taken", even if it has been frozen.
+    ///
+    /// This is used when the guarded data is going away regardless, like when
+    /// access to data owned by the host is revoked.
+    #[inline]
+    pub(crate) fn revoke(&self) -> Result<RawTakeGuard, NotAccessibleTake> {
+        let state = self.state.get();
 
         if state != 0 {
-            return Err(NotAccessibleTake(Snapshot(state)));
+            return Err(NotAccessibleTake(self.snapshot()));
         }
 
-        self.0.set(isize::max_value());
+        self.state.set(isize::max_value());
         Ok(RawTakeGuard { access: self })
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_shared(&self) {
-        let b = self.0.get().wrapping_add(1);
+        let b = self.state.get().wrapping_add(1);
         debug_assert!(b <= 0);
-        self.0.set(b);
+        self.state.set(b);
+
+        if b == 0 {
+            self.site.set(NO_SITE);
+        }
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_exclusive(&self) {
-        let b = self.0.get().wrapping_sub(1);
+        let b = self.state.get().wrapping_sub(1);
         debug_assert!(b == 0);
-        self.0.set(b);
+        self.state.set(b);
+        self.site.set(NO_SITE);
     }
 
     /// Unshare the current access.
     #[inline]
     fn release_take(&self) {
-        let b = self.0.get();
+        let b = self.state.get();
         debug_assert!(b == isize::max_value());
-        self.0.set(0);
+        self.state.set(0);
     }
 }
 
 impl fmt::Debug for Access {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", Snapshot(self.0.get()))
+        write!(f, "{}", self.snapshot())?;
+
+        if self.frozen.get() {
+            write!(f, " (frozen)")?;
+        }
+
+        Ok(())
     }
 }
 
@@ -364,3 +554,33 @@ where
         Pin::new(&mut **this).poll(cx)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::set_site;
+    use crate::{Shared, Unit};
+
+    #[test]
+    fn test_access_site() {
+        let unit = Unit::default();
+        let other = Unit::default();
+        let shared = Shared::new(1u32);
+
+        set_site(&unit, 42);
+        let guard = shared.clone().owned_mut().unwrap();
+        set_site(&unit, 100);
+
+        let error = shared.borrow_ref().unwrap_err();
+        let snapshot = error.snapshot().unwrap();
+        assert_eq!(snapshot.site(), Some(42));
+        assert_eq!(snapshot.site_in(&unit), Some(42));
+        assert_eq!(snapshot.site_in(&other), None);
+        assert_eq!(
+            error.to_string(),
+            "cannot read, value is exclusively accessed (accessed at instruction 42)"
+        );
+
+        drop(guard);
+        assert!(shared.borrow_ref().is_ok());
+    }
+}
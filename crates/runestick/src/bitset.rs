@@ -0,0 +1,219 @@
+use crate::VmError;
+use std::fmt;
+
+const BITS: usize = 64;
+
+/// A growable set of bits, like permission masks or sets of options.
+///
+/// Bits are identified by their index, and the set can be converted to and
+/// from integers for interoperating with C-style APIs.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::BitSet;
+///
+/// let mut set = BitSet::from_u64(0b101);
+/// set.insert(3);
+///
+/// assert!(set.contains(0));
+/// assert!(!set.contains(1));
+/// assert_eq!(set.to_u64(), Some(0b1101));
+/// assert_eq!(set.to_vec(), vec![0, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    /// Words of the set, without trailing empty words.
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// Construct a new empty bit set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a bit set out of the bits in the given integer.
+    pub fn from_u64(bits: u64) -> Self {
+        let mut this = Self { words: vec![bits] };
+        this.trim();
+        this
+    }
+
+    /// Construct a bit set out of the bits in the given signed integer.
+    pub fn from_int(bits: i64) -> Self {
+        Self::from_u64(bits as u64)
+    }
+
+    /// Convert into an integer, or `None` if a bit above 63 is set.
+    pub fn to_u64(&self) -> Option<u64> {
+        match &self.words[..] {
+            [] => Some(0),
+            [word] => Some(*word),
+            _ => None,
+        }
+    }
+
+    /// Convert into a signed integer, or error if a bit above 63 is set.
+    pub fn to_int(&self) -> Result<i64, VmError> {
+        match self.to_u64() {
+            Some(bits) => Ok(bits as i64),
+            None => Err(VmError::panic("bit set doesn't fit in an integer")),
+        }
+    }
+
+    /// Insert the given bit, returning `true` if it wasn't already set.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        let (word, mask) = (bit / BITS, 1 << (bit % BITS));
+
+        if self.words.len() <= word {
+            self.words.resize(word + 1, 0);
+        }
+
+        let inserted = self.words[word] & mask == 0;
+        self.words[word] |= mask;
+        inserted
+    }
+
+    /// Remove the given bit, returning `true` if it was set.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        if !self.contains(bit) {
+            return false;
+        }
+
+        self.words[bit / BITS] &= !(1 << (bit % BITS));
+        self.trim();
+        true
+    }
+
+    /// Test if the given bit is set.
+    pub fn contains(&self, bit: usize) -> bool {
+        match self.words.get(bit / BITS) {
+            Some(word) => word & (1 << (bit % BITS)) != 0,
+            None => false,
+        }
+    }
+
+    /// Test if every bit in `other` is also set in this set.
+    pub fn contains_all(&self, other: &Self) -> bool {
+        other
+            .words
+            .iter()
+            .enumerate()
+            .all(|(n, word)| self.words.get(n).copied().unwrap_or_default() & word == *word)
+    }
+
+    /// Test if any bit in `other` is also set in this set.
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.words.iter().zip(&other.words).any(|(a, b)| a & b != 0)
+    }
+
+    /// The number of bits which are set.
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Test if no bits are set.
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Clear all bits.
+    pub fn clear(&mut self) {
+        self.words.clear();
+    }
+
+    /// The indexes of the bits which are set, in ascending order.
+    pub fn to_vec(&self) -> Vec<usize> {
+        let mut bits = Vec::with_capacity(self.len());
+
+        for (n, word) in self.words.iter().enumerate() {
+            for bit in 0..BITS {
+                if word & (1 << bit) != 0 {
+                    bits.push(n * BITS + bit);
+                }
+            }
+        }
+
+        bits
+    }
+
+    /// The bits which are set in either set.
+    pub fn union(&self, other: &Self) -> Self {
+        let mut this = self.clone();
+        this.union_with(other);
+        this
+    }
+
+    /// The bits which are set in both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut this = self.clone();
+        this.intersect_with(other);
+        this
+    }
+
+    /// The bits which are set in exactly one of the sets.
+    pub fn symmetric_difference(&self, other: &Self) -> Self {
+        let mut this = self.clone();
+        this.symmetric_difference_with(other);
+        this
+    }
+
+    /// The bits which are set in this set but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut this = self.clone();
+        this.difference_with(other);
+        this
+    }
+
+    /// Set the bits which are set in `other`.
+    pub fn union_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a | b);
+    }
+
+    /// Clear the bits which are not set in `other`.
+    pub fn intersect_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a & b);
+    }
+
+    /// Toggle the bits which are set in `other`.
+    pub fn symmetric_difference_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a ^ b);
+    }
+
+    /// Clear the bits which are set in `other`.
+    pub fn difference_with(&mut self, other: &Self) {
+        self.zip_with(other, |a, b| a & !b);
+    }
+
+    /// Combine every word in this set with the corresponding word in `other`.
+    fn zip_with(&mut self, other: &Self, op: impl Fn(u64, u64) -> u64) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+
+        for (n, word) in self.words.iter_mut().enumerate() {
+            *word = op(*word, other.words.get(n).copied().unwrap_or_default());
+        }
+
+        self.trim();
+    }
+
+    /// Remove trailing empty words, so that equal sets compare equal.
+    fn trim(&mut self) {
+        while let Some(0) = self.words.last() {
+            self.words.pop();
+        }
+    }
+}
+
+impl fmt::Display for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.to_vec()).finish()
+    }
+}
+
+impl_external!(BitSet);
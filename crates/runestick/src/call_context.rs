@@ -0,0 +1,157 @@
+use crate::statics::Statics;
+use crate::{
+    Args, Context, Events, FromValue, Function, IntoHash, Stack, Unit, UnitFn, Vm, VmError,
+    VmErrorKind,
+};
+use std::sync::Arc;
+
+/// The context in which a native function is called.
+///
+/// Apart from the stack which holds the arguments of the call, this gives
+/// access to the context and unit the caller is running with, which allows
+/// native functions to call back into other functions. Callbacks run on their
+/// own stack, so they never alias the stack of the caller.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{CallContext, FromValue as _, Hash, Module, Value, VmError};
+///
+/// fn call_twice(cx: &mut CallContext<'_>, _: usize) -> Result<(), VmError> {
+///     let name = String::from_value(cx.stack_mut().pop()?)?;
+///     let hash = Hash::type_hash(&[name.as_str()]);
+///
+///     let a = cx.call_fn::<_, _, i64>(hash, (1i64,))?;
+///     let b = cx.call_fn::<_, _, i64>(hash, (a,))?;
+///
+///     cx.stack_mut().push(Value::Integer(b));
+///     Ok(())
+/// }
+///
+/// # fn main() -> runestick::Result<()> {
+/// let mut module = Module::default();
+/// module.raw_fn_with_context(&["call_twice"], call_twice)?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct CallContext<'a> {
+    stack: &'a mut Stack,
+    context: &'a Arc<Context>,
+    unit: &'a Arc<Unit>,
+    events: &'a Events,
+    statics: &'a Statics,
+}
+
+impl<'a> CallContext<'a> {
+    /// Construct a new call context.
+    pub(crate) fn new(
+        stack: &'a mut Stack,
+        context: &'a Arc<Context>,
+        unit: &'a Arc<Unit>,
+        events: &'a Events,
+        statics: &'a Statics,
+    ) -> Self {
+        Self {
+            stack,
+            context,
+            unit,
+            events,
+            statics,
+        }
+    }
+
+    /// Access the stack of the call.
+    pub fn stack(&self) -> &Stack {
+        self.stack
+    }
+
+    /// Access the stack of the call mutably.
+    pub fn stack_mut(&mut self) -> &mut Stack {
+        self.stack
+    }
+
+    /// Access the context the call is running with.
+    pub fn context(&self) -> &Arc<Context> {
+        self.context
+    }
+
+    /// Access the unit the call is running with.
+    pub fn unit(&self) -> &Arc<Unit> {
+        self.unit
+    }
+
+    /// Access the event handlers of the virtual machine the call is running
+    /// in.
+    pub fn events(&self) -> &Events {
+        self.events
+    }
+
+    /// Access the statics of the virtual machine the call is running in.
+    pub(crate) fn statics(&self) -> &Statics {
+        self.statics
+    }
+
+    /// Test if a function with the given name is available, either in the
+    /// unit or in the context.
+    pub fn contains_fn<N>(&self, name: N) -> bool
+    where
+        N: IntoHash,
+    {
+        let hash = name.into_hash();
+        self.unit.lookup(hash).is_some() || self.context.lookup(hash).is_some()
+    }
+
+    /// Call the function with the given name and arguments.
+    ///
+    /// Functions in the unit are executed to completion in a virtual machine
+    /// sharing the unit and context of the caller, and native functions are
+    /// called directly.
+    pub fn call_fn<N, A, T>(&self, name: N, args: A) -> Result<T, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+        T: FromValue,
+    {
+        let hash = name.into_hash();
+
+        if let Some(UnitFn::Offset { .. }) = self.unit.lookup(hash) {
+            let vm = Vm::new(self.context.clone(), self.unit.clone())
+                .with_events(self.events.clone())
+                .with_statics(self.statics.clone());
+            let value = vm.call(hash, args)?.complete()?;
+            return T::from_value(value);
+        }
+
+        let handler = self
+            .context
+            .lookup(hash)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
+
+        let count = A::count();
+        let mut stack = Stack::with_capacity(count);
+        args.into_stack(&mut stack)?;
+        handler(
+            &mut CallContext::new(
+                &mut stack,
+                self.context,
+                self.unit,
+                self.events,
+                self.statics,
+            ),
+            count,
+        )?;
+        T::from_value(stack.pop()?)
+    }
+
+    /// Call the given function, like a closure passed in as an argument.
+    ///
+    /// Any virtual machine used to run the function shares the event handlers
+    /// and statics of the caller.
+    pub fn call_function<A, T>(&self, function: &Function, args: A) -> Result<T, VmError>
+    where
+        A: Args,
+        T: FromValue,
+    {
+        function.call_with_events(args, self.events, self.statics)
+    }
+}
@@ -0,0 +1,195 @@
+use crate::{Context, ContextSignature, Hash, Item, Unit, Value, VmError};
+use std::fmt;
+use std::sync::Arc;
+
+/// A hook which is invoked around function calls in a virtual machine, as
+/// installed with [Vm::add_call_hook][crate::Vm::add_call_hook].
+///
+/// Hooks see every call to a script or native function by name, including
+/// instance functions. Calls through function pointers and closures, and calls
+/// made by the virtual machine to implement protocols are not hooked.
+///
+/// A hook can veto a call by returning an error from [before][Self::before],
+/// replace the call entirely with [CallAction::Replace], or wrap it by
+/// inspecting and modifying the value it returns in [after][Self::after].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{CallAction, CallHook, CallInfo, Context, Unit, Vm, VmError};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::sync::Arc;
+///
+/// struct RateLimit {
+///     calls: AtomicUsize,
+///     limit: usize,
+/// }
+///
+/// impl CallHook for RateLimit {
+///     fn before(&self, _: &CallInfo<'_>) -> Result<CallAction, VmError> {
+///         if self.calls.fetch_add(1, Ordering::SeqCst) >= self.limit {
+///             return Err(VmError::panic("too many calls"));
+///         }
+///
+///         Ok(CallAction::Continue)
+///     }
+/// }
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// let unit = Arc::new(Unit::default());
+///
+/// let mut vm = Vm::new(context, unit);
+/// vm.add_call_hook(RateLimit {
+///     calls: AtomicUsize::new(0),
+///     limit: 100,
+/// });
+/// # Ok(())
+/// # }
+/// ```
+pub trait CallHook {
+    /// Called before a function is called, with its arguments on the stack.
+    ///
+    /// Returning an error aborts the call and the virtual machine with it.
+    fn before(&self, call: &CallInfo<'_>) -> Result<CallAction, VmError> {
+        let _ = call;
+        Ok(CallAction::Continue)
+    }
+
+    /// Called after a function has returned, with the value it returned.
+    ///
+    /// For async functions, generators and streams this is the value
+    /// constructed by the call, and not the value it eventually produces. This
+    /// isn't called for calls which were replaced by a hook.
+    fn after(&self, call: &CallInfo<'_>, value: &mut Value) -> Result<(), VmError> {
+        let _ = (call, value);
+        Ok(())
+    }
+}
+
+/// What to do with a call after a [CallHook] has seen it.
+#[derive(Debug)]
+pub enum CallAction {
+    /// Perform the call as normal.
+    Continue,
+    /// Skip the call and use the given value as its result. Hooks installed
+    /// after the one which replaced the call don't see it.
+    Replace(Value),
+}
+
+/// Information about a function call passed to a [CallHook].
+pub struct CallInfo<'a> {
+    hash: Hash,
+    args: usize,
+    native: bool,
+    context: &'a Context,
+    unit: &'a Unit,
+}
+
+impl<'a> CallInfo<'a> {
+    /// Construct information about a call.
+    pub(crate) fn new(
+        hash: Hash,
+        args: usize,
+        native: bool,
+        context: &'a Context,
+        unit: &'a Unit,
+    ) -> Self {
+        Self {
+            hash,
+            args,
+            native,
+            context,
+            unit,
+        }
+    }
+
+    /// The hash of the called function.
+    pub fn hash(&self) -> Hash {
+        self.hash
+    }
+
+    /// The number of arguments the function is called with, including the
+    /// instance for instance functions.
+    pub fn args(&self) -> usize {
+        self.args
+    }
+
+    /// Test if the called function is a native function.
+    pub fn is_native(&self) -> bool {
+        self.native
+    }
+
+    /// Resolve the item of the called function.
+    ///
+    /// Script functions can only be resolved if the unit has debug
+    /// information.
+    pub fn item(&self) -> Option<Item> {
+        if self.native {
+            return match self.context.lookup_signature(self.hash)? {
+                ContextSignature::Function { path, .. } => Some(path.clone()),
+                ContextSignature::Instance { path, name, .. } => Some(path.extended(name.as_str())),
+            };
+        }
+
+        let signature = self.unit.debug_info()?.functions.get(&self.hash)?;
+        Some(signature.path.clone())
+    }
+}
+
+impl fmt::Debug for CallInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallInfo")
+            .field("hash", &self.hash)
+            .field("args", &self.args)
+            .field("native", &self.native)
+            .finish()
+    }
+}
+
+/// The hooks installed in a virtual machine.
+#[derive(Default, Clone)]
+pub(crate) struct CallHooks {
+    hooks: Vec<Arc<dyn CallHook>>,
+}
+
+impl CallHooks {
+    /// Install a hook after the existing ones.
+    pub(crate) fn push(&mut self, hook: Arc<dyn CallHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Test if there are no hooks installed.
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run the `before` hook of every hook in order, stopping at the first
+    /// one which doesn't continue the call.
+    pub(crate) fn before(&self, call: &CallInfo<'_>) -> Result<CallAction, VmError> {
+        for hook in &self.hooks {
+            if let CallAction::Replace(value) = hook.before(call)? {
+                return Ok(CallAction::Replace(value));
+            }
+        }
+
+        Ok(CallAction::Continue)
+    }
+
+    /// Run the `after` hook of every hook in reverse order, so that the first
+    /// hook installed wraps all the others.
+    pub(crate) fn after(&self, call: &CallInfo<'_>, value: &mut Value) -> Result<(), VmError> {
+        for hook in self.hooks.iter().rev() {
+            hook.after(call, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for CallHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "CallHooks({})", self.hooks.len())
+    }
+}
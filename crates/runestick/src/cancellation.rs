@@ -0,0 +1,62 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A handle which can be used to cancel the execution of a virtual machine,
+/// as returned by [Vm::cancellation_handle][crate::Vm::cancellation_handle].
+///
+/// Handles can be sent to and triggered from other threads. The virtual
+/// machine checks whether it's been cancelled before calling functions,
+/// jumping backwards, and awaiting futures, so that loops and recursion are
+/// always interrupted. When it has, execution stops with
+/// [VmHaltInfo::Cancelled][crate::VmHaltInfo::Cancelled] *before* the
+/// instruction is executed.
+///
+/// Cancellation can either be abortive or resumable. The host aborts the
+/// execution by dropping it, or resumes it from where it stopped by calling
+/// [reset][Self::reset] followed by resuming the execution.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Unit, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// let unit = Arc::new(Unit::default());
+///
+/// let mut vm = Vm::new(context, unit);
+/// let handle = vm.cancellation_handle();
+///
+/// std::thread::spawn(move || handle.cancel()).join().unwrap();
+/// assert!(vm.cancellation_handle().is_cancelled());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationHandle {
+    /// Construct a new handle which hasn't been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the execution of every virtual machine using this handle.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Test if the handle has been cancelled.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Reset the handle, so that cancelled executions can be resumed.
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Release);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::collections::HashSet;
-use crate::{Hash, Item, Type};
+use crate::{Hash, Item, Span, Type};
 use std::fmt;
 use std::sync::Arc;
 
@@ -58,6 +58,49 @@ pub enum CompileMeta {
         value_type: Type,
         /// The item of the function declaration.
         item: Item,
+        /// The signature of the function, if the number of arguments it
+        /// takes is known.
+        signature: Option<CompileMetaSignature>,
+    },
+    /// A function declared in an impl block which doesn't take `self`, like
+    /// `Point::new`.
+    AssociatedFunction {
+        /// The value type associated with this meta item.
+        value_type: Type,
+        /// The item of the function declaration.
+        item: Item,
+        /// The signature of the function, if the number of arguments it
+        /// takes is known.
+        signature: Option<CompileMetaSignature>,
+    },
+    /// A function declared in an impl block which takes `self`, like
+    /// `Point::len`.
+    InstanceFunction {
+        /// The value type associated with this meta item.
+        value_type: Type,
+        /// The item of the function declaration.
+        item: Item,
+        /// The signature of the function, if the number of arguments it
+        /// takes is known.
+        signature: Option<CompileMetaSignature>,
+    },
+    /// A constant declared in an impl block, like `Point::ORIGIN`.
+    ///
+    /// The value of the constant is produced by calling the function with the
+    /// hash of its item.
+    Const {
+        /// The item of the constant.
+        item: Item,
+    },
+    /// A static declared with a module-level `let` binding.
+    ///
+    /// The value of the static is produced by calling the function with the
+    /// hash of its item, which initializes it on first use.
+    Static {
+        /// The item of the static.
+        item: Item,
+        /// The slot the value of the static is stored in.
+        slot: usize,
     },
     /// A closure.
     Closure {
@@ -94,6 +137,10 @@ impl CompileMeta {
             CompileMeta::StructVariant { object, .. } => &object.item,
             CompileMeta::Enum { item, .. } => item,
             CompileMeta::Function { item, .. } => item,
+            CompileMeta::AssociatedFunction { item, .. } => item,
+            CompileMeta::InstanceFunction { item, .. } => item,
+            CompileMeta::Const { item, .. } => item,
+            CompileMeta::Static { item, .. } => item,
             CompileMeta::Closure { item, .. } => item,
             CompileMeta::AsyncBlock { item, .. } => item,
             CompileMeta::Macro { item, .. } => item,
@@ -109,6 +156,10 @@ impl CompileMeta {
             Self::StructVariant { .. } => None,
             Self::Enum { value_type, .. } => Some(*value_type),
             Self::Function { value_type, .. } => Some(*value_type),
+            Self::AssociatedFunction { value_type, .. } => Some(*value_type),
+            Self::InstanceFunction { value_type, .. } => Some(*value_type),
+            Self::Const { .. } => None,
+            Self::Static { .. } => None,
             Self::Closure { value_type, .. } => Some(*value_type),
             Self::AsyncBlock { value_type, .. } => Some(*value_type),
             Self::Macro { .. } => None,
@@ -137,6 +188,18 @@ impl fmt::Display for CompileMeta {
             Self::Function { item, .. } => {
                 write!(fmt, "fn {}", item)?;
             }
+            Self::AssociatedFunction { item, .. } => {
+                write!(fmt, "associated fn {}", item)?;
+            }
+            Self::InstanceFunction { item, .. } => {
+                write!(fmt, "instance fn {}", item)?;
+            }
+            Self::Const { item, .. } => {
+                write!(fmt, "const {}", item)?;
+            }
+            Self::Static { item, .. } => {
+                write!(fmt, "static {}", item)?;
+            }
             Self::Closure { item, .. } => {
                 write!(fmt, "closure {}", item)?;
             }
@@ -161,6 +224,39 @@ pub struct CompileMetaStruct {
     pub fields: Option<HashSet<String>>,
 }
 
+/// The signature of a function, used to check the number of arguments it's
+/// called with at compile time.
+#[derive(Debug, Clone)]
+pub struct CompileMetaSignature {
+    /// The path to the function.
+    pub item: Item,
+    /// The names of the arguments of the function.
+    pub args: Vec<String>,
+    /// The source id and span of the declaration of the function, if it's
+    /// declared in a script.
+    pub declared: Option<(usize, Span)>,
+}
+
+impl fmt::Display for CompileMetaSignature {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}(", self.item)?;
+
+        let mut it = self.args.iter();
+        let last = it.next_back();
+
+        for arg in it {
+            write!(fmt, "{}, ", arg)?;
+        }
+
+        if let Some(arg) = last {
+            write!(fmt, "{}", arg)?;
+        }
+
+        write!(fmt, ")")?;
+        Ok(())
+    }
+}
+
 /// The metadata about a variant.
 #[derive(Debug, Clone)]
 pub struct CompileMetaTuple {
@@ -1,12 +1,15 @@
 use crate::collections::{HashMap, HashSet};
 use crate::module::{
-    ModuleAssociatedFn, ModuleFn, ModuleInternalEnum, ModuleMacro, ModuleType, ModuleUnitType,
+    ModuleAssociatedFn, ModuleFn, ModuleInternalEnum, ModuleMacro, ModuleMatcher, ModuleType,
+    ModuleUnitType,
 };
 use crate::{
-    CompileMeta, CompileMetaStruct, CompileMetaTuple, Component, Hash, Item, Module, Names, Stack,
-    StaticType, Type, TypeCheck, TypeInfo, ValueType, VmError,
+    CallContext, CompileMeta, CompileMetaSignature, CompileMetaStruct, CompileMetaTuple, Component,
+    Hash, Item, Module, Names, Output, Stack, StaticType, Type, TypeCheck, TypeInfo, ValueType,
+    VmError, VmErrorKind,
 };
 use std::any;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
 use thiserror::Error;
@@ -81,12 +84,35 @@ pub enum ContextError {
         /// The type we're trying to insert.
         value_type: Type,
     },
+    /// Raised when we try to register a type alias with a name which is
+    /// already in use.
+    #[error("type alias with name `{name}` conflicts with an existing item")]
+    ConflictingAlias {
+        /// The name of the alias.
+        name: Item,
+    },
+    /// Raised when we try to register a type alias for a type which doesn't
+    /// exist.
+    #[error("type alias `{name}` refers to missing type `{target}`")]
+    MissingAliasTarget {
+        /// The name of the alias.
+        name: Item,
+        /// The type the alias refers to.
+        target: Item,
+    },
     /// Error raised when attempting to register a conflicting function.
     #[error("variant with name `{name}` already exists")]
     ConflictingVariant {
         /// The name of the conflicting variant.
         name: Item,
     },
+    /// Error raised when referring to a function which has not been
+    /// registered in the module.
+    #[error("function `{name}` does not exist in module")]
+    MissingFunction {
+        /// The name of the missing function.
+        name: Item,
+    },
     /// Error raised when attempting to register an instance function on an
     /// instance which does not exist.
     #[error("instance `{instance_type}` does not exist in module")]
@@ -104,11 +130,15 @@ pub enum ContextError {
 }
 
 /// A function handler.
-pub(crate) type Handler = dyn Fn(&mut Stack, usize) -> Result<(), VmError> + Sync;
+pub(crate) type Handler = dyn Fn(&mut CallContext<'_>, usize) -> Result<(), VmError> + Send + Sync;
+
+/// An intrinsic handler, which operates directly on the stack.
+pub(crate) type Intrinsic = dyn Fn(&mut Stack) -> Result<(), VmError> + Send + Sync;
 
 /// A (type erased) macro handler.
-pub(crate) type Macro =
-    dyn Fn(&mut dyn any::Any, &dyn any::Any) -> Result<Box<dyn any::Any>, crate::Error> + Sync;
+pub(crate) type Macro = dyn Fn(&mut dyn any::Any, &dyn any::Any) -> Result<Box<dyn any::Any>, crate::Error>
+    + Send
+    + Sync;
 
 /// Information on a specific type.
 #[derive(Debug, Clone)]
@@ -132,15 +162,28 @@ impl fmt::Display for ContextTypeInfo {
     }
 }
 
+/// How an external type is destructured in patterns, as registered through
+/// [Module::object_matcher] or [Module::tuple_matcher].
+#[derive(Debug, Clone)]
+pub enum ContextMatcher {
+    /// Destructured by object patterns, into an object with the given fields.
+    Object(HashSet<String>),
+    /// Destructured by tuple patterns, into a tuple with the given number of
+    /// fields.
+    Tuple(usize),
+}
+
 /// A description of a function signature.
 #[derive(Debug, Clone)]
 pub enum ContextSignature {
+    /// A free function.
     Function {
         /// Path to the function.
         path: Item,
         /// Arguments.
         args: Option<usize>,
     },
+    /// An instance function.
     Instance {
         /// Path to the instance function.
         path: Item,
@@ -200,6 +243,41 @@ impl fmt::Display for ContextSignature {
     }
 }
 
+/// The difference between the native items of two contexts, as calculated by
+/// [Context::diff].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextDiff {
+    /// Items which are only available in the new context.
+    pub added: Vec<Item>,
+    /// Items which are only available in the old context.
+    pub removed: Vec<Item>,
+    /// Items which are available in both contexts, but have a different
+    /// signature or type.
+    pub changed: Vec<Item>,
+}
+
+impl ContextDiff {
+    /// Test if the contexts provide the same native items.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The capabilities required by a native function.
+#[derive(Clone)]
+struct RequiredCapabilities {
+    capabilities: Vec<String>,
+    handler: Arc<Handler>,
+}
+
+/// An intrinsic installed in a context.
+#[derive(Clone)]
+struct ContextIntrinsic {
+    hash: Hash,
+    args: usize,
+    handler: Arc<Intrinsic>,
+}
+
 /// Static run context visible to the virtual machine.
 ///
 /// This contains:
@@ -210,10 +288,22 @@ impl fmt::Display for ContextSignature {
 pub struct Context {
     /// Whether or not to include the prelude when constructing a new unit.
     has_default_modules: bool,
+    /// Whether or not the context is a restricted copy of another context.
+    is_restricted: bool,
     /// Item metadata in the context.
     meta: HashMap<Item, CompileMeta>,
     /// Registered native function handlers.
     functions: HashMap<Hash, Arc<Handler>>,
+    /// Native functions which require capabilities, together with their
+    /// original handlers.
+    capabilities: HashMap<Hash, RequiredCapabilities>,
+    /// The capabilities granted by the context, or `None` if every capability
+    /// is granted.
+    granted: Option<HashSet<String>>,
+    /// Registered intrinsics, indexed by slot.
+    intrinsics: Vec<ContextIntrinsic>,
+    /// The slots of registered intrinsics.
+    intrinsic_slots: HashMap<Hash, usize>,
     /// Registered native macro handlers.
     macros: HashMap<Hash, Arc<Macro>>,
     /// Information on functions.
@@ -222,6 +312,10 @@ pub struct Context {
     types: HashMap<Hash, ContextTypeInfo>,
     /// Reverse lookup for types.
     types_rev: HashMap<Type, Hash>,
+    /// How external types are destructured in patterns.
+    matchers: HashMap<Item, ContextMatcher>,
+    /// Type aliases, and the names of the types they refer to.
+    aliases: HashMap<Item, Item>,
     /// Specialized information on unit types, if available.
     unit_type: Option<Hash>,
     /// Registered internal enums.
@@ -242,25 +336,55 @@ impl Context {
         Some(ty.type_check)
     }
 
+    /// Access how the external type with the given item is destructured in
+    /// patterns, if it has registered a matcher.
+    pub fn matcher_for(&self, item: &Item) -> Option<&ContextMatcher> {
+        self.matchers.get(item)
+    }
+
     /// Construct a new collection of functions with default packages installed.
     pub fn with_default_modules() -> Result<Self, ContextError> {
+        Self::with_default_modules_and_output(Output::default())
+    }
+
+    /// Construct a new collection of functions with default packages
+    /// installed, where functions like `print` and `dbg` write to the given
+    /// output instead of the stdout and stderr of the process.
+    pub fn with_default_modules_and_output(output: Output) -> Result<Self, ContextError> {
         let mut this = Self::new();
-        this.install(&crate::modules::core::module()?)?;
+        this.install(&crate::modules::core::module_with_output(output)?)?;
         this.install(&crate::modules::generator::module()?)?;
         this.install(&crate::modules::bytes::module()?)?;
+        this.install(&crate::modules::bitset::module()?)?;
+        this.install(&crate::modules::nd::module()?)?;
         this.install(&crate::modules::string::module()?)?;
         this.install(&crate::modules::int::module()?)?;
         this.install(&crate::modules::float::module()?)?;
+        this.install(&crate::modules::char::module()?)?;
+        this.install(&crate::modules::math::module()?)?;
+        this.install(&crate::modules::decimal::module()?)?;
+        this.install(&crate::modules::time::module()?)?;
+        this.install(&crate::modules::encoding::module()?)?;
+        this.install(&crate::modules::template::module()?)?;
+        this.install(&crate::modules::csv::module()?)?;
+        this.install(&crate::modules::path::module()?)?;
+        this.install(&crate::modules::proptest::module()?)?;
         this.install(&crate::modules::test::module()?)?;
         this.install(&crate::modules::iter::module()?)?;
+        this.install(&crate::modules::lazy::module()?)?;
+        this.install(&crate::modules::ops::module()?)?;
         this.install(&crate::modules::vec::module()?)?;
         this.install(&crate::modules::object::module()?)?;
         this.install(&crate::modules::result::module()?)?;
+        this.install(&crate::modules::error::module()?)?;
         this.install(&crate::modules::option::module()?)?;
         this.install(&crate::modules::future::module()?)?;
         this.install(&crate::modules::stream::module()?)?;
         this.install(&crate::modules::io::module()?)?;
         this.install(&crate::modules::fmt::module()?)?;
+        this.install(&crate::modules::clone::module()?)?;
+        this.install(&crate::modules::event::module()?)?;
+        this.install(&crate::modules::hash::module()?)?;
         this.has_default_modules = true;
         Ok(this)
     }
@@ -273,6 +397,88 @@ impl Context {
         self.has_default_modules
     }
 
+    /// Only grant the given capabilities to scripts using this context.
+    ///
+    /// Native functions which require a capability that isn't granted are
+    /// reported when a unit is linked against the context, and raise an error
+    /// if they are called anyway. By default, every capability is granted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Hash, Module};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = Module::new(&["fs"]);
+    /// module.function(&["read"], |path: String| path)?;
+    /// module.require_capability(&["read"], "fs-read")?;
+    /// module.function(&["remove"], |path: String| drop(path))?;
+    /// module.require_capability(&["remove"], "fs-write")?;
+    ///
+    /// let mut context = Context::new();
+    /// context.install(&module)?;
+    /// context.grant_only(vec!["fs-read"]);
+    ///
+    /// assert_eq!(context.missing_capability(Hash::type_hash(&["fs", "read"])), None);
+    /// assert_eq!(context.missing_capability(Hash::type_hash(&["fs", "remove"])), Some("fs-write"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn grant_only<I>(&mut self, capabilities: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.granted = Some(capabilities.into_iter().map(Into::into).collect());
+
+        let hashes = self.capabilities.keys().copied().collect::<Vec<_>>();
+
+        for hash in hashes {
+            self.apply_capabilities(hash);
+        }
+    }
+
+    /// Get the first capability required by the function with the given hash
+    /// which isn't granted by the context, if any.
+    pub fn missing_capability(&self, hash: Hash) -> Option<&str> {
+        let granted = self.granted.as_ref()?;
+        let required = self.capabilities.get(&hash)?;
+
+        required
+            .capabilities
+            .iter()
+            .find(|capability| !granted.contains(*capability))
+            .map(String::as_str)
+    }
+
+    /// Install the handler of the function with the given hash, depending on
+    /// whether the context grants the capabilities it requires.
+    fn apply_capabilities(&mut self, hash: Hash) {
+        let handler = match self.missing_capability(hash) {
+            Some(capability) => {
+                let capability = capability.to_owned();
+
+                Arc::new(move |_: &mut CallContext<'_>, _| {
+                    Err(VmError::from(VmErrorKind::MissingCapability {
+                        capability: capability.clone(),
+                    }))
+                })
+            }
+            None => match self.capabilities.get(&hash) {
+                Some(required) => required.handler.clone(),
+                None => return,
+            },
+        };
+
+        self.functions.insert(hash, handler);
+    }
+
+    /// Test if the context is a restricted copy of another context, as
+    /// constructed through [restrict][Context::restrict].
+    pub fn is_restricted(&self) -> bool {
+        self.is_restricted
+    }
+
     /// Iterate over known child components of the given name.
     pub fn iter_components<'a, I>(&'a self, iter: I) -> impl Iterator<Item = &'a Component>
     where
@@ -302,14 +508,72 @@ impl Context {
         self.functions.get(&hash)
     }
 
+    /// Get the slot of the intrinsic with the given hash, if the intrinsic
+    /// takes the given number of arguments.
+    ///
+    /// The compiler uses this to compile calls to intrinsics into
+    /// [Inst::CallIntrinsic][crate::Inst::CallIntrinsic].
+    pub fn intrinsic_slot(&self, hash: Hash, args: usize) -> Option<usize> {
+        let slot = *self.intrinsic_slots.get(&hash)?;
+
+        if self.intrinsics[slot].args != args {
+            return None;
+        }
+
+        Some(slot)
+    }
+
+    /// Lookup the intrinsic in the given slot, as long as it matches the
+    /// given hash and number of arguments.
+    pub(crate) fn lookup_intrinsic(
+        &self,
+        slot: usize,
+        hash: Hash,
+        args: usize,
+    ) -> Option<&Arc<Intrinsic>> {
+        let intrinsic = self.intrinsics.get(slot)?;
+
+        if intrinsic.hash != hash || intrinsic.args != args {
+            return None;
+        }
+
+        Some(&intrinsic.handler)
+    }
+
     /// Lookup the given macro handler.
     pub fn lookup_macro(&self, hash: Hash) -> Option<&Arc<Macro>> {
         self.macros.get(&hash)
     }
 
     /// Access the meta for the given language item.
+    ///
+    /// Items which start with a type alias are looked up through the type the
+    /// alias refers to.
     pub fn lookup_meta(&self, name: &Item) -> Option<CompileMeta> {
-        self.meta.get(name).cloned()
+        if let Some(meta) = self.meta.get(name) {
+            return Some(meta.clone());
+        }
+
+        self.meta.get(&self.resolve_alias(name)?).cloned()
+    }
+
+    /// Resolve the given item through the type alias it starts with, if any.
+    pub fn resolve_alias(&self, name: &Item) -> Option<Item> {
+        let mut prefix = name.clone();
+        let mut rest = Vec::new();
+
+        loop {
+            if let Some(target) = self.aliases.get(&prefix) {
+                return Some(target.join(rest.into_iter().rev()));
+            }
+
+            rest.push(prefix.pop()?);
+        }
+    }
+
+    /// Look up the signature of the native function with the given hash.
+    pub fn lookup_signature(&self, hash: Hash) -> Option<&ContextSignature> {
+        self.functions_info.get(&hash)
     }
 
     /// Iterate over all available functions
@@ -332,12 +596,217 @@ impl Context {
         })
     }
 
+    /// Construct a restricted copy of this context, where only native
+    /// functions and macros nested inside of the given items are available.
+    ///
+    /// This is cheap compared to installing modules, since native handlers are
+    /// shared with this context. Types are always kept, so that values passed
+    /// in by the host can still be type checked. Scripts which refer to
+    /// anything else fail to compile against the restricted context, and
+    /// units compiled against a less restricted context fail when they call a
+    /// function which is not available.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Hash, Item};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let context = Context::with_default_modules()?;
+    ///
+    /// let sandbox = context.restrict(vec![
+    ///     Item::of(&["std", "string"]),
+    ///     Item::of(&["std", "option"]),
+    /// ]);
+    ///
+    /// assert!(sandbox.lookup(Hash::type_hash(&["std", "option", "Option", "Some"])).is_some());
+    /// assert!(sandbox.lookup(Hash::type_hash(&["std", "print"])).is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn restrict<I>(&self, allow: I) -> Context
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let allow = allow.into_iter().collect::<Vec<_>>();
+        let is_allowed = |item: &Item| allow.iter().any(|prefix| item.starts_with(prefix));
+
+        let mut context = Context {
+            has_default_modules: self.has_default_modules,
+            is_restricted: true,
+            granted: self.granted.clone(),
+            types: self.types.clone(),
+            types_rev: self.types_rev.clone(),
+            matchers: self.matchers.clone(),
+            aliases: self.aliases.clone(),
+            unit_type: self.unit_type,
+            internal_enums: self.internal_enums.clone(),
+            ..Context::default()
+        };
+
+        for (hash, signature) in &self.functions_info {
+            let allowed = match signature {
+                ContextSignature::Function { path, .. } => is_allowed(path),
+                ContextSignature::Instance { path, name, .. } => {
+                    is_allowed(&path.extended(name.as_str()))
+                }
+            };
+
+            if !allowed {
+                continue;
+            }
+
+            if let Some(handler) = self.functions.get(hash) {
+                context.functions.insert(*hash, handler.clone());
+            }
+
+            if let Some(required) = self.capabilities.get(hash) {
+                context.capabilities.insert(*hash, required.clone());
+            }
+
+            if let Some(slot) = self.intrinsic_slots.get(hash) {
+                context.insert_intrinsic(self.intrinsics[*slot].clone());
+            }
+
+            context.functions_info.insert(*hash, signature.clone());
+        }
+
+        for (item, meta) in &self.meta {
+            let allowed = match meta {
+                CompileMeta::Struct { .. } | CompileMeta::Enum { .. } => true,
+                _ => is_allowed(item),
+            };
+
+            if allowed {
+                context.meta.insert(item.clone(), meta.clone());
+                context.names.insert(item);
+
+                if let CompileMeta::Macro { .. } = meta {
+                    let hash = Hash::type_hash(item);
+
+                    if let Some(handler) = self.macros.get(&hash) {
+                        context.macros.insert(hash, handler.clone());
+                    }
+                }
+            }
+        }
+
+        for info in self.types.values() {
+            context.names.insert(&info.name);
+        }
+
+        for name in self.aliases.keys() {
+            context.names.insert(name);
+        }
+
+        context
+    }
+
+    /// Calculate a fingerprint of the native items installed in the context.
+    ///
+    /// Units record the fingerprint of the context they were compiled
+    /// against, since they refer to native functions and types by hash. Two
+    /// contexts have the same fingerprint if they provide the same functions,
+    /// with the same number of arguments, and the same types.
+    pub fn fingerprint(&self) -> Hash {
+        Hash::of(self.native_items())
+    }
+
+    /// Calculate which native items are added, removed, or changed in the
+    /// `other` context compared to this one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Item, Module};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let old = Context::with_default_modules()?;
+    ///
+    /// let mut module = Module::new(&["host"]);
+    /// module.function(&["answer"], || 42i64)?;
+    ///
+    /// let mut new = Context::with_default_modules()?;
+    /// new.install(&module)?;
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.added, vec![Item::of(&["host", "answer"])]);
+    /// assert!(diff.removed.is_empty());
+    /// assert!(diff.changed.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn diff(&self, other: &Context) -> ContextDiff {
+        let old = self.native_items();
+        let new = other.native_items();
+
+        let mut diff = ContextDiff::default();
+
+        for (item, hash) in &old {
+            match new.get(item) {
+                Some(other) if other != hash => diff.changed.push(item.clone()),
+                Some(..) => (),
+                None => diff.removed.push(item.clone()),
+            }
+        }
+
+        for item in new.keys() {
+            if !old.contains_key(item) {
+                diff.added.push(item.clone());
+            }
+        }
+
+        diff
+    }
+
+    /// Collect the native items of the context, together with a hash of
+    /// everything a unit might depend on for each item.
+    fn native_items(&self) -> BTreeMap<Item, Hash> {
+        // NB: more than one native item might share an item, like a type and
+        // its constructor, so their hashes are sorted to make the combined
+        // hash independent of the order they are visited in.
+        let mut items = BTreeMap::<_, Vec<Hash>>::new();
+
+        let mut insert = |item: Item, hash: Hash| {
+            items.entry(item).or_default().push(hash);
+        };
+
+        for (hash, signature) in &self.functions_info {
+            match signature {
+                ContextSignature::Function { path, args } => {
+                    insert(path.clone(), Hash::of((hash, args)));
+                }
+                ContextSignature::Instance {
+                    path, name, args, ..
+                } => {
+                    insert(path.extended(name.as_str()), Hash::of((hash, args)));
+                }
+            }
+        }
+
+        for (hash, ty) in &self.types {
+            insert(ty.name.clone(), Hash::of((hash, ty.value_type)));
+        }
+
+        items
+            .into_iter()
+            .map(|(item, mut hashes)| {
+                hashes.sort();
+                (item, Hash::of(hashes))
+            })
+            .collect()
+    }
+
     /// Install the specified module.
     pub fn install(&mut self, module: &Module) -> Result<(), ContextError> {
         for (value_type, ty) in &module.types {
             self.install_type(&module, *value_type, ty)?;
         }
 
+        for (name, target) in &module.aliases {
+            self.install_alias(module, name, target)?;
+        }
+
         for (name, f) in &module.functions {
             self.install_function(&module, name, f)?;
         }
@@ -363,9 +832,20 @@ impl Context {
             )?;
         }
 
+        for (value_type, matcher) in &module.matchers {
+            self.install_matcher(*value_type, matcher)?;
+        }
+
         Ok(())
     }
 
+    /// Insert an intrinsic into the next free slot.
+    fn insert_intrinsic(&mut self, intrinsic: ContextIntrinsic) {
+        self.intrinsic_slots
+            .insert(intrinsic.hash, self.intrinsics.len());
+        self.intrinsics.push(intrinsic);
+    }
+
     /// Install the given meta.
     fn install_meta(&mut self, item: Item, meta: CompileMeta) -> Result<(), ContextError> {
         if let Some(existing) = self.meta.insert(item.clone(), meta.clone()) {
@@ -413,6 +893,31 @@ impl Context {
         Ok(())
     }
 
+    /// Install a type alias.
+    fn install_alias(
+        &mut self,
+        module: &Module,
+        name: &Item,
+        target: &Item,
+    ) -> Result<(), ContextError> {
+        let name = module.path.join(name);
+
+        if self.meta.contains_key(&name) || self.aliases.contains_key(&name) {
+            return Err(ContextError::ConflictingAlias { name });
+        }
+
+        if !self.types.contains_key(&Hash::type_hash(target)) {
+            return Err(ContextError::MissingAliasTarget {
+                name,
+                target: target.clone(),
+            });
+        }
+
+        self.names.insert(&name);
+        self.aliases.insert(name, target.clone());
+        Ok(())
+    }
+
     fn install_type_info(&mut self, hash: Hash, info: ContextTypeInfo) -> Result<(), ContextError> {
         self.names.insert(&info.name);
 
@@ -461,13 +966,48 @@ impl Context {
 
         self.functions.insert(hash, f.handler.clone());
 
-        self.meta.insert(
-            name.clone(),
+        if !f.capabilities.is_empty() {
+            self.capabilities.insert(
+                hash,
+                RequiredCapabilities {
+                    capabilities: f.capabilities.clone(),
+                    handler: f.handler.clone(),
+                },
+            );
+
+            self.apply_capabilities(hash);
+        }
+
+        // NB: functions which require capabilities always go through their
+        // handler, so that the capabilities are checked.
+        if let (Some(handler), Some(args), true) = (&f.intrinsic, f.args, f.capabilities.is_empty())
+        {
+            self.insert_intrinsic(ContextIntrinsic {
+                hash,
+                args,
+                handler: handler.clone(),
+            });
+        }
+
+        let meta = if f.constant {
+            CompileMeta::Const { item: name.clone() }
+        } else {
+            // NB: native functions are named after the position of their
+            // arguments, like in their context signature.
+            let signature = f.args.map(|args| CompileMetaSignature {
+                item: name.clone(),
+                args: (0..args).map(|n| format!("#{}", n)).collect(),
+                declared: None,
+            });
+
             CompileMeta::Function {
                 value_type: Type::Hash(hash),
                 item: name.clone(),
-            },
-        );
+                signature,
+            }
+        };
+
+        self.meta.insert(name.clone(), meta);
 
         Ok(())
     }
@@ -493,6 +1033,29 @@ impl Context {
         Ok(())
     }
 
+    fn install_matcher(
+        &mut self,
+        value_type: Type,
+        matcher: &ModuleMatcher,
+    ) -> Result<(), ContextError> {
+        let info = match self
+            .types_rev
+            .get(&value_type)
+            .and_then(|hash| self.types.get(hash))
+        {
+            Some(info) => info,
+            None => {
+                return Err(ContextError::MissingInstance {
+                    instance_type: matcher.type_info,
+                });
+            }
+        };
+
+        self.matchers
+            .insert(info.name.clone(), matcher.matcher.clone());
+        Ok(())
+    }
+
     fn install_associated_function(
         &mut self,
         value_type: Type,
@@ -672,8 +1235,9 @@ impl Context {
 
         self.install_meta(item.clone(), meta)?;
 
-        let constructor: Arc<Handler> =
-            Arc::new(move |stack, args| constructor.fn_call(stack, args));
+        let constructor: Arc<Handler> = Arc::new(move |cx: &mut CallContext<'_>, args| {
+            constructor.fn_call(cx.stack_mut(), args)
+        });
 
         let signature = ContextSignature::Function {
             path: item,
@@ -2,16 +2,19 @@
 
 use crate::collections::HashMap;
 use crate::{Hash, Item, Label, Span};
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Debug information about a unit.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct DebugInfo {
     /// Debug information on each instruction.
     pub instructions: Vec<DebugInst>,
     /// Function signatures.
+    #[serde(serialize_with = "crate::unit::serialize_sorted")]
     pub functions: HashMap<Hash, DebugSignature>,
     /// Reverse lookup of a function.
+    #[serde(serialize_with = "crate::unit::serialize_sorted")]
     pub functions_rev: HashMap<usize, Hash>,
 }
 
@@ -30,7 +33,7 @@ impl DebugInfo {
 }
 
 /// Debug information for every instruction.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct DebugInst {
     /// The file by id the instruction belongs to.
     pub source_id: usize,
@@ -39,11 +42,14 @@ pub struct DebugInst {
     /// The comment for the line.
     pub comment: Option<String>,
     /// Label associated with the location.
+    ///
+    /// Labels aren't preserved when debug information is serialized.
+    #[serde(skip)]
     pub label: Option<Label>,
 }
 
 /// Debug information on function arguments.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DebugArgs {
     /// A tuple, with the given number of arguments.
     TupleArgs(usize),
@@ -52,7 +58,7 @@ pub enum DebugArgs {
 }
 
 /// A description of a function signature.
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DebugSignature {
     /// The path of the function.
     pub path: Item,
@@ -72,10 +78,10 @@ impl DebugSignature {
 
 impl fmt::Display for DebugSignature {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match &self.args {
-            DebugArgs::TupleArgs(args) if *args > 0 => {
-                write!(fmt, "{}(", self.path)?;
+        write!(fmt, "{}(", self.path)?;
 
+        match &self.args {
+            DebugArgs::TupleArgs(args) => {
                 let mut it = 0..*args;
                 let last = it.next_back();
 
@@ -86,12 +92,8 @@ impl fmt::Display for DebugSignature {
                 if let Some(arg) = last {
                     write!(fmt, "{}", arg)?;
                 }
-
-                write!(fmt, ")")?;
             }
             DebugArgs::Named(args) => {
-                write!(fmt, "{}(", self.path)?;
-
                 let mut it = args.iter();
                 let last = it.next_back();
 
@@ -102,12 +104,10 @@ impl fmt::Display for DebugSignature {
                 if let Some(arg) = last {
                     write!(fmt, "{}", arg)?;
                 }
-
-                write!(fmt, ")")?;
             }
-            _ => (),
         }
 
+        write!(fmt, ")")?;
         Ok(())
     }
 }
@@ -0,0 +1,395 @@
+//! A fixed-point decimal number.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// An exact decimal number, like `1.05d`.
+///
+/// Decimals are stored as an integer mantissa and the number of digits after
+/// the decimal point, so they represent amounts like prices exactly where
+/// floats can't. Addition, subtraction and multiplication are exact unless
+/// they overflow. Division is exact if the result fits in
+/// [MAX_SCALE][Decimal::MAX_SCALE] digits after the decimal point, otherwise
+/// it's rounded half away from zero.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Decimal;
+///
+/// # fn main() -> Result<(), runestick::ParseDecimalError> {
+/// let price = "19.99".parse::<Decimal>()?;
+/// let total = price.checked_mul(Decimal::from(3)).unwrap();
+///
+/// assert_eq!(total.to_string(), "59.97");
+/// assert_eq!(total, "59.970".parse::<Decimal>()?);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Decimal {
+    /// The value of the decimal without the decimal point.
+    mantissa: i128,
+    /// The number of digits after the decimal point.
+    scale: u32,
+}
+
+impl Decimal {
+    /// The largest number of digits after the decimal point a decimal keeps.
+    ///
+    /// Results of operations which would need more digits, like dividing `1d`
+    /// by `3d`, are rounded to this many digits.
+    pub const MAX_SCALE: u32 = 28;
+
+    /// Construct a decimal from a mantissa and the number of digits after the
+    /// decimal point, returning `None` if the scale is larger than
+    /// [MAX_SCALE][Decimal::MAX_SCALE].
+    pub fn new(mantissa: i128, scale: u32) -> Option<Self> {
+        if scale > Self::MAX_SCALE {
+            return None;
+        }
+
+        Some(Self { mantissa, scale })
+    }
+
+    /// The value of the decimal without the decimal point.
+    pub fn mantissa(self) -> i128 {
+        self.mantissa
+    }
+
+    /// The number of digits after the decimal point.
+    pub fn scale(self) -> u32 {
+        self.scale
+    }
+
+    /// Test if the decimal is zero.
+    pub fn is_zero(self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Add two decimals, returning `None` on overflow.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = align(self, other)?;
+        Some(Self {
+            mantissa: a.checked_add(b)?,
+            scale,
+        })
+    }
+
+    /// Subtract two decimals, returning `None` on overflow.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = align(self, other)?;
+        Some(Self {
+            mantissa: a.checked_sub(b)?,
+            scale,
+        })
+    }
+
+    /// Multiply two decimals, returning `None` on overflow.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let mantissa = self.mantissa.checked_mul(other.mantissa)?;
+        let scale = self.scale + other.scale;
+
+        if scale > Self::MAX_SCALE {
+            return Some(round_mantissa(mantissa, scale, Self::MAX_SCALE));
+        }
+
+        Some(Self { mantissa, scale })
+    }
+
+    /// Divide two decimals, returning `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        if other.mantissa == 0 {
+            return None;
+        }
+
+        let mut numerator = self.mantissa;
+        let denominator = other.mantissa;
+        let mut scale = i64::from(self.scale) - i64::from(other.scale);
+
+        if scale < 0 {
+            numerator = numerator.checked_mul(pow10(-scale as u32)?)?;
+            scale = 0;
+        }
+
+        let mut quotient = numerator / denominator;
+        let mut remainder = numerator % denominator;
+
+        while remainder != 0 && scale < i64::from(Self::MAX_SCALE) {
+            let (q, r) = match (quotient.checked_mul(10), remainder.checked_mul(10)) {
+                (Some(q), Some(r)) => (q, r),
+                _ => break,
+            };
+
+            quotient = match q.checked_add(r / denominator) {
+                Some(quotient) => quotient,
+                None => break,
+            };
+
+            remainder = r % denominator;
+            scale += 1;
+        }
+
+        if remainder.unsigned_abs() >= denominator.unsigned_abs() - remainder.unsigned_abs() {
+            let sign = numerator.signum() * denominator.signum();
+            quotient = quotient.checked_add(sign)?;
+        }
+
+        Some(Self {
+            mantissa: quotient,
+            scale: scale as u32,
+        })
+    }
+
+    /// Calculate the remainder of dividing two decimals, returning `None` on
+    /// overflow or division by zero.
+    pub fn checked_rem(self, other: Self) -> Option<Self> {
+        let (a, b, scale) = align(self, other)?;
+
+        Some(Self {
+            mantissa: a.checked_rem(b)?,
+            scale,
+        })
+    }
+
+    /// Round the decimal to the given number of digits after the decimal
+    /// point, rounding half away from zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Decimal;
+    ///
+    /// # fn main() -> Result<(), runestick::ParseDecimalError> {
+    /// assert_eq!("2.345".parse::<Decimal>()?.round(2).to_string(), "2.35");
+    /// assert_eq!("-2.345".parse::<Decimal>()?.round(2).to_string(), "-2.35");
+    /// assert_eq!("2.3".parse::<Decimal>()?.round(2).to_string(), "2.3");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn round(self, scale: u32) -> Self {
+        if scale >= self.scale {
+            return self;
+        }
+
+        round_mantissa(self.mantissa, self.scale, scale)
+    }
+
+    /// Convert the decimal into the closest float.
+    pub fn to_f64(self) -> f64 {
+        self.mantissa as f64 / 10f64.powi(self.scale as i32)
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Self {
+            mantissa: i128::from(value),
+            scale: 0,
+        }
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if let Some((a, b, _)) = align(*self, *other) {
+            return a.cmp(&b);
+        }
+
+        // NB: aligning only fails if the decimal with the smaller scale has a
+        // larger magnitude than any decimal with the larger scale.
+        if self.scale < other.scale {
+            self.mantissa.cmp(&0)
+        } else {
+            0.cmp(&other.mantissa)
+        }
+    }
+}
+
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.mantissa.unsigned_abs().to_string();
+        let scale = self.scale as usize;
+
+        if self.mantissa < 0 {
+            write!(f, "-")?;
+        }
+
+        if scale == 0 {
+            return write!(f, "{}", digits);
+        }
+
+        if digits.len() <= scale {
+            write!(f, "0.{:0>width$}", digits, width = scale)
+        } else {
+            let (int, frac) = digits.split_at(digits.len() - scale);
+            write!(f, "{}.{}", int, frac)
+        }
+    }
+}
+
+/// Error raised when parsing a [Decimal] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseDecimalError {
+    kind: ParseDecimalErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ParseDecimalErrorKind {
+    Invalid,
+    TooManyDigits,
+}
+
+impl fmt::Display for ParseDecimalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParseDecimalErrorKind::Invalid => write!(f, "invalid decimal literal"),
+            ParseDecimalErrorKind::TooManyDigits => write!(
+                f,
+                "decimal literal has too many digits, at most {} are allowed after the decimal point",
+                Decimal::MAX_SCALE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseDecimalError {}
+
+impl FromStr for Decimal {
+    type Err = ParseDecimalError;
+
+    /// Parse a decimal like `-12.50`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseDecimalError {
+            kind: ParseDecimalErrorKind::Invalid,
+        };
+
+        let too_many_digits = || ParseDecimalError {
+            kind: ParseDecimalErrorKind::TooManyDigits,
+        };
+
+        let (negative, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+
+        let (int, frac) = match s.find('.') {
+            Some(n) if n + 1 < s.len() => (&s[..n], &s[n + 1..]),
+            Some(..) => return Err(invalid()),
+            None => (s, ""),
+        };
+
+        if int.is_empty() || !int.bytes().chain(frac.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        if frac.len() > Decimal::MAX_SCALE as usize {
+            return Err(too_many_digits());
+        }
+
+        let mut mantissa = 0i128;
+
+        for b in int.bytes().chain(frac.bytes()) {
+            mantissa = mantissa
+                .checked_mul(10)
+                .and_then(|m| m.checked_add(i128::from(b - b'0')))
+                .ok_or_else(too_many_digits)?;
+        }
+
+        Ok(Self {
+            mantissa: if negative { -mantissa } else { mantissa },
+            scale: frac.len() as u32,
+        })
+    }
+}
+
+/// Calculate `10` to the power of `exp`, returning `None` on overflow.
+fn pow10(exp: u32) -> Option<i128> {
+    10i128.checked_pow(exp)
+}
+
+/// Bring the mantissas of two decimals to the same scale, returning `None` if
+/// that overflows.
+fn align(a: Decimal, b: Decimal) -> Option<(i128, i128, u32)> {
+    match a.scale.cmp(&b.scale) {
+        Ordering::Equal => Some((a.mantissa, b.mantissa, a.scale)),
+        Ordering::Less => {
+            let a = a.mantissa.checked_mul(pow10(b.scale - a.scale)?)?;
+            Some((a, b.mantissa, b.scale))
+        }
+        Ordering::Greater => {
+            let b = b.mantissa.checked_mul(pow10(a.scale - b.scale)?)?;
+            Some((a.mantissa, b, a.scale))
+        }
+    }
+}
+
+/// Round a mantissa from one scale to a smaller one, rounding half away from
+/// zero.
+fn round_mantissa(mantissa: i128, from: u32, to: u32) -> Decimal {
+    // NB: the factor is at most 10^MAX_SCALE, which always fits.
+    let factor = 10i128.pow(from - to);
+    let mut quotient = mantissa / factor;
+    let remainder = (mantissa % factor).unsigned_abs();
+
+    if remainder >= factor.unsigned_abs() - remainder {
+        quotient += mantissa.signum();
+    }
+
+    Decimal {
+        mantissa: quotient,
+        scale: to,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Decimal;
+
+    fn d(s: &str) -> Decimal {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(d("0.1").checked_add(d("0.2")).unwrap(), d("0.3"));
+        assert_eq!(d("1.05").checked_sub(d("2")).unwrap().to_string(), "-0.95");
+        assert_eq!(
+            d("1.10").checked_mul(d("1.1")).unwrap().to_string(),
+            "1.210"
+        );
+        assert_eq!(d("1").checked_div(d("4")).unwrap().to_string(), "0.25");
+        assert_eq!(d("10").checked_div(d("0.5")).unwrap().to_string(), "20");
+        assert_eq!(
+            d("2").checked_div(d("3")).unwrap().to_string(),
+            "0.6666666666666666666666666667"
+        );
+        assert_eq!(d("-7.5").checked_rem(d("2")).unwrap().to_string(), "-1.5");
+        assert!(d("1").checked_div(d("0")).is_none());
+    }
+
+    #[test]
+    fn test_parse_display() {
+        assert_eq!(d("0.05").to_string(), "0.05");
+        assert_eq!(d("-0.5").to_string(), "-0.5");
+        assert_eq!(d("12").to_string(), "12");
+        assert!("1.".parse::<Decimal>().is_err());
+        assert!(".5".parse::<Decimal>().is_err());
+        assert!("1e5".parse::<Decimal>().is_err());
+    }
+}
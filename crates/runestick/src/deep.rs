@@ -0,0 +1,584 @@
+//! Deep cloning, structural hashing, and freezing of values.
+
+use crate::collections::{HashMap, HashSet};
+use crate::{
+    CallContext, GeneratorState, Hash, Object, Shared, Tuple, TupleVariant, TypedObject,
+    TypedTuple, Value, VariantObject, VmError, VmErrorKind, CLONE,
+};
+use std::hash::{BuildHasher as _, BuildHasherDefault, Hash as _, Hasher as _};
+use twox_hash::XxHash64;
+
+/// Helper to deep clone values.
+///
+/// Shared values which are encountered more than once are only cloned once,
+/// which preserves both aliasing and cycles in the cloned value.
+pub(crate) struct DeepClone<F> {
+    /// Values which have already been cloned, keyed by their source pointer.
+    seen: HashMap<*const (), Value>,
+    /// Callback used to clone external values.
+    external: F,
+}
+
+impl<F> DeepClone<F>
+where
+    F: FnMut(&Value) -> Result<Value, VmError>,
+{
+    /// Construct a new deep clone helper, using the given callback to clone
+    /// external values.
+    pub(crate) fn new(external: F) -> Self {
+        Self {
+            seen: HashMap::new(),
+            external,
+        }
+    }
+
+    /// Look up a value which has already been cloned.
+    fn lookup<T: ?Sized>(&self, shared: &Shared<T>) -> Option<Value> {
+        self.seen.get(&shared.as_ptr()).cloned()
+    }
+
+    /// Deep clone the given value.
+    pub(crate) fn clone_value(&mut self, value: &Value) -> Result<Value, VmError> {
+        let ptr = match value {
+            Value::Unit
+            | Value::Bool(..)
+            | Value::Byte(..)
+            | Value::Char(..)
+            | Value::Integer(..)
+            | Value::Float(..)
+            | Value::Type(..)
+            | Value::StaticString(..)
+            | Value::Function(..)
+            | Value::Handle(..) => return Ok(value.clone()),
+            Value::String(string) => {
+                let string = string.borrow_ref()?.clone();
+                return Ok(Value::String(Shared::new(string)));
+            }
+            Value::Bytes(bytes) => {
+                let bytes = bytes.borrow_ref()?.clone();
+                return Ok(Value::Bytes(Shared::new(bytes)));
+            }
+            Value::Future(..) | Value::Stream(..) | Value::Generator(..) => {
+                return Err(VmError::from(VmErrorKind::MissingProtocol {
+                    protocol: CLONE,
+                    actual: value.type_info()?,
+                }));
+            }
+            Value::Vec(vec) => {
+                if let Some(value) = self.lookup(vec) {
+                    return Ok(value);
+                }
+
+                let cloned = Shared::new(Vec::new());
+                self.seen.insert(vec.as_ptr(), Value::Vec(cloned.clone()));
+                let values = self.clone_values(vec.borrow_ref()?.iter())?;
+                *cloned.borrow_mut()? = values;
+                return Ok(Value::Vec(cloned));
+            }
+            Value::Tuple(tuple) => {
+                if let Some(value) = self.lookup(tuple) {
+                    return Ok(value);
+                }
+
+                let cloned = Shared::new(Tuple::from(Vec::new()));
+                self.seen
+                    .insert(tuple.as_ptr(), Value::Tuple(cloned.clone()));
+                let values = self.clone_values(tuple.borrow_ref()?.iter())?;
+                *cloned.borrow_mut()? = Tuple::from(values);
+                return Ok(Value::Tuple(cloned));
+            }
+            Value::Object(object) => {
+                if let Some(value) = self.lookup(object) {
+                    return Ok(value);
+                }
+
+                let cloned = Shared::new(Object::new());
+                self.seen
+                    .insert(object.as_ptr(), Value::Object(cloned.clone()));
+                let values = self.clone_object(&*object.borrow_ref()?)?;
+                *cloned.borrow_mut()? = values;
+                return Ok(Value::Object(cloned));
+            }
+            Value::Option(option) => {
+                if let Some(value) = self.lookup(option) {
+                    return Ok(value);
+                }
+
+                let cloned = Shared::new(None);
+                self.seen
+                    .insert(option.as_ptr(), Value::Option(cloned.clone()));
+
+                let value = match &*option.borrow_ref()? {
+                    Some(value) => Some(self.clone_value(value)?),
+                    None => None,
+                };
+
+                *cloned.borrow_mut()? = value;
+                return Ok(Value::Option(cloned));
+            }
+            Value::Result(result) => {
+                if let Some(value) = self.lookup(result) {
+                    return Ok(value);
+                }
+
+                let cloned = Shared::new(Ok(Value::Unit));
+                self.seen
+                    .insert(result.as_ptr(), Value::Result(cloned.clone()));
+
+                let value = match &*result.borrow_ref()? {
+                    Ok(value) => Ok(self.clone_value(value)?),
+                    Err(value) => Err(self.clone_value(value)?),
+                };
+
+                *cloned.borrow_mut()? = value;
+                return Ok(Value::Result(cloned));
+            }
+            Value::GeneratorState(state) => {
+                if let Some(value) = self.lookup(state) {
+                    return Ok(value);
+                }
+
+                let cloned = Shared::new(GeneratorState::Complete(Value::Unit));
+                self.seen
+                    .insert(state.as_ptr(), Value::GeneratorState(cloned.clone()));
+
+                let value = match &*state.borrow_ref()? {
+                    GeneratorState::Yielded(value) => {
+                        GeneratorState::Yielded(self.clone_value(value)?)
+                    }
+                    GeneratorState::Complete(value) => {
+                        GeneratorState::Complete(self.clone_value(value)?)
+                    }
+                };
+
+                *cloned.borrow_mut()? = value;
+                return Ok(Value::GeneratorState(cloned));
+            }
+            Value::TypedTuple(tuple) => {
+                if let Some(value) = self.lookup(tuple) {
+                    return Ok(value);
+                }
+
+                let source = tuple.borrow_ref()?;
+
+                let cloned = Shared::new(TypedTuple {
+                    hash: source.hash,
+                    tuple: Vec::new().into_boxed_slice(),
+                });
+
+                self.seen
+                    .insert(tuple.as_ptr(), Value::TypedTuple(cloned.clone()));
+                let values = self.clone_values(source.tuple.iter())?;
+                cloned.borrow_mut()?.tuple = values.into_boxed_slice();
+                return Ok(Value::TypedTuple(cloned));
+            }
+            Value::TupleVariant(tuple) => {
+                if let Some(value) = self.lookup(tuple) {
+                    return Ok(value);
+                }
+
+                let source = tuple.borrow_ref()?;
+
+                let cloned = Shared::new(TupleVariant {
+                    enum_hash: source.enum_hash,
+                    hash: source.hash,
+                    tuple: Vec::new().into_boxed_slice(),
+                });
+
+                self.seen
+                    .insert(tuple.as_ptr(), Value::TupleVariant(cloned.clone()));
+                let values = self.clone_values(source.tuple.iter())?;
+                cloned.borrow_mut()?.tuple = values.into_boxed_slice();
+                return Ok(Value::TupleVariant(cloned));
+            }
+            Value::TypedObject(object) => {
+                if let Some(value) = self.lookup(object) {
+                    return Ok(value);
+                }
+
+                let source = object.borrow_ref()?;
+
+                let cloned = Shared::new(TypedObject {
+                    hash: source.hash,
+                    object: Object::new(),
+                });
+
+                self.seen
+                    .insert(object.as_ptr(), Value::TypedObject(cloned.clone()));
+                let values = self.clone_object(&source.object)?;
+                cloned.borrow_mut()?.object = values;
+                return Ok(Value::TypedObject(cloned));
+            }
+            Value::VariantObject(object) => {
+                if let Some(value) = self.lookup(object) {
+                    return Ok(value);
+                }
+
+                let source = object.borrow_ref()?;
+
+                let cloned = Shared::new(VariantObject {
+                    enum_hash: source.enum_hash,
+                    hash: source.hash,
+                    object: Object::new(),
+                });
+
+                self.seen
+                    .insert(object.as_ptr(), Value::VariantObject(cloned.clone()));
+                let values = self.clone_object(&source.object)?;
+                cloned.borrow_mut()?.object = values;
+                return Ok(Value::VariantObject(cloned));
+            }
+            Value::Any(any) => {
+                if let Some(value) = self.lookup(any) {
+                    return Ok(value);
+                }
+
+                any.as_ptr()
+            }
+        };
+
+        let cloned = (self.external)(value)?;
+        self.seen.insert(ptr, cloned.clone());
+        Ok(cloned)
+    }
+
+    /// Deep clone a sequence of values.
+    fn clone_values<'a, I>(&mut self, values: I) -> Result<Vec<Value>, VmError>
+    where
+        I: IntoIterator<Item = &'a Value>,
+    {
+        let mut output = Vec::new();
+
+        for value in values {
+            output.push(self.clone_value(value)?);
+        }
+
+        Ok(output)
+    }
+
+    /// Deep clone the contents of an object.
+    fn clone_object(&mut self, object: &Object<Value>) -> Result<Object<Value>, VmError> {
+        let mut output = Object::with_capacity(object.len());
+
+        for (key, value) in object {
+            output.insert(key.clone(), self.clone_value(value)?);
+        }
+
+        Ok(output)
+    }
+}
+
+/// Deep clone the given value, where external values are cloned by calling
+/// the [CLONE] protocol registered for their type.
+pub(crate) fn deep_clone(cx: &CallContext<'_>, value: &Value) -> Result<Value, VmError> {
+    let mut deep = DeepClone::new(|value: &Value| {
+        let hash = Hash::instance_function(value.value_type()?, CLONE);
+
+        if !cx.contains_fn(hash) {
+            return Err(VmError::from(VmErrorKind::MissingProtocol {
+                protocol: CLONE,
+                actual: value.type_info()?,
+            }));
+        }
+
+        cx.call_fn(hash, (value.clone(),))
+    });
+
+    deep.clone_value(value)
+}
+
+/// Helper to calculate a structural hash of values.
+///
+/// Values which compare equal produce the same hash. Objects are hashed
+/// independently of their iteration order.
+pub(crate) struct DeepHash {
+    /// Shared values currently being hashed, used to detect cycles.
+    visiting: HashSet<*const ()>,
+}
+
+impl DeepHash {
+    const UNIT: u8 = 0;
+    const BOOL: u8 = 1;
+    const BYTE: u8 = 2;
+    const CHAR: u8 = 3;
+    const INTEGER: u8 = 4;
+    const FLOAT: u8 = 5;
+    const TYPE: u8 = 6;
+    const STRING: u8 = 7;
+    const BYTES: u8 = 8;
+    const VEC: u8 = 9;
+    const TUPLE: u8 = 10;
+    const OBJECT: u8 = 11;
+    const OPTION: u8 = 12;
+    const RESULT: u8 = 13;
+    const TYPED: u8 = 14;
+    const VARIANT: u8 = 15;
+    const CYCLE: u8 = 16;
+
+    /// Construct a new structural hash helper.
+    pub(crate) fn new() -> Self {
+        Self {
+            visiting: HashSet::new(),
+        }
+    }
+
+    /// Calculate the structural hash of the given value.
+    pub(crate) fn hash_value(&mut self, value: &Value) -> Result<u64, VmError> {
+        let mut hasher = BuildHasherDefault::<XxHash64>::default().build_hasher();
+
+        match value {
+            Value::Unit => {
+                Self::UNIT.hash(&mut hasher);
+            }
+            Value::Bool(b) => {
+                Self::BOOL.hash(&mut hasher);
+                b.hash(&mut hasher);
+            }
+            Value::Byte(b) => {
+                Self::BYTE.hash(&mut hasher);
+                b.hash(&mut hasher);
+            }
+            Value::Char(c) => {
+                Self::CHAR.hash(&mut hasher);
+                c.hash(&mut hasher);
+            }
+            Value::Integer(n) => {
+                Self::INTEGER.hash(&mut hasher);
+                n.hash(&mut hasher);
+            }
+            Value::Float(n) => {
+                Self::FLOAT.hash(&mut hasher);
+                // NB: make sure that `0.0` and `-0.0` hash the same since they
+                // compare equal.
+                let n = if *n == 0.0 { 0.0f64 } else { *n };
+                n.to_bits().hash(&mut hasher);
+            }
+            Value::Type(hash) => {
+                Self::TYPE.hash(&mut hasher);
+                hash.hash(&mut hasher);
+            }
+            Value::StaticString(string) => {
+                Self::STRING.hash(&mut hasher);
+                string.as_ref().as_str().hash(&mut hasher);
+            }
+            Value::String(string) => {
+                Self::STRING.hash(&mut hasher);
+                string.borrow_ref()?.as_str().hash(&mut hasher);
+            }
+            Value::Bytes(bytes) => {
+                Self::BYTES.hash(&mut hasher);
+                bytes.borrow_ref()?.hash(&mut hasher);
+            }
+            Value::Vec(vec) => {
+                if !self.visiting.insert(vec.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                Self::VEC.hash(&mut hasher);
+                self.hash_values(vec.borrow_ref()?.iter(), &mut hasher)?;
+                self.visiting.remove(&vec.as_ptr());
+            }
+            Value::Tuple(tuple) => {
+                if !self.visiting.insert(tuple.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                Self::TUPLE.hash(&mut hasher);
+                self.hash_values(tuple.borrow_ref()?.iter(), &mut hasher)?;
+                self.visiting.remove(&tuple.as_ptr());
+            }
+            Value::Object(object) => {
+                if !self.visiting.insert(object.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                Self::OBJECT.hash(&mut hasher);
+                self.hash_object(&*object.borrow_ref()?, &mut hasher)?;
+                self.visiting.remove(&object.as_ptr());
+            }
+            Value::Option(option) => {
+                if !self.visiting.insert(option.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                Self::OPTION.hash(&mut hasher);
+
+                if let Some(value) = &*option.borrow_ref()? {
+                    self.hash_value(value)?.hash(&mut hasher);
+                }
+
+                self.visiting.remove(&option.as_ptr());
+            }
+            Value::Result(result) => {
+                if !self.visiting.insert(result.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                Self::RESULT.hash(&mut hasher);
+
+                match &*result.borrow_ref()? {
+                    Ok(value) => {
+                        true.hash(&mut hasher);
+                        self.hash_value(value)?.hash(&mut hasher);
+                    }
+                    Err(value) => {
+                        false.hash(&mut hasher);
+                        self.hash_value(value)?.hash(&mut hasher);
+                    }
+                }
+
+                self.visiting.remove(&result.as_ptr());
+            }
+            Value::TypedTuple(tuple) => {
+                if !self.visiting.insert(tuple.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                let tuple_ref = tuple.borrow_ref()?;
+                Self::TYPED.hash(&mut hasher);
+                tuple_ref.hash.hash(&mut hasher);
+                self.hash_values(tuple_ref.tuple.iter(), &mut hasher)?;
+                self.visiting.remove(&tuple.as_ptr());
+            }
+            Value::TupleVariant(tuple) => {
+                if !self.visiting.insert(tuple.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                let tuple_ref = tuple.borrow_ref()?;
+                Self::VARIANT.hash(&mut hasher);
+                tuple_ref.enum_hash.hash(&mut hasher);
+                tuple_ref.hash.hash(&mut hasher);
+                self.hash_values(tuple_ref.tuple.iter(), &mut hasher)?;
+                self.visiting.remove(&tuple.as_ptr());
+            }
+            Value::TypedObject(object) => {
+                if !self.visiting.insert(object.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                let object_ref = object.borrow_ref()?;
+                Self::TYPED.hash(&mut hasher);
+                object_ref.hash.hash(&mut hasher);
+                self.hash_object(&object_ref.object, &mut hasher)?;
+                self.visiting.remove(&object.as_ptr());
+            }
+            Value::VariantObject(object) => {
+                if !self.visiting.insert(object.as_ptr()) {
+                    return Ok(Self::cycle());
+                }
+
+                let object_ref = object.borrow_ref()?;
+                Self::VARIANT.hash(&mut hasher);
+                object_ref.enum_hash.hash(&mut hasher);
+                object_ref.hash.hash(&mut hasher);
+                self.hash_object(&object_ref.object, &mut hasher)?;
+                self.visiting.remove(&object.as_ptr());
+            }
+            actual => {
+                return Err(VmError::from(VmErrorKind::UnsupportedHash {
+                    actual: actual.type_info()?,
+                }));
+            }
+        }
+
+        Ok(hasher.finish())
+    }
+
+    /// The hash used for a value which has already been visited.
+    fn cycle() -> u64 {
+        let mut hasher = BuildHasherDefault::<XxHash64>::default().build_hasher();
+        Self::CYCLE.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Hash a sequence of values in order.
+    fn hash_values<'a, I, H>(&mut self, values: I, hasher: &mut H) -> Result<(), VmError>
+    where
+        I: IntoIterator<Item = &'a Value>,
+        H: std::hash::Hasher,
+    {
+        let mut len = 0usize;
+
+        for value in values {
+            self.hash_value(value)?.hash(hasher);
+            len += 1;
+        }
+
+        len.hash(hasher);
+        Ok(())
+    }
+
+    /// Hash the content of an object, independently of iteration order.
+    fn hash_object<H>(&mut self, object: &Object<Value>, hasher: &mut H) -> Result<(), VmError>
+    where
+        H: std::hash::Hasher,
+    {
+        let mut combined = 0u64;
+
+        for (key, value) in object {
+            let mut entry = BuildHasherDefault::<XxHash64>::default().build_hasher();
+            key.hash(&mut entry);
+            self.hash_value(value)?.hash(&mut entry);
+            combined = combined.wrapping_add(entry.finish());
+        }
+
+        object.len().hash(hasher);
+        combined.hash(hasher);
+        Ok(())
+    }
+}
+
+/// Freeze the given value and every value reachable from it.
+pub(crate) fn freeze_deep(value: &Value) -> Result<(), VmError> {
+    let mut visited = HashSet::new();
+    let mut queue = vec![value.clone()];
+
+    while let Some(value) = queue.pop() {
+        value.freeze();
+
+        let ptr = match &value {
+            Value::Vec(vec) => vec.as_ptr(),
+            Value::Tuple(tuple) => tuple.as_ptr(),
+            Value::Object(object) => object.as_ptr(),
+            Value::Option(option) => option.as_ptr(),
+            Value::Result(result) => result.as_ptr(),
+            Value::GeneratorState(state) => state.as_ptr(),
+            Value::TypedTuple(tuple) => tuple.as_ptr(),
+            Value::TupleVariant(tuple) => tuple.as_ptr(),
+            Value::TypedObject(object) => object.as_ptr(),
+            Value::VariantObject(object) => object.as_ptr(),
+            _ => continue,
+        };
+
+        if !visited.insert(ptr) {
+            continue;
+        }
+
+        match &value {
+            Value::Vec(vec) => queue.extend(vec.borrow_ref()?.iter().cloned()),
+            Value::Tuple(tuple) => queue.extend(tuple.borrow_ref()?.iter().cloned()),
+            Value::Object(object) => queue.extend(object.borrow_ref()?.values().cloned()),
+            Value::Option(option) => queue.extend(option.borrow_ref()?.iter().cloned()),
+            Value::Result(result) => match &*result.borrow_ref()? {
+                Ok(value) | Err(value) => queue.push(value.clone()),
+            },
+            Value::GeneratorState(state) => match &*state.borrow_ref()? {
+                GeneratorState::Yielded(value) | GeneratorState::Complete(value) => {
+                    queue.push(value.clone())
+                }
+            },
+            Value::TypedTuple(tuple) => queue.extend(tuple.borrow_ref()?.tuple.iter().cloned()),
+            Value::TupleVariant(tuple) => queue.extend(tuple.borrow_ref()?.tuple.iter().cloned()),
+            Value::TypedObject(object) => {
+                queue.extend(object.borrow_ref()?.object.values().cloned())
+            }
+            Value::VariantObject(object) => {
+                queue.extend(object.borrow_ref()?.object.values().cloned())
+            }
+            _ => (),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use crate::collections::HashMap;
+use crate::statics::Statics;
+use crate::{Args, Function, Shared, Value, VmError};
+
+/// Handlers for named events, which scripts register with
+/// `std::event::on(name, handler)`.
+///
+/// Every virtual machine has a registry, which is shared with the virtual
+/// machines it spawns to call async functions, generators, and closures.
+/// Since running a virtual machine consumes it, the host can hold on to a
+/// clone of the registry to fire events after the script has run.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Unit, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// // NB: normally the unit would be compiled from a script which registers
+/// // handlers with `std::event::on`.
+/// let unit = Arc::new(Unit::default());
+///
+/// let vm = Vm::new(context, unit);
+/// let events = vm.events().clone();
+///
+/// let output = events.fire("save", (1i64,))?;
+/// assert!(output.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Events {
+    handlers: Shared<HashMap<String, Vec<Shared<Function>>>>,
+}
+
+impl Events {
+    /// Construct a new empty registry.
+    pub fn new() -> Self {
+        Self {
+            handlers: Shared::new(HashMap::new()),
+        }
+    }
+
+    /// Register a handler for the event with the given name.
+    ///
+    /// Handlers are called in the order they were registered in.
+    pub fn on(&self, name: &str, handler: Shared<Function>) -> Result<(), VmError> {
+        let mut handlers = self.handlers.borrow_mut()?;
+        handlers.entry(name.to_owned()).or_default().push(handler);
+        Ok(())
+    }
+
+    /// Remove every handler for the event with the given name, returning
+    /// `true` if there were any.
+    pub fn off(&self, name: &str) -> Result<bool, VmError> {
+        Ok(self.handlers.borrow_mut()?.remove(name).is_some())
+    }
+
+    /// Get the names of all events with registered handlers, in sorted order.
+    pub fn names(&self) -> Result<Vec<String>, VmError> {
+        let mut names = self
+            .handlers
+            .borrow_ref()?
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        names.sort();
+        Ok(names)
+    }
+
+    /// Get the handlers registered for the event with the given name.
+    pub fn handlers(&self, name: &str) -> Result<Vec<Shared<Function>>, VmError> {
+        Ok(match self.handlers.borrow_ref()?.get(name) {
+            Some(handlers) => handlers.clone(),
+            None => Vec::new(),
+        })
+    }
+
+    /// Fire the event with the given name, calling every handler registered
+    /// for it with a copy of the given arguments.
+    ///
+    /// Returns the values produced by the handlers in the order they were
+    /// registered in. Async handlers produce futures, which can be awaited by
+    /// using [async_fire][Events::async_fire] instead.
+    pub fn fire<A>(&self, name: &str, args: A) -> Result<Vec<Value>, VmError>
+    where
+        A: Args + Clone,
+    {
+        let mut output = Vec::new();
+
+        for handler in self.handlers(name)? {
+            let handler = handler.borrow_ref()?;
+            output.push(handler.call_with_events(args.clone(), self, &Statics::default())?);
+        }
+
+        Ok(output)
+    }
+
+    /// Fire the event with the given name like [fire][Events::fire], but
+    /// await the futures produced by async handlers.
+    pub async fn async_fire<A>(&self, name: &str, args: A) -> Result<Vec<Value>, VmError>
+    where
+        A: Args + Clone,
+    {
+        let mut output = Vec::new();
+
+        for value in self.fire(name, args)? {
+            output.push(match value {
+                Value::Future(future) => {
+                    let mut future = future.owned_mut()?;
+                    (&mut *future).await?
+                }
+                value => value,
+            });
+        }
+
+        Ok(output)
+    }
+}
+
+impl Default for Events {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,150 @@
+//! Finalizers for script-defined types, declared through `impl Drop`.
+
+use crate::statics::Statics;
+use crate::{Context, Events, Hash, Unit, Value, Vm, VmError, VmErrorKind};
+use std::cell::Cell;
+use std::sync::Arc;
+
+/// The number of instructions a single finalizer is allowed to execute before
+/// it's aborted with an error.
+pub const FINALIZER_BUDGET: usize = 10_000;
+
+thread_local! {
+    /// Set while a finalizer is running on the current thread.
+    static RUNNING: Cell<bool> = Cell::new(false);
+}
+
+/// Values with finalizers which are being tracked by a virtual machine.
+///
+/// A value is finalized at the first safe point after the virtual machine
+/// holds the only reference to it, which is whenever a function returns.
+/// Values which are still referenced by the host when the virtual machine is
+/// dropped are not finalized.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Finalizers {
+    /// Tracked values, in the order they were constructed, together with the
+    /// hash of their finalizer.
+    tracked: Vec<(Hash, Value)>,
+}
+
+impl Finalizers {
+    /// Start tracking the given value of the type with the given hash, if
+    /// the type has a finalizer.
+    pub(crate) fn track(&mut self, unit: &Unit, type_hash: Hash, value: &Value) {
+        if let Some(hash) = unit.hooks().finalizers.get(&type_hash) {
+            self.tracked.push((*hash, value.clone()));
+        }
+    }
+
+    /// Iterate over the tracked values.
+    pub(crate) fn values(&self) -> impl Iterator<Item = &Value> + '_ {
+        self.tracked.iter().map(|(_, value)| value)
+    }
+
+    /// Stop tracking all values without finalizing them.
+    pub(crate) fn clear(&mut self) {
+        self.tracked.clear();
+    }
+
+    /// Take the first tracked value which is no longer referenced by anything
+    /// but the tracker.
+    ///
+    /// Values which have been taken, like through `drop`, are no longer
+    /// tracked since they've already been finalized.
+    pub(crate) fn take_released(&mut self) -> Option<(Hash, Value)> {
+        loop {
+            let index = self.tracked.iter().position(|(_, v)| is_unique(v))?;
+            let (hash, value) = self.tracked.remove(index);
+
+            if is_readable(&value) {
+                return Some((hash, value));
+            }
+        }
+    }
+}
+
+/// Look up the finalizer for the given value, if it has one.
+pub(crate) fn lookup(unit: &Unit, value: &Value) -> Option<Hash> {
+    let type_hash = match value {
+        Value::TypedObject(object) => object.borrow_ref().ok()?.hash,
+        Value::TypedTuple(tuple) => tuple.borrow_ref().ok()?.hash,
+        _ => return None,
+    };
+
+    unit.hooks().finalizers.get(&type_hash).copied()
+}
+
+/// Call the finalizer with the given hash on a value.
+///
+/// The finalizer runs to completion in a new virtual machine, and errors if it
+/// executes more than [FINALIZER_BUDGET] instructions. Finalizers don't nest,
+/// so values released while a finalizer is running are not finalized.
+pub(crate) fn finalize(
+    context: &Arc<Context>,
+    unit: &Arc<Unit>,
+    events: &Events,
+    statics: &Statics,
+    hash: Hash,
+    value: Value,
+) -> Result<(), VmError> {
+    let _guard = match Running::enter() {
+        Some(guard) => guard,
+        None => return Ok(()),
+    };
+
+    let vm = Vm::new(context.clone(), unit.clone())
+        .with_events(events.clone())
+        .with_statics(statics.clone());
+    let mut execution = vm.call(hash, (value,))?;
+    let mut budget = FINALIZER_BUDGET;
+
+    while execution.step()?.is_none() {
+        budget -= 1;
+
+        if budget == 0 {
+            return Err(VmError::from(VmErrorKind::FinalizerBudgetExceeded {
+                budget: FINALIZER_BUDGET,
+            }));
+        }
+    }
+
+    Ok(())
+}
+
+/// Guard which marks a finalizer as running on the current thread.
+struct Running(());
+
+impl Running {
+    /// Mark a finalizer as running, unless one already is.
+    fn enter() -> Option<Self> {
+        if RUNNING.with(|running| running.replace(true)) {
+            return None;
+        }
+
+        Some(Self(()))
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.with(|running| running.set(false));
+    }
+}
+
+/// Test if the given tracked value is only referenced by the tracker.
+fn is_unique(value: &Value) -> bool {
+    match value {
+        Value::TypedObject(object) => object.is_unique(),
+        Value::TypedTuple(tuple) => tuple.is_unique(),
+        _ => false,
+    }
+}
+
+/// Test if the given tracked value can still be accessed.
+fn is_readable(value: &Value) -> bool {
+    match value {
+        Value::TypedObject(object) => object.is_readable(),
+        Value::TypedTuple(tuple) => tuple.is_readable(),
+        _ => false,
+    }
+}
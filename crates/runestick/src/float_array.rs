@@ -0,0 +1,208 @@
+use crate::{Function, VmError};
+use std::fmt;
+
+/// A contiguous array of floats, with elementwise operations implemented
+/// natively.
+///
+/// Unlike a vector of values, the elements are stored unboxed, which makes
+/// operations over large arrays like scoring or statistics cheap.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::FloatArray;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let a = FloatArray::from_vec(vec![1.0, 2.0, 3.0]);
+/// let b = FloatArray::filled(3, 2.0);
+///
+/// assert_eq!(a.mul(&b)?.to_vec(), vec![2.0, 4.0, 6.0]);
+/// assert_eq!(a.dot(&b)?, 12.0);
+/// assert_eq!(a.sum(), 6.0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FloatArray {
+    values: Vec<f64>,
+}
+
+impl FloatArray {
+    /// Construct a new empty array.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct an array out of the given floats.
+    pub fn from_vec(values: Vec<f64>) -> Self {
+        Self { values }
+    }
+
+    /// Construct an array with `len` elements, which are all `value`.
+    pub fn filled(len: usize, value: f64) -> Self {
+        Self {
+            values: vec![value; len],
+        }
+    }
+
+    /// Construct an array with `len` elements, which are all zero.
+    pub fn zeros(len: usize) -> Self {
+        Self::filled(len, 0.0)
+    }
+
+    /// Access the elements of the array.
+    pub fn as_slice(&self) -> &[f64] {
+        &self.values
+    }
+
+    /// Copy the elements of the array into a vector.
+    pub fn to_vec(&self) -> Vec<f64> {
+        self.values.clone()
+    }
+
+    /// The number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Test if the array is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Get the element at the given index.
+    pub fn get(&self, index: usize) -> Option<f64> {
+        self.values.get(index).copied()
+    }
+
+    /// Set the element at the given index.
+    pub fn set(&mut self, index: usize, value: f64) -> Result<(), VmError> {
+        match self.values.get_mut(index) {
+            Some(slot) => {
+                *slot = value;
+                Ok(())
+            }
+            None => Err(VmError::panic(format!(
+                "index {} is out of bounds for an array of length {}",
+                index,
+                self.values.len()
+            ))),
+        }
+    }
+
+    /// Append an element to the array.
+    pub fn push(&mut self, value: f64) {
+        self.values.push(value);
+    }
+
+    /// Add the elements of the two arrays.
+    pub fn add(&self, other: &Self) -> Result<Self, VmError> {
+        self.zip(other, |a, b| a + b)
+    }
+
+    /// Subtract the elements of `other` from the elements of this array.
+    pub fn sub(&self, other: &Self) -> Result<Self, VmError> {
+        self.zip(other, |a, b| a - b)
+    }
+
+    /// Multiply the elements of the two arrays.
+    pub fn mul(&self, other: &Self) -> Result<Self, VmError> {
+        self.zip(other, |a, b| a * b)
+    }
+
+    /// Multiply every element by the given factor.
+    pub fn scale(&self, factor: f64) -> Self {
+        Self {
+            values: self.values.iter().map(|v| v * factor).collect(),
+        }
+    }
+
+    /// The sum of all elements.
+    pub fn sum(&self) -> f64 {
+        self.values.iter().sum()
+    }
+
+    /// The mean of all elements, or `None` if the array is empty.
+    pub fn mean(&self) -> Option<f64> {
+        if self.values.is_empty() {
+            return None;
+        }
+
+        Some(self.sum() / self.values.len() as f64)
+    }
+
+    /// The smallest element, or `None` if the array is empty.
+    pub fn min(&self) -> Option<f64> {
+        self.values.iter().copied().fold(None, |min, v| match min {
+            Some(min) if min <= v => Some(min),
+            _ => Some(v),
+        })
+    }
+
+    /// The largest element, or `None` if the array is empty.
+    pub fn max(&self) -> Option<f64> {
+        self.values.iter().copied().fold(None, |max, v| match max {
+            Some(max) if max >= v => Some(max),
+            _ => Some(v),
+        })
+    }
+
+    /// The dot product of the two arrays.
+    pub fn dot(&self, other: &Self) -> Result<f64, VmError> {
+        self.check_len(other)?;
+
+        Ok(self
+            .values
+            .iter()
+            .zip(&other.values)
+            .map(|(a, b)| a * b)
+            .sum())
+    }
+
+    /// Construct a new array by calling the given function with every
+    /// element.
+    pub fn map(&self, f: Function) -> Result<Self, VmError> {
+        let mut values = Vec::with_capacity(self.values.len());
+
+        for v in &self.values {
+            values.push(f.call::<_, f64>((*v,))?);
+        }
+
+        Ok(Self { values })
+    }
+
+    /// Combine the elements of the two arrays with the given operation.
+    fn zip(&self, other: &Self, op: impl Fn(f64, f64) -> f64) -> Result<Self, VmError> {
+        self.check_len(other)?;
+
+        Ok(Self {
+            values: self
+                .values
+                .iter()
+                .zip(&other.values)
+                .map(|(a, b)| op(*a, *b))
+                .collect(),
+        })
+    }
+
+    /// Check that the two arrays have the same length.
+    fn check_len(&self, other: &Self) -> Result<(), VmError> {
+        if self.values.len() != other.values.len() {
+            return Err(VmError::panic(format!(
+                "arrays have different lengths: {} and {}",
+                self.values.len(),
+                other.values.len()
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for FloatArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(&self.values).finish()
+    }
+}
+
+impl_external!(FloatArray);
@@ -0,0 +1,196 @@
+use crate::Span;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// The delimiter used to open and close a frontmatter block.
+const FENCE: &str = "---";
+
+/// Key-value metadata declared in a frontmatter block at the top of a source.
+///
+/// Frontmatter is delimited by lines consisting of `---`, and each line inside
+/// of it is an entry like `key: value`. Empty lines and lines starting with `#`
+/// are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::SourceHeader;
+///
+/// let source = "#!/usr/bin/env rune\n---\nname: hello\n---\nfn main() {}";
+/// let header = SourceHeader::parse(source).unwrap();
+///
+/// assert_eq!(&source[header.span.end..], "fn main() {}");
+///
+/// let frontmatter = header.frontmatter.unwrap();
+/// assert_eq!(frontmatter.get("name"), Some("hello"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Frontmatter {
+    entries: BTreeMap<String, String>,
+}
+
+impl Frontmatter {
+    /// Construct a new, empty frontmatter.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the value of the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        Some(self.entries.get(key)?.as_str())
+    }
+
+    /// Insert an entry, returning the previous value if one was present.
+    pub fn insert(&mut self, key: String, value: String) -> Option<String> {
+        self.entries.insert(key, value)
+    }
+
+    /// Merge all entries from `other` into this frontmatter, replacing any
+    /// existing values.
+    pub fn extend(&mut self, other: Frontmatter) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Test if the frontmatter is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over all entries, ordered by key.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+}
+
+/// Error raised when parsing a malformed frontmatter block.
+#[derive(Debug, Clone, Copy, Error)]
+pub enum FrontmatterError {
+    /// The frontmatter block is missing its closing `---`.
+    #[error("unterminated frontmatter, expected a closing `---`")]
+    Unterminated {
+        /// The span of the frontmatter block.
+        span: Span,
+    },
+    /// A line in the frontmatter block is not a `key: value` entry.
+    #[error("expected frontmatter entry like `key: value`")]
+    ExpectedEntry {
+        /// The span of the bad line.
+        span: Span,
+    },
+}
+
+impl FrontmatterError {
+    /// Get the span of the error.
+    pub fn span(&self) -> Span {
+        match *self {
+            Self::Unterminated { span } => span,
+            Self::ExpectedEntry { span } => span,
+        }
+    }
+}
+
+/// The header of a source, which consists of an optional shebang line like
+/// `#!/usr/bin/env rune`, followed by an optional [Frontmatter] block.
+///
+/// Neither is part of the language, so the header is skipped when parsing.
+#[derive(Debug, Clone, Default)]
+pub struct SourceHeader {
+    /// The span of the shebang line, if present.
+    pub shebang: Option<Span>,
+    /// The parsed frontmatter, if present.
+    pub frontmatter: Option<Frontmatter>,
+    /// The span of the entire header. Code starts at the end of it.
+    pub span: Span,
+}
+
+impl SourceHeader {
+    /// Parse the header at the start of the given source.
+    pub fn parse(source: &str) -> Result<Self, FrontmatterError> {
+        let mut lines = Lines { source, cursor: 0 };
+        let mut header = Self::default();
+
+        if source.starts_with("#!") && !source.starts_with("#![") {
+            if let Some((span, _)) = lines.next() {
+                header.shebang = Some(span);
+                header.span.end = lines.cursor;
+            }
+        }
+
+        match lines.clone().next() {
+            Some((_, line)) if line.trim_end() == FENCE => {
+                lines.next();
+            }
+            _ => return Ok(header),
+        }
+
+        let start = header.span.end;
+        let mut frontmatter = Frontmatter::new();
+
+        loop {
+            let (span, line) = match lines.next() {
+                Some(line) => line,
+                None => {
+                    let span = Span::new(start, source.len());
+                    return Err(FrontmatterError::Unterminated { span });
+                }
+            };
+
+            let line = line.trim();
+
+            if line == FENCE {
+                break;
+            }
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let colon = line
+                .find(':')
+                .ok_or_else(|| FrontmatterError::ExpectedEntry { span })?;
+
+            let key = line[..colon].trim();
+
+            if key.is_empty() {
+                return Err(FrontmatterError::ExpectedEntry { span });
+            }
+
+            let value = line[colon + 1..].trim();
+            frontmatter.insert(key.to_owned(), value.to_owned());
+        }
+
+        header.frontmatter = Some(frontmatter);
+        header.span.end = lines.cursor;
+        Ok(header)
+    }
+}
+
+/// An iterator over the lines of a source and their spans.
+#[derive(Clone)]
+struct Lines<'a> {
+    source: &'a str,
+    cursor: usize,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = (Span, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.source.get(self.cursor..).filter(|s| !s.is_empty())?;
+        let start = self.cursor;
+
+        let line = match rest.find('\n') {
+            Some(n) => {
+                self.cursor += n + 1;
+                &rest[..n]
+            }
+            None => {
+                self.cursor = self.source.len();
+                rest
+            }
+        };
+
+        Some((Span::new(start, start + line.len()), line))
+    }
+}
@@ -1,8 +1,10 @@
 use crate::context::Handler;
+use crate::statics::Statics;
 use crate::VmErrorKind;
 use crate::{
-    Args, Call, Context, FromValue, Future, Generator, Hash, OwnedRef, RawOwnedRef, Shared, Stack,
-    Stream, Tuple, Unit, UnsafeFromValue, Value, Vm, VmCall, VmError, VmHalt,
+    Args, Call, CallContext, Context, Events, FromValue, Future, Generator, Hash, OwnedRef,
+    RawOwnedRef, Shared, Stack, Stream, Tuple, Unit, UnsafeFromValue, Value, Vm, VmCall, VmError,
+    VmHalt,
 };
 use std::fmt;
 use std::sync::Arc;
@@ -15,6 +17,29 @@ pub struct Function {
 impl Function {
     /// Perform a call over the function represented by this function pointer.
     pub fn call<A, T>(&self, args: A) -> Result<T, VmError>
+    where
+        A: Args,
+        T: FromValue,
+    {
+        self.call_with_events(args, &Events::new(), &Statics::default())
+    }
+
+    /// Access the environment captured by the function, if it's a closure.
+    pub(crate) fn environment(&self) -> Option<&Shared<Tuple>> {
+        match &self.inner {
+            Inner::FnClosureOffset(closure) => Some(&closure.environment),
+            _ => None,
+        }
+    }
+
+    /// Perform a call where any virtual machine used shares the given event
+    /// handlers and statics.
+    pub(crate) fn call_with_events<A, T>(
+        &self,
+        args: A,
+        events: &Events,
+        statics: &Statics,
+    ) -> Result<T, VmError>
     where
         A: Args,
         T: FromValue,
@@ -23,13 +48,17 @@ impl Function {
             Inner::FnHandler(handler) => {
                 let mut stack = Stack::with_capacity(A::count());
                 args.into_stack(&mut stack)?;
-                (handler.handler)(&mut stack, A::count())?;
+                let mut cx =
+                    CallContext::new(&mut stack, &handler.context, &handler.unit, events, statics);
+                (handler.handler)(&mut cx, A::count())?;
                 stack.pop()?
             }
-            Inner::FnOffset(fn_offset) => fn_offset.call(args, ())?,
-            Inner::FnClosureOffset(closure) => closure
-                .fn_offset
-                .call(args, (closure.environment.clone(),))?,
+            Inner::FnOffset(fn_offset) => fn_offset.call(args, (), events, statics)?,
+            Inner::FnClosureOffset(closure) => {
+                closure
+                    .fn_offset
+                    .call(args, (closure.environment.clone(),), events, statics)?
+            }
             Inner::FnTuple(tuple) => {
                 Self::check_args(A::count(), tuple.args)?;
                 Value::typed_tuple(tuple.hash, args.into_vec()?)
@@ -43,6 +72,42 @@ impl Function {
         Ok(T::from_value(value)?)
     }
 
+    /// Perform a call with a number of arguments which is only known at
+    /// runtime, where any virtual machine used shares the given event
+    /// handlers and statics.
+    pub(crate) fn call_vec_with_events(
+        &self,
+        args: Vec<Value>,
+        events: &Events,
+        statics: &Statics,
+    ) -> Result<Value, VmError> {
+        let count = args.len();
+
+        Ok(match &self.inner {
+            Inner::FnHandler(handler) => {
+                let mut stack = Stack::from(args);
+                let mut cx =
+                    CallContext::new(&mut stack, &handler.context, &handler.unit, events, statics);
+                (handler.handler)(&mut cx, count)?;
+                stack.pop()?
+            }
+            Inner::FnOffset(fn_offset) => fn_offset.call_vec(args, (), events, statics)?,
+            Inner::FnClosureOffset(closure) => {
+                closure
+                    .fn_offset
+                    .call_vec(args, (closure.environment.clone(),), events, statics)?
+            }
+            Inner::FnTuple(tuple) => {
+                Self::check_args(count, tuple.args)?;
+                Value::typed_tuple(tuple.hash, args)
+            }
+            Inner::FnVariantTuple(tuple) => {
+                Self::check_args(count, tuple.args)?;
+                Value::variant_tuple(tuple.enum_hash, tuple.hash, args)
+            }
+        })
+    }
+
     /// Call with the given virtual machine. This allows for certain
     /// optimizations, like avoiding the allocation of a new vm state in case
     /// the call is internal.
@@ -52,7 +117,16 @@ impl Function {
     pub(crate) fn call_with_vm(&self, vm: &mut Vm, args: usize) -> Result<Option<VmHalt>, VmError> {
         let reason = match &self.inner {
             Inner::FnHandler(handler) => {
-                (handler.handler)(vm.stack_mut(), args)?;
+                let events = vm.events().clone();
+                let statics = vm.statics().clone();
+                let mut cx = CallContext::new(
+                    vm.stack_mut(),
+                    &handler.context,
+                    &handler.unit,
+                    &events,
+                    &statics,
+                );
+                (handler.handler)(&mut cx, args)?;
                 None
             }
             Inner::FnOffset(fn_offset) => {
@@ -97,10 +171,19 @@ impl Function {
         Ok(reason)
     }
 
-    /// Create a function pointer from a handler.
-    pub(crate) fn from_handler(handler: Arc<Handler>) -> Self {
+    /// Create a function pointer from a handler, which is called with the
+    /// given context and unit.
+    pub(crate) fn from_handler(
+        handler: Arc<Handler>,
+        context: Arc<Context>,
+        unit: Arc<Unit>,
+    ) -> Self {
         Self {
-            inner: Inner::FnHandler(FnHandler { handler }),
+            inner: Inner::FnHandler(FnHandler {
+                handler,
+                context,
+                unit,
+            }),
         }
     }
 
@@ -233,6 +316,10 @@ enum Inner {
 struct FnHandler {
     /// The function handler.
     handler: Arc<Handler>,
+    /// The context the handler is called with.
+    context: Arc<Context>,
+    /// The unit the handler is called with.
+    unit: Arc<Unit>,
 }
 
 impl fmt::Debug for FnHandler {
@@ -255,19 +342,55 @@ struct FnOffset {
 
 impl FnOffset {
     /// Perform a call into the specified offset and return the produced value.
-    fn call<A, E>(&self, args: A, extra: E) -> Result<Value, VmError>
+    fn call<A, E>(
+        &self,
+        args: A,
+        extra: E,
+        events: &Events,
+        statics: &Statics,
+    ) -> Result<Value, VmError>
     where
         A: Args,
         E: Args,
     {
-        Function::check_args(A::count(), self.args)?;
+        self.unit.check_args(self.offset, A::count(), self.args)?;
 
-        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
+        let mut vm = Vm::new(self.context.clone(), self.unit.clone())
+            .with_events(events.clone())
+            .with_statics(statics.clone());
 
         vm.set_ip(self.offset);
         args.into_stack(vm.stack_mut())?;
         extra.into_stack(vm.stack_mut())?;
+        self.run(vm)
+    }
+
+    /// Perform a call into the specified offset with arguments in a vector
+    /// and return the produced value.
+    fn call_vec<E>(
+        &self,
+        args: Vec<Value>,
+        extra: E,
+        events: &Events,
+        statics: &Statics,
+    ) -> Result<Value, VmError>
+    where
+        E: Args,
+    {
+        self.unit.check_args(self.offset, args.len(), self.args)?;
+
+        let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), Stack::from(args))
+            .with_events(events.clone())
+            .with_statics(statics.clone());
+
+        vm.set_ip(self.offset);
+        extra.into_stack(vm.stack_mut())?;
+        self.run(vm)
+    }
 
+    /// Run the virtual machine set up to call the function according to its
+    /// calling convention.
+    fn run(&self, vm: Vm) -> Result<Value, VmError> {
         Ok(match self.call {
             Call::Stream => Value::from(Stream::new(vm)),
             Call::Generator => Value::from(Generator::new(vm)),
@@ -284,7 +407,7 @@ impl FnOffset {
     where
         E: Args,
     {
-        Function::check_args(args, self.args)?;
+        self.unit.check_args(self.offset, args, self.args)?;
 
         // Fast past, just allocate a call frame and keep running.
         if let Call::Immediate = self.call {
@@ -297,7 +420,9 @@ impl FnOffset {
 
         let mut new_stack = vm.stack_mut().drain_stack_top(args)?.collect::<Stack>();
         extra.into_stack(&mut new_stack)?;
-        let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), new_stack);
+        let mut vm = Vm::new_with_stack(self.context.clone(), self.unit.clone(), new_stack)
+            .with_events(vm.events().clone())
+            .with_statics(vm.statics().clone());
         vm.set_ip(self.offset);
         Ok(Some(VmCall::new(self.call, vm)))
     }
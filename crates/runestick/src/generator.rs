@@ -1,6 +1,6 @@
 use crate::{
-    FromValue, GeneratorState, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared,
-    UnsafeFromValue, Value, Vm, VmError, VmErrorKind, VmExecution,
+    FromValue, GeneratorState, GeneratorStatus, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef,
+    Shared, UnsafeFromValue, Value, Vm, VmError, VmErrorKind, VmExecution,
 };
 use std::fmt;
 use std::mem;
@@ -20,6 +20,11 @@ impl Generator {
         }
     }
 
+    /// Access the execution of the generator, unless it has completed.
+    pub(crate) fn execution(&self) -> Option<&VmExecution> {
+        self.execution.as_ref()
+    }
+
     /// Get the next value produced by this stream.
     pub fn next(&mut self) -> Result<Option<Value>, VmError> {
         Ok(match self.resume(Value::Unit)? {
@@ -49,6 +54,42 @@ impl Generator {
 
         Ok(state)
     }
+
+    /// Get the status of the generator.
+    pub fn state(&self) -> GeneratorStatus {
+        match &self.execution {
+            Some(..) if self.first => GeneratorStatus::Created,
+            Some(..) => GeneratorStatus::Suspended,
+            None => GeneratorStatus::Completed,
+        }
+    }
+
+    /// Dispose of the generator without resuming it to completion.
+    ///
+    /// If the generator is suspended in a scope with pending cleanup, like a
+    /// `defer` block or a `with` expression, that cleanup is run first as-if
+    /// the generator returned from where it was suspended. It is an error for
+    /// the cleanup to yield.
+    ///
+    /// This then releases the values held by the generator, running the
+    /// finalizers of any values which were only referenced by it. Once
+    /// disposed the generator is completed, and resuming it is an error.
+    pub fn dispose(&mut self) -> Result<(), VmError> {
+        let mut execution = match self.execution.take() {
+            Some(execution) => execution,
+            None => return Ok(()),
+        };
+
+        let result = if !self.first && execution.vm_mut()?.request_dispose() {
+            execution.vm_mut()?.stack_mut().push(Value::Unit);
+            execution.complete().map(drop)
+        } else {
+            Ok(())
+        };
+
+        execution.dispose()?;
+        result
+    }
 }
 
 impl fmt::Debug for Generator {
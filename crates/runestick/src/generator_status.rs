@@ -0,0 +1,42 @@
+use std::fmt;
+
+/// The status of a generator, as returned by
+/// [Generator::state][crate::Generator::state].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorStatus {
+    /// The generator has been created, but hasn't been resumed yet.
+    Created,
+    /// The generator has been resumed and is suspended at a `yield`.
+    Suspended,
+    /// The generator has completed or has been disposed.
+    Completed,
+}
+
+impl GeneratorStatus {
+    /// Test if the generator has been created, but not resumed.
+    pub fn is_created(&self) -> bool {
+        matches!(self, Self::Created)
+    }
+
+    /// Test if the generator is suspended.
+    pub fn is_suspended(&self) -> bool {
+        matches!(self, Self::Suspended)
+    }
+
+    /// Test if the generator has completed.
+    pub fn is_completed(&self) -> bool {
+        matches!(self, Self::Completed)
+    }
+}
+
+impl fmt::Display for GeneratorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Created => write!(f, "created"),
+            Self::Suspended => write!(f, "suspended"),
+            Self::Completed => write!(f, "completed"),
+        }
+    }
+}
+
+impl_external!(GeneratorStatus);
@@ -0,0 +1,255 @@
+use crate::{
+    FromValue, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, UnsafeFromValue, Value,
+    VmError, VmErrorKind,
+};
+use std::any;
+use std::fmt;
+
+/// A callback invoked with the resource of a handle when it is released.
+type DropFn = dyn FnOnce(Box<dyn any::Any>);
+
+/// An opaque handle to a resource owned by the host, like a socket or a
+/// database connection.
+///
+/// Scripts can't inspect handles, only pass them around. The resource is
+/// released when the last reference to the handle is dropped, at which point
+/// the drop callback registered through [with_drop][Handle::with_drop] is
+/// called with it. Native functions access the resource by downcasting the
+/// handle.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Handle, Value};
+/// use std::cell::Cell;
+/// use std::rc::Rc;
+///
+/// struct Connection {
+///     id: u32,
+/// }
+///
+/// # fn main() -> runestick::Result<()> {
+/// let closed = Rc::new(Cell::new(None));
+/// let c = closed.clone();
+///
+/// let handle = Handle::with_drop(Connection { id: 7 }, move |connection| {
+///     c.set(Some(connection.id));
+/// });
+///
+/// assert_eq!(handle.downcast_ref::<Connection>()?.id, 7);
+/// assert!(handle.downcast_ref::<String>().is_err());
+///
+/// let value = Value::from(handle);
+/// let copy = value.clone();
+///
+/// drop(value);
+/// assert_eq!(closed.get(), None);
+/// drop(copy);
+/// assert_eq!(closed.get(), Some(7));
+/// # Ok(())
+/// # }
+/// ```
+pub struct Handle {
+    /// The resource, or `None` if the handle has been closed.
+    resource: Option<Box<dyn any::Any>>,
+    /// The type name of the resource.
+    type_name: &'static str,
+    /// Callback to invoke when the resource is released.
+    on_drop: Option<Box<DropFn>>,
+}
+
+impl Handle {
+    /// Construct a new handle around the given resource, which is dropped
+    /// normally when the handle is released.
+    pub fn new<T>(resource: T) -> Self
+    where
+        T: any::Any,
+    {
+        Self {
+            resource: Some(Box::new(resource)),
+            type_name: any::type_name::<T>(),
+            on_drop: None,
+        }
+    }
+
+    /// Construct a new handle around the given resource, where the given
+    /// callback is called with the resource when the handle is released.
+    pub fn with_drop<T, F>(resource: T, on_drop: F) -> Self
+    where
+        T: any::Any,
+        F: 'static + FnOnce(T),
+    {
+        let on_drop = move |resource: Box<dyn any::Any>| {
+            if let Ok(resource) = resource.downcast::<T>() {
+                on_drop(*resource);
+            }
+        };
+
+        Self {
+            resource: Some(Box::new(resource)),
+            type_name: any::type_name::<T>(),
+            on_drop: Some(Box::new(on_drop)),
+        }
+    }
+
+    /// The type name of the resource in the handle.
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Test if the handle is a handle to a resource of the given type.
+    pub fn is<T>(&self) -> bool
+    where
+        T: any::Any,
+    {
+        match &self.resource {
+            Some(resource) => resource.is::<T>(),
+            None => false,
+        }
+    }
+
+    /// Test if the handle has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.resource.is_none()
+    }
+
+    /// Access the resource in the handle, erroring if it's a resource of a
+    /// different type or the handle is closed.
+    pub fn downcast_ref<T>(&self) -> Result<&T, VmError>
+    where
+        T: any::Any,
+    {
+        match &self.resource {
+            Some(resource) => resource
+                .downcast_ref::<T>()
+                .ok_or_else(|| unexpected::<T>(self.type_name)),
+            None => Err(closed(self.type_name)),
+        }
+    }
+
+    /// Access the resource in the handle mutably, erroring if it's a resource
+    /// of a different type or the handle is closed.
+    pub fn downcast_mut<T>(&mut self) -> Result<&mut T, VmError>
+    where
+        T: any::Any,
+    {
+        let type_name = self.type_name;
+
+        match &mut self.resource {
+            Some(resource) => resource
+                .downcast_mut::<T>()
+                .ok_or_else(|| unexpected::<T>(type_name)),
+            None => Err(closed(type_name)),
+        }
+    }
+
+    /// Take the resource out of the handle without calling the drop callback,
+    /// which closes the handle.
+    pub fn take<T>(&mut self) -> Result<T, VmError>
+    where
+        T: any::Any,
+    {
+        let resource = match self.resource.take() {
+            Some(resource) => resource,
+            None => return Err(closed(self.type_name)),
+        };
+
+        match resource.downcast::<T>() {
+            Ok(resource) => Ok(*resource),
+            Err(resource) => {
+                self.resource = Some(resource);
+                Err(unexpected::<T>(self.type_name))
+            }
+        }
+    }
+
+    /// Release the resource now, calling the drop callback, which closes the
+    /// handle.
+    ///
+    /// Closing a handle which is already closed does nothing.
+    pub fn close(&mut self) {
+        if let Some(resource) = self.resource.take() {
+            if let Some(on_drop) = self.on_drop.take() {
+                on_drop(resource);
+            }
+        }
+    }
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_closed() {
+            write!(f, "Handle({}, closed)", self.type_name)
+        } else {
+            write!(f, "Handle({})", self.type_name)
+        }
+    }
+}
+
+/// Construct an error for a handle which is closed.
+fn closed(type_name: &'static str) -> VmError {
+    VmError::from(VmErrorKind::HandleClosed { type_name })
+}
+
+/// Construct an error for a handle which was expected to hold a `T`.
+fn unexpected<T>(actual: &'static str) -> VmError
+where
+    T: any::Any,
+{
+    VmError::from(VmErrorKind::UnexpectedHandle {
+        expected: any::type_name::<T>(),
+        actual,
+    })
+}
+
+impl FromValue for Shared<Handle> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        value.into_handle()
+    }
+}
+
+impl FromValue for OwnedRef<Handle> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(value.into_handle()?.owned_ref()?)
+    }
+}
+
+impl FromValue for OwnedMut<Handle> {
+    fn from_value(value: Value) -> Result<Self, VmError> {
+        Ok(value.into_handle()?.owned_mut()?)
+    }
+}
+
+impl UnsafeFromValue for &Handle {
+    type Output = *const Handle;
+    type Guard = RawOwnedRef;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let handle = value.into_handle()?;
+        Ok(OwnedRef::into_raw(handle.owned_ref()?))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &*output
+    }
+}
+
+impl UnsafeFromValue for &mut Handle {
+    type Output = *mut Handle;
+    type Guard = RawOwnedMut;
+
+    unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        let handle = value.into_handle()?;
+        Ok(OwnedMut::into_raw(handle.owned_mut()?))
+    }
+
+    unsafe fn to_arg(output: Self::Output) -> Self {
+        &mut *output
+    }
+}
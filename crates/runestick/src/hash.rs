@@ -1,4 +1,5 @@
 use crate::{Component, Type};
+use serde::{Deserialize, Serialize};
 use std::any;
 use std::fmt;
 use std::hash;
@@ -13,7 +14,7 @@ const GETTER: usize = 3;
 const OBJECT_KEYS: usize = 4;
 
 /// The hash of a primitive thing.
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[repr(transparent)]
 pub struct Hash(u64);
 
@@ -35,7 +36,10 @@ impl Hash {
     pub fn from_type_id(type_id: any::TypeId) -> Self {
         // Safety: a type id is exactly a 64-bit unsigned integer.
         // And has an identical bit pattern to `Hash`.
-        unsafe { mem::transmute(type_id) }
+        {
+            let (a, b): (u64, u64) = unsafe { mem::transmute(type_id) };
+            Self(a ^ b)
+        }
     }
 
     /// Construct a hash to an instance function, where the instance is a
@@ -0,0 +1,475 @@
+//! Dumps of the values which are live in a virtual machine.
+
+use crate::collections::HashMap;
+use crate::{DebugInst, GeneratorState, Object, Shared, Unit, Value, Vm};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::mem;
+
+/// A graph of the shared values which are reachable from a virtual machine,
+/// as returned by [Vm::heap_dump].
+///
+/// Every shared allocation is a node in the graph, no matter how many values
+/// refer to it. Values which are stored inline, like integers, are not part of
+/// the graph. The dump can be serialized, like to JSON, to inspect it outside
+/// of the process.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Unit, Value, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// let unit = Arc::new(Unit::default());
+///
+/// let vm = Vm::new(context, unit);
+/// let dump = vm.heap_dump();
+/// assert!(dump.nodes.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeapDump {
+    /// References into the graph held directly by the virtual machine.
+    pub roots: Vec<HeapRoot>,
+    /// The nodes of the graph, where the index of each node is its id.
+    pub nodes: Vec<HeapNode>,
+}
+
+impl HeapDump {
+    /// The estimated number of bytes used by all nodes in the dump.
+    pub fn size(&self) -> usize {
+        self.nodes.iter().map(|node| node.size).sum()
+    }
+
+    /// Get the node with the given id.
+    pub fn node(&self, id: usize) -> Option<&HeapNode> {
+        self.nodes.get(id)
+    }
+
+    /// Compare this dump to one taken later, attributing the change in live
+    /// values to the sites they were allocated at.
+    ///
+    /// Values are grouped by their allocation site and type, so a group which
+    /// keeps growing between checkpoints points to values which are retained
+    /// when they shouldn't be, like through a closure which is never dropped.
+    pub fn diff(&self, later: &HeapDump) -> HeapDiff {
+        let mut groups = HashMap::<(Option<usize>, &str), HeapDiffEntry>::new();
+
+        for (nodes, sign) in &[(&self.nodes, -1), (&later.nodes, 1)] {
+            for node in nodes.iter() {
+                let entry = groups
+                    .entry((node.site, node.kind.as_str()))
+                    .or_insert_with(|| HeapDiffEntry {
+                        site: node.site,
+                        kind: node.kind.clone(),
+                        count: 0,
+                        size: 0,
+                    });
+
+                entry.count += sign;
+                entry.size += sign * node.size as isize;
+            }
+        }
+
+        let mut entries = groups
+            .into_iter()
+            .map(|(_, entry)| entry)
+            .filter(|entry| entry.count != 0 || entry.size != 0)
+            .collect::<Vec<_>>();
+
+        entries.sort_by(|a, b| {
+            b.size
+                .cmp(&a.size)
+                .then(b.count.cmp(&a.count))
+                .then(a.site.cmp(&b.site))
+                .then(a.kind.cmp(&b.kind))
+        });
+
+        HeapDiff { entries }
+    }
+}
+
+/// The difference between two [HeapDump]s, as returned by [HeapDump::diff].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HeapDiff {
+    /// Changes in live values for each allocation site and type, ordered from
+    /// the largest growth in size to the largest shrinkage.
+    pub entries: Vec<HeapDiffEntry>,
+}
+
+impl HeapDiff {
+    /// Iterate over the sites which grew between the two dumps.
+    pub fn growth(&self) -> impl Iterator<Item = &HeapDiffEntry> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| entry.count > 0 || entry.size > 0)
+    }
+
+    /// Pretty print the difference, using the debug information of the given
+    /// unit to resolve allocation sites to functions and source spans.
+    pub fn display<'a>(&'a self, unit: &'a Unit) -> impl fmt::Display + 'a {
+        HeapDiffDisplay { diff: self, unit }
+    }
+}
+
+/// The change in live values allocated at a single site.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapDiffEntry {
+    /// The instruction pointer of the allocation site, if known.
+    pub site: Option<usize>,
+    /// The type of the values.
+    pub kind: String,
+    /// The change in the number of live values.
+    pub count: isize,
+    /// The change in the estimated number of bytes used by the values.
+    pub size: isize,
+}
+
+impl HeapDiffEntry {
+    /// Look up the debug information for the allocation site, which holds the
+    /// source and span of the allocating instruction.
+    pub fn debug_inst<'a>(&self, unit: &'a Unit) -> Option<&'a DebugInst> {
+        unit.debug_info()?.instruction_at(self.site?)
+    }
+}
+
+struct HeapDiffDisplay<'a> {
+    diff: &'a HeapDiff,
+    unit: &'a Unit,
+}
+
+impl fmt::Display for HeapDiffDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for entry in &self.diff.entries {
+            write!(
+                f,
+                "{:+10} bytes {:+6} {}",
+                entry.size, entry.count, entry.kind
+            )?;
+
+            let site = match entry.site {
+                Some(site) => site,
+                None => {
+                    writeln!(f, " at unknown site")?;
+                    continue;
+                }
+            };
+
+            write!(f, " at {}", site)?;
+
+            let debug = match self.unit.debug_info() {
+                Some(debug) => debug,
+                None => {
+                    writeln!(f)?;
+                    continue;
+                }
+            };
+
+            if let Some((offset, hash)) = self.unit.function_containing(site) {
+                if let Some(signature) = debug.functions.get(&hash) {
+                    write!(f, " ({}+{})", signature.path, site - offset)?;
+                }
+            }
+
+            if let Some(inst) = debug.instruction_at(site) {
+                write!(f, " in source {} at {}", inst.source_id, inst.span)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A reference into the heap held directly by a virtual machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HeapRoot {
+    /// A value in the given slot of the stack.
+    Stack {
+        /// The slot of the value, counted from the bottom of the stack.
+        slot: usize,
+        /// The id of the referenced node.
+        node: usize,
+    },
+    /// A value which is tracked so that it can be finalized.
+    Finalizer {
+        /// The id of the referenced node.
+        node: usize,
+    },
+}
+
+impl HeapRoot {
+    /// The id of the node referenced by the root.
+    pub fn node(&self) -> usize {
+        match *self {
+            Self::Stack { node, .. } | Self::Finalizer { node } => node,
+        }
+    }
+}
+
+/// A shared value in a [HeapDump].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HeapNode {
+    /// The id of the node.
+    pub id: usize,
+    /// The type of the value, like `Vec` or `String`.
+    pub kind: String,
+    /// The instruction pointer of the instruction which was executing when the
+    /// value was allocated, if known.
+    ///
+    /// Allocation sites are only recorded in debug builds.
+    #[serde(default)]
+    pub site: Option<usize>,
+    /// The estimated number of bytes owned by the value, excluding the values
+    /// it refers to.
+    pub size: usize,
+    /// The number of strong references to the value.
+    pub refs: usize,
+    /// The ids of the nodes which the value refers to.
+    ///
+    /// Values which are exclusively borrowed while the dump is taken, like by
+    /// a native function, are listed without referents.
+    pub referents: Vec<usize>,
+}
+
+/// Take a dump of the values reachable from the given virtual machine.
+pub(crate) fn heap_dump(vm: &Vm) -> HeapDump {
+    let mut walker = HeapWalker::default();
+    let mut roots = Vec::new();
+
+    for (slot, value) in vm.stack().iter().enumerate() {
+        if let Some(node) = walker.visit(value) {
+            roots.push(HeapRoot::Stack { slot, node });
+        }
+    }
+
+    for value in vm.finalizers().values() {
+        if let Some(node) = walker.visit(value) {
+            roots.push(HeapRoot::Finalizer { node });
+        }
+    }
+
+    walker.walk();
+
+    HeapDump {
+        roots,
+        nodes: walker.nodes,
+    }
+}
+
+#[derive(Default)]
+struct HeapWalker {
+    /// Ids of the values which have been visited, keyed by their pointer.
+    seen: HashMap<*const (), usize>,
+    /// Values which have been visited but not yet walked, with their ids.
+    queue: Vec<(usize, Value)>,
+    nodes: Vec<HeapNode>,
+}
+
+impl HeapWalker {
+    /// Visit the given value, returning the id of its node if it's shared.
+    fn visit(&mut self, value: &Value) -> Option<usize> {
+        let (ptr, site) = match value {
+            Value::Unit
+            | Value::Bool(..)
+            | Value::Byte(..)
+            | Value::Char(..)
+            | Value::Integer(..)
+            | Value::Float(..)
+            | Value::Type(..)
+            | Value::StaticString(..) => return None,
+            Value::String(v) => identify(v),
+            Value::Bytes(v) => identify(v),
+            Value::Vec(v) => identify(v),
+            Value::Tuple(v) => identify(v),
+            Value::Object(v) => identify(v),
+            Value::Future(v) => identify(v),
+            Value::Stream(v) => identify(v),
+            Value::Generator(v) => identify(v),
+            Value::GeneratorState(v) => identify(v),
+            Value::Option(v) => identify(v),
+            Value::Result(v) => identify(v),
+            Value::TypedTuple(v) => identify(v),
+            Value::TupleVariant(v) => identify(v),
+            Value::TypedObject(v) => identify(v),
+            Value::VariantObject(v) => identify(v),
+            Value::Function(v) => identify(v),
+            Value::Handle(v) => identify(v),
+            Value::Any(v) => identify(v),
+        };
+
+        if let Some(id) = self.seen.get(&ptr) {
+            return Some(*id);
+        }
+
+        let id = self.nodes.len();
+        self.seen.insert(ptr, id);
+
+        let kind = match value.type_info() {
+            Ok(type_info) => type_info.to_string(),
+            Err(..) => String::from("?"),
+        };
+
+        self.nodes.push(HeapNode {
+            id,
+            kind,
+            site,
+            size: 0,
+            refs: 0,
+            referents: Vec::new(),
+        });
+
+        self.queue.push((id, value.clone()));
+        Some(id)
+    }
+
+    /// Walk all visited values until every reachable value has a node.
+    fn walk(&mut self) {
+        while let Some((id, value)) = self.queue.pop() {
+            let mut referents = Vec::new();
+            let (refs, size) = self.walk_value(&value, &mut referents);
+
+            let node = &mut self.nodes[id];
+            // NB: one reference is held by the queue.
+            node.refs = refs.saturating_sub(1);
+            node.size = size;
+            node.referents = referents;
+        }
+    }
+
+    /// Walk the referents of the given value, returning its strong count and
+    /// estimated size.
+    fn walk_value(&mut self, value: &Value, referents: &mut Vec<usize>) -> (usize, usize) {
+        match value {
+            Value::String(v) => (v.strong_count(), shared_size(v, |s| s.capacity())),
+            Value::Bytes(v) => (v.strong_count(), shared_size(v, |b| b.bytes.capacity())),
+            Value::Vec(v) => {
+                let size = shared_size(v, |vec| vec.capacity() * mem::size_of::<Value>());
+                self.referents(v, referents, |vec, f| vec.iter().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::Tuple(v) => {
+                let size = shared_size(v, |tuple| tuple.len() * mem::size_of::<Value>());
+                self.referents(v, referents, |tuple, f| tuple.iter().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::Object(v) => {
+                let size = shared_size(v, object_size);
+                self.referents(v, referents, |object, f| object.values().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::Option(v) => {
+                self.referents(v, referents, |option, f| option.iter().for_each(f));
+                (v.strong_count(), shared_size(v, |_| 0))
+            }
+            Value::Result(v) => {
+                self.referents(v, referents, |result, f| match result {
+                    Ok(value) | Err(value) => f(value),
+                });
+                (v.strong_count(), shared_size(v, |_| 0))
+            }
+            Value::GeneratorState(v) => {
+                self.referents(v, referents, |state, f| match state {
+                    GeneratorState::Yielded(value) | GeneratorState::Complete(value) => f(value),
+                });
+                (v.strong_count(), shared_size(v, |_| 0))
+            }
+            Value::TypedTuple(v) => {
+                let size = shared_size(v, |t| t.tuple.len() * mem::size_of::<Value>());
+                self.referents(v, referents, |t, f| t.tuple.iter().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::TupleVariant(v) => {
+                let size = shared_size(v, |t| t.tuple.len() * mem::size_of::<Value>());
+                self.referents(v, referents, |t, f| t.tuple.iter().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::TypedObject(v) => {
+                let size = shared_size(v, |o| object_size(&o.object));
+                self.referents(v, referents, |o, f| o.object.values().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::VariantObject(v) => {
+                let size = shared_size(v, |o| object_size(&o.object));
+                self.referents(v, referents, |o, f| o.object.values().for_each(f));
+                (v.strong_count(), size)
+            }
+            Value::Function(v) => {
+                self.referents(v, referents, |function, f| {
+                    if let Some(environment) = function.environment() {
+                        f(&Value::Tuple(environment.clone()));
+                    }
+                });
+                (v.strong_count(), shared_size(v, |_| 0))
+            }
+            Value::Generator(v) => {
+                self.referents(v, referents, |generator, f| {
+                    for vm in generator.execution().into_iter().flat_map(|e| e.vms()) {
+                        vm.stack().iter().for_each(&mut *f);
+                    }
+                });
+                (v.strong_count(), shared_size(v, |_| 0))
+            }
+            Value::Stream(v) => {
+                self.referents(v, referents, |stream, f| {
+                    for vm in stream.execution().into_iter().flat_map(|e| e.vms()) {
+                        vm.stack().iter().for_each(&mut *f);
+                    }
+                });
+                (v.strong_count(), shared_size(v, |_| 0))
+            }
+            Value::Future(v) => (v.strong_count(), shared_size(v, |_| 0)),
+            Value::Handle(v) => (v.strong_count(), shared_size(v, |_| 0)),
+            Value::Any(v) => (v.strong_count(), shared_size(v, |_| 0)),
+            _ => (0, 0),
+        }
+    }
+
+    /// Visit the values referred to by the given shared value, if it can be
+    /// borrowed.
+    fn referents<T, F>(&mut self, shared: &Shared<T>, referents: &mut Vec<usize>, each: F)
+    where
+        F: FnOnce(&T, &mut dyn FnMut(&Value)),
+    {
+        let value = match shared.borrow_ref() {
+            Ok(value) => value,
+            Err(..) => return,
+        };
+
+        each(&*value, &mut |value| {
+            if let Some(id) = self.visit(value) {
+                if !referents.contains(&id) {
+                    referents.push(id);
+                }
+            }
+        });
+    }
+}
+
+/// Get the pointer identifying a shared value and its allocation site.
+fn identify<T: ?Sized>(shared: &Shared<T>) -> (*const (), Option<usize>) {
+    (shared.as_ptr(), shared.site())
+}
+
+/// Estimate the size of a shared value, using the given function to estimate
+/// the size of the data it owns.
+fn shared_size<T>(shared: &Shared<T>, owned: impl FnOnce(&T) -> usize) -> usize {
+    let owned = match shared.borrow_ref() {
+        Ok(value) => owned(&*value),
+        Err(..) => 0,
+    };
+
+    mem::size_of::<T>() + owned
+}
+
+fn object_size(object: &Object<Value>) -> usize {
+    object
+        .keys()
+        .map(|key| mem::size_of::<(String, Value)>() + key.capacity())
+        .sum()
+}
@@ -0,0 +1,263 @@
+//! Zero-copy access to strings and bytes owned by the host.
+
+use crate::{
+    Any, Bytes, Hash, RawOwnedRef, Shared, Type, TypeInfo, UnsafeFromValue, Value, ValueType,
+    VmError,
+};
+use std::any;
+use std::fmt;
+use std::marker;
+use std::process;
+
+/// A string owned by the host, which is exposed to scripts without copying
+/// it.
+///
+/// Scripts can call a small set of string functions on it, or pass it to
+/// native functions which take a `&str`. Access is revoked once the guard
+/// which was constructed alongside it is dropped, after which the value can
+/// no longer be used.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{HostStr, Value};
+///
+/// let payload = String::from("a large payload");
+///
+/// let len = HostStr::scope(&payload, |value| match value {
+///     Value::Any(any) => any.downcast_borrow_ref::<HostStr>().unwrap().len(),
+///     _ => unreachable!(),
+/// });
+///
+/// assert_eq!(len, 15);
+/// ```
+pub struct HostStr {
+    data: *const str,
+}
+
+impl HostStr {
+    /// Expose the given string to scripts, returning the value to pass to the
+    /// script and a guard which revokes access when dropped.
+    ///
+    /// # Safety
+    ///
+    /// The guard must be dropped before the string is, which can only be
+    /// violated by leaking it, like through [std::mem::forget]. Prefer
+    /// [scope][HostStr::scope] where possible.
+    pub unsafe fn from_ref(s: &str) -> (Value, HostGuard<'_>) {
+        host_value(HostStr { data: s })
+    }
+
+    /// Expose the given string to scripts for the duration of the given
+    /// closure.
+    pub fn scope<F, T>(s: &str, f: F) -> T
+    where
+        F: FnOnce(Value) -> T,
+    {
+        // Safety: the guard is dropped before returning.
+        let (value, guard) = unsafe { Self::from_ref(s) };
+        let output = f(value);
+        drop(guard);
+        output
+    }
+
+    /// Access the string.
+    pub fn as_str(&self) -> &str {
+        // Safety: the string is alive for as long as access to the value
+        // hasn't been revoked, see `HostGuard`.
+        unsafe { &*self.data }
+    }
+
+    /// The length of the string in bytes.
+    pub fn len(&self) -> usize {
+        self.as_str().len()
+    }
+
+    /// Test if the string is empty.
+    pub fn is_empty(&self) -> bool {
+        self.as_str().is_empty()
+    }
+
+    /// Test if the string contains the given pattern.
+    pub fn contains(&self, pattern: &str) -> bool {
+        self.as_str().contains(pattern)
+    }
+
+    /// Test if the string starts with the given pattern.
+    pub fn starts_with(&self, pattern: &str) -> bool {
+        self.as_str().starts_with(pattern)
+    }
+
+    /// Test if the string ends with the given pattern.
+    pub fn ends_with(&self, pattern: &str) -> bool {
+        self.as_str().ends_with(pattern)
+    }
+
+    /// Copy the string into one owned by the virtual machine.
+    pub fn to_owned_string(&self) -> String {
+        self.as_str().to_owned()
+    }
+}
+
+impl fmt::Debug for HostStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for HostStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+/// Bytes owned by the host, which are exposed to scripts without copying
+/// them.
+///
+/// Like [HostStr], but for native functions which take a `&[u8]`.
+pub struct HostBytes {
+    data: *const [u8],
+}
+
+impl HostBytes {
+    /// Expose the given bytes to scripts, returning the value to pass to the
+    /// script and a guard which revokes access when dropped.
+    ///
+    /// # Safety
+    ///
+    /// The guard must be dropped before the bytes are, see
+    /// [HostStr::from_ref].
+    pub unsafe fn from_ref(bytes: &[u8]) -> (Value, HostGuard<'_>) {
+        host_value(HostBytes { data: bytes })
+    }
+
+    /// Expose the given bytes to scripts for the duration of the given
+    /// closure.
+    pub fn scope<F, T>(bytes: &[u8], f: F) -> T
+    where
+        F: FnOnce(Value) -> T,
+    {
+        // Safety: the guard is dropped before returning.
+        let (value, guard) = unsafe { Self::from_ref(bytes) };
+        let output = f(value);
+        drop(guard);
+        output
+    }
+
+    /// Access the bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        // Safety: the bytes are alive for as long as access to the value
+        // hasn't been revoked, see `HostGuard`.
+        unsafe { &*self.data }
+    }
+
+    /// The number of bytes.
+    pub fn len(&self) -> usize {
+        self.as_slice().len()
+    }
+
+    /// Test if there are no bytes.
+    pub fn is_empty(&self) -> bool {
+        self.as_slice().is_empty()
+    }
+
+    /// Get the byte at the given index.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        self.as_slice().get(index).copied()
+    }
+
+    /// Copy the bytes into bytes owned by the virtual machine.
+    pub fn to_bytes(&self) -> Bytes {
+        Bytes::from_vec(self.as_slice().to_vec())
+    }
+}
+
+impl fmt::Debug for HostBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_slice(), f)
+    }
+}
+
+/// A guard which revokes access to data exposed through [HostStr] or
+/// [HostBytes] when dropped.
+///
+/// Values which are still held by the virtual machine can't be used after
+/// this, and trying to do so raises an error.
+///
+/// # Panics
+///
+/// The process is aborted if the value is being accessed when the guard is
+/// dropped, since the data it refers to can't be kept alive. Values which
+/// have been frozen are still revoked.
+pub struct HostGuard<'a> {
+    shared: Option<Shared<Any>>,
+    _marker: marker::PhantomData<&'a ()>,
+}
+
+impl Drop for HostGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            // NB: revoking ignores if the value has been frozen, since the
+            // data it refers to is going away regardless.
+            if shared.revoke().is_err() {
+                process::abort();
+            }
+        }
+    }
+}
+
+impl fmt::Debug for HostGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HostGuard")
+    }
+}
+
+/// Construct a value around the given host reference, together with the
+/// guard for it.
+fn host_value<'a, T>(data: T) -> (Value, HostGuard<'a>)
+where
+    T: any::Any,
+{
+    let shared = Shared::new(Any::new(data));
+
+    let guard = HostGuard {
+        shared: Some(shared.clone()),
+        _marker: marker::PhantomData,
+    };
+
+    (Value::Any(shared), guard)
+}
+
+macro_rules! impl_host_ref {
+    ($ty:ty) => {
+        impl ValueType for $ty {
+            fn value_type() -> Type {
+                Type::Hash(Hash::from_type_id(any::TypeId::of::<$ty>()))
+            }
+
+            fn type_info() -> TypeInfo {
+                TypeInfo::Any(any::type_name::<$ty>())
+            }
+        }
+
+        // NB: only references are supported, since access is revoked through
+        // the shared value.
+        impl<'a> UnsafeFromValue for &'a $ty {
+            type Output = *const $ty;
+            type Guard = RawOwnedRef;
+
+            unsafe fn unsafe_from_value(
+                value: Value,
+            ) -> Result<(Self::Output, Self::Guard), VmError> {
+                value.unsafe_into_any_ref()
+            }
+
+            unsafe fn to_arg(output: Self::Output) -> Self {
+                &*output
+            }
+        }
+    };
+}
+
+impl_host_ref!(HostStr);
+impl_host_ref!(HostBytes);
@@ -1,10 +1,11 @@
 use crate::Hash;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
 /// Pre-canned panic reasons.
 ///
 /// To formulate a custom reason, use [crate::Panic::custom].
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum PanicReason {
     /// Not implemented.
     NotImplemented,
@@ -40,7 +41,7 @@ impl fmt::Display for PanicReason {
 }
 
 /// An encoded type check.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum TypeCheck {
     /// Matches a unit type.
     Unit,
@@ -78,8 +79,36 @@ impl fmt::Display for TypeCheck {
     }
 }
 
+/// An arithmetic operation which can fail, used by
+/// [Inst::CheckedOp] and [Inst::CheckedAssign].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ArithmeticOp {
+    /// Addition, `+`.
+    Add,
+    /// Subtraction, `-`.
+    Sub,
+    /// Multiplication, `*`.
+    Mul,
+    /// Division, `/`.
+    Div,
+    /// Remainder, `%`.
+    Rem,
+}
+
+impl fmt::Display for ArithmeticOp {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Add => write!(fmt, "add"),
+            Self::Sub => write!(fmt, "sub"),
+            Self::Mul => write!(fmt, "mul"),
+            Self::Div => write!(fmt, "div"),
+            Self::Rem => write!(fmt, "rem"),
+        }
+    }
+}
+
 /// An operation in the stack-based virtual machine.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum Inst {
     /// Not operator. Takes a boolean from the top of the stack  and inverts its
     /// logical value.
@@ -102,6 +131,16 @@ pub enum Inst {
         /// The frame offset to assign to.
         offset: usize,
     },
+    /// Add a value to the given frame offset, as if by `<offset> = <offset> +
+    /// <b>`.
+    ///
+    /// Unlike [AddAssign](Self::AddAssign), this preserves the value semantics
+    /// of the addition. A string held by the variable is only appended to in
+    /// place if it's not referenced from anywhere else.
+    ConcatAssign {
+        /// The frame offset to assign to.
+        offset: usize,
+    },
     /// Subtract two things.
     ///
     /// This is the result of an `<a> - <b>` expression.
@@ -155,6 +194,43 @@ pub enum Inst {
         /// The frame offset to assign to.
         offset: usize,
     },
+    /// Perform an arithmetic operation, followed by a boolean indicating if it
+    /// succeeded.
+    ///
+    /// If the operation overflows or divides by zero, the error is pushed
+    /// wrapped in an `Err` instead of the result. This is the result of an
+    /// arithmetic expression compiled with checked arithmetic.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// <value>
+    /// => <value or err>, <bool>
+    /// ```
+    CheckedOp {
+        /// The operation to perform.
+        op: ArithmeticOp,
+    },
+    /// Perform an arithmetic operation on the given frame offset, followed by
+    /// a boolean indicating if it succeeded.
+    ///
+    /// If the operation overflows or divides by zero, the value at the offset
+    /// is left as-is and the error is pushed wrapped in an `Err`. This is the
+    /// result of a compound assignment compiled with checked arithmetic.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <err>?, <bool>
+    /// ```
+    CheckedAssign {
+        /// The operation to perform.
+        op: ArithmeticOp,
+        /// The frame offset to assign to.
+        offset: usize,
+    },
     /// Encode a function pointer on the stack.
     ///
     /// # Operation
@@ -191,6 +267,18 @@ pub enum Inst {
         /// The number of arguments expected on the stack for this call.
         args: usize,
     },
+    /// Call the intrinsic in the given slot of the context.
+    ///
+    /// If the context doesn't have an intrinsic with the given hash and number
+    /// of arguments in the slot, this behaves like [Call][Inst::Call].
+    CallIntrinsic {
+        /// The slot of the intrinsic in the context.
+        slot: usize,
+        /// The hash of the intrinsic to call.
+        hash: Hash,
+        /// The number of arguments expected on the stack for this call.
+        args: usize,
+    },
     /// Perform a instance function call.
     ///
     /// The instance being called on should be on top of the stack, followed by
@@ -201,6 +289,21 @@ pub enum Inst {
         /// The number of arguments expected on the stack for this call.
         args: usize,
     },
+    /// Perform an instance function call through an extension which is in
+    /// scope.
+    ///
+    /// The extension function identified by `hash` is called if it's
+    /// implemented for the instance, otherwise this behaves like
+    /// [CallInstance][Inst::CallInstance] with the `fallback` hash.
+    CallExtension {
+        /// The hash of the extension function to call.
+        hash: Hash,
+        /// The hash of the name of the function to call if the extension
+        /// doesn't apply to the instance.
+        fallback: Hash,
+        /// The number of arguments expected on the stack for this call.
+        args: usize,
+    },
     /// Lookup the specified instance function and put it on the stack.
     /// This might help in cases where a single instance function is called many
     /// times (like in a loop) since it avoids calculating its full hash on
@@ -242,6 +345,18 @@ pub enum Inst {
     /// => <value>
     /// ```
     IndexGet,
+    /// Get the given index out of the target like [IndexGet][Inst::IndexGet],
+    /// except that negative integer indexes and range bounds count from the
+    /// end of vectors, tuples and strings.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <target>
+    /// <index>
+    /// => <value>
+    /// ```
+    NegativeIndexGet,
     /// Get the given index out of a tuple on the top of the stack.
     /// Errors if the item doesn't exist or the item is not a tuple.
     ///
@@ -336,6 +451,18 @@ pub enum Inst {
         /// The number to push.
         number: f64,
     },
+    /// Push a literal decimal, which is parsed from the static string at the
+    /// given slot.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <decimal>
+    /// ```
+    Decimal {
+        /// The static string slot to parse the decimal from.
+        slot: usize,
+    },
     /// Await the future that is on the stack and push the value that it
     /// produces.
     ///
@@ -457,6 +584,55 @@ pub enum Inst {
     /// The stack frame will be cleared, and a unit value will be pushed to the
     /// top of the stack.
     ReturnUnit,
+    /// Pop the current stack frame and restore the instruction pointer from it,
+    /// returning the `count` values on top of the stack as a tuple.
+    ///
+    /// Any other values in the stack frame are cleared. If the instruction
+    /// after the call being returned to is an [Inst::Unpack] of the same
+    /// number of values, the values are pushed directly onto the stack of the
+    /// caller and the unpacking is skipped, so no tuple is allocated.
+    ReturnTuple {
+        /// The number of values to return.
+        count: usize,
+    },
+    /// Return the value of the static in the given slot from the current
+    /// function if it has been initialized. Otherwise the static is marked as
+    /// being initialized, and execution continues with its initializer.
+    ///
+    /// Accessing a static while it's being initialized raises an error.
+    ReturnStatic {
+        /// The slot of the static.
+        slot: usize,
+    },
+    /// Pop the value on top of the stack and store it in the static in the
+    /// given slot.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => *noop*
+    /// ```
+    StoreStatic {
+        /// The slot of the static.
+        slot: usize,
+    },
+    /// Look up the cached result of a call to the memoized function in the
+    /// given slot, keyed by the arguments of the current call frame. A cached
+    /// result is returned from the current function, otherwise execution
+    /// continues and the value the function returns is cached.
+    ///
+    /// This is the first instruction of a memoized function.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <value>?
+    /// ```
+    Memoize {
+        /// The slot of the memoized function.
+        slot: usize,
+    },
     /// Compare two values on the stack for lt and push the result as a
     /// boolean on the stack.
     Lt,
@@ -530,6 +706,38 @@ pub enum Inst {
         /// Offset to jump to.
         offset: isize,
     },
+    /// Jump to `offset` relative to the current instruction pointer if the
+    /// condition is `true`, leaving the condition on the stack. Otherwise the
+    /// condition is popped.
+    ///
+    /// This is used to short-circuit `||`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <boolean>
+    /// => <boolean> | *nothing*
+    /// ```
+    JumpIfOrPop {
+        /// Offset to jump to.
+        offset: isize,
+    },
+    /// Jump to `offset` relative to the current instruction pointer if the
+    /// condition is `false`, leaving the condition on the stack. Otherwise the
+    /// condition is popped.
+    ///
+    /// This is used to short-circuit `&&`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <boolean>
+    /// => <boolean> | *nothing*
+    /// ```
+    JumpIfNotOrPop {
+        /// Offset to jump to.
+        offset: isize,
+    },
     /// Compares the `branch` register with the top of the stack, and if they
     /// match pops the top of the stack and performs the jump to offset.
     ///
@@ -590,6 +798,32 @@ pub enum Inst {
         /// The size of the tuple.
         count: usize,
     },
+    /// Pop a tuple with `count` elements from the stack, and push its elements
+    /// in order. Panics with an unmatched pattern if the value isn't a tuple
+    /// of that size.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <tuple>
+    /// => <value..>
+    /// ```
+    Unpack {
+        /// The number of elements in the tuple.
+        count: usize,
+    },
+    /// Construct a push a range value onto the stack. The start and end of the
+    /// range are popped from the stack, where a unit value indicates that the
+    /// bound is absent.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <end>
+    /// <start>
+    /// => <range>
+    /// ```
+    Range,
     /// Take the tuple that is on top of the stack and push its content onto the
     /// stack.
     ///
@@ -637,6 +871,28 @@ pub enum Inst {
         /// The static slot of the object keys.
         slot: usize,
     },
+    /// Construct a push an object of the given type onto the stack, copying
+    /// fields from an existing instance of the same type on top of the stack.
+    ///
+    /// The first `count` keys in the slot `slot` are popped from the stack
+    /// under the instance, and the remaining keys are copied from it. This is
+    /// the result of an expression like `Foo { a: 1, ..other }`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value..>
+    /// <object>
+    /// => <object>
+    /// ```
+    TypedObjectUpdate {
+        /// The type of the object to construct.
+        hash: Hash,
+        /// The static slot of the object keys.
+        slot: usize,
+        /// The number of keys which are popped from the stack.
+        count: usize,
+    },
     /// Construct a push an object of the given type onto the stack. The number
     /// of elements in the object are determined the slot of the object keys
     /// `slot` and are popped from the stack.
@@ -740,6 +996,33 @@ pub enum Inst {
     /// => <boolean>
     /// ```
     IsNot,
+    /// Convert the second item on the stack into the type on top of the
+    /// stack, like `value as int`.
+    ///
+    /// Numbers can be converted between `int` and `float`, and variants of
+    /// enums with discriminants can be converted into `int`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <type>
+    /// <value>
+    /// => <value>
+    /// ```
+    As,
+    /// Pop an integer from the stack and look up the variant of the given
+    /// enum which has it as its discriminant, producing an `Option`.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <integer>
+    /// => <option>
+    /// ```
+    FromDiscriminant {
+        /// The hash of the enum.
+        enum_hash: Hash,
+    },
     /// Pop two values from the stack and test if they are both boolean true.
     ///
     /// # Operation
@@ -912,6 +1195,33 @@ pub enum Inst {
     /// => <value>
     /// ```
     Unwrap,
+    /// Enter the value on top of the stack, which is used in a `with`
+    /// expression.
+    ///
+    /// This calls the [ENTER][crate::ENTER] protocol on the value if it's
+    /// implemented, and otherwise leaves the value as-is.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <value>
+    /// ```
+    Enter,
+    /// Exit the value on top of the stack, which was used in a `with`
+    /// expression.
+    ///
+    /// This calls the [EXIT][crate::EXIT] protocol on the value, or its `close`
+    /// function if it doesn't implement the protocol, and errors if it
+    /// implements neither.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// <value>
+    /// => <return value>
+    /// ```
+    Exit,
     /// Test if the top of the stack is a specific byte.
     ///
     /// # Operation
@@ -978,6 +1288,24 @@ pub enum Inst {
         /// `false`.
         exact: bool,
     },
+    /// Destructure the external value at the given offset through the
+    /// [MATCH][crate::MATCH] protocol, if it is of the type with the given
+    /// hash.
+    ///
+    /// If the protocol returns `Some` value, the value at the offset is
+    /// replaced with it and `true` is pushed. Otherwise `false` is pushed.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <boolean>
+    /// ```
+    Destructure {
+        /// The type hash of the value to destructure.
+        hash: Hash,
+        /// The offset of the value to destructure.
+        offset: usize,
+    },
     /// Test that the top of the stack is an object matching the given slot of
     /// object keys.
     ///
@@ -1029,6 +1357,18 @@ pub enum Inst {
     /// => <unit>
     /// ```
     YieldUnit,
+    /// Test if the generator is being disposed of after it was resumed from
+    /// the yield preceding this instruction, in which case it should run the
+    /// cleanup which is pending at that yield and return.
+    ///
+    /// This is only emitted after yields which have pending cleanup.
+    ///
+    /// # Operation
+    ///
+    /// ```text
+    /// => <boolean>
+    /// ```
+    Disposed,
     /// Cause the VM to panic and error out without a reason.
     ///
     /// This should only be used during testing or extreme scenarios that are
@@ -1054,6 +1394,9 @@ impl fmt::Display for Inst {
             Self::AddAssign { offset } => {
                 write!(fmt, "add-assign {}", offset)?;
             }
+            Self::ConcatAssign { offset } => {
+                write!(fmt, "concat-assign {}", offset)?;
+            }
             Self::Sub => {
                 write!(fmt, "sub")?;
             }
@@ -1078,12 +1421,28 @@ impl fmt::Display for Inst {
             Self::RemAssign { offset } => {
                 write!(fmt, "rem-assign {}", offset)?;
             }
+            Self::CheckedOp { op } => {
+                write!(fmt, "checked-op {}", op)?;
+            }
+            Self::CheckedAssign { op, offset } => {
+                write!(fmt, "checked-assign {}, {}", op, offset)?;
+            }
             Self::Call { hash, args } => {
                 write!(fmt, "call {}, {}", hash, args)?;
             }
+            Self::CallIntrinsic { slot, hash, args } => {
+                write!(fmt, "call-intrinsic {}, {}, {}", slot, hash, args)?;
+            }
             Self::CallInstance { hash, args } => {
                 write!(fmt, "call-instance {}, {}", hash, args)?;
             }
+            Self::CallExtension {
+                hash,
+                fallback,
+                args,
+            } => {
+                write!(fmt, "call-extension {}, {}, {}", hash, fallback, args)?;
+            }
             Self::Fn { hash } => {
                 write!(fmt, "fn {}", hash)?;
             }
@@ -1099,6 +1458,9 @@ impl fmt::Display for Inst {
             Self::IndexGet => {
                 write!(fmt, "index-get")?;
             }
+            Self::NegativeIndexGet => {
+                write!(fmt, "negative-index-get")?;
+            }
             Self::TupleIndexGet { index } => {
                 write!(fmt, "tuple-index-get {}", index)?;
             }
@@ -1123,6 +1485,9 @@ impl fmt::Display for Inst {
             Self::Float { number } => {
                 write!(fmt, "float {}", number)?;
             }
+            Self::Decimal { slot } => {
+                write!(fmt, "decimal {}", slot)?;
+            }
             Self::Await => {
                 write!(fmt, "await")?;
             }
@@ -1156,6 +1521,18 @@ impl fmt::Display for Inst {
             Self::ReturnUnit => {
                 write!(fmt, "return-unit")?;
             }
+            Self::ReturnTuple { count } => {
+                write!(fmt, "return-tuple {}", count)?;
+            }
+            Self::ReturnStatic { slot } => {
+                write!(fmt, "return-static {}", slot)?;
+            }
+            Self::StoreStatic { slot } => {
+                write!(fmt, "store-static {}", slot)?;
+            }
+            Self::Memoize { slot } => {
+                write!(fmt, "memoize {}", slot)?;
+            }
             Self::Lt => {
                 write!(fmt, "lt")?;
             }
@@ -1183,6 +1560,12 @@ impl fmt::Display for Inst {
             Self::JumpIfNot { offset } => {
                 write!(fmt, "jump-if-not {}", offset)?;
             }
+            Self::JumpIfOrPop { offset } => {
+                write!(fmt, "jump-if-or-pop {}", offset)?;
+            }
+            Self::JumpIfNotOrPop { offset } => {
+                write!(fmt, "jump-if-not-or-pop {}", offset)?;
+            }
             Self::JumpIfBranch { branch, offset } => {
                 write!(fmt, "jump-if-branch {}, {}", branch, offset)?;
             }
@@ -1198,12 +1581,21 @@ impl fmt::Display for Inst {
             Self::Tuple { count } => {
                 write!(fmt, "tuple {}", count)?;
             }
+            Self::Unpack { count } => {
+                write!(fmt, "unpack {}", count)?;
+            }
+            Self::Range => {
+                write!(fmt, "range")?;
+            }
             Self::PushTuple => {
                 write!(fmt, "push-tuple")?;
             }
             Self::TypedObject { hash, slot } => {
                 write!(fmt, "typed-object {}, {}", hash, slot)?;
             }
+            Self::TypedObjectUpdate { hash, slot, count } => {
+                write!(fmt, "typed-object-update {}, {}, {}", hash, slot, count)?;
+            }
             Self::VariantObject {
                 enum_hash,
                 hash,
@@ -1235,6 +1627,12 @@ impl fmt::Display for Inst {
             Self::IsNot => {
                 write!(fmt, "is-not")?;
             }
+            Self::As => {
+                write!(fmt, "as")?;
+            }
+            Self::FromDiscriminant { enum_hash } => {
+                write!(fmt, "from-discriminant {}", enum_hash)?;
+            }
             Self::And => {
                 write!(fmt, "and")?;
             }
@@ -1280,6 +1678,12 @@ impl fmt::Display for Inst {
             Self::Unwrap => {
                 write!(fmt, "unwrap")?;
             }
+            Self::Enter => {
+                write!(fmt, "enter")?;
+            }
+            Self::Exit => {
+                write!(fmt, "exit")?;
+            }
             Self::EqByte { byte } => {
                 write!(fmt, "eq-byte {:?}", byte)?;
             }
@@ -1299,6 +1703,9 @@ impl fmt::Display for Inst {
             } => {
                 write!(fmt, "match-sequence {}, {}, {}", type_check, len, exact)?;
             }
+            Self::Destructure { hash, offset } => {
+                write!(fmt, "destructure {}, {}", hash, offset)?;
+            }
             Self::MatchObject {
                 type_check,
                 slot,
@@ -1315,6 +1722,9 @@ impl fmt::Display for Inst {
             Self::YieldUnit => {
                 write!(fmt, "yield-unit")?;
             }
+            Self::Disposed => {
+                write!(fmt, "disposed")?;
+            }
             Self::Panic { reason } => {
                 write!(fmt, "panic {}", reason.ident())?;
             }
@@ -0,0 +1,185 @@
+use crate::{Call, Component, Hash, Item, Unit, UnitFn};
+use std::fmt;
+use thiserror::Error;
+
+/// A set of functions a unit is expected to implement, like the entrypoints
+/// of a plugin.
+///
+/// Checking a unit against an interface with [Unit::validate_interface] when
+/// it's loaded means that a script which doesn't implement it fails up front,
+/// instead of at the first call to a missing function.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Interface, InterfaceMismatch, Unit};
+///
+/// let mut interface = Interface::new();
+/// interface
+///     .function(&["on_load"], 0)
+///     .async_function(&["handle"], 1);
+///
+/// let unit = Unit::default();
+/// let error = unit.validate_interface(&interface).unwrap_err();
+///
+/// assert_eq!(error.mismatches().len(), 2);
+/// assert!(matches!(error.mismatches()[0], InterfaceMismatch::MissingFunction { .. }));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interface {
+    functions: Vec<InterfaceFn>,
+}
+
+/// A function declared in an interface.
+#[derive(Debug, Clone)]
+struct InterfaceFn {
+    item: Item,
+    args: usize,
+    call: Call,
+}
+
+impl Interface {
+    /// Construct a new empty interface.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a regular function with the given name and number of
+    /// arguments.
+    pub fn function<N>(&mut self, name: N, args: usize) -> &mut Self
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        self.function_with_call(name, args, Call::Immediate)
+    }
+
+    /// Declare an async function with the given name and number of
+    /// arguments.
+    pub fn async_function<N>(&mut self, name: N, args: usize) -> &mut Self
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        self.function_with_call(name, args, Call::Async)
+    }
+
+    /// Declare a function with the given name and number of arguments, which
+    /// is called in the given way, like a generator or a stream.
+    pub fn function_with_call<N>(&mut self, name: N, args: usize, call: Call) -> &mut Self
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        self.functions.push(InterfaceFn {
+            item: Item::of(name),
+            args,
+            call,
+        });
+
+        self
+    }
+
+    /// Check that the given unit implements every function in the
+    /// interface.
+    pub(crate) fn validate(&self, unit: &Unit) -> Result<(), InterfaceError> {
+        let mut mismatches = Vec::new();
+
+        for f in &self.functions {
+            let (call, args) = match unit.lookup(Hash::type_hash(&f.item)) {
+                Some(UnitFn::Offset { call, args, .. }) => (call, args),
+                Some(UnitFn::Tuple { args, .. }) | Some(UnitFn::TupleVariant { args, .. }) => {
+                    (Call::Immediate, args)
+                }
+                None => {
+                    mismatches.push(InterfaceMismatch::MissingFunction {
+                        item: f.item.clone(),
+                    });
+                    continue;
+                }
+            };
+
+            if args != f.args {
+                mismatches.push(InterfaceMismatch::ArgumentCount {
+                    item: f.item.clone(),
+                    expected: f.args,
+                    actual: args,
+                });
+            }
+
+            if call != f.call {
+                mismatches.push(InterfaceMismatch::Call {
+                    item: f.item.clone(),
+                    expected: f.call,
+                    actual: call,
+                });
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            Err(InterfaceError { mismatches })
+        }
+    }
+}
+
+/// Error raised when a unit doesn't implement an [Interface].
+#[derive(Debug, Clone)]
+pub struct InterfaceError {
+    mismatches: Vec<InterfaceMismatch>,
+}
+
+impl InterfaceError {
+    /// Get every way in which the unit doesn't implement the interface, in
+    /// the order the functions were declared in the interface.
+    pub fn mismatches(&self) -> &[InterfaceMismatch] {
+        &self.mismatches
+    }
+}
+
+impl fmt::Display for InterfaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unit does not implement the expected interface")?;
+
+        for mismatch in &self.mismatches {
+            write!(f, "\n- {}", mismatch)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for InterfaceError {}
+
+/// A way in which a unit doesn't implement an [Interface].
+#[derive(Debug, Clone, Error)]
+pub enum InterfaceMismatch {
+    /// The unit doesn't declare the function.
+    #[error("missing function `{item}`")]
+    MissingFunction {
+        /// The item of the missing function.
+        item: Item,
+    },
+    /// The function takes the wrong number of arguments.
+    #[error("function `{item}` takes {actual} arguments, but {expected} are expected")]
+    ArgumentCount {
+        /// The item of the function.
+        item: Item,
+        /// The number of arguments expected by the interface.
+        expected: usize,
+        /// The number of arguments the function takes.
+        actual: usize,
+    },
+    /// The function is called in the wrong way, like when it's expected to be
+    /// async but isn't.
+    #[error("function `{item}` is {actual}, but {expected} is expected")]
+    Call {
+        /// The item of the function.
+        item: Item,
+        /// How the interface expects the function to be called.
+        expected: Call,
+        /// How the function is called.
+        actual: Call,
+    },
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Serialize};
 use std::convert;
 use std::fmt;
 
@@ -5,7 +6,7 @@ use std::fmt;
 ///
 /// This is made up of a collection of strings, like `["foo", "bar"]`.
 /// This is indicated in rune as `foo::bar`.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
 pub struct Item {
     path: Vec<Component>,
 }
@@ -97,6 +98,11 @@ impl Item {
     pub fn last(&self) -> Option<&Component> {
         self.path.last()
     }
+
+    /// Test if the item is the given item or is nested inside of it.
+    pub fn starts_with(&self, prefix: &Item) -> bool {
+        self.path.starts_with(&prefix.path)
+    }
 }
 
 impl fmt::Display for Item {
@@ -134,7 +140,7 @@ impl<'a> IntoIterator for &'a Item {
 }
 
 /// The component of an item.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Component {
     /// A regular string component.
     String(String),
@@ -0,0 +1,905 @@
+//! An experimental JIT compiler for functions in a unit, built on Cranelift.
+//!
+//! Functions are compiled once they have been called a given number of times.
+//! Only functions which operate on integers and booleans, and which only call
+//! other such functions, are compiled. Anything else is left to the
+//! interpreter.
+//!
+//! Compiled functions are pure, so when compiled code encounters something it
+//! can't handle, like an arithmetic overflow or too deep recursion, it bails
+//! out and the call is performed again by the interpreter. This also happens
+//! if a compiled function is called with arguments which aren't integers.
+
+use crate::collections::HashMap;
+use crate::{Call, Hash, Inst, Stack, Unit, UnitFn, Value, VmError};
+use cranelift_codegen::ir::condcodes::IntCC;
+use cranelift_codegen::ir::types::I64;
+use cranelift_codegen::ir::{self, AbiParam, InstBuilder, MemFlags, Signature, UserFuncName};
+use cranelift_codegen::settings::{self, Configurable as _};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{FuncId, Module as _};
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+/// The maximum number of arguments of a compiled function.
+const MAX_ARGS: usize = 16;
+
+/// The maximum depth of calls between compiled functions, after which they
+/// bail out to the interpreter.
+const MAX_DEPTH: i64 = 1024;
+
+/// The entry point of a compiled function, which takes its arguments from
+/// memory.
+type Entry = unsafe extern "C" fn(*mut State, *const i64) -> i64;
+
+/// Error raised when constructing a [Jit].
+#[derive(Debug, Error)]
+#[error("failed to set up jit: {message}")]
+pub struct JitError {
+    message: String,
+}
+
+impl JitError {
+    fn new<E>(error: E) -> Self
+    where
+        E: fmt::Display,
+    {
+        Self {
+            message: error.to_string(),
+        }
+    }
+}
+
+/// The state shared by compiled functions while they run.
+#[repr(C)]
+struct State {
+    /// Set if compiled code bailed out.
+    bailed: i64,
+    /// The remaining call depth.
+    depth: i64,
+}
+
+/// A JIT compiler, which can be installed in a virtual machine with
+/// [Vm::set_jit][crate::Vm::set_jit].
+///
+/// A JIT is bound to the unit of the virtual machine it's first used by, and
+/// can be shared by virtual machines which run the same unit by cloning it.
+///
+/// Compiled functions don't count towards the instruction limit of a virtual
+/// machine, and they aren't observed by call hooks. Calls aren't compiled
+/// while call hooks are installed or functions are overridden.
+#[derive(Clone)]
+pub struct Jit {
+    inner: Arc<Mutex<Inner>>,
+}
+
+impl Jit {
+    /// Construct a JIT compiler for the host, which compiles functions once
+    /// they've been called `threshold` times.
+    pub fn new(threshold: usize) -> Result<Self, JitError> {
+        let mut flags = settings::builder();
+        flags.set("opt_level", "speed").map_err(JitError::new)?;
+        let isa = cranelift_native::builder().map_err(JitError::new)?;
+        let isa = isa
+            .finish(settings::Flags::new(flags))
+            .map_err(JitError::new)?;
+
+        let module = JITModule::new(JITBuilder::with_isa(
+            isa,
+            cranelift_module::default_libcall_names(),
+        ));
+
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Inner {
+                module: ManuallyDrop::new(module),
+                builder: FunctionBuilderContext::new(),
+                threshold,
+                unit: None,
+                functions: HashMap::new(),
+                ids: HashMap::new(),
+                bailouts: 0,
+            })),
+        })
+    }
+
+    /// Test if the function with the given hash has been compiled.
+    pub fn is_compiled(&self, hash: Hash) -> bool {
+        matches!(
+            self.lock().functions.get(&hash),
+            Some(Function::Compiled { .. })
+        )
+    }
+
+    /// The number of times compiled code has bailed out to the interpreter.
+    pub fn bailouts(&self) -> usize {
+        self.lock().bailouts
+    }
+
+    /// Call the function with the given hash, if it's compiled, returning
+    /// `false` if it has to be called by the interpreter.
+    pub(crate) fn call(
+        &self,
+        unit: &Arc<Unit>,
+        hash: Hash,
+        stack: &mut Stack,
+        args: usize,
+    ) -> Result<bool, VmError> {
+        let mut inner = self.lock();
+        inner.bind(unit);
+
+        let entry = match inner.function(unit, hash) {
+            Some(entry) => entry,
+            None => return Ok(false),
+        };
+
+        let mut values = [0i64; MAX_ARGS];
+        let start = stack.len() - args;
+
+        for (n, value) in stack.iter().skip(start).enumerate() {
+            match value {
+                Value::Integer(value) => values[n] = *value,
+                _ => return Ok(false),
+            }
+        }
+
+        let mut state = State {
+            bailed: 0,
+            depth: MAX_DEPTH,
+        };
+
+        // Safety: the entry point has been compiled for this number of
+        // arguments, and the module it lives in is alive for as long as the
+        // lock is held.
+        let value = unsafe { entry(&mut state, values.as_ptr()) };
+
+        if state.bailed != 0 {
+            inner.bailouts += 1;
+            return Ok(false);
+        }
+
+        stack.popn(args)?;
+        stack.push(value);
+        Ok(true)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        match self.inner.lock() {
+            Ok(inner) => inner,
+            Err(error) => error.into_inner(),
+        }
+    }
+}
+
+impl fmt::Debug for Jit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let inner = self.lock();
+
+        f.debug_struct("Jit")
+            .field("threshold", &inner.threshold)
+            .field("functions", &inner.functions.len())
+            .field("bailouts", &inner.bailouts)
+            .finish()
+    }
+}
+
+/// The state of a function in the JIT.
+enum Function {
+    /// The function is being called by the interpreter, and has been called
+    /// the given number of times.
+    Counting(usize),
+    /// The function has been compiled.
+    Compiled { entry: Entry },
+    /// The function can't be compiled.
+    Rejected,
+}
+
+struct Inner {
+    module: ManuallyDrop<JITModule>,
+    builder: FunctionBuilderContext,
+    threshold: usize,
+    /// The unit functions are compiled from.
+    unit: Option<Arc<Unit>>,
+    functions: HashMap<Hash, Function>,
+    /// Functions which have been declared in the module.
+    ids: HashMap<Hash, FuncId>,
+    bailouts: usize,
+}
+
+impl Inner {
+    /// Bind the JIT to the given unit, forgetting about functions compiled
+    /// from any other unit.
+    fn bind(&mut self, unit: &Arc<Unit>) {
+        if let Some(current) = &self.unit {
+            if Arc::ptr_eq(current, unit) {
+                return;
+            }
+        }
+
+        self.unit = Some(unit.clone());
+        self.functions.clear();
+        self.ids.clear();
+    }
+
+    /// Count a call to the given function, and get its entry point if it's
+    /// compiled.
+    fn function(&mut self, unit: &Unit, hash: Hash) -> Option<Entry> {
+        let count = match self.functions.get_mut(&hash) {
+            Some(Function::Compiled { entry }) => return Some(*entry),
+            Some(Function::Rejected) => return None,
+            Some(Function::Counting(count)) => {
+                *count += 1;
+                *count
+            }
+            None => {
+                self.functions.insert(hash, Function::Counting(1));
+                1
+            }
+        };
+
+        if count < self.threshold {
+            return None;
+        }
+
+        if let Err(reason) = self.compile(unit, hash) {
+            log::trace!("jit: not compiling function `{}`: {}", hash, reason);
+            self.functions.insert(hash, Function::Rejected);
+            return None;
+        }
+
+        match self.functions.get(&hash) {
+            Some(Function::Compiled { entry }) => Some(*entry),
+            _ => None,
+        }
+    }
+
+    /// Compile the given function, along with all the functions it calls.
+    fn compile(&mut self, unit: &Unit, hash: Hash) -> Result<(), String> {
+        let mut offsets = unit
+            .iter_functions()
+            .filter_map(|(_, f)| match f {
+                UnitFn::Offset { offset, .. } => Some(*offset),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        offsets.sort_unstable();
+
+        let mut queue = vec![hash];
+        let mut group = Vec::<(Hash, FuncId, usize, ir::Function)>::new();
+
+        while let Some(hash) = queue.pop() {
+            if group.iter().any(|(h, ..)| *h == hash) {
+                continue;
+            }
+
+            if let Some(Function::Compiled { .. }) = self.functions.get(&hash) {
+                continue;
+            }
+
+            let (offset, args) = match unit.lookup(hash) {
+                Some(UnitFn::Offset {
+                    offset,
+                    call: Call::Immediate,
+                    args,
+                }) if args <= MAX_ARGS => (offset, args),
+                _ => return Err(format!("function `{}` isn't supported", hash)),
+            };
+
+            let end = offsets
+                .iter()
+                .copied()
+                .find(|o| *o > offset)
+                .unwrap_or_else(|| unit.iter_instructions().count());
+
+            let insts = (offset..end)
+                .filter_map(|ip| unit.instruction_at(ip).copied())
+                .collect::<Vec<_>>();
+
+            let id = self.declare(hash, args)?;
+            let func = self.translate(unit, id, args, &insts, &mut queue)?;
+            group.push((hash, id, args, func));
+        }
+
+        let mut entries = Vec::new();
+
+        for (hash, id, args, func) in group {
+            let mut context = cranelift_codegen::Context::for_function(func);
+            self.module
+                .define_function(id, &mut context)
+                .map_err(|e| e.to_string())?;
+
+            entries.push((hash, self.entry(id, args)?));
+        }
+
+        self.module
+            .finalize_definitions()
+            .map_err(|e| e.to_string())?;
+
+        for (hash, id) in entries {
+            let code = self.module.get_finalized_function(id);
+            // Safety: the entry point was compiled with a matching signature.
+            let entry = unsafe { std::mem::transmute::<*const u8, Entry>(code) };
+            self.functions.insert(hash, Function::Compiled { entry });
+        }
+
+        Ok(())
+    }
+
+    /// Declare the function with the given hash.
+    fn declare(&mut self, hash: Hash, args: usize) -> Result<FuncId, String> {
+        if let Some(id) = self.ids.get(&hash) {
+            return Ok(*id);
+        }
+
+        let signature = self.signature(args);
+        let id = self
+            .module
+            .declare_anonymous_function(&signature)
+            .map_err(|e| e.to_string())?;
+
+        self.ids.insert(hash, id);
+        Ok(id)
+    }
+
+    /// The signature of a compiled function, which takes a pointer to the
+    /// state followed by its arguments.
+    fn signature(&self, args: usize) -> Signature {
+        let mut signature = self.module.make_signature();
+        let pointer = self.module.target_config().pointer_type();
+        signature.params.push(AbiParam::new(pointer));
+        signature
+            .params
+            .extend((0..args).map(|_| AbiParam::new(I64)));
+        signature.returns.push(AbiParam::new(I64));
+        signature
+    }
+
+    /// Define the entry point of a compiled function.
+    fn entry(&mut self, id: FuncId, args: usize) -> Result<FuncId, String> {
+        let pointer = self.module.target_config().pointer_type();
+        let mut signature = self.module.make_signature();
+        signature.params.push(AbiParam::new(pointer));
+        signature.params.push(AbiParam::new(pointer));
+        signature.returns.push(AbiParam::new(I64));
+
+        let entry_id = self
+            .module
+            .declare_anonymous_function(&signature)
+            .map_err(|e| e.to_string())?;
+
+        let mut func =
+            ir::Function::with_name_signature(UserFuncName::user(0, entry_id.as_u32()), signature);
+        let callee = self.module.declare_func_in_func(id, &mut func);
+
+        let mut b = FunctionBuilder::new(&mut func, &mut self.builder);
+        let block = b.create_block();
+        b.append_block_params_for_function_params(block);
+        b.switch_to_block(block);
+
+        let params = b.block_params(block).to_vec();
+        let mut call_args = vec![params[0]];
+
+        for n in 0..args {
+            let offset = (n * 8) as i32;
+            call_args.push(b.ins().load(I64, MemFlags::trusted(), params[1], offset));
+        }
+
+        let call = b.ins().call(callee, &call_args);
+        let value = b.inst_results(call)[0];
+        b.ins().return_(&[value]);
+        b.seal_all_blocks();
+        b.finalize();
+
+        let mut context = cranelift_codegen::Context::for_function(func);
+        self.module
+            .define_function(entry_id, &mut context)
+            .map_err(|e| e.to_string())?;
+        Ok(entry_id)
+    }
+
+    /// Translate the instructions of a function.
+    fn translate(
+        &mut self,
+        unit: &Unit,
+        id: FuncId,
+        args: usize,
+        insts: &[Inst],
+        queue: &mut Vec<Hash>,
+    ) -> Result<ir::Function, String> {
+        // NB: every jump target starts a new block, as does every instruction
+        // following a jump or a return.
+        let mut leaders = vec![false; insts.len() + 1];
+        leaders[0] = true;
+
+        for (n, inst) in insts.iter().enumerate() {
+            match *inst {
+                Inst::Jump { offset }
+                | Inst::JumpIf { offset }
+                | Inst::JumpIfNot { offset }
+                | Inst::JumpIfOrPop { offset }
+                | Inst::JumpIfNotOrPop { offset }
+                | Inst::PopAndJumpIfNot { offset, .. } => {
+                    leaders[target(n, offset, insts.len())?] = true;
+                    leaders[n + 1] = true;
+                }
+                Inst::Return | Inst::ReturnUnit => {
+                    leaders[n + 1] = true;
+                }
+                _ => (),
+            }
+        }
+
+        let mut calls = Vec::new();
+
+        for inst in insts {
+            if let Inst::Call { hash, .. } = *inst {
+                let args = match unit.lookup(hash) {
+                    Some(UnitFn::Offset { args, .. }) => args,
+                    _ => return Err(format!("call to unsupported function `{}`", hash)),
+                };
+
+                let id = self.declare(hash, args)?;
+                calls.push((hash, id));
+
+                if !matches!(self.functions.get(&hash), Some(Function::Compiled { .. })) {
+                    queue.push(hash);
+                }
+            }
+        }
+
+        let signature = self.signature(args);
+        let mut func =
+            ir::Function::with_name_signature(UserFuncName::user(0, id.as_u32()), signature);
+
+        let mut refs = HashMap::new();
+
+        for (hash, id) in calls {
+            let callee = self.module.declare_func_in_func(id, &mut func);
+            refs.insert(hash, callee);
+        }
+
+        let mut b = FunctionBuilder::new(&mut func, &mut self.builder);
+
+        let entry = b.create_block();
+        b.append_block_params_for_function_params(entry);
+        b.switch_to_block(entry);
+
+        let params = b.block_params(entry).to_vec();
+        let state = params[0];
+        let depth = b.ins().load(I64, MemFlags::trusted(), state, 8);
+
+        let bail = b.create_block();
+        let body = b.create_block();
+        b.ins().brif(depth, body, &[], bail, &[]);
+
+        b.switch_to_block(bail);
+        let one = b.ins().iconst(I64, 1);
+        b.ins().store(MemFlags::trusted(), one, state, 0);
+        let zero = b.ins().iconst(I64, 0);
+        b.ins().return_(&[zero]);
+
+        b.switch_to_block(body);
+        let remaining = b.ins().iadd_imm(depth, -1);
+        b.ins().store(MemFlags::trusted(), remaining, state, 8);
+
+        let mut t = Translator {
+            b,
+            insts,
+            leaders: &leaders,
+            blocks: HashMap::new(),
+            queue: Vec::new(),
+            state,
+            depth,
+            bail,
+            refs: &refs,
+            unit,
+        };
+
+        let stack = params[1..]
+            .iter()
+            .map(|v| (*v, Type::Integer))
+            .collect::<Vec<_>>();
+        let (block, values) = t.goto(0, &stack)?;
+        t.b.ins().jump(block, &values);
+
+        while let Some(start) = t.queue.pop() {
+            t.block(start)?;
+        }
+
+        t.b.seal_all_blocks();
+        t.b.finalize();
+        Ok(func)
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        // Safety: entry points into the module are only used while the lock
+        // is held, so none of them can be used after this.
+        unsafe {
+            ManuallyDrop::take(&mut self.module).free_memory();
+        }
+    }
+}
+
+/// The type of a value on the stack of a compiled function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Integer,
+    Bool,
+}
+
+/// Translates the blocks of a function.
+struct Translator<'a> {
+    b: FunctionBuilder<'a>,
+    insts: &'a [Inst],
+    leaders: &'a [bool],
+    /// The blocks which have been jumped to, with the types of the stack when
+    /// entering them.
+    blocks: HashMap<usize, (ir::Block, Vec<Type>)>,
+    /// Blocks waiting to be translated.
+    queue: Vec<usize>,
+    state: ir::Value,
+    depth: ir::Value,
+    bail: ir::Block,
+    refs: &'a HashMap<Hash, ir::FuncRef>,
+    unit: &'a Unit,
+}
+
+impl Translator<'_> {
+    /// Get the block starting at the given instruction, along with the
+    /// arguments to pass to it from the given stack.
+    fn goto(
+        &mut self,
+        start: usize,
+        stack: &[(ir::Value, Type)],
+    ) -> Result<(ir::Block, Vec<ir::Value>), String> {
+        let types = stack.iter().map(|(_, t)| *t).collect::<Vec<_>>();
+        let values = stack.iter().map(|(v, _)| *v).collect::<Vec<_>>();
+
+        if let Some((block, expected)) = self.blocks.get(&start) {
+            if *expected != types {
+                return Err(format!("stack mismatch when jumping to {}", start));
+            }
+
+            return Ok((*block, values));
+        }
+
+        let block = self.b.create_block();
+
+        for _ in &types {
+            self.b.append_block_param(block, I64);
+        }
+
+        self.blocks.insert(start, (block, types));
+        self.queue.push(start);
+        Ok((block, values))
+    }
+
+    /// Translate the block starting at the given instruction.
+    fn block(&mut self, start: usize) -> Result<(), String> {
+        let (block, types) = self.blocks[&start].clone();
+        self.b.switch_to_block(block);
+
+        let mut stack = self
+            .b
+            .block_params(block)
+            .iter()
+            .copied()
+            .zip(types)
+            .collect::<Vec<_>>();
+
+        let mut n = start;
+
+        loop {
+            if n >= self.insts.len() {
+                return Err(String::from("function falls through"));
+            }
+
+            match self.insts[n] {
+                Inst::Integer { number } => {
+                    stack.push((self.b.ins().iconst(I64, number), Type::Integer));
+                }
+                Inst::Bool { value } => {
+                    stack.push((self.b.ins().iconst(I64, value as i64), Type::Bool));
+                }
+                Inst::Pop => {
+                    pop(&mut stack)?;
+                }
+                Inst::PopN { count } => {
+                    truncate(&mut stack, count)?;
+                }
+                Inst::Clean { count } => {
+                    let top = pop(&mut stack)?;
+                    truncate(&mut stack, count)?;
+                    stack.push(top);
+                }
+                Inst::Copy { offset } => {
+                    let value = *at(&mut stack, offset)?;
+                    stack.push(value);
+                }
+                Inst::Drop { offset } => {
+                    at(&mut stack, offset)?;
+                }
+                Inst::Dup => {
+                    let value = *stack.last().ok_or("stack underflow")?;
+                    stack.push(value);
+                }
+                Inst::Replace { offset } => {
+                    let value = pop(&mut stack)?;
+                    *at(&mut stack, offset)? = value;
+                }
+                Inst::Add => self.num(&mut stack, Op::Add)?,
+                Inst::Sub => self.num(&mut stack, Op::Sub)?,
+                Inst::Mul => self.num(&mut stack, Op::Mul)?,
+                Inst::Div => self.num(&mut stack, Op::Div)?,
+                Inst::Rem => self.num(&mut stack, Op::Rem)?,
+                Inst::AddAssign { offset } | Inst::ConcatAssign { offset } => {
+                    self.num_assign(&mut stack, offset, Op::Add)?
+                }
+                Inst::SubAssign { offset } => self.num_assign(&mut stack, offset, Op::Sub)?,
+                Inst::MulAssign { offset } => self.num_assign(&mut stack, offset, Op::Mul)?,
+                Inst::DivAssign { offset } => self.num_assign(&mut stack, offset, Op::Div)?,
+                Inst::RemAssign { offset } => self.num_assign(&mut stack, offset, Op::Rem)?,
+                Inst::Lt => self.cmp(&mut stack, IntCC::SignedLessThan, false)?,
+                Inst::Gt => self.cmp(&mut stack, IntCC::SignedGreaterThan, false)?,
+                Inst::Lte => self.cmp(&mut stack, IntCC::SignedLessThanOrEqual, false)?,
+                Inst::Gte => self.cmp(&mut stack, IntCC::SignedGreaterThanOrEqual, false)?,
+                Inst::Eq => self.cmp(&mut stack, IntCC::Equal, true)?,
+                Inst::Neq => self.cmp(&mut stack, IntCC::NotEqual, true)?,
+                Inst::Not => {
+                    let (value, ty) = pop(&mut stack)?;
+
+                    let value = match ty {
+                        Type::Integer => self.b.ins().bnot(value),
+                        Type::Bool => self.b.ins().bxor_imm(value, 1),
+                    };
+
+                    stack.push((value, ty));
+                }
+                Inst::And | Inst::Or => {
+                    let (rhs, rhs_ty) = pop(&mut stack)?;
+                    let (lhs, lhs_ty) = pop(&mut stack)?;
+
+                    if (lhs_ty, rhs_ty) != (Type::Bool, Type::Bool) {
+                        return Err(String::from("logical operation on non-booleans"));
+                    }
+
+                    let value = match self.insts[n] {
+                        Inst::And => self.b.ins().band(lhs, rhs),
+                        _ => self.b.ins().bor(lhs, rhs),
+                    };
+
+                    stack.push((value, Type::Bool));
+                }
+                Inst::Call { hash, args } => {
+                    let expected = match self.unit.lookup(hash) {
+                        Some(UnitFn::Offset {
+                            call: Call::Immediate,
+                            args,
+                            ..
+                        }) => args,
+                        _ => return Err(format!("call to unsupported function `{}`", hash)),
+                    };
+
+                    if args != expected || args > stack.len() {
+                        return Err(format!("bad call to function `{}`", hash));
+                    }
+
+                    let mut call_args = vec![self.state];
+
+                    for (value, ty) in stack.drain(stack.len() - args..) {
+                        if ty != Type::Integer {
+                            return Err(format!("non-integer argument to `{}`", hash));
+                        }
+
+                        call_args.push(value);
+                    }
+
+                    let call = self.b.ins().call(self.refs[&hash], &call_args);
+                    let value = self.b.inst_results(call)[0];
+                    let bailed = self.b.ins().load(I64, MemFlags::trusted(), self.state, 0);
+                    self.bail_if(bailed);
+                    stack.push((value, Type::Integer));
+                }
+                Inst::Jump { offset } => {
+                    let (block, args) = self.goto(target(n, offset, self.insts.len())?, &stack)?;
+                    self.b.ins().jump(block, &args);
+                    return Ok(());
+                }
+                Inst::JumpIf { offset } | Inst::JumpIfNot { offset } => {
+                    let cond = self.condition(&mut stack)?;
+                    let jump = self.goto(target(n, offset, self.insts.len())?, &stack)?;
+                    let next = self.goto(n + 1, &stack)?;
+
+                    let (then, otherwise) = match self.insts[n] {
+                        Inst::JumpIf { .. } => (jump, next),
+                        _ => (next, jump),
+                    };
+
+                    self.b
+                        .ins()
+                        .brif(cond, then.0, &then.1, otherwise.0, &otherwise.1);
+                    return Ok(());
+                }
+                Inst::JumpIfOrPop { offset } | Inst::JumpIfNotOrPop { offset } => {
+                    let cond = self.condition(&mut stack)?;
+                    let next = self.goto(n + 1, &stack)?;
+                    stack.push((cond, Type::Bool));
+                    let jump = self.goto(target(n, offset, self.insts.len())?, &stack)?;
+
+                    let (then, otherwise) = match self.insts[n] {
+                        Inst::JumpIfOrPop { .. } => (jump, next),
+                        _ => (next, jump),
+                    };
+
+                    self.b
+                        .ins()
+                        .brif(cond, then.0, &then.1, otherwise.0, &otherwise.1);
+                    return Ok(());
+                }
+                Inst::PopAndJumpIfNot { count, offset } => {
+                    let cond = self.condition(&mut stack)?;
+                    let next = self.goto(n + 1, &stack)?;
+                    truncate(&mut stack, count)?;
+                    let jump = self.goto(target(n, offset, self.insts.len())?, &stack)?;
+                    self.b.ins().brif(cond, next.0, &next.1, jump.0, &jump.1);
+                    return Ok(());
+                }
+                Inst::Return => {
+                    let (value, ty) = pop(&mut stack)?;
+
+                    if ty != Type::Integer {
+                        return Err(String::from("returns a non-integer"));
+                    }
+
+                    self.b
+                        .ins()
+                        .store(MemFlags::trusted(), self.depth, self.state, 8);
+                    self.b.ins().return_(&[value]);
+                    return Ok(());
+                }
+                inst => return Err(format!("unsupported instruction `{}`", inst)),
+            }
+
+            n += 1;
+
+            if self.leaders[n] {
+                let (block, args) = self.goto(n, &stack)?;
+                self.b.ins().jump(block, &args);
+                return Ok(());
+            }
+        }
+    }
+
+    /// Pop a boolean condition off the stack.
+    fn condition(&mut self, stack: &mut Vec<(ir::Value, Type)>) -> Result<ir::Value, String> {
+        match pop(stack)? {
+            (value, Type::Bool) => Ok(value),
+            _ => Err(String::from("condition isn't a boolean")),
+        }
+    }
+
+    /// Jump to the bail out block if the given value is non-zero.
+    fn bail_if(&mut self, value: ir::Value) {
+        let next = self.b.create_block();
+        self.b.ins().brif(value, self.bail, &[], next, &[]);
+        self.b.switch_to_block(next);
+    }
+
+    /// Perform a checked arithmetic operation.
+    fn op(&mut self, op: Op, lhs: ir::Value, rhs: ir::Value) -> ir::Value {
+        let (value, overflow) = match op {
+            Op::Add => self.b.ins().sadd_overflow(lhs, rhs),
+            Op::Sub => self.b.ins().ssub_overflow(lhs, rhs),
+            Op::Mul => self.b.ins().smul_overflow(lhs, rhs),
+            Op::Div | Op::Rem => {
+                // NB: dividing by zero, or the smallest integer by -1, is an
+                // error.
+                let zero = self.b.ins().icmp_imm(IntCC::Equal, rhs, 0);
+                let min = self.b.ins().icmp_imm(IntCC::Equal, lhs, i64::MIN);
+                let neg = self.b.ins().icmp_imm(IntCC::Equal, rhs, -1);
+                let overflow = self.b.ins().band(min, neg);
+                let error = self.b.ins().bor(zero, overflow);
+                self.bail_if(error);
+
+                return match op {
+                    Op::Div => self.b.ins().sdiv(lhs, rhs),
+                    _ => self.b.ins().srem(lhs, rhs),
+                };
+            }
+        };
+
+        self.bail_if(overflow);
+        value
+    }
+
+    fn num(&mut self, stack: &mut Vec<(ir::Value, Type)>, op: Op) -> Result<(), String> {
+        let rhs = integer(pop(stack)?)?;
+        let lhs = integer(pop(stack)?)?;
+        let value = self.op(op, lhs, rhs);
+        stack.push((value, Type::Integer));
+        Ok(())
+    }
+
+    fn num_assign(
+        &mut self,
+        stack: &mut Vec<(ir::Value, Type)>,
+        offset: usize,
+        op: Op,
+    ) -> Result<(), String> {
+        let rhs = integer(pop(stack)?)?;
+        let lhs = integer(*at(stack, offset)?)?;
+        let value = self.op(op, lhs, rhs);
+        *at(stack, offset)? = (value, Type::Integer);
+        Ok(())
+    }
+
+    fn cmp(
+        &mut self,
+        stack: &mut Vec<(ir::Value, Type)>,
+        cc: IntCC,
+        booleans: bool,
+    ) -> Result<(), String> {
+        let (rhs, rhs_ty) = pop(stack)?;
+        let (lhs, lhs_ty) = pop(stack)?;
+
+        if lhs_ty != rhs_ty || (lhs_ty == Type::Bool && !booleans) {
+            return Err(String::from("unsupported comparison"));
+        }
+
+        let value = self.b.ins().icmp(cc, lhs, rhs);
+        let value = self.b.ins().uextend(I64, value);
+        stack.push((value, Type::Bool));
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+fn pop(stack: &mut Vec<(ir::Value, Type)>) -> Result<(ir::Value, Type), String> {
+    stack.pop().ok_or_else(|| String::from("stack underflow"))
+}
+
+fn truncate(stack: &mut Vec<(ir::Value, Type)>, count: usize) -> Result<(), String> {
+    let len = stack
+        .len()
+        .checked_sub(count)
+        .ok_or_else(|| String::from("stack underflow"))?;
+    stack.truncate(len);
+    Ok(())
+}
+
+fn at(stack: &mut [(ir::Value, Type)], offset: usize) -> Result<&mut (ir::Value, Type), String> {
+    stack
+        .get_mut(offset)
+        .ok_or_else(|| format!("stack offset {} out of bounds", offset))
+}
+
+fn integer((value, ty): (ir::Value, Type)) -> Result<ir::Value, String> {
+    match ty {
+        Type::Integer => Ok(value),
+        Type::Bool => Err(String::from("arithmetic on a boolean")),
+    }
+}
+
+/// Calculate the instruction targeted by a jump at the given instruction.
+fn target(n: usize, offset: isize, len: usize) -> Result<usize, String> {
+    let target = (n as isize) + 1 + offset;
+
+    if target < 0 || target as usize >= len {
+        return Err(format!("jump at {} leaves the function", n));
+    }
+
+    Ok(target as usize)
+}
@@ -37,6 +37,7 @@
 
 #![deny(missing_docs)]
 
+mod abi;
 mod any;
 mod context;
 mod value;
@@ -46,24 +47,49 @@ mod macros;
 mod access;
 mod args;
 mod awaited;
+mod bitset;
 mod bytes;
 mod call;
+mod call_context;
+mod call_hook;
+mod cancellation;
 mod compile_meta;
 pub mod debug;
+mod decimal;
+mod deep;
+mod events;
+mod finalizer;
+mod float_array;
+mod frontmatter;
 mod function;
 mod future;
 mod generator;
 mod generator_state;
+mod generator_status;
+mod handle;
 mod hash;
+mod heap;
+mod host_ref;
 mod inst;
+mod interface;
 mod item;
+#[cfg(feature = "jit")]
+mod jit;
 mod label;
+mod memoize;
 pub mod module;
 pub mod modules;
 mod names;
+mod native_frame;
+mod output;
+mod overrides;
 mod panic;
+mod pretty;
 mod protocol;
+mod quicken;
+mod range;
 mod reflection;
+mod runtime;
 mod select;
 mod serde;
 mod shared;
@@ -72,16 +98,21 @@ mod span;
 mod stack;
 mod static_string;
 mod static_type;
+mod statics;
 mod stream;
+mod time;
+mod trace;
 mod tuple;
 mod type_;
 mod type_info;
 mod unit;
+mod unit_registry;
 mod vec_tuple;
 mod vm_call;
 mod vm_error;
 mod vm_execution;
 mod vm_halt;
+mod vm_pool;
 
 impl_external!(anyhow::Error);
 
@@ -93,10 +124,11 @@ pub type Error = anyhow::Error;
 
 pub use self::args::Args;
 pub use self::compile_meta::{
-    CompileMeta, CompileMetaCapture, CompileMetaStruct, CompileMetaTuple,
+    CompileMeta, CompileMetaCapture, CompileMetaSignature, CompileMetaStruct, CompileMetaTuple,
 };
 pub use self::generator::Generator;
 pub use self::generator_state::GeneratorState;
+pub use self::generator_status::GeneratorStatus;
 pub use self::label::Label;
 pub use self::module::{IntoInstFnHash, Module};
 pub use self::select::Select;
@@ -105,48 +137,74 @@ pub use self::span::Span;
 pub use self::static_string::StaticString;
 pub use self::static_type::{
     StaticType, BOOL_TYPE, BYTES_TYPE, BYTE_TYPE, CHAR_TYPE, FLOAT_TYPE, FUNCTION_TYPE,
-    FUTURE_TYPE, GENERATOR_STATE_TYPE, GENERATOR_TYPE, INTEGER_TYPE, OBJECT_TYPE, OPTION_TYPE,
-    RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
+    FUTURE_TYPE, GENERATOR_STATE_TYPE, GENERATOR_TYPE, HANDLE_TYPE, INTEGER_TYPE, OBJECT_TYPE,
+    OPTION_TYPE, RESULT_TYPE, STREAM_TYPE, STRING_TYPE, TUPLE_TYPE, UNIT_TYPE, VEC_TYPE,
 };
 pub use self::stream::Stream;
+pub use self::trace::{TraceBuffer, TraceConfig, TraceEntry};
 pub use self::tuple::Tuple;
 pub use self::type_::Type;
 pub use self::type_info::TypeInfo;
+pub use crate::abi::{AbiError, UnitAbi, RUNTIME_VERSION, SUPPORTED_FEATURES};
 pub use crate::access::{
     AccessError, BorrowMut, BorrowRef, NotAccessibleMut, NotAccessibleRef, RawBorrowedMut,
-    RawBorrowedRef,
+    RawBorrowedRef, Snapshot,
 };
 pub use crate::any::{Any, AnyVtable};
 pub use crate::awaited::Awaited;
+pub use crate::bitset::BitSet;
 pub use crate::bytes::Bytes;
 pub use crate::call::Call;
-pub use crate::context::{Context, ContextError};
+pub use crate::call_context::CallContext;
+pub use crate::call_hook::{CallAction, CallHook, CallInfo};
+pub use crate::cancellation::CancellationHandle;
+pub use crate::context::{Context, ContextDiff, ContextError, ContextMatcher, ContextSignature};
 pub use crate::debug::{DebugInfo, DebugInst};
+pub use crate::decimal::{Decimal, ParseDecimalError};
+pub use crate::events::Events;
+pub use crate::finalizer::FINALIZER_BUDGET;
+pub use crate::float_array::FloatArray;
+pub use crate::frontmatter::{Frontmatter, FrontmatterError, SourceHeader};
 pub use crate::function::Function;
 pub use crate::future::Future;
+pub use crate::handle::Handle;
 pub use crate::hash::{Hash, IntoHash};
-pub use crate::inst::{Inst, PanicReason, TypeCheck};
+pub use crate::heap::{HeapDiff, HeapDiffEntry, HeapDump, HeapNode, HeapRoot};
+pub use crate::host_ref::{HostBytes, HostGuard, HostStr};
+pub use crate::inst::{ArithmeticOp, Inst, PanicReason, TypeCheck};
+pub use crate::interface::{Interface, InterfaceError, InterfaceMismatch};
 pub use crate::item::{Component, Item};
+#[cfg(feature = "jit")]
+pub use crate::jit::{Jit, JitError};
 pub use crate::names::Names;
+pub use crate::native_frame::NativeFrame;
+pub use crate::output::{Capture, Output, Sink};
 pub use crate::panic::Panic;
+pub use crate::pretty::PrettyLimits;
 pub use crate::protocol::{
     Protocol, ADD, ADD_ASSIGN, BIT_AND, BIT_AND_ASSIGN, BIT_OR, BIT_OR_ASSIGN, BIT_XOR,
-    BIT_XOR_ASSIGN, DIV, DIV_ASSIGN, INDEX_GET, INDEX_SET, INTO_FUTURE, INTO_ITER, MUL, MUL_ASSIGN,
-    NEXT, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN, STRING_DISPLAY, SUB, SUB_ASSIGN,
+    BIT_XOR_ASSIGN, CLONE, CMP, DIV, DIV_ASSIGN, ENTER, EXIT, INDEX_GET, INDEX_SET, INTO_FUTURE,
+    INTO_ITER, MATCH, MUL, MUL_ASSIGN, NEXT, REM, REM_ASSIGN, SHL, SHL_ASSIGN, SHR, SHR_ASSIGN,
+    STRING_DISPLAY, SUB, SUB_ASSIGN,
 };
+pub use crate::range::Range;
 pub use crate::reflection::{FromValue, ToValue, UnsafeFromValue, ValueType};
+pub use crate::runtime::Runtime;
 pub use crate::shared::{OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared};
 pub use crate::stack::{Stack, StackError};
-pub use crate::unit::{Unit, UnitFn, UnitTypeInfo};
+pub use crate::time::{Duration, ParseTimeError, TimeFormatError, Timestamp};
+pub use crate::unit::{Unit, UnitFn, UnitHooks, UnitMemoize, UnitTypeInfo};
+pub use crate::unit_registry::UnitRegistry;
 pub use crate::value::{
     Integer, Object, TupleVariant, TypedObject, TypedTuple, Value, VariantObject,
 };
 pub use crate::vec_tuple::VecTuple;
 pub use crate::vm::{CallFrame, Vm};
 pub use crate::vm_call::VmCall;
-pub use crate::vm_error::{VmError, VmErrorKind};
+pub use crate::vm_error::{ElementPath, ElementStep, VmError, VmErrorChain, VmErrorKind};
 pub use crate::vm_execution::VmExecution;
 pub use crate::vm_halt::{VmHalt, VmHaltInfo};
+pub use crate::vm_pool::{PooledVm, VmPool};
 
 mod collections {
     pub use hashbrown::HashMap;
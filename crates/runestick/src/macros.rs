@@ -3,13 +3,21 @@
 /// This is required to support the external type as a type argument in a
 /// registered function.
 ///
+/// Generic types are declared with their type parameters, like
+/// `impl_external!(impl<T> Registry<T>)`. Every instantiation of a generic
+/// type has a distinct type hash, so `Registry<Item>` and `Registry<Tool>` can
+/// be registered as separate types.
+///
 /// This will be **deprecated** once (or if) [specialization] lands.
 ///
 /// [specialization]: https://github.com/rust-lang/rust/issues/31844
 #[macro_export]
 macro_rules! impl_external {
-    ($external:ty) => {
-        impl $crate::ValueType for $external {
+    (@impl <$($p:ident),*> $external:ty) => {
+        impl<$($p,)*> $crate::ValueType for $external
+        where
+            $($p: 'static,)*
+        {
             fn value_type() -> $crate::Type {
                 $crate::Type::Hash($crate::Hash::from_type_id(
                     std::any::TypeId::of::<$external>(),
@@ -21,7 +29,10 @@ macro_rules! impl_external {
             }
         }
 
-        impl $crate::FromValue for $external {
+        impl<$($p,)*> $crate::FromValue for $external
+        where
+            $($p: 'static,)*
+        {
             fn from_value(value: $crate::Value) -> Result<Self, $crate::VmError> {
                 let any = value.into_any()?;
                 let any = any.take_downcast::<$external>()?;
@@ -29,7 +40,10 @@ macro_rules! impl_external {
             }
         }
 
-        impl $crate::ToValue for $external {
+        impl<$($p,)*> $crate::ToValue for $external
+        where
+            $($p: 'static,)*
+        {
             fn to_value(self) -> Result<$crate::Value, $crate::VmError> {
                 let any = $crate::Any::new(self);
                 let shared = $crate::Shared::new(any);
@@ -37,7 +51,10 @@ macro_rules! impl_external {
             }
         }
 
-        impl<'a> $crate::UnsafeFromValue for &'a $external {
+        impl<'a, $($p,)*> $crate::UnsafeFromValue for &'a $external
+        where
+            $($p: 'static,)*
+        {
             type Output = *const $external;
             type Guard = $crate::RawOwnedRef;
 
@@ -52,7 +69,10 @@ macro_rules! impl_external {
             }
         }
 
-        impl<'a> $crate::UnsafeFromValue for &'a mut $external {
+        impl<'a, $($p,)*> $crate::UnsafeFromValue for &'a mut $external
+        where
+            $($p: 'static,)*
+        {
             type Output = *mut $external;
             type Guard = $crate::RawOwnedMut;
 
@@ -67,6 +87,14 @@ macro_rules! impl_external {
             }
         }
     };
+
+    (impl <$($p:ident),*> $external:ty) => {
+        $crate::impl_external!(@impl <$($p),*> $external);
+    };
+
+    ($external:ty) => {
+        $crate::impl_external!(@impl <> $external);
+    };
 }
 
 /// Build an implementation of `ValueType` basic of a static type.
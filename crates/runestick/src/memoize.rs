@@ -0,0 +1,311 @@
+use crate::collections::HashMap;
+use crate::deep::{DeepClone, DeepHash};
+use crate::{UnitMemoize, Value, VmError, VmErrorKind, CLONE};
+use std::collections::VecDeque;
+use std::hash::{BuildHasher as _, BuildHasherDefault, Hasher as _};
+use std::time::Instant;
+use twox_hash::XxHash64;
+
+/// The arguments of a call to a memoized function, which its results are
+/// cached by.
+#[derive(Debug, Clone)]
+pub(crate) struct MemoizeKey {
+    /// The structural hash of the arguments.
+    hash: u64,
+    /// Deep clones of the arguments, used to tell apart calls with colliding
+    /// hashes.
+    args: Vec<Value>,
+}
+
+impl MemoizeKey {
+    /// Construct a key from the arguments of a call.
+    ///
+    /// Errors if an argument can't be hashed.
+    pub(crate) fn new(args: &[Value]) -> Result<Self, VmError> {
+        let mut deep = DeepHash::new();
+        let mut hasher = BuildHasherDefault::<XxHash64>::default().build_hasher();
+
+        for arg in args {
+            hasher.write_u64(deep.hash_value(arg)?);
+        }
+
+        // NB: the arguments are cloned so that they can't be modified once
+        // they're cached. External values can't be hashed, so they're never
+        // cloned.
+        let mut clone = DeepClone::new(|value: &Value| {
+            Err(VmError::from(VmErrorKind::MissingProtocol {
+                protocol: CLONE,
+                actual: value.type_info()?,
+            }))
+        });
+
+        let args = args
+            .iter()
+            .map(|arg| clone.clone_value(arg))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            hash: hasher.finish(),
+            args,
+        })
+    }
+
+    /// Test if the key is for the given arguments.
+    fn matches(&self, args: &[Value]) -> Result<bool, VmError> {
+        if self.args.len() != args.len() {
+            return Ok(false);
+        }
+
+        for (a, b) in self.args.iter().zip(args) {
+            if !Value::value_ptr_eq(a, b)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// A cached result of a call to a memoized function.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Identifies the entry in the insertion order of the cache.
+    id: usize,
+    args: Vec<Value>,
+    value: Value,
+    inserted: Instant,
+}
+
+/// The cached results of a single memoized function.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoizeCache {
+    /// Entries, keyed by the hash of their arguments.
+    entries: HashMap<u64, Vec<Entry>>,
+    /// The hash and id of entries in the order they were inserted in, which
+    /// is used to evict the oldest entries when the cache is full. Contains
+    /// ids of entries which have already been removed.
+    order: VecDeque<(u64, usize)>,
+    /// The number of cached entries.
+    len: usize,
+    /// The id of the next entry.
+    next_id: usize,
+}
+
+impl MemoizeCache {
+    /// Get the cached result for the given arguments, unless it has expired.
+    pub(crate) fn get(
+        &mut self,
+        memoize: &UnitMemoize,
+        key: &MemoizeKey,
+    ) -> Result<Option<Value>, VmError> {
+        let entry = match self.find(key)? {
+            Some(entry) => entry,
+            None => return Ok(None),
+        };
+
+        if is_expired(memoize, entry) {
+            let id = entry.id;
+            self.remove(key.hash, id);
+            return Ok(None);
+        }
+
+        Ok(Some(entry.value.clone()))
+    }
+
+    /// Cache the result for the given arguments, evicting the oldest entries
+    /// if the cache is full.
+    pub(crate) fn insert(
+        &mut self,
+        memoize: &UnitMemoize,
+        key: MemoizeKey,
+        value: Value,
+    ) -> Result<(), VmError> {
+        // NB: a recursive call with the same arguments might have cached a
+        // result while this one was running.
+        if let Some(entry) = self.find(&key)? {
+            let id = entry.id;
+            self.remove(key.hash, id);
+        }
+
+        self.prune(memoize);
+
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.entries.entry(key.hash).or_default().push(Entry {
+            id,
+            args: key.args,
+            value,
+            inserted: Instant::now(),
+        });
+
+        self.order.push_back((key.hash, id));
+        self.len += 1;
+
+        if let Some(capacity) = memoize.capacity {
+            while self.len > capacity {
+                self.evict_oldest();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the entry for the given key.
+    fn find(&self, key: &MemoizeKey) -> Result<Option<&Entry>, VmError> {
+        let bucket = match self.entries.get(&key.hash) {
+            Some(bucket) => bucket,
+            None => return Ok(None),
+        };
+
+        for entry in bucket {
+            if key.matches(&entry.args)? {
+                return Ok(Some(entry));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Test if the entry with the given hash and id is still cached, and if so
+    /// if it has expired.
+    fn is_live(&self, memoize: &UnitMemoize, hash: u64, id: usize) -> Option<bool> {
+        let entry = self.entries.get(&hash)?.iter().find(|e| e.id == id)?;
+        Some(!is_expired(memoize, entry))
+    }
+
+    /// Remove the entry with the given hash and id, along with its bucket if
+    /// it's the last entry in it.
+    fn remove(&mut self, hash: u64, id: usize) -> bool {
+        let bucket = match self.entries.get_mut(&hash) {
+            Some(bucket) => bucket,
+            None => return false,
+        };
+
+        let index = match bucket.iter().position(|entry| entry.id == id) {
+            Some(index) => index,
+            None => return false,
+        };
+
+        bucket.remove(index);
+
+        if bucket.is_empty() {
+            self.entries.remove(&hash);
+        }
+
+        self.len -= 1;
+        true
+    }
+
+    /// Remove expired entries, and the ids of entries which have already been
+    /// removed from the insertion order.
+    ///
+    /// Entries are inserted in the order they expire in, so expired entries
+    /// are always at the front. Removed entries elsewhere are dropped from
+    /// the insertion order once they make up most of it.
+    fn prune(&mut self, memoize: &UnitMemoize) {
+        while let Some(&(hash, id)) = self.order.front() {
+            match self.is_live(memoize, hash, id) {
+                Some(true) => break,
+                Some(false) => {
+                    self.remove(hash, id);
+                }
+                None => (),
+            }
+
+            self.order.pop_front();
+        }
+
+        if self.order.len() > self.len * 2 {
+            let entries = &self.entries;
+
+            self.order.retain(|(hash, id)| match entries.get(hash) {
+                Some(bucket) => bucket.iter().any(|entry| entry.id == *id),
+                None => false,
+            });
+        }
+    }
+
+    /// Evict the oldest entry which is still cached.
+    fn evict_oldest(&mut self) {
+        while let Some((hash, id)) = self.order.pop_front() {
+            if self.remove(hash, id) {
+                return;
+            }
+        }
+    }
+}
+
+/// Test if the given entry has expired.
+fn is_expired(memoize: &UnitMemoize, entry: &Entry) -> bool {
+    match memoize.ttl {
+        Some(ttl) => entry.inserted.elapsed() >= ttl,
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MemoizeCache, MemoizeKey};
+    use crate::{Item, UnitMemoize, Value};
+    use std::time::Duration;
+
+    fn memoize(ttl: Option<Duration>) -> UnitMemoize {
+        UnitMemoize {
+            item: Item::of(&["test"]),
+            capacity: None,
+            ttl,
+        }
+    }
+
+    fn key(n: i64) -> MemoizeKey {
+        MemoizeKey::new(&[Value::Integer(n)]).unwrap()
+    }
+
+    #[test]
+    fn test_memoize_cache_prunes_removed() {
+        let memoize = memoize(None);
+        let mut cache = MemoizeCache::default();
+
+        for _ in 0..100 {
+            cache.insert(&memoize, key(1), Value::Unit).unwrap();
+        }
+
+        assert_eq!(cache.len, 1);
+        assert!(cache.order.len() <= 2);
+    }
+
+    #[test]
+    fn test_memoize_cache_prunes_expired() {
+        let memoize = memoize(Some(Duration::from_secs(0)));
+        let mut cache = MemoizeCache::default();
+
+        for n in 0..100 {
+            cache.insert(&memoize, key(n), Value::Unit).unwrap();
+        }
+
+        assert_eq!(cache.len, 1);
+        assert_eq!(cache.entries.len(), 1);
+        assert_eq!(cache.order.len(), 1);
+
+        assert!(cache.get(&memoize, &key(99)).unwrap().is_none());
+        assert_eq!(cache.len, 0);
+        assert!(cache.entries.is_empty());
+    }
+
+    #[test]
+    fn test_memoize_key_is_cloned() {
+        let vec = Value::vec(vec![Value::Integer(1)]);
+        let key = MemoizeKey::new(std::slice::from_ref(&vec)).unwrap();
+
+        vec.into_vec()
+            .unwrap()
+            .borrow_mut()
+            .unwrap()
+            .push(Value::Integer(2));
+
+        let expected = MemoizeKey::new(&[Value::vec(vec![Value::Integer(1)])]).unwrap();
+        assert_eq!(key.hash, expected.hash);
+        assert!(key.matches(&expected.args).unwrap());
+    }
+}
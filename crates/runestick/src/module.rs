@@ -5,14 +5,17 @@
 
 use crate::collections::HashMap;
 use crate::{
-    Component, Future, Hash, Stack, ToValue, Type, TypeInfo, UnsafeFromValue, ValueType, VmError,
-    VmErrorKind,
+    CallContext, Component, Future, Hash, Stack, ToValue, Type, TypeInfo, UnsafeFromValue,
+    ValueType, Vm, VmError, VmErrorKind,
 };
 use std::any::type_name;
 use std::future;
+use std::marker::PhantomData;
+use std::pin::Pin;
 use std::sync::Arc;
 
-use crate::context::{ContextError, Handler, Macro};
+use crate::collections::HashSet;
+use crate::context::{ContextError, ContextMatcher, Handler, Intrinsic, Macro};
 use crate::{GeneratorState, Item, StaticType, TypeCheck, Value};
 
 /// Specialized information on `Option` types.
@@ -54,8 +57,9 @@ impl ModuleInternalEnum {
         C: crate::module::Function<Args>,
         C::Return: ValueType,
     {
-        let constructor: Arc<Handler> =
-            Arc::new(move |stack, args| constructor.fn_call(stack, args));
+        let constructor: Arc<Handler> = Arc::new(move |cx: &mut CallContext<'_>, args| {
+            constructor.fn_call(cx.stack_mut(), args)
+        });
         let value_type = C::Return::value_type();
 
         self.variants.push(ModuleInternalVariant {
@@ -105,6 +109,13 @@ impl ModuleAssociatedKind {
     }
 }
 
+pub(crate) struct ModuleMatcher {
+    /// How the type is destructured.
+    pub(crate) matcher: ContextMatcher,
+    /// Type information for the destructured type.
+    pub(crate) type_info: TypeInfo,
+}
+
 pub(crate) struct ModuleAssociatedFn {
     pub(crate) handler: Arc<Handler>,
     pub(crate) args: Option<usize>,
@@ -122,6 +133,10 @@ pub(crate) struct ModuleAssocKey {
 pub(crate) struct ModuleFn {
     pub(crate) handler: Arc<Handler>,
     pub(crate) args: Option<usize>,
+    pub(crate) capabilities: Vec<String>,
+    pub(crate) intrinsic: Option<Arc<Intrinsic>>,
+    /// If the function produces the value of a constant.
+    pub(crate) constant: bool,
 }
 
 pub(crate) struct ModuleMacro {
@@ -141,10 +156,14 @@ pub struct Module {
     pub(crate) associated_functions: HashMap<ModuleAssocKey, ModuleAssociatedFn>,
     /// Registered types.
     pub(crate) types: HashMap<Type, ModuleType>,
+    /// How registered types are destructured in patterns.
+    pub(crate) matchers: HashMap<Type, ModuleMatcher>,
     /// Registered unit type.
     pub(crate) unit_type: Option<ModuleUnitType>,
     /// Registered generator state type.
     pub(crate) internal_enums: Vec<ModuleInternalEnum>,
+    /// Type aliases, and the full names of the types they refer to.
+    pub(crate) aliases: HashMap<Item, Item>,
 }
 
 impl Module {
@@ -160,8 +179,10 @@ impl Module {
             macros: Default::default(),
             associated_functions: Default::default(),
             types: Default::default(),
+            matchers: Default::default(),
             unit_type: None,
             internal_enums: Vec::new(),
+            aliases: Default::default(),
         }
     }
 
@@ -217,6 +238,51 @@ impl Module {
         }
     }
 
+    /// Register an alias for a type, which can be used in scripts anywhere
+    /// the type can, like in type checks or when calling its associated
+    /// functions.
+    ///
+    /// The alias is named relative to the module, while the type is referred
+    /// to by its full name. The type must already be installed in the context
+    /// when the module is, unless it's registered by the same module.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// struct Registry<T> {
+    ///     values: Vec<T>,
+    /// }
+    ///
+    /// runestick::impl_external!(impl<T> Registry<T>);
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["game"]);
+    /// module.ty(&["ItemRegistry"]).build::<Registry<String>>()?;
+    /// module.ty(&["ScoreRegistry"]).build::<Registry<i64>>()?;
+    /// module.type_alias(&["Items"], &["game", "ItemRegistry"])?;
+    ///
+    /// let mut context = runestick::Context::new();
+    /// context.install(&module)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn type_alias<N, T>(&mut self, name: N, target: T) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+        T: IntoIterator,
+        T::Item: Into<Component>,
+    {
+        let name = Item::of(name);
+
+        if self.aliases.contains_key(&name) {
+            return Err(ContextError::ConflictingAlias { name });
+        }
+
+        self.aliases.insert(name, Item::of(target));
+        Ok(())
+    }
+
     /// Construct type information for the `unit` type.
     ///
     /// Registering this allows the given type to be used in Rune scripts when
@@ -384,8 +450,56 @@ impl Module {
         self.functions.insert(
             name,
             ModuleFn {
-                handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
+                handler: Arc::new(move |cx: &mut CallContext<'_>, args| {
+                    f.fn_call(cx.stack_mut(), args)
+                }),
                 args: Some(Func::args()),
+                capabilities: Vec::new(),
+                intrinsic: None,
+                constant: false,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Register a constant, which scripts use by path like any other constant.
+    ///
+    /// The value is converted every time the constant is used.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["limits"]);
+    /// module.constant(&["MAX_USERS"], 100i64)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn constant<N, V>(&mut self, name: N, value: V) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+        V: 'static + ToValue + Clone + Send + Sync,
+    {
+        let name = Item::of(name);
+
+        if self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        self.functions.insert(
+            name,
+            ModuleFn {
+                handler: Arc::new(move |cx: &mut CallContext<'_>, args| {
+                    Vm::check_args(args, 0)?;
+                    cx.stack_mut().push(value.clone().to_value()?);
+                    Ok(())
+                }),
+                args: Some(0),
+                capabilities: Vec::new(),
+                intrinsic: None,
+                constant: true,
             },
         );
 
@@ -439,9 +553,18 @@ impl Module {
 
     /// Register a function.
     ///
+    /// The function either returns a future, or a boxed future like
+    /// `Box<dyn Future<Output = T>>` which is pinned before it's awaited.
+    ///
     /// # Examples
     ///
     /// ```rust
+    /// use std::future::Future;
+    ///
+    /// fn boxed(a: i64) -> Box<dyn Future<Output = i64> + Send> {
+    ///     Box::new(async move { a + 1 })
+    /// }
+    ///
     /// # fn main() -> runestick::Result<()> {
     /// let mut module = runestick::Module::default();
     ///
@@ -449,6 +572,7 @@ impl Module {
     /// module.async_function(&["empty_fallible"], || async { Ok::<_, runestick::Error>(()) })?;
     /// module.async_function(&["string"], |a: String| async { Ok::<_, runestick::Error>(()) })?;
     /// module.async_function(&["optional"], |a: Option<String>| async { Ok::<_, runestick::Error>(()) })?;
+    /// module.async_function(&["boxed"], boxed)?;
     /// # Ok(())
     /// # }
     /// ```
@@ -467,8 +591,13 @@ impl Module {
         self.functions.insert(
             name,
             ModuleFn {
-                handler: Arc::new(move |stack, args| f.fn_call(stack, args)),
+                handler: Arc::new(move |cx: &mut CallContext<'_>, args| {
+                    f.fn_call(cx.stack_mut(), args)
+                }),
                 args: Some(Func::args()),
+                capabilities: Vec::new(),
+                intrinsic: None,
+                constant: false,
             },
         );
 
@@ -479,7 +608,7 @@ impl Module {
     /// machine.
     pub fn raw_fn<F, N>(&mut self, name: N, f: F) -> Result<(), ContextError>
     where
-        F: 'static + Copy + Fn(&mut Stack, usize) -> Result<(), VmError> + Send + Sync,
+        F: 'static + Fn(&mut Stack, usize) -> Result<(), VmError> + Send + Sync,
         N: IntoIterator,
         N::Item: Into<Component>,
     {
@@ -492,14 +621,144 @@ impl Module {
         self.functions.insert(
             name,
             ModuleFn {
-                handler: Arc::new(move |stack, args| f(stack, args)),
+                handler: Arc::new(move |cx: &mut CallContext<'_>, args| f(cx.stack_mut(), args)),
                 args: None,
+                capabilities: Vec::new(),
+                intrinsic: None,
+                constant: false,
             },
         );
 
         Ok(())
     }
 
+    /// Register a raw function which is passed the [CallContext] it's called
+    /// in, which allows it to call back into other functions.
+    pub fn raw_fn_with_context<F, N>(&mut self, name: N, f: F) -> Result<(), ContextError>
+    where
+        F: 'static + Fn(&mut CallContext<'_>, usize) -> Result<(), VmError> + Send + Sync,
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        let name = Item::of(name);
+
+        if self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        self.functions.insert(
+            name,
+            ModuleFn {
+                handler: Arc::new(f),
+                args: None,
+                capabilities: Vec::new(),
+                intrinsic: None,
+                constant: false,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Register an intrinsic, which is a function taking a fixed number of
+    /// arguments that operates directly on the stack of the virtual machine.
+    ///
+    /// Calls to intrinsics by path are compiled into a dedicated instruction,
+    /// which invokes the intrinsic without going through the regular call
+    /// path. The intrinsic must pop exactly `args` values from the stack and
+    /// push a single return value. It can still be called like any other
+    /// function, like through a function pointer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{FromValue as _, Value};
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["math"]);
+    ///
+    /// module.intrinsic(&["mul_add"], 3, |stack| {
+    ///     let c = f64::from_value(stack.pop()?)?;
+    ///     let b = f64::from_value(stack.pop()?)?;
+    ///     let a = f64::from_value(stack.pop()?)?;
+    ///     stack.push(Value::Float(a.mul_add(b, c)));
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intrinsic<F, N>(&mut self, name: N, args: usize, f: F) -> Result<(), ContextError>
+    where
+        F: 'static + Fn(&mut Stack) -> Result<(), VmError> + Send + Sync,
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        let name = Item::of(name);
+
+        if self.functions.contains_key(&name) {
+            return Err(ContextError::ConflictingFunctionName { name });
+        }
+
+        let intrinsic: Arc<Intrinsic> = Arc::new(f);
+        let f = intrinsic.clone();
+
+        let handler: Arc<Handler> = Arc::new(move |cx: &mut CallContext<'_>, actual| {
+            if actual != args {
+                return Err(VmError::from(VmErrorKind::BadArgumentCount {
+                    actual,
+                    expected: args,
+                }));
+            }
+
+            f(cx.stack_mut())
+        });
+
+        self.functions.insert(
+            name,
+            ModuleFn {
+                handler,
+                args: Some(args),
+                capabilities: Vec::new(),
+                intrinsic: Some(intrinsic),
+                constant: false,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Mark the function with the given name as requiring a capability.
+    ///
+    /// Scripts can only call the function if the context they are linked and
+    /// run with grants the capability, see
+    /// [Context::grant_only][crate::Context::grant_only].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["fs"]);
+    /// module.function(&["remove"], |path: String| drop(path))?;
+    /// module.require_capability(&["remove"], "fs-write")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn require_capability<N>(&mut self, name: N, capability: &str) -> Result<(), ContextError>
+    where
+        N: IntoIterator,
+        N::Item: Into<Component>,
+    {
+        let name = Item::of(name);
+
+        let f = self
+            .functions
+            .get_mut(&name)
+            .ok_or_else(|| ContextError::MissingFunction { name })?;
+
+        f.capabilities.push(capability.to_owned());
+        Ok(())
+    }
+
     /// Register an instance function.
     ///
     /// # Examples
@@ -543,6 +802,43 @@ impl Module {
         self.assoc_fn(name, f, ModuleAssociatedKind::Instance)
     }
 
+    /// Register a raw instance function on the type `T`, which is passed the
+    /// [CallContext] it's called in.
+    ///
+    /// The instance is the first argument on the stack, and like with
+    /// [raw_fn_with_context][Module::raw_fn_with_context] the function can
+    /// call back into other functions, like closures passed to it.
+    pub fn raw_inst_fn_with_context<T, N, F>(&mut self, name: N, f: F) -> Result<(), ContextError>
+    where
+        T: ValueType,
+        N: IntoInstFnHash,
+        F: 'static + Fn(&mut CallContext<'_>, usize) -> Result<(), VmError> + Send + Sync,
+    {
+        let type_info = T::type_info();
+
+        let key = ModuleAssocKey {
+            value_type: T::value_type(),
+            hash: name.into_inst_fn_hash(),
+            kind: ModuleAssociatedKind::Instance,
+        };
+
+        let name = name.into_name();
+
+        if self.associated_functions.contains_key(&key) {
+            return Err(ContextError::ConflictingInstanceFunction { type_info, name });
+        }
+
+        let instance_function = ModuleAssociatedFn {
+            handler: Arc::new(f),
+            args: None,
+            type_info,
+            name,
+        };
+
+        self.associated_functions.insert(key, instance_function);
+        Ok(())
+    }
+
     /// Install a getter for the specified field.
     pub fn getter<N, Func, Args>(&mut self, name: N, f: Func) -> Result<(), ContextError>
     where
@@ -552,6 +848,89 @@ impl Module {
         self.assoc_fn(name, f, ModuleAssociatedKind::Getter)
     }
 
+    /// Register how the type `T` is destructured by object patterns, like
+    /// `HttpResponse { status, body }`.
+    ///
+    /// The function implements the [MATCH][crate::MATCH] protocol, returning
+    /// `Some` object with the given fields if the value matches. The fields are
+    /// used to check object patterns when they are compiled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Object;
+    ///
+    /// #[derive(Debug)]
+    /// struct HttpResponse {
+    ///     status: i64,
+    ///     body: String,
+    /// }
+    ///
+    /// runestick::impl_external!(HttpResponse);
+    ///
+    /// impl HttpResponse {
+    ///     fn matcher(&self) -> Option<Object<runestick::Value>> {
+    ///         let mut object = Object::new();
+    ///         object.insert(String::from("status"), self.status.into());
+    ///         object.insert(String::from("body"), self.body.clone().into());
+    ///         Some(object)
+    ///     }
+    /// }
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let mut module = runestick::Module::new(&["http"]);
+    ///
+    /// module.ty(&["HttpResponse"]).build::<HttpResponse>()?;
+    /// module.object_matcher(&["status", "body"], HttpResponse::matcher)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn object_matcher<Func, Args>(
+        &mut self,
+        fields: &[&str],
+        f: Func,
+    ) -> Result<(), ContextError>
+    where
+        Func: InstFn<Args>,
+    {
+        let fields = fields
+            .iter()
+            .map(|field| field.to_string())
+            .collect::<HashSet<_>>();
+
+        self.matcher(ContextMatcher::Object(fields), f)
+    }
+
+    /// Register how the type `T` is destructured by tuple patterns, like
+    /// `Point(x, y)`.
+    ///
+    /// The function implements the [MATCH][crate::MATCH] protocol, returning
+    /// `Some` tuple with the given number of fields if the value matches. The
+    /// number of fields is used to check tuple patterns when they are
+    /// compiled.
+    pub fn tuple_matcher<Func, Args>(&mut self, args: usize, f: Func) -> Result<(), ContextError>
+    where
+        Func: InstFn<Args>,
+    {
+        self.matcher(ContextMatcher::Tuple(args), f)
+    }
+
+    /// Install a matcher.
+    fn matcher<Func, Args>(&mut self, matcher: ContextMatcher, f: Func) -> Result<(), ContextError>
+    where
+        Func: InstFn<Args>,
+    {
+        let value_type = Func::instance_value_type();
+        let type_info = Func::instance_value_type_info();
+
+        // NB: conflicting matchers are detected as conflicting protocol
+        // functions.
+        self.assoc_fn(crate::MATCH, f, ModuleAssociatedKind::Instance)?;
+        self.matchers
+            .insert(value_type, ModuleMatcher { matcher, type_info });
+        Ok(())
+    }
+
     /// Install an associated function.
     fn assoc_fn<N, Func, Args>(
         &mut self,
@@ -578,7 +957,8 @@ impl Module {
             return Err(ContextError::ConflictingInstanceFunction { type_info, name });
         }
 
-        let handler: Arc<Handler> = Arc::new(move |stack, args| f.fn_call(stack, args));
+        let handler: Arc<Handler> =
+            Arc::new(move |cx: &mut CallContext<'_>, args| f.fn_call(cx.stack_mut(), args));
 
         let instance_function = ModuleAssociatedFn {
             handler,
@@ -593,9 +973,14 @@ impl Module {
 
     /// Register an instance function.
     ///
+    /// Like with [async_function][Module::async_function], the function
+    /// either returns a future or a boxed future. The instance can be taken
+    /// by mutable reference.
+    ///
     /// # Examples
     ///
     /// ```rust
+    /// use std::future::Future;
     /// use std::sync::atomic::AtomicU32;
     /// use std::sync::Arc;
     ///
@@ -604,12 +989,20 @@ impl Module {
     /// #[derive(Clone, Debug)]
     /// struct MyType {
     ///     value: Arc<AtomicU32>,
+    ///     count: u32,
     /// }
     ///
     /// impl MyType {
     ///     async fn test(&self) -> runestick::Result<()> {
     ///         Ok(())
     ///     }
+    ///
+    ///     fn increment(&mut self) -> Box<dyn Future<Output = u32> + '_> {
+    ///         Box::new(async move {
+    ///             self.count += 1;
+    ///             self.count
+    ///         })
+    ///     }
     /// }
     ///
     /// # fn main() -> runestick::Result<()> {
@@ -617,6 +1010,7 @@ impl Module {
     ///
     /// module.ty(&["MyType"]).build::<MyType>()?;
     /// module.async_inst_fn("test", MyType::test)?;
+    /// module.async_inst_fn("increment", MyType::increment)?;
     /// # Ok(())
     /// # }
     /// ```
@@ -640,7 +1034,8 @@ impl Module {
             return Err(ContextError::ConflictingInstanceFunction { type_info, name });
         }
 
-        let handler: Arc<Handler> = Arc::new(move |stack, args| f.fn_call(stack, args));
+        let handler: Arc<Handler> =
+            Arc::new(move |cx: &mut CallContext<'_>, args| f.fn_call(cx.stack_mut(), args));
 
         let instance_function = ModuleAssociatedFn {
             handler,
@@ -734,6 +1129,44 @@ pub trait AsyncFunction<Args>: 'static + Copy + Send + Sync {
     fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError>;
 }
 
+/// A boxed future, like `Box<dyn Future<Output = T>>`, which native async
+/// functions can return instead of a future.
+///
+/// See [async_function][Module::async_function] and
+/// [async_inst_fn][Module::async_inst_fn].
+pub trait BoxedFuture {
+    /// The output of the future.
+    type Output;
+    /// The pinned future.
+    type Future: future::Future<Output = Self::Output>;
+
+    /// Pin the boxed future so that it can be polled.
+    fn into_pinned(self) -> Self::Future;
+}
+
+impl<'a, O> BoxedFuture for Box<dyn future::Future<Output = O> + 'a> {
+    type Output = O;
+    type Future = Pin<Self>;
+
+    fn into_pinned(self) -> Self::Future {
+        Box::into_pin(self)
+    }
+}
+
+impl<'a, O> BoxedFuture for Box<dyn future::Future<Output = O> + Send + 'a> {
+    type Output = O;
+    type Future = Pin<Self>;
+
+    fn into_pinned(self) -> Self::Future {
+        Box::into_pin(self)
+    }
+}
+
+/// Marks the arguments of native async functions which return a
+/// [BoxedFuture], so that they can be registered through the same functions as
+/// the ones which return a future.
+pub struct Boxed<Args>(PhantomData<Args>);
+
 /// Trait used to provide the [inst_fn][Module::inst_fn] function.
 pub trait InstFn<Args>: 'static + Copy + Send + Sync {
     /// The type of the instance.
@@ -871,6 +1304,51 @@ macro_rules! impl_register {
             }
         }
 
+        impl<Func, Return, $($ty,)*> AsyncFunction<Boxed<($($ty,)*)>> for Func
+        where
+            Func: 'static + Copy + Send + Sync + Fn($($ty,)*) -> Return,
+            Return: BoxedFuture,
+            Return::Output: ToValue,
+            $($ty: 'static + UnsafeFromValue,)*
+        {
+            type Return = Return;
+
+            fn args() -> usize {
+                $count
+            }
+
+            fn fn_call(
+                self,
+                stack: &mut Stack,
+                args: usize
+            ) -> Result<(), VmError> {
+                impl_register!{@check-args $count, args}
+
+                #[allow(unused_mut)]
+                let mut it = stack.drain_stack_top($count)?;
+                $(let $var = it.next().unwrap();)*
+                drop(it);
+
+                // Safety: Future is owned and will only be called within the
+                // context of the virtual machine, which will provide
+                // exclusive thread-local access to itself while the future is
+                // being polled.
+                #[allow(unused_unsafe)]
+                let ret = unsafe {
+                    impl_register!{@unsafe-vars $count, $($ty, $var, $num,)*}
+
+                    Future::new(async move {
+                        let output = self($(<$ty>::to_arg($var.0),)*).into_pinned().await;
+                        let value = output.to_value()?;
+                        Ok(value)
+                    })
+                };
+
+                impl_register!{@return stack, ret, Return}
+                Ok(())
+            }
+        }
+
         impl<Func, Return, Instance, $($ty,)*> InstFn<(Instance, $($ty,)*)> for Func
         where
             Func: 'static + Copy + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
@@ -969,6 +1447,58 @@ macro_rules! impl_register {
                 Ok(())
             }
         }
+
+        impl<Func, Return, Instance, $($ty,)*> AsyncInstFn<Boxed<(Instance, $($ty,)*)>> for Func
+        where
+            Func: 'static + Copy + Send + Sync + Fn(Instance $(, $ty)*) -> Return,
+            Return: BoxedFuture,
+            Return::Output: ToValue,
+            Instance: UnsafeFromValue + ValueType,
+            $($ty: UnsafeFromValue,)*
+        {
+            type Instance = Instance;
+            type Return = Return;
+
+            fn args() -> usize {
+                $count + 1
+            }
+
+            fn instance_value_type() -> Type {
+                Instance::value_type()
+            }
+
+            fn instance_value_type_info() -> TypeInfo {
+                Instance::type_info()
+            }
+
+            fn fn_call(self, stack: &mut Stack, args: usize) -> Result<(), VmError> {
+                impl_register!{@check-args ($count + 1), args}
+
+                #[allow(unused_mut)]
+                let mut it = stack.drain_stack_top($count + 1)?;
+                let inst = it.next().unwrap();
+                $(let $var = it.next().unwrap();)*
+                drop(it);
+
+                // Safety: Future is owned and will only be called within the
+                // context of the virtual machine, which will provide
+                // exclusive thread-local access to itself while the future is
+                // being polled.
+                #[allow(unused)]
+                let ret = unsafe {
+                    impl_register!{@unsafe-inst-vars inst, $count, $($ty, $var, $num,)*}
+
+                    Future::new(async move {
+                        let output = self(Instance::to_arg(inst.0), $(<$ty>::to_arg($var.0),)*).into_pinned().await;
+                        let value = output.to_value()?;
+                        Ok(value)
+                    })
+                };
+
+                impl_register!{@return stack, ret, Return}
+                Ok(())
+            }
+        }
     };
 
     (@return $stack:ident, $ret:ident, $ty:ty) => {
@@ -0,0 +1,44 @@
+//! The `std::bitset` module.
+
+use crate::{BitSet, ContextError, Module};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::bitset` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "bitset"]);
+
+    module.ty(&["BitSet"]).build::<BitSet>()?;
+    module.function(&["BitSet", "new"], BitSet::new)?;
+    module.function(&["BitSet", "from_int"], BitSet::from_int)?;
+
+    module.inst_fn("insert", BitSet::insert)?;
+    module.inst_fn("remove", BitSet::remove)?;
+    module.inst_fn("contains", BitSet::contains)?;
+    module.inst_fn("contains_all", BitSet::contains_all)?;
+    module.inst_fn("intersects", BitSet::intersects)?;
+    module.inst_fn("len", BitSet::len)?;
+    module.inst_fn("is_empty", BitSet::is_empty)?;
+    module.inst_fn("clear", BitSet::clear)?;
+    module.inst_fn("to_int", BitSet::to_int)?;
+    module.inst_fn("to_vec", BitSet::to_vec)?;
+    module.inst_fn("clone", BitSet::clone)?;
+    module.inst_fn("union", BitSet::union)?;
+    module.inst_fn("intersection", BitSet::intersection)?;
+    module.inst_fn("symmetric_difference", BitSet::symmetric_difference)?;
+    module.inst_fn("difference", BitSet::difference)?;
+
+    module.inst_fn(crate::BIT_OR, BitSet::union)?;
+    module.inst_fn(crate::BIT_AND, BitSet::intersection)?;
+    module.inst_fn(crate::BIT_XOR, BitSet::symmetric_difference)?;
+    module.inst_fn(crate::BIT_OR_ASSIGN, BitSet::union_with)?;
+    module.inst_fn(crate::BIT_AND_ASSIGN, BitSet::intersect_with)?;
+    module.inst_fn(crate::BIT_XOR_ASSIGN, BitSet::symmetric_difference_with)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_bit_set)?;
+    module.inst_fn(crate::CLONE, BitSet::clone)?;
+    Ok(module)
+}
+
+fn format_bit_set(set: &BitSet, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", set)
+}
@@ -1,12 +1,13 @@
 //! `std::bytes` module.
 
-use crate::{Bytes, ContextError, Module};
+use crate::{Bytes, ContextError, HostBytes, Module};
 
 /// Construct the `std::bytes` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "bytes"]);
 
     module.ty(&["Bytes"]).build::<Bytes>()?;
+    module.ty(&["HostBytes"]).build::<HostBytes>()?;
     module.function(&["Bytes", "new"], Bytes::new)?;
     module.function(&["Bytes", "with_capacity"], Bytes::with_capacity)?;
     module.function(&["Bytes", "from_vec"], Bytes::from_vec)?;
@@ -24,5 +25,10 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("reserve_exact", Bytes::reserve_exact)?;
     module.inst_fn("clone", Bytes::clone)?;
     module.inst_fn("shrink_to_fit", Bytes::shrink_to_fit)?;
+
+    module.inst_fn("len", HostBytes::len)?;
+    module.inst_fn("is_empty", HostBytes::is_empty)?;
+    module.inst_fn("get", HostBytes::get)?;
+    module.inst_fn("to_bytes", HostBytes::to_bytes)?;
     Ok(module)
 }
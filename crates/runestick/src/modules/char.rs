@@ -0,0 +1,19 @@
+//! The `std::char` module.
+
+use crate::{ContextError, Module};
+use std::convert::TryFrom as _;
+
+/// Construct the `std::char` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std"]);
+    module.function(&["char", "from_int"], from_int)?;
+    Ok(module)
+}
+
+/// Convert a unicode code point to a character, like `char::from_int(65)`.
+///
+/// Returns `None` if the number isn't a valid code point.
+fn from_int(value: i64) -> Option<char> {
+    let value = u32::try_from(value).ok()?;
+    std::char::from_u32(value)
+}
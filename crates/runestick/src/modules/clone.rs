@@ -0,0 +1,20 @@
+//! The `std::clone` module.
+
+use crate::{ContextError, Module, Vm};
+
+/// Construct the `std::clone` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "clone"]);
+
+    // NB: deep cloning calls the `CLONE` protocol for external values, which
+    // requires access to the context of the caller.
+    module.raw_fn_with_context(&["deep"], |cx, args| {
+        Vm::check_args(args, 1)?;
+        let value = cx.stack_mut().pop()?;
+        let value = crate::deep::deep_clone(cx, &value)?;
+        cx.stack_mut().push(value);
+        Ok(())
+    })?;
+
+    Ok(module)
+}
@@ -1,11 +1,18 @@
 //! The core `std` module.
 
-use crate::{ContextError, Module, Panic, Stack, Value, VmError};
-use std::io;
-use std::io::Write as _;
+use crate::finalizer;
+use crate::{
+    ContextError, FromValue as _, Module, Output, Panic, PrettyLimits, Value, Vm, VmError,
+};
 
 /// Construct the `std` module.
 pub fn module() -> Result<Module, ContextError> {
+    module_with_output(Output::default())
+}
+
+/// Construct the `std` module, where functions like `print` and `dbg` write
+/// to the given output.
+pub fn module_with_output(output: Output) -> Result<Module, ContextError> {
     let mut module = Module::new(&["std"]);
 
     module.unit(&["unit"])?;
@@ -13,14 +20,89 @@ pub fn module() -> Result<Module, ContextError> {
     module.ty(&["char"]).build::<char>()?;
     module.ty(&["byte"]).build::<u8>()?;
 
-    module.function(&["print"], print_impl)?;
-    module.function(&["println"], println_impl)?;
+    let stdout = output.stdout().clone();
+
+    module.raw_fn(&["print"], move |stack, args| {
+        Vm::check_args(args, 1)?;
+        let m = String::from_value(stack.pop()?)?;
+        stdout.write_str(&m).map_err(VmError::panic)?;
+        stack.push(Value::Unit);
+        Ok(())
+    })?;
+
+    let stdout = output.stdout().clone();
+
+    module.raw_fn(&["println"], move |stack, args| {
+        Vm::check_args(args, 1)?;
+        let mut m = String::from_value(stack.pop()?)?;
+        m.push('\n');
+        stdout.write_str(&m).map_err(VmError::panic)?;
+        stack.push(Value::Unit);
+        Ok(())
+    })?;
+
     module.function(&["panic"], panic_impl)?;
-    module.raw_fn(&["dbg"], dbg_impl)?;
 
-    module.function(&["drop"], drop_impl)?;
+    let stdout = output.stdout().clone();
+
+    module.raw_fn(&["dbg"], move |stack, args| {
+        let mut out = String::new();
+
+        for _ in 0..args {
+            match stack.pop() {
+                Ok(value) => out.push_str(&value.display_pretty(PrettyLimits::default())),
+                Err(e) => out.push_str(&e.to_string()),
+            }
+
+            out.push('\n');
+        }
+
+        stdout.write_str(&out).map_err(VmError::panic)?;
+        stack.push(Value::Unit);
+        Ok(())
+    })?;
+
+    // NB: used by the `dbg!` macro, which passes in the location and text of
+    // the expression being debugged.
+    let stderr = output.stderr().clone();
+
+    module.raw_fn(&["dbg_expr"], move |stack, args| {
+        Vm::check_args(args, 2)?;
+        let value = stack.pop()?;
+        let expr = String::from_value(stack.pop()?)?;
+        let pretty = value.display_pretty(PrettyLimits::default());
+        let out = format!("{} = {}\n", expr, pretty);
+        stderr.write_str(&out).map_err(VmError::panic)?;
+        stack.push(value);
+        Ok(())
+    })?;
+
+    // NB: dropping a value with a finalizer runs the finalizer before the
+    // value is taken.
+    module.raw_fn_with_context(&["drop"], |cx, args| {
+        Vm::check_args(args, 1)?;
+        let value = cx.stack_mut().pop()?;
+
+        if let Some(hash) = finalizer::lookup(cx.unit(), &value) {
+            finalizer::finalize(
+                cx.context(),
+                cx.unit(),
+                cx.events(),
+                cx.statics(),
+                hash,
+                value.clone(),
+            )?;
+        }
+
+        drop_impl(value)?;
+        cx.stack_mut().push(Value::Unit);
+        Ok(())
+    })?;
     module.function(&["is_readable"], is_readable)?;
     module.function(&["is_writable"], is_writable)?;
+    module.function(&["freeze"], freeze)?;
+    module.function(&["freeze_deep"], freeze_deep)?;
+    module.function(&["is_frozen"], is_frozen)?;
     Ok(module)
 }
 
@@ -56,43 +138,15 @@ fn drop_impl(value: Value) -> Result<(), VmError> {
         Value::VariantObject(object) => {
             object.take()?;
         }
+        Value::Handle(handle) => {
+            handle.borrow_mut()?.close();
+        }
         _ => (),
     }
 
     Ok::<(), VmError>(())
 }
 
-fn dbg_impl(stack: &mut Stack, args: usize) -> Result<(), VmError> {
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-
-    for _ in 0..args {
-        match stack.pop() {
-            Ok(value) => {
-                writeln!(stdout, "{:?}", value).map_err(VmError::panic)?;
-            }
-            Err(e) => {
-                writeln!(stdout, "{}", e).map_err(VmError::panic)?;
-            }
-        }
-    }
-
-    stack.push(Value::Unit);
-    Ok(())
-}
-
-fn print_impl(m: &str) -> Result<(), Panic> {
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-    write!(stdout, "{}", m).map_err(Panic::custom)
-}
-
-fn println_impl(m: &str) -> Result<(), Panic> {
-    let stdout = io::stdout();
-    let mut stdout = stdout.lock();
-    writeln!(stdout, "{}", m).map_err(Panic::custom)
-}
-
 fn panic_impl(m: &str) -> Result<(), Panic> {
     Err(Panic::custom(m.to_owned()))
 }
@@ -109,6 +163,7 @@ fn is_readable(value: Value) -> bool {
         Value::Object(object) => object.is_readable(),
         Value::TypedObject(object) => object.is_readable(),
         Value::VariantObject(object) => object.is_readable(),
+        Value::Handle(handle) => handle.is_readable(),
         _ => true,
     }
 }
@@ -125,6 +180,21 @@ fn is_writable(value: Value) -> bool {
         Value::Object(object) => object.is_writable(),
         Value::TypedObject(object) => object.is_writable(),
         Value::VariantObject(object) => object.is_writable(),
+        Value::Handle(handle) => handle.is_writable(),
         _ => true,
     }
 }
+
+fn freeze(value: Value) -> Value {
+    value.freeze();
+    value
+}
+
+fn freeze_deep(value: Value) -> Result<Value, VmError> {
+    value.freeze_deep()?;
+    Ok(value)
+}
+
+fn is_frozen(value: Value) -> bool {
+    value.is_frozen()
+}
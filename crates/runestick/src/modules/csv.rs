@@ -0,0 +1,388 @@
+//! The `std::csv` module.
+//!
+//! ```rust,ignore
+//! use std::csv::Reader;
+//! use std::csv::Writer;
+//!
+//! fn main() {
+//!     let writer = Writer::with_headers(["name", "price"]);
+//!
+//!     for row in Reader::from_string("name,price,count\ntea,3,2\ncake,5,0\n") {
+//!         let row = row?;
+//!
+//!         if row.count != "0" {
+//!             writer.write(#{ name: row.name, price: row.price })?;
+//!         }
+//!     }
+//!
+//!     writer.into_string()
+//! }
+//! ```
+
+use crate::{ContextError, Module, Object, Shared, Value, VmError};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::csv` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "csv"]);
+
+    module.ty(&["Reader"]).build::<Reader>()?;
+    module.ty(&["Writer"]).build::<Writer>()?;
+    module.ty(&["CsvError"]).build::<CsvError>()?;
+
+    module.function(&["Reader", "from_string"], Reader::from_string)?;
+    module.inst_fn("without_headers", Reader::without_headers)?;
+    module.inst_fn("delimiter", Reader::delimiter)?;
+    module.inst_fn("next", Reader::next)?;
+    module.inst_fn(crate::NEXT, Reader::next)?;
+    module.inst_fn(crate::INTO_ITER, Reader::into_iter)?;
+
+    module.function(&["Writer", "new"], Writer::new)?;
+    module.function(&["Writer", "with_headers"], Writer::with_headers)?;
+    module.inst_fn("write", Writer::write)?;
+    module.inst_fn("write_all", Writer::write_all)?;
+    module.inst_fn("into_string", Writer::into_string)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, format_csv_error)?;
+    Ok(module)
+}
+
+/// Error raised when reading or writing CSV fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    kind: CsvErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum CsvErrorKind {
+    UnterminatedQuote {
+        line: usize,
+    },
+    FieldCount {
+        line: usize,
+        expected: usize,
+        actual: usize,
+    },
+    MissingHeaders,
+    UnsupportedValue(String),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            CsvErrorKind::UnterminatedQuote { line } => {
+                write!(f, "unterminated quoted field on line {}", line)
+            }
+            CsvErrorKind::FieldCount {
+                line,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "expected {} fields but got {} on line {}",
+                expected, actual, line
+            ),
+            CsvErrorKind::MissingHeaders => {
+                write!(f, "writing objects requires a writer with headers")
+            }
+            CsvErrorKind::UnsupportedValue(ty) => {
+                write!(f, "cannot write value of type `{}`", ty)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// A reader which lazily parses CSV records from a string.
+///
+/// By default the first record holds the headers, and every following record
+/// is produced as an object keyed by them. Without headers, records are
+/// produced as vectors of strings.
+#[derive(Debug)]
+pub struct Reader {
+    input: String,
+    pos: usize,
+    line: usize,
+    delimiter: char,
+    has_headers: bool,
+    headers: Option<Vec<String>>,
+}
+
+impl Reader {
+    /// Construct a reader over the given string.
+    pub fn from_string(input: &str) -> Self {
+        Self {
+            input: input.to_owned(),
+            pos: 0,
+            line: 1,
+            delimiter: ',',
+            has_headers: true,
+            headers: None,
+        }
+    }
+
+    /// Treat the first record as data instead of headers.
+    pub fn without_headers(mut self) -> Self {
+        self.has_headers = false;
+        self
+    }
+
+    /// Use a different delimiter than `,`.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Parse the next record, skipping over blank lines.
+    fn record(&mut self) -> Option<Result<(Vec<String>, usize), CsvError>> {
+        loop {
+            let rest = &self.input[self.pos..];
+
+            if rest.is_empty() {
+                return None;
+            }
+
+            if rest.starts_with('\n') || rest.starts_with("\r\n") {
+                self.pos += if rest.starts_with('\n') { 1 } else { 2 };
+                self.line += 1;
+                continue;
+            }
+
+            let line = self.line;
+
+            return Some(match parse_record(rest, self.delimiter) {
+                Some((record, consumed, lines)) => {
+                    self.pos += consumed;
+                    self.line += lines;
+                    Ok((record, line))
+                }
+                None => {
+                    self.pos = self.input.len();
+                    Err(CsvError {
+                        kind: CsvErrorKind::UnterminatedQuote { line },
+                    })
+                }
+            });
+        }
+    }
+}
+
+impl Iterator for Reader {
+    type Item = Result<Value, CsvError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.has_headers && self.headers.is_none() {
+            match self.record()? {
+                Ok((headers, _)) => self.headers = Some(headers),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        let (record, line) = match self.record()? {
+            Ok(record) => record,
+            Err(error) => return Some(Err(error)),
+        };
+
+        let headers = match &self.headers {
+            Some(headers) => headers,
+            None => {
+                let record = record.into_iter().map(Value::from).collect();
+                return Some(Ok(Value::vec(record)));
+            }
+        };
+
+        if record.len() != headers.len() {
+            return Some(Err(CsvError {
+                kind: CsvErrorKind::FieldCount {
+                    line,
+                    expected: headers.len(),
+                    actual: record.len(),
+                },
+            }));
+        }
+
+        let object = headers
+            .iter()
+            .cloned()
+            .zip(record.into_iter().map(Value::from))
+            .collect::<Object<Value>>();
+
+        Some(Ok(Value::from(Shared::new(object))))
+    }
+}
+
+/// Parse a single record, returning its fields, the number of bytes consumed
+/// including the line ending, and the number of lines consumed. Returns `None`
+/// if a quoted field isn't terminated.
+fn parse_record(input: &str, delimiter: char) -> Option<(Vec<String>, usize, usize)> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut lines = 0;
+    let mut it = input.char_indices().peekable();
+
+    while let Some((_, c)) = it.next() {
+        match c {
+            '"' if field.is_empty() => loop {
+                match it.next()? {
+                    (_, '"') if matches!(it.peek(), Some((_, '"'))) => {
+                        it.next();
+                        field.push('"');
+                    }
+                    (_, '"') => break,
+                    (_, c) => {
+                        if c == '\n' {
+                            lines += 1;
+                        }
+
+                        field.push(c);
+                    }
+                }
+            },
+            '\r' if matches!(it.peek(), Some((_, '\n'))) => (),
+            '\n' => {
+                fields.push(field);
+                let consumed = it.peek().map(|(n, _)| *n).unwrap_or(input.len());
+                return Some((fields, consumed, lines + 1));
+            }
+            c if c == delimiter => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+
+    fields.push(field);
+    Some((fields, input.len(), lines))
+}
+
+/// A writer which builds CSV from rows of vectors or objects.
+#[derive(Debug, Default)]
+pub struct Writer {
+    headers: Option<Vec<String>>,
+    out: String,
+}
+
+impl Writer {
+    /// Construct a writer without headers, which can only write vectors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a writer which starts with the given headers. Objects are
+    /// written with their fields in the order of the headers.
+    pub fn with_headers(headers: Vec<String>) -> Self {
+        let mut out = String::new();
+        write_record(&mut out, headers.iter().map(String::as_str));
+
+        Self {
+            headers: Some(headers),
+            out,
+        }
+    }
+
+    /// Write a row, which is either a vector or an object.
+    ///
+    /// Fields can be strings, numbers, booleans or characters, where unit and
+    /// `None` are written as empty fields and missing object fields are
+    /// written as empty fields.
+    pub fn write(&mut self, row: Value) -> Result<Result<(), CsvError>, VmError> {
+        let fields = match &row {
+            Value::Vec(vec) => vec.borrow_ref()?.clone(),
+            Value::Tuple(tuple) => tuple.borrow_ref()?.to_vec(),
+            Value::Object(object) => {
+                let headers = match &self.headers {
+                    Some(headers) => headers,
+                    None => {
+                        return Ok(Err(CsvError {
+                            kind: CsvErrorKind::MissingHeaders,
+                        }))
+                    }
+                };
+
+                let object = object.borrow_ref()?;
+
+                headers
+                    .iter()
+                    .map(|header| object.get(header).cloned().unwrap_or(Value::Unit))
+                    .collect()
+            }
+            row => return Err(VmError::bad_argument::<Vec<Value>>(0, row)?),
+        };
+
+        let mut record = Vec::with_capacity(fields.len());
+
+        for value in fields {
+            match field(&value)? {
+                Some(field) => record.push(field),
+                None => {
+                    return Ok(Err(CsvError {
+                        kind: CsvErrorKind::UnsupportedValue(value.type_info()?.to_string()),
+                    }))
+                }
+            }
+        }
+
+        write_record(&mut self.out, record.iter().map(String::as_str));
+        Ok(Ok(()))
+    }
+
+    /// Write every row in the given vector.
+    pub fn write_all(&mut self, rows: Vec<Value>) -> Result<Result<(), CsvError>, VmError> {
+        for row in rows {
+            if let Err(error) = self.write(row)? {
+                return Ok(Err(error));
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Convert the writer into the CSV written to it.
+    pub fn into_string(self) -> String {
+        self.out
+    }
+}
+
+/// Convert a value into the text of a field.
+fn field(value: &Value) -> Result<Option<String>, VmError> {
+    Ok(Some(match value {
+        Value::Unit => String::new(),
+        Value::String(string) => string.borrow_ref()?.clone(),
+        Value::StaticString(string) => string.as_str().to_owned(),
+        Value::Char(c) => c.to_string(),
+        Value::Integer(integer) => integer.to_string(),
+        Value::Float(float) => crate::modules::float::to_string(*float),
+        Value::Bool(b) => b.to_string(),
+        Value::Option(option) => match &*option.borrow_ref()? {
+            Some(value) => return field(value),
+            None => String::new(),
+        },
+        _ => return Ok(None),
+    }))
+}
+
+/// Write a record, quoting fields which need it.
+fn write_record<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (n, field) in fields.enumerate() {
+        if n > 0 {
+            out.push(',');
+        }
+
+        if field.contains(&[',', '"', '\n', '\r'][..]) {
+            // NB: writing to a string can't fail.
+            let _ = write!(out, "\"{}\"", field.replace('"', "\"\""));
+        } else {
+            out.push_str(field);
+        }
+    }
+
+    out.push('\n');
+}
+
+fn format_csv_error(error: &CsvError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+impl_external!(Reader);
+impl_external!(Writer);
+impl_external!(CsvError);
@@ -0,0 +1,135 @@
+//! The `std::decimal` module.
+
+use crate::{ContextError, Decimal, Module, Panic, ParseDecimalError, Value, VmError, VmErrorKind};
+use std::convert::TryFrom as _;
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::decimal` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "decimal"]);
+
+    module.ty(&["Decimal"]).build::<Decimal>()?;
+    module
+        .ty(&["ParseDecimalError"])
+        .build::<ParseDecimalError>()?;
+
+    module.function(&["Decimal", "parse"], parse)?;
+    module.function(&["Decimal", "from_int"], from_int)?;
+
+    module.inst_fn(crate::ADD, add)?;
+    module.inst_fn(crate::SUB, sub)?;
+    module.inst_fn(crate::MUL, mul)?;
+    module.inst_fn(crate::DIV, div)?;
+    module.inst_fn(crate::REM, rem)?;
+    module.inst_fn(crate::CMP, cmp)?;
+    module.inst_fn(crate::STRING_DISPLAY, display)?;
+    module.inst_fn(crate::CLONE, Decimal::clone)?;
+
+    module.inst_fn("round", round)?;
+    module.inst_fn("scale", scale)?;
+    module.inst_fn("to_float", to_float)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, format_parse_error)?;
+    Ok(module)
+}
+
+/// Parse a decimal.
+fn parse(s: &str) -> Result<Decimal, ParseDecimalError> {
+    s.parse()
+}
+
+/// Convert an integer into a decimal.
+fn from_int(integer: i64) -> Decimal {
+    Decimal::from(integer)
+}
+
+/// Convert the right hand side of an operation into a decimal, which can be
+/// either a decimal or an integer.
+fn operand(value: Value) -> Result<Decimal, VmError> {
+    match value {
+        Value::Integer(integer) => Ok(Decimal::from(integer)),
+        Value::Any(any) => {
+            if let Ok(decimal) = any.downcast_borrow_ref::<Decimal>() {
+                return Ok(*decimal);
+            }
+
+            Err(VmError::expected::<Decimal>(Value::Any(any).type_info()?))
+        }
+        actual => Err(VmError::expected::<Decimal>(actual.type_info()?)),
+    }
+}
+
+fn add(a: &Decimal, b: Value) -> Result<Decimal, VmError> {
+    a.checked_add(operand(b)?)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn sub(a: &Decimal, b: Value) -> Result<Decimal, VmError> {
+    a.checked_sub(operand(b)?)
+        .ok_or_else(|| VmError::from(VmErrorKind::Underflow))
+}
+
+fn mul(a: &Decimal, b: Value) -> Result<Decimal, VmError> {
+    a.checked_mul(operand(b)?)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn div(a: &Decimal, b: Value) -> Result<Decimal, VmError> {
+    let b = operand(b)?;
+
+    if b.is_zero() {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    a.checked_div(b)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn rem(a: &Decimal, b: Value) -> Result<Decimal, VmError> {
+    let b = operand(b)?;
+
+    if b.is_zero() {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    a.checked_rem(b)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn cmp(a: &Decimal, b: Value) -> Result<i64, VmError> {
+    Ok(a.cmp(&operand(b)?) as i64)
+}
+
+fn display(decimal: &Decimal, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", decimal)
+}
+
+/// Round a decimal to the given number of digits after the decimal point,
+/// rounding half away from zero.
+fn round(decimal: &Decimal, scale: i64) -> Result<Decimal, Panic> {
+    match u32::try_from(scale) {
+        Ok(scale) => Ok(decimal.round(scale)),
+        Err(..) => Err(Panic::custom(format!(
+            "scale must not be negative, but got `{}`",
+            scale
+        ))),
+    }
+}
+
+/// Convert a decimal into the closest float.
+fn to_float(decimal: &Decimal) -> f64 {
+    decimal.to_f64()
+}
+
+/// The number of digits after the decimal point.
+fn scale(decimal: &Decimal) -> i64 {
+    i64::from(decimal.scale())
+}
+
+fn format_parse_error(error: &ParseDecimalError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+impl_external!(Decimal);
+impl_external!(ParseDecimalError);
@@ -0,0 +1,42 @@
+//! The `std::dynamic` module.
+
+use crate::{ContextError, FromValue, Module, Stack, ToValue, UnitRegistry, Vm};
+
+/// Construct the `std::dynamic` module, which calls functions in the units of
+/// the given registry.
+///
+/// Unlike most of the standard modules, this module isn't installed by
+/// default since it needs a registry to look units up in.
+pub fn module(registry: &UnitRegistry) -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "dynamic"]);
+
+    let units = registry.clone();
+
+    module.raw_fn(&["call"], move |stack: &mut Stack, args: usize| {
+        Vm::check_args(args, 3)?;
+        let args = Vec::from_value(stack.pop()?)?;
+        let function = String::from_value(stack.pop()?)?;
+        let unit = String::from_value(stack.pop()?)?;
+        stack.push(units.call(&unit, &function, args)?);
+        Ok(())
+    })?;
+
+    let units = registry.clone();
+
+    module.raw_fn(&["has"], move |stack: &mut Stack, args: usize| {
+        Vm::check_args(args, 1)?;
+        let unit = String::from_value(stack.pop()?)?;
+        stack.push(units.contains(&unit));
+        Ok(())
+    })?;
+
+    let units = registry.clone();
+
+    module.raw_fn(&["units"], move |stack: &mut Stack, args: usize| {
+        Vm::check_args(args, 0)?;
+        stack.push(units.names().to_value()?);
+        Ok(())
+    })?;
+
+    Ok(module)
+}
@@ -0,0 +1,253 @@
+//! The `std::encoding` module.
+
+use crate::{Bytes, ContextError, Module, Value, VmError};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::encoding` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "encoding"]);
+
+    module.ty(&["DecodeError"]).build::<DecodeError>()?;
+
+    module.function(&["base64", "encode"], base64_encode)?;
+    module.function(&["base64", "decode"], base64_decode)?;
+    module.function(&["hex", "encode"], hex_encode)?;
+    module.function(&["hex", "decode"], hex_decode)?;
+    module.function(&["url", "encode_component"], url_encode_component)?;
+    module.function(&["url", "decode"], url_decode)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, format_decode_error)?;
+    Ok(module)
+}
+
+/// Error raised when decoding invalid input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    kind: DecodeErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DecodeErrorKind {
+    InvalidByte { encoding: &'static str, at: usize },
+    InvalidLength { encoding: &'static str },
+    InvalidUtf8,
+}
+
+impl DecodeError {
+    fn invalid_byte(encoding: &'static str, at: usize) -> Self {
+        Self {
+            kind: DecodeErrorKind::InvalidByte { encoding, at },
+        }
+    }
+
+    fn invalid_length(encoding: &'static str) -> Self {
+        Self {
+            kind: DecodeErrorKind::InvalidLength { encoding },
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            DecodeErrorKind::InvalidByte { encoding, at } => {
+                write!(f, "invalid {} at byte {}", encoding, at)
+            }
+            DecodeErrorKind::InvalidLength { encoding } => {
+                write!(f, "invalid {} length", encoding)
+            }
+            DecodeErrorKind::InvalidUtf8 => write!(f, "decoded data is not valid UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// The bytes of the input to encode or decode, which can be either a string
+/// or bytes.
+fn input(value: Value) -> Result<Vec<u8>, VmError> {
+    Ok(match value {
+        Value::String(string) => string.borrow_ref()?.as_bytes().to_vec(),
+        Value::StaticString(string) => string.as_str().as_bytes().to_vec(),
+        Value::Bytes(bytes) => bytes.borrow_ref()?.to_vec(),
+        actual => return Err(VmError::bad_argument::<String>(0, &actual)?),
+    })
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode a string or bytes as base64, with padding.
+fn base64_encode(value: Value) -> Result<String, VmError> {
+    let input = input(value)?;
+    let mut out = String::with_capacity(input.len() / 3 * 4 + 4);
+
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or_default(),
+            chunk.get(2).copied().unwrap_or_default(),
+        ];
+
+        let n = u32::from(b[0]) << 16 | u32::from(b[1]) << 8 | u32::from(b[2]);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode base64 into bytes, where padding is optional.
+fn base64_decode(value: Value) -> Result<Result<Bytes, DecodeError>, VmError> {
+    let input = input(value)?;
+
+    let data = match input.iter().position(|b| *b == b'=') {
+        Some(n) => {
+            if input.len() % 4 != 0 || input.len() - n > 2 {
+                return Ok(Err(DecodeError::invalid_length("base64")));
+            }
+
+            if let Some(at) = input[n..].iter().position(|b| *b != b'=') {
+                return Ok(Err(DecodeError::invalid_byte("base64", n + at)));
+            }
+
+            &input[..n]
+        }
+        None => &input[..],
+    };
+
+    if data.len() % 4 == 1 {
+        return Ok(Err(DecodeError::invalid_length("base64")));
+    }
+
+    let mut out = Vec::with_capacity(data.len() / 4 * 3 + 2);
+
+    for (c, chunk) in data.chunks(4).enumerate() {
+        let mut n = 0u32;
+
+        for (i, b) in chunk.iter().enumerate() {
+            let digit = match BASE64.iter().position(|c| c == b) {
+                Some(digit) => digit as u32,
+                None => return Ok(Err(DecodeError::invalid_byte("base64", c * 4 + i))),
+            };
+
+            n |= digit << (18 - 6 * i);
+        }
+
+        let bytes = [(n >> 16) as u8, (n >> 8) as u8, n as u8];
+        out.extend_from_slice(&bytes[..chunk.len() - 1]);
+    }
+
+    Ok(Ok(Bytes::from_vec(out)))
+}
+
+/// Encode a string or bytes as lowercase hex.
+fn hex_encode(value: Value) -> Result<String, VmError> {
+    let input = input(value)?;
+    let mut out = String::with_capacity(input.len() * 2);
+
+    for b in input {
+        // NB: writing to a string can't fail.
+        let _ = write!(out, "{:02x}", b);
+    }
+
+    Ok(out)
+}
+
+/// Decode hex into bytes, where digits can be either upper or lowercase.
+fn hex_decode(value: Value) -> Result<Result<Bytes, DecodeError>, VmError> {
+    let input = input(value)?;
+
+    if input.len() % 2 != 0 {
+        return Ok(Err(DecodeError::invalid_length("hex")));
+    }
+
+    let mut out = Vec::with_capacity(input.len() / 2);
+
+    for (at, pair) in input.chunks(2).enumerate() {
+        let hi = hex_digit(pair[0]);
+        let lo = hex_digit(pair[1]);
+
+        match (hi, lo) {
+            (Some(hi), Some(lo)) => out.push(hi << 4 | lo),
+            (None, _) => return Ok(Err(DecodeError::invalid_byte("hex", at * 2))),
+            (_, None) => return Ok(Err(DecodeError::invalid_byte("hex", at * 2 + 1))),
+        }
+    }
+
+    Ok(Ok(Bytes::from_vec(out)))
+}
+
+fn hex_digit(b: u8) -> Option<u8> {
+    match b {
+        b'0'..=b'9' => Some(b - b'0'),
+        b'a'..=b'f' => Some(b - b'a' + 10),
+        b'A'..=b'F' => Some(b - b'A' + 10),
+        _ => None,
+    }
+}
+
+/// Percent-encode a string or bytes so that it can be used as a component of
+/// a URL, like a path segment or a query parameter.
+///
+/// Only the unreserved characters `A-Z`, `a-z`, `0-9`, `-`, `_`, `.` and `~`
+/// are kept as they are.
+fn url_encode_component(value: Value) -> Result<String, VmError> {
+    let input = input(value)?;
+    let mut out = String::with_capacity(input.len());
+
+    for b in input {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(b as char)
+            }
+            b => {
+                // NB: writing to a string can't fail.
+                let _ = write!(out, "%{:02X}", b);
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decode a percent-encoded string.
+fn url_decode(value: Value) -> Result<Result<String, DecodeError>, VmError> {
+    let input = input(value)?;
+    let mut out = Vec::with_capacity(input.len());
+    let mut at = 0;
+
+    while at < input.len() {
+        if input[at] != b'%' {
+            out.push(input[at]);
+            at += 1;
+            continue;
+        }
+
+        let hi = input.get(at + 1).copied().and_then(hex_digit);
+        let lo = input.get(at + 2).copied().and_then(hex_digit);
+
+        match (hi, lo) {
+            (Some(hi), Some(lo)) => out.push(hi << 4 | lo),
+            _ => return Ok(Err(DecodeError::invalid_byte("percent-encoding", at))),
+        }
+
+        at += 3;
+    }
+
+    Ok(String::from_utf8(out).map_err(|_| DecodeError {
+        kind: DecodeErrorKind::InvalidUtf8,
+    }))
+}
+
+fn format_decode_error(error: &DecodeError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+impl_external!(DecodeError);
@@ -0,0 +1,87 @@
+//! The `std::error` module.
+
+use crate::{
+    CallContext, ContextError, FromValue as _, Function, Module, Shared, ToValue as _, Value, Vm,
+    VmError, VmErrorKind,
+};
+use std::fmt;
+
+/// Construct the `std::error` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "error"]);
+    module.ty(&["Error"]).build::<Error>()?;
+
+    module.raw_fn_with_context(&["catch"], |cx, args| {
+        Vm::check_args(args, 1)?;
+        let f = Shared::<Function>::from_value(cx.stack_mut().pop()?)?;
+        let result = catch(cx, &*f.borrow_ref()?)?;
+        cx.stack_mut().push(result.to_value()?);
+        Ok(())
+    })?;
+
+    module.raw_fn_with_context(&["context"], |cx, args| {
+        Vm::check_args(args, 2)?;
+        let f = Shared::<Function>::from_value(cx.stack_mut().pop()?)?;
+        let context = String::from_value(cx.stack_mut().pop()?)?;
+        let value = f
+            .borrow_ref()?
+            .call_vec_with_events(Vec::new(), cx.events(), cx.statics())
+            .map_err(|error| error.with_context(context))?;
+        cx.stack_mut().push(value);
+        Ok(())
+    })?;
+
+    module.inst_fn("chain", Error::chain)?;
+    module.inst_fn(crate::STRING_DISPLAY, Error::display)?;
+    Ok(module)
+}
+
+/// An error raised by the virtual machine, which was caught with
+/// `std::error::catch`.
+#[derive(Debug)]
+struct Error {
+    error: VmError,
+}
+
+impl Error {
+    /// The messages of the errors in the chain of errors.
+    fn chain(&self) -> Vec<String> {
+        self.error.chain().map(|e| e.to_string()).collect()
+    }
+
+    fn display(&self, buf: &mut String) -> fmt::Result {
+        use std::fmt::Write as _;
+
+        if let Some(error) = self.error.chain().next() {
+            write!(buf, "{}", error)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl_external!(Error);
+
+/// Convert an error raised by the virtual machine into a value, as if it had
+/// been caught with `std::error::catch`.
+pub(crate) fn caught(error: VmError) -> Result<Value, VmError> {
+    Error { error }.to_value()
+}
+
+/// Call the given function, catching any error it raises which isn't a panic.
+fn catch(cx: &CallContext<'_>, f: &Function) -> Result<Result<Value, Error>, VmError> {
+    match f.call_vec_with_events(Vec::new(), cx.events(), cx.statics()) {
+        Ok(value) => Ok(Ok(value)),
+        Err(error) => {
+            let panicked = error
+                .chain()
+                .any(|e| matches!(e, VmErrorKind::Panic { .. }));
+
+            if panicked {
+                return Err(error);
+            }
+
+            Ok(Err(Error { error }))
+        }
+    }
+}
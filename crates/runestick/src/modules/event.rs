@@ -0,0 +1,27 @@
+//! The `std::event` module.
+
+use crate::{ContextError, FromValue as _, Function, Module, Shared, Value, Vm};
+
+/// Construct the `std::event` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "event"]);
+
+    module.raw_fn_with_context(&["on"], |cx, args| {
+        Vm::check_args(args, 2)?;
+        let handler = Shared::<Function>::from_value(cx.stack_mut().pop()?)?;
+        let name = String::from_value(cx.stack_mut().pop()?)?;
+        cx.events().on(&name, handler)?;
+        cx.stack_mut().push(Value::Unit);
+        Ok(())
+    })?;
+
+    module.raw_fn_with_context(&["off"], |cx, args| {
+        Vm::check_args(args, 1)?;
+        let name = String::from_value(cx.stack_mut().pop()?)?;
+        let removed = cx.events().off(&name)?;
+        cx.stack_mut().push(Value::Bool(removed));
+        Ok(())
+    })?;
+
+    Ok(module)
+}
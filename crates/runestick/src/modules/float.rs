@@ -1,13 +1,70 @@
 //! The `std::float` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, Module, Panic};
+use std::convert::TryFrom as _;
+use std::fmt;
+use std::fmt::Write as _;
 use std::num::ParseFloatError;
 
-/// Parse an integer.
+/// Parse a float.
 fn parse(s: &str) -> Result<f64, ParseFloatError> {
     Ok(str::parse::<f64>(s)?)
 }
 
+fn format_parse_float_error(error: &ParseFloatError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+/// Write the shortest representation of a float which parses back into the
+/// same float, like `0.1` or `1e21`.
+///
+/// This is how floats are displayed by default, since it doesn't depend on the
+/// platform.
+pub(crate) fn format_shortest(value: f64, buf: &mut String) {
+    let mut buffer = ryu::Buffer::new();
+    buf.push_str(buffer.format(value));
+}
+
+/// Convert a float into its shortest round-trippable representation.
+pub(crate) fn to_string(value: f64) -> String {
+    let mut buf = String::new();
+    format_shortest(value, &mut buf);
+    buf
+}
+
+/// Format a float with the given number of digits after the decimal point,
+/// either in `"fixed"` notation like `1234.50` or in `"scientific"` notation
+/// like `1.23e3`.
+fn format(value: f64, precision: i64, mode: &str) -> Result<String, Panic> {
+    let precision = match usize::try_from(precision) {
+        Ok(precision) => precision,
+        Err(..) => {
+            return Err(Panic::custom(format!(
+                "precision must not be negative, but got `{}`",
+                precision
+            )))
+        }
+    };
+
+    // NB: the formatter panics on precisions that do not fit in a `u16`.
+    if precision > u16::MAX as usize {
+        return Err(Panic::custom(format!(
+            "precision must be at most `{}`, but got `{}`",
+            u16::MAX,
+            precision
+        )));
+    }
+
+    match mode {
+        "fixed" => Ok(format!("{:.*}", precision, value)),
+        "scientific" => Ok(format!("{:.*e}", precision, value)),
+        mode => Err(Panic::custom(format!(
+            "unsupported format mode `{}`, expected `fixed` or `scientific`",
+            mode
+        ))),
+    }
+}
+
 /// Convert a float to a whole number.
 fn to_integer(value: f64) -> i64 {
     value as i64
@@ -24,7 +81,10 @@ pub fn module() -> Result<Module, ContextError> {
         .ty(&["float", "ParseFloatError"])
         .build::<ParseFloatError>()?;
     module.function(&["float", "parse"], parse)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_parse_float_error)?;
     module.inst_fn("to_integer", to_integer)?;
+    module.inst_fn("to_string", to_string)?;
+    module.inst_fn("format", format)?;
 
     Ok(module)
 }
@@ -1,14 +1,28 @@
 //! The `std::generator` module.
 
-use crate::{ContextError, Generator, Module};
+use crate::{ContextError, Generator, GeneratorStatus, Module};
+use std::fmt;
+use std::fmt::Write as _;
 
 /// Construct the `std::generator` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "generator"]);
     module.ty(&["Generator"]).build::<Generator>()?;
     module.generator_state(&["GeneratorState"])?;
+    module.ty(&["GeneratorStatus"]).build::<GeneratorStatus>()?;
 
     module.inst_fn("next", Generator::next)?;
     module.inst_fn("resume", Generator::resume)?;
+    module.inst_fn("state", Generator::state)?;
+    module.inst_fn("dispose", Generator::dispose)?;
+
+    module.inst_fn("is_created", GeneratorStatus::is_created)?;
+    module.inst_fn("is_suspended", GeneratorStatus::is_suspended)?;
+    module.inst_fn("is_completed", GeneratorStatus::is_completed)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_status)?;
     Ok(module)
 }
+
+fn format_status(status: &GeneratorStatus, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", status)
+}
@@ -0,0 +1,16 @@
+//! The `std::hash` module.
+
+use crate::deep::DeepHash;
+use crate::{ContextError, Module, Value, VmError};
+
+/// Construct the `std::hash` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "hash"]);
+    module.function(&["hash"], hash)?;
+    Ok(module)
+}
+
+/// Calculate the structural hash of the given value.
+fn hash(value: Value) -> Result<i64, VmError> {
+    Ok(DeepHash::new().hash_value(&value)? as i64)
+}
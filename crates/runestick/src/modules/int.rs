@@ -1,6 +1,8 @@
 //! The `std::int` module.
 
-use crate::{ContextError, Module};
+use crate::{ContextError, FromValue as _, Module, Stack, ToValue as _, Vm, VmError};
+use std::fmt;
+use std::fmt::Write as _;
 use std::num::ParseIntError;
 
 /// Construct the `std::int` module.
@@ -11,7 +13,22 @@ pub fn module() -> Result<Module, ContextError> {
     module
         .ty(&["int", "ParseIntError"])
         .build::<ParseIntError>()?;
-    module.function(&["int", "parse"], parse)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_parse_int_error)?;
+
+    // NB: the radix is optional, and defaults to 10.
+    module.raw_fn(&["int", "parse"], |stack, args| {
+        let radix = optional_radix(stack, args, 1)?;
+        let s = String::from_value(stack.pop()?)?;
+        stack.push(parse(&s, radix).to_value()?);
+        Ok(())
+    })?;
+
+    module.raw_inst_fn_with_context::<i64, _, _>("to_string", |cx, args| {
+        let radix = optional_radix(cx.stack_mut(), args, 1)?;
+        let value = i64::from_value(cx.stack_mut().pop()?)?;
+        cx.stack_mut().push(to_string(value, radix));
+        Ok(())
+    })?;
 
     module.inst_fn("to_float", to_float)?;
 
@@ -20,12 +37,18 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("checked_div", i64::checked_div)?;
     module.inst_fn("checked_mul", i64::checked_mul)?;
     module.inst_fn("checked_rem", i64::checked_rem)?;
+    module.inst_fn("checked_neg", i64::checked_neg)?;
+    module.inst_fn("checked_abs", i64::checked_abs)?;
+    module.inst_fn("checked_pow", i64::checked_pow)?;
 
     module.inst_fn("wrapping_add", i64::wrapping_add)?;
     module.inst_fn("wrapping_sub", i64::wrapping_sub)?;
     module.inst_fn("wrapping_div", i64::wrapping_div)?;
     module.inst_fn("wrapping_mul", i64::wrapping_mul)?;
     module.inst_fn("wrapping_rem", i64::wrapping_rem)?;
+    module.inst_fn("wrapping_neg", i64::wrapping_neg)?;
+    module.inst_fn("wrapping_abs", i64::wrapping_abs)?;
+    module.inst_fn("wrapping_pow", i64::wrapping_pow)?;
 
     module.inst_fn("saturating_add", i64::saturating_add)?;
     module.inst_fn("saturating_sub", i64::saturating_sub)?;
@@ -37,9 +60,59 @@ pub fn module() -> Result<Module, ContextError> {
     Ok(module)
 }
 
-/// Parse an integer.
-fn parse(s: &str) -> Result<i64, ParseIntError> {
-    Ok(str::parse::<i64>(s)?)
+/// Pop the radix argument following `required` arguments off the stack, which
+/// defaults to 10 if it isn't provided.
+fn optional_radix(stack: &mut Stack, args: usize, required: usize) -> Result<u32, VmError> {
+    if args == required {
+        return Ok(10);
+    }
+
+    Vm::check_args(args, required + 1)?;
+    let radix = i64::from_value(stack.pop()?)?;
+
+    match radix {
+        2..=36 => Ok(radix as u32),
+        _ => Err(VmError::panic(format!(
+            "radix `{}` is not in the range 2 to 36",
+            radix
+        ))),
+    }
+}
+
+/// Parse an integer in the given radix, like `int::parse("ff", 16)`.
+fn parse(s: &str, radix: u32) -> Result<i64, ParseIntError> {
+    i64::from_str_radix(s, radix)
+}
+
+/// Format an integer in the given radix, like `255.to_string(16)`.
+///
+/// Digits above 9 are lowercase letters, and negative numbers are prefixed
+/// with a `-`.
+fn to_string(value: i64, radix: u32) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let radix = u64::from(radix);
+    let mut digits = Vec::new();
+    let mut n = value.unsigned_abs();
+
+    loop {
+        digits.push(char::from(DIGITS[(n % radix) as usize]));
+        n /= radix;
+
+        if n == 0 {
+            break;
+        }
+    }
+
+    if value < 0 {
+        digits.push('-');
+    }
+
+    digits.iter().rev().collect()
+}
+
+fn format_parse_int_error(error: &ParseIntError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
 }
 
 /// Convert a whole number to float.
@@ -36,13 +36,13 @@ impl Iterator for Rev {
 }
 
 #[derive(Debug)]
-struct Range {
+pub(crate) struct Range {
     current: i64,
     end: i64,
 }
 
 impl Range {
-    fn new(start: i64, end: i64) -> Self {
+    pub(crate) fn new(start: i64, end: i64) -> Self {
         Self {
             current: start,
             end,
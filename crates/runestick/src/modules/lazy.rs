@@ -0,0 +1,105 @@
+//! The `std::lazy` module.
+//!
+//! ```rust,ignore
+//! use std::lazy::Lazy;
+//!
+//! let CONFIG = Lazy::new(|| load_config());
+//!
+//! fn main() {
+//!     let config = CONFIG.get();
+//! }
+//! ```
+
+use crate::{
+    ContextError, FromValue as _, Function, Module, Shared, Value, Vm, VmError, VmErrorKind,
+};
+
+/// Construct the `std::lazy` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "lazy"]);
+
+    module.ty(&["Lazy"]).build::<Lazy>()?;
+    module.function(&["Lazy", "new"], Lazy::new)?;
+    module.inst_fn("is_initialized", Lazy::is_initialized)?;
+
+    // NB: the initializer is called back into on first access, which requires
+    // access to the context of the caller.
+    module.raw_inst_fn_with_context::<Lazy, _, _>("get", |cx, args| {
+        Vm::check_args(args, 1)?;
+        let lazy = cx.stack_mut().pop()?.into_any()?;
+
+        let init = {
+            let mut lazy = lazy.downcast_borrow_mut::<Lazy>()?;
+
+            match std::mem::replace(&mut lazy.state, State::Initializing) {
+                State::Initialized(value) => {
+                    lazy.state = State::Initialized(value.clone());
+                    cx.stack_mut().push(value);
+                    return Ok(());
+                }
+                State::Initializing => {
+                    return Err(VmError::from(VmErrorKind::LazyInitializing));
+                }
+                State::Uninitialized(init) => init,
+            }
+        };
+
+        // NB: the lazy value is not borrowed while the initializer runs, so
+        // that accessing it again is reported as re-entrancy instead of an
+        // access error.
+        let result = cx.call_function::<_, Value>(&*init.borrow_ref()?, ());
+        let mut lazy = lazy.downcast_borrow_mut::<Lazy>()?;
+
+        match result {
+            Ok(value) => {
+                lazy.state = State::Initialized(value.clone());
+                cx.stack_mut().push(value);
+                Ok(())
+            }
+            Err(error) => {
+                // A failed initializer is retried on the next access.
+                lazy.state = State::Uninitialized(init);
+                Err(error)
+            }
+        }
+    })?;
+
+    Ok(module)
+}
+
+/// A value which is computed by calling an initializer the first time it's
+/// accessed.
+///
+/// Values aren't shared between threads, so a lazy value is initialized at
+/// most once by whichever virtual machine accesses it first. Declared as a
+/// static it's initialized once for every virtual machine.
+#[derive(Debug)]
+pub struct Lazy {
+    state: State,
+}
+
+#[derive(Debug)]
+enum State {
+    /// The value hasn't been accessed yet.
+    Uninitialized(Shared<Function>),
+    /// The initializer is running.
+    Initializing,
+    /// The value has been computed.
+    Initialized(Value),
+}
+
+impl Lazy {
+    /// Construct a lazy value which is computed by calling `init`.
+    fn new(init: Value) -> Result<Self, VmError> {
+        Ok(Self {
+            state: State::Uninitialized(Shared::<Function>::from_value(init)?),
+        })
+    }
+
+    /// Test if the value has been computed.
+    fn is_initialized(&self) -> bool {
+        matches!(self.state, State::Initialized(..))
+    }
+}
+
+impl_external!(Lazy);
@@ -0,0 +1,40 @@
+//! The `std::math` module.
+
+use crate::{ContextError, Module, Panic};
+use std::convert::TryFrom as _;
+use std::f64::consts;
+
+/// Construct the `std::math` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "math"]);
+
+    module.constant(&["PI"], consts::PI)?;
+    module.constant(&["E"], consts::E)?;
+    module.constant(&["TAU"], 2.0 * consts::PI)?;
+
+    module.function(&["sin"], f64::sin)?;
+    module.function(&["cos"], f64::cos)?;
+    module.function(&["tan"], f64::tan)?;
+    module.function(&["atan2"], f64::atan2)?;
+
+    module.function(&["ln"], f64::ln)?;
+    module.function(&["log10"], f64::log10)?;
+    module.function(&["exp"], f64::exp)?;
+
+    module.function(&["sqrt"], f64::sqrt)?;
+    module.function(&["cbrt"], f64::cbrt)?;
+
+    module.function(&["format"], format)?;
+    Ok(module)
+}
+
+/// Format a float with the given number of digits after the decimal point.
+fn format(value: f64, precision: i64) -> Result<String, Panic> {
+    match usize::try_from(precision) {
+        Ok(precision) => Ok(format!("{:.*}", precision, value)),
+        Err(..) => Err(Panic::custom(format!(
+            "precision must not be negative, but got `{}`",
+            precision
+        ))),
+    }
+}
@@ -1,19 +1,37 @@
 //! Public packages that can be used to provide functionality to virtual
 //! machines.
 
+pub mod bitset;
 pub mod bytes;
+pub mod char;
+pub mod clone;
 pub mod core;
+pub mod csv;
+pub mod decimal;
+pub mod dynamic;
+pub mod encoding;
+pub mod error;
+pub mod event;
 pub mod float;
 pub mod fmt;
 pub mod future;
 pub mod generator;
+pub mod hash;
 pub mod int;
 pub mod io;
 pub mod iter;
+pub mod lazy;
+pub mod math;
+pub mod nd;
 pub mod object;
+pub mod ops;
 pub mod option;
+pub mod path;
+pub mod proptest;
 pub mod result;
 pub mod stream;
 pub mod string;
+pub mod template;
 pub mod test;
+pub mod time;
 pub mod vec;
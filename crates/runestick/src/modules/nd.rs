@@ -0,0 +1,45 @@
+//! The `std::nd` module.
+
+use crate::{ContextError, FloatArray, Module};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::nd` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "nd"]);
+
+    module.ty(&["FloatArray"]).build::<FloatArray>()?;
+    module.function(&["FloatArray", "new"], FloatArray::new)?;
+    module.function(&["FloatArray", "from_vec"], FloatArray::from_vec)?;
+    module.function(&["FloatArray", "filled"], FloatArray::filled)?;
+    module.function(&["FloatArray", "zeros"], FloatArray::zeros)?;
+
+    module.inst_fn("len", FloatArray::len)?;
+    module.inst_fn("is_empty", FloatArray::is_empty)?;
+    module.inst_fn("get", FloatArray::get)?;
+    module.inst_fn("set", FloatArray::set)?;
+    module.inst_fn("push", FloatArray::push)?;
+    module.inst_fn("to_vec", FloatArray::to_vec)?;
+    module.inst_fn("add", FloatArray::add)?;
+    module.inst_fn("sub", FloatArray::sub)?;
+    module.inst_fn("mul", FloatArray::mul)?;
+    module.inst_fn("scale", FloatArray::scale)?;
+    module.inst_fn("sum", FloatArray::sum)?;
+    module.inst_fn("mean", FloatArray::mean)?;
+    module.inst_fn("min", FloatArray::min)?;
+    module.inst_fn("max", FloatArray::max)?;
+    module.inst_fn("dot", FloatArray::dot)?;
+    module.inst_fn("map", FloatArray::map)?;
+    module.inst_fn("clone", FloatArray::clone)?;
+
+    module.inst_fn(crate::ADD, FloatArray::add)?;
+    module.inst_fn(crate::SUB, FloatArray::sub)?;
+    module.inst_fn(crate::MUL, FloatArray::mul)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_float_array)?;
+    module.inst_fn(crate::CLONE, FloatArray::clone)?;
+    Ok(module)
+}
+
+fn format_float_array(array: &FloatArray, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", array)
+}
@@ -0,0 +1,27 @@
+//! The `std::ops` module.
+
+use crate::{ContextError, Module, Panic, Range};
+
+/// Construct the `std::ops` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "ops"]);
+    module.ty(&["Range"]).build::<Range>()?;
+    module.inst_fn("contains", Range::contains)?;
+    module.inst_fn("iter", range_iter)?;
+    module.inst_fn(crate::INTO_ITER, range_iter)?;
+    Ok(module)
+}
+
+/// Iterate over a range, where a range without an end runs until the largest
+/// integer.
+fn range_iter(range: &Range) -> Result<super::iter::Range, Panic> {
+    let start = match range.start {
+        Some(start) => start,
+        None => return Err(Panic::custom(format!("cannot iterate over `{}`", range))),
+    };
+
+    Ok(super::iter::Range::new(
+        start,
+        range.end.unwrap_or(i64::MAX),
+    ))
+}
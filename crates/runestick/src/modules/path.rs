@@ -0,0 +1,220 @@
+//! The `std::path` module.
+//!
+//! Paths are manipulated lexically, without touching the filesystem, so this
+//! module is always available.
+
+use crate::{ContextError, Module, Value, VmError};
+use std::fmt;
+use std::fmt::Write as _;
+use std::path::{Component, PathBuf};
+
+/// Construct the `std::path` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "path"]);
+
+    module.ty(&["Path"]).build::<Path>()?;
+
+    module.function(&["Path", "new"], Path::new)?;
+    module.inst_fn("join", Path::join)?;
+    module.inst_fn("parent", Path::parent)?;
+    module.inst_fn("file_name", Path::file_name)?;
+    module.inst_fn("extension", Path::extension)?;
+    module.inst_fn("with_extension", Path::with_extension)?;
+    module.inst_fn("is_absolute", Path::is_absolute)?;
+    module.inst_fn("normalize", Path::normalize)?;
+    module.inst_fn("matches_glob", Path::matches_glob)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_path)?;
+    module.inst_fn(crate::CLONE, Path::clone)?;
+    Ok(module)
+}
+
+/// A filesystem path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path {
+    inner: PathBuf,
+}
+
+impl Path {
+    /// Construct a path from a string.
+    pub fn new(path: &str) -> Self {
+        Self {
+            inner: PathBuf::from(path),
+        }
+    }
+
+    /// Convert a value which is either a string or a path into a path buffer,
+    /// for functions which accept both.
+    pub fn to_path_buf(value: Value) -> Result<PathBuf, VmError> {
+        Ok(match value {
+            Value::String(string) => PathBuf::from(&*string.borrow_ref()?),
+            Value::StaticString(string) => PathBuf::from(string.as_str()),
+            Value::Any(any) => match any.downcast_borrow_ref::<Path>() {
+                Ok(path) => path.inner.clone(),
+                Err(..) => return Err(VmError::bad_argument::<Path>(0, &Value::Any(any))?),
+            },
+            actual => return Err(VmError::bad_argument::<Path>(0, &actual)?),
+        })
+    }
+
+    /// Access the underlying standard path.
+    pub fn as_path(&self) -> &std::path::Path {
+        &self.inner
+    }
+
+    /// Join a string or another path onto this one. Joining an absolute path
+    /// replaces this one.
+    fn join(&self, other: Value) -> Result<Self, VmError> {
+        Ok(Self {
+            inner: self.inner.join(Self::to_path_buf(other)?),
+        })
+    }
+
+    /// The path without its last component, if it has one.
+    fn parent(&self) -> Option<Self> {
+        Some(Self {
+            inner: self.inner.parent()?.to_owned(),
+        })
+    }
+
+    /// The last component of the path, unless it's `..`.
+    fn file_name(&self) -> Option<String> {
+        Some(self.inner.file_name()?.to_string_lossy().into_owned())
+    }
+
+    /// The extension of the file name, without the leading `.`.
+    fn extension(&self) -> Option<String> {
+        Some(self.inner.extension()?.to_string_lossy().into_owned())
+    }
+
+    /// The path with the extension of its file name replaced.
+    fn with_extension(&self, extension: &str) -> Self {
+        Self {
+            inner: self.inner.with_extension(extension),
+        }
+    }
+
+    /// Test if the path starts at the root.
+    fn is_absolute(&self) -> bool {
+        self.inner.has_root()
+    }
+
+    /// Remove `.` components and resolve `..` components against the
+    /// components before them.
+    ///
+    /// Since this doesn't consult the filesystem, `a/link/..` becomes `a`
+    /// even if `link` is a symbolic link.
+    fn normalize(&self) -> Self {
+        let mut inner = PathBuf::new();
+        // NB: the number of normal components at the end of `inner` which a
+        // `..` can remove.
+        let mut depth = 0usize;
+
+        for component in self.inner.components() {
+            match component {
+                Component::CurDir => (),
+                Component::ParentDir if depth > 0 => {
+                    inner.pop();
+                    depth -= 1;
+                }
+                // NB: `..` can't go above the root.
+                Component::ParentDir if inner.has_root() => (),
+                Component::Normal(..) => {
+                    inner.push(component);
+                    depth += 1;
+                }
+                component => inner.push(component),
+            }
+        }
+
+        if inner.as_os_str().is_empty() {
+            inner.push(".");
+        }
+
+        Self { inner }
+    }
+
+    /// Test if the path matches a glob pattern.
+    ///
+    /// In the pattern, `*` matches any sequence of characters within a
+    /// component, `?` matches any single character, `[abc]` and `[a-z]` match
+    /// one character in the set, `[!abc]` matches one character not in the
+    /// set, and a `**` component matches any number of components.
+    fn matches_glob(&self, pattern: &str) -> bool {
+        let path = self.inner.to_string_lossy();
+        let path = path.split('/').collect::<Vec<_>>();
+        let pattern = pattern.split('/').collect::<Vec<_>>();
+        matches_components(&pattern, &path)
+    }
+}
+
+fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|n| matches_components(rest, &path[n..])),
+        Some((first, rest)) => match path.split_first() {
+            Some((component, path)) => {
+                let pattern = first.chars().collect::<Vec<_>>();
+                let component = component.chars().collect::<Vec<_>>();
+                matches_component(&pattern, &component) && matches_components(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+fn matches_component(pattern: &[char], text: &[char]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some(('*', rest)) => (0..=text.len()).any(|n| matches_component(rest, &text[n..])),
+        Some(('?', rest)) => !text.is_empty() && matches_component(rest, &text[1..]),
+        Some(('[', rest)) => match (class(rest), text.split_first()) {
+            (Some((matches, len)), Some((c, text))) => {
+                matches(*c) && matches_component(&rest[len..], text)
+            }
+            (Some(..), None) => false,
+            // NB: an unclosed `[` matches itself.
+            (None, _) => text.first() == Some(&'[') && matches_component(rest, &text[1..]),
+        },
+        Some((c, rest)) => text.first() == Some(c) && matches_component(rest, &text[1..]),
+    }
+}
+
+/// Parse a character class after its opening `[`, returning a function which
+/// tests if a character is in the class and the length of the class including
+/// its closing `]`.
+fn class(pattern: &[char]) -> Option<(impl Fn(char) -> bool + '_, usize)> {
+    let (negated, start) = match pattern.first() {
+        Some('!') => (true, 1),
+        _ => (false, 0),
+    };
+
+    // NB: a `]` right at the start of the class is part of it.
+    let end = start + 1 + pattern.get(start + 1..)?.iter().position(|c| *c == ']')?;
+    let set = &pattern[start..end];
+
+    let matches = move |c: char| {
+        let mut it = set.iter();
+        let mut found = false;
+
+        while let Some(&first) = it.next() {
+            let mut range = it.clone();
+
+            if let (Some('-'), Some(&last)) = (range.next(), range.next()) {
+                found |= (first..=last).contains(&c);
+                it = range;
+            } else {
+                found |= first == c;
+            }
+        }
+
+        found != negated
+    };
+
+    Some((matches, end + 1))
+}
+
+fn format_path(path: &Path, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", path.inner.display())
+}
+
+impl_external!(Path);
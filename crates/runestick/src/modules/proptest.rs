@@ -0,0 +1,628 @@
+//! The `std::proptest` module.
+//!
+//! ```rust,ignore
+//! use std::proptest;
+//!
+//! fn reverse(v) {
+//!     let out = [];
+//!
+//!     for n in v.iter().rev() {
+//!         out.push(n);
+//!     }
+//!
+//!     out
+//! }
+//!
+//! fn reverse_twice(v) {
+//!     reverse(reverse(v)) == v
+//! }
+//!
+//! fn main() {
+//!     proptest::check(reverse_twice, [proptest::vec(proptest::int(-10, 10), 20)]);
+//! }
+//! ```
+
+use crate::{
+    CallContext, ContextError, FromValue as _, Function, Module, Object, Shared, Value, Vm, VmError,
+};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Construct the `std::proptest` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "proptest"]);
+
+    module.ty(&["Gen"]).build::<Gen>()?;
+
+    module.function(&["int"], |min: i64, max: i64| {
+        Gen::new(GenKind::Int { min, max })
+    })?;
+    module.function(&["float"], |min: f64, max: f64| {
+        Gen::new(GenKind::Float { min, max })
+    })?;
+    module.function(&["bool"], || Gen::new(GenKind::Bool))?;
+    module.function(&["string"], |max_len: usize| {
+        Gen::new(GenKind::String { max_len })
+    })?;
+    module.function(&["vec"], |item: &Gen, max_len: usize| {
+        Gen::new(GenKind::Vec {
+            item: Box::new(item.clone()),
+            max_len,
+        })
+    })?;
+    module.function(&["object"], object)?;
+    module.function(&["one_of"], one_of)?;
+    module.function(&["any"], || Gen::new(GenKind::Any))?;
+
+    module.raw_fn_with_context(&["check"], |cx, args| {
+        Vm::check_args(args, 2)?;
+        let gens = Vec::<Value>::from_value(cx.stack_mut().pop()?)?;
+        let f = Shared::<Function>::from_value(cx.stack_mut().pop()?)?;
+        check(cx, &*f.borrow_ref()?, gens, Config::default())?;
+        cx.stack_mut().push(Value::Unit);
+        Ok(())
+    })?;
+
+    module.raw_fn_with_context(&["check_with"], |cx, args| {
+        Vm::check_args(args, 3)?;
+        let config = Config::from_object(Object::<Value>::from_value(cx.stack_mut().pop()?)?)?;
+        let gens = Vec::<Value>::from_value(cx.stack_mut().pop()?)?;
+        let f = Shared::<Function>::from_value(cx.stack_mut().pop()?)?;
+        check(cx, &*f.borrow_ref()?, gens, config)?;
+        cx.stack_mut().push(Value::Unit);
+        Ok(())
+    })?;
+
+    Ok(module)
+}
+
+/// A generator of random values of some shape.
+#[derive(Debug, Clone)]
+pub struct Gen {
+    kind: GenKind,
+}
+
+#[derive(Debug, Clone)]
+enum GenKind {
+    Int { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    Bool,
+    String { max_len: usize },
+    Vec { item: Box<Gen>, max_len: usize },
+    Object { fields: Vec<(String, Gen)> },
+    OneOf { gens: Vec<Gen> },
+    Any,
+}
+
+impl Gen {
+    fn new(kind: GenKind) -> Self {
+        Self { kind }
+    }
+
+    /// Convert a value which is expected to be a generator.
+    fn from_value(value: &Value) -> Result<Self, VmError> {
+        if let Value::Any(any) = value {
+            if let Ok(gen) = any.downcast_borrow_ref::<Gen>() {
+                return Ok(gen.clone());
+            }
+        }
+
+        Err(VmError::bad_argument::<Gen>(0, value)?)
+    }
+
+    /// Generate a sample, where `depth` is the number of containers the
+    /// sample is nested in.
+    fn generate(&self, rng: &mut Rng, config: &Config, depth: usize) -> Sample {
+        match &self.kind {
+            GenKind::Int { min, max } => Sample::Int {
+                value: rng.int(*min, *max),
+                min: *min,
+                max: *max,
+            },
+            GenKind::Float { min, max } => Sample::Float {
+                value: rng.float(*min, *max),
+                min: *min,
+                max: *max,
+            },
+            GenKind::Bool => Sample::Bool(rng.below(2) == 1),
+            GenKind::String { max_len } => Sample::String(rng.string(*max_len)),
+            GenKind::Vec { item, max_len } => {
+                let len = rng.below(*max_len as u64 + 1) as usize;
+
+                Sample::Vec(
+                    (0..len)
+                        .map(|_| item.generate(rng, config, depth + 1))
+                        .collect(),
+                )
+            }
+            GenKind::Object { fields } => Sample::Object(
+                fields
+                    .iter()
+                    .map(|(key, gen)| (key.clone(), gen.generate(rng, config, depth + 1)))
+                    .collect(),
+            ),
+            GenKind::OneOf { gens } => {
+                let gen = &gens[rng.below(gens.len() as u64) as usize];
+                gen.generate(rng, config, depth)
+            }
+            GenKind::Any => any(rng, config, depth),
+        }
+    }
+}
+
+/// Generate an arbitrary value, which only contains containers up to the
+/// configured depth.
+fn any(rng: &mut Rng, config: &Config, depth: usize) -> Sample {
+    let choices = if depth < config.max_depth { 6 } else { 4 };
+
+    match rng.below(choices) {
+        0 => Sample::Int {
+            value: rng.int(-1000, 1000),
+            min: i64::MIN,
+            max: i64::MAX,
+        },
+        1 => Sample::Float {
+            value: rng.float(-1000.0, 1000.0),
+            min: f64::MIN,
+            max: f64::MAX,
+        },
+        2 => Sample::Bool(rng.below(2) == 1),
+        3 => Sample::String(rng.string(16)),
+        4 => {
+            let len = rng.below(5) as usize;
+            Sample::Vec((0..len).map(|_| any(rng, config, depth + 1)).collect())
+        }
+        _ => {
+            let len = rng.below(5) as usize;
+            let mut fields = Vec::<(String, Sample)>::new();
+
+            for _ in 0..len {
+                let key = rng.key();
+
+                if fields.iter().all(|(k, _)| *k != key) {
+                    fields.push((key, any(rng, config, depth + 1)));
+                }
+            }
+
+            Sample::Object(fields)
+        }
+    }
+}
+
+fn object(fields: Object<Value>) -> Result<Gen, VmError> {
+    let mut fields = fields
+        .iter()
+        .map(|(key, value)| Ok((key.clone(), Gen::from_value(value)?)))
+        .collect::<Result<Vec<_>, VmError>>()?;
+
+    // NB: sort the fields so that generation is reproducible from the seed.
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(Gen::new(GenKind::Object { fields }))
+}
+
+fn one_of(gens: Vec<Value>) -> Result<Gen, VmError> {
+    if gens.is_empty() {
+        return Err(VmError::panic("`one_of` requires at least one generator"));
+    }
+
+    let gens = gens
+        .iter()
+        .map(Gen::from_value)
+        .collect::<Result<_, VmError>>()?;
+
+    Ok(Gen::new(GenKind::OneOf { gens }))
+}
+
+/// Options for running a property.
+#[derive(Debug, Clone)]
+struct Config {
+    /// The number of cases to generate.
+    cases: usize,
+    /// The seed of the random number generator.
+    seed: u64,
+    /// The maximum number of containers that values generated by `any` are
+    /// nested in.
+    max_depth: usize,
+    /// The maximum number of times a failing input is tested while shrinking
+    /// it.
+    max_shrinks: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        Self {
+            cases: 100,
+            seed,
+            max_depth: 3,
+            max_shrinks: 1000,
+        }
+    }
+}
+
+impl Config {
+    fn from_object(object: Object<Value>) -> Result<Self, VmError> {
+        let mut config = Self::default();
+
+        for (key, value) in object {
+            let value = usize::from_value(value)?;
+
+            match key.as_str() {
+                "cases" => config.cases = value,
+                "seed" => config.seed = value as u64,
+                "max_depth" => config.max_depth = value,
+                "max_shrinks" => config.max_shrinks = value,
+                _ => return Err(VmError::panic(format!("unsupported option `{}`", key))),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+/// A generated value, which remembers enough about how it was generated to
+/// be shrunk.
+#[derive(Debug, Clone)]
+enum Sample {
+    Int { value: i64, min: i64, max: i64 },
+    Float { value: f64, min: f64, max: f64 },
+    Bool(bool),
+    String(String),
+    Vec(Vec<Sample>),
+    Object(Vec<(String, Sample)>),
+}
+
+impl Sample {
+    fn to_value(&self) -> Value {
+        match self {
+            Self::Int { value, .. } => Value::Integer(*value),
+            Self::Float { value, .. } => Value::Float(*value),
+            Self::Bool(b) => Value::Bool(*b),
+            Self::String(string) => Value::from(string.clone()),
+            Self::Vec(items) => Value::vec(items.iter().map(Self::to_value).collect()),
+            Self::Object(fields) => {
+                let object = fields
+                    .iter()
+                    .map(|(key, sample)| (key.clone(), sample.to_value()))
+                    .collect::<Object<Value>>();
+
+                Value::from(Shared::new(object))
+            }
+        }
+    }
+
+    /// Get simpler versions of the sample, with the simplest first.
+    fn shrink(&self) -> Vec<Sample> {
+        let mut out = Vec::new();
+
+        match self {
+            Self::Int { value, min, max } => {
+                let target = 0.max(*min).min(*max);
+                let mut distance = (*value as i128 - target as i128) / 2;
+
+                if *value != target {
+                    out.push(Self::Int {
+                        value: target,
+                        min: *min,
+                        max: *max,
+                    });
+                }
+
+                while distance != 0 {
+                    out.push(Self::Int {
+                        value: (*value as i128 - distance) as i64,
+                        min: *min,
+                        max: *max,
+                    });
+
+                    distance /= 2;
+                }
+            }
+            Self::Float { value, min, max } => {
+                let target = 0f64.max(*min).min(*max);
+
+                for candidate in &[target, value.trunc(), value / 2.0] {
+                    let simpler = candidate.abs() < value.abs() || value.is_nan();
+
+                    if simpler && *min <= *candidate && *candidate <= *max {
+                        out.push(Self::Float {
+                            value: *candidate,
+                            min: *min,
+                            max: *max,
+                        });
+                    }
+                }
+            }
+            Self::Bool(true) => out.push(Self::Bool(false)),
+            Self::Bool(false) => (),
+            Self::String(string) => {
+                let chars = string.chars().collect::<Vec<_>>();
+
+                for candidate in shrink_seq(&chars, |c| match c {
+                    'a' => Vec::new(),
+                    _ => vec!['a'],
+                }) {
+                    out.push(Self::String(candidate.into_iter().collect()));
+                }
+            }
+            Self::Vec(items) => {
+                out.extend(shrink_seq(items, Self::shrink).into_iter().map(Self::Vec));
+            }
+            Self::Object(fields) => {
+                for (n, (key, sample)) in fields.iter().enumerate() {
+                    for candidate in sample.shrink() {
+                        let mut fields = fields.clone();
+                        fields[n] = (key.clone(), candidate);
+                        out.push(Self::Object(fields));
+                    }
+                }
+            }
+        }
+
+        out
+    }
+}
+
+/// Shrink a sequence by removing elements from it, and by shrinking each of
+/// its elements.
+fn shrink_seq<T>(items: &[T], shrink: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>>
+where
+    T: Clone,
+{
+    let mut out = Vec::new();
+
+    if items.is_empty() {
+        return out;
+    }
+
+    out.push(Vec::new());
+
+    if items.len() > 1 {
+        out.push(items[..items.len() / 2].to_vec());
+        out.push(items[items.len() / 2..].to_vec());
+    }
+
+    for n in 0..items.len() {
+        let mut items = items.to_vec();
+        items.remove(n);
+        out.push(items);
+    }
+
+    for (n, item) in items.iter().enumerate() {
+        for candidate in shrink(item) {
+            let mut items = items.to_vec();
+            items[n] = candidate;
+            out.push(items);
+        }
+    }
+
+    out
+}
+
+impl fmt::Display for Sample {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int { value, .. } => write!(f, "{}", value),
+            Self::Float { value, .. } => write!(f, "{:?}", value),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::String(string) => write!(f, "{:?}", string),
+            Self::Vec(items) => {
+                write!(f, "[")?;
+
+                for (n, item) in items.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}", item)?;
+                }
+
+                write!(f, "]")
+            }
+            Self::Object(fields) => {
+                write!(f, "#{{")?;
+
+                for (n, (key, sample)) in fields.iter().enumerate() {
+                    if n > 0 {
+                        write!(f, ", ")?;
+                    }
+
+                    write!(f, "{}: {}", key, sample)?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// A small and fast random number generator (SplitMix64), which produces the
+/// same values for the same seed on every platform.
+#[derive(Debug, Clone)]
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, or `0` if `n` is `0`.
+    fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+
+        self.next_u64() % n
+    }
+
+    /// An integer in `min..=max`, which is biased towards the bounds and
+    /// zero since those are where bugs tend to be.
+    fn int(&mut self, min: i64, max: i64) -> i64 {
+        let (min, max) = (min.min(max), min.max(max));
+
+        match self.below(8) {
+            0 => return min,
+            1 => return max,
+            2 if min <= 0 && 0 <= max => return 0,
+            _ => (),
+        }
+
+        let span = (max as i128 - min as i128 + 1) as u128;
+
+        if span > u64::MAX as u128 {
+            return self.next_u64() as i64;
+        }
+
+        (min as i128 + self.below(span as u64) as i128) as i64
+    }
+
+    /// A float in `min..=max`.
+    fn float(&mut self, min: f64, max: f64) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        min + (max - min) * unit
+    }
+
+    /// A string of at most `max_len` characters, which are mostly ASCII.
+    fn string(&mut self, max_len: usize) -> String {
+        const SPECIAL: &[char] = &[' ', '\n', '"', '\\', 'é', 'λ', '😀'];
+
+        let len = self.below(max_len as u64 + 1);
+
+        (0..len)
+            .map(|_| match self.below(8) {
+                0 => SPECIAL[self.below(SPECIAL.len() as u64) as usize],
+                _ => (b'!' + self.below(94) as u8) as char,
+            })
+            .collect()
+    }
+
+    /// A short key for an object.
+    fn key(&mut self) -> String {
+        let len = 1 + self.below(3);
+        (0..len)
+            .map(|_| (b'a' + self.below(5) as u8) as char)
+            .collect()
+    }
+}
+
+/// A property which doesn't hold.
+#[derive(Debug)]
+struct PropertyFailed {
+    cases: usize,
+    seed: u64,
+    shrinks: usize,
+    input: String,
+    reason: String,
+}
+
+impl fmt::Display for PropertyFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "property failed after {} cases with seed {}: {}\nminimal input (shrunk {} times): {}",
+            self.cases, self.seed, self.reason, self.shrinks, self.input
+        )
+    }
+}
+
+/// Call the property with the given samples, returning the reason it failed
+/// if it didn't hold.
+fn test(cx: &CallContext<'_>, f: &Function, samples: &[Sample]) -> Option<String> {
+    let args = samples.iter().map(Sample::to_value).collect();
+
+    match f.call_vec_with_events(args, cx.events(), cx.statics()) {
+        Ok(Value::Bool(false)) => Some(String::from("returned `false`")),
+        Ok(Value::Result(result)) => match &*result.borrow_ref().ok()? {
+            Err(..) => Some(String::from("returned an error")),
+            Ok(..) => None,
+        },
+        Ok(..) => None,
+        Err(error) => Some(error.into_unwound().0.to_string()),
+    }
+}
+
+/// Test the property with generated inputs, shrinking the first input it
+/// fails for.
+fn check(
+    cx: &CallContext<'_>,
+    f: &Function,
+    gens: Vec<Value>,
+    config: Config,
+) -> Result<(), VmError> {
+    let gens = gens
+        .iter()
+        .map(Gen::from_value)
+        .collect::<Result<Vec<_>, VmError>>()?;
+
+    let mut rng = Rng::new(config.seed);
+
+    for case in 1..=config.cases {
+        let mut samples = gens
+            .iter()
+            .map(|gen| gen.generate(&mut rng, &config, 0))
+            .collect::<Vec<_>>();
+
+        let mut reason = match test(cx, f, &samples) {
+            Some(reason) => reason,
+            None => continue,
+        };
+
+        let mut shrinks = 0;
+        let mut budget = config.max_shrinks;
+
+        'shrink: while budget > 0 {
+            for n in 0..samples.len() {
+                for candidate in samples[n].shrink() {
+                    if budget == 0 {
+                        break 'shrink;
+                    }
+
+                    budget -= 1;
+                    let mut simpler = samples.clone();
+                    simpler[n] = candidate;
+
+                    if let Some(simpler_reason) = test(cx, f, &simpler) {
+                        samples = simpler;
+                        reason = simpler_reason;
+                        shrinks += 1;
+                        continue 'shrink;
+                    }
+                }
+            }
+
+            break;
+        }
+
+        let input = samples
+            .iter()
+            .map(Sample::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        return Err(VmError::panic(PropertyFailed {
+            cases: case,
+            seed: config.seed,
+            shrinks,
+            input: format!("({})", input),
+            reason,
+        }));
+    }
+
+    Ok(())
+}
+
+impl_external!(Gen);
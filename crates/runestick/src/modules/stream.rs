@@ -8,6 +8,12 @@ pub fn module() -> Result<Module, ContextError> {
     module.ty(&["Stream"]).build::<Stream>()?;
 
     module.async_inst_fn("next", Stream::next)?;
+    // NB: used by `for await` loops.
+    module.async_inst_fn(crate::NEXT, Stream::next)?;
     module.async_inst_fn("resume", Stream::resume)?;
+    module.async_inst_fn("collect", Stream::collect)?;
+    module.inst_fn("map", Stream::map)?;
+    module.inst_fn("filter", Stream::filter)?;
+    module.inst_fn("take", Stream::take)?;
     Ok(module)
 }
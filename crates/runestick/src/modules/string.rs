@@ -1,16 +1,25 @@
 //! The `std::string` module.
 
-use crate::{Bytes, ContextError, Module};
+use crate::{Bytes, ContextError, HostStr, Module, Panic, Range};
+use std::fmt;
+use std::fmt::Write as _;
 
 /// Construct the `std::string` module.
 pub fn module() -> Result<Module, ContextError> {
     let mut module = Module::new(&["std", "string"]);
 
     module.ty(&["String"]).build::<String>()?;
+    module.ty(&["NotCharBoundary"]).build::<NotCharBoundary>()?;
+    module.ty(&["Chars"]).build::<Chars>()?;
+    module.ty(&["CharIndices"]).build::<CharIndices>()?;
+    module.ty(&["Builder"]).build::<Builder>()?;
+    module.ty(&["HostStr"]).build::<HostStr>()?;
 
     module.function(&["String", "from_str"], <String as From<&str>>::from)?;
     module.function(&["String", "new"], String::new)?;
     module.function(&["String", "with_capacity"], String::with_capacity)?;
+    module.function(&["Builder", "new"], Builder::new)?;
+    module.function(&["Builder", "with_capacity"], Builder::with_capacity)?;
 
     module.inst_fn("len", String::len)?;
     module.inst_fn("capacity", String::capacity)?;
@@ -23,13 +32,157 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("clone", String::clone)?;
     module.inst_fn("shrink_to_fit", String::shrink_to_fit)?;
     module.inst_fn("char_at", char_at)?;
+    module.inst_fn("get", get)?;
+    module.inst_fn("get_or", get_or)?;
+    module.inst_fn("is_char_boundary", str::is_char_boundary)?;
+    module.inst_fn("slice", slice)?;
+    module.inst_fn("chars", chars)?;
+    module.inst_fn("char_indices", char_indices)?;
+    module.inst_fn("starts_with", starts_with)?;
+    module.inst_fn("ends_with", ends_with)?;
+    module.inst_fn("contains", contains)?;
+    module.inst_fn("find", find)?;
+    module.inst_fn("rfind", rfind)?;
+    module.inst_fn("split", split)?;
+    module.inst_fn("replace", replace)?;
+    module.inst_fn("trim", trim)?;
+    module.inst_fn("trim_start", trim_start)?;
+    module.inst_fn("trim_end", trim_end)?;
     module.inst_fn(crate::ADD, add)?;
     module.inst_fn(crate::ADD_ASSIGN, String::push_str)?;
+    module.inst_fn(crate::INDEX_GET, index_slice)?;
+
+    module.inst_fn("next", Chars::next)?;
+    module.inst_fn(crate::NEXT, Chars::next)?;
+    module.inst_fn(crate::INTO_ITER, Chars::into_iter)?;
+
+    module.inst_fn("next", CharIndices::next)?;
+    module.inst_fn(crate::NEXT, CharIndices::next)?;
+    module.inst_fn(crate::INTO_ITER, CharIndices::into_iter)?;
+
+    module.inst_fn("push", Builder::push)?;
+    module.inst_fn("push_str", Builder::push_str)?;
+    module.inst_fn("len", Builder::len)?;
+    module.inst_fn("finish", Builder::finish)?;
+    module.inst_fn(crate::ADD_ASSIGN, Builder::push_str)?;
+
+    module.inst_fn("len", HostStr::len)?;
+    module.inst_fn("is_empty", HostStr::is_empty)?;
+    module.inst_fn("contains", HostStr::contains)?;
+    module.inst_fn("starts_with", HostStr::starts_with)?;
+    module.inst_fn("ends_with", HostStr::ends_with)?;
+    module.inst_fn("to_string", HostStr::to_owned_string)?;
+    module.inst_fn(crate::STRING_DISPLAY, format_host_str)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, format_not_char_boundary)?;
+
+    #[cfg(feature = "graphemes")]
+    {
+        module.ty(&["Graphemes"]).build::<Graphemes>()?;
+        module.inst_fn("graphemes", graphemes)?;
+        module.inst_fn("next", Graphemes::next)?;
+        module.inst_fn(crate::NEXT, Graphemes::next)?;
+        module.inst_fn(crate::INTO_ITER, Graphemes::into_iter)?;
+    }
+
     Ok(module)
 }
 
+/// Error raised when an index does not fall on a character boundary.
 #[derive(Debug, Clone, Copy)]
-struct NotCharBoundary(());
+struct NotCharBoundary {
+    index: usize,
+}
+
+impl fmt::Display for NotCharBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "index `{}` is not on a character boundary", self.index)
+    }
+}
+
+fn format_host_str(s: &HostStr, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", s)
+}
+
+fn format_not_char_boundary(error: &NotCharBoundary, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+/// A builder used to efficiently construct a string out of many pieces.
+#[derive(Debug, Default)]
+struct Builder {
+    buf: String,
+}
+
+impl Builder {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buf: String::with_capacity(capacity),
+        }
+    }
+
+    fn push(&mut self, c: char) {
+        self.buf.push(c);
+    }
+
+    fn push_str(&mut self, s: &str) {
+        self.buf.push_str(s);
+    }
+
+    fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Finish building, returning the constructed string.
+    fn finish(self) -> String {
+        self.buf
+    }
+}
+
+/// An iterator over the characters of a string.
+struct Chars {
+    iter: std::vec::IntoIter<char>,
+}
+
+impl Iterator for Chars {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        self.iter.next()
+    }
+}
+
+/// An iterator over the characters of a string and their byte indexes.
+struct CharIndices {
+    iter: std::vec::IntoIter<(usize, char)>,
+}
+
+impl Iterator for CharIndices {
+    type Item = (usize, char);
+
+    fn next(&mut self) -> Option<(usize, char)> {
+        self.iter.next()
+    }
+}
+
+/// An iterator over the extended grapheme clusters of a string.
+#[cfg(feature = "graphemes")]
+struct Graphemes {
+    iter: std::vec::IntoIter<String>,
+}
+
+#[cfg(feature = "graphemes")]
+impl Iterator for Graphemes {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.iter.next()
+    }
+}
 
 /// into_bytes shim for strings.
 fn into_bytes(s: String) -> Bytes {
@@ -38,12 +191,122 @@ fn into_bytes(s: String) -> Bytes {
 
 fn char_at(s: &str, index: usize) -> Result<Option<char>, NotCharBoundary> {
     if !s.is_char_boundary(index) {
-        return Err(NotCharBoundary(()));
+        return Err(NotCharBoundary { index });
     }
 
     Ok(s[index..].chars().next())
 }
 
+/// Get the character at the given byte index, or `None` if the index is out of
+/// bounds or not on a character boundary.
+fn get(s: &str, index: i64) -> Option<char> {
+    use std::convert::TryFrom as _;
+
+    let index = usize::try_from(index).ok()?;
+
+    if !s.is_char_boundary(index) {
+        return None;
+    }
+
+    s[index..].chars().next()
+}
+
+/// Get the character at the given byte index, or the default character if
+/// there is none.
+fn get_or(s: &str, index: i64, default: char) -> char {
+    get(s, index).unwrap_or(default)
+}
+
+/// Get the substring covered by the given byte range, erroring if either end
+/// does not fall on a character boundary.
+fn slice(s: &str, range: &Range) -> Result<String, NotCharBoundary> {
+    let bounds = range.slice_bounds(s.len());
+
+    for &index in &[bounds.start, bounds.end] {
+        if !s.is_char_boundary(index) {
+            return Err(NotCharBoundary { index });
+        }
+    }
+
+    Ok(s[bounds].to_owned())
+}
+
+/// Get a copy of the substring covered by the given byte range.
+fn index_slice(s: &str, range: &Range) -> Result<String, Panic> {
+    slice(s, range).map_err(Panic::custom)
+}
+
+fn chars(s: &str) -> Chars {
+    Chars {
+        iter: s.chars().collect::<Vec<_>>().into_iter(),
+    }
+}
+
+fn char_indices(s: &str) -> CharIndices {
+    CharIndices {
+        iter: s.char_indices().collect::<Vec<_>>().into_iter(),
+    }
+}
+
+#[cfg(feature = "graphemes")]
+fn graphemes(s: &str) -> Graphemes {
+    use unicode_segmentation::UnicodeSegmentation as _;
+
+    Graphemes {
+        iter: s
+            .graphemes(true)
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .into_iter(),
+    }
+}
+
+fn starts_with(s: &str, prefix: &str) -> bool {
+    s.starts_with(prefix)
+}
+
+fn ends_with(s: &str, suffix: &str) -> bool {
+    s.ends_with(suffix)
+}
+
+fn contains(s: &str, needle: &str) -> bool {
+    s.contains(needle)
+}
+
+/// Find the byte index of the first occurrence of `needle`.
+///
+/// The returned index is always on a character boundary.
+fn find(s: &str, needle: &str) -> Option<usize> {
+    s.find(needle)
+}
+
+/// Find the byte index of the last occurrence of `needle`.
+///
+/// The returned index is always on a character boundary.
+fn rfind(s: &str, needle: &str) -> Option<usize> {
+    s.rfind(needle)
+}
+
+fn split(s: &str, separator: &str) -> Vec<String> {
+    s.split(separator).map(String::from).collect()
+}
+
+fn replace(s: &str, from: &str, to: &str) -> String {
+    s.replace(from, to)
+}
+
+fn trim(s: &str) -> String {
+    s.trim().to_owned()
+}
+
+fn trim_start(s: &str) -> String {
+    s.trim_start().to_owned()
+}
+
+fn trim_end(s: &str) -> String {
+    s.trim_end().to_owned()
+}
+
 /// The add operation for strings.
 fn add(a: &str, b: &str) -> String {
     let mut string = String::with_capacity(a.len() + b.len());
@@ -53,3 +316,8 @@ fn add(a: &str, b: &str) -> String {
 }
 
 impl_external!(NotCharBoundary);
+impl_external!(Builder);
+impl_external!(Chars);
+impl_external!(CharIndices);
+#[cfg(feature = "graphemes")]
+impl_external!(Graphemes);
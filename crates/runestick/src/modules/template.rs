@@ -0,0 +1,558 @@
+//! The `std::template` module.
+//!
+//! Templates are text with tags in them:
+//!
+//! * `{{ user.name }}` outputs a value from the context, which can be
+//!   followed by filters like `{{ user.name | upper }}`. The available
+//!   filters are `escape` which escapes HTML, `upper` and `lower`.
+//! * `{% if path %}...{% else %}...{% end %}` outputs one of two branches
+//!   depending on if the value at the path is truthy. Unit, `false`, `None`,
+//!   zero, and empty strings and collections aren't.
+//! * `{% for item in path %}...{% end %}` outputs its body once for every
+//!   element of a vector or a tuple. Inside of it, `loop.index`, `loop.first`
+//!   and `loop.last` describe the current iteration.
+
+use crate::{ContextError, Module, Object, Shared, Value, VmError};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::template` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "template"]);
+
+    module.ty(&["Template"]).build::<Template>()?;
+    module.ty(&["TemplateError"]).build::<TemplateError>()?;
+
+    module.function(&["render"], render)?;
+    module.function(&["Template", "compile"], Template::compile)?;
+    module.inst_fn("render", Template::render)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, format_template_error)?;
+    Ok(module)
+}
+
+/// Compile and render a template with the given context in one go.
+fn render(template: &str, context: Value) -> Result<Result<String, TemplateError>, VmError> {
+    match Template::compile(template) {
+        Ok(template) => template.render(context),
+        Err(error) => Ok(Err(error)),
+    }
+}
+
+/// A compiled template, which can be rendered many times.
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Compile a template.
+    pub fn compile(source: &str) -> Result<Self, TemplateError> {
+        let mut parser = Parser { source, at: 0 };
+        let (nodes, end) = parser.parse_block()?;
+
+        if let Some((tag, at)) = end {
+            return Err(TemplateError::new(
+                at,
+                TemplateErrorKind::UnexpectedTag(tag),
+            ));
+        }
+
+        Ok(Self { nodes })
+    }
+
+    /// Render the template with the given context, which is usually an
+    /// object.
+    pub fn render(&self, context: Value) -> Result<Result<String, TemplateError>, VmError> {
+        let mut renderer = Renderer {
+            context,
+            scopes: Vec::new(),
+            out: String::new(),
+        };
+
+        Ok(match renderer.render(&self.nodes)? {
+            Ok(()) => Ok(renderer.out),
+            Err(error) => Err(error),
+        })
+    }
+}
+
+/// Error raised when compiling or rendering a template fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateError {
+    at: usize,
+    kind: TemplateErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplateErrorKind {
+    Unclosed(&'static str),
+    UnexpectedTag(String),
+    UnknownTag(String),
+    UnknownFilter(String),
+    InvalidPath(String),
+    MissingValue(String),
+    NotRenderable(String),
+    NotIterable(String),
+    BadFilterTarget(String),
+}
+
+impl TemplateError {
+    fn new(at: usize, kind: TemplateErrorKind) -> Self {
+        Self { at, kind }
+    }
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            TemplateErrorKind::Unclosed(what) => write!(f, "unclosed {}", what)?,
+            TemplateErrorKind::UnexpectedTag(tag) => write!(f, "unexpected tag `{}`", tag)?,
+            TemplateErrorKind::UnknownTag(tag) => write!(f, "unknown tag `{}`", tag)?,
+            TemplateErrorKind::UnknownFilter(name) => write!(f, "unknown filter `{}`", name)?,
+            TemplateErrorKind::InvalidPath(path) => write!(f, "invalid path `{}`", path)?,
+            TemplateErrorKind::MissingValue(path) => write!(f, "missing value `{}`", path)?,
+            TemplateErrorKind::NotRenderable(ty) => {
+                write!(f, "cannot render value of type `{}`", ty)?
+            }
+            TemplateErrorKind::NotIterable(ty) => {
+                write!(f, "cannot iterate over value of type `{}`", ty)?
+            }
+            TemplateErrorKind::BadFilterTarget(name) => {
+                write!(f, "filter `{}` can only be used on strings", name)?
+            }
+        }
+
+        write!(f, " at byte {}", self.at)
+    }
+}
+
+impl std::error::Error for TemplateError {}
+
+#[derive(Debug, Clone)]
+enum Node {
+    /// Text which is output as-is.
+    Text(String),
+    /// A value output with `{{ path | filter }}`.
+    Value { path: Path, filters: Vec<Filter> },
+    /// An `{% if path %}` block.
+    If {
+        path: Path,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+    /// A `{% for name in path %}` block.
+    For {
+        name: String,
+        path: Path,
+        body: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Filter {
+    Escape,
+    Upper,
+    Lower,
+}
+
+impl Filter {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Escape => "escape",
+            Self::Upper => "upper",
+            Self::Lower => "lower",
+        }
+    }
+}
+
+/// A dotted path like `user.name` or `items.0`.
+#[derive(Debug, Clone)]
+struct Path {
+    at: usize,
+    source: String,
+}
+
+impl Path {
+    fn segments(&self) -> impl Iterator<Item = &str> {
+        self.source.split('.')
+    }
+}
+
+/// The tag which ended a block, and where it starts.
+type BlockEnd = Option<(String, usize)>;
+
+struct Parser<'a> {
+    source: &'a str,
+    at: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Parse nodes until the end of the source, or until a tag which ends a
+    /// block like `{% else %}` or `{% end %}`.
+    fn parse_block(&mut self) -> Result<(Vec<Node>, BlockEnd), TemplateError> {
+        let mut nodes = Vec::new();
+
+        loop {
+            let rest = &self.source[self.at..];
+
+            let open = match (rest.find("{{"), rest.find("{%")) {
+                (Some(a), Some(b)) => a.min(b),
+                (Some(n), None) | (None, Some(n)) => n,
+                (None, None) => {
+                    if !rest.is_empty() {
+                        nodes.push(Node::Text(rest.to_owned()));
+                    }
+
+                    self.at = self.source.len();
+                    return Ok((nodes, None));
+                }
+            };
+
+            if open > 0 {
+                nodes.push(Node::Text(rest[..open].to_owned()));
+            }
+
+            let start = self.at + open;
+
+            if rest[open..].starts_with("{{") {
+                let (content, at) = self.tag(start, "}}", "value")?;
+                nodes.push(self.parse_value(content, at)?);
+                continue;
+            }
+
+            let (content, at) = self.tag(start, "%}", "tag")?;
+            let mut words = content.split_whitespace();
+
+            match (words.next(), words.next(), words.next(), words.next()) {
+                (Some("if"), Some(path), None, None) => {
+                    let path = self.path(path, at)?;
+                    let (then, end) = self.parse_block()?;
+
+                    let otherwise = match end {
+                        Some((tag, _)) if tag == "else" => match self.parse_block()? {
+                            (otherwise, Some((tag, _))) if tag == "end" => otherwise,
+                            (_, end) => return Err(unclosed("if", start, end)),
+                        },
+                        Some((tag, _)) if tag == "end" => Vec::new(),
+                        end => return Err(unclosed("if", start, end)),
+                    };
+
+                    nodes.push(Node::If {
+                        path,
+                        then,
+                        otherwise,
+                    });
+                }
+                (Some("for"), Some(name), Some("in"), Some(path)) if words.next().is_none() => {
+                    let path = self.path(path, at)?;
+
+                    let body = match self.parse_block()? {
+                        (body, Some((tag, _))) if tag == "end" => body,
+                        (_, end) => return Err(unclosed("for", start, end)),
+                    };
+
+                    nodes.push(Node::For {
+                        name: name.to_owned(),
+                        path,
+                        body,
+                    });
+                }
+                (Some("else"), None, ..) | (Some("end"), None, ..) => {
+                    return Ok((nodes, Some((content.trim().to_owned(), start))));
+                }
+                _ => {
+                    return Err(TemplateError::new(
+                        start,
+                        TemplateErrorKind::UnknownTag(content.trim().to_owned()),
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Find the end of a tag starting at `start`, returning its content and
+    /// the offset of the content.
+    fn tag(
+        &mut self,
+        start: usize,
+        close: &str,
+        what: &'static str,
+    ) -> Result<(&'a str, usize), TemplateError> {
+        let source = self.source;
+        let content = start + 2;
+
+        match source[content..].find(close) {
+            Some(n) => {
+                self.at = content + n + close.len();
+                Ok((&source[content..content + n], content))
+            }
+            None => Err(TemplateError::new(start, TemplateErrorKind::Unclosed(what))),
+        }
+    }
+
+    fn parse_value(&self, content: &str, at: usize) -> Result<Node, TemplateError> {
+        let mut parts = content.split('|');
+        // NB: split always produces at least one part.
+        let path = self.path(parts.next().unwrap_or_default().trim(), at)?;
+        let mut filters = Vec::new();
+
+        for name in parts {
+            let filter = match name.trim() {
+                "escape" => Filter::Escape,
+                "upper" => Filter::Upper,
+                "lower" => Filter::Lower,
+                name => {
+                    return Err(TemplateError::new(
+                        at,
+                        TemplateErrorKind::UnknownFilter(name.to_owned()),
+                    ))
+                }
+            };
+
+            filters.push(filter);
+        }
+
+        Ok(Node::Value { path, filters })
+    }
+
+    fn path(&self, source: &str, at: usize) -> Result<Path, TemplateError> {
+        let valid = source.split('.').all(|segment| {
+            !segment.is_empty() && segment.chars().all(|c| c.is_alphanumeric() || c == '_')
+        });
+
+        if !valid {
+            return Err(TemplateError::new(
+                at,
+                TemplateErrorKind::InvalidPath(source.to_owned()),
+            ));
+        }
+
+        Ok(Path {
+            at,
+            source: source.to_owned(),
+        })
+    }
+}
+
+/// Construct the error for a block which isn't closed with `{% end %}`.
+fn unclosed(what: &'static str, start: usize, end: BlockEnd) -> TemplateError {
+    match end {
+        Some((tag, at)) => TemplateError::new(at, TemplateErrorKind::UnexpectedTag(tag)),
+        None => TemplateError::new(start, TemplateErrorKind::Unclosed(what)),
+    }
+}
+
+struct Renderer {
+    context: Value,
+    scopes: Vec<(String, Value)>,
+    out: String,
+}
+
+impl Renderer {
+    fn render(&mut self, nodes: &[Node]) -> Result<Result<(), TemplateError>, VmError> {
+        for node in nodes {
+            match node {
+                Node::Text(text) => self.out.push_str(text),
+                Node::Value { path, filters } => {
+                    let value = match self.lookup(path)? {
+                        Ok(value) => value,
+                        Err(error) => return Ok(Err(error)),
+                    };
+
+                    // NB: only strings and chars are case converted or escaped,
+                    // to avoid surprises when rendering other values.
+                    if let (Some(filter), false) = (filters.first(), is_text(&value)) {
+                        let name = filter.name().to_owned();
+                        let kind = TemplateErrorKind::BadFilterTarget(name);
+                        return Ok(Err(TemplateError::new(path.at, kind)));
+                    }
+
+                    let mut text = match display(&value)? {
+                        Some(text) => text,
+                        None => {
+                            let ty = value.type_info()?.to_string();
+                            let kind = TemplateErrorKind::NotRenderable(ty);
+                            return Ok(Err(TemplateError::new(path.at, kind)));
+                        }
+                    };
+
+                    for filter in filters {
+                        text = match filter {
+                            Filter::Escape => escape(&text),
+                            Filter::Upper => text.to_uppercase(),
+                            Filter::Lower => text.to_lowercase(),
+                        };
+                    }
+
+                    self.out.push_str(&text);
+                }
+                Node::If {
+                    path,
+                    then,
+                    otherwise,
+                } => {
+                    let value = match self.lookup(path)? {
+                        Ok(value) => value,
+                        Err(error) => return Ok(Err(error)),
+                    };
+
+                    let branch = if is_truthy(&value)? { then } else { otherwise };
+
+                    if let Err(error) = self.render(branch)? {
+                        return Ok(Err(error));
+                    }
+                }
+                Node::For { name, path, body } => {
+                    let value = match self.lookup(path)? {
+                        Ok(value) => value,
+                        Err(error) => return Ok(Err(error)),
+                    };
+
+                    let items = match &value {
+                        Value::Vec(vec) => vec.borrow_ref()?.clone(),
+                        Value::Tuple(tuple) => tuple.borrow_ref()?.to_vec(),
+                        value => {
+                            let ty = value.type_info()?.to_string();
+                            let kind = TemplateErrorKind::NotIterable(ty);
+                            return Ok(Err(TemplateError::new(path.at, kind)));
+                        }
+                    };
+
+                    let len = items.len();
+
+                    for (index, item) in items.into_iter().enumerate() {
+                        let mut meta = Object::new();
+                        meta.insert(String::from("index"), Value::Integer(index as i64));
+                        meta.insert(String::from("first"), Value::Bool(index == 0));
+                        meta.insert(String::from("last"), Value::Bool(index + 1 == len));
+
+                        self.scopes
+                            .push((String::from("loop"), Value::from(Shared::new(meta))));
+                        self.scopes.push((name.clone(), item));
+                        let result = self.render(body);
+                        self.scopes.truncate(self.scopes.len() - 2);
+
+                        if let Err(error) = result? {
+                            return Ok(Err(error));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Ok(()))
+    }
+
+    /// Look up the value at the given path, first in the variables of
+    /// enclosing loops and then in the context.
+    fn lookup(&self, path: &Path) -> Result<Result<Value, TemplateError>, VmError> {
+        let mut segments = path.segments();
+        // NB: paths always have at least one segment.
+        let first = segments.next().unwrap_or_default();
+
+        let scope = self
+            .scopes
+            .iter()
+            .rev()
+            .find(|(name, _)| name == first)
+            .map(|(_, value)| value.clone());
+
+        let mut value = match scope {
+            Some(value) => Some(value),
+            None => field(&self.context, first)?,
+        };
+
+        for segment in segments {
+            value = match value {
+                Some(value) => field(&value, segment)?,
+                None => break,
+            };
+        }
+
+        Ok(value.ok_or_else(|| {
+            TemplateError::new(
+                path.at,
+                TemplateErrorKind::MissingValue(path.source.clone()),
+            )
+        }))
+    }
+}
+
+/// Get a field of an object, or an element of a vector or tuple by index.
+fn field(value: &Value, name: &str) -> Result<Option<Value>, VmError> {
+    let index = name.parse::<usize>().ok();
+
+    Ok(match (value, index) {
+        (Value::Object(object), _) => object.borrow_ref()?.get(name).cloned(),
+        (Value::TypedObject(object), _) => object.borrow_ref()?.object.get(name).cloned(),
+        (Value::VariantObject(object), _) => object.borrow_ref()?.object.get(name).cloned(),
+        (Value::Vec(vec), Some(index)) => vec.borrow_ref()?.get(index).cloned(),
+        (Value::Tuple(tuple), Some(index)) => tuple.borrow_ref()?.get(index).cloned(),
+        (Value::TypedTuple(tuple), Some(index)) => tuple.borrow_ref()?.tuple.get(index).cloned(),
+        (Value::TupleVariant(tuple), Some(index)) => tuple.borrow_ref()?.tuple.get(index).cloned(),
+        _ => None,
+    })
+}
+
+/// Convert a primitive value into the text it renders as.
+fn display(value: &Value) -> Result<Option<String>, VmError> {
+    Ok(Some(match value {
+        Value::String(string) => string.borrow_ref()?.clone(),
+        Value::StaticString(string) => string.as_str().to_owned(),
+        Value::Char(c) => c.to_string(),
+        Value::Integer(integer) => integer.to_string(),
+        Value::Float(float) => crate::modules::float::to_string(*float),
+        Value::Bool(b) => b.to_string(),
+        Value::Byte(b) => b.to_string(),
+        _ => return Ok(None),
+    }))
+}
+
+fn is_text(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::String(..) | Value::StaticString(..) | Value::Char(..)
+    )
+}
+
+fn is_truthy(value: &Value) -> Result<bool, VmError> {
+    Ok(match value {
+        Value::Unit => false,
+        Value::Bool(b) => *b,
+        Value::Integer(integer) => *integer != 0,
+        Value::Float(float) => *float != 0.0,
+        Value::String(string) => !string.borrow_ref()?.is_empty(),
+        Value::StaticString(string) => !string.as_str().is_empty(),
+        Value::Vec(vec) => !vec.borrow_ref()?.is_empty(),
+        Value::Tuple(tuple) => !tuple.borrow_ref()?.is_empty(),
+        Value::Object(object) => !object.borrow_ref()?.is_empty(),
+        Value::Option(option) => option.borrow_ref()?.is_some(),
+        _ => true,
+    })
+}
+
+/// Escape the characters which are special in HTML.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+fn format_template_error(error: &TemplateError, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
+impl_external!(Template);
+impl_external!(TemplateError);
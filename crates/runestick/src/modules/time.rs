@@ -0,0 +1,163 @@
+//! The `std::time` module.
+
+use crate::{
+    ContextError, Duration, Module, ParseTimeError, TimeFormatError, Timestamp, ToValue as _,
+    Value, VmError, VmErrorKind,
+};
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Construct the `std::time` module.
+pub fn module() -> Result<Module, ContextError> {
+    let mut module = Module::new(&["std", "time"]);
+
+    module.ty(&["Duration"]).build::<Duration>()?;
+    module.ty(&["Timestamp"]).build::<Timestamp>()?;
+    module.ty(&["ParseTimeError"]).build::<ParseTimeError>()?;
+    module.ty(&["TimeFormatError"]).build::<TimeFormatError>()?;
+
+    module.function(&["Duration", "parse"], parse_duration)?;
+    module.function(&["Duration", "from_secs"], duration_from_secs)?;
+    module.function(&["Duration", "from_millis"], duration_from_millis)?;
+    module.function(&["Timestamp", "now"], Timestamp::now)?;
+    module.function(&["Timestamp", "parse"], parse_timestamp)?;
+    module.function(&["Timestamp", "from_millis"], timestamp_from_millis)?;
+
+    module.inst_fn(crate::ADD, duration_add)?;
+    module.inst_fn(crate::SUB, duration_sub)?;
+    module.inst_fn(crate::MUL, duration_mul)?;
+    module.inst_fn(crate::DIV, duration_div)?;
+    module.inst_fn(crate::CMP, duration_cmp)?;
+    module.inst_fn(crate::STRING_DISPLAY, display::<Duration>)?;
+    module.inst_fn(crate::CLONE, Duration::clone)?;
+    module.inst_fn("as_millis", duration_as_millis)?;
+    module.inst_fn("as_secs", duration_as_secs)?;
+
+    module.inst_fn(crate::ADD, timestamp_add)?;
+    module.inst_fn(crate::SUB, timestamp_sub)?;
+    module.inst_fn(crate::CMP, timestamp_cmp)?;
+    module.inst_fn(crate::STRING_DISPLAY, display::<Timestamp>)?;
+    module.inst_fn(crate::CLONE, Timestamp::clone)?;
+    module.inst_fn("as_millis", timestamp_as_millis)?;
+    module.inst_fn("format", format)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, display::<ParseTimeError>)?;
+    module.inst_fn(crate::STRING_DISPLAY, display::<TimeFormatError>)?;
+    Ok(module)
+}
+
+/// Parse a duration like `5m30s`.
+fn parse_duration(s: &str) -> Result<Duration, ParseTimeError> {
+    s.parse()
+}
+
+/// Parse an RFC 3339 timestamp like `2020-09-01T12:00:00Z`.
+fn parse_timestamp(s: &str) -> Result<Timestamp, ParseTimeError> {
+    s.parse()
+}
+
+fn duration_from_secs(secs: i64) -> Result<Duration, VmError> {
+    Duration::from_secs(secs).ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn duration_from_millis(millis: i64) -> Result<Duration, VmError> {
+    Duration::from_millis(millis).ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn timestamp_from_millis(millis: i64) -> Result<Timestamp, VmError> {
+    Timestamp::from_millis(millis).ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn duration_add(a: &Duration, b: &Duration) -> Result<Duration, VmError> {
+    a.checked_add(*b)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn duration_sub(a: &Duration, b: &Duration) -> Result<Duration, VmError> {
+    a.checked_sub(*b)
+        .ok_or_else(|| VmError::from(VmErrorKind::Underflow))
+}
+
+fn duration_mul(a: &Duration, factor: i64) -> Result<Duration, VmError> {
+    a.checked_mul(factor)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn duration_div(a: &Duration, divisor: i64) -> Result<Duration, VmError> {
+    if divisor == 0 {
+        return Err(VmError::from(VmErrorKind::DivideByZero));
+    }
+
+    a.checked_div(divisor)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+fn duration_cmp(a: &Duration, b: &Duration) -> i64 {
+    a.cmp(b) as i64
+}
+
+/// The duration in whole milliseconds.
+fn duration_as_millis(duration: &Duration) -> i64 {
+    duration.as_millis()
+}
+
+/// The duration in seconds, as a float.
+fn duration_as_secs(duration: &Duration) -> f64 {
+    duration.as_secs_f64()
+}
+
+fn timestamp_add(a: &Timestamp, b: &Duration) -> Result<Timestamp, VmError> {
+    a.checked_add(*b)
+        .ok_or_else(|| VmError::from(VmErrorKind::Overflow))
+}
+
+/// Subtracting a duration from a timestamp produces a timestamp, while
+/// subtracting two timestamps produces the duration between them.
+fn timestamp_sub(a: &Timestamp, b: Value) -> Result<Value, VmError> {
+    let any = match b {
+        Value::Any(any) => any,
+        actual => return Err(VmError::expected::<Duration>(actual.type_info()?)),
+    };
+
+    if let Ok(duration) = any.downcast_borrow_ref::<Duration>() {
+        return a
+            .checked_sub(*duration)
+            .ok_or_else(|| VmError::from(VmErrorKind::Underflow))?
+            .to_value();
+    }
+
+    if let Ok(timestamp) = any.downcast_borrow_ref::<Timestamp>() {
+        return a
+            .duration_since(*timestamp)
+            .ok_or_else(|| VmError::from(VmErrorKind::Underflow))?
+            .to_value();
+    }
+
+    Err(VmError::expected::<Duration>(Value::Any(any).type_info()?))
+}
+
+fn timestamp_cmp(a: &Timestamp, b: &Timestamp) -> i64 {
+    a.cmp(b) as i64
+}
+
+/// Milliseconds since the Unix epoch.
+fn timestamp_as_millis(timestamp: &Timestamp) -> i64 {
+    timestamp.as_millis()
+}
+
+/// Format the timestamp in UTC, like `timestamp.format("%Y-%m-%d")`.
+fn format(timestamp: &Timestamp, format: &str) -> Result<String, TimeFormatError> {
+    timestamp.format(format)
+}
+
+fn display<T>(value: &T, buf: &mut String) -> fmt::Result
+where
+    T: fmt::Display,
+{
+    write!(buf, "{}", value)
+}
+
+impl_external!(Duration);
+impl_external!(Timestamp);
+impl_external!(ParseTimeError);
+impl_external!(TimeFormatError);
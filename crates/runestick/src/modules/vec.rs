@@ -1,6 +1,13 @@
 //! The `std::vec` module.
 
-use crate::{ContextError, Module, Value};
+use crate::{
+    CallContext, ContextError, FromValue as _, Function, Module, Object, Panic, Range, Shared,
+    Value, Vm, VmError,
+};
+use std::cmp::Ordering;
+use std::convert::TryFrom as _;
+use std::fmt;
+use std::fmt::Write as _;
 use std::iter::Rev;
 
 /// Construct the `std::vec` module.
@@ -10,6 +17,11 @@ pub fn module() -> Result<Module, ContextError> {
     module.ty(&["Vec"]).build::<Vec<Value>>()?;
     module.ty(&["Iter"]).build::<Iter>()?;
     module.ty(&["Rev"]).build::<Rev<Iter>>()?;
+    module.ty(&["Chunks"]).build::<Chunks>()?;
+    module.ty(&["Windows"]).build::<Windows>()?;
+    module
+        .ty(&["IndexOutOfBounds"])
+        .build::<IndexOutOfBounds>()?;
 
     module.function(&["Vec", "new"], Vec::<Value>::new)?;
     module.inst_fn("iter", vec_iter)?;
@@ -17,6 +29,43 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("push", Vec::<Value>::push)?;
     module.inst_fn("clear", Vec::<Value>::clear)?;
     module.inst_fn("pop", Vec::<Value>::pop)?;
+    module.inst_fn("get", vec_get)?;
+    module.inst_fn("get_or", vec_get_or)?;
+    module.inst_fn("insert", vec_insert)?;
+    module.inst_fn("remove", vec_remove)?;
+    module.inst_fn("swap", vec_swap)?;
+    module.inst_fn("drain", vec_drain)?;
+    module.inst_fn("splice", vec_splice)?;
+
+    // NB: the comparator is a function which is called back into while
+    // sorting, which requires access to the context of the caller.
+    module.raw_inst_fn_with_context::<Vec<Value>, _, _>("sort_by", |cx, args| {
+        Vm::check_args(args, 2)?;
+        let comparator = Function::from_value(cx.stack_mut().pop()?)?;
+        let vec = cx.stack_mut().pop()?.into_vec()?;
+        vec_sort_by(cx, &vec, &comparator)?;
+        cx.stack_mut().push(Value::Unit);
+        Ok(())
+    })?;
+    module.raw_inst_fn_with_context::<Vec<Value>, _, _>("binary_search_by", |cx, args| {
+        Vm::check_args(args, 2)?;
+        let comparator = Function::from_value(cx.stack_mut().pop()?)?;
+        let vec = cx.stack_mut().pop()?.into_vec()?;
+        let result = vec_binary_search_by(cx, &vec, &comparator)?;
+        cx.stack_mut().push(Value::from(Shared::new(result)));
+        Ok(())
+    })?;
+    module.raw_inst_fn_with_context::<Vec<Value>, _, _>("group_by", |cx, args| {
+        Vm::check_args(args, 2)?;
+        let key = Function::from_value(cx.stack_mut().pop()?)?;
+        let vec = cx.stack_mut().pop()?.into_vec()?;
+        let groups = vec_group_by(cx, &vec, &key)?;
+        cx.stack_mut().push(Value::from(Shared::new(groups)));
+        Ok(())
+    })?;
+    module.inst_fn("chunks", vec_chunks)?;
+    module.inst_fn("windows", vec_windows)?;
+    module.inst_fn(crate::INDEX_GET, vec_slice)?;
 
     module.inst_fn(crate::INTO_ITER, vec_iter)?;
     module.inst_fn("next", Iter::next)?;
@@ -28,9 +77,40 @@ pub fn module() -> Result<Module, ContextError> {
     module.inst_fn("next_back", Rev::<Iter>::next_back)?;
     module.inst_fn(crate::NEXT, Rev::<Iter>::next)?;
     module.inst_fn(crate::INTO_ITER, Rev::<Iter>::into_iter)?;
+
+    module.inst_fn("next", Chunks::next)?;
+    module.inst_fn(crate::NEXT, Chunks::next)?;
+    module.inst_fn(crate::INTO_ITER, Chunks::into_iter)?;
+
+    module.inst_fn("next", Windows::next)?;
+    module.inst_fn(crate::NEXT, Windows::next)?;
+    module.inst_fn(crate::INTO_ITER, Windows::into_iter)?;
+
+    module.inst_fn(crate::STRING_DISPLAY, format_index_out_of_bounds)?;
     Ok(module)
 }
 
+/// Error returned when an index is out of bounds for a vector.
+#[derive(Debug, Clone, Copy)]
+struct IndexOutOfBounds {
+    index: i64,
+    len: usize,
+}
+
+impl fmt::Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index `{}` is out of bounds for a vector of length {}",
+            self.index, self.len
+        )
+    }
+}
+
+fn format_index_out_of_bounds(error: &IndexOutOfBounds, buf: &mut String) -> fmt::Result {
+    write!(buf, "{}", error)
+}
+
 /// An iterator over a vector.
 pub struct Iter {
     iter: std::vec::IntoIter<Value>,
@@ -56,5 +136,251 @@ fn vec_iter(vec: &[Value]) -> Iter {
     }
 }
 
+/// Get the element at the given index, or `None` if it's out of bounds.
+fn vec_get(vec: &[Value], index: i64) -> Option<Value> {
+    use std::convert::TryFrom as _;
+
+    let index = usize::try_from(index).ok()?;
+    vec.get(index).cloned()
+}
+
+/// Get the element at the given index, or the default value if it's out of
+/// bounds.
+fn vec_get_or(vec: &[Value], index: i64, default: Value) -> Value {
+    vec_get(vec, index).unwrap_or(default)
+}
+
+/// Check that the given index is in bounds for a vector of the given length,
+/// where `end` indicates that the index one past the last element is allowed.
+fn check_index(index: i64, len: usize, end: bool) -> Result<usize, IndexOutOfBounds> {
+    match usize::try_from(index) {
+        Ok(n) if n < len || (end && n == len) => Ok(n),
+        _ => Err(IndexOutOfBounds { index, len }),
+    }
+}
+
+/// Insert a value at the given index, shifting all values after it.
+fn vec_insert(vec: &mut Vec<Value>, index: i64, value: Value) -> Result<(), IndexOutOfBounds> {
+    let index = check_index(index, vec.len(), true)?;
+    vec.insert(index, value);
+    Ok(())
+}
+
+/// Remove and return the value at the given index, shifting all values after
+/// it.
+fn vec_remove(vec: &mut Vec<Value>, index: i64) -> Result<Value, IndexOutOfBounds> {
+    let index = check_index(index, vec.len(), false)?;
+    Ok(vec.remove(index))
+}
+
+/// Swap the values at the given indexes.
+// NB: slices can't be borrowed mutably from values.
+#[allow(clippy::ptr_arg)]
+fn vec_swap(vec: &mut Vec<Value>, a: i64, b: i64) -> Result<(), IndexOutOfBounds> {
+    let a = check_index(a, vec.len(), false)?;
+    let b = check_index(b, vec.len(), false)?;
+    vec.swap(a, b);
+    Ok(())
+}
+
+/// Remove and return the values covered by the given range.
+fn vec_drain(vec: &mut Vec<Value>, range: &Range) -> Vec<Value> {
+    vec.drain(range.slice_bounds(vec.len())).collect()
+}
+
+/// Replace the values covered by the given range with the given values,
+/// returning the values which were removed.
+fn vec_splice(vec: &mut Vec<Value>, range: &Range, values: Vec<Value>) -> Vec<Value> {
+    vec.splice(range.slice_bounds(vec.len()), values).collect()
+}
+
+/// Sort a vector with the given comparator, which returns an integer that is
+/// negative, zero, or positive if the first argument is less than, equal to,
+/// or greater than the second.
+///
+/// The sort is stable. Values are sorted outside of the vector, so the
+/// comparator can access the vector while it's being sorted. If the comparator
+/// errors, the vector is left unchanged and the first error is returned.
+fn vec_sort_by(
+    cx: &CallContext<'_>,
+    vec: &Shared<Vec<Value>>,
+    comparator: &Function,
+) -> Result<(), VmError> {
+    let mut values = vec.borrow_ref()?.clone();
+    let mut error = None;
+
+    values.sort_by(|a, b| {
+        if error.is_some() {
+            return Ordering::Equal;
+        }
+
+        match cx.call_function::<_, i64>(comparator, (a.clone(), b.clone())) {
+            Ok(n) => n.cmp(&0),
+            Err(e) => {
+                error = Some(e);
+                Ordering::Equal
+            }
+        }
+    });
+
+    if let Some(error) = error {
+        return Err(error);
+    }
+
+    *vec.borrow_mut()? = values;
+    Ok(())
+}
+
+/// Binary search a sorted vector with the given comparator, which returns an
+/// integer that is negative, zero, or positive if the element it's passed is
+/// less than, equal to, or greater than the one searched for.
+///
+/// Returns `Ok` with the index of a matching element, or `Err` with the index
+/// where a matching element could be inserted while keeping the vector sorted.
+fn vec_binary_search_by(
+    cx: &CallContext<'_>,
+    vec: &Shared<Vec<Value>>,
+    comparator: &Function,
+) -> Result<Result<Value, Value>, VmError> {
+    let mut low = 0;
+    let mut high = vec.borrow_ref()?.len();
+
+    while low < high {
+        let mid = low + (high - low) / 2;
+
+        // NB: the vector is only borrowed while the probe is fetched, since
+        // the comparator is free to access it.
+        let probe = match vec.borrow_ref()?.get(mid) {
+            Some(probe) => probe.clone(),
+            None => break,
+        };
+
+        match cx.call_function::<_, i64>(comparator, (probe,))?.cmp(&0) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Ok(Ok(Value::Integer(mid as i64))),
+        }
+    }
+
+    Ok(Err(Value::Integer(low as i64)))
+}
+
+/// Group the values of a vector by the key returned for each of them by the
+/// given function.
+///
+/// Keys must be strings, integers, characters, or booleans. Values keep their
+/// relative order within each group.
+fn vec_group_by(
+    cx: &CallContext<'_>,
+    vec: &Shared<Vec<Value>>,
+    key: &Function,
+) -> Result<Object<Value>, VmError> {
+    let values = vec.borrow_ref()?.clone();
+    let mut groups = Object::<Vec<Value>>::new();
+
+    for value in values {
+        let key = group_key(cx.call_function(key, (value.clone(),))?)?;
+        groups.entry(key).or_default().push(value);
+    }
+
+    Ok(groups
+        .into_iter()
+        .map(|(key, group)| (key, Value::from(Shared::new(group))))
+        .collect())
+}
+
+/// Convert the key of a group into the string it's stored under.
+fn group_key(key: Value) -> Result<String, VmError> {
+    Ok(match key {
+        Value::String(string) => string.borrow_ref()?.clone(),
+        Value::StaticString(string) => string.as_str().to_owned(),
+        Value::Integer(integer) => integer.to_string(),
+        Value::Char(c) => c.to_string(),
+        Value::Bool(b) => b.to_string(),
+        actual => return Err(VmError::expected::<String>(actual.type_info()?)),
+    })
+}
+
+/// Check that the size of a chunk or window isn't zero or negative.
+fn check_size(size: i64) -> Result<usize, Panic> {
+    match usize::try_from(size) {
+        Ok(size) if size > 0 => Ok(size),
+        _ => Err(Panic::custom(format!(
+            "size must be greater than zero, but got `{}`",
+            size
+        ))),
+    }
+}
+
+/// An iterator over non-overlapping chunks of a vector, where the last chunk
+/// might be shorter than the rest.
+pub struct Chunks {
+    values: Vec<Value>,
+    size: usize,
+    start: usize,
+}
+
+impl Iterator for Chunks {
+    type Item = Vec<Value>;
+
+    fn next(&mut self) -> Option<Vec<Value>> {
+        if self.start >= self.values.len() {
+            return None;
+        }
+
+        let end = usize::min(self.start + self.size, self.values.len());
+        let chunk = self.values[self.start..end].to_vec();
+        self.start = end;
+        Some(chunk)
+    }
+}
+
+fn vec_chunks(vec: &[Value], size: i64) -> Result<Chunks, Panic> {
+    Ok(Chunks {
+        values: vec.to_vec(),
+        size: check_size(size)?,
+        start: 0,
+    })
+}
+
+/// An iterator over overlapping windows of a vector.
+pub struct Windows {
+    values: Vec<Value>,
+    size: usize,
+    start: usize,
+}
+
+impl Iterator for Windows {
+    type Item = Vec<Value>;
+
+    fn next(&mut self) -> Option<Vec<Value>> {
+        let end = self.start + self.size;
+
+        if end > self.values.len() {
+            return None;
+        }
+
+        let window = self.values[self.start..end].to_vec();
+        self.start += 1;
+        Some(window)
+    }
+}
+
+fn vec_windows(vec: &[Value], size: i64) -> Result<Windows, Panic> {
+    Ok(Windows {
+        values: vec.to_vec(),
+        size: check_size(size)?,
+        start: 0,
+    })
+}
+
+/// Get a copy of the slice of a vector covered by the given range.
+fn vec_slice(vec: &[Value], range: &Range) -> Vec<Value> {
+    vec[range.slice_bounds(vec.len())].to_vec()
+}
+
+impl_external!(IndexOutOfBounds);
 impl_external!(Iter);
 impl_external!(Rev<Iter>);
+impl_external!(Chunks);
+impl_external!(Windows);
@@ -0,0 +1,378 @@
+use crate::{Stack, Value, VmError, VmErrorKind};
+
+/// The call frame of a function which has been transpiled into Rust with
+/// `rune::transpile`.
+///
+/// Transpiled functions operate on their own stack, through operations which
+/// have the same semantics as the corresponding instructions do in the
+/// virtual machine. Since they don't have access to a virtual machine,
+/// operations which would fall back to calling a protocol function, like
+/// adding two strings, raise an error instead.
+#[derive(Debug)]
+pub struct NativeFrame {
+    stack: Stack,
+}
+
+impl NativeFrame {
+    /// Construct a frame out of the arguments on top of the given stack,
+    /// making sure that the expected number of arguments were passed in.
+    pub fn from_stack(stack: &mut Stack, args: usize, expected: usize) -> Result<Self, VmError> {
+        Self::from_args(stack, args, expected)
+    }
+
+    /// Construct the frame of a call to another transpiled function, by
+    /// popping the given number of arguments off this frame.
+    pub fn call(&mut self, args: usize, expected: usize) -> Result<Self, VmError> {
+        Self::from_args(&mut self.stack, args, expected)
+    }
+
+    fn from_args(stack: &mut Stack, args: usize, expected: usize) -> Result<Self, VmError> {
+        if args != expected {
+            return Err(VmError::from(VmErrorKind::BadArgumentCount {
+                actual: args,
+                expected,
+            }));
+        }
+
+        let mut frame = Stack::with_capacity(args);
+        frame.extend(stack.pop_sequence(args)?);
+        Ok(Self { stack: frame })
+    }
+
+    /// Push a value onto the frame.
+    #[inline]
+    pub fn push(&mut self, value: Value) {
+        self.stack.push(value);
+    }
+
+    /// Pop the value on top of the frame.
+    #[inline]
+    pub fn pop(&mut self) -> Result<Value, VmError> {
+        Ok(self.stack.pop()?)
+    }
+
+    /// Pop the boolean on top of the frame.
+    #[inline]
+    pub fn pop_bool(&mut self) -> Result<bool, VmError> {
+        self.stack.pop()?.into_bool()
+    }
+
+    /// Pop the given number of values, as by the [PopN][crate::Inst::PopN]
+    /// instruction.
+    #[inline]
+    pub fn popn(&mut self, count: usize) -> Result<(), VmError> {
+        self.stack.popn(count)?;
+        Ok(())
+    }
+
+    /// Pop the given number of values under the value on top of the frame, as
+    /// by the [Clean][crate::Inst::Clean] instruction.
+    #[inline]
+    pub fn clean(&mut self, count: usize) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+        self.stack.popn(count)?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Copy the value at the given offset to the top of the frame.
+    #[inline]
+    pub fn copy(&mut self, offset: usize) -> Result<(), VmError> {
+        let value = self.stack.at_offset(offset)?.clone();
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Drop the value at the given offset, as by the
+    /// [Drop][crate::Inst::Drop] instruction.
+    #[inline]
+    pub fn drop(&mut self, offset: usize) -> Result<(), VmError> {
+        let _ = self.stack.at_offset(offset)?;
+        Ok(())
+    }
+
+    /// Duplicate the value on top of the frame.
+    #[inline]
+    pub fn dup(&mut self) -> Result<(), VmError> {
+        let value = self.stack.last()?.clone();
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Replace the value at the given offset with the value on top of the
+    /// frame.
+    #[inline]
+    pub fn replace(&mut self, offset: usize) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+        *self.stack.at_offset_mut(offset)? = value;
+        Ok(())
+    }
+
+    /// Add the two values on top of the frame.
+    #[inline]
+    pub fn add(&mut self) -> Result<(), VmError> {
+        self.num(VmErrorKind::Overflow, i64::checked_add, |a, b| a + b, "+")
+    }
+
+    /// Subtract the two values on top of the frame.
+    #[inline]
+    pub fn sub(&mut self) -> Result<(), VmError> {
+        self.num(VmErrorKind::Underflow, i64::checked_sub, |a, b| a - b, "-")
+    }
+
+    /// Multiply the two values on top of the frame.
+    #[inline]
+    pub fn mul(&mut self) -> Result<(), VmError> {
+        self.num(VmErrorKind::Overflow, i64::checked_mul, |a, b| a * b, "*")
+    }
+
+    /// Divide the two values on top of the frame.
+    #[inline]
+    pub fn div(&mut self) -> Result<(), VmError> {
+        self.num(
+            VmErrorKind::DivideByZero,
+            i64::checked_div,
+            |a, b| a / b,
+            "/",
+        )
+    }
+
+    /// Calculate the remainder of the two values on top of the frame.
+    #[inline]
+    pub fn rem(&mut self) -> Result<(), VmError> {
+        self.num(
+            VmErrorKind::DivideByZero,
+            i64::checked_rem,
+            |a, b| a % b,
+            "%",
+        )
+    }
+
+    /// Add the value on top of the frame to the value at the given offset.
+    #[inline]
+    pub fn add_assign(&mut self, offset: usize) -> Result<(), VmError> {
+        self.num_assign(
+            offset,
+            VmErrorKind::Overflow,
+            i64::checked_add,
+            |a, b| a + b,
+            "+=",
+        )
+    }
+
+    /// Subtract the value on top of the frame from the value at the given
+    /// offset.
+    #[inline]
+    pub fn sub_assign(&mut self, offset: usize) -> Result<(), VmError> {
+        self.num_assign(
+            offset,
+            VmErrorKind::Underflow,
+            i64::checked_sub,
+            |a, b| a - b,
+            "-=",
+        )
+    }
+
+    /// Multiply the value at the given offset with the value on top of the
+    /// frame.
+    #[inline]
+    pub fn mul_assign(&mut self, offset: usize) -> Result<(), VmError> {
+        self.num_assign(
+            offset,
+            VmErrorKind::Overflow,
+            i64::checked_mul,
+            |a, b| a * b,
+            "*=",
+        )
+    }
+
+    /// Divide the value at the given offset with the value on top of the
+    /// frame.
+    #[inline]
+    pub fn div_assign(&mut self, offset: usize) -> Result<(), VmError> {
+        self.num_assign(
+            offset,
+            VmErrorKind::DivideByZero,
+            i64::checked_div,
+            |a, b| a / b,
+            "/=",
+        )
+    }
+
+    /// Store the remainder of the value at the given offset and the value on
+    /// top of the frame.
+    #[inline]
+    pub fn rem_assign(&mut self, offset: usize) -> Result<(), VmError> {
+        self.num_assign(
+            offset,
+            VmErrorKind::DivideByZero,
+            i64::checked_rem,
+            |a, b| a % b,
+            "%=",
+        )
+    }
+
+    /// Test if the second value on top of the frame is less than the first.
+    #[inline]
+    pub fn lt(&mut self) -> Result<(), VmError> {
+        self.cmp(|a, b| a < b, |a, b| a < b, "<")
+    }
+
+    /// Test if the second value on top of the frame is greater than the first.
+    #[inline]
+    pub fn gt(&mut self) -> Result<(), VmError> {
+        self.cmp(|a, b| a > b, |a, b| a > b, ">")
+    }
+
+    /// Test if the second value on top of the frame is less than or equal to
+    /// the first.
+    #[inline]
+    pub fn lte(&mut self) -> Result<(), VmError> {
+        self.cmp(|a, b| a <= b, |a, b| a <= b, "<=")
+    }
+
+    /// Test if the second value on top of the frame is greater than or equal
+    /// to the first.
+    #[inline]
+    pub fn gte(&mut self) -> Result<(), VmError> {
+        self.cmp(|a, b| a >= b, |a, b| a >= b, ">=")
+    }
+
+    /// Test the two values on top of the frame for equality.
+    ///
+    /// External types are compared by reference, since the
+    /// [CMP][crate::CMP] protocol isn't available.
+    #[inline]
+    pub fn eq(&mut self) -> Result<(), VmError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        self.stack.push(Value::value_ptr_eq(&a, &b)?);
+        Ok(())
+    }
+
+    /// Test the two values on top of the frame for inequality.
+    #[inline]
+    pub fn neq(&mut self) -> Result<(), VmError> {
+        let b = self.stack.pop()?;
+        let a = self.stack.pop()?;
+        self.stack.push(!Value::value_ptr_eq(&a, &b)?);
+        Ok(())
+    }
+
+    /// Negate the boolean or integer on top of the frame.
+    #[inline]
+    pub fn not(&mut self) -> Result<(), VmError> {
+        let value = match self.stack.pop()? {
+            Value::Bool(value) => Value::from(!value),
+            Value::Integer(value) => Value::from(!value),
+            other => {
+                return Err(VmError::from(VmErrorKind::UnsupportedUnaryOperation {
+                    op: "!",
+                    operand: other.type_info()?,
+                }));
+            }
+        };
+
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Perform a logical and of the two booleans on top of the frame.
+    #[inline]
+    pub fn and(&mut self) -> Result<(), VmError> {
+        self.bool(|a, b| a && b, "&&")
+    }
+
+    /// Perform a logical or of the two booleans on top of the frame.
+    #[inline]
+    pub fn or(&mut self) -> Result<(), VmError> {
+        self.bool(|a, b| a || b, "||")
+    }
+
+    fn num(
+        &mut self,
+        error: VmErrorKind,
+        integer_op: fn(i64, i64) -> Option<i64>,
+        float_op: fn(f64, f64) -> f64,
+        op: &'static str,
+    ) -> Result<(), VmError> {
+        let rhs = self.stack.pop()?;
+        let lhs = self.stack.pop()?;
+
+        let value = match (lhs, rhs) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => {
+                Value::Integer(integer_op(lhs, rhs).ok_or_else(|| VmError::from(error))?)
+            }
+            (Value::Float(lhs), Value::Float(rhs)) => Value::Float(float_op(lhs, rhs)),
+            (lhs, rhs) => return Err(unsupported(op, &lhs, &rhs)),
+        };
+
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn num_assign(
+        &mut self,
+        offset: usize,
+        error: VmErrorKind,
+        integer_op: fn(i64, i64) -> Option<i64>,
+        float_op: fn(f64, f64) -> f64,
+        op: &'static str,
+    ) -> Result<(), VmError> {
+        let rhs = self.stack.pop()?;
+        let lhs = self.stack.at_offset_mut(offset)?;
+
+        match (lhs, rhs) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => {
+                *lhs = integer_op(*lhs, rhs).ok_or_else(|| VmError::from(error))?;
+            }
+            (Value::Float(lhs), Value::Float(rhs)) => {
+                *lhs = float_op(*lhs, rhs);
+            }
+            (lhs, rhs) => return Err(unsupported(op, lhs, &rhs)),
+        }
+
+        Ok(())
+    }
+
+    fn cmp(
+        &mut self,
+        integer_op: fn(i64, i64) -> bool,
+        float_op: fn(f64, f64) -> bool,
+        op: &'static str,
+    ) -> Result<(), VmError> {
+        let rhs = self.stack.pop()?;
+        let lhs = self.stack.pop()?;
+
+        let value = match (lhs, rhs) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => integer_op(lhs, rhs),
+            (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs),
+            (lhs, rhs) => return Err(unsupported(op, &lhs, &rhs)),
+        };
+
+        self.stack.push(value);
+        Ok(())
+    }
+
+    fn bool(&mut self, bool_op: fn(bool, bool) -> bool, op: &'static str) -> Result<(), VmError> {
+        let rhs = self.stack.pop()?;
+        let lhs = self.stack.pop()?;
+
+        let value = match (lhs, rhs) {
+            (Value::Bool(lhs), Value::Bool(rhs)) => bool_op(lhs, rhs),
+            (lhs, rhs) => return Err(unsupported(op, &lhs, &rhs)),
+        };
+
+        self.stack.push(value);
+        Ok(())
+    }
+}
+
+fn unsupported(op: &'static str, lhs: &Value, rhs: &Value) -> VmError {
+    match (lhs.type_info(), rhs.type_info()) {
+        (Ok(lhs), Ok(rhs)) => {
+            VmError::from(VmErrorKind::UnsupportedBinaryOperation { op, lhs, rhs })
+        }
+        (Err(error), _) | (_, Err(error)) => error,
+    }
+}
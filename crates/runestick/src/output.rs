@@ -0,0 +1,167 @@
+//! Sinks for output written by scripts, like through `print` or `dbg`.
+
+use std::fmt;
+use std::io;
+use std::io::Write as _;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Where output from functions like `print`, `println`, `dbg`, and the `dbg!`
+/// macro is written.
+///
+/// By default output is written to the stdout and stderr of the process.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Capture, Context, Output, Sink};
+///
+/// # fn main() -> runestick::Result<()> {
+/// let stdout = Capture::new();
+/// let output = Output::new(stdout.sink(), Sink::stderr());
+/// let context = Context::with_default_modules_and_output(output)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Output {
+    stdout: Sink,
+    stderr: Sink,
+}
+
+impl Output {
+    /// Construct output which is written to the given sinks.
+    pub fn new(stdout: Sink, stderr: Sink) -> Self {
+        Self { stdout, stderr }
+    }
+
+    /// Access the sink used for regular output, like from `print`.
+    pub fn stdout(&self) -> &Sink {
+        &self.stdout
+    }
+
+    /// Access the sink used for diagnostics, like from `dbg!`.
+    pub fn stderr(&self) -> &Sink {
+        &self.stderr
+    }
+}
+
+impl Default for Output {
+    fn default() -> Self {
+        Self::new(Sink::stdout(), Sink::stderr())
+    }
+}
+
+/// A (type erased) function which receives output.
+type SinkFn = dyn Fn(&str) -> io::Result<()> + Send + Sync;
+
+/// A destination for output.
+///
+/// Every call to [write_str][Sink::write_str] corresponds to one piece of
+/// output, like a single line printed by `println`.
+#[derive(Clone)]
+pub struct Sink {
+    inner: Arc<SinkFn>,
+}
+
+impl Sink {
+    /// A sink which writes to the stdout of the process.
+    pub fn stdout() -> Self {
+        Self::from_fn(|s| {
+            let stdout = io::stdout();
+            let mut stdout = stdout.lock();
+            stdout.write_all(s.as_bytes())
+        })
+    }
+
+    /// A sink which writes to the stderr of the process.
+    pub fn stderr() -> Self {
+        Self::from_fn(|s| {
+            let stderr = io::stderr();
+            let mut stderr = stderr.lock();
+            stderr.write_all(s.as_bytes())
+        })
+    }
+
+    /// A sink which writes to the given writer.
+    pub fn from_writer<W>(writer: W) -> Self
+    where
+        W: 'static + io::Write + Send,
+    {
+        let writer = Mutex::new(writer);
+        Self::from_fn(move |s| lock(&writer).write_all(s.as_bytes()))
+    }
+
+    /// A sink which calls the given callback with each piece of output, which
+    /// can for example be used to forward output to a structured logger.
+    pub fn from_fn<F>(f: F) -> Self
+    where
+        F: 'static + Fn(&str) -> io::Result<()> + Send + Sync,
+    {
+        Self { inner: Arc::new(f) }
+    }
+
+    /// Write the given string to the sink.
+    pub fn write_str(&self, s: &str) -> io::Result<()> {
+        (self.inner)(s)
+    }
+}
+
+impl fmt::Debug for Sink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Sink")
+    }
+}
+
+/// An in-memory buffer which output can be captured into, which is useful
+/// when testing.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Capture;
+///
+/// let capture = Capture::new();
+/// capture.sink().write_str("hello").unwrap();
+/// assert_eq!(capture.take(), "hello");
+/// assert_eq!(capture.take(), "");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    buffer: Arc<Mutex<String>>,
+}
+
+impl Capture {
+    /// Construct a new empty capture buffer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a sink which writes into this buffer.
+    pub fn sink(&self) -> Sink {
+        let buffer = self.buffer.clone();
+
+        Sink::from_fn(move |s| {
+            lock(&buffer).push_str(s);
+            Ok(())
+        })
+    }
+
+    /// Get a copy of everything captured so far.
+    pub fn contents(&self) -> String {
+        lock(&self.buffer).clone()
+    }
+
+    /// Take everything captured so far, leaving the buffer empty.
+    pub fn take(&self) -> String {
+        std::mem::take(&mut *lock(&self.buffer))
+    }
+}
+
+/// Lock the given mutex, ignoring poisoning since the protected data is only
+/// ever appended to.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    match mutex.lock() {
+        Ok(guard) => guard,
+        Err(error) => error.into_inner(),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::collections::HashMap;
+use crate::context::Handler;
+use crate::Hash;
+use std::fmt;
+use std::sync::Arc;
+
+/// Functions overridden in a virtual machine, as installed with
+/// [Vm::override_function][crate::Vm::override_function].
+#[derive(Default, Clone)]
+pub(crate) struct Overrides {
+    handlers: HashMap<Hash, Arc<Handler>>,
+}
+
+impl Overrides {
+    /// Override the function with the given hash, replacing any earlier
+    /// override of it.
+    pub(crate) fn insert(&mut self, hash: Hash, handler: Arc<Handler>) {
+        self.handlers.insert(hash, handler);
+    }
+
+    /// Get the handler overriding the function with the given hash, if any.
+    #[inline]
+    pub(crate) fn get(&self, hash: Hash) -> Option<Arc<Handler>> {
+        if self.handlers.is_empty() {
+            return None;
+        }
+
+        self.handlers.get(&hash).cloned()
+    }
+
+    /// Test if there are no overridden functions.
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+}
+
+impl fmt::Debug for Overrides {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Overrides({})", self.handlers.len())
+    }
+}
@@ -0,0 +1,324 @@
+//! Pretty printing of values, as used by `dbg`.
+
+use crate::{GeneratorState, Hash, Object, Shared, Value};
+use std::any;
+use std::fmt;
+use std::fmt::Write as _;
+
+/// Limits which apply when pretty printing a value with
+/// [Value::display_pretty].
+#[derive(Debug, Clone, Copy)]
+pub struct PrettyLimits {
+    /// The maximum depth of nested collections to print. Collections nested
+    /// deeper than this are elided as `...`.
+    pub max_depth: usize,
+    /// The maximum number of elements to print from a single collection.
+    /// Remaining elements are summarized with a count.
+    pub max_width: usize,
+    /// The number of spaces used for each level of indentation.
+    pub indent: usize,
+}
+
+impl Default for PrettyLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 8,
+            max_width: 32,
+            indent: 4,
+        }
+    }
+}
+
+/// Pretty print the given value.
+pub(crate) fn display_pretty(value: &Value, limits: PrettyLimits) -> String {
+    let mut pretty = Pretty {
+        out: String::new(),
+        limits,
+        path: Vec::new(),
+    };
+
+    pretty.value(value, 0);
+    pretty.out
+}
+
+/// The state of a pretty printer.
+struct Pretty {
+    /// The output being built.
+    out: String,
+    /// The limits to apply.
+    limits: PrettyLimits,
+    /// Shared values which are currently being printed, used to detect cycles.
+    path: Vec<*const ()>,
+}
+
+impl Pretty {
+    fn write(&mut self, args: fmt::Arguments<'_>) {
+        // NB: writing to a string never fails.
+        let _ = self.out.write_fmt(args);
+    }
+
+    fn value(&mut self, value: &Value, depth: usize) {
+        match value {
+            Value::Unit => self.out.push_str("()"),
+            Value::Bool(value) => self.write(format_args!("{:?}", value)),
+            Value::Byte(value) => self.write(format_args!("{:?}", value)),
+            Value::Char(value) => self.write(format_args!("{:?}", value)),
+            Value::Integer(value) => self.write(format_args!("{:?}", value)),
+            Value::Float(value) => crate::modules::float::format_shortest(*value, &mut self.out),
+            Value::Type(hash) => self.write(format_args!("Type({})", hash)),
+            Value::StaticString(string) => self.write(format_args!("{:?}", string)),
+            Value::String(string) => {
+                self.shared(string, |this, string| {
+                    this.write(format_args!("{:?}", string))
+                });
+            }
+            Value::Bytes(bytes) => {
+                self.shared(bytes, |this, bytes| this.write(format_args!("{:?}", bytes)));
+            }
+            Value::Vec(vec) => {
+                self.shared(vec, |this, vec| this.seq("[", "]", vec.iter(), depth));
+            }
+            Value::Tuple(tuple) => {
+                self.shared(tuple, |this, tuple| this.seq("(", ")", tuple.iter(), depth));
+            }
+            Value::Object(object) => {
+                self.shared(object, |this, object| {
+                    this.object("#{", object, true, depth)
+                });
+            }
+            Value::Option(option) => {
+                self.shared(option, |this, option| match option {
+                    Some(value) => this.wrapped("Some", value, depth),
+                    None => this.out.push_str("None"),
+                });
+            }
+            Value::Result(result) => {
+                self.shared(result, |this, result| match result {
+                    Ok(value) => this.wrapped("Ok", value, depth),
+                    Err(value) => this.wrapped("Err", value, depth),
+                });
+            }
+            Value::GeneratorState(state) => {
+                self.shared(state, |this, state| match state {
+                    GeneratorState::Yielded(value) => this.wrapped("Yielded", value, depth),
+                    GeneratorState::Complete(value) => this.wrapped("Complete", value, depth),
+                });
+            }
+            Value::TypedTuple(tuple) => {
+                self.shared(tuple, |this, tuple| {
+                    this.typed(tuple.hash);
+                    this.seq("(", ")", tuple.tuple.iter(), depth);
+                });
+            }
+            Value::TupleVariant(tuple) => {
+                self.shared(tuple, |this, tuple| {
+                    this.typed(tuple.hash);
+                    this.seq("(", ")", tuple.tuple.iter(), depth);
+                });
+            }
+            Value::TypedObject(object) => {
+                self.shared(object, |this, object| {
+                    this.typed(object.hash);
+                    this.object("{", &object.object, false, depth);
+                });
+            }
+            Value::VariantObject(object) => {
+                self.shared(object, |this, object| {
+                    this.typed(object.hash);
+                    this.object("{", &object.object, false, depth);
+                });
+            }
+            Value::Future(..)
+            | Value::Stream(..)
+            | Value::Generator(..)
+            | Value::Function(..)
+            | Value::Handle(..)
+            | Value::Any(..) => self.write(format_args!("{:?}", value)),
+        }
+    }
+
+    /// Print the content of a shared value, guarding against cycles and
+    /// values which are not accessible.
+    fn shared<T>(&mut self, shared: &Shared<T>, f: impl FnOnce(&mut Self, &T))
+    where
+        T: any::Any,
+    {
+        let ptr = shared.as_ptr();
+
+        if self.path.contains(&ptr) {
+            self.out.push_str("*cycle*");
+            return;
+        }
+
+        let value = match shared.borrow_ref() {
+            Ok(value) => value,
+            Err(..) => {
+                self.out.push_str("*not accessible*");
+                return;
+            }
+        };
+
+        self.path.push(ptr);
+        f(self, &*value);
+        self.path.pop();
+    }
+
+    /// Print the prefix of a typed value.
+    fn typed(&mut self, hash: Hash) {
+        self.write(format_args!("Type({}) ", hash));
+    }
+
+    /// Print a value wrapped in a variant, like `Some(value)`.
+    fn wrapped(&mut self, name: &str, value: &Value, depth: usize) {
+        self.out.push_str(name);
+        self.out.push('(');
+        self.value(value, depth);
+        self.out.push(')');
+    }
+
+    /// Print the fields of an object sorted by key, so that the output is
+    /// stable.
+    fn object(&mut self, open: &str, object: &Object<Value>, quoted: bool, depth: usize) {
+        let mut fields = object
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+            .collect::<Vec<_>>();
+        fields.sort_by_key(|(key, _)| *key);
+        self.fields(open, fields, quoted, depth);
+    }
+
+    /// Print a sequence of values, one per line.
+    fn seq<'a, I>(&mut self, open: &str, close: &str, values: I, depth: usize)
+    where
+        I: ExactSizeIterator<Item = &'a Value>,
+    {
+        let len = values.len();
+
+        self.out.push_str(open);
+
+        if len > 0 {
+            if depth < self.limits.max_depth {
+                for value in values.take(self.limits.max_width) {
+                    self.newline(depth + 1);
+                    self.value(value, depth + 1);
+                    self.out.push(',');
+                }
+
+                self.remaining(len, depth);
+                self.newline(depth);
+            } else {
+                self.out.push_str("...");
+            }
+        }
+
+        self.out.push_str(close);
+    }
+
+    /// Print named fields, one per line.
+    fn fields(&mut self, open: &str, fields: Vec<(&str, &Value)>, quoted: bool, depth: usize) {
+        let len = fields.len();
+
+        self.out.push_str(open);
+
+        if len > 0 {
+            if depth < self.limits.max_depth {
+                for (key, value) in fields.into_iter().take(self.limits.max_width) {
+                    self.newline(depth + 1);
+
+                    if quoted {
+                        self.write(format_args!("{:?}: ", key));
+                    } else {
+                        self.write(format_args!("{}: ", key));
+                    }
+
+                    self.value(value, depth + 1);
+                    self.out.push(',');
+                }
+
+                self.remaining(len, depth);
+                self.newline(depth);
+            } else {
+                self.out.push_str("...");
+            }
+        }
+
+        self.out.push('}');
+    }
+
+    /// Summarize the elements which were not printed because of the width
+    /// limit.
+    fn remaining(&mut self, len: usize, depth: usize) {
+        if len > self.limits.max_width {
+            self.newline(depth + 1);
+            self.write(format_args!("... {} more", len - self.limits.max_width));
+        }
+    }
+
+    fn newline(&mut self, depth: usize) {
+        self.out.push('\n');
+
+        for _ in 0..depth * self.limits.indent {
+            self.out.push(' ');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrettyLimits;
+    use crate::{Object, Shared, Value};
+
+    #[test]
+    fn test_nested() {
+        let mut object = Object::new();
+        object.insert(String::from("b"), Value::vec(vec![]));
+        object.insert(String::from("a"), Value::Integer(1));
+
+        let value = Value::vec(vec![
+            Value::Integer(1),
+            Value::Object(Shared::new(object)),
+            Value::Option(Shared::new(Some(Value::tuple(vec![Value::Bool(true)])))),
+        ]);
+
+        assert_eq! {
+            value.display_pretty(PrettyLimits::default()),
+            "[\n    1,\n    #{\n        \"a\": 1,\n        \"b\": [],\n    },\n    Some((\n        true,\n    )),\n]",
+        };
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = PrettyLimits {
+            max_depth: 1,
+            max_width: 2,
+            indent: 2,
+        };
+
+        let value = Value::vec(vec![
+            Value::vec(vec![Value::Integer(1)]),
+            Value::Integer(2),
+            Value::Integer(3),
+            Value::Integer(4),
+        ]);
+
+        assert_eq! {
+            value.display_pretty(limits),
+            "[\n  [...],\n  2,\n  ... 2 more\n]",
+        };
+    }
+
+    #[test]
+    fn test_cycle() {
+        let vec = Shared::new(vec![Value::Integer(1)]);
+        vec.borrow_mut().unwrap().push(Value::Vec(vec.clone()));
+        let value = Value::Vec(vec.clone());
+
+        assert_eq! {
+            value.display_pretty(PrettyLimits::default()),
+            "[\n    1,\n    *cycle*,\n]",
+        };
+
+        // NB: break the cycle so that the vector is freed.
+        vec.borrow_mut().unwrap().clear();
+    }
+}
@@ -195,3 +195,43 @@ pub const INTO_FUTURE: Protocol = Protocol {
     name: "into_future",
     hash: Hash::new(0x596e6428deabfda2),
 };
+
+/// Function used to produce a deep copy of an external value.
+pub const CLONE: Protocol = Protocol {
+    name: "clone",
+    hash: Hash::new(0x2af2c875e36971eb),
+};
+
+/// Function used to compare two values, returning an integer which is
+/// negative, zero, or positive if the first value is less than, equal to, or
+/// greater than the second.
+///
+/// This is used by comparison operators like `<` and `==` for external types.
+pub const CMP: Protocol = Protocol {
+    name: "cmp",
+    hash: Hash::new(0x5f4d49341a6a345e),
+};
+
+/// Function used to destructure an external value in a pattern, like
+/// `HttpResponse { status, body }`.
+///
+/// It returns `Some` object or tuple with the fields of the value if it
+/// matches, or `None` if it doesn't.
+pub const MATCH: Protocol = Protocol {
+    name: "match",
+    hash: Hash::new(0x8b3ad3f7a6c0a2e1),
+};
+
+/// Function called on the value of a `with` expression before its block is
+/// entered, producing the value which is bound in the block.
+pub const ENTER: Protocol = Protocol {
+    name: "enter",
+    hash: Hash::new(0xcb26b856be639100),
+};
+
+/// Function called on the value of a `with` expression once its block is
+/// exited, however it's exited.
+pub const EXIT: Protocol = Protocol {
+    name: "exit",
+    hash: Hash::new(0x1bd46d62d2005795),
+};
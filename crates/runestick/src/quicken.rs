@@ -0,0 +1,65 @@
+use crate::Value;
+
+/// The operand types observed at a numeric instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Site {
+    /// The instruction hasn't been executed yet.
+    Unseen,
+    /// Only integer operands have been observed.
+    Integer,
+    /// Only float operands have been observed.
+    Float,
+    /// Operands of some other type, or of mixed types, have been observed.
+    Generic,
+}
+
+impl Site {
+    /// The site corresponding to the given operands.
+    #[inline]
+    pub(crate) fn of(lhs: &Value, rhs: &Value) -> Self {
+        match (lhs, rhs) {
+            (Value::Integer(..), Value::Integer(..)) => Self::Integer,
+            (Value::Float(..), Value::Float(..)) => Self::Float,
+            _ => Self::Generic,
+        }
+    }
+}
+
+/// The operand types observed at each numeric instruction executed by a
+/// virtual machine, indexed by instruction pointer.
+///
+/// Instructions which have only observed integer or float operands are
+/// quickened, and operate on their operands in place instead of dispatching
+/// over their types. A quickened instruction which observes an operand of
+/// another type falls back to the generic implementation, and is never
+/// quickened again.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Sites {
+    sites: Vec<Site>,
+}
+
+impl Sites {
+    /// Record the operand types observed at the given instruction, returning
+    /// `true` if the instruction is quickened for them.
+    #[inline]
+    pub(crate) fn observe(&mut self, ip: usize, observed: Site) -> bool {
+        if ip >= self.sites.len() {
+            self.sites.resize(ip + 1, Site::Unseen);
+        }
+
+        let site = &mut self.sites[ip];
+
+        match (*site, observed) {
+            (Site::Generic, _) => false,
+            (Site::Unseen, observed) => {
+                *site = observed;
+                observed != Site::Generic
+            }
+            (site, observed) if site == observed => true,
+            _ => {
+                *site = Site::Generic;
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+use std::ops;
+
+/// A range of integers, as constructed by range expressions like `a..b`,
+/// `a..`, `..b`, or `..`.
+///
+/// This is primarily used to slice collections through the
+/// [INDEX_GET](crate::INDEX_GET) protocol, and to iterate over integers in
+/// `for` loops.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Range;
+///
+/// let range = Range::new(Some(1), None);
+/// assert_eq!(range.slice_bounds(4), 1..4);
+///
+/// let range = Range::new(Some(-2), Some(10));
+/// assert_eq!(range.slice_bounds(4), 0..4);
+///
+/// let range = Range::new(Some(3), Some(1));
+/// assert_eq!(range.slice_bounds(4), 3..3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Range {
+    /// The start of the range, if bounded.
+    pub start: Option<i64>,
+    /// The end of the range (exclusive), if bounded.
+    pub end: Option<i64>,
+}
+
+impl Range {
+    /// Construct a new range.
+    pub fn new(start: Option<i64>, end: Option<i64>) -> Self {
+        Self { start, end }
+    }
+
+    /// Test if the range contains the given value.
+    pub fn contains(&self, value: i64) -> bool {
+        self.start.map(|start| start <= value).unwrap_or(true)
+            && self.end.map(|end| value < end).unwrap_or(true)
+    }
+
+    /// Resolve negative bounds relative to the end of a collection of the
+    /// given length, so that `-2..` covers the last two elements.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Range;
+    ///
+    /// let range = Range::new(Some(-2), None).from_end(4);
+    /// assert_eq!(range, Range::new(Some(2), None));
+    /// assert_eq!(range.slice_bounds(4), 2..4);
+    /// ```
+    pub fn from_end(&self, len: usize) -> Self {
+        let resolve = |n: i64| {
+            if n < 0 {
+                n.saturating_add(len as i64)
+            } else {
+                n
+            }
+        };
+
+        Self {
+            start: self.start.map(resolve),
+            end: self.end.map(resolve),
+        }
+    }
+
+    /// Calculate the bounds of a slice over a collection of the given length.
+    ///
+    /// Bounds saturate to the collection, so that negative or out of bounds
+    /// values are clamped, and a range where the end comes before the start
+    /// produces an empty slice.
+    pub fn slice_bounds(&self, len: usize) -> ops::Range<usize> {
+        let clamp = |n: i64| {
+            if n < 0 {
+                0
+            } else if n as u64 > len as u64 {
+                len
+            } else {
+                n as usize
+            }
+        };
+
+        let start = self.start.map(clamp).unwrap_or(0);
+        let end = self.end.map(clamp).unwrap_or(len).max(start);
+        start..end
+    }
+}
+
+impl fmt::Display for Range {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(start) = self.start {
+            write!(f, "{}", start)?;
+        }
+
+        write!(f, "..")?;
+
+        if let Some(end) = self.end {
+            write!(f, "{}", end)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl_external!(Range);
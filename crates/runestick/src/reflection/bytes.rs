@@ -1,11 +1,13 @@
 use crate::{
-    Bytes, FromValue, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, UnsafeFromValue, Value, VmError,
+    Bytes, FromValue, HostBytes, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, UnsafeFromValue,
+    Value, VmError,
 };
 
 impl FromValue for Bytes {
     fn from_value(value: Value) -> Result<Self, VmError> {
         let bytes = value.into_bytes()?;
-        Ok(bytes.borrow_ref()?.clone())
+        let bytes = bytes.borrow_ref()?.clone();
+        Ok(bytes)
     }
 }
 
@@ -44,6 +46,13 @@ impl<'a> UnsafeFromValue for &'a [u8] {
     type Guard = RawOwnedRef;
 
     unsafe fn unsafe_from_value(value: Value) -> Result<(Self::Output, Self::Guard), VmError> {
+        if let Value::Any(any) = &value {
+            if any.borrow_ref()?.is::<HostBytes>() {
+                let (value, guard) = value.unsafe_into_any_ref::<HostBytes>()?;
+                return Ok(((*value).as_slice(), guard));
+            }
+        }
+
         let bytes = value.into_bytes()?;
         let bytes = bytes.owned_ref()?;
         let (value, guard) = OwnedRef::into_raw(bytes);
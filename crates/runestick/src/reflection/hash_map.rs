@@ -13,7 +13,12 @@ macro_rules! impl_map {
                 let mut output = <$ty>::with_capacity(object.len());
 
                 for (key, value) in object {
-                    output.insert(key, T::from_value(value)?);
+                    let value = match T::from_value(value) {
+                        Ok(value) => value,
+                        Err(e) => return Err(e.with_element($crate::ElementStep::Key(key))),
+                    };
+
+                    output.insert(key, value);
                 }
 
                 Ok(output)
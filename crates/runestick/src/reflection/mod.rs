@@ -54,6 +54,12 @@ pub trait ToValue: Sized {
 }
 
 /// Trait for converting from a value.
+///
+/// This is implemented for common containers like `Vec<T>`, `Option<T>`,
+/// `Result<T, E>`, `HashMap<String, T>`, and tuples, so native functions can
+/// take them as arguments. If an element of a container fails to convert, the
+/// error is a [VmErrorKind::BadElement][crate::VmErrorKind::BadElement] with
+/// the path to the element, like `[1].1["z"]`.
 pub trait FromValue: 'static + Sized {
     /// Try to convert to the given type, from the given value.
     fn from_value(value: Value) -> Result<Self, VmError>;
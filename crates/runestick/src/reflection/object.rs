@@ -1,5 +1,5 @@
 use crate::{
-    FromValue, Object, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, ToValue,
+    ElementStep, FromValue, Object, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, ToValue,
     UnsafeFromValue, Value, VmError,
 };
 
@@ -13,7 +13,12 @@ where
         let mut output = Object::with_capacity(object.len());
 
         for (key, value) in object {
-            output.insert(key, T::from_value(value)?);
+            let value = match T::from_value(value) {
+                Ok(value) => value,
+                Err(e) => return Err(e.with_element(ElementStep::Key(key))),
+            };
+
+            output.insert(key, value);
         }
 
         Ok(output)
@@ -1,8 +1,8 @@
 //! String trait implementations.
 
 use crate::{
-    FromValue, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, ToValue, UnsafeFromValue,
-    Value, VmError,
+    FromValue, HostStr, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, ToValue,
+    UnsafeFromValue, Value, VmError,
 };
 
 impl FromValue for String {
@@ -41,6 +41,11 @@ impl UnsafeFromValue for &str {
                 ((*s).as_str(), Some(guard))
             }
             Value::StaticString(string) => (string.as_ref().as_str(), None),
+            Value::Any(any) if any.borrow_ref()?.is::<HostStr>() => {
+                let string = any.downcast_owned_ref::<HostStr>()?;
+                let (s, guard) = OwnedRef::into_raw(string);
+                ((*s).as_str(), Some(guard))
+            }
             actual => return Err(VmError::expected::<String>(actual.type_info()?)),
         })
     }
@@ -30,7 +30,9 @@ macro_rules! impl_from_value_tuple {
 
                 $(
                     let $var = match it.next() {
-                        Some(value) => <$ty>::from_value(value)?,
+                        Some(value) => <$ty>::from_value(value).map_err(|e| {
+                            e.with_element($crate::ElementStep::TupleIndex($count - $ignore_count))
+                        })?,
                         None => {
                             return Err($crate::VmError::from($crate::VmErrorKind::IterationError));
                         },
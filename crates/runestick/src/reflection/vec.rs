@@ -1,6 +1,6 @@
 use crate::{
-    FromValue, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, ToValue, UnsafeFromValue,
-    Value, VmError,
+    ElementStep, FromValue, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared, ToValue,
+    UnsafeFromValue, Value, VmError,
 };
 
 impl<T> FromValue for Vec<T>
@@ -13,8 +13,10 @@ where
 
         let mut output = Vec::with_capacity(vec.len());
 
-        for value in vec {
-            output.push(T::from_value(value)?);
+        for (index, value) in vec.into_iter().enumerate() {
+            let value =
+                T::from_value(value).map_err(|e| e.with_element(ElementStep::Index(index)))?;
+            output.push(value);
         }
 
         Ok(output)
@@ -0,0 +1,136 @@
+use crate::{Context, Events, Hash, Unit, Vm, VmError};
+use std::sync::Arc;
+
+/// A unit installed together with the context it runs in, which runs the
+/// lifecycle hooks declared in the unit.
+///
+/// Functions without arguments named `__init__` are run when the unit is
+/// installed, and functions named `__shutdown__` are run when it is swapped
+/// out or the runtime is dropped. This allows scripts to set up caches and
+/// release resources deterministically when they are reloaded.
+///
+/// Virtual machines constructed through [vm][Runtime::vm] share the event
+/// handlers of the runtime, so handlers registered by an `__init__` hook can
+/// be fired through [events][Runtime::events].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Runtime, Unit};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// // NB: normally the unit would be compiled from a script.
+/// let unit = Arc::new(Unit::default());
+///
+/// let mut runtime = Runtime::install(context, unit)?;
+/// runtime.swap(Arc::new(Unit::default()))?;
+/// runtime.shutdown()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Runtime {
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+    events: Events,
+    /// If the init hooks of the current unit have completed, in which case
+    /// its shutdown hooks are due.
+    installed: bool,
+}
+
+impl Runtime {
+    /// Install the given unit, running its `__init__` hooks.
+    ///
+    /// This errors if the unit is not compatible with the context, see
+    /// [Unit::check_abi]. If a hook errors, the shutdown hooks of the unit are
+    /// not run.
+    pub fn install(context: Arc<Context>, unit: Arc<Unit>) -> Result<Self, VmError> {
+        unit.check_abi(&context)?;
+
+        let mut runtime = Self {
+            context,
+            unit,
+            events: Events::new(),
+            installed: false,
+        };
+
+        runtime.init()?;
+        Ok(runtime)
+    }
+
+    /// Construct a virtual machine running the installed unit.
+    pub fn vm(&self) -> Vm {
+        Vm::new(self.context.clone(), self.unit.clone()).with_events(self.events.clone())
+    }
+
+    /// Access the context of the runtime.
+    pub fn context(&self) -> &Arc<Context> {
+        &self.context
+    }
+
+    /// Access the installed unit.
+    pub fn unit(&self) -> &Arc<Unit> {
+        &self.unit
+    }
+
+    /// Access the event handlers shared by virtual machines of the runtime.
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+
+    /// Replace the installed unit, like when a script is reloaded.
+    ///
+    /// This runs the `__shutdown__` hooks of the current unit followed by the
+    /// `__init__` hooks of the new one, and returns the unit which was
+    /// replaced. The current unit is left installed if the new one is not
+    /// compatible with the context.
+    pub fn swap(&mut self, unit: Arc<Unit>) -> Result<Arc<Unit>, VmError> {
+        unit.check_abi(&self.context)?;
+        self.teardown()?;
+        let old = std::mem::replace(&mut self.unit, unit);
+        self.init()?;
+        Ok(old)
+    }
+
+    /// Shut down the runtime, running the `__shutdown__` hooks of the
+    /// installed unit.
+    ///
+    /// Dropping the runtime does the same, but ignores any errors.
+    pub fn shutdown(mut self) -> Result<(), VmError> {
+        self.teardown()
+    }
+
+    fn init(&mut self) -> Result<(), VmError> {
+        let unit = self.unit.clone();
+        self.run_hooks(&unit.hooks().init)?;
+        self.installed = true;
+        Ok(())
+    }
+
+    fn teardown(&mut self) -> Result<(), VmError> {
+        if !std::mem::take(&mut self.installed) {
+            return Ok(());
+        }
+
+        let unit = self.unit.clone();
+        self.run_hooks(&unit.hooks().shutdown)
+    }
+
+    fn run_hooks(&self, hooks: &[Hash]) -> Result<(), VmError> {
+        for hash in hooks {
+            self.vm().call(*hash, ())?.complete()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Runtime {
+    fn drop(&mut self) {
+        // NB: errors can't be reported from drop, use `shutdown` to observe
+        // them.
+        let _ = self.teardown();
+    }
+}
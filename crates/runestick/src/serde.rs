@@ -1,6 +1,7 @@
 use crate::bytes::Bytes;
 use crate::collections::HashMap;
 use crate::shared::Shared;
+use crate::time::{Duration, Timestamp};
 use crate::value::Value;
 use serde::{de, ser};
 use std::fmt;
@@ -87,7 +88,20 @@ impl ser::Serialize for Value {
                 Err(ser::Error::custom("cannot serialize generator states"))
             }
             Value::Function(..) => Err(ser::Error::custom("cannot serialize function pointers")),
-            Value::Any(..) => Err(ser::Error::custom("cannot serialize external objects")),
+            Value::Handle(..) => Err(ser::Error::custom("cannot serialize handles")),
+            Value::Any(any) => {
+                // NB: durations and timestamps serialize as the strings they
+                // parse from, like `5m30s` and `2020-09-01T12:00:00Z`.
+                if let Ok(duration) = any.downcast_borrow_ref::<Duration>() {
+                    return serializer.collect_str(&*duration);
+                }
+
+                if let Ok(timestamp) = any.downcast_borrow_ref::<Timestamp>() {
+                    return serializer.collect_str(&*timestamp);
+                }
+
+                Err(ser::Error::custom("cannot serialize external objects"))
+            }
         }
     }
 }
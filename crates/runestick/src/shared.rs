@@ -23,6 +23,8 @@ impl<T> Shared<T> {
         let inner = Box::leak(Box::new(SharedBox {
             access: Access::new(),
             count: Cell::new(1),
+            #[cfg(debug_assertions)]
+            site: crate::access::current_site(),
             data: data.into(),
         }));
 
@@ -95,7 +97,41 @@ impl<T> Shared<T> {
     pub fn is_writable(&self) -> bool {
         // Safety: Since we have a reference to this shared, we know that the
         // inner is available.
-        unsafe { self.inner.as_ref().access.is_exclusive() }
+        unsafe {
+            let access = &self.inner.as_ref().access;
+            access.is_exclusive() && !access.is_frozen()
+        }
+    }
+
+    /// Freeze the value, making it read-only.
+    ///
+    /// Any future attempts to access the value exclusively or to take it will
+    /// result in an error. Freezing can not be undone.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::Shared;
+    /// let shared = Shared::new(1u32);
+    /// shared.freeze();
+    ///
+    /// assert!(shared.is_frozen());
+    /// assert!(shared.is_readable());
+    /// assert!(!shared.is_writable());
+    /// assert!(shared.borrow_mut().is_err());
+    /// assert!(shared.take().is_err());
+    /// ```
+    pub fn freeze(&self) {
+        // Safety: Since we have a reference to this shared, we know that the
+        // inner is available.
+        unsafe { self.inner.as_ref().access.freeze() }
+    }
+
+    /// Test if the value has been frozen.
+    pub fn is_frozen(&self) -> bool {
+        // Safety: Since we have a reference to this shared, we know that the
+        // inner is available.
+        unsafe { self.inner.as_ref().access.is_frozen() }
     }
 
     /// Take the interior value, if we have exlusive access to it and there
@@ -149,6 +185,27 @@ impl<T> Shared<T> {
         }
     }
 
+    /// Take the interior value like [take][Shared::take], even if the value
+    /// has been frozen.
+    ///
+    /// This is used to revoke access to values which refer to data that is
+    /// going away, which freezing mustn't prevent.
+    pub(crate) fn revoke(self) -> Result<T, AccessError> {
+        // Safety: see `take`.
+        unsafe {
+            let inner = self.inner.as_ref();
+            let _ = ManuallyDrop::new(inner.access.revoke()?);
+
+            // Read the pointer out without dropping the inner structure.
+            // The data field will be invalid at this point, which should be
+            // flagged through a `taken` access flag.
+            //
+            // Future access is forever prevented since we never release
+            // the access (see above).
+            Ok(ptr::read(inner.data.get()))
+        }
+    }
+
     /// Get a reference to the interior value while checking for shared access
     /// that holds onto a reference count of the inner value.
     ///
@@ -188,6 +245,7 @@ impl<T> Shared<T> {
         // Appropriate access is checked when constructing the guards.
         unsafe {
             let guard = self.inner.as_ref().access.shared()?;
+            self.inner.as_ref().access.record_site();
 
             // NB: we need to prevent the Drop impl for Shared from being called,
             // since we are deconstructing its internals.
@@ -238,6 +296,7 @@ impl<T> Shared<T> {
         // Appropriate access is checked when constructing the guards.
         unsafe {
             let guard = self.inner.as_ref().access.exclusive()?;
+            self.inner.as_ref().access.record_site();
 
             // NB: we need to prevent the Drop impl for Shared from being called,
             // since we are deconstructing its internals.
@@ -254,6 +313,46 @@ impl<T> Shared<T> {
 }
 
 impl<T: ?Sized> Shared<T> {
+    /// Get a pointer identifying the shared allocation.
+    ///
+    /// Two shared values pointing to the same allocation will return the same
+    /// pointer, which can be used to detect cycles when traversing values.
+    pub(crate) fn as_ptr(&self) -> *const () {
+        self.inner.as_ptr() as *const ()
+    }
+
+    /// Get the number of strong references to the shared value.
+    pub(crate) fn strong_count(&self) -> usize {
+        // Safety: Since we have a reference to this shared, we know that the
+        // inner is available.
+        unsafe { self.inner.as_ref().count.get() }
+    }
+
+    /// Get the instruction pointer of the instruction which was executing when
+    /// the value was allocated.
+    ///
+    /// This is only recorded in debug builds.
+    pub(crate) fn site(&self) -> Option<usize> {
+        #[cfg(debug_assertions)]
+        {
+            // Safety: Since we have a reference to this shared, we know that
+            // the inner is available.
+            unsafe { self.inner.as_ref().site.ip() }
+        }
+
+        #[cfg(not(debug_assertions))]
+        {
+            None
+        }
+    }
+
+    /// Test if this is the only reference to the shared value.
+    pub(crate) fn is_unique(&self) -> bool {
+        // Safety: Since we have a reference to this shared, we know that the
+        // inner is available.
+        unsafe { self.inner.as_ref().count.get() == 1 }
+    }
+
     /// Get a reference to the interior value while checking for shared access.
     ///
     /// This prevents other exclusive accesses from being performed while the
@@ -422,6 +521,7 @@ impl Shared<Any> {
             let (data, guard) = {
                 let inner = self.inner.as_ref();
                 let guard = inner.access.shared()?;
+                inner.access.record_site();
                 let expected = Hash::from_type_id(any::TypeId::of::<T>());
 
                 match (*inner.data.get()).as_ptr(expected) {
@@ -481,6 +581,7 @@ impl Shared<Any> {
             let (data, guard) = {
                 let inner = self.inner.as_ref();
                 let guard = inner.access.exclusive()?;
+                inner.access.record_site();
                 let expected = Hash::from_type_id(any::TypeId::of::<T>());
 
                 match (*inner.data.get()).as_mut_ptr(expected) {
@@ -584,6 +685,11 @@ struct SharedBox<T: ?Sized> {
     access: Access,
     /// The number of strong references to the shared data.
     count: Cell<usize>,
+    /// The instruction which allocated the shared data. Only recorded in debug
+    /// builds, so that heap dumps can attribute values to where they were
+    /// allocated.
+    #[cfg(debug_assertions)]
+    site: crate::access::RawSite,
     /// The value being held. Guarded by the `access` field to determine if it
     /// can be access shared or exclusively.
     data: UnsafeCell<T>,
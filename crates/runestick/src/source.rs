@@ -1,4 +1,4 @@
-use crate::Span;
+use crate::{FrontmatterError, SourceHeader, Span};
 use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
@@ -49,6 +49,16 @@ impl Source {
         self.source.get(span.start..span.end)
     }
 
+    /// Get the one-based line number which the given byte offset is on.
+    pub fn line(&self, offset: usize) -> usize {
+        let offset = usize::min(offset, self.source.len());
+        self.source.as_bytes()[..offset]
+            .iter()
+            .filter(|b| **b == b'\n')
+            .count()
+            + 1
+    }
+
     /// Get the end of the source.
     pub fn end(&self) -> usize {
         self.source.len()
@@ -63,4 +73,10 @@ impl Source {
     pub fn path(&self) -> Option<&Path> {
         self.path.as_deref()
     }
+
+    /// Parse the header of the source, which consists of an optional shebang
+    /// line and an optional frontmatter block.
+    pub fn header(&self) -> Result<SourceHeader, FrontmatterError> {
+        SourceHeader::parse(&self.source)
+    }
 }
@@ -111,6 +111,20 @@ impl Stack {
         }
     }
 
+    /// Access the two values at the top of the stack mutably.
+    pub(crate) fn last_pair_mut(&mut self) -> Option<(&mut Value, &mut Value)> {
+        let n = self.stack.len().checked_sub(2)?;
+
+        if n < self.stack_bottom {
+            return None;
+        }
+
+        match &mut self.stack[n..] {
+            [lhs, rhs] => Some((lhs, rhs)),
+            _ => None,
+        }
+    }
+
     /// Push a value onto the stack.
     pub fn push<T>(&mut self, value: T)
     where
@@ -188,6 +202,33 @@ impl Stack {
         Err(StackError(()))
     }
 
+    /// Remove every value in the current stack frame except for the `count`
+    /// values on top of it.
+    pub(crate) fn retain_top(&mut self, count: usize) -> Result<(), StackError> {
+        let end = match self.stack.len().checked_sub(count) {
+            Some(end) if end >= self.stack_bottom => end,
+            _ => return Err(StackError(())),
+        };
+
+        self.stack.drain(self.stack_bottom..end);
+        Ok(())
+    }
+
+    /// Pop the current stack top and modify it to a different one, keeping the
+    /// `count` values in the current stack frame on top of the new one.
+    pub(crate) fn pop_stack_top_with(
+        &mut self,
+        stack_bottom: usize,
+        count: usize,
+    ) -> Result<(), StackError> {
+        if self.stack.len() != self.stack_bottom + count {
+            return Err(StackError(()));
+        }
+
+        self.stack_bottom = stack_bottom;
+        Ok(())
+    }
+
     /// Pop the current stack top and modify it to a different one.
     ///
     /// This asserts that the size of the current stack frame is exactly zero
@@ -183,3 +183,14 @@ pub static FUNCTION_TYPE: &StaticType = &StaticType {
 impl_static_type!(crate::Function => FUNCTION_TYPE);
 impl_static_type!(crate::Shared<crate::Function> => FUNCTION_TYPE);
 impl_static_type!(crate::OwnedRef<crate::Function> => FUNCTION_TYPE);
+
+/// The specialized type information for a handle type.
+pub static HANDLE_TYPE: &StaticType = &StaticType {
+    name: "Handle",
+    hash: Hash::new(0x9f3c5e1b7d2a4c86),
+};
+
+impl_static_type!(crate::Handle => HANDLE_TYPE);
+impl_static_type!(crate::Shared<crate::Handle> => HANDLE_TYPE);
+impl_static_type!(crate::OwnedRef<crate::Handle> => HANDLE_TYPE);
+impl_static_type!(crate::OwnedMut<crate::Handle> => HANDLE_TYPE);
@@ -0,0 +1,107 @@
+use crate::memoize::{MemoizeCache, MemoizeKey};
+use crate::{Shared, Unit, Value, VmError, VmErrorKind};
+
+/// The state of a single static.
+#[derive(Debug, Clone)]
+enum Static {
+    /// The static hasn't been accessed yet.
+    Uninitialized,
+    /// The initializer of the static is running.
+    Initializing,
+    /// The static has been initialized with a value.
+    Initialized(Value),
+}
+
+/// The values of statics declared with module-level `let` bindings, and the
+/// cached results of functions declared with `#[memoize]`.
+///
+/// Statics are initialized on first use. Every virtual machine has its own
+/// statics, which are shared with the virtual machines it spawns to call async
+/// functions, generators, and closures.
+#[derive(Debug, Clone)]
+pub(crate) struct Statics {
+    slots: Shared<Vec<Static>>,
+    caches: Shared<Vec<MemoizeCache>>,
+}
+
+impl Default for Statics {
+    fn default() -> Self {
+        Self {
+            slots: Shared::new(Vec::new()),
+            caches: Shared::new(Vec::new()),
+        }
+    }
+}
+
+impl Statics {
+    /// Get the value of the static in the given slot if it has been
+    /// initialized. Otherwise the static is marked as being initialized.
+    ///
+    /// Errors if the static is already being initialized.
+    pub(crate) fn get_or_init(&self, unit: &Unit, slot: usize) -> Result<Option<Value>, VmError> {
+        let mut slots = self.slots.borrow_mut()?;
+
+        if slots.len() <= slot {
+            slots.resize(slot + 1, Static::Uninitialized);
+        }
+
+        match &slots[slot] {
+            Static::Initialized(value) => Ok(Some(value.clone())),
+            Static::Initializing => {
+                let item = unit.lookup_static(slot)?.clone();
+                Err(VmError::from(VmErrorKind::StaticInitializing { item }))
+            }
+            Static::Uninitialized => {
+                slots[slot] = Static::Initializing;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Store the value of the static in the given slot.
+    pub(crate) fn store(&self, slot: usize, value: Value) -> Result<(), VmError> {
+        let mut slots = self.slots.borrow_mut()?;
+
+        if slots.len() <= slot {
+            slots.resize(slot + 1, Static::Uninitialized);
+        }
+
+        slots[slot] = Static::Initialized(value);
+        Ok(())
+    }
+
+    /// Get the cached result of a call to the memoized function in the given
+    /// slot, if there is one.
+    pub(crate) fn get_memoized(
+        &self,
+        unit: &Unit,
+        slot: usize,
+        key: &MemoizeKey,
+    ) -> Result<Option<Value>, VmError> {
+        let memoize = unit.lookup_memoized(slot)?;
+        let mut caches = self.caches.borrow_mut()?;
+
+        match caches.get_mut(slot) {
+            Some(cache) => cache.get(memoize, key),
+            None => Ok(None),
+        }
+    }
+
+    /// Cache the result of a call to the memoized function in the given slot.
+    pub(crate) fn store_memoized(
+        &self,
+        unit: &Unit,
+        slot: usize,
+        key: MemoizeKey,
+        value: Value,
+    ) -> Result<(), VmError> {
+        let memoize = unit.lookup_memoized(slot)?;
+        let mut caches = self.caches.borrow_mut()?;
+
+        if caches.len() <= slot {
+            caches.resize_with(slot + 1, MemoizeCache::default);
+        }
+
+        caches[slot].insert(memoize, key, value)
+    }
+}
@@ -1,22 +1,37 @@
 use crate::{
-    FromValue, GeneratorState, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared,
+    FromValue, Function, GeneratorState, OwnedMut, OwnedRef, RawOwnedMut, RawOwnedRef, Shared,
     UnsafeFromValue, Value, Vm, VmError, VmErrorKind, VmExecution,
 };
+use futures::future::LocalBoxFuture;
 use std::fmt;
 use std::mem;
 
-/// A stream with a stored virtual machine.
+/// A stream with a stored virtual machine, or an adapter over another stream
+/// as constructed by [map][Self::map], [filter][Self::filter] and
+/// [take][Self::take].
 pub struct Stream {
-    execution: Option<VmExecution>,
-    first: bool,
+    /// The state of the stream, which is `None` once it has completed.
+    inner: Option<Inner>,
 }
 
 impl Stream {
     /// Construct a stream from a virtual machine.
     pub(crate) fn new(vm: Vm) -> Self {
         Self {
-            execution: Some(VmExecution::new(vm)),
-            first: true,
+            inner: Some(Inner::Vm {
+                execution: VmExecution::new(vm),
+                first: true,
+            }),
+        }
+    }
+
+    /// Access the execution of the stream, or of the stream being adapted,
+    /// unless it has completed.
+    pub(crate) fn execution(&self) -> Option<&VmExecution> {
+        match self.inner.as_ref()? {
+            Inner::Vm { execution, .. } => Some(execution),
+            Inner::Map { stream, .. } | Inner::Filter { stream, .. } => stream.execution(),
+            Inner::Take { stream, .. } => stream.execution(),
         }
     }
 
@@ -30,31 +45,147 @@ impl Stream {
 
     /// Get the next value produced by this stream.
     pub async fn resume(&mut self, value: Value) -> Result<GeneratorState, VmError> {
-        let execution = match &mut self.execution {
-            Some(execution) => execution,
+        let inner = match &mut self.inner {
+            Some(inner) => inner,
             None => {
                 return Err(VmError::from(VmErrorKind::GeneratorComplete));
             }
         };
 
-        if !mem::take(&mut self.first) {
-            execution.vm_mut()?.stack_mut().push(value);
-        }
-
-        let state = execution.async_resume().await?;
+        let state = inner.resume(value).await?;
 
         if state.is_complete() {
-            self.execution = None;
+            self.inner = None;
         }
 
         Ok(state)
     }
+
+    /// Construct a stream which yields the values of this stream passed
+    /// through the given function.
+    ///
+    /// If the function returns a future, it is awaited.
+    pub fn map(self, f: Function) -> Self {
+        Self::adapt(self, |stream| Inner::Map { stream, f })
+    }
+
+    /// Construct a stream which only yields the values of this stream for
+    /// which the given function returns `true`.
+    ///
+    /// If the function returns a future, it is awaited.
+    pub fn filter(self, f: Function) -> Self {
+        Self::adapt(self, |stream| Inner::Filter { stream, f })
+    }
+
+    /// Construct a stream which yields at most `n` values of this stream.
+    pub fn take(self, n: usize) -> Self {
+        Self::adapt(self, |stream| Inner::Take {
+            stream,
+            remaining: n,
+        })
+    }
+
+    /// Collect the remaining values yielded by the stream into a vector.
+    pub async fn collect(mut self) -> Result<Vec<Value>, VmError> {
+        let mut values = Vec::new();
+
+        while self.inner.is_some() {
+            if let GeneratorState::Yielded(value) = self.resume(Value::Unit).await? {
+                values.push(value);
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn adapt(self, f: impl FnOnce(Box<Self>) -> Inner) -> Self {
+        // NB: adapting a completed stream results in a completed stream.
+        if self.inner.is_none() {
+            return self;
+        }
+
+        Self {
+            inner: Some(f(Box::new(self))),
+        }
+    }
+}
+
+enum Inner {
+    /// A stream driven by a virtual machine.
+    Vm { execution: VmExecution, first: bool },
+    /// A stream constructed through [Stream::map].
+    Map { stream: Box<Stream>, f: Function },
+    /// A stream constructed through [Stream::filter].
+    Filter { stream: Box<Stream>, f: Function },
+    /// A stream constructed through [Stream::take].
+    Take {
+        stream: Box<Stream>,
+        remaining: usize,
+    },
+}
+
+impl Inner {
+    /// Resume the stream.
+    ///
+    /// NB: this is boxed since adapters resume the stream they adapt.
+    fn resume(&mut self, value: Value) -> LocalBoxFuture<'_, Result<GeneratorState, VmError>> {
+        Box::pin(async move {
+            match self {
+                Self::Vm { execution, first } => {
+                    if !mem::take(first) {
+                        execution.vm_mut()?.stack_mut().push(value);
+                    }
+
+                    execution.async_resume().await
+                }
+                Self::Map { stream, f } => match stream.resume(value).await? {
+                    GeneratorState::Yielded(value) => {
+                        Ok(GeneratorState::Yielded(call(f, value).await?))
+                    }
+                    state => Ok(state),
+                },
+                Self::Filter { stream, f } => {
+                    let mut value = value;
+
+                    loop {
+                        match stream.resume(value).await? {
+                            GeneratorState::Yielded(yielded) => {
+                                if call(f, yielded.clone()).await?.into_bool()? {
+                                    return Ok(GeneratorState::Yielded(yielded));
+                                }
+                            }
+                            state => return Ok(state),
+                        }
+
+                        value = Value::Unit;
+                    }
+                }
+                Self::Take { stream, remaining } => {
+                    if *remaining == 0 {
+                        return Ok(GeneratorState::Complete(Value::Unit));
+                    }
+
+                    *remaining -= 1;
+                    stream.resume(value).await
+                }
+            }
+        })
+    }
+}
+
+/// Call the function of a stream adapter, awaiting the value it returns if
+/// it's a future.
+async fn call(f: &Function, value: Value) -> Result<Value, VmError> {
+    match f.call::<_, Value>((value,))? {
+        Value::Future(future) => future.borrow_mut()?.await,
+        value => Ok(value),
+    }
 }
 
 impl fmt::Debug for Stream {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Stream")
-            .field("completed", &self.execution.is_none())
+            .field("completed", &self.inner.is_none())
             .finish()
     }
 }
@@ -0,0 +1,635 @@
+//! Durations and timestamps.
+
+use std::fmt;
+use std::fmt::Write as _;
+use std::str::FromStr;
+use std::time;
+
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_SEC: i64 = 1_000_000_000;
+const SECS_PER_DAY: i64 = 86_400;
+
+/// The units a duration can be written in, from the largest to the smallest.
+const UNITS: [(&str, i64); 7] = [
+    ("d", SECS_PER_DAY * NANOS_PER_SEC),
+    ("h", 3_600 * NANOS_PER_SEC),
+    ("m", 60 * NANOS_PER_SEC),
+    ("s", NANOS_PER_SEC),
+    ("ms", NANOS_PER_MILLI),
+    ("us", NANOS_PER_MICRO),
+    ("ns", 1),
+];
+
+/// A span of time with nanosecond precision, which might be negative.
+///
+/// Durations are written as a sequence of amounts and units, like `5m30s` or
+/// `1h250ms`, where the units are `d`, `h`, `m`, `s`, `ms`, `us` and `ns`.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::Duration;
+///
+/// # fn main() -> Result<(), runestick::ParseTimeError> {
+/// let duration = "5m30s".parse::<Duration>()?;
+/// assert_eq!(duration.as_millis(), 330_000);
+/// assert_eq!(duration.to_string(), "5m30s");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    nanos: i64,
+}
+
+impl Duration {
+    /// The empty duration.
+    pub const ZERO: Self = Self { nanos: 0 };
+
+    /// Construct a duration from nanoseconds.
+    pub fn from_nanos(nanos: i64) -> Self {
+        Self { nanos }
+    }
+
+    /// Construct a duration from milliseconds, returning `None` on overflow.
+    pub fn from_millis(millis: i64) -> Option<Self> {
+        Some(Self {
+            nanos: millis.checked_mul(NANOS_PER_MILLI)?,
+        })
+    }
+
+    /// Construct a duration from seconds, returning `None` on overflow.
+    pub fn from_secs(secs: i64) -> Option<Self> {
+        Some(Self {
+            nanos: secs.checked_mul(NANOS_PER_SEC)?,
+        })
+    }
+
+    /// The duration in nanoseconds.
+    pub fn as_nanos(self) -> i64 {
+        self.nanos
+    }
+
+    /// The duration in whole milliseconds, rounded towards zero.
+    pub fn as_millis(self) -> i64 {
+        self.nanos / NANOS_PER_MILLI
+    }
+
+    /// The duration in seconds.
+    pub fn as_secs_f64(self) -> f64 {
+        self.nanos as f64 / NANOS_PER_SEC as f64
+    }
+
+    /// Test if the duration is negative.
+    pub fn is_negative(self) -> bool {
+        self.nanos < 0
+    }
+
+    /// Convert into a standard duration, returning `None` if the duration is
+    /// negative.
+    pub fn to_std(self) -> Option<time::Duration> {
+        if self.nanos < 0 {
+            return None;
+        }
+
+        Some(time::Duration::from_nanos(self.nanos as u64))
+    }
+
+    /// Add two durations, returning `None` on overflow.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Self {
+            nanos: self.nanos.checked_add(other.nanos)?,
+        })
+    }
+
+    /// Subtract two durations, returning `None` on overflow.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(Self {
+            nanos: self.nanos.checked_sub(other.nanos)?,
+        })
+    }
+
+    /// Multiply the duration, returning `None` on overflow.
+    pub fn checked_mul(self, factor: i64) -> Option<Self> {
+        Some(Self {
+            nanos: self.nanos.checked_mul(factor)?,
+        })
+    }
+
+    /// Divide the duration, returning `None` if the divisor is zero.
+    pub fn checked_div(self, divisor: i64) -> Option<Self> {
+        Some(Self {
+            nanos: self.nanos.checked_div(divisor)?,
+        })
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.nanos == 0 {
+            return write!(f, "0s");
+        }
+
+        if self.nanos < 0 {
+            write!(f, "-")?;
+        }
+
+        let mut rest = self.nanos.unsigned_abs();
+
+        for (unit, nanos) in UNITS.iter() {
+            let nanos = *nanos as u64;
+            let amount = rest / nanos;
+
+            if amount > 0 {
+                write!(f, "{}{}", amount, unit)?;
+                rest -= amount * nanos;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ParseTimeError;
+
+    /// Parse a duration like `5m30s` or `-250ms`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseTimeError::duration(s);
+
+        let (negative, mut rest) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+
+        if rest.is_empty() {
+            return Err(error());
+        }
+
+        let mut nanos = 0i64;
+
+        while !rest.is_empty() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit()).ok_or_else(error)?;
+
+            if digits == 0 {
+                return Err(error());
+            }
+
+            let amount = rest[..digits].parse::<i64>().map_err(|_| error())?;
+            rest = &rest[digits..];
+
+            let len = rest
+                .find(|c: char| c.is_ascii_digit())
+                .unwrap_or(rest.len());
+
+            let unit = UNITS
+                .iter()
+                .find(|(unit, _)| *unit == &rest[..len])
+                .map(|(_, nanos)| *nanos)
+                .ok_or_else(error)?;
+
+            rest = &rest[len..];
+
+            nanos = amount
+                .checked_mul(unit)
+                .and_then(|n| nanos.checked_add(n))
+                .ok_or_else(error)?;
+        }
+
+        Ok(Self {
+            nanos: if negative { -nanos } else { nanos },
+        })
+    }
+}
+
+/// A point in time, stored as nanoseconds since the Unix epoch in UTC.
+///
+/// Timestamps are written in the RFC 3339 format, like
+/// `2020-09-01T12:30:00Z`.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Duration, Timestamp};
+///
+/// # fn main() -> Result<(), runestick::ParseTimeError> {
+/// let start = "2020-09-01T12:30:00+02:00".parse::<Timestamp>()?;
+/// let end = start.checked_add("90m".parse::<Duration>()?).unwrap();
+///
+/// assert_eq!(end.to_string(), "2020-09-01T12:00:00Z");
+/// assert_eq!(end.format("%d/%m/%Y %H:%M").unwrap(), "01/09/2020 12:00");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp {
+    nanos: i64,
+}
+
+impl Timestamp {
+    /// The current time according to the system clock.
+    pub fn now() -> Self {
+        let nanos = match time::SystemTime::now().duration_since(time::UNIX_EPOCH) {
+            Ok(duration) => duration.as_nanos() as i64,
+            Err(error) => -(error.duration().as_nanos() as i64),
+        };
+
+        Self { nanos }
+    }
+
+    /// Construct a timestamp from milliseconds since the Unix epoch,
+    /// returning `None` on overflow.
+    pub fn from_millis(millis: i64) -> Option<Self> {
+        Some(Self {
+            nanos: millis.checked_mul(NANOS_PER_MILLI)?,
+        })
+    }
+
+    /// Milliseconds since the Unix epoch, rounded towards negative infinity.
+    pub fn as_millis(self) -> i64 {
+        div_floor(self.nanos, NANOS_PER_MILLI)
+    }
+
+    /// Add a duration to the timestamp, returning `None` on overflow.
+    pub fn checked_add(self, duration: Duration) -> Option<Self> {
+        Some(Self {
+            nanos: self.nanos.checked_add(duration.nanos)?,
+        })
+    }
+
+    /// Subtract a duration from the timestamp, returning `None` on overflow.
+    pub fn checked_sub(self, duration: Duration) -> Option<Self> {
+        Some(Self {
+            nanos: self.nanos.checked_sub(duration.nanos)?,
+        })
+    }
+
+    /// The duration from the given timestamp to this one, which is negative
+    /// if the given timestamp is later. Returns `None` on overflow.
+    pub fn duration_since(self, earlier: Self) -> Option<Duration> {
+        Some(Duration {
+            nanos: self.nanos.checked_sub(earlier.nanos)?,
+        })
+    }
+
+    /// Format the timestamp in UTC.
+    ///
+    /// The format supports the specifiers `%Y` (year), `%m` (month), `%d`
+    /// (day), `%H` (hour), `%M` (minute), `%S` (second), `%f` (milliseconds),
+    /// `%s` (seconds since the Unix epoch), and `%%` (a literal `%`).
+    pub fn format(self, format: &str) -> Result<String, TimeFormatError> {
+        let parts = Parts::from_nanos(self.nanos);
+        let mut out = String::new();
+        let mut it = format.chars();
+
+        while let Some(c) = it.next() {
+            if c != '%' {
+                out.push(c);
+                continue;
+            }
+
+            // NB: writing to a string can't fail.
+            let _ = match it.next() {
+                Some('Y') => write!(out, "{:04}", parts.year),
+                Some('m') => write!(out, "{:02}", parts.month),
+                Some('d') => write!(out, "{:02}", parts.day),
+                Some('H') => write!(out, "{:02}", parts.hour),
+                Some('M') => write!(out, "{:02}", parts.minute),
+                Some('S') => write!(out, "{:02}", parts.second),
+                Some('f') => write!(out, "{:03}", parts.nanos / NANOS_PER_MILLI),
+                Some('s') => write!(out, "{}", div_floor(self.nanos, NANOS_PER_SEC)),
+                Some('%') => write!(out, "%"),
+                specifier => return Err(TimeFormatError { specifier }),
+            };
+        }
+
+        Ok(out)
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let p = Parts::from_nanos(self.nanos);
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            p.year, p.month, p.day, p.hour, p.minute, p.second
+        )?;
+
+        if p.nanos % NANOS_PER_MILLI == 0 {
+            if p.nanos != 0 {
+                write!(f, ".{:03}", p.nanos / NANOS_PER_MILLI)?;
+            }
+        } else {
+            write!(f, ".{:09}", p.nanos)?;
+        }
+
+        write!(f, "Z")
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = ParseTimeError;
+
+    /// Parse an RFC 3339 timestamp like `2020-09-01T12:30:00.250+02:00`, or a
+    /// date like `2020-09-01` which is taken to be midnight in UTC.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_timestamp(s).ok_or_else(|| ParseTimeError::timestamp(s))
+    }
+}
+
+/// Error raised when parsing a [Duration] or a [Timestamp] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeError {
+    what: &'static str,
+    input: String,
+}
+
+impl ParseTimeError {
+    fn duration(input: &str) -> Self {
+        Self {
+            what: "duration",
+            input: input.to_owned(),
+        }
+    }
+
+    fn timestamp(input: &str) -> Self {
+        Self {
+            what: "timestamp",
+            input: input.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ParseTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid {} `{}`", self.what, self.input)
+    }
+}
+
+impl std::error::Error for ParseTimeError {}
+
+/// Error raised when formatting a [Timestamp] with an unsupported format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeFormatError {
+    specifier: Option<char>,
+}
+
+impl fmt::Display for TimeFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.specifier {
+            Some(c) => write!(f, "unsupported format specifier `%{}`", c),
+            None => write!(f, "format ends with an incomplete specifier `%`"),
+        }
+    }
+}
+
+impl std::error::Error for TimeFormatError {}
+
+/// The calendar parts of a timestamp in UTC.
+struct Parts {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    nanos: i64,
+}
+
+impl Parts {
+    fn from_nanos(nanos: i64) -> Self {
+        let secs = div_floor(nanos, NANOS_PER_SEC);
+        let days = div_floor(secs, SECS_PER_DAY);
+        let of_day = secs - days * SECS_PER_DAY;
+        let (year, month, day) = civil_from_days(days);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: of_day / 3_600,
+            minute: of_day % 3_600 / 60,
+            second: of_day % 60,
+            nanos: nanos - secs * NANOS_PER_SEC,
+        }
+    }
+}
+
+/// Integer division rounding towards negative infinity.
+fn div_floor(a: i64, b: i64) -> i64 {
+    let q = a / b;
+
+    if (a % b != 0) && ((a < 0) != (b < 0)) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+/// Convert days since the Unix epoch into a year, month and day.
+///
+/// See <http://howardhinnant.github.io/date_algorithms.html>.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = div_floor(z, 146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Convert a year, month and day into days since the Unix epoch.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = div_floor(year, 400);
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// Test if the given year is a leap year.
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// The number of days in the given month.
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+fn parse_timestamp(s: &str) -> Option<Timestamp> {
+    let mut p = Cursor { s };
+
+    let year = p.number(4)?;
+    p.expect('-')?;
+    let month = p.number(2)?;
+    p.expect('-')?;
+    let day = p.number(2)?;
+
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return None;
+    }
+
+    let mut secs = days_from_civil(year, month, day) * SECS_PER_DAY;
+    let mut nanos = 0;
+
+    if !p.s.is_empty() {
+        match p.next()? {
+            'T' | 't' | ' ' => (),
+            _ => return None,
+        }
+
+        let hour = p.number(2)?;
+        p.expect(':')?;
+        let minute = p.number(2)?;
+        p.expect(':')?;
+        let second = p.number(2)?;
+
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        secs += hour * 3_600 + minute * 60 + second;
+
+        if p.s.starts_with('.') {
+            p.next()?;
+            let len = p.s.find(|c: char| !c.is_ascii_digit()).unwrap_or(p.s.len());
+
+            if len == 0 || len > 9 {
+                return None;
+            }
+
+            nanos = p.number(len)? * 10i64.pow(9 - len as u32);
+        }
+
+        let offset = match p.next()? {
+            'Z' | 'z' => 0,
+            sign @ '+' | sign @ '-' => {
+                let hours = p.number(2)?;
+                p.expect(':')?;
+                let minutes = p.number(2)?;
+                let offset = hours * 3_600 + minutes * 60;
+
+                if sign == '-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return None,
+        };
+
+        secs -= offset;
+    }
+
+    if !p.s.is_empty() {
+        return None;
+    }
+
+    let nanos = secs.checked_mul(NANOS_PER_SEC)?.checked_add(nanos)?;
+    Some(Timestamp { nanos })
+}
+
+/// A cursor over the timestamp being parsed.
+struct Cursor<'a> {
+    s: &'a str,
+}
+
+impl Cursor<'_> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.s.chars().next()?;
+        self.s = &self.s[c.len_utf8()..];
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Option<()> {
+        if self.next()? == expected {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    /// Parse a number with exactly the given number of digits.
+    fn number(&mut self, len: usize) -> Option<i64> {
+        let digits = self.s.get(..len)?;
+
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        self.s = &self.s[len..];
+        digits.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Duration, Timestamp};
+
+    #[test]
+    fn test_duration_roundtrip() {
+        for s in &["0s", "5m30s", "1d2h", "-250ms", "1s1ns", "3us"] {
+            let duration = s.parse::<Duration>().unwrap();
+            assert_eq!(duration.to_string(), *s);
+        }
+
+        assert_eq!("90s".parse::<Duration>().unwrap().to_string(), "1m30s");
+        assert!("".parse::<Duration>().is_err());
+        assert!("5".parse::<Duration>().is_err());
+        assert!("m".parse::<Duration>().is_err());
+        assert!("5x".parse::<Duration>().is_err());
+    }
+
+    #[test]
+    fn test_timestamp_roundtrip() {
+        for s in &[
+            "1970-01-01T00:00:00Z",
+            "2020-02-29T23:59:59.500Z",
+            "1969-12-31T23:59:59.000000001Z",
+            "2100-03-01T00:00:00Z",
+        ] {
+            let timestamp = s.parse::<Timestamp>().unwrap();
+            assert_eq!(timestamp.to_string(), *s);
+        }
+
+        assert_eq!(
+            "2020-09-01".parse::<Timestamp>().unwrap().as_millis(),
+            1_598_918_400_000
+        );
+        assert!("2021-02-29".parse::<Timestamp>().is_err());
+        assert!("2400-01-01T00:00:00Z".parse::<Timestamp>().is_err());
+        assert!("2020-09-01T12:00:00".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn test_serialize() {
+        use crate::ToValue as _;
+
+        let value = (
+            Duration::from_secs(90).unwrap(),
+            Timestamp::from_millis(1_500).unwrap(),
+        )
+            .to_value()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"["1m30s","1970-01-01T00:00:01.500Z"]"#
+        );
+    }
+}
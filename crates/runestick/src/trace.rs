@@ -0,0 +1,340 @@
+//! Tracing of the instructions executed by a virtual machine.
+
+use crate::{Hash, Inst, Item, Stack, Unit, UnitFn, Value, VmError, VmErrorKind};
+use std::collections::VecDeque;
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+/// The maximum number of characters of a string included in the summary of
+/// the top of the stack.
+const MAX_STRING: usize = 32;
+
+/// Configuration of the instruction tracing performed by a virtual machine,
+/// as installed with [Vm::trace][crate::Vm::trace].
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Item, TraceBuffer, TraceConfig, Unit, Vm};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// let unit = Arc::new(Unit::default());
+///
+/// let buffer = TraceBuffer::new(1024);
+///
+/// let mut vm = Vm::new(context, unit);
+/// vm.trace(TraceConfig::buffer(buffer.clone()))?;
+/// assert!(buffer.entries().is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub struct TraceConfig {
+    sink: TraceSink,
+    functions: Vec<Item>,
+}
+
+impl TraceConfig {
+    /// Trace instructions by calling the given callback with each instruction
+    /// before it's executed.
+    pub fn callback<F>(callback: F) -> Self
+    where
+        F: FnMut(&TraceEntry) + Send + 'static,
+    {
+        Self {
+            sink: TraceSink::Callback(Box::new(callback)),
+            functions: Vec::new(),
+        }
+    }
+
+    /// Trace instructions into the given ring buffer, which retains the most
+    /// recently executed instructions.
+    pub fn buffer(buffer: TraceBuffer) -> Self {
+        Self {
+            sink: TraceSink::Buffer(buffer),
+            functions: Vec::new(),
+        }
+    }
+
+    /// Only trace the instructions of the given function.
+    ///
+    /// This can be called multiple times to trace several functions. If it's
+    /// never called, all instructions are traced.
+    pub fn function(mut self, item: Item) -> Self {
+        self.functions.push(item);
+        self
+    }
+}
+
+impl fmt::Debug for TraceConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TraceConfig")
+            .field("functions", &self.functions)
+            .finish()
+    }
+}
+
+/// Where traced instructions end up.
+enum TraceSink {
+    Callback(Box<dyn FnMut(&TraceEntry) + Send>),
+    Buffer(TraceBuffer),
+}
+
+/// A bounded buffer of traced instructions, which drops the oldest entries
+/// once it's full.
+///
+/// Buffers are cheap to clone, and clones refer to the same entries.
+#[derive(Debug, Clone)]
+pub struct TraceBuffer {
+    inner: Arc<Mutex<TraceBufferInner>>,
+}
+
+impl TraceBuffer {
+    /// Construct a new buffer which retains at most `capacity` entries.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(TraceBufferInner {
+                capacity,
+                entries: VecDeque::with_capacity(capacity),
+                dropped: 0,
+            })),
+        }
+    }
+
+    /// Get a copy of the entries in the buffer, from oldest to newest.
+    pub fn entries(&self) -> Vec<TraceEntry> {
+        self.lock().entries.iter().cloned().collect()
+    }
+
+    /// The number of entries which have been dropped from the buffer to make
+    /// room for newer ones.
+    pub fn dropped(&self) -> usize {
+        self.lock().dropped
+    }
+
+    /// Clear the buffer.
+    pub fn clear(&self) {
+        let mut inner = self.lock();
+        inner.entries.clear();
+        inner.dropped = 0;
+    }
+
+    fn push(&self, entry: TraceEntry) {
+        let mut inner = self.lock();
+
+        if inner.capacity == 0 {
+            inner.dropped += 1;
+            return;
+        }
+
+        if inner.entries.len() == inner.capacity {
+            inner.entries.pop_front();
+            inner.dropped += 1;
+        }
+
+        inner.entries.push_back(entry);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, TraceBufferInner> {
+        // NB: the buffer is always left in a consistent state, so it can be
+        // used even if a thread panicked while holding the lock.
+        match self.inner.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TraceBufferInner {
+    capacity: usize,
+    entries: VecDeque<TraceEntry>,
+    dropped: usize,
+}
+
+/// A single traced instruction, as it was about to be executed.
+#[derive(Debug, Clone)]
+pub struct TraceEntry {
+    /// The instruction pointer of the instruction.
+    pub ip: usize,
+    /// The instruction.
+    pub inst: Inst,
+    /// A summary of the value on top of the stack before the instruction was
+    /// executed, or `None` if the stack is empty.
+    pub top: Option<String>,
+}
+
+impl TraceEntry {
+    /// Pretty print the entry, using the debug information of the given unit
+    /// to name the function the instruction belongs to along with its label
+    /// and comment if there are any.
+    ///
+    /// The unit must be the one the traced virtual machine was executing.
+    pub fn display<'a>(&'a self, unit: &'a Unit) -> impl fmt::Display + 'a {
+        TraceDisplay { entry: self, unit }
+    }
+}
+
+struct TraceDisplay<'a> {
+    entry: &'a TraceEntry,
+    unit: &'a Unit,
+}
+
+impl fmt::Display for TraceDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let TraceEntry { ip, top, .. } = self.entry;
+        let debug = self.unit.debug_info();
+
+        write!(f, "{:04}", ip)?;
+
+        let function = debug.and_then(|debug| {
+            let (offset, hash) = self.unit.function_containing(*ip)?;
+            let signature = debug.functions.get(&hash)?;
+            Some((offset, signature))
+        });
+
+        if let Some((offset, signature)) = function {
+            write!(f, " {}+{}", signature.path, ip - offset)?;
+        }
+
+        let inst = debug.and_then(|debug| debug.instruction_at(*ip));
+
+        if let Some(label) = inst.and_then(|inst| inst.label.as_ref()) {
+            write!(f, " {}:", label)?;
+        }
+
+        write!(f, " {}", self.entry.inst)?;
+
+        if let Some(top) = top {
+            write!(f, " [{}]", top)?;
+        }
+
+        if let Some(comment) = inst.and_then(|inst| inst.comment.as_ref()) {
+            write!(f, " // {}", comment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// An installed tracer, shared with virtual machines spawned to call async
+/// functions and generators.
+pub(crate) struct Tracer {
+    /// Ranges of instructions to trace, or `None` to trace everything.
+    ranges: Option<Vec<Range<usize>>>,
+    sink: Mutex<TraceSink>,
+}
+
+impl Tracer {
+    /// Construct a tracer for the given unit, resolving the functions to
+    /// trace into the ranges of instructions they occupy.
+    pub(crate) fn new(config: TraceConfig, unit: &Unit) -> Result<Self, VmError> {
+        let TraceConfig { sink, functions } = config;
+
+        let ranges = if functions.is_empty() {
+            None
+        } else {
+            let mut ranges = Vec::with_capacity(functions.len());
+
+            for item in functions {
+                let hash = Hash::type_hash(&item);
+
+                let offset = match unit.lookup(hash) {
+                    Some(UnitFn::Offset { offset, .. }) => offset,
+                    _ => return Err(VmError::from(VmErrorKind::MissingFunction { hash })),
+                };
+
+                let end = function_offsets(unit)
+                    .find(|o| *o > offset)
+                    .unwrap_or_else(|| unit.instructions().len());
+
+                ranges.push(offset..end);
+            }
+
+            Some(ranges)
+        };
+
+        Ok(Self {
+            ranges,
+            sink: Mutex::new(sink),
+        })
+    }
+
+    /// Trace the instruction at the given instruction pointer, if it's
+    /// included by the filter.
+    pub(crate) fn trace(&self, ip: usize, inst: Inst, stack: &Stack) {
+        if let Some(ranges) = &self.ranges {
+            if !ranges.iter().any(|range| range.contains(&ip)) {
+                return;
+            }
+        }
+
+        let entry = TraceEntry {
+            ip,
+            inst,
+            top: stack.last().ok().map(summarize),
+        };
+
+        let mut sink = match self.sink.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        match &mut *sink {
+            TraceSink::Callback(callback) => callback(&entry),
+            TraceSink::Buffer(buffer) => buffer.push(entry),
+        }
+    }
+}
+
+impl fmt::Debug for Tracer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tracer")
+            .field("ranges", &self.ranges)
+            .finish()
+    }
+}
+
+/// The sorted offsets of all functions in the unit.
+fn function_offsets(unit: &Unit) -> impl Iterator<Item = usize> {
+    let mut offsets = unit
+        .iter_functions()
+        .filter_map(|(_, f)| match f {
+            UnitFn::Offset { offset, .. } => Some(*offset),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    offsets.sort();
+    offsets.into_iter()
+}
+
+/// Summarize a value in a single short line.
+fn summarize(value: &Value) -> String {
+    match value {
+        Value::Unit => String::from("()"),
+        Value::Bool(value) => format!("{:?}", value),
+        Value::Byte(value) => format!("{:?}", value),
+        Value::Char(value) => format!("{:?}", value),
+        Value::Integer(value) => format!("{:?}", value),
+        Value::Float(value) => format!("{:?}", value),
+        Value::StaticString(string) => summarize_str(string.as_str()),
+        Value::String(string) => match string.borrow_ref() {
+            Ok(string) => summarize_str(string.as_str()),
+            Err(..) => String::from("String"),
+        },
+        value => match value.type_info() {
+            Ok(type_info) => type_info.to_string(),
+            Err(..) => String::from("?"),
+        },
+    }
+}
+
+fn summarize_str(string: &str) -> String {
+    match string.char_indices().nth(MAX_STRING) {
+        Some((n, _)) => format!("{:?}...", &string[..n]),
+        None => format!("{:?}", string),
+    }
+}
@@ -1,4 +1,5 @@
 use crate::{Hash, StaticType};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp;
 use std::fmt;
 use std::hash;
@@ -22,6 +23,26 @@ impl Type {
     }
 }
 
+/// Types are serialized as their type hash, since static types can't be
+/// deserialized.
+impl Serialize for Type {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.as_type_hash().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Type {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::Hash(Hash::deserialize(deserializer)?))
+    }
+}
+
 impl cmp::PartialEq for Type {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
@@ -4,18 +4,25 @@
 //! metadata like function locations.
 
 use crate::collections::HashMap;
-use crate::{Call, DebugInfo, Hash, Inst, StaticString, Type, VmError, VmErrorKind};
+use crate::{
+    AbiError, Call, Context, DebugInfo, Frontmatter, Hash, Inst, Interface, InterfaceError, Item,
+    StaticString, Type, UnitAbi, VmError, VmErrorKind,
+};
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Instructions from a single source file.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Unit {
     /// The instructions contained in the source file.
     instructions: Vec<Inst>,
     /// Where functions are located in the collection of instructions.
+    #[serde(serialize_with = "serialize_sorted")]
     functions: HashMap<Hash, UnitFn>,
     /// Declared types.
+    #[serde(serialize_with = "serialize_sorted")]
     types: HashMap<Hash, UnitTypeInfo>,
     /// A static string.
     static_strings: Vec<Arc<StaticString>>,
@@ -28,12 +35,29 @@ pub struct Unit {
     ///
     /// All keys are sorted with the default string sort.
     static_object_keys: Vec<Box<[String]>>,
+    /// The items of statics declared in the unit, by slot.
+    statics: Vec<Item>,
+    /// Functions declared with `#[memoize]` in the unit, by slot.
+    memoized: Vec<UnitMemoize>,
+    /// Discriminants of enum variants, by the hash of the variant.
+    #[serde(serialize_with = "serialize_sorted")]
+    discriminants: HashMap<Hash, i64>,
+    /// Enum variants, by the hash of the enum and their discriminant.
+    #[serde(serialize_with = "serialize_sorted")]
+    variants: HashMap<(Hash, i64), Hash>,
     /// Debug info if available for unit.
     debug: Option<Box<DebugInfo>>,
+    /// Frontmatter declared in the entry source of the unit.
+    frontmatter: Frontmatter,
+    /// Lifecycle hooks declared in the unit.
+    hooks: UnitHooks,
+    /// Metadata on the environment the unit was compiled in, if available.
+    abi: Option<UnitAbi>,
 }
 
 impl Unit {
     /// Construct a new unit with the given content.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         instructions: Vec<Inst>,
         functions: HashMap<Hash, UnitFn>,
@@ -41,7 +65,14 @@ impl Unit {
         static_strings: Vec<Arc<StaticString>>,
         static_bytes: Vec<Vec<u8>>,
         static_object_keys: Vec<Box<[String]>>,
+        statics: Vec<Item>,
+        memoized: Vec<UnitMemoize>,
+        discriminants: HashMap<Hash, i64>,
+        variants: HashMap<(Hash, i64), Hash>,
         debug: Option<Box<DebugInfo>>,
+        frontmatter: Frontmatter,
+        hooks: UnitHooks,
+        abi: Option<UnitAbi>,
     ) -> Self {
         Self {
             instructions,
@@ -50,7 +81,14 @@ impl Unit {
             static_strings,
             static_bytes,
             static_object_keys,
+            statics,
+            memoized,
+            discriminants,
+            variants,
             debug,
+            frontmatter,
+            hooks,
+            abi,
         }
     }
 
@@ -65,6 +103,40 @@ impl Unit {
         Some(&**debug)
     }
 
+    /// Access the frontmatter declared in the entry source of the unit.
+    pub fn frontmatter(&self) -> &Frontmatter {
+        &self.frontmatter
+    }
+
+    /// Access the lifecycle hooks declared in the unit.
+    pub fn hooks(&self) -> &UnitHooks {
+        &self.hooks
+    }
+
+    /// Access metadata on the environment the unit was compiled in, if
+    /// available.
+    pub fn abi(&self) -> Option<&UnitAbi> {
+        self.abi.as_ref()
+    }
+
+    /// Check that the unit is compatible with the current runtime and the
+    /// given context.
+    ///
+    /// Units without [abi][Unit::abi] metadata, like ones constructed by
+    /// hand, are always considered compatible.
+    pub fn check_abi(&self, context: &Context) -> Result<(), AbiError> {
+        match &self.abi {
+            Some(abi) => abi.check(context),
+            None => Ok(()),
+        }
+    }
+
+    /// Check that the unit implements every function in the given interface,
+    /// with the expected number of arguments and async-ness.
+    pub fn validate_interface(&self, interface: &Interface) -> Result<(), InterfaceError> {
+        interface.validate(self)
+    }
+
     /// Get the instruction at the given instruction pointer.
     pub fn instruction_at(&self, ip: usize) -> Option<&Inst> {
         self.instructions.get(ip)
@@ -85,19 +157,38 @@ impl Unit {
         })
     }
 
+    /// Access all instructions in order.
+    pub(crate) fn instructions(&self) -> &[Inst] {
+        &self.instructions
+    }
+
     /// Iterate over all instructions in order.
     pub fn iter_instructions(&self) -> impl Iterator<Item = Inst> + '_ {
         self.instructions.iter().copied()
     }
 
-    /// Iterate over known functions.
+    /// Find the offset and hash of the function the given instruction pointer
+    /// belongs to.
+    pub(crate) fn function_containing(&self, ip: usize) -> Option<(usize, Hash)> {
+        self.functions
+            .iter()
+            .filter_map(|(hash, f)| match f {
+                UnitFn::Offset { offset, .. } if *offset <= ip => Some((*offset, *hash)),
+                _ => None,
+            })
+            .max_by_key(|(offset, _)| *offset)
+    }
+
+    /// Iterate over known functions, ordered by their hash.
     pub fn iter_functions(&self) -> impl Iterator<Item = (Hash, &UnitFn)> + '_ {
-        let mut it = self.functions.iter();
+        let mut functions = self
+            .functions
+            .iter()
+            .map(|(hash, f)| (*hash, f))
+            .collect::<Vec<_>>();
 
-        std::iter::from_fn(move || {
-            let (k, v) = it.next()?;
-            Some((*k, v))
-        })
+        functions.sort_by_key(|(hash, _)| *hash);
+        functions.into_iter()
     }
 
     /// Lookup the static string by slot, if it exists.
@@ -108,6 +199,21 @@ impl Unit {
             .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticString { slot }))?)
     }
 
+    /// Lookup the item of the static in the given slot, if it exists.
+    pub fn lookup_static(&self, slot: usize) -> Result<&Item, VmError> {
+        self.statics
+            .get(slot)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingStatic { slot }))
+    }
+
+    /// Lookup how the results of the memoized function in the given slot are
+    /// cached, if it exists.
+    pub fn lookup_memoized(&self, slot: usize) -> Result<&UnitMemoize, VmError> {
+        self.memoized
+            .get(slot)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingMemoized { slot }))
+    }
+
     /// Lookup the static byte string by slot, if it exists.
     pub fn lookup_bytes(&self, slot: usize) -> Result<&[u8], VmError> {
         Ok(self
@@ -126,10 +232,48 @@ impl Unit {
     pub fn lookup(&self, hash: Hash) -> Option<UnitFn> {
         self.functions.get(&hash).copied()
     }
+
+    /// Check that the number of arguments passed to the function at the
+    /// given offset matches the expected number of arguments.
+    ///
+    /// If debug information is available, the raised error includes the
+    /// signature of the function, like `connect(host, port)`.
+    pub(crate) fn check_args(
+        &self,
+        offset: usize,
+        actual: usize,
+        expected: usize,
+    ) -> Result<(), VmError> {
+        if actual == expected {
+            return Ok(());
+        }
+
+        let signature = self.debug_info().and_then(|d| d.function_at(offset));
+
+        Err(VmError::from(match signature {
+            Some((_, signature)) => VmErrorKind::BadFunctionArgumentCount {
+                signature: signature.clone(),
+                actual,
+                expected,
+            },
+            None => VmErrorKind::BadArgumentCount { actual, expected },
+        }))
+    }
+
+    /// Lookup the discriminant of the enum variant with the given hash.
+    pub fn lookup_discriminant(&self, hash: Hash) -> Option<i64> {
+        self.discriminants.get(&hash).copied()
+    }
+
+    /// Lookup the hash of the variant of the given enum which has the given
+    /// discriminant.
+    pub fn lookup_variant(&self, enum_hash: Hash, discriminant: i64) -> Option<Hash> {
+        self.variants.get(&(enum_hash, discriminant)).copied()
+    }
 }
 
 /// The kind and necessary information on registered functions.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum UnitFn {
     /// Offset to call a "real" function.
     Offset {
@@ -180,11 +324,60 @@ impl fmt::Display for UnitFn {
     }
 }
 
+/// Lifecycle hooks declared in a unit.
+///
+/// Hooks are functions without arguments named `__init__` or `__shutdown__`,
+/// which can be declared directly in any module of the unit. They are run by a
+/// [Runtime][crate::Runtime] when the unit is installed and torn down.
+///
+/// Finalizers are declared for a struct through `impl Drop`, and are run by
+/// the virtual machine when the last reference to a value of that type is
+/// released.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnitHooks {
+    /// Functions named `__init__`, in the order they were declared in.
+    pub init: Vec<Hash>,
+    /// Functions named `__shutdown__`, in the order they were declared in.
+    pub shutdown: Vec<Hash>,
+    /// Finalizers, keyed by the hash of the type they are declared for.
+    #[serde(serialize_with = "serialize_sorted")]
+    pub finalizers: HashMap<Hash, Hash>,
+}
+
+/// How the results of a function declared with `#[memoize]` are cached.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitMemoize {
+    /// The item of the memoized function.
+    pub item: Item,
+    /// The maximum number of results which are cached, if limited.
+    pub capacity: Option<usize>,
+    /// How long a result is cached for, if it expires.
+    pub ttl: Option<Duration>,
+}
+
 /// Type information on a unit.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct UnitTypeInfo {
     /// A type declared in a unit.
     pub hash: Hash,
+    /// The item of the type.
+    pub item: Item,
     /// value type of the given type.
     pub value_type: Type,
 }
+
+/// Serialize a map ordered by its keys, so that serialized units are
+/// reproducible.
+pub(crate) fn serialize_sorted<K, V, S>(
+    map: &HashMap<K, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    let mut entries = map.iter().collect::<Vec<_>>();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    serializer.collect_map(entries)
+}
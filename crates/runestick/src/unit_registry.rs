@@ -0,0 +1,116 @@
+use crate::collections::HashMap;
+use crate::{Context, Hash, Item, Stack, Unit, UnitFn, Value, Vm, VmError, VmErrorKind};
+use std::sync::{Arc, RwLock};
+
+/// A registry of units which are registered under names, so that scripts can
+/// call functions in other units at runtime through the `std::dynamic` module.
+///
+/// The registry is a cheap handle which can be cloned and shared across
+/// threads, and every clone refers to the same units.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, UnitRegistry};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let registry = UnitRegistry::new();
+///
+/// let mut context = Context::with_default_modules()?;
+/// context.install(&runestick::modules::dynamic::module(&registry)?)?;
+/// let context = Arc::new(context);
+///
+/// // Units compiled against the context can now be registered with
+/// // `registry.insert("plugin", context.clone(), unit)`, after which scripts
+/// // can call their functions with `std::dynamic::call("plugin", "handler", [])`.
+/// assert!(!registry.contains("plugin"));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default, Clone)]
+pub struct UnitRegistry {
+    units: Arc<RwLock<HashMap<String, RegisteredUnit>>>,
+}
+
+#[derive(Clone)]
+struct RegisteredUnit {
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+}
+
+impl UnitRegistry {
+    /// Construct a new empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a unit under the given name, together with the context it
+    /// runs in. Returns the unit which was previously registered under the
+    /// name, if any.
+    pub fn insert<N>(&self, name: N, context: Arc<Context>, unit: Arc<Unit>) -> Option<Arc<Unit>>
+    where
+        N: Into<String>,
+    {
+        let mut units = self.units.write().unwrap_or_else(|e| e.into_inner());
+        let old = units.insert(name.into(), RegisteredUnit { context, unit })?;
+        Some(old.unit)
+    }
+
+    /// Remove the unit registered under the given name, returning it if it
+    /// was registered.
+    pub fn remove(&self, name: &str) -> Option<Arc<Unit>> {
+        let mut units = self.units.write().unwrap_or_else(|e| e.into_inner());
+        Some(units.remove(name)?.unit)
+    }
+
+    /// Test if a unit is registered under the given name.
+    pub fn contains(&self, name: &str) -> bool {
+        let units = self.units.read().unwrap_or_else(|e| e.into_inner());
+        units.contains_key(name)
+    }
+
+    /// The names of all registered units, in sorted order.
+    pub fn names(&self) -> Vec<String> {
+        let units = self.units.read().unwrap_or_else(|e| e.into_inner());
+        let mut names = units.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Call a function in the unit registered under the given name, running
+    /// it to completion in a new virtual machine.
+    ///
+    /// The function is named by its path in the unit, like `handler` or
+    /// `handlers::on_load`.
+    pub fn call(&self, name: &str, function: &str, args: Vec<Value>) -> Result<Value, VmError> {
+        let registered = {
+            let units = self.units.read().unwrap_or_else(|e| e.into_inner());
+
+            units
+                .get(name)
+                .cloned()
+                .ok_or_else(|| VmError::from(VmErrorKind::MissingUnit { name: name.into() }))?
+        };
+
+        let hash = Hash::type_hash(Item::of(function.split("::")));
+
+        let offset = match registered.unit.lookup(hash) {
+            Some(UnitFn::Offset {
+                offset,
+                args: expected,
+                ..
+            }) => {
+                registered.unit.check_args(offset, args.len(), expected)?;
+                offset
+            }
+            _ => return Err(VmError::from(VmErrorKind::MissingFunction { hash })),
+        };
+
+        registered.unit.check_abi(&registered.context)?;
+
+        let mut vm = Vm::new_with_stack(registered.context, registered.unit, Stack::from(args));
+        vm.set_ip(offset);
+        vm.complete()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
-    Any, Bytes, Function, Future, Generator, GeneratorState, Hash, OwnedMut, OwnedRef, RawOwnedMut,
-    RawOwnedRef, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
+    Any, Bytes, Function, Future, Generator, GeneratorState, Handle, Hash, OwnedMut, OwnedRef,
+    RawOwnedMut, RawOwnedRef, Shared, StaticString, Stream, Tuple, Type, TypeInfo, VmError,
 };
 use std::any;
 use std::fmt;
@@ -135,6 +135,8 @@ pub enum Value {
     VariantObject(Shared<VariantObject>),
     /// A stored function pointer.
     Function(Shared<Function>),
+    /// An opaque handle to a resource owned by the host.
+    Handle(Shared<Handle>),
     /// An opaque value that can be downcasted.
     Any(Shared<Any>),
 }
@@ -331,6 +333,15 @@ impl Value {
         }
     }
 
+    /// Try to coerce value into a handle.
+    #[inline]
+    pub fn into_handle(self) -> Result<Shared<Handle>, VmError> {
+        match self {
+            Self::Handle(handle) => Ok(handle),
+            actual => Err(VmError::expected::<Handle>(actual.type_info()?)),
+        }
+    }
+
     /// Try to coerce value into an opaque value.
     #[inline]
     pub fn into_any(self) -> Result<Shared<Any>, VmError> {
@@ -388,6 +399,87 @@ impl Value {
         }
     }
 
+    /// Freeze the value, making it read-only.
+    ///
+    /// This only affects the value itself and not any values it contains. See
+    /// [freeze_deep][Self::freeze_deep] for how to freeze a value
+    /// transitively.
+    ///
+    /// Values which are not reference counted are unaffected.
+    pub fn freeze(&self) {
+        match self {
+            Self::String(string) => string.freeze(),
+            Self::Bytes(bytes) => bytes.freeze(),
+            Self::Vec(vec) => vec.freeze(),
+            Self::Tuple(tuple) => tuple.freeze(),
+            Self::Object(object) => object.freeze(),
+            Self::Future(future) => future.freeze(),
+            Self::Stream(stream) => stream.freeze(),
+            Self::Generator(generator) => generator.freeze(),
+            Self::GeneratorState(state) => state.freeze(),
+            Self::Option(option) => option.freeze(),
+            Self::Result(result) => result.freeze(),
+            Self::TypedTuple(tuple) => tuple.freeze(),
+            Self::TupleVariant(tuple) => tuple.freeze(),
+            Self::TypedObject(object) => object.freeze(),
+            Self::VariantObject(object) => object.freeze(),
+            Self::Function(function) => function.freeze(),
+            Self::Handle(handle) => handle.freeze(),
+            Self::Any(any) => any.freeze(),
+            _ => (),
+        }
+    }
+
+    /// Freeze the value and every value reachable from it.
+    pub fn freeze_deep(&self) -> Result<(), VmError> {
+        crate::deep::freeze_deep(self)
+    }
+
+    /// Render the value as an indented, human readable string.
+    ///
+    /// Nested collections are printed one element per line, subject to the
+    /// given limits. Cycles and values which are currently borrowed
+    /// exclusively are marked instead of causing an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{PrettyLimits, Value};
+    ///
+    /// let value = Value::vec(vec![Value::Integer(1), Value::vec(vec![])]);
+    /// assert_eq!(value.display_pretty(PrettyLimits::default()), "[\n    1,\n    [],\n]");
+    /// ```
+    pub fn display_pretty(&self, limits: crate::PrettyLimits) -> String {
+        crate::pretty::display_pretty(self, limits)
+    }
+
+    /// Test if the value is frozen.
+    ///
+    /// Values which are not reference counted are never frozen.
+    pub fn is_frozen(&self) -> bool {
+        match self {
+            Self::String(string) => string.is_frozen(),
+            Self::Bytes(bytes) => bytes.is_frozen(),
+            Self::Vec(vec) => vec.is_frozen(),
+            Self::Tuple(tuple) => tuple.is_frozen(),
+            Self::Object(object) => object.is_frozen(),
+            Self::Future(future) => future.is_frozen(),
+            Self::Stream(stream) => stream.is_frozen(),
+            Self::Generator(generator) => generator.is_frozen(),
+            Self::GeneratorState(state) => state.is_frozen(),
+            Self::Option(option) => option.is_frozen(),
+            Self::Result(result) => result.is_frozen(),
+            Self::TypedTuple(tuple) => tuple.is_frozen(),
+            Self::TupleVariant(tuple) => tuple.is_frozen(),
+            Self::TypedObject(object) => object.is_frozen(),
+            Self::VariantObject(object) => object.is_frozen(),
+            Self::Function(function) => function.is_frozen(),
+            Self::Handle(handle) => handle.is_frozen(),
+            Self::Any(any) => any.is_frozen(),
+            _ => false,
+        }
+    }
+
     /// Get the type information for the current value.
     pub fn value_type(&self) -> Result<Type, VmError> {
         Ok(match self {
@@ -410,6 +502,7 @@ impl Value {
             Self::Result(..) => Type::StaticType(crate::RESULT_TYPE),
             Self::Option(..) => Type::StaticType(crate::OPTION_TYPE),
             Self::Function(..) => Type::StaticType(crate::FUNCTION_TYPE),
+            Self::Handle(..) => Type::StaticType(crate::HANDLE_TYPE),
             Self::Type(hash) => Type::Hash(*hash),
             Self::TypedObject(object) => Type::Hash(object.borrow_ref()?.hash),
             Self::VariantObject(object) => {
@@ -447,6 +540,7 @@ impl Value {
             Self::Option(..) => TypeInfo::StaticType(crate::OPTION_TYPE),
             Self::Result(..) => TypeInfo::StaticType(crate::RESULT_TYPE),
             Self::Function(..) => TypeInfo::StaticType(crate::FUNCTION_TYPE),
+            Self::Handle(..) => TypeInfo::StaticType(crate::HANDLE_TYPE),
             Self::Type(hash) => TypeInfo::Hash(*hash),
             Self::TypedObject(object) => object.borrow_ref()?.type_info(),
             Self::VariantObject(object) => object.borrow_ref()?.type_info(),
@@ -602,6 +696,9 @@ impl fmt::Debug for Value {
             Value::Function(value) => {
                 write!(f, "{:?}", value)?;
             }
+            Value::Handle(value) => {
+                write!(f, "{:?}", value)?;
+            }
             Value::Any(value) => {
                 write!(f, "{:?}", value)?;
             }
@@ -662,6 +759,7 @@ impl_from_shared!(Shared<TupleVariant>, TupleVariant);
 impl_from_shared!(Shared<TypedObject>, TypedObject);
 impl_from_shared!(Shared<VariantObject>, VariantObject);
 impl_from_shared!(Shared<Function>, Function);
+impl_from_shared!(Shared<Handle>, Handle);
 impl_from_shared!(Shared<Any>, Any);
 
 /// A type-erased rust number.
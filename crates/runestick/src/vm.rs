@@ -1,9 +1,20 @@
+use crate::call_hook::CallHooks;
+use crate::context::Handler;
+use crate::finalizer::{self, Finalizers};
 use crate::future::SelectFuture;
+use crate::memoize::MemoizeKey;
+use crate::module;
+use crate::overrides::Overrides;
+use crate::quicken::{Site, Sites};
+use crate::statics::Statics;
+use crate::trace::Tracer;
 use crate::unit::UnitFn;
 use crate::{
-    Args, Awaited, Bytes, Call, Context, FromValue, Function, Future, Generator, Hash, Inst,
-    Integer, IntoHash, Object, Panic, Select, Shared, Stack, Stream, Tuple, TypeCheck, TypedObject,
-    Unit, Value, VariantObject, VmError, VmErrorKind, VmExecution, VmHalt,
+    Args, ArithmeticOp, Awaited, Bytes, Call, CallAction, CallContext, CallHook, CallInfo,
+    CancellationHandle, Context, Decimal, Events, FromValue, Function, Future, Generator, Hash,
+    HeapDump, Inst, Integer, IntoHash, Object, Panic, Protocol, Range, Select, Shared, Stack,
+    Stream, ToValue as _, TraceConfig, Tuple, TypeCheck, TypeInfo, TypedObject, Unit, Value,
+    VariantObject, VmError, VmErrorKind, VmExecution, VmHalt,
 };
 use std::fmt;
 use std::mem;
@@ -22,25 +33,225 @@ pub struct Vm {
     stack: Stack,
     /// Frames relative to the stack.
     call_frames: Vec<CallFrame>,
+    /// Event handlers registered by scripts.
+    events: Events,
+    /// Values with finalizers which are tracked by the virtual machine.
+    finalizers: Finalizers,
+    /// Hooks invoked around function calls.
+    call_hooks: CallHooks,
+    /// Calls to script functions waiting for their call frame to return, so
+    /// that the `after` hooks can be invoked.
+    hooked_frames: Vec<HookedFrame>,
+    /// Calls to memoized functions waiting for their call frame to return, so
+    /// that the value they return can be cached.
+    memoized_frames: Vec<MemoizedFrame>,
+    /// Functions overridden in this virtual machine.
+    overrides: Overrides,
+    /// The operand types observed at numeric instructions.
+    sites: Sites,
+    /// The JIT compiler used for function calls, if any.
+    #[cfg(feature = "jit")]
+    jit: Option<crate::Jit>,
+    /// Tracer of executed instructions, if tracing is enabled.
+    tracer: Option<Arc<Tracer>>,
+    /// Handle used to cancel execution, if one has been requested.
+    cancellation: Option<CancellationHandle>,
+    /// The values of statics declared in the unit.
+    statics: Statics,
+    /// If the virtual machine is being disposed of, and should run the
+    /// cleanup which is pending where it was suspended.
+    disposing: bool,
 }
 
 impl Vm {
     /// Construct a new runestick virtual machine.
-    pub const fn new(context: Arc<Context>, unit: Arc<Unit>) -> Self {
+    pub fn new(context: Arc<Context>, unit: Arc<Unit>) -> Self {
         Self::new_with_stack(context, unit, Stack::new())
     }
 
     /// Construct a new runestick virtual machine.
-    pub const fn new_with_stack(context: Arc<Context>, unit: Arc<Unit>, stack: Stack) -> Self {
+    pub fn new_with_stack(context: Arc<Context>, unit: Arc<Unit>, stack: Stack) -> Self {
         Self {
             context,
             unit,
             ip: 0,
             stack,
             call_frames: Vec::new(),
+            events: Events::new(),
+            finalizers: Finalizers::default(),
+            call_hooks: CallHooks::default(),
+            hooked_frames: Vec::new(),
+            memoized_frames: Vec::new(),
+            overrides: Overrides::default(),
+            sites: Sites::default(),
+            #[cfg(feature = "jit")]
+            jit: None,
+            tracer: None,
+            cancellation: None,
+            statics: Statics::default(),
+            disposing: false,
         }
     }
 
+    /// Construct a new runestick virtual machine, after checking that the
+    /// unit is compatible with the runtime and the given context.
+    ///
+    /// See [Unit::check_abi].
+    pub fn try_new(context: Arc<Context>, unit: Arc<Unit>) -> Result<Self, VmError> {
+        unit.check_abi(&context)?;
+        Ok(Self::new(context, unit))
+    }
+
+    /// Share the event handlers of another virtual machine, which is used for
+    /// virtual machines spawned to call functions.
+    pub(crate) fn with_events(self, events: Events) -> Self {
+        Self { events, ..self }
+    }
+
+    /// Share the statics of another virtual machine, which is used for
+    /// virtual machines spawned to call functions.
+    pub(crate) fn with_statics(self, statics: Statics) -> Self {
+        Self { statics, ..self }
+    }
+
+    /// Share the call hooks of another virtual machine, which is used for
+    /// virtual machines spawned to call async functions and generators.
+    pub(crate) fn with_call_hooks(self, call_hooks: CallHooks) -> Self {
+        Self { call_hooks, ..self }
+    }
+
+    /// Share the overridden functions of another virtual machine, which is
+    /// used for virtual machines spawned to call async functions and
+    /// generators.
+    pub(crate) fn with_overrides(self, overrides: Overrides) -> Self {
+        Self { overrides, ..self }
+    }
+
+    /// Share the cancellation handle of another virtual machine, which is used
+    /// for virtual machines spawned to call functions.
+    pub(crate) fn with_cancellation(self, cancellation: Option<CancellationHandle>) -> Self {
+        Self {
+            cancellation,
+            ..self
+        }
+    }
+
+    /// Access the cancellation handle of the virtual machine, if it has one.
+    pub(crate) fn cancellation(&self) -> Option<&CancellationHandle> {
+        self.cancellation.as_ref()
+    }
+
+    /// Test if the virtual machine has been cancelled, which is checked at
+    /// preemption points.
+    #[inline(always)]
+    fn is_cancelled(&self) -> bool {
+        match &self.cancellation {
+            Some(handle) => handle.is_cancelled(),
+            None => false,
+        }
+    }
+
+    /// Share the tracer of another virtual machine, which is used for virtual
+    /// machines spawned to call async functions and generators.
+    pub(crate) fn with_tracer(self, tracer: Option<Arc<Tracer>>) -> Self {
+        Self { tracer, ..self }
+    }
+
+    /// Override the function with the given hash or item in this virtual
+    /// machine only, which shadows both native functions in the context and
+    /// functions in the unit. This is useful to stub out functions like
+    /// `http::get` when testing scripts.
+    ///
+    /// Instance functions are overridden by their instance function hash, as
+    /// constructed with [Hash::instance_function].
+    ///
+    /// Like call hooks, overrides are also installed in the virtual machines
+    /// spawned to run async functions, generators and streams called by this
+    /// one, and calls through function pointers are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{Context, Item, Unit, Vm};
+    /// use std::sync::Arc;
+    ///
+    /// # fn main() -> runestick::Result<()> {
+    /// let context = Arc::new(Context::with_default_modules()?);
+    /// let unit = Arc::new(Unit::default());
+    ///
+    /// let mut vm = Vm::new(context, unit);
+    /// vm.override_function(&["time", "now"], || 1_600_000_000i64);
+    /// vm.override_function(Item::of(&["http", "get"]), |url: &str| format!("stub for {}", url));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn override_function<H, Func, Args>(&mut self, hash: H, f: Func)
+    where
+        H: IntoHash,
+        Func: module::Function<Args>,
+    {
+        self.overrides.insert(
+            hash.into_hash(),
+            Arc::new(move |cx: &mut CallContext<'_>, args| f.fn_call(cx.stack_mut(), args)),
+        );
+    }
+
+    /// Install a hook which is invoked around function calls. Hooks are
+    /// invoked in the order they're installed before a call, and in reverse
+    /// order after it.
+    ///
+    /// The hooks are also installed in the virtual machines spawned to run
+    /// async functions, generators and streams called by this one.
+    ///
+    /// See [CallHook].
+    pub fn add_call_hook<H>(&mut self, hook: H)
+    where
+        H: CallHook + 'static,
+    {
+        self.call_hooks.push(Arc::new(hook));
+    }
+
+    /// Install a JIT compiler, which compiles functions in the unit once
+    /// they've been called often enough.
+    ///
+    /// See [Jit][crate::Jit].
+    #[cfg(feature = "jit")]
+    pub fn set_jit(&mut self, jit: crate::Jit) {
+        self.jit = Some(jit);
+    }
+
+    /// Trace every instruction executed by the virtual machine, including
+    /// those executed by async functions and generators it calls, as
+    /// configured by the given [TraceConfig].
+    ///
+    /// While tracing, the instruction at which long-lived accesses are acquired
+    /// is recorded, so that access errors can point at it. This always happens
+    /// in debug builds.
+    ///
+    /// Errors if the configuration names a function which isn't in the unit.
+    /// Instructions aren't compiled by the JIT while tracing.
+    pub fn trace(&mut self, config: TraceConfig) -> Result<(), VmError> {
+        self.tracer = Some(Arc::new(Tracer::new(config, &self.unit)?));
+        Ok(())
+    }
+
+    /// Stop tracing instructions.
+    pub fn clear_trace(&mut self) {
+        self.tracer = None;
+    }
+
+    /// Get a handle which can be used to cancel the execution of this virtual
+    /// machine from another thread or task.
+    ///
+    /// All calls return handles to the same cancellation state, which is
+    /// shared with virtual machines spawned to call async functions and
+    /// generators. See [CancellationHandle].
+    pub fn cancellation_handle(&mut self) -> CancellationHandle {
+        self.cancellation
+            .get_or_insert_with(CancellationHandle::new)
+            .clone()
+    }
+
     /// Run the given vm to completion.
     ///
     /// If any async instructions are encountered, this will error.
@@ -78,12 +289,58 @@ impl Vm {
         &self.stack
     }
 
+    /// Access the values tracked for finalization.
+    pub(crate) fn finalizers(&self) -> &Finalizers {
+        &self.finalizers
+    }
+
+    /// Take a dump of all shared values which are reachable from the virtual
+    /// machine, including their types, estimated sizes, and the values they
+    /// refer to.
+    ///
+    /// See [HeapDump].
+    pub fn heap_dump(&self) -> HeapDump {
+        crate::heap::heap_dump(self)
+    }
+
     /// Get the stack mutably.
     #[inline]
     pub fn stack_mut(&mut self) -> &mut Stack {
         &mut self.stack
     }
 
+    /// Access the event handlers registered by scripts running in this
+    /// virtual machine.
+    pub fn events(&self) -> &Events {
+        &self.events
+    }
+
+    /// Access the statics of the virtual machine.
+    pub(crate) fn statics(&self) -> &Statics {
+        &self.statics
+    }
+
+    /// Fire the event with the given name.
+    ///
+    /// See [Events::fire].
+    pub fn fire<A>(&self, name: &str, args: A) -> Result<Vec<Value>, VmError>
+    where
+        A: Args + Clone,
+    {
+        self.events.fire(name, args)
+    }
+
+    /// Fire the event with the given name and await the futures produced by
+    /// async handlers.
+    ///
+    /// See [Events::async_fire].
+    pub async fn async_fire<A>(&self, name: &str, args: A) -> Result<Vec<Value>, VmError>
+    where
+        A: Args + Clone,
+    {
+        self.events.async_fire(name, args).await
+    }
+
     /// Access the context related to the virtual machine.
     pub fn context(&self) -> &Arc<Context> {
         &self.context
@@ -95,10 +352,58 @@ impl Vm {
     }
 
     /// Reset this virtual machine, freeing all memory used.
+    ///
+    /// Values with finalizers are still tracked, so that they're finalized
+    /// at the next safe point once they're no longer referenced.
     pub fn clear(&mut self) {
         self.ip = 0;
         self.stack.clear();
         self.call_frames.clear();
+        self.hooked_frames.clear();
+        self.memoized_frames.clear();
+    }
+
+    /// Prepare the virtual machine to be reused by another tenant of a
+    /// [VmPool][crate::VmPool].
+    ///
+    /// Values are released and finalized like when the virtual machine is
+    /// disposed, and state which is built up by scripts, like registered event
+    /// handlers, statics, and the results cached for memoized functions, is
+    /// replaced so that it doesn't carry over to the next tenant.
+    /// The cancellation handle is also dropped, so that cancelling one tenant
+    /// doesn't cancel the next. How the virtual machine has been configured is
+    /// kept.
+    pub(crate) fn release(&mut self) -> Result<(), VmError> {
+        self.ip = 0;
+        self.dispose()?;
+        self.events = Events::new();
+        self.statics = Statics::default();
+        self.cancellation = None;
+        Ok(())
+    }
+
+    /// Request that the virtual machine runs the cleanup which is pending
+    /// where it was suspended the next time it's resumed, returning `false` if
+    /// there is no such cleanup.
+    pub(crate) fn request_dispose(&mut self) -> bool {
+        if let Some(Inst::Disposed) = self.unit.instruction_at(self.ip) {
+            self.disposing = true;
+            return true;
+        }
+
+        false
+    }
+
+    /// Release all values held by the virtual machine, running the finalizers
+    /// of tracked values which are no longer referenced.
+    pub(crate) fn dispose(&mut self) -> Result<(), VmError> {
+        self.stack.clear();
+        self.call_frames.clear();
+        self.hooked_frames.clear();
+        self.memoized_frames.clear();
+        self.run_finalizers()?;
+        self.finalizers.clear();
+        Ok(())
     }
 
     /// Access the current instruction pointer.
@@ -150,6 +455,17 @@ impl Vm {
     /// }
     /// ```
     pub fn call<A, N>(mut self, name: N, args: A) -> Result<VmExecution, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+    {
+        self.set_entrypoint(name, args)?;
+        Ok(VmExecution::new(self))
+    }
+
+    /// Prepare the virtual machine to call the given function, by clearing
+    /// the stack and pushing the arguments onto it.
+    pub(crate) fn set_entrypoint<A, N>(&mut self, name: N, args: A) -> Result<(), VmError>
     where
         N: IntoHash,
         A: Args,
@@ -169,7 +485,7 @@ impl Vm {
                 args: expected,
                 ..
             } => {
-                Self::check_args(A::count(), expected)?;
+                self.unit.check_args(offset, A::count(), expected)?;
                 offset
             }
             _ => {
@@ -183,7 +499,22 @@ impl Vm {
         // Safety: we bind the lifetime of the arguments to the outgoing task,
         // ensuring that the task won't outlive any references passed in.
         args.into_stack(&mut self.stack)?;
-        Ok(VmExecution::new(self))
+        Ok(())
+    }
+
+    /// Deep clone the given value.
+    ///
+    /// Like `std::clone::deep`, external values are cloned by calling the
+    /// [CLONE][crate::CLONE] protocol registered for their type.
+    pub fn deep_clone(&mut self, value: &Value) -> Result<Value, VmError> {
+        let cx = CallContext::new(
+            &mut self.stack,
+            &self.context,
+            &self.unit,
+            &self.events,
+            &self.statics,
+        );
+        crate::deep::deep_clone(&cx, value)
     }
 
     fn op_await(&mut self) -> Result<Shared<Future>, VmError> {
@@ -241,7 +572,7 @@ impl Vm {
             args: expected,
         }) = self.unit.lookup(hash)
         {
-            Self::check_args(count, expected)?;
+            self.unit.check_args(offset, count, expected)?;
             self.stack.push(target.clone());
             args.into_stack(&mut self.stack)?;
             self.call_offset_fn(offset, call, count)?;
@@ -256,7 +587,16 @@ impl Vm {
         self.stack.push(target.clone());
         args.into_stack(&mut self.stack)?;
 
-        handler(&mut self.stack, count)?;
+        handler(
+            &mut CallContext::new(
+                &mut self.stack,
+                &self.context,
+                &self.unit,
+                &self.events,
+                &self.statics,
+            ),
+            count,
+        )?;
         Ok(true)
     }
 
@@ -277,7 +617,16 @@ impl Vm {
         args.into_stack(&mut self.stack)?;
 
         self.stack.push(target.clone());
-        handler(&mut self.stack, count)?;
+        handler(
+            &mut CallContext::new(
+                &mut self.stack,
+                &self.context,
+                &self.unit,
+                &self.events,
+                &self.statics,
+            ),
+            count,
+        )?;
         Ok(true)
     }
 
@@ -339,29 +688,74 @@ impl Vm {
 
     fn internal_boolean_ops(
         &mut self,
-        int_op: impl FnOnce(i64, i64) -> bool,
-        float_op: impl FnOnce(f64, f64) -> bool,
+        int_op: impl Copy + FnOnce(i64, i64) -> bool,
+        float_op: impl Copy + FnOnce(f64, f64) -> bool,
         op: &'static str,
     ) -> Result<(), VmError> {
+        if self.quickened_boolean_ops(int_op, float_op)? {
+            return Ok(());
+        }
+
         let rhs = self.stack.pop()?;
         let lhs = self.stack.pop()?;
 
         let out = match (lhs, rhs) {
             (Value::Integer(lhs), Value::Integer(rhs)) => int_op(lhs, rhs),
             (Value::Float(lhs), Value::Float(rhs)) => float_op(lhs, rhs),
-            (lhs, rhs) => {
-                return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
-                    op,
-                    lhs: lhs.type_info()?,
-                    rhs: rhs.type_info()?,
-                }))
-            }
+            (lhs, rhs) => match self.call_cmp(&lhs, &rhs)? {
+                Some(ordering) => int_op(ordering, 0),
+                None => {
+                    return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
+                        op,
+                        lhs: lhs.type_info()?,
+                        rhs: rhs.type_info()?,
+                    }))
+                }
+            },
         };
 
         self.stack.push(out);
         Ok(())
     }
 
+    /// Perform a comparison in place if the instruction is quickened,
+    /// returning `false` if the generic implementation has to be used.
+    #[inline]
+    fn quickened_boolean_ops(
+        &mut self,
+        int_op: impl FnOnce(i64, i64) -> bool,
+        float_op: impl FnOnce(f64, f64) -> bool,
+    ) -> Result<bool, VmError> {
+        let (lhs, rhs) = match self.stack.last_pair_mut() {
+            Some(pair) => pair,
+            None => return Ok(false),
+        };
+
+        if !self.sites.observe(self.ip, Site::of(lhs, rhs)) {
+            return Ok(false);
+        }
+
+        let out = match (&*lhs, &*rhs) {
+            (Value::Integer(a), Value::Integer(b)) => int_op(*a, *b),
+            (Value::Float(a), Value::Float(b)) => float_op(*a, *b),
+            _ => return Ok(false),
+        };
+
+        *lhs = Value::Bool(out);
+        self.stack.pop()?;
+        Ok(true)
+    }
+
+    /// Compare two values through the [CMP][crate::CMP] protocol, if it's
+    /// implemented for the type of the first value.
+    fn call_cmp(&mut self, lhs: &Value, rhs: &Value) -> Result<Option<i64>, VmError> {
+        if !self.call_instance_fn(lhs, crate::CMP, (rhs,))? {
+            return Ok(None);
+        }
+
+        Ok(Some(i64::from_value(self.stack.pop()?)?))
+    }
+
     fn op_gt(&mut self) -> Result<(), VmError> {
         self.internal_boolean_ops(|a, b| a > b, |a, b| a > b, ">")?;
         Ok(())
@@ -418,7 +812,8 @@ impl Vm {
     fn op_eq(&mut self) -> Result<(), VmError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(Value::value_ptr_eq(&a, &b)?);
+        let eq = self.internal_eq(&a, &b)?;
+        self.stack.push(eq);
         Ok(())
     }
 
@@ -427,10 +822,23 @@ impl Vm {
     fn op_neq(&mut self) -> Result<(), VmError> {
         let b = self.stack.pop()?;
         let a = self.stack.pop()?;
-        self.stack.push(!Value::value_ptr_eq(&a, &b)?);
+        let eq = self.internal_eq(&a, &b)?;
+        self.stack.push(!eq);
         Ok(())
     }
 
+    /// Test two values for equality, where external values are compared
+    /// through the [CMP][crate::CMP] protocol if it's implemented.
+    fn internal_eq(&mut self, a: &Value, b: &Value) -> Result<bool, VmError> {
+        if let Value::Any(..) = a {
+            if let Some(ordering) = self.call_cmp(a, b)? {
+                return Ok(ordering == 0);
+            }
+        }
+
+        Value::value_ptr_eq(a, b)
+    }
+
     /// Perform a jump operation.
     #[inline]
     fn op_jump(&mut self, offset: isize) -> Result<(), VmError> {
@@ -458,6 +866,20 @@ impl Vm {
         Ok(())
     }
 
+    /// Perform a conditional jump operation, which keeps the condition on the
+    /// stack if the jump is performed.
+    #[inline]
+    fn op_jump_if_or_pop(&mut self, expected: bool, offset: isize) -> Result<(), VmError> {
+        let value = self.stack.pop()?.into_bool()?;
+
+        if value == expected {
+            self.stack.push(Value::Bool(value));
+            self.modify_ip(offset)?;
+        }
+
+        Ok(())
+    }
+
     /// Perform a branch-conditional jump operation.
     #[inline]
     fn op_jump_if_branch(&mut self, branch: i64, offset: isize) -> Result<(), VmError> {
@@ -487,6 +909,43 @@ impl Vm {
         Ok(())
     }
 
+    /// Unpack a tuple onto the stack.
+    #[inline]
+    fn op_unpack(&mut self, count: usize) -> Result<(), VmError> {
+        if let Value::Tuple(tuple) = self.stack.pop()? {
+            let tuple = tuple.borrow_ref()?;
+
+            if tuple.len() == count {
+                for value in tuple.iter() {
+                    self.stack.push(value.clone());
+                }
+
+                return Ok(());
+            }
+        }
+
+        Err(VmError::from(VmErrorKind::Panic {
+            reason: Panic::from(crate::PanicReason::UnmatchedPattern),
+        }))
+    }
+
+    /// Construct a range out of the two bounds on the stack.
+    #[inline]
+    fn op_range(&mut self) -> Result<(), VmError> {
+        let end = self.stack.pop()?;
+        let start = self.stack.pop()?;
+
+        let bound = |value: Value| match value {
+            Value::Unit => Ok(None),
+            Value::Integer(n) => Ok(Some(n)),
+            actual => Err(VmError::expected::<i64>(actual.type_info()?)),
+        };
+
+        let range = Range::new(bound(start)?, bound(end)?);
+        self.stack.push(range.to_value()?);
+        Ok(())
+    }
+
     /// Push the tuple that is on top of the stack.
     #[inline]
     fn op_push_tuple(&mut self) -> Result<(), VmError> {
@@ -542,7 +1001,7 @@ impl Vm {
     #[inline]
     fn op_mul(&mut self) -> Result<(), VmError> {
         self.internal_num(
-            crate::ADD,
+            crate::MUL,
             || VmError::from(VmErrorKind::Overflow),
             i64::checked_mul,
             std::ops::Mul::mul,
@@ -554,11 +1013,11 @@ impl Vm {
     #[inline]
     fn op_div(&mut self) -> Result<(), VmError> {
         self.internal_num(
-            crate::ADD,
+            crate::DIV,
             || VmError::from(VmErrorKind::DivideByZero),
             i64::checked_div,
             std::ops::Div::div,
-            "+",
+            "/",
         )?;
         Ok(())
     }
@@ -677,6 +1136,7 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::ADD_ASSIGN,
+            crate::ADD,
             || VmError::from(VmErrorKind::Overflow),
             i64::checked_add,
             std::ops::Add::add,
@@ -685,11 +1145,53 @@ impl Vm {
         Ok(())
     }
 
+    #[inline]
+    fn op_concat_assign(&mut self, offset: usize) -> Result<(), VmError> {
+        if self.quickened_num_assign(
+            offset,
+            || VmError::from(VmErrorKind::Overflow),
+            i64::checked_add,
+            std::ops::Add::add,
+        )? {
+            return Ok(());
+        }
+
+        let rhs = self.stack.pop()?;
+
+        if let Value::String(string) = self.stack.at_offset(offset)? {
+            // NB: appending in place is only invisible if nothing else can
+            // observe the string.
+            if string.is_unique() && string.is_writable() {
+                match &rhs {
+                    Value::String(rhs) => {
+                        string.borrow_mut()?.push_str(&*rhs.borrow_ref()?);
+                        return Ok(());
+                    }
+                    Value::StaticString(rhs) => {
+                        string.borrow_mut()?.push_str(rhs.as_ref());
+                        return Ok(());
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        let lhs = self.stack.at_offset(offset)?.clone();
+        self.stack.push(lhs);
+        self.stack.push(rhs);
+        self.op_add()?;
+
+        let value = self.stack.pop()?;
+        *self.stack.at_offset_mut(offset)? = value;
+        Ok(())
+    }
+
     #[inline]
     fn op_sub_assign(&mut self, offset: usize) -> Result<(), VmError> {
         self.internal_num_assign(
             offset,
             crate::SUB_ASSIGN,
+            crate::SUB,
             || VmError::from(VmErrorKind::Underflow),
             i64::checked_sub,
             std::ops::Sub::sub,
@@ -703,6 +1205,7 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::MUL_ASSIGN,
+            crate::MUL,
             || VmError::from(VmErrorKind::Overflow),
             i64::checked_mul,
             std::ops::Mul::mul,
@@ -716,6 +1219,7 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::DIV_ASSIGN,
+            crate::DIV,
             || VmError::from(VmErrorKind::DivideByZero),
             i64::checked_div,
             std::ops::Div::div,
@@ -729,6 +1233,7 @@ impl Vm {
         self.internal_num_assign(
             offset,
             crate::REM_ASSIGN,
+            crate::REM,
             || VmError::from(VmErrorKind::DivideByZero),
             i64::checked_rem,
             std::ops::Rem::rem,
@@ -737,6 +1242,52 @@ impl Vm {
         Ok(())
     }
 
+    /// Perform an arithmetic operation, or an assignment to the given offset,
+    /// pushing a boolean indicating if it succeeded.
+    ///
+    /// Overflows and divisions by zero push the error wrapped in an `Err`
+    /// with its operands removed from the stack, so that it can be returned
+    /// from the current function.
+    #[inline]
+    fn op_checked(&mut self, op: ArithmeticOp, offset: Option<usize>) -> Result<(), VmError> {
+        let len = self.stack.len();
+
+        let result = match (op, offset) {
+            (ArithmeticOp::Add, None) => self.op_add(),
+            (ArithmeticOp::Sub, None) => self.op_sub(),
+            (ArithmeticOp::Mul, None) => self.op_mul(),
+            (ArithmeticOp::Div, None) => self.op_div(),
+            (ArithmeticOp::Rem, None) => self.op_rem(),
+            (ArithmeticOp::Add, Some(offset)) => self.op_add_assign(offset),
+            (ArithmeticOp::Sub, Some(offset)) => self.op_sub_assign(offset),
+            (ArithmeticOp::Mul, Some(offset)) => self.op_mul_assign(offset),
+            (ArithmeticOp::Div, Some(offset)) => self.op_div_assign(offset),
+            (ArithmeticOp::Rem, Some(offset)) => self.op_rem_assign(offset),
+        };
+
+        let error = match result {
+            Ok(()) => {
+                self.stack.push(true);
+                return Ok(());
+            }
+            Err(error) => error,
+        };
+
+        match error.kind() {
+            VmErrorKind::Overflow | VmErrorKind::Underflow | VmErrorKind::DivideByZero => (),
+            _ => return Err(error),
+        }
+
+        // NB: quickened operations fail before their operands are popped.
+        let operands = if offset.is_some() { 1 } else { 2 };
+        self.stack.popn(self.stack.len() - (len - operands))?;
+
+        let error = crate::modules::error::caught(error)?;
+        self.stack.push(Value::Result(Shared::new(Err(error))));
+        self.stack.push(false);
+        Ok(())
+    }
+
     /// Perform an index set operation.
     #[inline]
     fn op_index_set(&mut self) -> Result<(), VmError> {
@@ -808,52 +1359,310 @@ impl Vm {
 
     #[inline]
     fn op_return(&mut self) -> Result<bool, VmError> {
-        let return_value = self.stack.pop()?;
+        let mut return_value = self.stack.pop()?;
+        let hooked = self.pop_hooked_frame();
+        let memoized = self.pop_memoized_frame();
         let exit = self.pop_call_frame()?;
+
+        if let Some(frame) = memoized {
+            self.statics
+                .store_memoized(&self.unit, frame.slot, frame.key, return_value.clone())?;
+        }
+
+        if let Some(frame) = hooked {
+            self.run_after_hooks(frame.hash, frame.args, false, &mut return_value)?;
+        }
+
         self.stack.push(return_value);
+        self.run_finalizers()?;
         Ok(exit)
     }
 
     #[inline]
     fn op_return_unit(&mut self) -> Result<bool, VmError> {
+        let hooked = self.pop_hooked_frame();
+        let memoized = self.pop_memoized_frame();
         let exit = self.pop_call_frame()?;
-        self.stack.push(());
+        let mut return_value = Value::Unit;
+
+        if let Some(frame) = memoized {
+            self.statics
+                .store_memoized(&self.unit, frame.slot, frame.key, Value::Unit)?;
+        }
+
+        if let Some(frame) = hooked {
+            self.run_after_hooks(frame.hash, frame.args, false, &mut return_value)?;
+        }
+
+        self.stack.push(return_value);
+        self.run_finalizers()?;
         Ok(exit)
     }
 
+    /// Return a tuple of values, which are pushed directly onto the stack of
+    /// the caller if it immediately unpacks them.
     #[inline]
-    fn op_load_instance_fn(&mut self, hash: Hash) -> Result<(), VmError> {
-        let instance = self.stack.pop()?;
-        let ty = instance.value_type()?;
-        let hash = Hash::instance_function(ty, hash);
-        self.stack.push(Value::Type(hash));
-        Ok(())
-    }
+    fn op_return_tuple(&mut self, count: usize) -> Result<bool, VmError> {
+        self.stack.retain_top(count)?;
 
-    /// Try to convert the given value into a future.
-    ///
-    /// Returns the value we failed to convert as an `Err` variant if we are
-    /// unsuccessful.
-    fn try_into_future(&mut self, value: Value) -> Result<Result<Shared<Future>, Value>, VmError> {
-        match value {
-            Value::Future(future) => Ok(Ok(future)),
-            value => {
-                if !self.call_instance_fn(&value, crate::INTO_FUTURE, ())? {
-                    return Ok(Err(value));
-                }
+        let is_hooked = matches! {
+            self.hooked_frames.last(),
+            Some(frame) if frame.depth == self.call_frames.len()
+        };
 
-                if let Value::Future(future) = self.stack.pop()? {
-                    return Ok(Ok(future));
-                }
+        let is_memoized = matches! {
+            self.memoized_frames.last(),
+            Some(frame) if frame.depth == self.call_frames.len()
+        };
 
-                Ok(Err(value))
+        // NB: the return value of a hooked or memoized call is passed to its
+        // hooks or cached, so it has to be constructed.
+        if !is_hooked && !is_memoized {
+            if let Some(frame) = self.call_frames.last() {
+                let ip = frame.ip.overflowing_add(1).0;
+
+                if let Some(Inst::Unpack { count: expected }) = self.unit.instruction_at(ip) {
+                    if *expected == count {
+                        let frame = self.call_frames.pop().expect("frame to be present");
+                        self.stack.pop_stack_top_with(frame.stack_bottom, count)?;
+                        self.ip = ip;
+                        self.run_finalizers()?;
+                        return Ok(false);
+                    }
+                }
             }
         }
+
+        self.op_tuple(count)?;
+        self.op_return()
     }
 
-    /// Implementation of getting a string index on an object-like type.
-    fn try_object_like_index_get(&mut self, target: &Value, field: &str) -> Result<bool, VmError> {
-        let value = match &target {
+    /// Pop the hooked call waiting for the current call frame to return, if
+    /// any.
+    #[inline]
+    fn pop_hooked_frame(&mut self) -> Option<HookedFrame> {
+        match self.hooked_frames.last() {
+            Some(frame) if frame.depth == self.call_frames.len() => self.hooked_frames.pop(),
+            _ => None,
+        }
+    }
+
+    /// Pop the memoized call waiting for the current call frame to return, if
+    /// any.
+    #[inline]
+    fn pop_memoized_frame(&mut self) -> Option<MemoizedFrame> {
+        match self.memoized_frames.last() {
+            Some(frame) if frame.depth == self.call_frames.len() => self.memoized_frames.pop(),
+            _ => None,
+        }
+    }
+
+    /// Look up the cached result of a call to the memoized function in the
+    /// given slot, with the arguments of the current call frame. A cached
+    /// result replaces the arguments, otherwise the value the call returns is
+    /// cached once it returns.
+    fn op_memoize(&mut self, slot: usize) -> Result<Option<Value>, VmError> {
+        let args = self
+            .stack
+            .get(self.stack.stack_bottom()..)
+            .unwrap_or_default();
+
+        let count = args.len();
+        let key = MemoizeKey::new(args)?;
+
+        if let Some(value) = self.statics.get_memoized(&self.unit, slot, &key)? {
+            self.stack.popn(count)?;
+            return Ok(Some(value));
+        }
+
+        self.memoized_frames.push(MemoizedFrame {
+            depth: self.call_frames.len(),
+            slot,
+            key,
+        });
+
+        Ok(None)
+    }
+
+    /// Run the `before` hooks for a call with its arguments on the stack.
+    /// Returns `true` if a hook replaced the call, in which case the arguments
+    /// have been replaced by its result.
+    fn run_before_hooks(&mut self, hash: Hash, args: usize, native: bool) -> Result<bool, VmError> {
+        let call = CallInfo::new(hash, args, native, &self.context, &self.unit);
+
+        match self.call_hooks.before(&call)? {
+            CallAction::Continue => Ok(false),
+            CallAction::Replace(value) => {
+                self.stack.popn(args)?;
+                self.stack.push(value);
+                Ok(true)
+            }
+        }
+    }
+
+    /// Run the `after` hooks for a call which returned the given value.
+    fn run_after_hooks(
+        &self,
+        hash: Hash,
+        args: usize,
+        native: bool,
+        value: &mut Value,
+    ) -> Result<(), VmError> {
+        let call = CallInfo::new(hash, args, native, &self.context, &self.unit);
+        self.call_hooks.after(&call, value)
+    }
+
+    /// Run the `after` hooks for a call which left its result on the stack.
+    fn run_after_hooks_on_stack(
+        &mut self,
+        hash: Hash,
+        args: usize,
+        native: bool,
+    ) -> Result<(), VmError> {
+        let mut value = self.stack.pop()?;
+        self.run_after_hooks(hash, args, native, &mut value)?;
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Call the script function at the given offset, invoking the hooks
+    /// around it if there are any.
+    fn call_offset_fn_hooked(
+        &mut self,
+        hash: Hash,
+        offset: usize,
+        call: Call,
+        args: usize,
+    ) -> Result<(), VmError> {
+        if self.call_hooks.is_empty() {
+            return self.call_offset_fn(offset, call, args);
+        }
+
+        if self.run_before_hooks(hash, args, false)? {
+            return Ok(());
+        }
+
+        self.call_offset_fn(offset, call, args)?;
+
+        match call {
+            Call::Immediate => self.hooked_frames.push(HookedFrame {
+                depth: self.call_frames.len(),
+                hash,
+                args,
+            }),
+            _ => self.run_after_hooks_on_stack(hash, args, false)?,
+        }
+
+        Ok(())
+    }
+
+    /// Call the given native handler, invoking the hooks around it if there
+    /// are any.
+    fn call_handler(
+        &mut self,
+        hash: Hash,
+        handler: Arc<Handler>,
+        args: usize,
+    ) -> Result<(), VmError> {
+        if !self.call_hooks.is_empty() {
+            return self.call_handler_hooked(hash, handler, args);
+        }
+
+        handler(
+            &mut CallContext::new(
+                &mut self.stack,
+                &self.context,
+                &self.unit,
+                &self.events,
+                &self.statics,
+            ),
+            args,
+        )
+    }
+
+    /// Call the given native handler with the hooks invoked around it.
+    fn call_handler_hooked(
+        &mut self,
+        hash: Hash,
+        handler: Arc<Handler>,
+        args: usize,
+    ) -> Result<(), VmError> {
+        if self.run_before_hooks(hash, args, true)? {
+            return Ok(());
+        }
+
+        handler(
+            &mut CallContext::new(
+                &mut self.stack,
+                &self.context,
+                &self.unit,
+                &self.events,
+                &self.statics,
+            ),
+            args,
+        )?;
+
+        self.run_after_hooks_on_stack(hash, args, true)
+    }
+
+    /// Run the finalizers of tracked values which have been released.
+    fn run_finalizers(&mut self) -> Result<(), VmError> {
+        while let Some((hash, value)) = self.finalizers.take_released() {
+            finalizer::finalize(
+                &self.context,
+                &self.unit,
+                &self.events,
+                &self.statics,
+                hash,
+                value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn op_load_instance_fn(&mut self, hash: Hash) -> Result<(), VmError> {
+        let instance = self.stack.pop()?;
+        let ty = instance.value_type()?;
+        let hash = Hash::instance_function(ty, hash);
+        self.stack.push(Value::Type(hash));
+        Ok(())
+    }
+
+    /// Try to convert the given value into a future.
+    ///
+    /// Returns the value we failed to convert as an `Err` variant if we are
+    /// unsuccessful.
+    fn try_into_future(&mut self, value: Value) -> Result<Result<Shared<Future>, Value>, VmError> {
+        match value {
+            Value::Future(future) => Ok(Ok(future)),
+            value => {
+                if !self.call_instance_fn(&value, crate::INTO_FUTURE, ())? {
+                    return Ok(Err(value));
+                }
+
+                if let Value::Future(future) = self.stack.pop()? {
+                    return Ok(Ok(future));
+                }
+
+                Ok(Err(value))
+            }
+        }
+    }
+
+    /// Implementation of getting a string index on an object-like type.
+    ///
+    /// Fields missing from a struct are looked up through the
+    /// [INDEX_GET][crate::INDEX_GET] protocol with the given index, if the
+    /// struct implements it.
+    fn try_object_like_index_get(
+        &mut self,
+        target: &Value,
+        field: &str,
+        index: &Value,
+    ) -> Result<bool, VmError> {
+        let value = match &target {
             Value::Object(target) => target.borrow_ref()?.get(field).cloned(),
             Value::TypedObject(target) => target.borrow_ref()?.object.get(field).cloned(),
             Value::VariantObject(target) => target.borrow_ref()?.object.get(field).cloned(),
@@ -863,6 +1672,12 @@ impl Vm {
         let value = match value {
             Some(value) => value,
             None => {
+                if let Value::TypedObject(..) = target {
+                    if self.call_instance_fn(target, crate::INDEX_GET, (index,))? {
+                        return Ok(true);
+                    }
+                }
+
                 return Err(VmError::from(VmErrorKind::MissingField {
                     target: target.type_info()?,
                     field: field.to_owned(),
@@ -1007,47 +1822,50 @@ impl Vm {
     }
 
     /// Perform an index get operation.
+    ///
+    /// If `negative` is set, negative indexes count from the end of the
+    /// target.
     #[inline]
-    fn op_index_get(&mut self) -> Result<(), VmError> {
+    fn op_index_get(&mut self, negative: bool) -> Result<(), VmError> {
         let target = self.stack.pop()?;
-        let index = self.stack.pop()?;
+        let mut index = self.stack.pop()?;
 
-        // This is a useful pattern.
-        #[allow(clippy::never_loop)]
-        loop {
-            match &index {
-                Value::String(string) => {
-                    let string_ref = string.borrow_ref()?;
+        if negative {
+            index = Self::resolve_negative_index(&target, index)?;
+        }
 
-                    if self.try_object_like_index_get(&target, string_ref.as_str())? {
-                        return Ok(());
-                    }
+        match &index {
+            Value::String(string) => {
+                let string_ref = string.borrow_ref()?;
+
+                if self.try_object_like_index_get(&target, string_ref.as_str(), &index)? {
+                    return Ok(());
                 }
-                Value::StaticString(string) => {
-                    if self.try_object_like_index_get(&target, string.as_ref())? {
-                        return Ok(());
-                    }
+            }
+            Value::StaticString(string) => {
+                if self.try_object_like_index_get(&target, string.as_ref(), &index)? {
+                    return Ok(());
                 }
-                Value::Integer(index) => {
-                    use std::convert::TryInto as _;
-
-                    let index = match (*index).try_into() {
-                        Ok(index) => index,
-                        Err(..) => {
-                            return Err(VmError::from(VmErrorKind::MissingIndex {
-                                target: target.type_info()?,
-                                index: Integer::I64(*index),
-                            }));
-                        }
-                    };
-
-                    if let Some(value) = Self::try_tuple_like_index_get(&target, index)? {
-                        self.stack.push(value);
-                        return Ok(());
+            }
+            Value::Integer(index) => {
+                use std::convert::TryInto as _;
+
+                let index = match (*index).try_into() {
+                    Ok(index) => index,
+                    Err(..) => {
+                        return Err(VmError::from(VmErrorKind::MissingIndex {
+                            target: target.type_info()?,
+                            index: Integer::I64(*index),
+                        }));
                     }
+                };
+
+                if let Some(value) = Self::try_tuple_like_index_get(&target, index)? {
+                    self.stack.push(value);
+                    return Ok(());
                 }
-                _ => break,
-            };
+            }
+            _ => (),
         }
 
         if !self.call_instance_fn(&target, crate::INDEX_GET, (&index,))? {
@@ -1060,6 +1878,40 @@ impl Vm {
         Ok(())
     }
 
+    /// Resolve a negative integer index or negative range bounds relative to
+    /// the end of the given target.
+    ///
+    /// Indexes which are still negative after being resolved are left as-is.
+    fn resolve_negative_index(target: &Value, index: Value) -> Result<Value, VmError> {
+        let len = match target {
+            Value::Vec(vec) => vec.borrow_ref()?.len(),
+            Value::Tuple(tuple) => tuple.borrow_ref()?.len(),
+            Value::TypedTuple(typed_tuple) => typed_tuple.borrow_ref()?.tuple.len(),
+            Value::TupleVariant(variant_tuple) => variant_tuple.borrow_ref()?.tuple.len(),
+            Value::String(string) => string.borrow_ref()?.len(),
+            Value::StaticString(string) => string.len(),
+            _ => return Ok(index),
+        };
+
+        match &index {
+            Value::Integer(n) if *n < 0 => {
+                let resolved = n.saturating_add(len as i64);
+
+                if resolved >= 0 {
+                    return Ok(Value::Integer(resolved));
+                }
+            }
+            Value::Any(any) => {
+                if let Ok(range) = any.downcast_borrow_ref::<Range>() {
+                    return range.from_end(len).to_value();
+                }
+            }
+            _ => (),
+        }
+
+        Ok(index)
+    }
+
     /// Perform an index get operation specialized for tuples.
     #[inline]
     fn op_tuple_index_get(&mut self, index: usize) -> Result<(), VmError> {
@@ -1105,15 +1957,19 @@ impl Vm {
         }))
     }
 
-    /// Implementation of getting a string index on an object-like type.
+    /// Implementation of getting a string index on an object-like type,
+    /// which pushes the value on the stack if successful.
+    ///
+    /// Like for [IndexGet][Inst::IndexGet], fields missing from a struct are
+    /// looked up through the [INDEX_GET][crate::INDEX_GET] protocol.
     fn try_object_slot_index_get(
         &mut self,
         target: &Value,
         string_slot: usize,
-    ) -> Result<Option<Value>, VmError> {
-        let index = self.unit.lookup_string(string_slot)?;
+    ) -> Result<bool, VmError> {
+        let index = self.unit.lookup_string(string_slot)?.clone();
 
-        Ok(match target {
+        let value = match target {
             Value::Object(object) => {
                 let object = object.borrow_ref()?;
 
@@ -1127,11 +1983,17 @@ impl Vm {
                 }
             }
             Value::TypedObject(typed_object) => {
-                let typed_object = typed_object.borrow_ref()?;
+                let value = typed_object.borrow_ref()?.object.get(&***index).cloned();
 
-                match typed_object.object.get(&***index).cloned() {
+                match value {
                     Some(value) => Some(value),
                     None => {
+                        let index = Value::StaticString(index);
+
+                        if self.call_instance_fn(target, crate::INDEX_GET, (&index,))? {
+                            return Ok(true);
+                        }
+
                         return Err(VmError::from(VmErrorKind::ObjectIndexMissing {
                             slot: string_slot,
                         }));
@@ -1152,14 +2014,17 @@ impl Vm {
             }
             target => {
                 let hash = index.hash();
+                return self.call_getter(target, hash, ());
+            }
+        };
 
-                if self.call_getter(target, hash, ())? {
-                    Some(self.stack.pop()?)
-                } else {
-                    None
-                }
+        match value {
+            Some(value) => {
+                self.stack.push(value);
+                Ok(true)
             }
-        })
+            None => Ok(false),
+        }
     }
 
     /// Perform a specialized index get operation on an object.
@@ -1167,8 +2032,7 @@ impl Vm {
     fn op_object_slot_index_get(&mut self, string_slot: usize) -> Result<(), VmError> {
         let target = self.stack.pop()?;
 
-        if let Some(value) = self.try_object_slot_index_get(&target, string_slot)? {
-            self.stack.push(value);
+        if self.try_object_slot_index_get(&target, string_slot)? {
             return Ok(());
         }
 
@@ -1187,8 +2051,7 @@ impl Vm {
     ) -> Result<(), VmError> {
         let target = self.stack.at_offset(offset)?.clone();
 
-        if let Some(value) = self.try_object_slot_index_get(&target, string_slot)? {
-            self.stack.push(value);
+        if self.try_object_slot_index_get(&target, string_slot)? {
             return Ok(());
         }
 
@@ -1233,7 +2096,83 @@ impl Vm {
             object.insert(key.clone(), value);
         }
 
-        self.stack.push(TypedObject { hash, object });
+        let value = Value::from(TypedObject { hash, object });
+        self.finalizers.track(&self.unit, hash, &value);
+        self.stack.push(value);
+        Ok(())
+    }
+
+    /// Operation to allocate an object, copying the fields which aren't on
+    /// the stack from an existing instance of the same type.
+    #[inline]
+    /// Resolve the name of the given type, looking up types declared in the
+    /// unit by their item.
+    fn type_name(&self, type_info: TypeInfo) -> String {
+        match type_info {
+            TypeInfo::Hash(hash) => match self.unit.lookup_type(hash) {
+                Some(info) => info.item.to_string(),
+                None => type_info.to_string(),
+            },
+            type_info => type_info.to_string(),
+        }
+    }
+
+    fn op_typed_object_update(
+        &mut self,
+        hash: Hash,
+        slot: usize,
+        count: usize,
+    ) -> Result<(), VmError> {
+        let keys = self
+            .unit
+            .lookup_object_keys(slot)
+            .ok_or_else(|| VmError::from(VmErrorKind::MissingStaticObjectKeys { slot }))?;
+
+        let base = match self.stack.pop()? {
+            Value::TypedObject(base) => base,
+            actual => {
+                return Err(VmError::from(VmErrorKind::ExpectedUpdateBase {
+                    expected: self.type_name(TypeInfo::Hash(hash)),
+                    actual: self.type_name(actual.type_info()?),
+                }));
+            }
+        };
+
+        let base = base.borrow_ref()?;
+
+        if base.hash != hash {
+            return Err(VmError::from(VmErrorKind::ExpectedUpdateBase {
+                expected: self.type_name(TypeInfo::Hash(hash)),
+                actual: self.type_name(base.type_info()),
+            }));
+        }
+
+        let mut object = Object::with_capacity(keys.len());
+        let values = self.stack.drain_stack_top(count)?;
+
+        for (key, value) in keys.iter().zip(values) {
+            object.insert(key.clone(), value);
+        }
+
+        for key in keys.iter().skip(count) {
+            let value = match base.object.get(key) {
+                Some(value) => value.clone(),
+                None => {
+                    return Err(VmError::from(VmErrorKind::MissingField {
+                        target: base.type_info(),
+                        field: key.clone(),
+                    }));
+                }
+            };
+
+            object.insert(key.clone(), value);
+        }
+
+        drop(base);
+
+        let value = Value::from(TypedObject { hash, object });
+        self.finalizers.track(&self.unit, hash, &value);
+        self.stack.push(value);
         Ok(())
     }
 
@@ -1273,6 +2212,14 @@ impl Vm {
         Ok(())
     }
 
+    #[inline]
+    fn op_decimal(&mut self, slot: usize) -> Result<(), VmError> {
+        let string = self.unit.lookup_string(slot)?;
+        let decimal = string.parse::<Decimal>().map_err(VmError::panic)?;
+        self.stack.push(decimal.to_value()?);
+        Ok(())
+    }
+
     #[inline]
     fn op_bytes(&mut self, slot: usize) -> Result<(), VmError> {
         let bytes = self.unit.lookup_bytes(slot)?.to_owned();
@@ -1299,8 +2246,7 @@ impl Vm {
                     buf.push_str(buffer.format(integer));
                 }
                 Value::Float(float) => {
-                    let mut buffer = ryu::Buffer::new();
-                    buf.push_str(buffer.format(float));
+                    crate::modules::float::format_shortest(float, &mut buf);
                 }
                 actual => {
                     let b = Shared::new(std::mem::take(&mut buf));
@@ -1393,6 +2339,69 @@ impl Vm {
         Ok(())
     }
 
+    fn op_as(&mut self) -> Result<(), VmError> {
+        let target = self.stack.pop()?;
+        let value = self.stack.pop()?;
+
+        let target = match target {
+            Value::Type(hash) => hash,
+            target => {
+                return Err(VmError::from(VmErrorKind::UnsupportedAs {
+                    value: value.type_info()?,
+                    target: target.value_type()?.as_type_hash(),
+                }));
+            }
+        };
+
+        let converted = if target == crate::INTEGER_TYPE.hash {
+            match &value {
+                Value::Integer(n) => Some(*n),
+                Value::Float(f) => Some(*f as i64),
+                Value::Byte(b) => Some(*b as i64),
+                Value::Char(c) => Some(*c as i64),
+                Value::TupleVariant(variant) => {
+                    let hash = variant.borrow_ref()?.hash;
+                    self.unit.lookup_discriminant(hash)
+                }
+                _ => None,
+            }
+            .map(Value::Integer)
+        } else if target == crate::FLOAT_TYPE.hash {
+            match &value {
+                Value::Integer(n) => Some(Value::Float(*n as f64)),
+                Value::Float(f) => Some(Value::Float(*f)),
+                _ => None,
+            }
+        } else {
+            None
+        };
+
+        let converted = match converted {
+            Some(converted) => converted,
+            None => {
+                return Err(VmError::from(VmErrorKind::UnsupportedAs {
+                    value: value.type_info()?,
+                    target,
+                }));
+            }
+        };
+
+        self.stack.push(converted);
+        Ok(())
+    }
+
+    fn op_from_discriminant(&mut self, enum_hash: Hash) -> Result<(), VmError> {
+        let discriminant = self.stack.pop()?.into_integer()?;
+
+        let variant = self
+            .unit
+            .lookup_variant(enum_hash, discriminant)
+            .map(|hash| Value::variant_tuple(enum_hash, hash, Vec::new()));
+
+        self.stack.push(Value::Option(Shared::new(variant)));
+        Ok(())
+    }
+
     #[inline]
     fn op_is_unit(&mut self) -> Result<(), VmError> {
         let value = self.stack.pop()?;
@@ -1419,9 +2428,40 @@ impl Vm {
         Ok(())
     }
 
-    fn internal_boolean_op(
-        &mut self,
-        bool_op: impl FnOnce(bool, bool) -> bool,
+    /// Enter the value of a `with` expression.
+    #[inline]
+    fn op_enter(&mut self) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        if !self.call_instance_fn(&value, crate::ENTER, ())? {
+            self.stack.push(value);
+        }
+
+        Ok(())
+    }
+
+    /// Exit the value of a `with` expression.
+    #[inline]
+    fn op_exit(&mut self) -> Result<(), VmError> {
+        let value = self.stack.pop()?;
+
+        if self.call_instance_fn(&value, crate::EXIT, ())? {
+            return Ok(());
+        }
+
+        if self.call_instance_fn(&value, Hash::of("close"), ())? {
+            return Ok(());
+        }
+
+        Err(VmError::from(VmErrorKind::MissingProtocol {
+            protocol: crate::EXIT,
+            actual: value.type_info()?,
+        }))
+    }
+
+    fn internal_boolean_op(
+        &mut self,
+        bool_op: impl FnOnce(bool, bool) -> bool,
         op: &'static str,
     ) -> Result<(), VmError> {
         let b = self.stack.pop()?;
@@ -1532,6 +2572,42 @@ impl Vm {
         Ok(())
     }
 
+    /// Destructure an external value through the [MATCH][crate::MATCH]
+    /// protocol.
+    #[inline]
+    fn op_destructure(&mut self, hash: Hash, offset: usize) -> Result<(), VmError> {
+        let value = self.stack.at_offset(offset)?.clone();
+
+        if value.value_type()?.as_type_hash() != hash {
+            self.stack.push(Value::Bool(false));
+            return Ok(());
+        }
+
+        if !self.call_instance_fn(&value, crate::MATCH, ())? {
+            return Err(VmError::from(VmErrorKind::MissingProtocol {
+                protocol: crate::MATCH,
+                actual: value.type_info()?,
+            }));
+        }
+
+        let fields = match self.stack.pop()? {
+            Value::Option(option) => option.borrow_ref()?.clone(),
+            actual => return Err(VmError::expected::<Option<Value>>(actual.type_info()?)),
+        };
+
+        match fields {
+            Some(fields) => {
+                *self.stack.at_offset_mut(offset)? = fields;
+                self.stack.push(Value::Bool(true));
+            }
+            None => {
+                self.stack.push(Value::Bool(false));
+            }
+        }
+
+        Ok(())
+    }
+
     #[inline]
     fn op_match_object(
         &mut self,
@@ -1670,7 +2746,13 @@ impl Vm {
     /// Construct a future from calling an async function.
     fn call_generator_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
-        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
+        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack)
+            .with_events(self.events.clone())
+            .with_statics(self.statics.clone())
+            .with_call_hooks(self.call_hooks.clone())
+            .with_overrides(self.overrides.clone())
+            .with_tracer(self.tracer.clone())
+            .with_cancellation(self.cancellation.clone());
         vm.ip = offset;
         self.stack.push(Generator::new(vm));
         Ok(())
@@ -1679,7 +2761,13 @@ impl Vm {
     /// Construct a stream from calling a function.
     fn call_stream_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
-        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
+        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack)
+            .with_events(self.events.clone())
+            .with_statics(self.statics.clone())
+            .with_call_hooks(self.call_hooks.clone())
+            .with_overrides(self.overrides.clone())
+            .with_tracer(self.tracer.clone())
+            .with_cancellation(self.cancellation.clone());
         vm.ip = offset;
         self.stack.push(Stream::new(vm));
         Ok(())
@@ -1688,7 +2776,13 @@ impl Vm {
     /// Construct a future from calling a function.
     fn call_async_fn(&mut self, offset: usize, args: usize) -> Result<(), VmError> {
         let stack = self.stack.drain_stack_top(args)?.collect::<Stack>();
-        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack);
+        let mut vm = Self::new_with_stack(self.context.clone(), self.unit.clone(), stack)
+            .with_events(self.events.clone())
+            .with_statics(self.statics.clone())
+            .with_call_hooks(self.call_hooks.clone())
+            .with_overrides(self.overrides.clone())
+            .with_tracer(self.tracer.clone())
+            .with_cancellation(self.cancellation.clone());
         vm.ip = offset;
         self.stack.push(Future::new(vm.async_complete()));
         Ok(())
@@ -1736,7 +2830,7 @@ impl Vm {
                     .lookup(hash)
                     .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-                Function::from_handler(handler.clone())
+                Function::from_handler(handler.clone(), self.context.clone(), self.unit.clone())
             }
         };
 
@@ -1773,7 +2867,26 @@ impl Vm {
     }
 
     /// Implementation of a function call.
+    /// Call the intrinsic in the given slot, or fall back to a regular call if
+    /// the context doesn't have a matching intrinsic.
+    fn op_call_intrinsic(&mut self, slot: usize, hash: Hash, args: usize) -> Result<(), VmError> {
+        // NB: intrinsics bypass hooks and overrides, so such calls go through
+        // the regular handlers.
+        if !self.call_hooks.is_empty() || !self.overrides.is_empty() {
+            return self.op_call(hash, args);
+        }
+
+        match self.context.lookup_intrinsic(slot, hash, args) {
+            Some(intrinsic) => intrinsic(&mut self.stack),
+            None => self.op_call(hash, args),
+        }
+    }
+
     fn op_call(&mut self, hash: Hash, args: usize) -> Result<(), VmError> {
+        if let Some(handler) = self.overrides.get(hash) {
+            return self.call_handler(hash, handler, args);
+        }
+
         match self.unit.lookup(hash) {
             Some(info) => match info {
                 UnitFn::Offset {
@@ -1781,8 +2894,23 @@ impl Vm {
                     call,
                     args: expected,
                 } => {
-                    Self::check_args(args, expected)?;
-                    self.call_offset_fn(offset, call, args)?;
+                    self.unit.check_args(offset, args, expected)?;
+
+                    #[cfg(feature = "jit")]
+                    {
+                        if let Some(jit) = &self.jit {
+                            if call == Call::Immediate
+                                && self.call_hooks.is_empty()
+                                && self.overrides.is_empty()
+                                && self.tracer.is_none()
+                                && jit.call(&self.unit, hash, &mut self.stack, args)?
+                            {
+                                return Ok(());
+                            }
+                        }
+                    }
+
+                    self.call_offset_fn_hooked(hash, offset, call, args)?;
                 }
                 UnitFn::Tuple {
                     hash,
@@ -1791,6 +2919,7 @@ impl Vm {
                     Self::check_args(args, expected)?;
                     let tuple = self.stack.pop_sequence(args)?;
                     let value = Value::typed_tuple(hash, tuple);
+                    self.finalizers.track(&self.unit, hash, &value);
                     self.stack.push(value);
                 }
                 UnitFn::TupleVariant {
@@ -1810,7 +2939,21 @@ impl Vm {
                     .lookup(hash)
                     .ok_or_else(|| VmError::from(VmErrorKind::MissingFunction { hash }))?;
 
-                handler(&mut self.stack, args)?;
+                if !self.call_hooks.is_empty() {
+                    let handler = handler.clone();
+                    return self.call_handler_hooked(hash, handler, args);
+                }
+
+                handler(
+                    &mut CallContext::new(
+                        &mut self.stack,
+                        &self.context,
+                        &self.unit,
+                        &self.events,
+                        &self.statics,
+                    ),
+                    args,
+                )?;
             }
         }
 
@@ -1828,6 +2971,10 @@ impl Vm {
         let value_type = instance.value_type()?;
         let hash = Hash::instance_function(value_type, hash);
 
+        if let Some(handler) = self.overrides.get(hash) {
+            return self.call_handler(hash, handler, args);
+        }
+
         match self.unit.lookup(hash) {
             Some(info) => match info {
                 UnitFn::Offset {
@@ -1835,8 +2982,8 @@ impl Vm {
                     call,
                     args: expected,
                 } => {
-                    Self::check_args(args, expected)?;
-                    self.call_offset_fn(offset, call, args)?;
+                    self.unit.check_args(offset, args, expected)?;
+                    self.call_offset_fn_hooked(hash, offset, call, args)?;
                 }
                 _ => {
                     return Err(VmError::from(VmErrorKind::MissingInstanceFunction {
@@ -1856,13 +3003,57 @@ impl Vm {
                     }
                 };
 
-                handler(&mut self.stack, args)?;
+                if !self.call_hooks.is_empty() {
+                    let handler = handler.clone();
+                    return self.call_handler_hooked(hash, handler, args);
+                }
+
+                handler(
+                    &mut CallContext::new(
+                        &mut self.stack,
+                        &self.context,
+                        &self.unit,
+                        &self.events,
+                        &self.statics,
+                    ),
+                    args,
+                )?;
             }
         }
 
         Ok(())
     }
 
+    /// Call an extension function if it's implemented for the instance,
+    /// falling back to a regular instance function call.
+    fn op_call_extension(
+        &mut self,
+        hash: Hash,
+        fallback: Hash,
+        args: usize,
+    ) -> Result<(), VmError> {
+        let instance = self.stack.at_offset_from_top(args + 1)?;
+        let value_type = instance.value_type()?;
+        let hash = Hash::instance_function(value_type, hash);
+
+        if let Some(handler) = self.overrides.get(hash) {
+            return self.call_handler(hash, handler, args + 1);
+        }
+
+        if let Some(UnitFn::Offset {
+            offset,
+            call,
+            args: expected,
+        }) = self.unit.lookup(hash)
+        {
+            self.unit.check_args(offset, args + 1, expected)?;
+            self.call_offset_fn_hooked(hash, offset, call, args + 1)?;
+            return Ok(());
+        }
+
+        self.op_call_instance(fallback, args)
+    }
+
     fn op_call_fn(&mut self, args: usize) -> Result<Option<VmHalt>, VmError> {
         let function = self.stack.pop()?;
 
@@ -1889,329 +3080,538 @@ impl Vm {
         self.ip = self.ip.overflowing_add(1).0;
     }
 
-    /// Evaluate a single instruction.
-    pub(crate) fn run_for(&mut self, mut limit: Option<usize>) -> Result<VmHalt, VmError> {
-        loop {
-            let inst = *self
-                .unit
-                .instruction_at(self.ip)
-                .ok_or_else(|| VmError::from(VmErrorKind::IpOutOfBounds))?;
+    /// Evaluate instructions until the virtual machine halts, or until the
+    /// given number of instructions have been executed.
+    pub(crate) fn run_for(&mut self, limit: Option<usize>) -> Result<VmHalt, VmError> {
+        // NB: the site of the caller is restored once we halt, so that
+        // accesses it acquires after calling into a nested virtual machine
+        // aren't attributed to an instruction in it.
+        let site = crate::access::current_site();
+        let result = self.run_for_inner(limit);
+        crate::access::restore_site(site);
+        result
+    }
 
-            log::trace!("{}: {}", self.ip, inst);
+    fn run_for_inner(&mut self, limit: Option<usize>) -> Result<VmHalt, VmError> {
+        // NB: a separate handle to the unit, so that its instructions can be
+        // borrowed while they are executed.
+        let unit = self.unit.clone();
+        let instructions = unit.instructions();
 
-            match inst {
-                Inst::Not => {
-                    self.op_not()?;
-                }
-                Inst::Add => {
-                    self.op_add()?;
-                }
-                Inst::AddAssign { offset } => {
-                    self.op_add_assign(offset)?;
-                }
-                Inst::Sub => {
-                    self.op_sub()?;
-                }
-                Inst::SubAssign { offset } => {
-                    self.op_sub_assign(offset)?;
-                }
-                Inst::Mul => {
-                    self.op_mul()?;
-                }
-                Inst::MulAssign { offset } => {
-                    self.op_mul_assign(offset)?;
-                }
-                Inst::Div => {
-                    self.op_div()?;
-                }
-                Inst::DivAssign { offset } => {
-                    self.op_div_assign(offset)?;
-                }
-                Inst::Rem => {
-                    self.op_rem()?;
-                }
-                Inst::RemAssign { offset } => {
-                    self.op_rem_assign(offset)?;
-                }
-                Inst::Fn { hash } => {
-                    self.op_fn(hash)?;
-                }
-                Inst::Closure { hash, count } => {
-                    self.op_closure(hash, count)?;
+        if let Some(tracer) = self.tracer.clone() {
+            return self.run_traced(instructions, limit, &tracer);
+        }
+
+        match limit {
+            None => loop {
+                if let Some(halt) = self.step(instructions)? {
+                    return Ok(halt);
                 }
-                Inst::Call { hash, args } => {
-                    self.op_call(hash, args)?;
+            },
+            Some(mut limit) => loop {
+                if let Some(halt) = self.step(instructions)? {
+                    return Ok(halt);
                 }
-                Inst::CallInstance { hash, args } => {
-                    self.op_call_instance(hash, args)?;
+
+                if limit <= 1 {
+                    return Ok(VmHalt::Limited);
                 }
-                Inst::CallFn { args } => {
-                    if let Some(reason) = self.op_call_fn(args)? {
-                        return Ok(reason);
-                    }
+
+                limit -= 1;
+            },
+        }
+    }
+
+    /// The dispatch loop used while tracing, which is kept separate so that
+    /// untraced execution doesn't pay for it.
+    #[cold]
+    fn run_traced(
+        &mut self,
+        instructions: &[Inst],
+        mut limit: Option<usize>,
+        tracer: &Tracer,
+    ) -> Result<VmHalt, VmError> {
+        loop {
+            if let Some(inst) = instructions.get(self.ip) {
+                tracer.trace(self.ip, *inst, &self.stack);
+            }
+
+            #[cfg(not(debug_assertions))]
+            crate::access::set_site(&self.unit, self.ip);
+
+            if let Some(halt) = self.step(instructions)? {
+                return Ok(halt);
+            }
+
+            if let Some(limit) = &mut limit {
+                if *limit <= 1 {
+                    return Ok(VmHalt::Limited);
                 }
-                Inst::LoadInstanceFn { hash } => {
-                    self.op_load_instance_fn(hash)?;
+
+                *limit -= 1;
+            }
+        }
+    }
+
+    /// Execute the instruction at the instruction pointer, returning the
+    /// reason the virtual machine halted, if it did.
+    #[inline(always)]
+    fn step(&mut self, instructions: &[Inst]) -> Result<Option<VmHalt>, VmError> {
+        let inst = *instructions
+            .get(self.ip)
+            .ok_or_else(|| VmError::from(VmErrorKind::IpOutOfBounds))?;
+
+        log::trace!("{}: {}", self.ip, inst);
+
+        // NB: in release builds sites are only recorded while tracing, see
+        // `run_traced`.
+        #[cfg(debug_assertions)]
+        crate::access::set_site(&self.unit, self.ip);
+
+        match inst {
+            Inst::Not => {
+                self.op_not()?;
+            }
+            Inst::Add => {
+                self.op_add()?;
+            }
+            Inst::AddAssign { offset } => {
+                self.op_add_assign(offset)?;
+            }
+            Inst::ConcatAssign { offset } => {
+                self.op_concat_assign(offset)?;
+            }
+            Inst::Sub => {
+                self.op_sub()?;
+            }
+            Inst::SubAssign { offset } => {
+                self.op_sub_assign(offset)?;
+            }
+            Inst::Mul => {
+                self.op_mul()?;
+            }
+            Inst::MulAssign { offset } => {
+                self.op_mul_assign(offset)?;
+            }
+            Inst::Div => {
+                self.op_div()?;
+            }
+            Inst::DivAssign { offset } => {
+                self.op_div_assign(offset)?;
+            }
+            Inst::Rem => {
+                self.op_rem()?;
+            }
+            Inst::RemAssign { offset } => {
+                self.op_rem_assign(offset)?;
+            }
+            Inst::CheckedOp { op } => {
+                self.op_checked(op, None)?;
+            }
+            Inst::CheckedAssign { op, offset } => {
+                self.op_checked(op, Some(offset))?;
+            }
+            Inst::Fn { hash } => {
+                self.op_fn(hash)?;
+            }
+            Inst::Closure { hash, count } => {
+                self.op_closure(hash, count)?;
+            }
+            Inst::Call { hash, args } => {
+                if self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::IndexGet => {
-                    self.op_index_get()?;
+
+                self.op_call(hash, args)?;
+            }
+            Inst::CallIntrinsic { slot, hash, args } => {
+                self.op_call_intrinsic(slot, hash, args)?;
+            }
+            Inst::CallInstance { hash, args } => {
+                if self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::TupleIndexGet { index } => {
-                    self.op_tuple_index_get(index)?;
+
+                self.op_call_instance(hash, args)?;
+            }
+            Inst::CallExtension {
+                hash,
+                fallback,
+                args,
+            } => {
+                if self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::TupleIndexSet { index } => {
-                    self.op_tuple_index_set(index)?;
+
+                self.op_call_extension(hash, fallback, args)?;
+            }
+            Inst::CallFn { args } => {
+                if self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::TupleIndexGetAt { offset, index } => {
-                    self.op_tuple_index_get_at(offset, index)?;
+
+                if let Some(reason) = self.op_call_fn(args)? {
+                    return Ok(Some(reason));
                 }
-                Inst::ObjectSlotIndexGet { slot } => {
-                    self.op_object_slot_index_get(slot)?;
+            }
+            Inst::LoadInstanceFn { hash } => {
+                self.op_load_instance_fn(hash)?;
+            }
+            Inst::IndexGet => {
+                self.op_index_get(false)?;
+            }
+            Inst::NegativeIndexGet => {
+                self.op_index_get(true)?;
+            }
+            Inst::TupleIndexGet { index } => {
+                self.op_tuple_index_get(index)?;
+            }
+            Inst::TupleIndexSet { index } => {
+                self.op_tuple_index_set(index)?;
+            }
+            Inst::TupleIndexGetAt { offset, index } => {
+                self.op_tuple_index_get_at(offset, index)?;
+            }
+            Inst::ObjectSlotIndexGet { slot } => {
+                self.op_object_slot_index_get(slot)?;
+            }
+            Inst::ObjectSlotIndexGetAt { offset, slot } => {
+                self.op_object_slot_index_get_at(offset, slot)?;
+            }
+            Inst::IndexSet => {
+                self.op_index_set()?;
+            }
+            Inst::Return => {
+                if self.op_return()? {
+                    self.advance();
+                    return Ok(Some(VmHalt::Exited));
                 }
-                Inst::ObjectSlotIndexGetAt { offset, slot } => {
-                    self.op_object_slot_index_get_at(offset, slot)?;
+            }
+            Inst::ReturnUnit => {
+                if self.op_return_unit()? {
+                    self.advance();
+                    return Ok(Some(VmHalt::Exited));
                 }
-                Inst::IndexSet => {
-                    self.op_index_set()?;
+            }
+            Inst::ReturnTuple { count } => {
+                if self.op_return_tuple(count)? {
+                    self.advance();
+                    return Ok(Some(VmHalt::Exited));
                 }
-                Inst::Return => {
+            }
+            Inst::ReturnStatic { slot } => {
+                if let Some(value) = self.statics.get_or_init(&self.unit, slot)? {
+                    self.stack.push(value);
+
                     if self.op_return()? {
                         self.advance();
-                        return Ok(VmHalt::Exited);
+                        return Ok(Some(VmHalt::Exited));
                     }
                 }
-                Inst::ReturnUnit => {
-                    if self.op_return_unit()? {
+            }
+            Inst::StoreStatic { slot } => {
+                let value = self.stack.pop()?;
+                self.statics.store(slot, value)?;
+            }
+            Inst::Memoize { slot } => {
+                if let Some(value) = self.op_memoize(slot)? {
+                    self.stack.push(value);
+
+                    if self.op_return()? {
                         self.advance();
-                        return Ok(VmHalt::Exited);
-                    }
-                }
-                Inst::Await => {
-                    let future = self.op_await()?;
-                    // NB: the future itself will advance the virtual machine.
-                    return Ok(VmHalt::Awaited(Awaited::Future(future)));
-                }
-                Inst::Select { len } => {
-                    if let Some(select) = self.op_select(len)? {
-                        // NB: the future itself will advance the virtual machine.
-                        return Ok(VmHalt::Awaited(Awaited::Select(select)));
+                        return Ok(Some(VmHalt::Exited));
                     }
                 }
-                Inst::Pop => {
-                    self.stack.pop()?;
-                }
-                Inst::PopN { count } => {
-                    self.op_popn(count)?;
-                }
-                Inst::PopAndJumpIfNot { count, offset } => {
-                    self.op_pop_and_jump_if_not(count, offset)?;
-                }
-                Inst::Clean { count } => {
-                    self.op_clean(count)?;
-                }
-                Inst::Integer { number } => {
-                    self.stack.push(Value::Integer(number));
-                }
-                Inst::Float { number } => {
-                    self.stack.push(Value::Float(number));
-                }
-                Inst::Copy { offset } => {
-                    self.op_copy(offset)?;
-                }
-                Inst::Drop { offset } => {
-                    self.op_drop(offset)?;
-                }
-                Inst::Dup => {
-                    self.op_dup()?;
-                }
-                Inst::Replace { offset } => {
-                    self.op_replace(offset)?;
-                }
-                Inst::Gt => {
-                    self.op_gt()?;
-                }
-                Inst::Gte => {
-                    self.op_gte()?;
-                }
-                Inst::Lt => {
-                    self.op_lt()?;
-                }
-                Inst::Lte => {
-                    self.op_lte()?;
-                }
-                Inst::Eq => {
-                    self.op_eq()?;
-                }
-                Inst::Neq => {
-                    self.op_neq()?;
-                }
-                Inst::Jump { offset } => {
-                    self.op_jump(offset)?;
-                }
-                Inst::JumpIf { offset } => {
-                    self.op_jump_if(offset)?;
-                }
-                Inst::JumpIfNot { offset } => {
-                    self.op_jump_if_not(offset)?;
-                }
-                Inst::JumpIfBranch { branch, offset } => {
-                    self.op_jump_if_branch(branch, offset)?;
-                }
-                Inst::Unit => {
-                    self.stack.push(Value::Unit);
-                }
-                Inst::Bool { value } => {
-                    self.stack.push(Value::Bool(value));
-                }
-                Inst::Vec { count } => {
-                    self.op_vec(count)?;
-                }
-                Inst::Tuple { count } => {
-                    self.op_tuple(count)?;
-                }
-                Inst::PushTuple => {
-                    self.op_push_tuple()?;
-                }
-                Inst::Object { slot } => {
-                    self.op_object(slot)?;
-                }
-                Inst::TypedObject { hash, slot } => {
-                    self.op_typed_object(hash, slot)?;
-                }
-                Inst::VariantObject {
-                    enum_hash,
-                    hash,
-                    slot,
-                } => {
-                    self.op_variant_object(enum_hash, hash, slot)?;
-                }
-                Inst::Type { hash } => {
-                    self.stack.push(Value::Type(hash));
-                }
-                Inst::Char { c } => {
-                    self.stack.push(Value::Char(c));
-                }
-                Inst::Byte { b } => {
-                    self.stack.push(Value::Byte(b));
-                }
-                Inst::String { slot } => {
-                    self.op_string(slot)?;
-                }
-                Inst::Bytes { slot } => {
-                    self.op_bytes(slot)?;
-                }
-                Inst::StringConcat { len, size_hint } => {
-                    self.op_string_concat(len, size_hint)?;
-                }
-                Inst::Is => {
-                    self.op_is()?;
-                }
-                Inst::IsNot => {
-                    self.op_is_not()?;
-                }
-                Inst::IsUnit => {
-                    self.op_is_unit()?;
-                }
-                Inst::IsValue => {
-                    self.op_is_value()?;
-                }
-                Inst::Unwrap => {
-                    self.op_unwrap()?;
-                }
-                Inst::And => {
-                    self.op_and()?;
-                }
-                Inst::Or => {
-                    self.op_or()?;
-                }
-                Inst::BitAnd => {
-                    self.op_bit_and()?;
-                }
-                Inst::BitAndAssign { offset } => {
-                    self.op_bit_and_assign(offset)?;
-                }
-                Inst::BitXor => {
-                    self.op_bit_xor()?;
-                }
-                Inst::BitXorAssign { offset } => {
-                    self.op_bit_xor_assign(offset)?;
-                }
-                Inst::BitOr => {
-                    self.op_bit_or()?;
-                }
-                Inst::BitOrAssign { offset } => {
-                    self.op_bit_or_assign(offset)?;
-                }
-                Inst::Shl => {
-                    self.op_shl()?;
-                }
-                Inst::ShlAssign { offset } => {
-                    self.op_shl_assign(offset)?;
-                }
-                Inst::Shr => {
-                    self.op_shr()?;
-                }
-                Inst::ShrAssign { offset } => {
-                    self.op_shr_assign(offset)?;
-                }
-                Inst::EqByte { byte } => {
-                    self.op_eq_byte(byte)?;
-                }
-                Inst::EqCharacter { character } => {
-                    self.op_eq_character(character)?;
-                }
-                Inst::EqInteger { integer } => {
-                    self.op_eq_integer(integer)?;
-                }
-                Inst::EqStaticString { slot } => {
-                    self.op_eq_static_string(slot)?;
+            }
+            Inst::Await => {
+                if self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::MatchSequence {
-                    type_check,
-                    len,
-                    exact,
-                } => {
-                    self.op_match_sequence(type_check, len, exact)?;
+
+                let future = self.op_await()?;
+                // NB: the future itself will advance the virtual machine.
+                return Ok(Some(VmHalt::Awaited(Awaited::Future(future))));
+            }
+            Inst::Select { len } => {
+                if self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::MatchObject {
-                    type_check,
-                    slot,
-                    exact,
-                } => {
-                    self.op_match_object(type_check, slot, exact)?;
+
+                if let Some(select) = self.op_select(len)? {
+                    // NB: the future itself will advance the virtual machine.
+                    return Ok(Some(VmHalt::Awaited(Awaited::Select(select))));
                 }
-                Inst::Yield => {
-                    self.advance();
-                    return Ok(VmHalt::Yielded);
+            }
+            Inst::Pop => {
+                self.stack.pop()?;
+            }
+            Inst::PopN { count } => {
+                self.op_popn(count)?;
+            }
+            Inst::PopAndJumpIfNot { count, offset } => {
+                self.op_pop_and_jump_if_not(count, offset)?;
+            }
+            Inst::Clean { count } => {
+                self.op_clean(count)?;
+            }
+            Inst::Integer { number } => {
+                self.stack.push(Value::Integer(number));
+            }
+            Inst::Float { number } => {
+                self.stack.push(Value::Float(number));
+            }
+            Inst::Copy { offset } => {
+                self.op_copy(offset)?;
+            }
+            Inst::Drop { offset } => {
+                self.op_drop(offset)?;
+            }
+            Inst::Dup => {
+                self.op_dup()?;
+            }
+            Inst::Replace { offset } => {
+                self.op_replace(offset)?;
+            }
+            Inst::Gt => {
+                self.op_gt()?;
+            }
+            Inst::Gte => {
+                self.op_gte()?;
+            }
+            Inst::Lt => {
+                self.op_lt()?;
+            }
+            Inst::Lte => {
+                self.op_lte()?;
+            }
+            Inst::Eq => {
+                self.op_eq()?;
+            }
+            Inst::Neq => {
+                self.op_neq()?;
+            }
+            Inst::Jump { offset } => {
+                if offset < 0 && self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::YieldUnit => {
-                    self.advance();
-                    self.stack.push(Value::Unit);
-                    return Ok(VmHalt::Yielded);
+
+                self.op_jump(offset)?;
+            }
+            Inst::JumpIf { offset } => {
+                if offset < 0 && self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
-                Inst::Panic { reason } => {
-                    return Err(VmError::from(VmErrorKind::Panic {
-                        reason: Panic::from(reason),
-                    }));
+
+                self.op_jump_if(offset)?;
+            }
+            Inst::JumpIfNot { offset } => {
+                if offset < 0 && self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
+
+                self.op_jump_if_not(offset)?;
             }
+            Inst::JumpIfOrPop { offset } => {
+                if offset < 0 && self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
+                }
 
-            self.advance();
+                self.op_jump_if_or_pop(true, offset)?;
+            }
+            Inst::JumpIfNotOrPop { offset } => {
+                if offset < 0 && self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
+                }
 
-            if let Some(limit) = &mut limit {
-                if *limit <= 1 {
-                    return Ok(VmHalt::Limited);
+                self.op_jump_if_or_pop(false, offset)?;
+            }
+            Inst::JumpIfBranch { branch, offset } => {
+                if offset < 0 && self.is_cancelled() {
+                    return Ok(Some(VmHalt::Cancelled));
                 }
 
-                *limit -= 1;
+                self.op_jump_if_branch(branch, offset)?;
+            }
+            Inst::Unit => {
+                self.stack.push(Value::Unit);
+            }
+            Inst::Bool { value } => {
+                self.stack.push(Value::Bool(value));
+            }
+            Inst::Vec { count } => {
+                self.op_vec(count)?;
+            }
+            Inst::Tuple { count } => {
+                self.op_tuple(count)?;
+            }
+            Inst::Unpack { count } => {
+                self.op_unpack(count)?;
+            }
+            Inst::Range => {
+                self.op_range()?;
+            }
+            Inst::PushTuple => {
+                self.op_push_tuple()?;
+            }
+            Inst::Object { slot } => {
+                self.op_object(slot)?;
+            }
+            Inst::TypedObject { hash, slot } => {
+                self.op_typed_object(hash, slot)?;
+            }
+            Inst::TypedObjectUpdate { hash, slot, count } => {
+                self.op_typed_object_update(hash, slot, count)?;
+            }
+            Inst::VariantObject {
+                enum_hash,
+                hash,
+                slot,
+            } => {
+                self.op_variant_object(enum_hash, hash, slot)?;
+            }
+            Inst::Type { hash } => {
+                self.stack.push(Value::Type(hash));
+            }
+            Inst::Char { c } => {
+                self.stack.push(Value::Char(c));
+            }
+            Inst::Byte { b } => {
+                self.stack.push(Value::Byte(b));
+            }
+            Inst::Decimal { slot } => {
+                self.op_decimal(slot)?;
+            }
+            Inst::String { slot } => {
+                self.op_string(slot)?;
+            }
+            Inst::Bytes { slot } => {
+                self.op_bytes(slot)?;
+            }
+            Inst::StringConcat { len, size_hint } => {
+                self.op_string_concat(len, size_hint)?;
+            }
+            Inst::Is => {
+                self.op_is()?;
+            }
+            Inst::IsNot => {
+                self.op_is_not()?;
+            }
+            Inst::As => {
+                self.op_as()?;
+            }
+            Inst::FromDiscriminant { enum_hash } => {
+                self.op_from_discriminant(enum_hash)?;
+            }
+            Inst::IsUnit => {
+                self.op_is_unit()?;
+            }
+            Inst::IsValue => {
+                self.op_is_value()?;
+            }
+            Inst::Unwrap => {
+                self.op_unwrap()?;
+            }
+            Inst::Enter => {
+                self.op_enter()?;
+            }
+            Inst::Exit => {
+                self.op_exit()?;
+            }
+            Inst::And => {
+                self.op_and()?;
+            }
+            Inst::Or => {
+                self.op_or()?;
+            }
+            Inst::BitAnd => {
+                self.op_bit_and()?;
+            }
+            Inst::BitAndAssign { offset } => {
+                self.op_bit_and_assign(offset)?;
+            }
+            Inst::BitXor => {
+                self.op_bit_xor()?;
+            }
+            Inst::BitXorAssign { offset } => {
+                self.op_bit_xor_assign(offset)?;
+            }
+            Inst::BitOr => {
+                self.op_bit_or()?;
+            }
+            Inst::BitOrAssign { offset } => {
+                self.op_bit_or_assign(offset)?;
+            }
+            Inst::Shl => {
+                self.op_shl()?;
+            }
+            Inst::ShlAssign { offset } => {
+                self.op_shl_assign(offset)?;
+            }
+            Inst::Shr => {
+                self.op_shr()?;
+            }
+            Inst::ShrAssign { offset } => {
+                self.op_shr_assign(offset)?;
+            }
+            Inst::EqByte { byte } => {
+                self.op_eq_byte(byte)?;
+            }
+            Inst::EqCharacter { character } => {
+                self.op_eq_character(character)?;
+            }
+            Inst::EqInteger { integer } => {
+                self.op_eq_integer(integer)?;
+            }
+            Inst::EqStaticString { slot } => {
+                self.op_eq_static_string(slot)?;
+            }
+            Inst::MatchSequence {
+                type_check,
+                len,
+                exact,
+            } => {
+                self.op_match_sequence(type_check, len, exact)?;
+            }
+            Inst::Destructure { hash, offset } => {
+                self.op_destructure(hash, offset)?;
+            }
+            Inst::MatchObject {
+                type_check,
+                slot,
+                exact,
+            } => {
+                self.op_match_object(type_check, slot, exact)?;
+            }
+            Inst::Yield => {
+                self.advance();
+                return Ok(Some(VmHalt::Yielded));
+            }
+            Inst::YieldUnit => {
+                self.advance();
+                self.stack.push(Value::Unit);
+                return Ok(Some(VmHalt::Yielded));
+            }
+            Inst::Disposed => {
+                let disposing = mem::take(&mut self.disposing);
+                self.stack.push(Value::Bool(disposing));
+            }
+            Inst::Panic { reason } => {
+                return Err(VmError::from(VmErrorKind::Panic {
+                    reason: Panic::from(reason),
+                }));
             }
         }
+
+        self.advance();
+        Ok(None)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn internal_num_assign<H, E, I, F>(
         &mut self,
         offset: usize,
         hash: H,
+        fallback: Protocol,
         error: E,
         integer_op: I,
         float_op: F,
@@ -2220,9 +3620,13 @@ impl Vm {
     where
         H: IntoHash,
         E: Copy + FnOnce() -> VmError,
-        I: FnOnce(i64, i64) -> Option<i64>,
-        F: FnOnce(f64, f64) -> f64,
+        I: Copy + FnOnce(i64, i64) -> Option<i64>,
+        F: Copy + FnOnce(f64, f64) -> f64,
     {
+        if self.quickened_num_assign(offset, error, integer_op, float_op)? {
+            return Ok(());
+        }
+
         let rhs = self.stack.pop()?;
         let lhs = self.stack.at_offset_mut(offset)?;
 
@@ -2240,7 +3644,14 @@ impl Vm {
             (lhs, rhs) => (lhs.clone(), rhs),
         };
 
-        if !self.call_instance_fn(&lhs, hash, (&rhs,))? {
+        if self.call_instance_fn(&lhs, hash, (&rhs,))? {
+            self.stack.pop()?;
+            return Ok(());
+        }
+
+        // NB: types which only implement the plain operation, like values
+        // which are immutable, have the result stored in the slot instead.
+        if !self.call_instance_fn(&lhs, fallback, (&rhs,))? {
             return Err(VmError::from(VmErrorKind::UnsupportedBinaryOperation {
                 op,
                 lhs: lhs.type_info()?,
@@ -2248,7 +3659,8 @@ impl Vm {
             }));
         }
 
-        self.stack.pop()?;
+        let value = self.stack.pop()?;
+        *self.stack.at_offset_mut(offset)? = value;
         Ok(())
     }
 
@@ -2264,9 +3676,13 @@ impl Vm {
     where
         H: IntoHash,
         E: Copy + FnOnce() -> VmError,
-        I: FnOnce(i64, i64) -> Option<i64>,
-        F: FnOnce(f64, f64) -> f64,
+        I: Copy + FnOnce(i64, i64) -> Option<i64>,
+        F: Copy + FnOnce(f64, f64) -> f64,
     {
+        if self.quickened_num(error, integer_op, float_op)? {
+            return Ok(());
+        }
+
         let rhs = self.stack.pop()?;
         let lhs = self.stack.pop()?;
 
@@ -2293,6 +3709,78 @@ impl Vm {
         Ok(())
     }
 
+    /// Perform a numeric operation in place if the instruction is quickened,
+    /// returning `false` if the generic implementation has to be used.
+    #[inline]
+    fn quickened_num<E, I, F>(
+        &mut self,
+        error: E,
+        integer_op: I,
+        float_op: F,
+    ) -> Result<bool, VmError>
+    where
+        E: FnOnce() -> VmError,
+        I: FnOnce(i64, i64) -> Option<i64>,
+        F: FnOnce(f64, f64) -> f64,
+    {
+        let (lhs, rhs) = match self.stack.last_pair_mut() {
+            Some(pair) => pair,
+            None => return Ok(false),
+        };
+
+        if !self.sites.observe(self.ip, Site::of(lhs, rhs)) {
+            return Ok(false);
+        }
+
+        match (lhs, rhs) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => {
+                *lhs = integer_op(*lhs, *rhs).ok_or_else(error)?;
+            }
+            (Value::Float(lhs), Value::Float(rhs)) => {
+                *lhs = float_op(*lhs, *rhs);
+            }
+            _ => return Ok(false),
+        }
+
+        self.stack.pop()?;
+        Ok(true)
+    }
+
+    /// Perform a numeric assignment in place if the instruction is quickened,
+    /// returning `false` if the generic implementation has to be used.
+    #[inline]
+    fn quickened_num_assign<E, I, F>(
+        &mut self,
+        offset: usize,
+        error: E,
+        integer_op: I,
+        float_op: F,
+    ) -> Result<bool, VmError>
+    where
+        E: FnOnce() -> VmError,
+        I: FnOnce(i64, i64) -> Option<i64>,
+        F: FnOnce(f64, f64) -> f64,
+    {
+        let lhs = self.stack.at_offset(offset)?;
+        let rhs = self.stack.last()?;
+
+        if !self.sites.observe(self.ip, Site::of(lhs, rhs)) {
+            return Ok(false);
+        }
+
+        let value = match (lhs, rhs) {
+            (Value::Integer(lhs), Value::Integer(rhs)) => {
+                Value::Integer(integer_op(*lhs, *rhs).ok_or_else(error)?)
+            }
+            (Value::Float(lhs), Value::Float(rhs)) => Value::Float(float_op(*lhs, *rhs)),
+            _ => return Ok(false),
+        };
+
+        self.stack.pop()?;
+        *self.stack.at_offset_mut(offset)? = value;
+        Ok(true)
+    }
+
     /// Internal impl of a numeric operation.
     fn internal_infallible_bitwise<H, I>(
         &mut self,
@@ -2431,8 +3919,8 @@ impl Vm {
     }
 
     /// Check that arguments matches expected or raise the appropriate error.
-    fn check_args(args: usize, expected: usize) -> Result<(), VmError> {
-        if args != args {
+    pub(crate) fn check_args(args: usize, expected: usize) -> Result<(), VmError> {
+        if args != expected {
             return Err(VmError::from(VmErrorKind::BadArgumentCount {
                 actual: args,
                 expected,
@@ -2443,6 +3931,29 @@ impl Vm {
     }
 }
 
+/// A call to a script function which is waiting for its call frame to return.
+#[derive(Debug, Clone, Copy)]
+struct HookedFrame {
+    /// The number of call frames while the function is running.
+    depth: usize,
+    /// The hash of the called function.
+    hash: Hash,
+    /// The number of arguments the function was called with.
+    args: usize,
+}
+
+/// A call to a memoized function which is waiting for its call frame to
+/// return.
+#[derive(Debug, Clone)]
+struct MemoizedFrame {
+    /// The number of call frames while the function is running.
+    depth: usize,
+    /// The slot of the memoized function.
+    slot: usize,
+    /// The arguments the function was called with.
+    key: MemoizeKey,
+}
+
 /// A call frame.
 ///
 /// This is used to store the return point after an instruction has been run.
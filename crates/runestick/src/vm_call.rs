@@ -15,12 +15,15 @@ impl VmCall {
 
     /// Encode the push itno an execution.
     pub(crate) fn into_execution<'vm>(self, execution: &mut VmExecution) -> Result<(), VmError> {
+        let cancellation = execution.vm()?.cancellation().cloned();
+        let vm = self.vm.with_cancellation(cancellation);
+
         let value = match self.call {
-            Call::Async => Value::from(Future::new(self.vm.async_complete())),
-            Call::Stream => Value::from(Stream::new(self.vm)),
-            Call::Generator => Value::from(Generator::new(self.vm)),
+            Call::Async => Value::from(Future::new(vm.async_complete())),
+            Call::Stream => Value::from(Stream::new(vm)),
+            Call::Generator => Value::from(Generator::new(vm)),
             Call::Immediate => {
-                execution.push_vm(self.vm);
+                execution.push_vm(vm);
                 return Ok(());
             }
         };
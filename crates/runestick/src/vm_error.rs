@@ -1,8 +1,10 @@
+use crate::debug::DebugSignature;
 use crate::panic::BoxedPanic;
 use crate::{
-    AccessError, Hash, Integer, Panic, Protocol, StackError, TypeInfo, Unit, Value, ValueType,
-    VmHaltInfo,
+    AbiError, AccessError, Hash, Integer, Item, Panic, Protocol, StackError, TypeInfo, Unit, Value,
+    ValueType, VmHaltInfo,
 };
+use std::fmt;
 use std::sync::Arc;
 use thiserror::Error;
 
@@ -57,6 +59,74 @@ impl VmError {
         &*self.kind
     }
 
+    /// Wrap the error with context describing what was being done when it
+    /// happened.
+    ///
+    /// If the error has been unwound, the context is added inside of it so
+    /// that where the error happened is preserved.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use runestick::{VmError, VmErrorKind};
+    ///
+    /// let error = VmError::from(VmErrorKind::DivideByZero)
+    ///     .with_context("calculating average")
+    ///     .with_context("rendering report");
+    ///
+    /// let chain = error.chain().map(|e| e.to_string()).collect::<Vec<_>>();
+    /// assert_eq!(chain, vec!["rendering report", "calculating average", "division by zero"]);
+    /// ```
+    pub fn with_context<C>(self, context: C) -> Self
+    where
+        C: fmt::Display,
+    {
+        match *self.kind {
+            VmErrorKind::Unwound { kind, unit, ip } => Self::from(VmErrorKind::Unwound {
+                kind: Box::new(VmErrorKind::Context {
+                    context: context.to_string(),
+                    error: Self { kind },
+                }),
+                unit,
+                ip,
+            }),
+            kind => Self::from(VmErrorKind::Context {
+                context: context.to_string(),
+                error: Self::from(kind),
+            }),
+        }
+    }
+
+    /// Wrap the error as having happened while converting the given element of
+    /// a container, like an index in a vector.
+    ///
+    /// Errors which already happened inside of an element get the step added
+    /// to the start of their path, so that the path leads from the outermost
+    /// container to the element which failed to convert.
+    pub fn with_element(self, step: ElementStep) -> Self {
+        match *self.kind {
+            VmErrorKind::BadElement { mut path, error } => {
+                path.steps.insert(0, step);
+                Self::from(VmErrorKind::BadElement { path, error })
+            }
+            kind => Self::from(VmErrorKind::BadElement {
+                path: ElementPath { steps: vec![step] },
+                error: Self::from(kind),
+            }),
+        }
+    }
+
+    /// Iterate over the chain of errors, starting with this error and
+    /// followed by the errors which caused it.
+    ///
+    /// Unwound errors are looked through, so every error in the chain
+    /// describes what went wrong.
+    pub fn chain(&self) -> VmErrorChain<'_> {
+        VmErrorChain {
+            next: Some(&*self.kind),
+        }
+    }
+
     /// Convert into an unwinded vm error.
     pub fn into_unwinded(self, unit: &Arc<Unit>, ip: usize) -> Self {
         if let VmErrorKind::Unwound { .. } = &*self.kind {
@@ -98,11 +168,40 @@ impl VmError {
         match &*self.kind {
             VmErrorKind::Panic { .. } => true,
             VmErrorKind::Unwound { .. } => true,
+            VmErrorKind::Context { error, .. } => error.is_critical(),
             _ => false,
         }
     }
 }
 
+/// An iterator over the chain of errors which caused a [VmError], see
+/// [VmError::chain].
+pub struct VmErrorChain<'a> {
+    next: Option<&'a VmErrorKind>,
+}
+
+impl<'a> Iterator for VmErrorChain<'a> {
+    type Item = &'a VmErrorKind;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut kind = self.next.take()?;
+
+        while let VmErrorKind::Unwound { kind: inner, .. } = kind {
+            kind = &**inner;
+        }
+
+        self.next = match kind {
+            VmErrorKind::Context { error, .. } => Some(error.kind()),
+            VmErrorKind::BadArgument { error, .. } => Some(error.kind()),
+            VmErrorKind::BadReturn { error, .. } => Some(error.kind()),
+            VmErrorKind::BadElement { error, .. } => Some(error.kind()),
+            _ => None,
+        };
+
+        Some(kind)
+    }
+}
+
 impl<E> From<E> for VmError
 where
     VmErrorKind: From<E>,
@@ -130,6 +229,16 @@ pub enum VmErrorKind {
         /// The instruction pointer of where the original error happened.
         ip: usize,
     },
+    /// An error with context describing what was being done when it happened,
+    /// see [VmError::with_context].
+    #[error("{context}")]
+    Context {
+        /// The context of the error.
+        context: String,
+        /// The error which happened.
+        #[source]
+        error: VmError,
+    },
     /// The virtual machine panicked for a specific reason.
     #[error("panicked `{reason}`")]
     Panic {
@@ -155,6 +264,14 @@ pub enum VmErrorKind {
         #[from]
         error: StackError,
     },
+    /// Error raised when a unit is not compatible with the runtime or
+    /// context.
+    #[error("incompatible unit: {error}")]
+    AbiError {
+        /// The source error.
+        #[from]
+        error: AbiError,
+    },
     /// The virtual machine encountered a numerical overflow.
     #[error("numerical overflow")]
     Overflow,
@@ -170,6 +287,40 @@ pub enum VmErrorKind {
         /// Hash of function to look up.
         hash: Hash,
     },
+    /// No unit is registered under the given name in a
+    /// [UnitRegistry][crate::UnitRegistry].
+    #[error("missing unit `{name}`")]
+    MissingUnit {
+        /// The name of the unit.
+        name: String,
+    },
+    /// A native function was called which requires a capability that the
+    /// context doesn't grant.
+    #[error("function requires the capability `{capability}`, which is not granted")]
+    MissingCapability {
+        /// The capability which is not granted.
+        capability: String,
+    },
+    /// A handle was used as a handle to a resource of a different type.
+    #[error("expected handle to `{expected}`, but found handle to `{actual}`")]
+    UnexpectedHandle {
+        /// The type of resource which was expected.
+        expected: &'static str,
+        /// The type of resource in the handle.
+        actual: &'static str,
+    },
+    /// A finalizer executed too many instructions.
+    #[error("finalizer exceeded its budget of {budget} instructions")]
+    FinalizerBudgetExceeded {
+        /// The number of instructions a finalizer may execute.
+        budget: usize,
+    },
+    /// A handle was used after it was closed.
+    #[error("handle to `{type_name}` is closed")]
+    HandleClosed {
+        /// The type of resource in the handle.
+        type_name: &'static str,
+    },
     /// Failure to lookup instance function.
     #[error("missing instance function `{hash}` for `{instance}`")]
     MissingInstanceFunction {
@@ -213,6 +364,12 @@ pub enum VmErrorKind {
         /// The encountered argument.
         actual: TypeInfo,
     },
+    /// Value could not be hashed.
+    #[error("`{actual}` does not support structural hashing")]
+    UnsupportedHash {
+        /// The encountered value.
+        actual: TypeInfo,
+    },
     /// Indicates that a static string is missing for the given slot.
     #[error("static string slot `{slot}` does not exist")]
     MissingStaticString {
@@ -225,6 +382,28 @@ pub enum VmErrorKind {
         /// Slot which is missing a static object keys.
         slot: usize,
     },
+    /// Indicates that a static is missing for the given slot.
+    #[error("static slot `{slot}` does not exist")]
+    MissingStatic {
+        /// Slot which is missing a static.
+        slot: usize,
+    },
+    /// Indicates that a memoized function is missing for the given slot.
+    #[error("memoized function slot `{slot}` does not exist")]
+    MissingMemoized {
+        /// Slot which is missing a memoized function.
+        slot: usize,
+    },
+    /// A static was accessed while it was being initialized, which happens
+    /// when its initializer depends on itself.
+    #[error("static `{item}` was accessed while it was being initialized")]
+    StaticInitializing {
+        /// The item of the static.
+        item: Item,
+    },
+    /// A lazy value was accessed while its initializer was running.
+    #[error("lazy value was accessed while it was being initialized")]
+    LazyInitializing,
     /// Wrong number of arguments provided in call.
     #[error("wrong number of arguments `{actual}`, expected `{expected}`")]
     BadArgumentCount {
@@ -233,6 +412,16 @@ pub enum VmErrorKind {
         /// The expected number of arguments.
         expected: usize,
     },
+    /// Wrong number of arguments provided in a call to a function in a unit.
+    #[error("wrong number of arguments `{actual}` to `{signature}`, expected `{expected}`")]
+    BadFunctionArgumentCount {
+        /// The signature of the function being called.
+        signature: DebugSignature,
+        /// The actual number of arguments.
+        actual: usize,
+        /// The expected number of arguments.
+        expected: usize,
+    },
     /// Failure to convert from one type to another.
     #[error("bad argument #{arg}, expected `{expected}` but got `{actual}`")]
     BadArgumentType {
@@ -263,6 +452,16 @@ pub enum VmErrorKind {
         /// Type of the return value we attempted to convert.
         ret: &'static str,
     },
+    /// Failure to convert an element inside of a container, see
+    /// [VmError::with_element].
+    #[error("bad element at `{path}`: {error}")]
+    BadElement {
+        /// The path to the element which failed to convert.
+        path: ElementPath,
+        /// Error describing the failed conversion.
+        #[source]
+        error: VmError,
+    },
     /// An index set operation that is not supported.
     #[error("the index set operation `{target}[{index}] = {value}` is not supported")]
     UnsupportedIndexSet {
@@ -307,6 +506,14 @@ pub enum VmErrorKind {
         /// The type that is not supported.
         test_type: TypeInfo,
     },
+    /// An `as` conversion is not supported.
+    #[error("cannot convert `{value}` into type `{target}`")]
+    UnsupportedAs {
+        /// The value being converted.
+        value: TypeInfo,
+        /// The hash of the type it's being converted into.
+        target: Hash,
+    },
     /// Encountered a value that could not be called as a function
     #[error("`{actual_type}` cannot be called since it's not a function")]
     UnsupportedCallFn {
@@ -375,6 +582,15 @@ pub enum VmErrorKind {
         /// The actual type found.
         actual: TypeInfo,
     },
+    /// Error raised when the base of a struct update is not an instance of the
+    /// struct being constructed.
+    #[error("expected an instance of `{expected}` to update, but found `{actual}`")]
+    ExpectedUpdateBase {
+        /// The name of the struct being constructed.
+        expected: String,
+        /// The name of the type found instead.
+        actual: String,
+    },
     /// Error raised when we expected a value.
     #[error("expected `Any` type, but found `{actual}`")]
     ExpectedAny {
@@ -418,4 +634,65 @@ impl VmErrorKind {
             kind => (kind, None),
         }
     }
+
+    /// Find the access error which caused this error, if there is one.
+    ///
+    /// This looks through errors which wrap other errors, like the ones raised
+    /// when converting arguments or return values.
+    pub fn access_error(&self) -> Option<&AccessError> {
+        match self {
+            VmErrorKind::AccessError { error } => Some(error),
+            VmErrorKind::Unwound { kind, .. } => kind.access_error(),
+            VmErrorKind::Context { error, .. } => error.kind().access_error(),
+            VmErrorKind::BadArgument { error, .. } => error.kind().access_error(),
+            VmErrorKind::BadReturn { error, .. } => error.kind().access_error(),
+            VmErrorKind::BadElement { error, .. } => error.kind().access_error(),
+            _ => None,
+        }
+    }
+}
+
+/// The path to an element inside of nested containers which failed to convert,
+/// like `[2]["name"].0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElementPath {
+    steps: Vec<ElementStep>,
+}
+
+impl ElementPath {
+    /// The steps leading from the outermost container to the element.
+    pub fn steps(&self) -> &[ElementStep] {
+        &self.steps
+    }
+}
+
+impl fmt::Display for ElementPath {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            write!(fmt, "{}", step)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A single step in an [ElementPath].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElementStep {
+    /// An index in a vector, like `[2]`.
+    Index(usize),
+    /// A key in an object, like `["name"]`.
+    Key(String),
+    /// A field in a tuple, like `.0`.
+    TupleIndex(usize),
+}
+
+impl fmt::Display for ElementStep {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(fmt, "[{}]", index),
+            Self::Key(key) => write!(fmt, "[{:?}]", key),
+            Self::TupleIndex(index) => write!(fmt, ".{}", index),
+        }
+    }
 }
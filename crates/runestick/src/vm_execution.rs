@@ -3,12 +3,46 @@ use crate::{GeneratorState, Value, Vm, VmError, VmErrorKind, VmHalt, VmHaltInfo}
 /// The execution environment for a virtual machine.
 pub struct VmExecution {
     vms: Vec<Vm>,
+    /// The virtual machine which started the execution, once the execution
+    /// has completed.
+    completed: Option<Vm>,
 }
 
 impl VmExecution {
     /// Construct an execution from a virtual machine.
     pub(crate) fn new(vm: Vm) -> Self {
-        Self { vms: vec![vm] }
+        Self {
+            vms: vec![vm],
+            completed: None,
+        }
+    }
+
+    /// Take the virtual machine which started the execution.
+    pub(crate) fn into_vm(self) -> Option<Vm> {
+        match self.completed {
+            Some(vm) => Some(vm),
+            None => self.vms.into_iter().next(),
+        }
+    }
+
+    /// Iterate over the virtual machines of the execution, from the one which
+    /// started it to the one currently executing.
+    pub(crate) fn vms(&self) -> impl Iterator<Item = &Vm> + '_ {
+        self.vms.iter()
+    }
+
+    /// Dispose of the execution, releasing the values held by its virtual
+    /// machines from the innermost one and outwards.
+    pub(crate) fn dispose(mut self) -> Result<(), VmError> {
+        while let Some(mut vm) = self.vms.pop() {
+            vm.dispose()?;
+        }
+
+        if let Some(mut vm) = self.completed.take() {
+            vm.dispose()?;
+        }
+
+        Ok(())
     }
 
     /// Get the current virtual machine.
@@ -83,7 +117,7 @@ impl VmExecution {
             if len == 1 {
                 let value = vm.stack_mut().pop()?;
                 debug_assert!(vm.stack().is_empty(), "the final vm should be empty");
-                self.vms.clear();
+                self.completed = self.vms.pop();
                 return Ok(GeneratorState::Complete(value));
             }
 
@@ -116,7 +150,7 @@ impl VmExecution {
             if len == 1 {
                 let value = vm.stack_mut().pop()?;
                 debug_assert!(vm.stack().is_empty(), "the final vm should be empty");
-                self.vms.clear();
+                self.completed = self.vms.pop();
                 return Ok(GeneratorState::Complete(value));
             }
 
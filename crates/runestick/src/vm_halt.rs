@@ -14,6 +14,9 @@ pub enum VmHalt {
     Awaited(Awaited),
     /// Call into a new virtual machine.
     VmCall(VmCall),
+    /// The virtual machine was cancelled through a
+    /// [CancellationHandle][crate::CancellationHandle].
+    Cancelled,
 }
 
 impl VmHalt {
@@ -25,6 +28,7 @@ impl VmHalt {
             Self::Yielded => VmHaltInfo::Yielded,
             Self::Awaited(..) => VmHaltInfo::Awaited,
             Self::VmCall(..) => VmHaltInfo::VmCall,
+            Self::Cancelled => VmHaltInfo::Cancelled,
         }
     }
 }
@@ -42,6 +46,8 @@ pub enum VmHaltInfo {
     Awaited,
     /// Received instruction to push the inner virtual machine.
     VmCall,
+    /// The virtual machine was cancelled.
+    Cancelled,
 }
 
 impl fmt::Display for VmHaltInfo {
@@ -52,6 +58,7 @@ impl fmt::Display for VmHaltInfo {
             Self::Yielded => write!(f, "yielded"),
             Self::Awaited => write!(f, "awaited"),
             Self::VmCall => write!(f, "calling into other vm"),
+            Self::Cancelled => write!(f, "cancelled"),
         }
     }
 }
@@ -0,0 +1,277 @@
+use crate::{Args, Context, IntoHash, Unit, Value, Vm, VmError, VmExecution};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::ops;
+use std::sync::Arc;
+
+/// A pool of virtual machines running the same unit in the same context,
+/// which reuses them across calls.
+///
+/// Virtual machines returned to the pool are reset, but keep the memory
+/// allocated for their stacks and call frames along with how they were
+/// configured, like their call hooks and the operand types observed by their
+/// instructions. Values held by a virtual machine are finalized when it's
+/// returned, and event handlers and statics of scripts don't carry over to the
+/// next checkout. This makes calling many short functions considerably
+/// cheaper than constructing a new virtual machine for each call.
+///
+/// # Examples
+///
+/// ```rust
+/// use runestick::{Context, Unit, VmPool};
+/// use std::sync::Arc;
+///
+/// # fn main() -> runestick::Result<()> {
+/// let context = Arc::new(Context::with_default_modules()?);
+/// // NB: normally the unit would be compiled from a script.
+/// let unit = Arc::new(Unit::default());
+///
+/// let pool = VmPool::new(context, unit, 4);
+///
+/// {
+///     let vm = pool.get();
+///     assert!(vm.stack().is_empty());
+/// }
+///
+/// assert_eq!(pool.idle(), 1);
+/// # Ok(())
+/// # }
+/// ```
+pub struct VmPool {
+    context: Arc<Context>,
+    unit: Arc<Unit>,
+    /// The maximum number of idle virtual machines to keep around.
+    capacity: usize,
+    /// Called to configure each new virtual machine.
+    setup: Option<Box<dyn Fn(&mut Vm)>>,
+    /// Idle virtual machines.
+    idle: RefCell<Vec<Vm>>,
+    /// The number of virtual machines which have been constructed.
+    created: Cell<usize>,
+}
+
+impl VmPool {
+    /// Construct a new pool, which keeps at most `capacity` idle virtual
+    /// machines around.
+    pub fn new(context: Arc<Context>, unit: Arc<Unit>, capacity: usize) -> Self {
+        Self {
+            context,
+            unit,
+            capacity,
+            setup: None,
+            idle: RefCell::new(Vec::with_capacity(capacity)),
+            created: Cell::new(0),
+        }
+    }
+
+    /// Configure each virtual machine constructed by the pool with the given
+    /// function, like to install call hooks.
+    ///
+    /// The configuration is preserved when virtual machines are returned to
+    /// the pool.
+    pub fn with_setup<F>(self, setup: F) -> Self
+    where
+        F: Fn(&mut Vm) + 'static,
+    {
+        Self {
+            setup: Some(Box::new(setup)),
+            ..self
+        }
+    }
+
+    /// Check out a virtual machine, reusing an idle one if there is one.
+    ///
+    /// The virtual machine is reset and returned to the pool when the guard
+    /// is dropped.
+    pub fn get(&self) -> PooledVm<'_> {
+        let vm = match self.idle.borrow_mut().pop() {
+            Some(vm) => vm,
+            None => self.create(),
+        };
+
+        PooledVm {
+            pool: self,
+            vm: Some(vm),
+        }
+    }
+
+    /// Call the given function to completion on a pooled virtual machine.
+    pub fn call<A, N>(&self, name: N, args: A) -> Result<Value, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+    {
+        self.get().call(name, args)
+    }
+
+    /// Access the context of the pool.
+    pub fn context(&self) -> &Arc<Context> {
+        &self.context
+    }
+
+    /// Access the unit of the pool.
+    pub fn unit(&self) -> &Arc<Unit> {
+        &self.unit
+    }
+
+    /// The maximum number of idle virtual machines kept in the pool.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of idle virtual machines in the pool.
+    pub fn idle(&self) -> usize {
+        self.idle.borrow().len()
+    }
+
+    /// The number of virtual machines which have been constructed by the pool.
+    pub fn created(&self) -> usize {
+        self.created.get()
+    }
+
+    fn create(&self) -> Vm {
+        let mut vm = Vm::new(self.context.clone(), self.unit.clone());
+
+        if let Some(setup) = &self.setup {
+            setup(&mut vm);
+        }
+
+        self.created.set(self.created.get() + 1);
+        vm
+    }
+
+    fn release(&self, mut vm: Vm) {
+        // NB: a virtual machine whose finalizers fail isn't reused.
+        if vm.release().is_err() {
+            return;
+        }
+
+        let mut idle = self.idle.borrow_mut();
+
+        if idle.len() < self.capacity {
+            idle.push(vm);
+        }
+    }
+}
+
+impl fmt::Debug for VmPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VmPool")
+            .field("capacity", &self.capacity)
+            .field("idle", &self.idle())
+            .field("created", &self.created())
+            .finish()
+    }
+}
+
+/// A virtual machine checked out from a [VmPool], as returned by
+/// [VmPool::get].
+#[derive(Debug)]
+pub struct PooledVm<'a> {
+    pool: &'a VmPool,
+    /// The checked out virtual machine, which is only absent while it's
+    /// executing a call.
+    vm: Option<Vm>,
+}
+
+impl PooledVm<'_> {
+    /// Call the given function to completion, keeping the virtual machine
+    /// checked out so that it can be called again.
+    ///
+    /// If any async instructions are encountered, this will error.
+    pub fn call<A, N>(&mut self, name: N, args: A) -> Result<Value, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+    {
+        let mut execution = self.execution(name, args)?;
+        let result = execution.complete();
+        self.restore(execution);
+        result
+    }
+
+    /// Call the given function to completion with support for async
+    /// instructions.
+    pub async fn async_call<A, N>(&mut self, name: N, args: A) -> Result<Value, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+    {
+        let execution = self.execution(name, args)?;
+
+        // NB: the guard puts the virtual machine back even if the future is
+        // dropped before it completes.
+        let mut guard = Restore {
+            pooled: self,
+            execution: Some(execution),
+        };
+
+        match &mut guard.execution {
+            Some(execution) => execution.async_complete().await,
+            None => unreachable!(),
+        }
+    }
+
+    fn execution<A, N>(&mut self, name: N, args: A) -> Result<VmExecution, VmError>
+    where
+        N: IntoHash,
+        A: Args,
+    {
+        let mut vm = match self.vm.take() {
+            Some(vm) => vm,
+            None => self.pool.create(),
+        };
+
+        // NB: a previous call might have errored, leaving its call frames
+        // behind.
+        vm.clear();
+
+        if let Err(error) = vm.set_entrypoint(name, args) {
+            self.vm = Some(vm);
+            return Err(error);
+        }
+
+        Ok(VmExecution::new(vm))
+    }
+
+    fn restore(&mut self, execution: VmExecution) {
+        self.vm = execution.into_vm();
+    }
+}
+
+/// Guard which puts the virtual machine of an execution back into the pooled
+/// virtual machine it was taken from.
+struct Restore<'a, 'b> {
+    pooled: &'a mut PooledVm<'b>,
+    execution: Option<VmExecution>,
+}
+
+impl Drop for Restore<'_, '_> {
+    fn drop(&mut self) {
+        if let Some(execution) = self.execution.take() {
+            self.pooled.restore(execution);
+        }
+    }
+}
+
+impl ops::Deref for PooledVm<'_> {
+    type Target = Vm;
+
+    fn deref(&self) -> &Self::Target {
+        self.vm.as_ref().expect("virtual machine is executing")
+    }
+}
+
+impl ops::DerefMut for PooledVm<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.vm.as_mut().expect("virtual machine is executing")
+    }
+}
+
+impl Drop for PooledVm<'_> {
+    fn drop(&mut self) {
+        if let Some(vm) = self.vm.take() {
+            self.pool.release(vm);
+        }
+    }
+}